@@ -0,0 +1,305 @@
+//! Load-testing harness for the update pipeline
+//!
+//! Replays synthetic Telegram updates against the real handler stack, backed by
+//! PostgreSQL, Redis and a mock Telegram API, and reports throughput and latency
+//! per update kind. Two workloads run at the same time:
+//!
+//! - `--users N` users go through onboarding concurrently
+//!   (`/start`, language, name, location)
+//! - `--group-rate M` messages per second are posted into a group chat for
+//!   `--duration S` seconds
+//!
+//! Servers are provisioned the same way as for the integration tests, so
+//! `TEST_DATABASE_URL` / `TEST_REDIS_URL` can point the harness at existing ones:
+//!
+//! ```text
+//! cargo bench --bench update_pipeline -- --users 200 --group-rate 50 --duration 10
+//! ```
+
+#[allow(dead_code, unused_imports)]
+#[path = "../tests/helpers/mod.rs"]
+mod helpers;
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use teloxide::types::{CallbackQuery, Me, Message};
+use teloxide::utils::command::BotCommands;
+use teloxide::Bot;
+use tokio::task::JoinSet;
+use tracing_subscriber::EnvFilter;
+use wiremock::{matchers::{any, path}, Mock, ResponseTemplate};
+
+use SwingBuddy::handlers::callbacks::handle_callback_query;
+use SwingBuddy::handlers::commands::{handle_command, Command};
+use SwingBuddy::handlers::messages::handle_message;
+use SwingBuddy::i18n::I18n;
+use SwingBuddy::services::ServiceFactory;
+use SwingBuddy::state::{ScenarioManager, StateStorage};
+
+use helpers::{create_simple_test_callback_query, create_simple_test_message, test_bot_me, TestConfig, TestContext};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// First Telegram ID of the synthetic onboarding users
+const ONBOARDING_USER_BASE: i64 = 7_000_000_000;
+/// First Telegram ID of the synthetic group members
+const GROUP_USER_BASE: i64 = 8_000_000_000;
+/// Number of distinct members posting into the group
+const GROUP_MEMBERS: i64 = 500;
+const GROUP_CHAT_ID: i64 = -1_001_234_567_890;
+const NAMES: [&str; 6] = ["Frankie", "Norma", "Dawn", "Skye", "Al", "Leon"];
+
+/// Load profile, taken from the command line
+#[derive(Debug, Clone)]
+struct LoadConfig {
+    users: usize,
+    group_rate: u32,
+    duration: Duration,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            users: 100,
+            group_rate: 20,
+            duration: Duration::from_secs(10),
+        }
+    }
+}
+
+impl LoadConfig {
+    /// Parse `--users`, `--group-rate` and `--duration`, ignoring the flags cargo adds
+    fn from_args() -> Result<Self, BoxError> {
+        let mut config = Self::default();
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("Missing value for {}", arg));
+            match arg.as_str() {
+                "--users" => config.users = value()?.parse()?,
+                "--group-rate" => config.group_rate = value()?.parse()?,
+                "--duration" => config.duration = Duration::from_secs(value()?.parse()?),
+                _ => {}
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// A synthetic update fed into the pipeline
+enum Update {
+    Message(Box<Message>),
+    CallbackQuery(Box<CallbackQuery>),
+}
+
+/// Latency of a single processed update
+struct Sample {
+    kind: &'static str,
+    latency: Duration,
+    ok: bool,
+}
+
+/// The handler stack, routed the same way the dispatcher in `main.rs` does
+#[derive(Clone)]
+struct Pipeline {
+    bot: Bot,
+    /// The bot's own user, as the mocked `getMe` returns it
+    me: Me,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+}
+
+impl Pipeline {
+    async fn dispatch(&self, update: Update) -> SwingBuddy::Result<()> {
+        match update {
+            Update::Message(msg) => {
+                let command = msg.text().and_then(|text| Command::parse(text, self.me.username()).ok());
+                match command {
+                    Some(cmd) => handle_command(
+                        self.bot.clone(), *msg, self.me.clone(), cmd, self.services.clone(),
+                        self.scenario_manager.clone(), self.state_storage.clone(), self.i18n.clone(),
+                    ).await,
+                    None => handle_message(
+                        self.bot.clone(), *msg, self.services.clone(),
+                        self.scenario_manager.clone(), self.state_storage.clone(), self.i18n.clone(),
+                    ).await,
+                }
+            }
+            Update::CallbackQuery(query) => {
+                handle_callback_query(
+                    self.bot.clone(), *query, self.me.clone(), self.services.clone(),
+                    self.scenario_manager.clone(), self.state_storage.clone(), self.i18n.clone(),
+                ).await
+            }
+        }
+    }
+
+    /// Process an update and measure how long it took
+    async fn timed(&self, kind: &'static str, update: Update) -> Sample {
+        let started = Instant::now();
+        let result = self.dispatch(update).await;
+        let latency = started.elapsed();
+
+        if let Err(e) = &result {
+            tracing::warn!(kind = kind, error = %e, "Update failed");
+        }
+
+        Sample { kind, latency, ok: result.is_ok() }
+    }
+}
+
+/// Walk one user through the onboarding scenario
+async fn onboard_user(pipeline: Pipeline, index: usize) -> Vec<Sample> {
+    let user_id = ONBOARDING_USER_BASE + index as i64;
+    let name = NAMES[index % NAMES.len()];
+
+    let steps = [
+        ("command", Update::Message(Box::new(create_simple_test_message(user_id, user_id, "/start")))),
+        ("callback", Update::CallbackQuery(Box::new(create_simple_test_callback_query(user_id, user_id, "lang:en")))),
+        ("message", Update::Message(Box::new(create_simple_test_message(user_id, user_id, name)))),
+        ("callback", Update::CallbackQuery(Box::new(create_simple_test_callback_query(user_id, user_id, "location:Moscow")))),
+    ];
+
+    let mut samples = Vec::with_capacity(steps.len());
+    for (kind, update) in steps {
+        samples.push(pipeline.timed(kind, update).await);
+    }
+    samples
+}
+
+/// Post messages into the group at a fixed rate, without waiting for earlier ones
+async fn group_traffic(pipeline: Pipeline, rate: u32, duration: Duration) -> Vec<Sample> {
+    let mut tasks = JoinSet::new();
+    if rate > 0 {
+        let mut ticker = tokio::time::interval(Duration::from_secs(1) / rate);
+        let deadline = Instant::now() + duration;
+        let mut sent: i64 = 0;
+
+        while Instant::now() < deadline {
+            ticker.tick().await;
+            let user_id = GROUP_USER_BASE + sent % GROUP_MEMBERS;
+            let msg = Box::new(create_simple_test_message(user_id, GROUP_CHAT_ID, &format!("Who is going to the social tonight? #{}", sent)));
+            let pipeline = pipeline.clone();
+            tasks.spawn(async move { pipeline.timed("group_message", Update::Message(msg)).await });
+            sent += 1;
+        }
+    }
+
+    let mut samples = Vec::new();
+    while let Some(sample) = tasks.join_next().await {
+        samples.extend(sample.ok());
+    }
+    samples
+}
+
+/// Nearest-rank percentile of sorted latencies
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn print_report(config: &LoadConfig, samples: &[Sample], elapsed: Duration) {
+    let mut by_kind: BTreeMap<&str, Vec<&Sample>> = BTreeMap::new();
+    for sample in samples {
+        by_kind.entry(sample.kind).or_default().push(sample);
+        by_kind.entry("total").or_default().push(sample);
+    }
+
+    println!();
+    println!(
+        "{} onboarding users, {} group msg/s for {:?}: {} updates in {:.2?} ({:.1} updates/s)",
+        config.users,
+        config.group_rate,
+        config.duration,
+        samples.len(),
+        elapsed,
+        samples.len() as f64 / elapsed.as_secs_f64(),
+    );
+    println!(
+        "{:<14} {:>8} {:>7} {:>10} {:>10} {:>10} {:>10}",
+        "kind", "count", "errors", "p50", "p95", "p99", "max"
+    );
+
+    for (kind, samples) in by_kind {
+        let mut latencies: Vec<Duration> = samples.iter().map(|s| s.latency).collect();
+        latencies.sort();
+        let errors = samples.iter().filter(|s| !s.ok).count();
+
+        println!(
+            "{:<14} {:>8} {:>7} {:>10.2?} {:>10.2?} {:>10.2?} {:>10.2?}",
+            kind,
+            latencies.len(),
+            errors,
+            percentile(&latencies, 50.0),
+            percentile(&latencies, 95.0),
+            percentile(&latencies, 99.0),
+            latencies.last().copied().unwrap_or_default(),
+        );
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), BoxError> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("error")))
+        .init();
+
+    let config = LoadConfig::from_args()?;
+
+    let ctx = TestContext::new_with_config(TestConfig {
+        use_redis: true,
+        ..TestConfig::default()
+    }).await?;
+
+    // CAS lookups for group members come back clean
+    Mock::given(path("/cas/check"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true, "result": null })))
+        .mount(&ctx.telegram_mock.server)
+        .await;
+
+    // Methods without a dedicated mock (edits, deletions, ...) just succeed
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true, "result": true })))
+        .with_priority(u8::MAX)
+        .mount(&ctx.telegram_mock.server)
+        .await;
+
+    let app_state = ctx.create_app_state().await?;
+    let pipeline = Pipeline {
+        bot: ctx.create_bot().await?,
+        me: test_bot_me(),
+        services: (*app_state.services).clone(),
+        scenario_manager: (*app_state.scenario_manager).clone(),
+        state_storage: (*app_state.state_storage).clone(),
+        i18n: (*app_state.i18n).clone(),
+    };
+
+    let started = Instant::now();
+
+    let group = tokio::spawn(group_traffic(pipeline.clone(), config.group_rate, config.duration));
+
+    let mut onboarding = JoinSet::new();
+    for index in 0..config.users {
+        onboarding.spawn(onboard_user(pipeline.clone(), index));
+    }
+
+    let mut samples = Vec::new();
+    while let Some(user_samples) = onboarding.join_next().await {
+        samples.extend(user_samples?);
+    }
+    samples.extend(group.await?);
+
+    let elapsed = started.elapsed();
+    print_report(&config, &samples, elapsed);
+
+    ctx.cleanup().await?;
+    Ok(())
+}
@@ -0,0 +1,23 @@
+-- Private user notes for admins and organizers
+
+-- User notes table
+CREATE TABLE user_notes (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    author_id BIGINT NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Previous versions of edited notes
+CREATE TABLE user_note_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    note_id BIGINT NOT NULL REFERENCES user_notes(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    edited_by BIGINT NOT NULL,
+    edited_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_user_notes_user ON user_notes(user_id);
+CREATE INDEX idx_user_note_revisions_note ON user_note_revisions(note_id);
@@ -0,0 +1,12 @@
+-- Referral attribution for /start deep links
+
+-- Referrals table: each user can be referred at most once
+CREATE TABLE referrals (
+    id BIGSERIAL PRIMARY KEY,
+    referrer_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    referred_id BIGINT UNIQUE NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (referrer_id <> referred_id)
+);
+
+CREATE INDEX idx_referrals_referrer ON referrals(referrer_id);
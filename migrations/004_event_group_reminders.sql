@@ -0,0 +1,9 @@
+-- Day-of event reminders posted into the event's linked group
+
+-- Per-event reminder state; events without a row use the defaults
+CREATE TABLE event_group_reminders (
+    event_id BIGINT PRIMARY KEY REFERENCES events(id) ON DELETE CASCADE,
+    is_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    sent_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,14 @@
+-- Optional user birthdays for automatic greetings
+
+-- Birthdays table; year is intentionally not stored
+CREATE TABLE user_birthdays (
+    user_id BIGINT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    birth_day SMALLINT NOT NULL CHECK (birth_day BETWEEN 1 AND 31),
+    birth_month SMALLINT NOT NULL CHECK (birth_month BETWEEN 1 AND 12),
+    -- Group where the user agreed to have their birthday announced
+    announce_group_id BIGINT REFERENCES groups(id) ON DELETE SET NULL,
+    last_greeted_year INTEGER,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_user_birthdays_date ON user_birthdays(birth_month, birth_day);
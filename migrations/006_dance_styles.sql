@@ -0,0 +1,19 @@
+-- Favorite dance styles on user profiles and style tags on events
+
+-- Styles a user likes to dance
+CREATE TABLE user_dance_styles (
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    style VARCHAR(32) NOT NULL CHECK (style IN ('lindy_hop', 'balboa', 'blues', 'solo_jazz')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, style)
+);
+
+-- Styles an event is about, matched against user styles for targeted messages
+CREATE TABLE event_dance_styles (
+    event_id BIGINT NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    style VARCHAR(32) NOT NULL CHECK (style IN ('lindy_hop', 'balboa', 'blues', 'solo_jazz')),
+    PRIMARY KEY (event_id, style)
+);
+
+CREATE INDEX idx_user_dance_styles_style ON user_dance_styles(style);
+CREATE INDEX idx_event_dance_styles_style ON event_dance_styles(style);
@@ -0,0 +1,16 @@
+-- Join captcha for new group members
+
+-- Members who joined a captcha-protected group and have not passed the check yet.
+-- Telegram IDs are stored because new members are usually not registered users.
+CREATE TABLE pending_captchas (
+    id BIGSERIAL PRIMARY KEY,
+    chat_telegram_id BIGINT NOT NULL,
+    user_telegram_id BIGINT NOT NULL,
+    message_id INTEGER,
+    answer VARCHAR(64) NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (chat_telegram_id, user_telegram_id)
+);
+
+CREATE INDEX idx_pending_captchas_expires_at ON pending_captchas(expires_at);
@@ -0,0 +1,28 @@
+-- Member warnings with automatic escalation
+
+-- Warnings issued by group admins. Telegram IDs are stored because warned
+-- members are usually not registered users.
+CREATE TABLE warnings (
+    id BIGSERIAL PRIMARY KEY,
+    chat_telegram_id BIGINT NOT NULL,
+    user_telegram_id BIGINT NOT NULL,
+    issued_by BIGINT NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_warnings_chat_user ON warnings(chat_telegram_id, user_telegram_id, created_at);
+
+-- Audit trail of moderation actions; actor is NULL for automatic escalations
+CREATE TABLE moderation_actions (
+    id BIGSERIAL PRIMARY KEY,
+    chat_telegram_id BIGINT NOT NULL,
+    target_telegram_id BIGINT NOT NULL,
+    actor_telegram_id BIGINT,
+    action VARCHAR(20) NOT NULL CHECK (action IN ('warn', 'mute', 'ban')),
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_moderation_actions_chat ON moderation_actions(chat_telegram_id, created_at);
+CREATE INDEX idx_moderation_actions_target ON moderation_actions(target_telegram_id);
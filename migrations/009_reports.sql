@@ -0,0 +1,17 @@
+-- Member reports of messages in groups
+
+CREATE TABLE reports (
+    id BIGSERIAL PRIMARY KEY,
+    chat_telegram_id BIGINT NOT NULL,
+    message_id INTEGER NOT NULL,
+    reporter_telegram_id BIGINT NOT NULL,
+    reported_telegram_id BIGINT,
+    reason TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'resolved')),
+    resolved_by BIGINT,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(chat_telegram_id, message_id, reporter_telegram_id)
+);
+
+CREATE INDEX idx_reports_chat_status ON reports(chat_telegram_id, status);
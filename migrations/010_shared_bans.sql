@@ -0,0 +1,15 @@
+-- Bot-level ban list shared by the groups that opt in
+
+CREATE TABLE shared_bans (
+    id BIGSERIAL PRIMARY KEY,
+    user_telegram_id BIGINT NOT NULL UNIQUE,
+    banned_by BIGINT NOT NULL,
+    origin_chat_telegram_id BIGINT NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Record unbans in the moderation audit trail too
+ALTER TABLE moderation_actions DROP CONSTRAINT moderation_actions_action_check;
+ALTER TABLE moderation_actions ADD CONSTRAINT moderation_actions_action_check
+    CHECK (action IN ('warn', 'mute', 'ban', 'unban'));
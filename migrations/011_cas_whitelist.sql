@@ -0,0 +1,9 @@
+-- Users exempt from CAS auto-bans (false positives)
+
+CREATE TABLE cas_whitelist (
+    id BIGSERIAL PRIMARY KEY,
+    telegram_id BIGINT NOT NULL UNIQUE,
+    added_by BIGINT NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,18 @@
+-- Per-group spam filters: keywords, regular expressions and link domains
+
+CREATE TABLE spam_filters (
+    id BIGSERIAL PRIMARY KEY,
+    chat_telegram_id BIGINT NOT NULL,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('keyword', 'regex', 'domain')),
+    pattern TEXT NOT NULL,
+    created_by BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(chat_telegram_id, kind, pattern)
+);
+
+CREATE INDEX idx_spam_filters_chat ON spam_filters(chat_telegram_id);
+
+-- Messages removed by the filter are recorded in the moderation audit trail
+ALTER TABLE moderation_actions DROP CONSTRAINT moderation_actions_action_check;
+ALTER TABLE moderation_actions ADD CONSTRAINT moderation_actions_action_check
+    CHECK (action IN ('warn', 'mute', 'ban', 'unban', 'delete'));
@@ -0,0 +1,14 @@
+-- Delayed deletion of service messages and temporary bot replies in groups
+
+-- Messages waiting to be deleted; kept in the database so a restart does not
+-- leave them in the chat forever.
+CREATE TABLE scheduled_deletions (
+    id BIGSERIAL PRIMARY KEY,
+    chat_telegram_id BIGINT NOT NULL,
+    message_id INTEGER NOT NULL,
+    delete_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (chat_telegram_id, message_id)
+);
+
+CREATE INDEX idx_scheduled_deletions_delete_at ON scheduled_deletions(delete_at);
@@ -0,0 +1,13 @@
+-- Quiet hours for group postings
+
+-- Announcements held back during a group's quiet hours; posted in order when
+-- the window ends, and kept in the database so a restart does not lose them.
+CREATE TABLE queued_posts (
+    id BIGSERIAL PRIMARY KEY,
+    group_id BIGINT NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    thread_id INTEGER,
+    text TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_queued_posts_group_id ON queued_posts(group_id);
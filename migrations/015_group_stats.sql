@@ -0,0 +1,11 @@
+-- Activity counters for /groupstats
+
+-- Daily per-group counters for things no other table records, such as handled
+-- messages. One row per group, day and counter keeps the table small.
+CREATE TABLE group_activity_counters (
+    group_id BIGINT NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    counter VARCHAR(50) NOT NULL,
+    value BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (group_id, day, counter)
+);
@@ -0,0 +1,18 @@
+-- Timed mutes issued with /mute
+
+-- Members to unmute once their mute runs out. One row per member and chat;
+-- muting a member again replaces the end time.
+CREATE TABLE scheduled_unmutes (
+    chat_telegram_id BIGINT NOT NULL,
+    user_telegram_id BIGINT NOT NULL,
+    unmute_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (chat_telegram_id, user_telegram_id)
+);
+
+CREATE INDEX idx_scheduled_unmutes_unmute_at ON scheduled_unmutes(unmute_at);
+
+-- Lifted mutes are recorded in the moderation audit trail
+ALTER TABLE moderation_actions DROP CONSTRAINT moderation_actions_action_check;
+ALTER TABLE moderation_actions ADD CONSTRAINT moderation_actions_action_check
+    CHECK (action IN ('warn', 'mute', 'unmute', 'ban', 'unban', 'delete'));
@@ -0,0 +1,14 @@
+-- Periodic CAS re-check of active group members
+
+-- When members last posted in a group and when they were last checked against
+-- CAS. One row per member and chat; recording activity only moves last_seen_at.
+CREATE TABLE member_activity (
+    chat_telegram_id BIGINT NOT NULL,
+    user_telegram_id BIGINT NOT NULL,
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    cas_checked_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (chat_telegram_id, user_telegram_id)
+);
+
+CREATE INDEX idx_member_activity_user ON member_activity(user_telegram_id);
+CREATE INDEX idx_member_activity_last_seen_at ON member_activity(last_seen_at);
@@ -0,0 +1,25 @@
+-- Join-request invite links created with /invite
+
+-- Invite links the bot created for a group. Rotating a link replaces its URL but
+-- keeps the row, so the statistics of a promo channel carry over.
+CREATE TABLE invite_links (
+    id BIGSERIAL PRIMARY KEY,
+    group_id BIGINT NOT NULL REFERENCES groups(id) ON DELETE CASCADE,
+    name VARCHAR(32) NOT NULL,
+    invite_link TEXT NOT NULL UNIQUE,
+    created_by BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    rotated_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_invite_links_group_id ON invite_links(group_id);
+
+-- Join requests sent through the links. A member asking again updates their row.
+CREATE TABLE invite_link_requests (
+    invite_link_id BIGINT NOT NULL REFERENCES invite_links(id) ON DELETE CASCADE,
+    user_telegram_id BIGINT NOT NULL,
+    status VARCHAR(20) NOT NULL CHECK (status IN ('approved', 'declined')),
+    requested_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (invite_link_id, user_telegram_id)
+);
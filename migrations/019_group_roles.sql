@@ -0,0 +1,12 @@
+-- Bot roles that group admins delegate with /role
+
+-- A member's role in a group on top of their Telegram status, e.g. a moderator
+-- who may warn and mute without being a Telegram admin. One role per member and chat.
+CREATE TABLE group_roles (
+    chat_telegram_id BIGINT NOT NULL,
+    user_telegram_id BIGINT NOT NULL,
+    role VARCHAR(20) NOT NULL CHECK (role IN ('owner', 'admin', 'moderator', 'organizer')),
+    granted_by BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (chat_telegram_id, user_telegram_id)
+);
@@ -0,0 +1,10 @@
+-- Pinned event announcements that are kept up to date as registrations come in
+
+-- The posted reminder message, so it can be edited in place and unpinned once the
+-- event starts, and the participant count it was last rendered with
+ALTER TABLE event_group_reminders
+    ADD COLUMN message_id INTEGER,
+    ADD COLUMN participant_count INTEGER,
+    ADD COLUMN is_pinned BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_event_group_reminders_message ON event_group_reminders(event_id) WHERE message_id IS NOT NULL;
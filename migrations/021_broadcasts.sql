@@ -0,0 +1,29 @@
+-- Segmented broadcasts sent by bot admins
+
+-- A broadcast is a copy of a message the admin composed in their private chat.
+-- The segment is stored as text, e.g. 'all', 'city:Berlin', 'language:ru' or 'event:42'.
+CREATE TABLE broadcasts (
+    id BIGSERIAL PRIMARY KEY,
+    created_by BIGINT NOT NULL,
+    language_code VARCHAR(10) NOT NULL DEFAULT 'en',
+    segment VARCHAR(100) NOT NULL,
+    source_chat_id BIGINT NOT NULL,
+    source_message_id INTEGER NOT NULL,
+    total INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP WITH TIME ZONE
+);
+
+-- One row per recipient; pending rows form the delivery queue, so a restart
+-- continues a broadcast where it stopped
+CREATE TABLE broadcast_deliveries (
+    broadcast_id BIGINT NOT NULL REFERENCES broadcasts(id) ON DELETE CASCADE,
+    user_telegram_id BIGINT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'sent', 'failed')),
+    error TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (broadcast_id, user_telegram_id)
+);
+
+CREATE INDEX idx_broadcasts_unfinished ON broadcasts(created_at) WHERE finished_at IS NULL;
+CREATE INDEX idx_broadcast_deliveries_pending ON broadcast_deliveries(broadcast_id) WHERE status = 'pending';
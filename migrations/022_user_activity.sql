@@ -0,0 +1,7 @@
+-- Last activity of users for the admin user list
+
+-- Updated when a user sends the bot a message, command or button press,
+-- at most once every few minutes
+ALTER TABLE users ADD COLUMN last_active_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_users_last_active_at ON users(last_active_at DESC NULLS LAST);
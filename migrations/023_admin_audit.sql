@@ -0,0 +1,13 @@
+-- Audit trail of admin panel actions
+
+-- Every button an admin presses in the admin panel, with its target (e.g. a group
+-- chat ID or filter ID), so admins can see what the others changed
+CREATE TABLE admin_audit (
+    id BIGSERIAL PRIMARY KEY,
+    admin_id BIGINT NOT NULL,
+    action VARCHAR(50) NOT NULL,
+    target TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_admin_audit_created_at ON admin_audit(created_at DESC);
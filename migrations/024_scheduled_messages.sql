@@ -0,0 +1,20 @@
+-- Broadcasts and group announcements scheduled by bot admins
+
+-- Like a broadcast, the message is a copy of one the admin composed in their
+-- private chat. The target is stored as text: 'broadcast:<segment>', e.g.
+-- 'broadcast:city:Berlin', or 'group:<chat_id>'.
+CREATE TABLE scheduled_messages (
+    id BIGSERIAL PRIMARY KEY,
+    created_by BIGINT NOT NULL,
+    language_code VARCHAR(10) NOT NULL DEFAULT 'en',
+    target VARCHAR(120) NOT NULL,
+    source_chat_id BIGINT NOT NULL,
+    source_message_id INTEGER NOT NULL,
+    send_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'sent', 'failed', 'cancelled')),
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sent_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_scheduled_messages_pending ON scheduled_messages(send_at) WHERE status = 'pending';
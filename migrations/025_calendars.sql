@@ -0,0 +1,18 @@
+-- Event calendars offered in /events, managed by bot admins
+
+CREATE TABLE calendars (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    -- Public Google Calendar the "Add to Google Calendar" button points at
+    google_calendar_id VARCHAR(255),
+    created_by BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- The calendars /events used to show
+INSERT INTO calendars (name, description) VALUES
+    ('📅 Swing Dance Events', 'Regular swing dance events, milongas, and dance parties. Perfect for social dancing and meeting other dancers in the community.'),
+    ('🎭 Workshops & Classes', 'Educational workshops, dance classes, and skill-building sessions. Learn new moves, techniques, and styles from experienced instructors.'),
+    ('🎉 Social Events', 'Community gatherings, meetups, and special celebrations. Connect with fellow dancers outside of regular dance events.');
@@ -0,0 +1,7 @@
+-- Personalized broadcasts
+
+-- When the text or caption of a broadcast contains placeholders such as
+-- {first_name}, it is kept here as HTML and rendered for every recipient.
+-- NULL means the message is copied as it is.
+ALTER TABLE broadcasts ADD COLUMN text TEXT;
+ALTER TABLE scheduled_messages ADD COLUMN text TEXT;
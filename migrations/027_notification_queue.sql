@@ -0,0 +1,22 @@
+-- Persistent queue for outgoing notifications
+
+-- Bulk notifications are queued here and sent by a background task that keeps
+-- within Telegram's rate limits. Failed sends are retried until they succeed,
+-- fail permanently or run out of attempts.
+CREATE TABLE notification_queue (
+    id BIGSERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    thread_id INTEGER,
+    text TEXT NOT NULL,
+    parse_mode VARCHAR(20),
+    disable_link_preview BOOLEAN NOT NULL DEFAULT FALSE,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'sent', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_notification_queue_due ON notification_queue(next_attempt_at, id) WHERE status = 'pending';
+CREATE INDEX idx_notification_queue_finished ON notification_queue(updated_at) WHERE status <> 'pending';
@@ -0,0 +1,12 @@
+-- Notification templates edited from the admin panel
+
+-- The built-in templates define the available keys and placeholders; a row here
+-- replaces the text of one template in one language.
+CREATE TABLE notification_templates (
+    template_key VARCHAR(100) NOT NULL,
+    language_code VARCHAR(10) NOT NULL,
+    content TEXT NOT NULL,
+    updated_by BIGINT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (template_key, language_code)
+);
@@ -0,0 +1,8 @@
+-- Users the bot can no longer message
+
+-- Set when a direct message fails because the user blocked the bot or deleted
+-- their account; such users are left out of bulk sends. Cleared when the user
+-- talks to the bot again.
+ALTER TABLE users ADD COLUMN blocked_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_users_blocked_at ON users(blocked_at) WHERE blocked_at IS NOT NULL;
@@ -0,0 +1,15 @@
+-- Community Google account connected from the admin panel
+
+-- At most one account is connected; the bot uses it for Google Calendar and
+-- refreshes the access token with the stored refresh token.
+CREATE TABLE google_account (
+    id SMALLINT PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+    email VARCHAR(255),
+    access_token TEXT NOT NULL,
+    refresh_token TEXT NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    scope TEXT NOT NULL,
+    connected_by BIGINT NOT NULL,
+    connected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,15 @@
+-- Google Calendar push notifications and incremental sync
+
+-- One row per watched calendar: the channel Google posts change notifications
+-- to, and the sync token of the last incremental sync.
+CREATE TABLE google_calendar_sync (
+    calendar_id VARCHAR(255) PRIMARY KEY,
+    channel_id VARCHAR(64),
+    resource_id VARCHAR(255),
+    channel_token VARCHAR(64),
+    address TEXT,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    sync_token TEXT,
+    last_synced_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,16 @@
+-- Google Sheets mirroring the participant list of an event
+
+-- The sheet is rewritten whenever needs_sync is set; sync_requested_at tells
+-- changes made during an export apart from the ones it already covered.
+CREATE TABLE event_sheets (
+    event_id BIGINT PRIMARY KEY REFERENCES events(id) ON DELETE CASCADE,
+    spreadsheet_id VARCHAR(255) NOT NULL,
+    needs_sync BOOLEAN NOT NULL DEFAULT TRUE,
+    sync_requested_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_synced_at TIMESTAMP WITH TIME ZONE,
+    last_error TEXT,
+    created_by BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_event_sheets_needs_sync ON event_sheets(sync_requested_at) WHERE needs_sync;
@@ -0,0 +1,19 @@
+-- Log of significant actions across the bot
+
+-- One row per completed onboarding, event (un)registration or moderation action,
+-- with who did it, what it was done to and anything else worth keeping in metadata.
+-- Actions are named "<category>.<action>", e.g. "event.registered".
+CREATE TABLE activity_log (
+    id BIGSERIAL PRIMARY KEY,
+    action VARCHAR(50) NOT NULL,
+    actor_id BIGINT,
+    entity_type VARCHAR(30) NOT NULL,
+    entity_id BIGINT NOT NULL,
+    metadata JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_activity_log_created_at ON activity_log(created_at DESC);
+CREATE INDEX idx_activity_log_action ON activity_log(action, created_at DESC);
+CREATE INDEX idx_activity_log_actor_id ON activity_log(actor_id, created_at DESC);
+CREATE INDEX idx_activity_log_entity ON activity_log(entity_type, entity_id, created_at DESC);
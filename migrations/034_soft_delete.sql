@@ -0,0 +1,12 @@
+-- Soft delete for users, groups and events
+
+-- Deleted rows stay in place so statistics, the activity log and old
+-- registrations keep pointing at them; the repositories leave them out and
+-- admins can restore them from the trash.
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE groups ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE events ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX idx_users_deleted_at ON users(deleted_at DESC) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_groups_deleted_at ON groups(deleted_at DESC) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_events_deleted_at ON events(deleted_at DESC) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,21 @@
+-- Archive for old events and their participants
+
+-- Events that ended longer ago than [archive] retention_days are moved here
+-- with their registrations, so the hot tables stay small while the growth
+-- statistics keep counting them. Reminders, dance styles and sheet links of
+-- archived events are dropped with them. Columns added to events or
+-- event_participants later must be added here too, in the same order.
+CREATE TABLE events_archive (LIKE events INCLUDING DEFAULTS);
+ALTER TABLE events_archive
+    ADD PRIMARY KEY (id),
+    ADD COLUMN archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+CREATE TABLE event_participants_archive (LIKE event_participants INCLUDING DEFAULTS);
+ALTER TABLE event_participants_archive
+    ADD PRIMARY KEY (id),
+    ADD COLUMN archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+CREATE INDEX idx_events_archive_created_at ON events_archive(created_at);
+CREATE INDEX idx_events_archive_group_id ON events_archive(group_id);
+CREATE INDEX idx_event_participants_archive_event_id ON event_participants_archive(event_id);
+CREATE INDEX idx_event_participants_archive_registered_at ON event_participants_archive(registered_at);
@@ -0,0 +1,12 @@
+-- Conversation state for deployments that keep it in PostgreSQL instead of Redis
+CREATE TABLE conversation_contexts (
+    user_id BIGINT NOT NULL,
+    -- Group chat the context is scoped to; 0 for the user's private chat
+    chat_id BIGINT NOT NULL DEFAULT 0,
+    context JSONB NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, chat_id)
+);
+
+CREATE INDEX idx_conversation_contexts_expires_at ON conversation_contexts(expires_at);
@@ -0,0 +1,15 @@
+-- Songs attendees ask the DJ for during an event
+
+-- Requests stay after the party, so the event keeps a playlist of what was played
+CREATE TABLE song_requests (
+    id BIGSERIAL PRIMARY KEY,
+    event_id BIGINT NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    requested_by BIGINT NOT NULL,
+    track VARCHAR(200) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'played', 'skipped')),
+    handled_by BIGINT,
+    handled_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_song_requests_event_id ON song_requests(event_id, created_at);
@@ -0,0 +1,15 @@
+-- Dance venues listed with /venues
+
+-- Events are matched to a venue by their location mentioning its name
+CREATE TABLE venues (
+    id BIGSERIAL PRIMARY KEY,
+    city VARCHAR(100) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    address TEXT NOT NULL,
+    floor_type VARCHAR(50),
+    created_by BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(city, name)
+);
+
+CREATE INDEX idx_venues_city ON venues(LOWER(city));
@@ -0,0 +1,7 @@
+-- Notifications users can opt out of
+
+-- Direct messages users did not ask for: admin broadcasts, including
+-- scheduled ones, and reminders to finish a conversation they left halfway.
+-- /unsubscribe turns both off and /subscribe back on.
+ALTER TABLE users ADD COLUMN broadcasts_enabled BOOLEAN NOT NULL DEFAULT true;
+ALTER TABLE users ADD COLUMN reminders_enabled BOOLEAN NOT NULL DEFAULT true;
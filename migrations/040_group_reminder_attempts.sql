@@ -0,0 +1,5 @@
+-- Failed group reminder posts
+
+-- A reminder the bot could not post is retried on the next checks, up to a
+-- few times, instead of on every check until the event starts.
+ALTER TABLE event_group_reminders ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0;
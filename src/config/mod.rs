@@ -6,4 +6,4 @@
 pub mod settings;
 pub mod validation;
 
-pub use settings::{Settings, I18nConfig, BotConfig, DatabaseConfig, RedisConfig, GoogleConfig, CasConfig, LoggingConfig, FeaturesConfig};
\ No newline at end of file
+pub use settings::{Settings, SharedSettings, I18nConfig, BotConfig, DatabaseConfig, RedisConfig, GoogleConfig, GoogleOAuthConfig, GoogleWatchConfig, CasConfig, LoggingConfig, FeaturesConfig, OnboardingConfig, WelcomeMediaConfig, MediaKind, RemindersConfig, BirthdaysConfig, CaptchaConfig, WarningsConfig, FloodConfig, RateLimitsConfig, CommandRateLimitConfig, CleanupConfig, ArchiveConfig, ScenariosConfig, StepRemindersConfig, StateConfig, StateBackendKind, DigestConfig, CasRecheckConfig, ExportConfig, WebhookConfig, HealthConfig, ApiConfig, ApiKeyConfig, OutgoingWebhooksConfig, WebhookEndpointConfig, RedisSentinelConfig};
\ No newline at end of file
@@ -3,6 +3,8 @@
 //! This module defines the configuration structure and provides methods
 //! for loading settings from TOML files and environment variables.
 
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use serde::{Deserialize, Serialize};
 
 /// Main application configuration structure
@@ -16,12 +18,33 @@ pub struct Settings {
     pub i18n: I18nConfig,
     pub logging: LoggingConfig,
     pub features: FeaturesConfig,
+    pub onboarding: Option<OnboardingConfig>,
+    pub reminders: Option<RemindersConfig>,
+    pub birthdays: Option<BirthdaysConfig>,
+    pub captcha: Option<CaptchaConfig>,
+    pub warnings: Option<WarningsConfig>,
+    pub flood: Option<FloodConfig>,
+    pub rate_limits: Option<RateLimitsConfig>,
+    pub cleanup: Option<CleanupConfig>,
+    pub digest: Option<DigestConfig>,
+    pub cas_recheck: Option<CasRecheckConfig>,
+    pub export: Option<ExportConfig>,
+    pub webhook: Option<WebhookConfig>,
+    pub health: Option<HealthConfig>,
+    pub api: Option<ApiConfig>,
+    pub webapp: Option<WebAppConfig>,
+    pub outgoing_webhooks: Option<OutgoingWebhooksConfig>,
+    pub archive: Option<ArchiveConfig>,
+    pub scenarios: Option<ScenariosConfig>,
+    pub step_reminders: Option<StepRemindersConfig>,
+    pub state: Option<StateConfig>,
 }
 
 /// Telegram bot configuration
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BotConfig {
     pub token: String,
+    /// Public HTTPS URL for webhook mode; empty or missing means polling
     pub webhook_url: Option<String>,
     pub admin_ids: Vec<i64>,
 }
@@ -32,21 +55,113 @@ pub struct DatabaseConfig {
     pub url: String,
     pub max_connections: u32,
     pub min_connections: u32,
+    /// Queries taking at least this many milliseconds are logged and counted as slow
+    #[serde(default = "default_slow_query_ms")]
+    pub slow_query_ms: u64,
+}
+
+fn default_slow_query_ms() -> u64 {
+    500
 }
 
 /// Redis configuration
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RedisConfig {
+    /// Used when no Sentinel is configured
     pub url: String,
     pub prefix: String,
     pub ttl_seconds: u64,
+    /// Find the master through Redis Sentinel and follow failovers
+    #[serde(default)]
+    pub sentinel: Option<RedisSentinelConfig>,
+}
+
+/// Redis Sentinel configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RedisSentinelConfig {
+    /// Sentinel nodes, e.g. `redis://sentinel-1:26379`
+    pub urls: Vec<String>,
+    /// Name the master is monitored under
+    pub master_name: String,
+    /// Password of the master and replicas, if any
+    pub password: Option<String>,
+    /// Database number on the master
+    #[serde(default)]
+    pub db: i64,
+    /// How often Sentinel is asked for the current master
+    #[serde(default = "default_sentinel_check_interval")]
+    pub check_interval_seconds: u64,
+}
+
+fn default_sentinel_check_interval() -> u64 {
+    5
 }
 
 /// Google Calendar configuration
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GoogleConfig {
+    /// Service account key; not needed when an account is connected through `[google.oauth]`
+    #[serde(default)]
     pub service_account_path: String,
     pub calendar_id: String,
+    /// OAuth client for connecting a community Google account from the admin panel
+    #[serde(default)]
+    pub oauth: Option<GoogleOAuthConfig>,
+    /// Push notifications for calendar changes instead of polling
+    #[serde(default)]
+    pub watch: Option<GoogleWatchConfig>,
+}
+
+/// Google Calendar push notification channel
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GoogleWatchConfig {
+    /// Public HTTPS URL of `/google/calendar/notifications` on the health server
+    pub notification_url: String,
+    /// Renew the channel when it expires within this many hours
+    #[serde(default = "default_google_watch_renew_before")]
+    pub renew_before_hours: u64,
+    /// How often the channel is checked and renewed
+    #[serde(default = "default_google_watch_check_interval")]
+    pub check_interval_seconds: u64,
+}
+
+fn default_google_watch_renew_before() -> u64 {
+    24
+}
+
+fn default_google_watch_check_interval() -> u64 {
+    3600
+}
+
+/// Google OAuth client ("Web application" type)
+#[derive(Clone, Deserialize, Serialize)]
+pub struct GoogleOAuthConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    /// Public URL of `/oauth/google/callback` on the health server; it must be
+    /// listed as an authorized redirect URI of the client
+    pub redirect_uri: String,
+    /// Scopes asked for when connecting the account
+    #[serde(default = "default_google_oauth_scopes")]
+    pub scopes: Vec<String>,
+}
+
+// The client secret is kept out of logs
+impl std::fmt::Debug for GoogleOAuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GoogleOAuthConfig")
+            .field("client_id", &self.client_id)
+            .field("redirect_uri", &self.redirect_uri)
+            .field("scopes", &self.scopes)
+            .finish_non_exhaustive()
+    }
+}
+
+fn default_google_oauth_scopes() -> Vec<String> {
+    vec![
+        "https://www.googleapis.com/auth/calendar".to_string(),
+        "https://www.googleapis.com/auth/spreadsheets".to_string(),
+    ]
 }
 
 /// CAS API configuration
@@ -55,6 +170,48 @@ pub struct CasConfig {
     pub api_url: String,
     pub timeout_seconds: u64,
     pub auto_ban: bool,
+    /// How long a CAS listing is cached
+    #[serde(default = "default_cas_cache_ttl")]
+    pub cache_ttl_seconds: u64,
+    /// How long a clean result is cached; shorter, since anyone can get listed later
+    #[serde(default = "default_cas_negative_cache_ttl")]
+    pub negative_cache_ttl_seconds: u64,
+    /// Parallel CAS requests while checking a batch of users
+    #[serde(default = "default_cas_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// Authors of group messages are checked in batches of up to this many
+    #[serde(default = "default_cas_message_batch_size")]
+    pub message_batch_size: usize,
+    /// Longest a queued message author waits for their batch
+    #[serde(default = "default_cas_message_batch_wait_ms")]
+    pub message_batch_wait_ms: u64,
+    /// Authors found clean are not checked again for this long
+    #[serde(default = "default_cas_message_cooldown")]
+    pub message_cooldown_seconds: u64,
+}
+
+fn default_cas_cache_ttl() -> u64 {
+    86400
+}
+
+fn default_cas_negative_cache_ttl() -> u64 {
+    3600
+}
+
+fn default_cas_max_concurrent_requests() -> usize {
+    5
+}
+
+fn default_cas_message_batch_size() -> usize {
+    50
+}
+
+fn default_cas_message_batch_wait_ms() -> u64 {
+    500
+}
+
+fn default_cas_message_cooldown() -> u64 {
+    600
 }
 
 /// Internationalization configuration
@@ -79,6 +236,347 @@ pub struct FeaturesConfig {
     pub cas_protection: bool,
     pub google_calendar: bool,
     pub admin_panel: bool,
+    /// New-member captcha; it also needs a `[captcha]` section
+    #[serde(default = "default_true")]
+    pub captcha: bool,
+}
+
+/// Onboarding configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OnboardingConfig {
+    pub welcome_media: Option<WelcomeMediaConfig>,
+}
+
+/// Media sent along with the onboarding greeting
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WelcomeMediaConfig {
+    pub kind: MediaKind,
+    /// Telegram file_id or HTTP(S) URL
+    pub source: String,
+    /// Captions by language code
+    #[serde(default)]
+    pub captions: HashMap<String, String>,
+}
+
+/// Kind of media attachment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Photo,
+    Video,
+    Animation,
+}
+
+/// Scheduled reminders configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RemindersConfig {
+    /// Post day-of reminders into the event's linked group
+    pub group_reminders: bool,
+    /// How many hours before the event the group reminder is posted
+    pub group_reminder_lead_hours: i64,
+    /// How often to look for due reminders
+    pub check_interval_seconds: u64,
+}
+
+/// Birthday greetings configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BirthdaysConfig {
+    pub enabled: bool,
+    /// Hour of the day (UTC) after which greetings are sent
+    pub greeting_hour_utc: u32,
+    /// How often to look for birthdays to greet
+    pub check_interval_seconds: u64,
+}
+
+/// Weekly event digest configuration; groups subscribe with /digest
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DigestConfig {
+    /// Day of the week the digest is posted (1 = Monday ... 7 = Sunday)
+    pub weekday: u32,
+    /// Hour of the day (UTC) after which the digest is posted
+    pub hour_utc: u32,
+    /// Pin the new digest in the group, unpinning the previous one
+    pub pin: bool,
+    /// Edit the posted digest when the week's events change
+    pub update_during_week: bool,
+    /// How often to look for digests to post or update
+    pub check_interval_seconds: u64,
+}
+
+/// Join captcha configuration; groups turn the captcha on with /captcha
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CaptchaConfig {
+    /// Time new members have to pass the captcha before they are removed
+    pub timeout_seconds: u64,
+    /// How often to look for expired captchas
+    pub check_interval_seconds: u64,
+}
+
+/// Warning escalation configuration; without it warnings are only recorded
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WarningsConfig {
+    /// Warnings after which a member is muted (0 disables muting)
+    pub mute_after: u32,
+    /// How long an automatic mute lasts
+    pub mute_minutes: u32,
+    /// Warnings after which a member is banned (0 disables banning)
+    pub ban_after: u32,
+    /// Warnings older than this no longer count (0 keeps them forever)
+    pub expire_days: u32,
+}
+
+/// Flood control for group messages; without it flooding is not limited
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FloodConfig {
+    /// Messages a member may send within the window
+    pub max_messages: u32,
+    pub window_seconds: u64,
+    /// How long a flooding member is muted
+    pub mute_seconds: u64,
+}
+
+/// Limits on the commands and buttons a user may use; without it they are not limited
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RateLimitsConfig {
+    /// Commands and button presses a user may send within the window
+    pub max_requests: u32,
+    pub window_seconds: u64,
+    /// Separate limits for single commands, by name without the slash
+    #[serde(default)]
+    pub commands: std::collections::HashMap<String, CommandRateLimitConfig>,
+}
+
+/// Limit on one command
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CommandRateLimitConfig {
+    pub max_requests: u32,
+    pub window_seconds: u64,
+}
+
+/// Message cleanup configuration; groups turn cleanup on with /cleanup
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CleanupConfig {
+    /// Delay before deletion for groups that did not choose their own
+    pub delay_seconds: u64,
+    /// How often to look for messages due for deletion
+    pub check_interval_seconds: u64,
+}
+
+/// Scenario files overriding or adding to the built-in conversation scenarios
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ScenariosConfig {
+    /// Directory with one `.toml` or `.json` file per scenario
+    pub directory: String,
+}
+
+/// One reminder before an unfinished scenario expires
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StepRemindersConfig {
+    /// How long before the context expires the reminder is sent
+    pub remind_before_seconds: u64,
+    /// How often to look for contexts about to expire
+    pub check_interval_seconds: u64,
+}
+
+/// Where conversation state is kept; Redis unless configured otherwise
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StateConfig {
+    pub backend: StateBackendKind,
+    /// How often expired contexts are deleted, for backends without their own expiry
+    #[serde(default = "default_state_cleanup_interval")]
+    pub cleanup_interval_seconds: u64,
+}
+
+fn default_state_cleanup_interval() -> u64 {
+    300
+}
+
+/// Conversation state backend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateBackendKind {
+    #[default]
+    Redis,
+    /// The `conversation_contexts` table, for deployments without Redis
+    Postgres,
+}
+
+/// Moving old events and their participants into the archive tables
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ArchiveConfig {
+    /// Events are archived this many days after they took place
+    pub retention_days: u64,
+    /// Events moved per run, to keep the transaction short
+    #[serde(default = "default_archive_batch_size")]
+    pub batch_size: i64,
+    /// How often to look for events to archive
+    #[serde(default = "default_archive_check_interval")]
+    pub check_interval_seconds: u64,
+}
+
+fn default_archive_batch_size() -> i64 {
+    500
+}
+
+fn default_archive_check_interval() -> u64 {
+    86400
+}
+
+/// Periodic CAS re-check of members who were active in groups recently
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CasRecheckConfig {
+    /// How often a member is checked again
+    pub interval_hours: u64,
+    /// Only members who posted within this many days are checked
+    pub active_within_days: u64,
+    /// Members checked per run, to stay within the CAS API rate limits
+    pub batch_size: i64,
+    /// Pause between two CAS API requests
+    pub request_delay_ms: u64,
+    /// How often a batch is checked
+    pub check_interval_seconds: u64,
+}
+
+/// Data exports for bot admins
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExportConfig {
+    /// Columns included in the CSV user export, in this order; without this
+    /// section all of telegram_id, name, city, language, registered_at and banned
+    pub user_columns: Vec<String>,
+}
+
+/// Webhook server used when `bot.webhook_url` is set. Telegram only delivers
+/// to HTTPS URLs, so TLS is terminated by a reverse proxy in front of the
+/// server, or by the server itself with a self-signed certificate.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebhookConfig {
+    /// Local address the webhook server listens on
+    #[serde(default = "default_webhook_listen_address")]
+    pub listen_address: String,
+    /// Path updates are accepted on; defaults to the path of `bot.webhook_url`
+    pub path: Option<String>,
+    /// Expected in the `X-Telegram-Bot-Api-Secret-Token` header of every
+    /// request; a random token is generated on startup when not set
+    pub secret_token: Option<String>,
+    /// Public key certificate uploaded to Telegram for self-signed TLS
+    pub certificate_path: Option<String>,
+    /// Simultaneous connections Telegram may open, 1-100
+    pub max_connections: Option<u8>,
+    /// Drop updates that arrived while the bot was down
+    #[serde(default)]
+    pub drop_pending_updates: bool,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            listen_address: default_webhook_listen_address(),
+            path: None,
+            secret_token: None,
+            certificate_path: None,
+            max_connections: None,
+            drop_pending_updates: false,
+        }
+    }
+}
+
+fn default_webhook_listen_address() -> String {
+    "0.0.0.0:8443".to_string()
+}
+
+/// HTTP server with `/healthz` and `/readyz` for container probes; without
+/// this section no server is started
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HealthConfig {
+    /// Local address the health server listens on
+    pub listen_address: String,
+}
+
+/// Read-only JSON API for the community website, served by the health server
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiConfig {
+    /// Clients allowed to call the API
+    pub keys: Vec<ApiKeyConfig>,
+}
+
+/// A client of the public API
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ApiKeyConfig {
+    /// Shown in the logs instead of the key
+    pub name: String,
+    /// Sent as `Authorization: Bearer <key>` or `X-Api-Key: <key>`
+    pub key: String,
+}
+
+// The key itself is kept out of logs
+impl std::fmt::Debug for ApiKeyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKeyConfig")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Telegram Mini App for browsing events, served by the health server
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebAppConfig {
+    /// Public HTTPS URL of `/webapp` on the health server; Telegram only opens
+    /// Mini Apps over HTTPS
+    pub url: String,
+}
+
+/// HTTP POSTs sent to external systems when something happens in the bot
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OutgoingWebhooksConfig {
+    pub endpoints: Vec<WebhookEndpointConfig>,
+    /// Time a receiver has to answer one delivery
+    #[serde(default = "default_outgoing_webhook_timeout")]
+    pub timeout_seconds: u64,
+    /// Deliveries are retried with a growing delay until this many attempts
+    #[serde(default = "default_outgoing_webhook_max_attempts")]
+    pub max_attempts: u32,
+}
+
+fn default_outgoing_webhook_timeout() -> u64 {
+    10
+}
+
+fn default_outgoing_webhook_max_attempts() -> u32 {
+    5
+}
+
+/// A receiver of outgoing webhooks
+#[derive(Clone, Deserialize, Serialize)]
+pub struct WebhookEndpointConfig {
+    pub name: String,
+    pub url: String,
+    /// Key of the HMAC-SHA256 signature sent with every delivery
+    pub secret: String,
+    /// Event types sent to this endpoint, e.g. `event.created`; empty means all
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+// The secret is kept out of logs
+impl std::fmt::Debug for WebhookEndpointConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookEndpointConfig")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("events", &self.events)
+            .finish_non_exhaustive()
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl BotConfig {
+    /// The webhook URL, if webhook mode is configured
+    pub fn webhook_url(&self) -> Option<&str> {
+        self.webhook_url.as_deref().map(str::trim).filter(|url| !url.is_empty())
+    }
 }
 
 impl Settings {
@@ -98,6 +596,30 @@ impl Settings {
     }
 }
 
+/// Settings shared by the services. `/reload_config` replaces them as a whole,
+/// so services read the current values instead of keeping a copy.
+#[derive(Debug, Clone)]
+pub struct SharedSettings {
+    current: Arc<RwLock<Arc<Settings>>>,
+}
+
+impl SharedSettings {
+    pub fn new(settings: Settings) -> Self {
+        Self { current: Arc::new(RwLock::new(Arc::new(settings))) }
+    }
+
+    /// Get the current settings
+    pub fn get(&self) -> Arc<Settings> {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the settings, returning the previous ones
+    pub fn replace(&self, settings: Settings) -> Arc<Settings> {
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        std::mem::replace(&mut *current, Arc::new(settings))
+    }
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -110,17 +632,25 @@ impl Default for Settings {
                 url: "postgresql://localhost/swingbuddy".to_string(),
                 max_connections: 10,
                 min_connections: 1,
+                slow_query_ms: default_slow_query_ms(),
             },
             redis: RedisConfig {
                 url: "redis://localhost:6379".to_string(),
                 prefix: "swingbuddy:".to_string(),
                 ttl_seconds: 3600,
+                sentinel: None,
             },
             google: None,
             cas: CasConfig {
                 api_url: "https://api.cas.chat".to_string(),
                 timeout_seconds: 5,
                 auto_ban: true,
+                cache_ttl_seconds: default_cas_cache_ttl(),
+                negative_cache_ttl_seconds: default_cas_negative_cache_ttl(),
+                max_concurrent_requests: default_cas_max_concurrent_requests(),
+                message_batch_size: default_cas_message_batch_size(),
+                message_batch_wait_ms: default_cas_message_batch_wait_ms(),
+                message_cooldown_seconds: default_cas_message_cooldown(),
             },
             i18n: I18nConfig {
                 default_language: "en".to_string(),
@@ -136,7 +666,28 @@ impl Default for Settings {
                 cas_protection: true,
                 google_calendar: false,
                 admin_panel: true,
+                captcha: true,
             },
+            onboarding: None,
+            reminders: None,
+            birthdays: None,
+            captcha: None,
+            warnings: None,
+            flood: None,
+            rate_limits: None,
+            cleanup: None,
+            digest: None,
+            cas_recheck: None,
+            export: None,
+            webhook: None,
+            health: None,
+            api: None,
+            webapp: None,
+            outgoing_webhooks: None,
+            archive: None,
+            scenarios: None,
+            step_reminders: None,
+            state: None,
         }
     }
 }
\ No newline at end of file
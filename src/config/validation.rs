@@ -18,6 +18,99 @@ pub fn validate_settings(settings: &Settings) -> Result<()> {
     if let Some(ref google_config) = settings.google {
         validate_google_config(google_config)?;
     }
+
+    if let Some(ref onboarding_config) = settings.onboarding {
+        validate_onboarding_config(onboarding_config)?;
+    }
+
+    if let Some(ref reminders_config) = settings.reminders {
+        validate_reminders_config(reminders_config)?;
+    }
+
+    if let Some(ref birthdays_config) = settings.birthdays {
+        validate_birthdays_config(birthdays_config)?;
+    }
+
+    if let Some(ref digest_config) = settings.digest {
+        validate_digest_config(digest_config)?;
+    }
+
+    if let Some(ref captcha_config) = settings.captcha {
+        validate_captcha_config(captcha_config)?;
+    }
+
+    if let Some(ref warnings_config) = settings.warnings {
+        validate_warnings_config(warnings_config)?;
+    }
+
+    if let Some(ref flood_config) = settings.flood {
+        validate_flood_config(flood_config)?;
+    }
+
+    if let Some(ref rate_limits_config) = settings.rate_limits {
+        validate_rate_limits_config(rate_limits_config)?;
+    }
+
+    if let Some(ref cleanup_config) = settings.cleanup {
+        validate_cleanup_config(cleanup_config)?;
+    }
+
+    if let Some(ref cas_recheck_config) = settings.cas_recheck {
+        validate_cas_recheck_config(cas_recheck_config)?;
+    }
+
+    if let Some(ref export_config) = settings.export {
+        validate_export_config(export_config)?;
+    }
+
+    if let Some(ref webhook_config) = settings.webhook {
+        validate_webhook_config(webhook_config)?;
+    }
+
+    if let Some(ref health_config) = settings.health {
+        validate_health_config(health_config)?;
+    }
+
+    if let Some(ref api_config) = settings.api {
+        if settings.health.is_none() {
+            return Err(SwingBuddyError::Config(
+                "The [api] section needs a [health] section to be served".to_string()
+            ));
+        }
+        validate_api_config(api_config)?;
+    }
+
+    if let Some(ref outgoing_webhooks_config) = settings.outgoing_webhooks {
+        validate_outgoing_webhooks_config(outgoing_webhooks_config)?;
+    }
+
+    if let Some(ref step_reminders_config) = settings.step_reminders {
+        if step_reminders_config.remind_before_seconds == 0 || step_reminders_config.check_interval_seconds == 0 {
+            return Err(SwingBuddyError::Config(
+                "Step reminder lead time and check interval must be greater than 0".to_string()
+            ));
+        }
+    }
+
+    if settings.state.as_ref().is_some_and(|state| state.cleanup_interval_seconds == 0) {
+        return Err(SwingBuddyError::Config(
+            "State cleanup interval must be greater than 0".to_string()
+        ));
+    }
+
+    if let Some(ref archive_config) = settings.archive {
+        validate_archive_config(archive_config)?;
+    }
+
+    if settings.scenarios.as_ref().is_some_and(|scenarios| scenarios.directory.is_empty()) {
+        return Err(SwingBuddyError::Config(
+            "Scenario directory must not be empty".to_string()
+        ));
+    }
+
+    if settings.bot.webhook_url().is_some() {
+        crate::utils::webhook::webhook_options(settings)?;
+    }
     
     Ok(())
 }
@@ -58,12 +151,40 @@ fn validate_database_config(config: &super::DatabaseConfig) -> Result<()> {
             "Min connections cannot be greater than max connections".to_string()
         ));
     }
+
+    if config.slow_query_ms == 0 {
+        return Err(SwingBuddyError::Config(
+            "Slow query threshold must be greater than 0".to_string()
+        ));
+    }
     
     Ok(())
 }
 
 /// Validate Redis configuration
 fn validate_redis_config(config: &super::RedisConfig) -> Result<()> {
+    if let Some(sentinel) = &config.sentinel {
+        if sentinel.urls.is_empty() {
+            return Err(SwingBuddyError::Config(
+                "At least one Redis Sentinel URL is required".to_string()
+            ));
+        }
+
+        if sentinel.master_name.is_empty() {
+            return Err(SwingBuddyError::Config(
+                "Redis Sentinel master name is required".to_string()
+            ));
+        }
+
+        if sentinel.check_interval_seconds == 0 {
+            return Err(SwingBuddyError::Config(
+                "Redis Sentinel check interval must be greater than 0".to_string()
+            ));
+        }
+
+        return Ok(());
+    }
+
     if config.url.is_empty() {
         return Err(SwingBuddyError::Config(
             "Redis URL is required".to_string()
@@ -75,11 +196,45 @@ fn validate_redis_config(config: &super::RedisConfig) -> Result<()> {
 
 /// Validate Google Calendar configuration
 fn validate_google_config(config: &super::GoogleConfig) -> Result<()> {
-    if config.service_account_path.is_empty() {
+    if config.service_account_path.is_empty() && config.oauth.is_none() {
         return Err(SwingBuddyError::Config(
-            "Google service account path is required".to_string()
+            "Google service account path or [google.oauth] is required".to_string()
         ));
     }
+
+    if let Some(ref oauth) = config.oauth {
+        if oauth.client_id.is_empty() || oauth.client_secret.is_empty() {
+            return Err(SwingBuddyError::Config(
+                "Google OAuth client ID and secret are required".to_string()
+            ));
+        }
+
+        if !oauth.redirect_uri.starts_with("https://") && !oauth.redirect_uri.starts_with("http://") {
+            return Err(SwingBuddyError::Config(
+                "Google OAuth redirect URI must be an http(s) URL".to_string()
+            ));
+        }
+
+        if oauth.scopes.is_empty() {
+            return Err(SwingBuddyError::Config(
+                "Google OAuth needs at least one scope".to_string()
+            ));
+        }
+    }
+
+    if let Some(ref watch) = config.watch {
+        if !watch.notification_url.starts_with("https://") {
+            return Err(SwingBuddyError::Config(
+                "Google Calendar notification URL must be an https URL".to_string()
+            ));
+        }
+
+        if watch.check_interval_seconds == 0 {
+            return Err(SwingBuddyError::Config(
+                "Google Calendar watch check interval must be greater than 0".to_string()
+            ));
+        }
+    }
     
     if config.calendar_id.is_empty() {
         return Err(SwingBuddyError::Config(
@@ -90,6 +245,299 @@ fn validate_google_config(config: &super::GoogleConfig) -> Result<()> {
     Ok(())
 }
 
+/// Validate onboarding configuration
+fn validate_onboarding_config(config: &super::OnboardingConfig) -> Result<()> {
+    if let Some(ref media) = config.welcome_media {
+        if media.source.is_empty() {
+            return Err(SwingBuddyError::Config(
+                "Welcome media source (file_id or URL) is required".to_string()
+            ));
+        }
+
+        // Telegram limits media captions to 1024 characters
+        if let Some((lang, _)) = media.captions.iter().find(|(_, caption)| caption.chars().count() > 1024) {
+            return Err(SwingBuddyError::Config(
+                format!("Welcome media caption for {} exceeds 1024 characters", lang)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate reminders configuration
+fn validate_reminders_config(config: &super::RemindersConfig) -> Result<()> {
+    if !(1..=24).contains(&config.group_reminder_lead_hours) {
+        return Err(SwingBuddyError::Config(
+            "Group reminder lead time must be between 1 and 24 hours".to_string()
+        ));
+    }
+
+    if config.check_interval_seconds == 0 {
+        return Err(SwingBuddyError::Config(
+            "Reminder check interval must be greater than 0".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate birthday greetings configuration
+fn validate_birthdays_config(config: &super::BirthdaysConfig) -> Result<()> {
+    if config.greeting_hour_utc > 23 {
+        return Err(SwingBuddyError::Config(
+            "Birthday greeting hour must be between 0 and 23".to_string()
+        ));
+    }
+
+    if config.check_interval_seconds == 0 {
+        return Err(SwingBuddyError::Config(
+            "Birthday check interval must be greater than 0".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate weekly digest configuration
+fn validate_digest_config(config: &super::DigestConfig) -> Result<()> {
+    if !(1..=7).contains(&config.weekday) {
+        return Err(SwingBuddyError::Config(
+            "Digest weekday must be between 1 (Monday) and 7 (Sunday)".to_string()
+        ));
+    }
+
+    if config.hour_utc > 23 {
+        return Err(SwingBuddyError::Config(
+            "Digest hour must be between 0 and 23".to_string()
+        ));
+    }
+
+    if config.check_interval_seconds == 0 {
+        return Err(SwingBuddyError::Config(
+            "Digest check interval must be greater than 0".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate join captcha configuration
+fn validate_captcha_config(config: &super::CaptchaConfig) -> Result<()> {
+    if !(30..=3600).contains(&config.timeout_seconds) {
+        return Err(SwingBuddyError::Config(
+            "Captcha timeout must be between 30 and 3600 seconds".to_string()
+        ));
+    }
+
+    if config.check_interval_seconds == 0 {
+        return Err(SwingBuddyError::Config(
+            "Captcha check interval must be greater than 0".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate warning escalation configuration
+fn validate_warnings_config(config: &super::WarningsConfig) -> Result<()> {
+    if config.mute_after > 0 && !(1..=525_600).contains(&config.mute_minutes) {
+        return Err(SwingBuddyError::Config(
+            "Warning mute duration must be between 1 minute and 365 days".to_string()
+        ));
+    }
+
+    if config.mute_after > 0 && config.ban_after > 0 && config.ban_after <= config.mute_after {
+        return Err(SwingBuddyError::Config(
+            "Warnings before a ban must be greater than warnings before a mute".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate flood control configuration
+fn validate_flood_config(config: &super::FloodConfig) -> Result<()> {
+    if config.max_messages == 0 {
+        return Err(SwingBuddyError::Config(
+            "Flood control message limit must be greater than 0".to_string()
+        ));
+    }
+
+    if !(1..=3600).contains(&config.window_seconds) {
+        return Err(SwingBuddyError::Config(
+            "Flood control window must be between 1 and 3600 seconds".to_string()
+        ));
+    }
+
+    // Telegram treats restrictions shorter than 30 seconds as permanent
+    if !(60..=86400).contains(&config.mute_seconds) {
+        return Err(SwingBuddyError::Config(
+            "Flood control mute must be between 60 and 86400 seconds".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate message cleanup configuration
+fn validate_cleanup_config(config: &super::CleanupConfig) -> Result<()> {
+    if !(10..=86400).contains(&config.delay_seconds) {
+        return Err(SwingBuddyError::Config(
+            "Cleanup delay must be between 10 and 86400 seconds".to_string()
+        ));
+    }
+
+    if config.check_interval_seconds == 0 {
+        return Err(SwingBuddyError::Config(
+            "Cleanup check interval must be greater than 0".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate periodic CAS re-check configuration
+fn validate_cas_recheck_config(config: &super::CasRecheckConfig) -> Result<()> {
+    if config.interval_hours == 0 || config.active_within_days == 0 {
+        return Err(SwingBuddyError::Config(
+            "CAS re-check interval and activity window must be greater than 0".to_string()
+        ));
+    }
+
+    if !(1..=1000).contains(&config.batch_size) {
+        return Err(SwingBuddyError::Config(
+            "CAS re-check batch size must be between 1 and 1000".to_string()
+        ));
+    }
+
+    if config.check_interval_seconds == 0 {
+        return Err(SwingBuddyError::Config(
+            "CAS re-check interval must be greater than 0".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate data export configuration
+fn validate_export_config(config: &super::ExportConfig) -> Result<()> {
+    if config.user_columns.is_empty() {
+        return Err(SwingBuddyError::Config(
+            "User export needs at least one column".to_string()
+        ));
+    }
+
+    for column in &config.user_columns {
+        column.parse::<crate::models::user::UserExportColumn>()
+            .map_err(SwingBuddyError::Config)?;
+    }
+
+    Ok(())
+}
+
+/// Validate webhook configuration
+fn validate_webhook_config(config: &super::WebhookConfig) -> Result<()> {
+    if config.max_connections.is_some_and(|n| !(1..=100).contains(&n)) {
+        return Err(SwingBuddyError::Config(
+            "Webhook max connections must be between 1 and 100".to_string()
+        ));
+    }
+
+    if let Some(ref path) = config.certificate_path {
+        if !std::path::Path::new(path).is_file() {
+            return Err(SwingBuddyError::Config(
+                format!("Webhook certificate not found: {}", path)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate health server configuration
+fn validate_health_config(config: &super::HealthConfig) -> Result<()> {
+    config.listen_address.parse::<std::net::SocketAddr>()
+        .map_err(|e| SwingBuddyError::Config(format!("Invalid health server listen address: {}", e)))?;
+
+    Ok(())
+}
+
+/// Validate command rate limits
+fn validate_rate_limits_config(config: &super::RateLimitsConfig) -> Result<()> {
+    if config.max_requests == 0 || config.window_seconds == 0 {
+        return Err(SwingBuddyError::Config(
+            "Rate limit requests and window must be greater than 0".to_string()
+        ));
+    }
+
+    for (command, limit) in &config.commands {
+        if limit.max_requests == 0 || limit.window_seconds == 0 {
+            return Err(SwingBuddyError::Config(format!("Rate limit of /{} must allow at least one request per window", command)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate public API configuration
+fn validate_api_config(config: &super::ApiConfig) -> Result<()> {
+    let mut names = std::collections::HashSet::new();
+    for key in &config.keys {
+        if key.name.trim().is_empty() {
+            return Err(SwingBuddyError::Config("API key names can't be empty".to_string()));
+        }
+        if !names.insert(key.name.as_str()) {
+            return Err(SwingBuddyError::Config(format!("API key name '{}' is used twice", key.name)));
+        }
+        if key.key.len() < 16 {
+            return Err(SwingBuddyError::Config(format!("API key '{}' must be at least 16 characters long", key.name)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate outgoing webhooks configuration
+fn validate_outgoing_webhooks_config(config: &super::OutgoingWebhooksConfig) -> Result<()> {
+    if config.timeout_seconds == 0 || config.max_attempts == 0 {
+        return Err(SwingBuddyError::Config(
+            "Outgoing webhook timeout and attempts must be greater than 0".to_string()
+        ));
+    }
+
+    for endpoint in &config.endpoints {
+        if !endpoint.url.starts_with("https://") && !endpoint.url.starts_with("http://") {
+            return Err(SwingBuddyError::Config(format!("Webhook endpoint '{}' must have an HTTP(S) URL", endpoint.name)));
+        }
+        if endpoint.secret.len() < 16 {
+            return Err(SwingBuddyError::Config(format!("Webhook endpoint '{}' needs a secret of at least 16 characters", endpoint.name)));
+        }
+        if let Some(unknown) = endpoint.events.iter().find(|event| event.parse::<crate::services::outgoing_webhook::WebhookEvent>().is_err()) {
+            return Err(SwingBuddyError::Config(format!("Webhook endpoint '{}' subscribes to unknown event '{}'", endpoint.name, unknown)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate event archival configuration
+fn validate_archive_config(config: &super::ArchiveConfig) -> Result<()> {
+    if config.retention_days == 0 {
+        return Err(SwingBuddyError::Config(
+            "Archive retention must be at least 1 day".to_string()
+        ));
+    }
+
+    if config.batch_size <= 0 || config.check_interval_seconds == 0 {
+        return Err(SwingBuddyError::Config(
+            "Archive batch size and check interval must be greater than 0".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
 /// Validate CAS configuration
 fn validate_cas_config(config: &super::CasConfig) -> Result<()> {
     if config.api_url.is_empty() {
@@ -103,6 +551,30 @@ fn validate_cas_config(config: &super::CasConfig) -> Result<()> {
             "CAS timeout must be greater than 0".to_string()
         ));
     }
+
+    if config.cache_ttl_seconds == 0 || config.negative_cache_ttl_seconds == 0 {
+        return Err(SwingBuddyError::Config(
+            "CAS cache TTLs must be greater than 0".to_string()
+        ));
+    }
+
+    if config.max_concurrent_requests == 0 {
+        return Err(SwingBuddyError::Config(
+            "CAS max_concurrent_requests must be greater than 0".to_string()
+        ));
+    }
+
+    if !(1..=1000).contains(&config.message_batch_size) {
+        return Err(SwingBuddyError::Config(
+            "CAS message_batch_size must be between 1 and 1000".to_string()
+        ));
+    }
+
+    if config.message_batch_wait_ms == 0 {
+        return Err(SwingBuddyError::Config(
+            "CAS message_batch_wait_ms must be greater than 0".to_string()
+        ));
+    }
     
     Ok(())
 }
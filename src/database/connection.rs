@@ -61,13 +61,15 @@ pub async fn run_migrations(pool: &DatabasePool) -> Result<(), SwingBuddyError>
     Ok(())
 }
 
-/// Check database health
-pub async fn health_check(pool: &DatabasePool) -> Result<(), SwingBuddyError> {
-    sqlx::query("SELECT 1")
-        .execute(pool)
-        .await?;
+/// Check database health; returns how long it took to get a connection from the pool
+pub async fn health_check(pool: &DatabasePool) -> Result<Duration, SwingBuddyError> {
+    let started = std::time::Instant::now();
+    let mut connection = pool.acquire().await?;
+    let acquire_wait = started.elapsed();
+
+    crate::utils::metrics::time_query("health_check", sqlx::query("SELECT 1").execute(&mut *connection)).await?;
     
-    Ok(())
+    Ok(acquire_wait)
 }
 
 #[cfg(test)]
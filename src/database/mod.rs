@@ -8,5 +8,5 @@ pub mod service;
 
 // Re-export commonly used database components
 pub use connection::{DatabasePool, DatabaseConfig, create_pool, run_migrations, health_check};
-pub use repositories::{UserRepository, GroupRepository, EventRepository, AdminRepository};
+pub use repositories::{UserRepository, GroupRepository, EventRepository, AdminRepository, NoteRepository, ReferralRepository, BirthdayRepository, DanceStyleRepository, CaptchaRepository, ModerationRepository, CleanupRepository, QuietHoursRepository, StatsRepository, MemberActivityRepository, InviteLinkRepository, RoleRepository, BroadcastRepository, ScheduledMessageRepository, CalendarRepository, NotificationQueueRepository, NotificationTemplateRepository, GoogleAccountRepository, GoogleCalendarSyncRepository, EventSheetRepository, ActivityRepository, SongRequestRepository, VenueRepository};
 pub use service::DatabaseService;
\ No newline at end of file
@@ -0,0 +1,90 @@
+//! Activity log repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::activity::{ActivityLogEntry, CreateActivityLogEntryRequest, ActivityCategory};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ActivityRepository {
+    pool: PgPool,
+}
+
+impl ActivityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record an action
+    pub async fn log(&self, request: CreateActivityLogEntryRequest) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            "INSERT INTO activity_log (action, actor_id, entity_type, entity_id, metadata, created_at) VALUES ($1, $2, $3, $4, $5, $6)"
+        )
+        .bind(request.action.as_str())
+        .bind(request.actor_id)
+        .bind(request.action.entity_type().as_str())
+        .bind(request.entity_id)
+        .bind(request.metadata)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// List actions, newest first, optionally only those of one category
+    pub async fn list(&self, category: Option<ActivityCategory>, limit: i64, offset: i64) -> Result<Vec<ActivityLogEntry>, SwingBuddyError> {
+        let entries = sqlx::query_as::<_, ActivityLogEntry>(
+            r#"
+            SELECT a.id, a.action, a.actor_id, u.first_name AS actor_name, u.username AS actor_username,
+                   a.entity_type, a.entity_id, a.metadata, a.created_at
+            FROM activity_log a
+            LEFT JOIN users u ON u.telegram_id = a.actor_id
+            WHERE $1::TEXT IS NULL OR a.action LIKE $1 || '.%'
+            ORDER BY a.created_at DESC, a.id DESC
+            LIMIT $2 OFFSET $3
+            "#
+        )
+        .bind(category.map(|category| category.as_str()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Count actions, optionally only those of one category
+    pub async fn count(&self, category: Option<ActivityCategory>) -> Result<i64, SwingBuddyError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM activity_log WHERE $1::TEXT IS NULL OR action LIKE $1 || '.%'"
+        )
+        .bind(category.map(|category| category.as_str()))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Actions done by or to a user, newest first
+    pub async fn list_for_user(&self, telegram_id: i64, limit: i64) -> Result<Vec<ActivityLogEntry>, SwingBuddyError> {
+        let entries = sqlx::query_as::<_, ActivityLogEntry>(
+            r#"
+            SELECT a.id, a.action, a.actor_id, u.first_name AS actor_name, u.username AS actor_username,
+                   a.entity_type, a.entity_id, a.metadata, a.created_at
+            FROM activity_log a
+            LEFT JOIN users u ON u.telegram_id = a.actor_id
+            WHERE a.actor_id = $1 OR (a.entity_type = 'user' AND a.entity_id = $1)
+            ORDER BY a.created_at DESC, a.id DESC
+            LIMIT $2
+            "#
+        )
+        .bind(telegram_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
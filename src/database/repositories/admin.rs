@@ -2,9 +2,12 @@
 
 use sqlx::PgPool;
 use chrono::Utc;
-use crate::models::admin::{AdminSettings, UserState, CasCheck, CreateAdminSettingRequest, UpdateAdminSettingRequest, CreateUserStateRequest, UpdateUserStateRequest, CreateCasCheckRequest};
+use crate::models::admin::{AdminSettings, UserState, CasCheck, CreateAdminSettingRequest, UpdateAdminSettingRequest, CreateUserStateRequest, UpdateUserStateRequest, CreateCasCheckRequest, CasWhitelistEntry, CreateCasWhitelistEntryRequest, AdminAuditEntry, CreateAdminAuditEntryRequest};
 use crate::utils::errors::SwingBuddyError;
 
+/// Tables included in a database backup
+pub const EXPORT_TABLES: [&str; 4] = ["users", "groups", "events", "event_participants"];
+
 #[derive(Clone)]
 #[derive(Debug)]
 pub struct AdminRepository {
@@ -177,6 +180,104 @@ impl AdminRepository {
         Ok(result.rows_affected() as i64)
     }
 
+    // CAS whitelist methods
+    /// Add a user to the CAS whitelist, updating the reason if they are on it already
+    pub async fn add_cas_whitelist_entry(&self, request: CreateCasWhitelistEntryRequest) -> Result<CasWhitelistEntry, SwingBuddyError> {
+        let entry = sqlx::query_as::<_, CasWhitelistEntry>(
+            r#"
+            INSERT INTO cas_whitelist (telegram_id, added_by, reason, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (telegram_id) DO UPDATE SET added_by = $2, reason = $3
+            RETURNING id, telegram_id, added_by, reason, created_at
+            "#
+        )
+        .bind(request.telegram_id)
+        .bind(request.added_by)
+        .bind(request.reason)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Remove a user from the CAS whitelist; returns `false` if they were not on it
+    pub async fn remove_cas_whitelist_entry(&self, telegram_id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM cas_whitelist WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Find the CAS whitelist entry of a user
+    pub async fn find_cas_whitelist_entry(&self, telegram_id: i64) -> Result<Option<CasWhitelistEntry>, SwingBuddyError> {
+        let entry = sqlx::query_as::<_, CasWhitelistEntry>(
+            "SELECT id, telegram_id, added_by, reason, created_at FROM cas_whitelist WHERE telegram_id = $1"
+        )
+        .bind(telegram_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// List the CAS whitelist, newest first
+    pub async fn list_cas_whitelist(&self) -> Result<Vec<CasWhitelistEntry>, SwingBuddyError> {
+        let entries = sqlx::query_as::<_, CasWhitelistEntry>(
+            "SELECT id, telegram_id, added_by, reason, created_at FROM cas_whitelist ORDER BY created_at DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    // Admin audit methods
+    /// Record an admin panel action
+    pub async fn log_admin_action(&self, request: CreateAdminAuditEntryRequest) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            "INSERT INTO admin_audit (admin_id, action, target, created_at) VALUES ($1, $2, $3, $4)"
+        )
+        .bind(request.admin_id)
+        .bind(request.action)
+        .bind(request.target)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// List admin panel actions, newest first
+    pub async fn list_admin_actions(&self, limit: i64, offset: i64) -> Result<Vec<AdminAuditEntry>, SwingBuddyError> {
+        let entries = sqlx::query_as::<_, AdminAuditEntry>(
+            r#"
+            SELECT a.id, a.admin_id, u.first_name AS admin_name, u.username AS admin_username, a.action, a.target, a.created_at
+            FROM admin_audit a
+            LEFT JOIN users u ON u.telegram_id = a.admin_id
+            ORDER BY a.created_at DESC, a.id DESC
+            LIMIT $1 OFFSET $2
+            "#
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Count the admin panel actions in the audit trail
+    pub async fn count_admin_actions(&self) -> Result<i64, SwingBuddyError> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM admin_audit")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
     // CAS Check methods
     /// Create CAS check record
     pub async fn create_cas_check(&self, request: CreateCasCheckRequest) -> Result<CasCheck, SwingBuddyError> {
@@ -258,6 +359,21 @@ impl AdminRepository {
         Ok(result.rows_affected() as i64)
     }
 
+    /// Dump all rows of a table as a JSON array, ordered by ID. Only the
+    /// tables listed in `EXPORT_TABLES` can be exported.
+    pub async fn export_table(&self, table: &str) -> Result<serde_json::Value, SwingBuddyError> {
+        if !EXPORT_TABLES.contains(&table) {
+            return Err(SwingBuddyError::InvalidInput(format!("Table {} cannot be exported", table)));
+        }
+
+        let query = format!("SELECT COALESCE(json_agg(t ORDER BY t.id), '[]'::json) FROM {} t", table);
+        let rows: serde_json::Value = sqlx::query_scalar(&query)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+
     /// Get statistics
     pub async fn get_stats(&self) -> Result<serde_json::Value, SwingBuddyError> {
         let user_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users")
@@ -272,6 +388,10 @@ impl AdminRepository {
             .fetch_one(&self.pool)
             .await?;
 
+        let archived_events: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events_archive")
+            .fetch_one(&self.pool)
+            .await?;
+
         let active_states: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM user_states WHERE expires_at IS NULL OR expires_at > NOW()")
             .fetch_one(&self.pool)
             .await?;
@@ -289,7 +409,8 @@ impl AdminRepository {
                 "total": group_count.0
             },
             "events": {
-                "total": event_count.0
+                "total": event_count.0,
+                "archived": archived_events.0
             },
             "states": {
                 "active": active_states.0
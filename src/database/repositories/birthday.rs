@@ -0,0 +1,111 @@
+//! Birthday repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::birthday::{UserBirthday, SetBirthdayRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct BirthdayRepository {
+    pool: PgPool,
+}
+
+impl BirthdayRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Set or replace a user's birthday
+    pub async fn upsert(&self, request: SetBirthdayRequest) -> Result<UserBirthday, SwingBuddyError> {
+        let birthday = sqlx::query_as::<_, UserBirthday>(
+            r#"
+            INSERT INTO user_birthdays (user_id, birth_day, birth_month, updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id) DO UPDATE
+            SET birth_day = $2, birth_month = $3, updated_at = $4
+            RETURNING user_id, birth_day, birth_month, announce_group_id, last_greeted_year, updated_at
+            "#
+        )
+        .bind(request.user_id)
+        .bind(request.birth_day)
+        .bind(request.birth_month)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(birthday)
+    }
+
+    /// Find birthday by user ID
+    pub async fn find_by_user(&self, user_id: i64) -> Result<Option<UserBirthday>, SwingBuddyError> {
+        let birthday = sqlx::query_as::<_, UserBirthday>(
+            "SELECT user_id, birth_day, birth_month, announce_group_id, last_greeted_year, updated_at FROM user_birthdays WHERE user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(birthday)
+    }
+
+    /// Delete a user's birthday
+    pub async fn delete(&self, user_id: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query("DELETE FROM user_birthdays WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Set the group where the birthday may be announced (`None` revokes consent)
+    pub async fn set_announce_group(&self, user_id: i64, group_id: Option<i64>) -> Result<Option<UserBirthday>, SwingBuddyError> {
+        let birthday = sqlx::query_as::<_, UserBirthday>(
+            r#"
+            UPDATE user_birthdays
+            SET announce_group_id = $2, updated_at = $3
+            WHERE user_id = $1
+            RETURNING user_id, birth_day, birth_month, announce_group_id, last_greeted_year, updated_at
+            "#
+        )
+        .bind(user_id)
+        .bind(group_id)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(birthday)
+    }
+
+    /// Get birthdays on the given month and days that were not greeted in `year` yet
+    pub async fn get_pending_greetings(&self, month: i16, days: &[i16], year: i32) -> Result<Vec<UserBirthday>, SwingBuddyError> {
+        let birthdays = sqlx::query_as::<_, UserBirthday>(
+            r#"
+            SELECT user_id, birth_day, birth_month, announce_group_id, last_greeted_year, updated_at
+            FROM user_birthdays
+            WHERE birth_month = $1
+              AND birth_day = ANY($2)
+              AND (last_greeted_year IS NULL OR last_greeted_year < $3)
+            "#
+        )
+        .bind(month)
+        .bind(days)
+        .bind(year)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(birthdays)
+    }
+
+    /// Record that the user was greeted this year
+    pub async fn mark_greeted(&self, user_id: i64, year: i32) -> Result<(), SwingBuddyError> {
+        sqlx::query("UPDATE user_birthdays SET last_greeted_year = $2 WHERE user_id = $1")
+            .bind(user_id)
+            .bind(year)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
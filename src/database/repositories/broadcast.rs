@@ -0,0 +1,252 @@
+//! Broadcast repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::broadcast::{Broadcast, CreateBroadcastRequest, BroadcastReport, BroadcastSegment, DeliveryStatus};
+use crate::utils::errors::SwingBuddyError;
+
+/// Reachable users taking broadcasts who match a segment; `$1` is the city, `$2` the language and `$3` the event ID,
+/// each `NULL` when the segment does not filter by it
+const SEGMENT_USERS: &str = r#"
+    SELECT u.telegram_id
+    FROM users u
+    WHERE u.is_banned = false
+      AND u.deleted_at IS NULL
+      AND u.blocked_at IS NULL
+      AND u.broadcasts_enabled
+      AND ($1::text IS NULL OR LOWER(u.location) = LOWER($1))
+      AND ($2::text IS NULL OR u.language_code = $2)
+      AND ($3::bigint IS NULL OR EXISTS (
+          SELECT 1 FROM event_participants ep WHERE ep.user_id = u.id AND ep.event_id = $3
+      ))
+"#;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct BroadcastRepository {
+    pool: PgPool,
+}
+
+impl BroadcastRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Count the users a broadcast to the segment would reach
+    pub async fn count_recipients(&self, segment: &BroadcastSegment) -> Result<i64, SwingBuddyError> {
+        let (city, language, event_id) = segment_binds(segment);
+        let count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM ({}) recipients", SEGMENT_USERS))
+            .bind(city)
+            .bind(language)
+            .bind(event_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Get one user of the segment to preview a personalized broadcast with
+    pub async fn sample_recipient(&self, segment: &BroadcastSegment) -> Result<Option<i64>, SwingBuddyError> {
+        let (city, language, event_id) = segment_binds(segment);
+        let user_id = sqlx::query_scalar::<_, i64>(&format!("SELECT telegram_id FROM ({}) recipients ORDER BY telegram_id LIMIT 1", SEGMENT_USERS))
+            .bind(city)
+            .bind(language)
+            .bind(event_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(user_id)
+    }
+
+    /// Get the most common user locations with their user counts
+    pub async fn get_top_cities(&self, limit: i64) -> Result<Vec<(String, i64)>, SwingBuddyError> {
+        let cities = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT MIN(location), COUNT(*)
+            FROM users
+            WHERE is_banned = false AND deleted_at IS NULL AND location IS NOT NULL AND location <> ''
+            GROUP BY LOWER(location)
+            ORDER BY COUNT(*) DESC, MIN(location)
+            LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(cities)
+    }
+
+    /// Get the languages of users with their user counts
+    pub async fn get_languages(&self) -> Result<Vec<(String, i64)>, SwingBuddyError> {
+        let languages = sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT language_code, COUNT(*)
+            FROM users
+            WHERE is_banned = false AND deleted_at IS NULL
+            GROUP BY language_code
+            ORDER BY COUNT(*) DESC, language_code
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(languages)
+    }
+
+    /// Create a broadcast and queue a delivery for every user in its segment
+    pub async fn create(&self, request: CreateBroadcastRequest) -> Result<Broadcast, SwingBuddyError> {
+        let mut tx = self.pool.begin().await?;
+
+        let broadcast = sqlx::query_as::<_, Broadcast>(
+            r#"
+            INSERT INTO broadcasts (created_by, language_code, segment, source_chat_id, source_message_id, text, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, created_by, language_code, segment, source_chat_id, source_message_id, text, total, created_at, finished_at
+            "#
+        )
+        .bind(request.created_by)
+        .bind(&request.language_code)
+        .bind(request.segment.to_string())
+        .bind(request.source_chat_id)
+        .bind(request.source_message_id)
+        .bind(&request.text)
+        .bind(Utc::now())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let (city, language, event_id) = segment_binds(&request.segment);
+        let queued = sqlx::query(&format!(
+            "INSERT INTO broadcast_deliveries (broadcast_id, user_telegram_id) SELECT $4, telegram_id FROM ({}) recipients",
+            SEGMENT_USERS
+        ))
+        .bind(city)
+        .bind(language)
+        .bind(event_id)
+        .bind(broadcast.id)
+        .execute(&mut *tx)
+        .await?;
+
+        let broadcast = sqlx::query_as::<_, Broadcast>(
+            r#"
+            UPDATE broadcasts SET total = $2
+            WHERE id = $1
+            RETURNING id, created_by, language_code, segment, source_chat_id, source_message_id, text, total, created_at, finished_at
+            "#
+        )
+        .bind(broadcast.id)
+        .bind(queued.rows_affected() as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(broadcast)
+    }
+
+    /// Get broadcasts that are still being delivered, oldest first
+    pub async fn get_unfinished(&self) -> Result<Vec<Broadcast>, SwingBuddyError> {
+        let broadcasts = sqlx::query_as::<_, Broadcast>(
+            r#"
+            SELECT id, created_by, language_code, segment, source_chat_id, source_message_id, text, total, created_at, finished_at
+            FROM broadcasts
+            WHERE finished_at IS NULL
+            ORDER BY created_at, id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(broadcasts)
+    }
+
+    /// Get the next recipients still waiting for a broadcast
+    pub async fn get_pending_recipients(&self, broadcast_id: i64, limit: i64) -> Result<Vec<i64>, SwingBuddyError> {
+        let recipients = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT user_telegram_id
+            FROM broadcast_deliveries
+            WHERE broadcast_id = $1 AND status = 'pending'
+            ORDER BY user_telegram_id
+            LIMIT $2
+            "#
+        )
+        .bind(broadcast_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(recipients)
+    }
+
+    /// Record the outcome of one delivery
+    pub async fn set_delivery_status(&self, broadcast_id: i64, user_telegram_id: i64, status: DeliveryStatus, error: Option<&str>) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            UPDATE broadcast_deliveries SET status = $3, error = $4, updated_at = $5
+            WHERE broadcast_id = $1 AND user_telegram_id = $2
+            "#
+        )
+        .bind(broadcast_id)
+        .bind(user_telegram_id)
+        .bind(status.as_str())
+        .bind(error)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count the deliveries of a broadcast by status
+    pub async fn get_report(&self, broadcast_id: i64) -> Result<BroadcastReport, SwingBuddyError> {
+        let report = sqlx::query_as::<_, BroadcastReport>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE status = 'sent') AS sent,
+                COUNT(*) FILTER (WHERE status = 'failed') AS failed,
+                COUNT(*) FILTER (WHERE status = 'pending') AS pending
+            FROM broadcast_deliveries
+            WHERE broadcast_id = $1
+            "#
+        )
+        .bind(broadcast_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    /// Mark a broadcast as delivered
+    pub async fn finish(&self, broadcast_id: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query("UPDATE broadcasts SET finished_at = $2 WHERE id = $1")
+            .bind(broadcast_id)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Split a segment into the city, language and event ID filters of `SEGMENT_USERS`
+fn segment_binds(segment: &BroadcastSegment) -> (Option<String>, Option<String>, Option<i64>) {
+    match segment {
+        BroadcastSegment::All => (None, None, None),
+        BroadcastSegment::City(city) => (Some(city.clone()), None, None),
+        BroadcastSegment::Language(language) => (None, Some(language.clone()), None),
+        BroadcastSegment::EventParticipants(event_id) => (None, None, Some(*event_id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_binds() {
+        assert_eq!(segment_binds(&BroadcastSegment::All), (None, None, None));
+        assert_eq!(segment_binds(&BroadcastSegment::City("Berlin".to_string())), (Some("Berlin".to_string()), None, None));
+        assert_eq!(segment_binds(&BroadcastSegment::Language("ru".to_string())), (None, Some("ru".to_string()), None));
+        assert_eq!(segment_binds(&BroadcastSegment::EventParticipants(42)), (None, None, Some(42)));
+    }
+}
@@ -0,0 +1,91 @@
+//! Calendar repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::calendar::{Calendar, CreateCalendarRequest, UpdateCalendarRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct CalendarRepository {
+    pool: PgPool,
+}
+
+impl CalendarRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a new calendar
+    pub async fn create(&self, request: CreateCalendarRequest) -> Result<Calendar, SwingBuddyError> {
+        let calendar = sqlx::query_as::<_, Calendar>(
+            r#"
+            INSERT INTO calendars (name, description, google_calendar_id, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, name, description, google_calendar_id, created_by, created_at, updated_at
+            "#
+        )
+        .bind(request.name)
+        .bind(request.description)
+        .bind(request.google_calendar_id)
+        .bind(request.created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(calendar)
+    }
+
+    /// Find calendar by ID
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<Calendar>, SwingBuddyError> {
+        let calendar = sqlx::query_as::<_, Calendar>(
+            "SELECT id, name, description, google_calendar_id, created_by, created_at, updated_at FROM calendars WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(calendar)
+    }
+
+    /// Get all calendars in the order they were added
+    pub async fn list(&self) -> Result<Vec<Calendar>, SwingBuddyError> {
+        let calendars = sqlx::query_as::<_, Calendar>(
+            "SELECT id, name, description, google_calendar_id, created_by, created_at, updated_at FROM calendars ORDER BY id"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(calendars)
+    }
+
+    /// Replace the editable fields of a calendar
+    pub async fn update(&self, id: i64, request: UpdateCalendarRequest) -> Result<Calendar, SwingBuddyError> {
+        let calendar = sqlx::query_as::<_, Calendar>(
+            r#"
+            UPDATE calendars
+            SET name = $2, description = $3, google_calendar_id = $4, updated_at = $5
+            WHERE id = $1
+            RETURNING id, name, description, google_calendar_id, created_by, created_at, updated_at
+            "#
+        )
+        .bind(id)
+        .bind(request.name)
+        .bind(request.description)
+        .bind(request.google_calendar_id)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(calendar)
+    }
+
+    /// Delete a calendar; returns false if it did not exist
+    pub async fn delete(&self, id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM calendars WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
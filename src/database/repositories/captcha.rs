@@ -0,0 +1,95 @@
+//! Join captcha repository implementation
+
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use crate::models::captcha::{PendingCaptcha, CreatePendingCaptchaRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct CaptchaRepository {
+    pool: PgPool,
+}
+
+impl CaptchaRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a pending captcha, replacing an older one for the same member
+    pub async fn upsert(&self, request: CreatePendingCaptchaRequest) -> Result<PendingCaptcha, SwingBuddyError> {
+        let captcha = sqlx::query_as::<_, PendingCaptcha>(
+            r#"
+            INSERT INTO pending_captchas (chat_telegram_id, user_telegram_id, answer, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (chat_telegram_id, user_telegram_id) DO UPDATE
+            SET answer = $3, expires_at = $4, message_id = NULL, created_at = $5
+            RETURNING id, chat_telegram_id, user_telegram_id, message_id, answer, expires_at, created_at
+            "#
+        )
+        .bind(request.chat_telegram_id)
+        .bind(request.user_telegram_id)
+        .bind(request.answer)
+        .bind(request.expires_at)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(captcha)
+    }
+
+    /// Remember the message with the captcha so it can be deleted later
+    pub async fn set_message_id(&self, id: i64, message_id: i32) -> Result<(), SwingBuddyError> {
+        sqlx::query("UPDATE pending_captchas SET message_id = $2 WHERE id = $1")
+            .bind(id)
+            .bind(message_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Find the pending captcha of a member in a chat
+    pub async fn find(&self, chat_telegram_id: i64, user_telegram_id: i64) -> Result<Option<PendingCaptcha>, SwingBuddyError> {
+        let captcha = sqlx::query_as::<_, PendingCaptcha>(
+            r#"
+            SELECT id, chat_telegram_id, user_telegram_id, message_id, answer, expires_at, created_at
+            FROM pending_captchas
+            WHERE chat_telegram_id = $1 AND user_telegram_id = $2
+            "#
+        )
+        .bind(chat_telegram_id)
+        .bind(user_telegram_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(captcha)
+    }
+
+    /// Get captchas that were not solved before `now`
+    pub async fn get_expired(&self, now: DateTime<Utc>) -> Result<Vec<PendingCaptcha>, SwingBuddyError> {
+        let captchas = sqlx::query_as::<_, PendingCaptcha>(
+            r#"
+            SELECT id, chat_telegram_id, user_telegram_id, message_id, answer, expires_at, created_at
+            FROM pending_captchas
+            WHERE expires_at <= $1
+            ORDER BY expires_at
+            "#
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(captchas)
+    }
+
+    /// Delete a pending captcha
+    pub async fn delete(&self, id: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query("DELETE FROM pending_captchas WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+//! Message cleanup repository implementation
+
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use crate::models::cleanup::{ScheduledDeletion, CreateScheduledDeletionRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct CleanupRepository {
+    pool: PgPool,
+}
+
+impl CleanupRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Schedule a message for deletion; an already scheduled message keeps its time
+    pub async fn schedule(&self, request: CreateScheduledDeletionRequest) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            INSERT INTO scheduled_deletions (chat_telegram_id, message_id, delete_at, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (chat_telegram_id, message_id) DO NOTHING
+            "#
+        )
+        .bind(request.chat_telegram_id)
+        .bind(request.message_id)
+        .bind(request.delete_at)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get messages that are due for deletion at `now`
+    pub async fn get_due(&self, now: DateTime<Utc>) -> Result<Vec<ScheduledDeletion>, SwingBuddyError> {
+        let deletions = sqlx::query_as::<_, ScheduledDeletion>(
+            r#"
+            SELECT id, chat_telegram_id, message_id, delete_at, created_at
+            FROM scheduled_deletions
+            WHERE delete_at <= $1
+            ORDER BY delete_at
+            "#
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deletions)
+    }
+
+    /// Delete all scheduled deletions of a chat. Returns the number of rows deleted.
+    pub async fn delete_for_chat(&self, chat_telegram_id: i64) -> Result<u64, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM scheduled_deletions WHERE chat_telegram_id = $1")
+            .bind(chat_telegram_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete a scheduled deletion
+    pub async fn delete(&self, id: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query("DELETE FROM scheduled_deletions WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+//! Dance style repository implementation
+
+use sqlx::PgPool;
+use crate::models::dance_style::DanceStyle;
+use crate::models::user::User;
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct DanceStyleRepository {
+    pool: PgPool,
+}
+
+impl DanceStyleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get a user's favorite styles
+    pub async fn get_user_styles(&self, user_id: i64) -> Result<Vec<DanceStyle>, SwingBuddyError> {
+        let styles = sqlx::query_scalar::<_, String>(
+            "SELECT style FROM user_dance_styles WHERE user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Self::parse_styles(styles))
+    }
+
+    /// Replace a user's favorite styles
+    pub async fn set_user_styles(&self, user_id: i64, styles: &[DanceStyle]) -> Result<(), SwingBuddyError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM user_dance_styles WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("INSERT INTO user_dance_styles (user_id, style) SELECT $1, UNNEST($2::VARCHAR[])")
+            .bind(user_id)
+            .bind(Self::style_names(styles))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Add a style to a user's favorites; returns `false` if it was already there
+    pub async fn add_user_style(&self, user_id: i64, style: DanceStyle) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("INSERT INTO user_dance_styles (user_id, style) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+            .bind(user_id)
+            .bind(style.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove a style from a user's favorites; returns `false` if it was not there
+    pub async fn remove_user_style(&self, user_id: i64, style: DanceStyle) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM user_dance_styles WHERE user_id = $1 AND style = $2")
+            .bind(user_id)
+            .bind(style.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the styles an event is tagged with
+    pub async fn get_event_styles(&self, event_id: i64) -> Result<Vec<DanceStyle>, SwingBuddyError> {
+        let styles = sqlx::query_scalar::<_, String>(
+            "SELECT style FROM event_dance_styles WHERE event_id = $1"
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Self::parse_styles(styles))
+    }
+
+    /// Replace the styles an event is tagged with
+    pub async fn set_event_styles(&self, event_id: i64, styles: &[DanceStyle]) -> Result<(), SwingBuddyError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM event_dance_styles WHERE event_id = $1")
+            .bind(event_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("INSERT INTO event_dance_styles (event_id, style) SELECT $1, UNNEST($2::VARCHAR[])")
+            .bind(event_id)
+            .bind(Self::style_names(styles))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Get non-banned users who like at least one of the given styles
+    pub async fn find_users_by_styles(&self, styles: &[DanceStyle]) -> Result<Vec<User>, SwingBuddyError> {
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, u.language_code, u.location, u.is_banned, u.created_at, u.updated_at
+            FROM users u
+            WHERE u.is_banned = false
+              AND u.deleted_at IS NULL
+              AND EXISTS (
+                SELECT 1 FROM user_dance_styles s
+                WHERE s.user_id = u.id AND s.style = ANY($1)
+              )
+            ORDER BY u.created_at
+            "#
+        )
+        .bind(Self::style_names(styles))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// Get non-banned users whose styles match the event's style tags
+    pub async fn find_users_for_event(&self, event_id: i64) -> Result<Vec<User>, SwingBuddyError> {
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, u.language_code, u.location, u.is_banned, u.created_at, u.updated_at
+            FROM users u
+            WHERE u.is_banned = false
+              AND u.deleted_at IS NULL
+              AND EXISTS (
+                SELECT 1 FROM user_dance_styles s
+                JOIN event_dance_styles e ON e.style = s.style
+                WHERE s.user_id = u.id AND e.event_id = $1
+              )
+            ORDER BY u.created_at
+            "#
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    fn style_names(styles: &[DanceStyle]) -> Vec<String> {
+        styles.iter().map(|s| s.as_str().to_string()).collect()
+    }
+
+    fn parse_styles(styles: Vec<String>) -> Vec<DanceStyle> {
+        let mut styles: Vec<DanceStyle> = styles.iter().filter_map(|s| s.parse().ok()).collect();
+        styles.sort_by_key(|s| DanceStyle::ALL.iter().position(|a| a == s));
+        styles
+    }
+}
@@ -1,8 +1,9 @@
 //! Event repository implementation
 
 use sqlx::PgPool;
-use chrono::Utc;
-use crate::models::event::{Event, EventParticipant, CreateEventRequest, UpdateEventRequest, RegisterParticipantRequest};
+use chrono::{DateTime, Utc};
+use crate::models::event::{Event, EventParticipant, EventGroupReminder, CreateEventRequest, UpdateEventRequest, RegisterParticipantRequest, ParticipantExportRow};
+use crate::models::trash::TrashEntry;
 use crate::utils::errors::SwingBuddyError;
 
 #[derive(Clone)]
@@ -43,7 +44,7 @@ impl EventRepository {
     /// Find event by ID
     pub async fn find_by_id(&self, id: i64) -> Result<Option<Event>, SwingBuddyError> {
         let event = sqlx::query_as::<_, Event>(
-            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE id = $1"
+            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE id = $1 AND deleted_at IS NULL"
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -52,6 +53,18 @@ impl EventRepository {
         Ok(event)
     }
 
+    /// Find the event linked to a Google Calendar event
+    pub async fn find_by_google_calendar_id(&self, google_calendar_id: &str) -> Result<Option<Event>, SwingBuddyError> {
+        let event = sqlx::query_as::<_, Event>(
+            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE google_calendar_id = $1 AND deleted_at IS NULL"
+        )
+        .bind(google_calendar_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(event)
+    }
+
     /// Update event
     pub async fn update(&self, id: i64, request: UpdateEventRequest) -> Result<Event, SwingBuddyError> {
         let event = sqlx::query_as::<_, Event>(
@@ -84,20 +97,106 @@ impl EventRepository {
         Ok(event)
     }
 
-    /// Delete event
-    pub async fn delete(&self, id: i64) -> Result<(), SwingBuddyError> {
-        sqlx::query("DELETE FROM events WHERE id = $1")
+    /// Move an event to the trash. Returns whether it was there to delete.
+    pub async fn delete(&self, id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("UPDATE events SET deleted_at = $2 WHERE id = $1 AND deleted_at IS NULL")
             .bind(id)
+            .bind(Utc::now())
             .execute(&self.pool)
             .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take an event out of the trash
+    pub async fn restore(&self, id: i64) -> Result<Option<Event>, SwingBuddyError> {
+        let event = sqlx::query_as::<_, Event>(
+            "UPDATE events SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL RETURNING id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// List deleted events, most recently deleted first
+    pub async fn list_deleted(&self, limit: i64, offset: i64) -> Result<Vec<TrashEntry>, SwingBuddyError> {
+        let events = sqlx::query_as::<_, TrashEntry>(
+            r#"
+            SELECT id, title || ' (' || TO_CHAR(event_date, 'DD.MM.YYYY') || ')' AS label, deleted_at
+            FROM events
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, id DESC
+            LIMIT $1 OFFSET $2
+            "#
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Count deleted events
+    pub async fn count_deleted(&self) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events WHERE deleted_at IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count.0)
+    }
+
+    /// Move up to `limit` events that took place before `before`, deleted or not,
+    /// with their participants into the archive tables. Returns the number of
+    /// events moved.
+    pub async fn archive_before(&self, before: DateTime<Utc>, limit: i64) -> Result<u64, SwingBuddyError> {
+        let mut tx = self.pool.begin().await?;
+
+        let ids: Vec<(i64,)> = sqlx::query_as(
+            "SELECT id FROM events WHERE event_date < $1 ORDER BY event_date LIMIT $2 FOR UPDATE SKIP LOCKED"
+        )
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+        let ids: Vec<i64> = ids.into_iter().map(|(id,)| id).collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        sqlx::query("INSERT INTO event_participants_archive SELECT *, NOW() FROM event_participants WHERE event_id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO events_archive SELECT *, NOW() FROM events WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+        // Participants, reminders, dance styles and sheet links go with the event
+        let result = sqlx::query("DELETE FROM events WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Count archived events
+    pub async fn count_archived(&self) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events_archive")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count.0)
     }
 
     /// List events with pagination
     pub async fn list(&self, limit: i64, offset: i64) -> Result<Vec<Event>, SwingBuddyError> {
         let events = sqlx::query_as::<_, Event>(
-            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events ORDER BY event_date ASC LIMIT $1 OFFSET $2"
+            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE deleted_at IS NULL ORDER BY event_date ASC LIMIT $1 OFFSET $2"
         )
         .bind(limit)
         .bind(offset)
@@ -111,8 +210,56 @@ impl EventRepository {
     pub async fn get_upcoming_events(&self, limit: Option<i64>) -> Result<Vec<Event>, SwingBuddyError> {
         let limit = limit.unwrap_or(50);
         let events = sqlx::query_as::<_, Event>(
-            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE event_date > NOW() AND is_active = true ORDER BY event_date ASC LIMIT $1"
+            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE event_date > NOW() AND is_active = true AND deleted_at IS NULL ORDER BY event_date ASC LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Get the upcoming events whose location mentions a place, soonest first
+    pub async fn get_upcoming_events_at(&self, place: &str, limit: i64) -> Result<Vec<Event>, SwingBuddyError> {
+        let events = sqlx::query_as::<_, Event>(
+            r#"
+            SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at
+            FROM events
+            WHERE event_date > NOW() AND is_active = true AND deleted_at IS NULL
+              AND STRPOS(LOWER(location), LOWER($1)) > 0
+            ORDER BY event_date ASC
+            LIMIT $2
+            "#
+        )
+        .bind(place)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Get the nearest upcoming event, only looking at the events of a group
+    /// and at events whose location mentions a city when these are given
+    pub async fn get_next_event(&self, city: Option<&str>, group_id: Option<i64>) -> Result<Option<Event>, SwingBuddyError> {
+        Ok(self.get_next_events(city, group_id, 1).await?.into_iter().next())
+    }
+
+    /// Get the nearest upcoming events, soonest first, filtered like `get_next_event`
+    pub async fn get_next_events(&self, city: Option<&str>, group_id: Option<i64>, limit: i64) -> Result<Vec<Event>, SwingBuddyError> {
+        let events = sqlx::query_as::<_, Event>(
+            r#"
+            SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at
+            FROM events
+            WHERE event_date > NOW() AND is_active = true AND deleted_at IS NULL
+              AND ($1::text IS NULL OR STRPOS(LOWER(location), LOWER($1)) > 0)
+              AND ($2::bigint IS NULL OR group_id = $2)
+            ORDER BY event_date ASC
+            LIMIT $3
+            "#
         )
+        .bind(city)
+        .bind(group_id)
         .bind(limit)
         .fetch_all(&self.pool)
         .await?;
@@ -123,9 +270,28 @@ impl EventRepository {
     /// Get events for group
     pub async fn get_group_events(&self, group_id: i64) -> Result<Vec<Event>, SwingBuddyError> {
         let events = sqlx::query_as::<_, Event>(
-            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE group_id = $1 AND is_active = true ORDER BY event_date ASC"
+            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE group_id = $1 AND is_active = true AND deleted_at IS NULL ORDER BY event_date ASC"
+        )
+        .bind(group_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Get active events of a group taking place in `[from, until)`
+    pub async fn get_group_events_between(&self, group_id: i64, from: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<Event>, SwingBuddyError> {
+        let events = sqlx::query_as::<_, Event>(
+            r#"
+            SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at
+            FROM events
+            WHERE group_id = $1 AND is_active = true AND deleted_at IS NULL AND event_date >= $2 AND event_date < $3
+            ORDER BY event_date ASC
+            "#
         )
         .bind(group_id)
+        .bind(from)
+        .bind(until)
         .fetch_all(&self.pool)
         .await?;
 
@@ -174,6 +340,24 @@ impl EventRepository {
         Ok(participants)
     }
 
+    /// Get the participants of an event with their profile, in registration order
+    pub async fn get_participant_export(&self, event_id: i64) -> Result<Vec<ParticipantExportRow>, SwingBuddyError> {
+        let rows = sqlx::query_as::<_, ParticipantExportRow>(
+            r#"
+            SELECT u.telegram_id, u.username, u.first_name, u.last_name, u.location, ep.status, ep.registered_at
+            FROM event_participants ep
+            INNER JOIN users u ON u.id = ep.user_id
+            WHERE ep.event_id = $1
+            ORDER BY ep.registered_at, ep.id
+            "#
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
     /// Check if user is registered for event
     pub async fn is_registered(&self, event_id: i64, user_id: i64) -> Result<bool, SwingBuddyError> {
         let count: (i64,) = sqlx::query_as(
@@ -218,10 +402,22 @@ impl EventRepository {
         Ok(count.0)
     }
 
+    /// Get participant counts for several events; events without participants are left out
+    pub async fn get_participant_counts(&self, event_ids: &[i64]) -> Result<Vec<(i64, i64)>, SwingBuddyError> {
+        let counts = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT event_id, COUNT(*) FROM event_participants WHERE event_id = ANY($1) GROUP BY event_id"
+        )
+        .bind(event_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
     /// Get events created by user
     pub async fn get_user_events(&self, user_id: i64) -> Result<Vec<Event>, SwingBuddyError> {
         let events = sqlx::query_as::<_, Event>(
-            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE created_by = $1 ORDER BY event_date ASC"
+            "SELECT id, title, description, event_date, location, max_participants, google_calendar_id, created_by, group_id, is_active, created_at, updated_at FROM events WHERE created_by = $1 AND deleted_at IS NULL ORDER BY event_date ASC"
         )
         .bind(user_id)
         .fetch_all(&self.pool)
@@ -237,7 +433,7 @@ impl EventRepository {
             SELECT e.id, e.title, e.description, e.event_date, e.location, e.max_participants, e.google_calendar_id, e.created_by, e.group_id, e.is_active, e.created_at, e.updated_at
             FROM events e
             INNER JOIN event_participants ep ON e.id = ep.event_id
-            WHERE ep.user_id = $1 AND e.is_active = true
+            WHERE ep.user_id = $1 AND e.is_active = true AND e.deleted_at IS NULL
             ORDER BY e.event_date ASC
             "#
         )
@@ -250,12 +446,178 @@ impl EventRepository {
 
     /// Count total events
     pub async fn count(&self) -> Result<i64, SwingBuddyError> {
-        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events")
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events WHERE deleted_at IS NULL")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count.0)
+    }
+
+    /// Count active events that haven't started yet
+    pub async fn count_upcoming(&self) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events WHERE event_date > NOW() AND is_active = true AND deleted_at IS NULL")
             .fetch_one(&self.pool)
             .await?;
 
         Ok(count.0)
     }
+
+    /// Get group reminder state for event
+    pub async fn get_group_reminder(&self, event_id: i64) -> Result<Option<EventGroupReminder>, SwingBuddyError> {
+        let reminder = sqlx::query_as::<_, EventGroupReminder>(
+            "SELECT event_id, is_enabled, sent_at, message_id, participant_count, is_pinned, updated_at FROM event_group_reminders WHERE event_id = $1"
+        )
+        .bind(event_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(reminder)
+    }
+
+    /// Enable or disable the group reminder for event
+    pub async fn set_group_reminder_enabled(&self, event_id: i64, is_enabled: bool) -> Result<EventGroupReminder, SwingBuddyError> {
+        let reminder = sqlx::query_as::<_, EventGroupReminder>(
+            r#"
+            INSERT INTO event_group_reminders (event_id, is_enabled, updated_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (event_id) DO UPDATE SET is_enabled = $2, updated_at = $3
+            RETURNING event_id, is_enabled, sent_at, message_id, participant_count, is_pinned, updated_at
+            "#
+        )
+        .bind(event_id)
+        .bind(is_enabled)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(reminder)
+    }
+
+    /// Mark the group reminder for event as sent
+    pub async fn mark_group_reminder_sent(&self, event_id: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            INSERT INTO event_group_reminders (event_id, sent_at, updated_at)
+            VALUES ($1, $2, $2)
+            ON CONFLICT (event_id) DO UPDATE SET sent_at = $2, updated_at = $2
+            "#
+        )
+        .bind(event_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count a failed attempt to post the group reminder for event; returns the attempts so far
+    pub async fn record_group_reminder_failure(&self, event_id: i64) -> Result<i32, SwingBuddyError> {
+        let attempts = sqlx::query_scalar::<_, i32>(
+            r#"
+            INSERT INTO event_group_reminders (event_id, failed_attempts, updated_at)
+            VALUES ($1, 1, $2)
+            ON CONFLICT (event_id) DO UPDATE
+            SET failed_attempts = event_group_reminders.failed_attempts + 1, updated_at = $2
+            RETURNING failed_attempts
+            "#
+        )
+        .bind(event_id)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(attempts)
+    }
+
+    /// Remember the posted group reminder message so it can be refreshed
+    pub async fn set_group_reminder_message(&self, event_id: i64, message_id: i32, participant_count: i32, is_pinned: bool) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            UPDATE event_group_reminders
+            SET message_id = $2, participant_count = $3, is_pinned = $4, updated_at = $5
+            WHERE event_id = $1
+            "#
+        )
+        .bind(event_id)
+        .bind(message_id)
+        .bind(participant_count)
+        .bind(is_pinned)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Update the participant count the posted group reminder shows
+    pub async fn set_group_reminder_count(&self, event_id: i64, participant_count: i32) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            "UPDATE event_group_reminders SET participant_count = $2, updated_at = $3 WHERE event_id = $1"
+        )
+        .bind(event_id)
+        .bind(participant_count)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stop tracking the posted group reminder message
+    pub async fn clear_group_reminder_message(&self, event_id: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            "UPDATE event_group_reminders SET message_id = NULL, is_pinned = false, updated_at = $2 WHERE event_id = $1"
+        )
+        .bind(event_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get group reminders whose posted message is still tracked
+    pub async fn get_tracked_group_reminders(&self) -> Result<Vec<EventGroupReminder>, SwingBuddyError> {
+        let reminders = sqlx::query_as::<_, EventGroupReminder>(
+            r#"
+            SELECT event_id, is_enabled, sent_at, message_id, participant_count, is_pinned, updated_at
+            FROM event_group_reminders
+            WHERE message_id IS NOT NULL
+            ORDER BY event_id ASC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(reminders)
+    }
+
+    /// Get group-linked events starting before `until` whose group reminder is still pending
+    /// and has failed fewer than `max_attempts` times
+    pub async fn get_pending_group_reminders(&self, until: DateTime<Utc>, max_attempts: i32) -> Result<Vec<Event>, SwingBuddyError> {
+        let events = sqlx::query_as::<_, Event>(
+            r#"
+            SELECT e.id, e.title, e.description, e.event_date, e.location, e.max_participants, e.google_calendar_id, e.created_by, e.group_id, e.is_active, e.created_at, e.updated_at
+            FROM events e
+            LEFT JOIN event_group_reminders r ON r.event_id = e.id
+            WHERE e.is_active = true
+              AND e.deleted_at IS NULL
+              AND e.group_id IS NOT NULL
+              AND e.event_date > NOW()
+              AND e.event_date <= $1
+              AND COALESCE(r.is_enabled, true)
+              AND r.sent_at IS NULL
+              AND COALESCE(r.failed_attempts, 0) < $2
+            ORDER BY e.event_date ASC
+            "#
+        )
+        .bind(until)
+        .bind(max_attempts)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,133 @@
+//! Event sheet repository implementation
+
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use crate::models::event::EventSheet;
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct EventSheetRepository {
+    pool: PgPool,
+}
+
+impl EventSheetRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get the sheet of an event
+    pub async fn get(&self, event_id: i64) -> Result<Option<EventSheet>, SwingBuddyError> {
+        let sheet = sqlx::query_as::<_, EventSheet>(
+            r#"
+            SELECT event_id, spreadsheet_id, needs_sync, sync_requested_at, last_synced_at, last_error, created_by, created_at
+            FROM event_sheets
+            WHERE event_id = $1
+            "#
+        )
+        .bind(event_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(sheet)
+    }
+
+    /// Link a spreadsheet to an event, replacing the previous one. It is exported on the next run.
+    pub async fn set(&self, event_id: i64, spreadsheet_id: &str, created_by: i64) -> Result<EventSheet, SwingBuddyError> {
+        let now = Utc::now();
+        let sheet = sqlx::query_as::<_, EventSheet>(
+            r#"
+            INSERT INTO event_sheets (event_id, spreadsheet_id, needs_sync, sync_requested_at, created_by, created_at)
+            VALUES ($1, $2, TRUE, $3, $4, $3)
+            ON CONFLICT (event_id) DO UPDATE SET
+                spreadsheet_id = EXCLUDED.spreadsheet_id,
+                needs_sync = TRUE,
+                sync_requested_at = EXCLUDED.sync_requested_at,
+                last_synced_at = NULL,
+                last_error = NULL,
+                created_by = EXCLUDED.created_by,
+                created_at = EXCLUDED.created_at
+            RETURNING event_id, spreadsheet_id, needs_sync, sync_requested_at, last_synced_at, last_error, created_by, created_at
+            "#
+        )
+        .bind(event_id)
+        .bind(spreadsheet_id)
+        .bind(now)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(sheet)
+    }
+
+    /// Stop mirroring an event. Returns whether it had a sheet.
+    pub async fn delete(&self, event_id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM event_sheets WHERE event_id = $1")
+            .bind(event_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Flag an event's sheet for export. Returns whether the event has one.
+    pub async fn mark_needs_sync(&self, event_id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("UPDATE event_sheets SET needs_sync = TRUE, sync_requested_at = $2 WHERE event_id = $1")
+            .bind(event_id)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the sheets waiting for an export, oldest request first
+    pub async fn get_needing_sync(&self, limit: i64) -> Result<Vec<EventSheet>, SwingBuddyError> {
+        let sheets = sqlx::query_as::<_, EventSheet>(
+            r#"
+            SELECT event_id, spreadsheet_id, needs_sync, sync_requested_at, last_synced_at, last_error, created_by, created_at
+            FROM event_sheets
+            WHERE needs_sync
+            ORDER BY sync_requested_at
+            LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sheets)
+    }
+
+    /// Record a successful export of the participants as of `started_at`.
+    /// Changes requested after that keep the sheet flagged.
+    pub async fn mark_synced(&self, event_id: i64, started_at: DateTime<Utc>) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            UPDATE event_sheets
+            SET needs_sync = needs_sync AND sync_requested_at > $2,
+                last_synced_at = $3,
+                last_error = NULL
+            WHERE event_id = $1
+            "#
+        )
+        .bind(event_id)
+        .bind(started_at)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a failed export; the sheet stays flagged and is retried
+    pub async fn mark_failed(&self, event_id: i64, error: &str) -> Result<(), SwingBuddyError> {
+        sqlx::query("UPDATE event_sheets SET last_error = $2 WHERE event_id = $1")
+            .bind(event_id)
+            .bind(error)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
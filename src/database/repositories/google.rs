@@ -0,0 +1,86 @@
+//! Google account repository implementation
+
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use crate::models::google::{GoogleAccount, ConnectGoogleAccountRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct GoogleAccountRepository {
+    pool: PgPool,
+}
+
+impl GoogleAccountRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get the connected account, if any
+    pub async fn get(&self) -> Result<Option<GoogleAccount>, SwingBuddyError> {
+        let account = sqlx::query_as::<_, GoogleAccount>(
+            r#"
+            SELECT email, access_token, refresh_token, expires_at, scope, connected_by, connected_at, updated_at
+            FROM google_account
+            WHERE id = 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// Store a newly connected account, replacing the previous one
+    pub async fn save(&self, request: ConnectGoogleAccountRequest) -> Result<GoogleAccount, SwingBuddyError> {
+        let now = Utc::now();
+        let account = sqlx::query_as::<_, GoogleAccount>(
+            r#"
+            INSERT INTO google_account (id, email, access_token, refresh_token, expires_at, scope, connected_by, connected_at, updated_at)
+            VALUES (1, $1, $2, $3, $4, $5, $6, $7, $7)
+            ON CONFLICT (id) DO UPDATE SET
+                email = EXCLUDED.email,
+                access_token = EXCLUDED.access_token,
+                refresh_token = EXCLUDED.refresh_token,
+                expires_at = EXCLUDED.expires_at,
+                scope = EXCLUDED.scope,
+                connected_by = EXCLUDED.connected_by,
+                connected_at = EXCLUDED.connected_at,
+                updated_at = EXCLUDED.updated_at
+            RETURNING email, access_token, refresh_token, expires_at, scope, connected_by, connected_at, updated_at
+            "#
+        )
+        .bind(&request.email)
+        .bind(&request.access_token)
+        .bind(&request.refresh_token)
+        .bind(request.expires_at)
+        .bind(&request.scope)
+        .bind(request.connected_by)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// Store a refreshed access token
+    pub async fn update_access_token(&self, access_token: &str, expires_at: DateTime<Utc>) -> Result<(), SwingBuddyError> {
+        sqlx::query("UPDATE google_account SET access_token = $1, expires_at = $2, updated_at = $3 WHERE id = 1")
+            .bind(access_token)
+            .bind(expires_at)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Forget the connected account. Returns whether there was one.
+    pub async fn delete(&self) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM google_account WHERE id = 1")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,117 @@
+//! Google Calendar sync repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::google::{GoogleCalendarSync, SaveWatchChannelRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct GoogleCalendarSyncRepository {
+    pool: PgPool,
+}
+
+impl GoogleCalendarSyncRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get the sync state of a calendar
+    pub async fn get(&self, calendar_id: &str) -> Result<Option<GoogleCalendarSync>, SwingBuddyError> {
+        let sync = sqlx::query_as::<_, GoogleCalendarSync>(
+            r#"
+            SELECT calendar_id, channel_id, resource_id, channel_token, address, expires_at, sync_token, last_synced_at, updated_at
+            FROM google_calendar_sync
+            WHERE calendar_id = $1
+            "#
+        )
+        .bind(calendar_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(sync)
+    }
+
+    /// Find the calendar a push channel belongs to
+    pub async fn find_by_channel(&self, channel_id: &str) -> Result<Option<GoogleCalendarSync>, SwingBuddyError> {
+        let sync = sqlx::query_as::<_, GoogleCalendarSync>(
+            r#"
+            SELECT calendar_id, channel_id, resource_id, channel_token, address, expires_at, sync_token, last_synced_at, updated_at
+            FROM google_calendar_sync
+            WHERE channel_id = $1
+            "#
+        )
+        .bind(channel_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(sync)
+    }
+
+    /// Store a newly registered push channel, replacing the previous one
+    pub async fn save_channel(&self, request: SaveWatchChannelRequest) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            INSERT INTO google_calendar_sync (calendar_id, channel_id, resource_id, channel_token, address, expires_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (calendar_id) DO UPDATE SET
+                channel_id = EXCLUDED.channel_id,
+                resource_id = EXCLUDED.resource_id,
+                channel_token = EXCLUDED.channel_token,
+                address = EXCLUDED.address,
+                expires_at = EXCLUDED.expires_at,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(&request.calendar_id)
+        .bind(&request.channel_id)
+        .bind(&request.resource_id)
+        .bind(&request.channel_token)
+        .bind(&request.address)
+        .bind(request.expires_at)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Forget the push channel of a calendar
+    pub async fn clear_channel(&self, calendar_id: &str) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            UPDATE google_calendar_sync
+            SET channel_id = NULL, resource_id = NULL, channel_token = NULL, address = NULL, expires_at = NULL, updated_at = $2
+            WHERE calendar_id = $1
+            "#
+        )
+        .bind(calendar_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the token for the next incremental sync; `None` forces a full sync
+    pub async fn save_sync_token(&self, calendar_id: &str, sync_token: Option<&str>) -> Result<(), SwingBuddyError> {
+        let now = Utc::now();
+        sqlx::query(
+            r#"
+            INSERT INTO google_calendar_sync (calendar_id, sync_token, last_synced_at, updated_at)
+            VALUES ($1, $2, $3, $3)
+            ON CONFLICT (calendar_id) DO UPDATE SET
+                sync_token = EXCLUDED.sync_token,
+                last_synced_at = EXCLUDED.last_synced_at,
+                updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(calendar_id)
+        .bind(sync_token)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
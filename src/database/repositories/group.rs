@@ -3,7 +3,9 @@
 use sqlx::PgPool;
 use chrono::Utc;
 use crate::models::group::{Group, GroupMember, CreateGroupRequest, UpdateGroupRequest, AddMemberRequest};
+use crate::models::trash::TrashEntry;
 use crate::utils::errors::SwingBuddyError;
+use crate::utils::metrics::time_query;
 
 #[derive(Clone)]
 #[derive(Debug)]
@@ -16,12 +18,19 @@ impl GroupRepository {
         Self { pool }
     }
 
-    /// Create a new group
+    /// Create a new group. A deleted group with the same Telegram ID is restored
+    /// under the new title instead, keeping its settings.
     pub async fn create(&self, request: CreateGroupRequest) -> Result<Group, SwingBuddyError> {
         let group = sqlx::query_as::<_, Group>(
             r#"
             INSERT INTO groups (telegram_id, title, description, language_code, settings, created_at, updated_at)
             VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (telegram_id) DO UPDATE SET
+                title = EXCLUDED.title,
+                is_active = true,
+                updated_at = EXCLUDED.updated_at,
+                deleted_at = NULL
+            WHERE groups.deleted_at IS NOT NULL
             RETURNING id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at
             "#
         )
@@ -41,7 +50,7 @@ impl GroupRepository {
     /// Find group by ID
     pub async fn find_by_id(&self, id: i64) -> Result<Option<Group>, SwingBuddyError> {
         let group = sqlx::query_as::<_, Group>(
-            "SELECT id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at FROM groups WHERE id = $1"
+            "SELECT id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at FROM groups WHERE id = $1 AND deleted_at IS NULL"
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -52,11 +61,11 @@ impl GroupRepository {
 
     /// Find group by Telegram ID
     pub async fn find_by_telegram_id(&self, telegram_id: i64) -> Result<Option<Group>, SwingBuddyError> {
-        let group = sqlx::query_as::<_, Group>(
-            "SELECT id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at FROM groups WHERE telegram_id = $1"
+        let group = time_query("groups.find_by_telegram_id", sqlx::query_as::<_, Group>(
+            "SELECT id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at FROM groups WHERE telegram_id = $1 AND deleted_at IS NULL"
         )
         .bind(telegram_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&self.pool))
         .await?;
 
         Ok(group)
@@ -90,20 +99,76 @@ impl GroupRepository {
         Ok(group)
     }
 
-    /// Delete group
-    pub async fn delete(&self, id: i64) -> Result<(), SwingBuddyError> {
-        sqlx::query("DELETE FROM groups WHERE id = $1")
+    /// Set a single key in the group settings, keeping the other keys
+    pub async fn set_setting(&self, id: i64, key: &str, value: serde_json::Value) -> Result<Group, SwingBuddyError> {
+        let group = sqlx::query_as::<_, Group>(
+            r#"
+            UPDATE groups
+            SET settings = COALESCE(settings, '{}'::jsonb) || jsonb_build_object($2::text, $3::jsonb),
+                updated_at = $4
+            WHERE id = $1
+            RETURNING id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at
+            "#
+        )
+        .bind(id)
+        .bind(key)
+        .bind(value)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(group)
+    }
+
+    /// Move a group to the trash. Returns whether it was there to delete.
+    pub async fn delete(&self, id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("UPDATE groups SET deleted_at = $2 WHERE id = $1 AND deleted_at IS NULL")
             .bind(id)
+            .bind(Utc::now())
             .execute(&self.pool)
             .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take a group out of the trash
+    pub async fn restore(&self, id: i64) -> Result<Option<Group>, SwingBuddyError> {
+        let group = sqlx::query_as::<_, Group>(
+            "UPDATE groups SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL RETURNING id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(group)
+    }
+
+    /// List deleted groups, most recently deleted first
+    pub async fn list_deleted(&self, limit: i64, offset: i64) -> Result<Vec<TrashEntry>, SwingBuddyError> {
+        let groups = sqlx::query_as::<_, TrashEntry>(
+            "SELECT id, title AS label, deleted_at FROM groups WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id DESC LIMIT $1 OFFSET $2"
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(groups)
+    }
+
+    /// Count deleted groups
+    pub async fn count_deleted(&self) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM groups WHERE deleted_at IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count.0)
     }
 
     /// List all groups with pagination
     pub async fn list(&self, limit: i64, offset: i64) -> Result<Vec<Group>, SwingBuddyError> {
         let groups = sqlx::query_as::<_, Group>(
-            "SELECT id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at FROM groups ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+            "SELECT id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at FROM groups WHERE deleted_at IS NULL ORDER BY created_at DESC LIMIT $1 OFFSET $2"
         )
         .bind(limit)
         .bind(offset)
@@ -115,7 +180,7 @@ impl GroupRepository {
 
     /// Count total groups
     pub async fn count(&self) -> Result<i64, SwingBuddyError> {
-        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM groups")
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM groups WHERE deleted_at IS NULL")
             .fetch_one(&self.pool)
             .await?;
 
@@ -152,6 +217,65 @@ impl GroupRepository {
         Ok(())
     }
 
+    /// Record a member of a group with their status, both by Telegram ID.
+    /// Returns `false` if the group or the user is unknown.
+    pub async fn save_member(&self, group_telegram_id: i64, user_telegram_id: i64, role: &str) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO group_members (group_id, user_id, role, joined_at)
+            SELECT g.id, u.id, $3, $4
+            FROM groups g, users u
+            WHERE g.telegram_id = $1 AND u.telegram_id = $2
+            ON CONFLICT (group_id, user_id) DO UPDATE SET role = EXCLUDED.role
+            "#
+        )
+        .bind(group_telegram_id)
+        .bind(user_telegram_id)
+        .bind(role)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove a member from a group, both by Telegram ID. Returns whether there was a record.
+    pub async fn forget_member(&self, group_telegram_id: i64, user_telegram_id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM group_members gm
+            USING groups g, users u
+            WHERE gm.group_id = g.id AND gm.user_id = u.id AND g.telegram_id = $1 AND u.telegram_id = $2
+            "#
+        )
+        .bind(group_telegram_id)
+        .bind(user_telegram_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the city most of a group's registered members live in
+    pub async fn get_member_city(&self, group_id: i64) -> Result<Option<String>, SwingBuddyError> {
+        let city = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT u.location
+            FROM group_members gm
+            JOIN users u ON u.id = gm.user_id
+            WHERE gm.group_id = $1 AND u.location IS NOT NULL AND u.deleted_at IS NULL
+            GROUP BY u.location
+            ORDER BY COUNT(*) DESC, u.location
+            LIMIT 1
+            "#
+        )
+        .bind(group_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(city)
+    }
+
     /// Get group members
     pub async fn get_members(&self, group_id: i64) -> Result<Vec<GroupMember>, SwingBuddyError> {
         let members = sqlx::query_as::<_, GroupMember>(
@@ -203,7 +327,7 @@ impl GroupRepository {
             SELECT g.id, g.telegram_id, g.title, g.description, g.language_code, g.settings, g.is_active, g.created_at, g.updated_at
             FROM groups g
             INNER JOIN group_members gm ON g.id = gm.group_id
-            WHERE gm.user_id = $1 AND g.is_active = true
+            WHERE gm.user_id = $1 AND g.is_active = true AND g.deleted_at IS NULL
             ORDER BY gm.joined_at DESC
             "#
         )
@@ -217,8 +341,20 @@ impl GroupRepository {
     /// Get active groups
     pub async fn get_active_groups(&self) -> Result<Vec<Group>, SwingBuddyError> {
         let groups = sqlx::query_as::<_, Group>(
-            "SELECT id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at FROM groups WHERE is_active = true ORDER BY created_at DESC"
+            "SELECT id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at FROM groups WHERE is_active = true AND deleted_at IS NULL ORDER BY created_at DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(groups)
+    }
+
+    /// Get active groups that turned on a boolean setting
+    pub async fn get_active_groups_with_flag(&self, key: &str) -> Result<Vec<Group>, SwingBuddyError> {
+        let groups = sqlx::query_as::<_, Group>(
+            "SELECT id, telegram_id, title, description, language_code, settings, is_active, created_at, updated_at FROM groups WHERE is_active = true AND deleted_at IS NULL AND settings @> jsonb_build_object($1::TEXT, true) ORDER BY created_at DESC"
         )
+        .bind(key)
         .fetch_all(&self.pool)
         .await?;
 
@@ -0,0 +1,145 @@
+//! Invite link repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::invite_link::{InviteLink, CreateInviteLinkRequest, InviteLinkStats, JoinRequestStatus};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct InviteLinkRepository {
+    pool: PgPool,
+}
+
+impl InviteLinkRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a newly created invite link
+    pub async fn create(&self, request: CreateInviteLinkRequest) -> Result<InviteLink, SwingBuddyError> {
+        let link = sqlx::query_as::<_, InviteLink>(
+            r#"
+            INSERT INTO invite_links (group_id, name, invite_link, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, group_id, name, invite_link, created_by, created_at, rotated_at, revoked_at
+            "#
+        )
+        .bind(request.group_id)
+        .bind(request.name)
+        .bind(request.invite_link)
+        .bind(request.created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    /// Find a group's invite link by ID
+    pub async fn find_by_id(&self, group_id: i64, id: i64) -> Result<Option<InviteLink>, SwingBuddyError> {
+        let link = sqlx::query_as::<_, InviteLink>(
+            r#"
+            SELECT id, group_id, name, invite_link, created_by, created_at, rotated_at, revoked_at
+            FROM invite_links
+            WHERE group_id = $1 AND id = $2
+            "#
+        )
+        .bind(group_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    /// Find an active invite link by its URL
+    pub async fn find_active_by_link(&self, invite_link: &str) -> Result<Option<InviteLink>, SwingBuddyError> {
+        let link = sqlx::query_as::<_, InviteLink>(
+            r#"
+            SELECT id, group_id, name, invite_link, created_by, created_at, rotated_at, revoked_at
+            FROM invite_links
+            WHERE invite_link = $1 AND revoked_at IS NULL
+            "#
+        )
+        .bind(invite_link)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    /// Get a group's active invite links with their join request counts
+    pub async fn get_stats_for_group(&self, group_id: i64) -> Result<Vec<InviteLinkStats>, SwingBuddyError> {
+        let stats = sqlx::query_as::<_, InviteLinkStats>(
+            r#"
+            SELECT
+                l.id,
+                l.name,
+                l.invite_link,
+                COUNT(r.user_telegram_id) AS requests,
+                COUNT(r.user_telegram_id) FILTER (WHERE r.status = 'approved') AS approved,
+                COUNT(r.user_telegram_id) FILTER (WHERE r.status = 'declined') AS declined
+            FROM invite_links l
+            LEFT JOIN invite_link_requests r ON r.invite_link_id = l.id
+            WHERE l.group_id = $1 AND l.revoked_at IS NULL
+            GROUP BY l.id
+            ORDER BY l.created_at
+            "#
+        )
+        .bind(group_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Replace the URL of a rotated link
+    pub async fn update_link(&self, id: i64, invite_link: &str) -> Result<Option<InviteLink>, SwingBuddyError> {
+        let link = sqlx::query_as::<_, InviteLink>(
+            r#"
+            UPDATE invite_links
+            SET invite_link = $2, rotated_at = $3
+            WHERE id = $1
+            RETURNING id, group_id, name, invite_link, created_by, created_at, rotated_at, revoked_at
+            "#
+        )
+        .bind(id)
+        .bind(invite_link)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    /// Mark a link as revoked; its statistics stay in the database
+    pub async fn revoke(&self, id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("UPDATE invite_links SET revoked_at = $2 WHERE id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a join request sent through a link
+    pub async fn record_request(&self, invite_link_id: i64, user_telegram_id: i64, status: JoinRequestStatus) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            INSERT INTO invite_link_requests (invite_link_id, user_telegram_id, status, requested_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (invite_link_id, user_telegram_id)
+            DO UPDATE SET status = EXCLUDED.status, requested_at = EXCLUDED.requested_at
+            "#
+        )
+        .bind(invite_link_id)
+        .bind(user_telegram_id)
+        .bind(status.as_str())
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! Member activity repository implementation
+
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use crate::utils::errors::SwingBuddyError;
+use crate::utils::metrics::time_query;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct MemberActivityRepository {
+    pool: PgPool,
+}
+
+impl MemberActivityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record that a member posted in a chat. The row is only rewritten when the
+    /// stored time is older than `stale_before`, so busy chats cause few writes.
+    pub async fn touch(&self, chat_telegram_id: i64, user_telegram_id: i64, now: DateTime<Utc>, stale_before: DateTime<Utc>) -> Result<(), SwingBuddyError> {
+        time_query("member_activity.touch", sqlx::query(
+            r#"
+            INSERT INTO member_activity (chat_telegram_id, user_telegram_id, last_seen_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (chat_telegram_id, user_telegram_id)
+            DO UPDATE SET last_seen_at = EXCLUDED.last_seen_at
+            WHERE member_activity.last_seen_at < $4
+            "#
+        )
+        .bind(chat_telegram_id)
+        .bind(user_telegram_id)
+        .bind(now)
+        .bind(stale_before)
+        .execute(&self.pool))
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get members active since `active_since` who were not checked since `checked_before`,
+    /// never checked members first
+    pub async fn get_due_for_recheck(&self, active_since: DateTime<Utc>, checked_before: DateTime<Utc>, limit: i64) -> Result<Vec<i64>, SwingBuddyError> {
+        let user_ids = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT user_telegram_id
+            FROM member_activity
+            WHERE last_seen_at >= $1
+            GROUP BY user_telegram_id
+            HAVING MAX(COALESCE(cas_checked_at, '-infinity'::TIMESTAMPTZ)) < $2
+            ORDER BY MAX(COALESCE(cas_checked_at, '-infinity'::TIMESTAMPTZ)), user_telegram_id
+            LIMIT $3
+            "#
+        )
+        .bind(active_since)
+        .bind(checked_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(user_ids)
+    }
+
+    /// Get the chats a member was seen in
+    pub async fn get_user_chats(&self, user_telegram_id: i64) -> Result<Vec<i64>, SwingBuddyError> {
+        let chat_ids = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT chat_telegram_id
+            FROM member_activity
+            WHERE user_telegram_id = $1
+            ORDER BY chat_telegram_id
+            "#
+        )
+        .bind(user_telegram_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(chat_ids)
+    }
+
+    /// Remember when a member was last checked against CAS
+    pub async fn mark_checked(&self, user_telegram_id: i64, checked_at: DateTime<Utc>) -> Result<(), SwingBuddyError> {
+        sqlx::query("UPDATE member_activity SET cas_checked_at = $2 WHERE user_telegram_id = $1")
+            .bind(user_telegram_id)
+            .bind(checked_at)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Forget a member in a chat, e.g. after they were banned
+    pub async fn delete(&self, chat_telegram_id: i64, user_telegram_id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM member_activity WHERE chat_telegram_id = $1 AND user_telegram_id = $2")
+            .bind(chat_telegram_id)
+            .bind(user_telegram_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete activity older than the given time; returns the number of rows removed
+    pub async fn delete_inactive(&self, before: DateTime<Utc>) -> Result<u64, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM member_activity WHERE last_seen_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -6,9 +6,55 @@ pub mod user;
 pub mod group;
 pub mod event;
 pub mod admin;
+pub mod note;
+pub mod referral;
+pub mod birthday;
+pub mod dance_style;
+pub mod captcha;
+pub mod moderation;
+pub mod cleanup;
+pub mod quiet_hours;
+pub mod stats;
+pub mod member_activity;
+pub mod invite_link;
+pub mod role;
+pub mod broadcast;
+pub mod scheduled_message;
+pub mod calendar;
+pub mod notification_queue;
+pub mod notification_template;
+pub mod google;
+pub mod google_calendar_sync;
+pub mod event_sheet;
+pub mod activity;
+pub mod song_request;
+pub mod venue;
 
 // Re-export repositories
 pub use user::UserRepository;
 pub use group::GroupRepository;
 pub use event::EventRepository;
-pub use admin::AdminRepository;
\ No newline at end of file
+pub use admin::AdminRepository;
+pub use note::NoteRepository;
+pub use referral::ReferralRepository;
+pub use birthday::BirthdayRepository;
+pub use dance_style::DanceStyleRepository;
+pub use captcha::CaptchaRepository;
+pub use moderation::ModerationRepository;
+pub use cleanup::CleanupRepository;
+pub use quiet_hours::QuietHoursRepository;
+pub use stats::StatsRepository;
+pub use member_activity::MemberActivityRepository;
+pub use invite_link::InviteLinkRepository;
+pub use role::RoleRepository;
+pub use broadcast::BroadcastRepository;
+pub use scheduled_message::ScheduledMessageRepository;
+pub use calendar::CalendarRepository;
+pub use notification_queue::NotificationQueueRepository;
+pub use notification_template::NotificationTemplateRepository;
+pub use google::GoogleAccountRepository;
+pub use google_calendar_sync::GoogleCalendarSyncRepository;
+pub use event_sheet::EventSheetRepository;
+pub use activity::ActivityRepository;
+pub use song_request::SongRequestRepository;
+pub use venue::VenueRepository;
\ No newline at end of file
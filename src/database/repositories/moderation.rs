@@ -0,0 +1,383 @@
+//! Moderation repository implementation
+
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use crate::models::moderation::{Warning, CreateWarningRequest, ModerationAction, CreateModerationActionRequest, Report, CreateReportRequest, SharedBan, CreateSharedBanRequest, ScheduledUnmute, SpamFilter, CreateSpamFilterRequest, BanListEntry};
+use crate::utils::errors::SwingBuddyError;
+
+/// Current bans: the latest ban or unban per member and group, kept if it is a ban,
+/// together with the registered users banned from the bot
+const CURRENT_BANS: &str = r#"
+    WITH latest AS (
+        SELECT DISTINCT ON (chat_telegram_id, target_telegram_id)
+               chat_telegram_id, target_telegram_id, actor_telegram_id, action, reason, created_at
+        FROM moderation_actions
+        WHERE action IN ('ban', 'unban')
+        ORDER BY chat_telegram_id, target_telegram_id, created_at DESC, id DESC
+    ),
+    bans AS (
+        SELECT target_telegram_id AS user_telegram_id, chat_telegram_id, actor_telegram_id, reason, created_at AS banned_at
+        FROM latest
+        WHERE action = 'ban'
+        UNION ALL
+        SELECT telegram_id, NULL, NULL, NULL, updated_at
+        FROM users
+        WHERE is_banned = true AND deleted_at IS NULL
+    )
+"#;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ModerationRepository {
+    pool: PgPool,
+}
+
+impl ModerationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a warning for a group member
+    pub async fn create_warning(&self, request: CreateWarningRequest) -> Result<Warning, SwingBuddyError> {
+        let warning = sqlx::query_as::<_, Warning>(
+            r#"
+            INSERT INTO warnings (chat_telegram_id, user_telegram_id, issued_by, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, chat_telegram_id, user_telegram_id, issued_by, reason, created_at
+            "#
+        )
+        .bind(request.chat_telegram_id)
+        .bind(request.user_telegram_id)
+        .bind(request.issued_by)
+        .bind(request.reason)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(warning)
+    }
+
+    /// Count the warnings of a member in a chat, optionally only those issued after `since`
+    pub async fn count_warnings(&self, chat_telegram_id: i64, user_telegram_id: i64, since: Option<DateTime<Utc>>) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM warnings
+            WHERE chat_telegram_id = $1 AND user_telegram_id = $2
+              AND ($3::TIMESTAMPTZ IS NULL OR created_at > $3)
+            "#
+        )
+        .bind(chat_telegram_id)
+        .bind(user_telegram_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    /// Record a moderation action in the audit trail
+    pub async fn log_action(&self, request: CreateModerationActionRequest) -> Result<ModerationAction, SwingBuddyError> {
+        let action = sqlx::query_as::<_, ModerationAction>(
+            r#"
+            INSERT INTO moderation_actions (chat_telegram_id, target_telegram_id, actor_telegram_id, action, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, chat_telegram_id, target_telegram_id, actor_telegram_id, action, reason, created_at
+            "#
+        )
+        .bind(request.chat_telegram_id)
+        .bind(request.target_telegram_id)
+        .bind(request.actor_telegram_id)
+        .bind(request.action.as_str())
+        .bind(request.reason)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(action)
+    }
+
+    /// Get the latest moderation actions in a chat, newest first
+    pub async fn find_recent_actions(&self, chat_telegram_id: i64, limit: i64) -> Result<Vec<ModerationAction>, SwingBuddyError> {
+        let actions = sqlx::query_as::<_, ModerationAction>(
+            r#"
+            SELECT id, chat_telegram_id, target_telegram_id, actor_telegram_id, action, reason, created_at
+            FROM moderation_actions
+            WHERE chat_telegram_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#
+        )
+        .bind(chat_telegram_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(actions)
+    }
+
+    /// Record a report; returns `None` if the member already reported this message
+    pub async fn create_report(&self, request: CreateReportRequest) -> Result<Option<Report>, SwingBuddyError> {
+        let report = sqlx::query_as::<_, Report>(
+            r#"
+            INSERT INTO reports (chat_telegram_id, message_id, reporter_telegram_id, reported_telegram_id, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (chat_telegram_id, message_id, reporter_telegram_id) DO NOTHING
+            RETURNING id, chat_telegram_id, message_id, reporter_telegram_id, reported_telegram_id, reason, status, resolved_by, resolved_at, created_at
+            "#
+        )
+        .bind(request.chat_telegram_id)
+        .bind(request.message_id)
+        .bind(request.reporter_telegram_id)
+        .bind(request.reported_telegram_id)
+        .bind(request.reason)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    /// Find a report by ID
+    pub async fn find_report(&self, id: i64) -> Result<Option<Report>, SwingBuddyError> {
+        let report = sqlx::query_as::<_, Report>(
+            r#"
+            SELECT id, chat_telegram_id, message_id, reporter_telegram_id, reported_telegram_id, reason, status, resolved_by, resolved_at, created_at
+            FROM reports
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    /// Mark an open report as resolved; returns `None` if it was already resolved
+    pub async fn resolve_report(&self, id: i64, resolved_by: i64) -> Result<Option<Report>, SwingBuddyError> {
+        let report = sqlx::query_as::<_, Report>(
+            r#"
+            UPDATE reports
+            SET status = 'resolved', resolved_by = $2, resolved_at = $3
+            WHERE id = $1 AND status = 'open'
+            RETURNING id, chat_telegram_id, message_id, reporter_telegram_id, reported_telegram_id, reason, status, resolved_by, resolved_at, created_at
+            "#
+        )
+        .bind(id)
+        .bind(resolved_by)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    /// Count open reports in a chat
+    pub async fn count_open_reports(&self, chat_telegram_id: i64) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM reports WHERE chat_telegram_id = $1 AND status = 'open'"
+        )
+        .bind(chat_telegram_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    /// Add a member to the shared ban list, keeping the original entry if they are on it already
+    pub async fn add_shared_ban(&self, request: CreateSharedBanRequest) -> Result<SharedBan, SwingBuddyError> {
+        let ban = sqlx::query_as::<_, SharedBan>(
+            r#"
+            INSERT INTO shared_bans (user_telegram_id, banned_by, origin_chat_telegram_id, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_telegram_id) DO UPDATE SET user_telegram_id = EXCLUDED.user_telegram_id
+            RETURNING id, user_telegram_id, banned_by, origin_chat_telegram_id, reason, created_at
+            "#
+        )
+        .bind(request.user_telegram_id)
+        .bind(request.banned_by)
+        .bind(request.origin_chat_telegram_id)
+        .bind(request.reason)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ban)
+    }
+
+    /// Find the shared ban of a member
+    pub async fn find_shared_ban(&self, user_telegram_id: i64) -> Result<Option<SharedBan>, SwingBuddyError> {
+        let ban = sqlx::query_as::<_, SharedBan>(
+            r#"
+            SELECT id, user_telegram_id, banned_by, origin_chat_telegram_id, reason, created_at
+            FROM shared_bans
+            WHERE user_telegram_id = $1
+            "#
+        )
+        .bind(user_telegram_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(ban)
+    }
+
+    /// Get the whole shared ban list, oldest first
+    pub async fn list_shared_bans(&self) -> Result<Vec<SharedBan>, SwingBuddyError> {
+        let bans = sqlx::query_as::<_, SharedBan>(
+            r#"
+            SELECT id, user_telegram_id, banned_by, origin_chat_telegram_id, reason, created_at
+            FROM shared_bans
+            ORDER BY created_at
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(bans)
+    }
+
+    /// Remove a member from the shared ban list; returns `false` if they were not on it
+    pub async fn remove_shared_ban(&self, user_telegram_id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM shared_bans WHERE user_telegram_id = $1")
+            .bind(user_telegram_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count the current bans: members whose latest ban/unban in a group is a ban,
+    /// plus registered users banned from the bot
+    pub async fn count_current_bans(&self) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as(&format!("{} SELECT COUNT(*) FROM bans", CURRENT_BANS))
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count.0)
+    }
+
+    /// Get a page of the current bans, newest first
+    pub async fn list_current_bans(&self, limit: i64, offset: i64) -> Result<Vec<BanListEntry>, SwingBuddyError> {
+        let bans = sqlx::query_as::<_, BanListEntry>(&format!(
+            r#"
+            {}
+            SELECT b.user_telegram_id, u.username, u.first_name, b.chat_telegram_id, g.title AS chat_title,
+                   b.actor_telegram_id, b.reason, b.banned_at
+            FROM bans b
+            LEFT JOIN users u ON u.telegram_id = b.user_telegram_id
+            LEFT JOIN groups g ON g.telegram_id = b.chat_telegram_id
+            ORDER BY b.banned_at DESC, b.user_telegram_id
+            LIMIT $1 OFFSET $2
+            "#,
+            CURRENT_BANS
+        ))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(bans)
+    }
+
+    /// Schedule a member's unmute, replacing an earlier one in the same chat
+    pub async fn schedule_unmute(&self, chat_telegram_id: i64, user_telegram_id: i64, unmute_at: DateTime<Utc>) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            INSERT INTO scheduled_unmutes (chat_telegram_id, user_telegram_id, unmute_at, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (chat_telegram_id, user_telegram_id) DO UPDATE SET unmute_at = EXCLUDED.unmute_at
+            "#
+        )
+        .bind(chat_telegram_id)
+        .bind(user_telegram_id)
+        .bind(unmute_at)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get the unmutes that are due at `now`
+    pub async fn get_due_unmutes(&self, now: DateTime<Utc>) -> Result<Vec<ScheduledUnmute>, SwingBuddyError> {
+        let unmutes = sqlx::query_as::<_, ScheduledUnmute>(
+            r#"
+            SELECT chat_telegram_id, user_telegram_id, unmute_at, created_at
+            FROM scheduled_unmutes
+            WHERE unmute_at <= $1
+            ORDER BY unmute_at
+            "#
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(unmutes)
+    }
+
+    /// Remove a member's scheduled unmute; returns `false` if none was scheduled
+    pub async fn delete_scheduled_unmute(&self, chat_telegram_id: i64, user_telegram_id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM scheduled_unmutes WHERE chat_telegram_id = $1 AND user_telegram_id = $2")
+            .bind(chat_telegram_id)
+            .bind(user_telegram_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Add a spam filter to a chat; returns `None` if the chat already has it
+    pub async fn create_spam_filter(&self, request: CreateSpamFilterRequest) -> Result<Option<SpamFilter>, SwingBuddyError> {
+        let filter = sqlx::query_as::<_, SpamFilter>(
+            r#"
+            INSERT INTO spam_filters (chat_telegram_id, kind, pattern, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (chat_telegram_id, kind, pattern) DO NOTHING
+            RETURNING id, chat_telegram_id, kind, pattern, created_by, created_at
+            "#
+        )
+        .bind(request.chat_telegram_id)
+        .bind(request.kind.as_str())
+        .bind(request.pattern)
+        .bind(request.created_by)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(filter)
+    }
+
+    /// Get the spam filters of a chat, oldest first
+    pub async fn find_spam_filters(&self, chat_telegram_id: i64) -> Result<Vec<SpamFilter>, SwingBuddyError> {
+        let filters = sqlx::query_as::<_, SpamFilter>(
+            r#"
+            SELECT id, chat_telegram_id, kind, pattern, created_by, created_at
+            FROM spam_filters
+            WHERE chat_telegram_id = $1
+            ORDER BY created_at, id
+            "#
+        )
+        .bind(chat_telegram_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(filters)
+    }
+
+    /// Delete a spam filter, returning it if it existed
+    pub async fn delete_spam_filter(&self, id: i64) -> Result<Option<SpamFilter>, SwingBuddyError> {
+        let filter = sqlx::query_as::<_, SpamFilter>(
+            r#"
+            DELETE FROM spam_filters
+            WHERE id = $1
+            RETURNING id, chat_telegram_id, kind, pattern, created_by, created_at
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(filter)
+    }
+}
@@ -0,0 +1,137 @@
+//! User note repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::note::{UserNote, UserNoteRevision, CreateUserNoteRequest, UpdateUserNoteRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct NoteRepository {
+    pool: PgPool,
+}
+
+impl NoteRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a new note
+    pub async fn create(&self, request: CreateUserNoteRequest) -> Result<UserNote, SwingBuddyError> {
+        let note = sqlx::query_as::<_, UserNote>(
+            r#"
+            INSERT INTO user_notes (user_id, author_id, content, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, author_id, content, created_at, updated_at
+            "#
+        )
+        .bind(request.user_id)
+        .bind(request.author_id)
+        .bind(request.content)
+        .bind(Utc::now())
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Find note by ID
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<UserNote>, SwingBuddyError> {
+        let note = sqlx::query_as::<_, UserNote>(
+            "SELECT id, user_id, author_id, content, created_at, updated_at FROM user_notes WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Get all notes attached to a user
+    pub async fn get_user_notes(&self, user_id: i64) -> Result<Vec<UserNote>, SwingBuddyError> {
+        let notes = sqlx::query_as::<_, UserNote>(
+            "SELECT id, user_id, author_id, content, created_at, updated_at FROM user_notes WHERE user_id = $1 ORDER BY created_at ASC"
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notes)
+    }
+
+    /// Get notes for all participants of an event
+    pub async fn get_event_participant_notes(&self, event_id: i64) -> Result<Vec<UserNote>, SwingBuddyError> {
+        let notes = sqlx::query_as::<_, UserNote>(
+            r#"
+            SELECT n.id, n.user_id, n.author_id, n.content, n.created_at, n.updated_at
+            FROM user_notes n
+            INNER JOIN event_participants ep ON n.user_id = ep.user_id
+            WHERE ep.event_id = $1
+            ORDER BY n.created_at ASC
+            "#
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notes)
+    }
+
+    /// Update note content, keeping the previous content as a revision
+    pub async fn update(&self, id: i64, request: UpdateUserNoteRequest) -> Result<UserNote, SwingBuddyError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_note_revisions (note_id, content, edited_by, edited_at)
+            SELECT id, content, $2, $3 FROM user_notes WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(request.edited_by)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        let note = sqlx::query_as::<_, UserNote>(
+            r#"
+            UPDATE user_notes
+            SET content = $2, updated_at = $3
+            WHERE id = $1
+            RETURNING id, user_id, author_id, content, created_at, updated_at
+            "#
+        )
+        .bind(id)
+        .bind(request.content)
+        .bind(Utc::now())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(note)
+    }
+
+    /// Get previous versions of a note, newest first
+    pub async fn get_revisions(&self, note_id: i64) -> Result<Vec<UserNoteRevision>, SwingBuddyError> {
+        let revisions = sqlx::query_as::<_, UserNoteRevision>(
+            "SELECT id, note_id, content, edited_by, edited_at FROM user_note_revisions WHERE note_id = $1 ORDER BY edited_at DESC"
+        )
+        .bind(note_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(revisions)
+    }
+
+    /// Delete note
+    pub async fn delete(&self, id: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query("DELETE FROM user_notes WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+//! Notification queue repository implementation
+
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use crate::models::broadcast::DeliveryStatus;
+use crate::models::notification::{QueuedNotification, CreateQueuedNotificationRequest, NotificationQueueStats};
+use crate::utils::errors::SwingBuddyError;
+
+const NOTIFICATION_COLUMNS: &str = "id, chat_id, thread_id, text, parse_mode, disable_link_preview, status, attempts, error, next_attempt_at, created_at";
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct NotificationQueueRepository {
+    pool: PgPool,
+}
+
+impl NotificationQueueRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queue notifications to be sent as soon as possible. Users the bot can't
+    /// message any more are skipped. Returns the number queued.
+    pub async fn enqueue(&self, requests: &[CreateQueuedNotificationRequest]) -> Result<u64, SwingBuddyError> {
+        let mut tx = self.pool.begin().await?;
+        let now = Utc::now();
+        let mut queued = 0;
+
+        for request in requests {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO notification_queue (chat_id, thread_id, text, parse_mode, disable_link_preview, next_attempt_at, created_at, updated_at)
+                SELECT $1, $2, $3, $4, $5, $6, $6, $6
+                WHERE NOT EXISTS (SELECT 1 FROM users WHERE telegram_id = $1 AND blocked_at IS NOT NULL)
+                "#
+            )
+            .bind(request.chat_id)
+            .bind(request.thread_id)
+            .bind(&request.text)
+            .bind(&request.parse_mode)
+            .bind(request.disable_link_preview)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+            queued += result.rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(queued)
+    }
+
+    /// Get pending notifications that are due, oldest first
+    pub async fn get_due(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<QueuedNotification>, SwingBuddyError> {
+        let notifications = sqlx::query_as::<_, QueuedNotification>(&format!(
+            r#"
+            SELECT {}
+            FROM notification_queue
+            WHERE status = 'pending' AND next_attempt_at <= $1
+            ORDER BY next_attempt_at, id
+            LIMIT $2
+            "#,
+            NOTIFICATION_COLUMNS
+        ))
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    /// Record that a notification was sent or failed for good
+    pub async fn finish(&self, id: i64, status: DeliveryStatus, error: Option<&str>) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            UPDATE notification_queue
+            SET status = $2, error = $3, attempts = attempts + CASE WHEN $2 = 'failed' THEN 1 ELSE 0 END, updated_at = $4
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(error)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a failed attempt and try again at `next_attempt_at`
+    pub async fn reschedule(&self, id: i64, error: &str, next_attempt_at: DateTime<Utc>) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            UPDATE notification_queue
+            SET attempts = attempts + 1, error = $2, next_attempt_at = $3, updated_at = $4
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count the queued notifications by status
+    pub async fn get_stats(&self) -> Result<NotificationQueueStats, SwingBuddyError> {
+        let stats = sqlx::query_as::<_, NotificationQueueStats>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE status = 'pending') AS pending,
+                COUNT(*) FILTER (WHERE status = 'sent') AS sent,
+                COUNT(*) FILTER (WHERE status = 'failed') AS failed,
+                (SELECT COUNT(*) FROM users WHERE blocked_at IS NOT NULL) AS blocked_users
+            FROM notification_queue
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Fail the pending notifications to a chat the bot can't reach any more. Returns the number failed.
+    pub async fn fail_pending_for_chat(&self, chat_id: i64, error: &str) -> Result<u64, SwingBuddyError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE notification_queue SET status = 'failed', error = $2, updated_at = $3
+            WHERE chat_id = $1 AND status = 'pending'
+            "#
+        )
+        .bind(chat_id)
+        .bind(error)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete sent and failed notifications finished before `before`. Returns the number deleted.
+    pub async fn delete_finished(&self, before: DateTime<Utc>) -> Result<u64, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM notification_queue WHERE status <> 'pending' AND updated_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,65 @@
+//! Notification template repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::notification::NotificationTemplateOverride;
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct NotificationTemplateRepository {
+    pool: PgPool,
+}
+
+impl NotificationTemplateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get all edited templates
+    pub async fn list(&self) -> Result<Vec<NotificationTemplateOverride>, SwingBuddyError> {
+        let overrides = sqlx::query_as::<_, NotificationTemplateOverride>(
+            r#"
+            SELECT template_key, language_code, content, updated_by, updated_at
+            FROM notification_templates
+            ORDER BY template_key, language_code
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(overrides)
+    }
+
+    /// Store the text of a template in one language
+    pub async fn upsert(&self, template_key: &str, language_code: &str, content: &str, updated_by: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            INSERT INTO notification_templates (template_key, language_code, content, updated_by, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (template_key, language_code)
+            DO UPDATE SET content = EXCLUDED.content, updated_by = EXCLUDED.updated_by, updated_at = EXCLUDED.updated_at
+            "#
+        )
+        .bind(template_key)
+        .bind(language_code)
+        .bind(content)
+        .bind(updated_by)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove the edited text of a template in one language. Returns whether there was one.
+    pub async fn delete(&self, template_key: &str, language_code: &str) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM notification_templates WHERE template_key = $1 AND language_code = $2")
+            .bind(template_key)
+            .bind(language_code)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
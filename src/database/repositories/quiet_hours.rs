@@ -0,0 +1,97 @@
+//! Quiet hours repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::quiet_hours::{QueuedPost, CreateQueuedPostRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct QuietHoursRepository {
+    pool: PgPool,
+}
+
+impl QuietHoursRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queue a post until the group's quiet hours end
+    pub async fn enqueue(&self, request: CreateQueuedPostRequest) -> Result<QueuedPost, SwingBuddyError> {
+        let post = sqlx::query_as::<_, QueuedPost>(
+            r#"
+            INSERT INTO queued_posts (group_id, thread_id, text, created_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, group_id, thread_id, text, created_at
+            "#
+        )
+        .bind(request.group_id)
+        .bind(request.thread_id)
+        .bind(&request.text)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(post)
+    }
+
+    /// Get the IDs of groups that have queued posts
+    pub async fn get_queued_group_ids(&self) -> Result<Vec<i64>, SwingBuddyError> {
+        let group_ids = sqlx::query_scalar::<_, i64>(
+            "SELECT DISTINCT group_id FROM queued_posts"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(group_ids)
+    }
+
+    /// Get the queued posts of a group, oldest first
+    pub async fn get_for_group(&self, group_id: i64) -> Result<Vec<QueuedPost>, SwingBuddyError> {
+        let posts = sqlx::query_as::<_, QueuedPost>(
+            r#"
+            SELECT id, group_id, thread_id, text, created_at
+            FROM queued_posts
+            WHERE group_id = $1
+            ORDER BY created_at, id
+            "#
+        )
+        .bind(group_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
+    /// Count the queued posts of a group
+    pub async fn count_for_group(&self, group_id: i64) -> Result<i64, SwingBuddyError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM queued_posts WHERE group_id = $1"
+        )
+        .bind(group_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Delete all queued posts of a group. Returns the number of posts deleted.
+    pub async fn delete_for_group(&self, group_id: i64) -> Result<u64, SwingBuddyError> {
+        let result = sqlx::query("DELETE FROM queued_posts WHERE group_id = $1")
+            .bind(group_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete a queued post
+    pub async fn delete(&self, id: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query("DELETE FROM queued_posts WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
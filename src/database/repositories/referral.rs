@@ -0,0 +1,80 @@
+//! Referral repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::referral::{Referral, TopReferrer, CreateReferralRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ReferralRepository {
+    pool: PgPool,
+}
+
+impl ReferralRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a referral; returns `None` if the referred user was already attributed
+    pub async fn create(&self, request: CreateReferralRequest) -> Result<Option<Referral>, SwingBuddyError> {
+        let referral = sqlx::query_as::<_, Referral>(
+            r#"
+            INSERT INTO referrals (referrer_id, referred_id, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (referred_id) DO NOTHING
+            RETURNING id, referrer_id, referred_id, created_at
+            "#
+        )
+        .bind(request.referrer_id)
+        .bind(request.referred_id)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(referral)
+    }
+
+    /// Find the referral that brought in a user
+    pub async fn find_by_referred(&self, referred_id: i64) -> Result<Option<Referral>, SwingBuddyError> {
+        let referral = sqlx::query_as::<_, Referral>(
+            "SELECT id, referrer_id, referred_id, created_at FROM referrals WHERE referred_id = $1"
+        )
+        .bind(referred_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(referral)
+    }
+
+    /// Count users invited by a referrer
+    pub async fn count_by_referrer(&self, referrer_id: i64) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM referrals WHERE referrer_id = $1"
+        )
+        .bind(referrer_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    /// Get users with the most referrals
+    pub async fn get_top_referrers(&self, limit: i64) -> Result<Vec<TopReferrer>, SwingBuddyError> {
+        let referrers = sqlx::query_as::<_, TopReferrer>(
+            r#"
+            SELECT u.id AS user_id, u.telegram_id, u.username, u.first_name, COUNT(r.id) AS referral_count
+            FROM referrals r
+            INNER JOIN users u ON u.id = r.referrer_id
+            GROUP BY u.id, u.telegram_id, u.username, u.first_name
+            ORDER BY referral_count DESC, u.id ASC
+            LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(referrers)
+    }
+}
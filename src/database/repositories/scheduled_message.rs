@@ -0,0 +1,103 @@
+//! Scheduled message repository implementation
+
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use crate::models::scheduled_message::{ScheduledMessage, CreateScheduledMessageRequest, ScheduledMessageStatus};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ScheduledMessageRepository {
+    pool: PgPool,
+}
+
+impl ScheduledMessageRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Store a new scheduled message
+    pub async fn create(&self, request: CreateScheduledMessageRequest) -> Result<ScheduledMessage, SwingBuddyError> {
+        let message = sqlx::query_as::<_, ScheduledMessage>(
+            r#"
+            INSERT INTO scheduled_messages (created_by, language_code, target, source_chat_id, source_message_id, text, send_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, created_by, language_code, target, source_chat_id, source_message_id, text, send_at, status, error, created_at, sent_at
+            "#
+        )
+        .bind(request.created_by)
+        .bind(request.language_code)
+        .bind(request.target.to_string())
+        .bind(request.source_chat_id)
+        .bind(request.source_message_id)
+        .bind(request.text)
+        .bind(request.send_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(message)
+    }
+
+    /// Get pending messages that are due, oldest first
+    pub async fn get_due(&self, now: DateTime<Utc>) -> Result<Vec<ScheduledMessage>, SwingBuddyError> {
+        let messages = sqlx::query_as::<_, ScheduledMessage>(
+            r#"
+            SELECT id, created_by, language_code, target, source_chat_id, source_message_id, text, send_at, status, error, created_at, sent_at
+            FROM scheduled_messages
+            WHERE status = 'pending' AND send_at <= $1
+            ORDER BY send_at, id
+            "#
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    /// Get all pending messages, the next one first
+    pub async fn get_pending(&self) -> Result<Vec<ScheduledMessage>, SwingBuddyError> {
+        let messages = sqlx::query_as::<_, ScheduledMessage>(
+            r#"
+            SELECT id, created_by, language_code, target, source_chat_id, source_message_id, text, send_at, status, error, created_at, sent_at
+            FROM scheduled_messages
+            WHERE status = 'pending'
+            ORDER BY send_at, id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    /// Cancel a pending message; returns false if it was already sent or cancelled
+    pub async fn cancel(&self, id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query(
+            "UPDATE scheduled_messages SET status = 'cancelled' WHERE id = $1 AND status = 'pending'"
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record how sending a due message went
+    pub async fn set_status(&self, id: i64, status: ScheduledMessageStatus, error: Option<&str>) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            UPDATE scheduled_messages SET status = $2, error = $3, sent_at = $4
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(error)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+//! Song request repository implementation
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::song_request::{SongRequest, CreateSongRequestRequest, SongRequestStatus};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct SongRequestRepository {
+    pool: PgPool,
+}
+
+impl SongRequestRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Add a track to an event's queue
+    pub async fn create(&self, request: CreateSongRequestRequest) -> Result<SongRequest, SwingBuddyError> {
+        let song = sqlx::query_as::<_, SongRequest>(
+            r#"
+            INSERT INTO song_requests (event_id, requested_by, track)
+            VALUES ($1, $2, $3)
+            RETURNING id, event_id, requested_by, track, status, handled_by, handled_at, created_at
+            "#
+        )
+        .bind(request.event_id)
+        .bind(request.requested_by)
+        .bind(request.track)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(song)
+    }
+
+    /// Find a song request by ID
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<SongRequest>, SwingBuddyError> {
+        let song = sqlx::query_as::<_, SongRequest>(
+            r#"
+            SELECT id, event_id, requested_by, track, status, handled_by, handled_at, created_at
+            FROM song_requests
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(song)
+    }
+
+    /// Find a track that is already waiting in an event's queue, ignoring case
+    pub async fn find_pending_track(&self, event_id: i64, track: &str) -> Result<Option<SongRequest>, SwingBuddyError> {
+        let song = sqlx::query_as::<_, SongRequest>(
+            r#"
+            SELECT id, event_id, requested_by, track, status, handled_by, handled_at, created_at
+            FROM song_requests
+            WHERE event_id = $1 AND status = 'pending' AND LOWER(track) = LOWER($2)
+            ORDER BY created_at
+            LIMIT 1
+            "#
+        )
+        .bind(event_id)
+        .bind(track)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(song)
+    }
+
+    /// Count the tracks an attendee has waiting in an event's queue
+    pub async fn count_pending_by_user(&self, event_id: i64, requested_by: i64) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM song_requests WHERE event_id = $1 AND requested_by = $2 AND status = 'pending'"
+        )
+        .bind(event_id)
+        .bind(requested_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    /// Get the requests of an event with a status; waiting tracks in the order
+    /// they were asked for, handled ones in the order they were handled
+    pub async fn get_by_status(&self, event_id: i64, status: SongRequestStatus) -> Result<Vec<SongRequest>, SwingBuddyError> {
+        let songs = sqlx::query_as::<_, SongRequest>(
+            r#"
+            SELECT id, event_id, requested_by, track, status, handled_by, handled_at, created_at
+            FROM song_requests
+            WHERE event_id = $1 AND status = $2
+            ORDER BY COALESCE(handled_at, created_at), id
+            "#
+        )
+        .bind(event_id)
+        .bind(status.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(songs)
+    }
+
+    /// Mark a waiting track as played or skipped; returns `None` if it was
+    /// not waiting anymore
+    pub async fn handle(&self, id: i64, status: SongRequestStatus, handled_by: i64) -> Result<Option<SongRequest>, SwingBuddyError> {
+        let song = sqlx::query_as::<_, SongRequest>(
+            r#"
+            UPDATE song_requests
+            SET status = $2, handled_by = $3, handled_at = $4
+            WHERE id = $1 AND status = 'pending'
+            RETURNING id, event_id, requested_by, track, status, handled_by, handled_at, created_at
+            "#
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(handled_by)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(song)
+    }
+}
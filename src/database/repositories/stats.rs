@@ -0,0 +1,136 @@
+//! Group statistics repository implementation
+
+use sqlx::PgPool;
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::models::stats::{GroupCounter, GroupStats, GrowthInterval, GrowthPoint};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct StatsRepository {
+    pool: PgPool,
+}
+
+impl StatsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Add to a group's counter for a day; groups the bot does not know are ignored
+    pub async fn increment(&self, group_telegram_id: i64, counter: GroupCounter, day: NaiveDate, amount: i64) -> Result<(), SwingBuddyError> {
+        sqlx::query(
+            r#"
+            INSERT INTO group_activity_counters (group_id, day, counter, value)
+            SELECT id, $2, $3, $4 FROM groups WHERE telegram_id = $1
+            ON CONFLICT (group_id, day, counter)
+            DO UPDATE SET value = group_activity_counters.value + EXCLUDED.value
+            "#
+        )
+        .bind(group_telegram_id)
+        .bind(day)
+        .bind(counter.as_str())
+        .bind(amount)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get a group's activity since the given time. Counters are kept per day,
+    /// so `since` should be a midnight.
+    pub async fn get_group_stats(&self, group_id: i64, since: DateTime<Utc>) -> Result<GroupStats, SwingBuddyError> {
+        let stats = sqlx::query_as::<_, GroupStats>(
+            r#"
+            SELECT
+                COALESCE(SUM(c.value) FILTER (WHERE c.counter = 'new_members'), 0)::BIGINT AS new_members,
+                COALESCE(SUM(c.value) FILTER (WHERE c.counter = 'messages'), 0)::BIGINT AS messages,
+                COALESCE(SUM(c.value) FILTER (WHERE c.counter = 'cas_bans'), 0)::BIGINT AS cas_bans,
+                (
+                    SELECT COUNT(*)
+                    FROM event_participants p
+                    JOIN events e ON e.id = p.event_id
+                    WHERE e.group_id = $1 AND p.registered_at >= $2
+                ) + (
+                    SELECT COUNT(*)
+                    FROM event_participants_archive p
+                    JOIN events_archive e ON e.id = p.event_id
+                    WHERE e.group_id = $1 AND p.registered_at >= $2
+                ) AS event_registrations
+            FROM group_activity_counters c
+            WHERE c.group_id = $1 AND c.day >= $2::DATE
+            "#
+        )
+        .bind(group_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Get bot-wide growth per day or week from the period starting on `since` up to
+    /// the current one; periods without any activity are included with zeros
+    pub async fn get_growth(&self, interval: GrowthInterval, since: NaiveDate) -> Result<Vec<GrowthPoint>, SwingBuddyError> {
+        let points = sqlx::query_as::<_, GrowthPoint>(
+            r#"
+            WITH periods AS (
+                SELECT generate_series(
+                    date_trunc($1, $2::DATE::TIMESTAMP),
+                    date_trunc($1, (NOW() AT TIME ZONE 'UTC')),
+                    ('1 ' || $1)::INTERVAL
+                )::DATE AS period
+            ),
+            new_users AS (
+                SELECT date_trunc($1, created_at AT TIME ZONE 'UTC')::DATE AS period, COUNT(*) AS value
+                FROM users
+                WHERE created_at >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+                GROUP BY 1
+            ),
+            events_created AS (
+                SELECT date_trunc($1, created_at AT TIME ZONE 'UTC')::DATE AS period, COUNT(*) AS value
+                FROM (
+                    SELECT created_at FROM events
+                    UNION ALL
+                    SELECT created_at FROM events_archive
+                ) events
+                WHERE created_at >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+                GROUP BY 1
+            ),
+            registrations AS (
+                SELECT date_trunc($1, registered_at AT TIME ZONE 'UTC')::DATE AS period, COUNT(*) AS value
+                FROM (
+                    SELECT registered_at FROM event_participants
+                    UNION ALL
+                    SELECT registered_at FROM event_participants_archive
+                ) event_participants
+                WHERE registered_at >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+                GROUP BY 1
+            ),
+            cas_bans AS (
+                SELECT date_trunc($1, day::TIMESTAMP)::DATE AS period, SUM(value)::BIGINT AS value
+                FROM group_activity_counters
+                WHERE counter = 'cas_bans' AND day >= $2::DATE
+                GROUP BY 1
+            )
+            SELECT
+                p.period,
+                COALESCE(u.value, 0) AS new_users,
+                COALESCE(e.value, 0) AS events_created,
+                COALESCE(r.value, 0) AS registrations,
+                COALESCE(c.value, 0) AS cas_bans
+            FROM periods p
+            LEFT JOIN new_users u ON u.period = p.period
+            LEFT JOIN events_created e ON e.period = p.period
+            LEFT JOIN registrations r ON r.period = p.period
+            LEFT JOIN cas_bans c ON c.period = p.period
+            ORDER BY p.period
+            "#
+        )
+        .bind(interval.as_str())
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(points)
+    }
+}
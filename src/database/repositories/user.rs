@@ -1,9 +1,11 @@
 //! User repository implementation
 
 use sqlx::PgPool;
-use chrono::Utc;
-use crate::models::user::{User, CreateUserRequest, UpdateUserRequest};
+use chrono::{DateTime, Utc};
+use crate::models::trash::TrashEntry;
+use crate::models::user::{User, CreateUserRequest, UpdateUserRequest, NotificationPreferences, UserListEntry, UserListSort};
 use crate::utils::errors::SwingBuddyError;
+use crate::utils::metrics::time_query;
 
 #[derive(Clone)]
 #[derive(Debug)]
@@ -29,12 +31,20 @@ impl UserRepository {
         Self { pool }
     }
 
-    /// Create a new user
+    /// Create a new user. A deleted user with the same Telegram ID is restored
+    /// with the new details instead.
     pub async fn create(&self, request: CreateUserRequest) -> Result<User, SwingBuddyError> {
         let user = sqlx::query_as::<_, User>(
             r#"
             INSERT INTO users (telegram_id, username, first_name, last_name, language_code, location, created_at, updated_at)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (telegram_id) DO UPDATE SET
+                username = EXCLUDED.username,
+                first_name = EXCLUDED.first_name,
+                last_name = EXCLUDED.last_name,
+                updated_at = EXCLUDED.updated_at,
+                deleted_at = NULL
+            WHERE users.deleted_at IS NOT NULL
             RETURNING id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at
             "#
         )
@@ -52,10 +62,40 @@ impl UserRepository {
         Ok(user)
     }
 
+    /// Create a user or fill in the given details of an existing one; details missing
+    /// from the request are kept. Returns whether the user was created.
+    pub async fn upsert(&self, request: CreateUserRequest) -> Result<bool, SwingBuddyError> {
+        let created: bool = time_query("users.upsert", sqlx::query_scalar(
+            r#"
+            INSERT INTO users (telegram_id, username, first_name, last_name, language_code, location, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, COALESCE($5, 'en'), $6, $7, $7)
+            ON CONFLICT (telegram_id) DO UPDATE SET
+                username = COALESCE($2, users.username),
+                first_name = COALESCE($3, users.first_name),
+                last_name = COALESCE($4, users.last_name),
+                language_code = COALESCE($5, users.language_code),
+                location = COALESCE($6, users.location),
+                updated_at = $7
+            RETURNING (xmax = 0)
+            "#
+        )
+        .bind(request.telegram_id)
+        .bind(request.username)
+        .bind(request.first_name)
+        .bind(request.last_name)
+        .bind(request.language_code)
+        .bind(request.location)
+        .bind(Utc::now())
+        .fetch_one(&self.pool))
+        .await?;
+
+        Ok(created)
+    }
+
     /// Find user by ID
     pub async fn find_by_id(&self, id: i64) -> Result<Option<User>, SwingBuddyError> {
         let user = sqlx::query_as::<_, User>(
-            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE id = $1"
+            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE id = $1 AND deleted_at IS NULL"
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -66,11 +106,11 @@ impl UserRepository {
 
     /// Find user by Telegram ID
     pub async fn find_by_telegram_id(&self, telegram_id: i64) -> Result<Option<User>, SwingBuddyError> {
-        let user = sqlx::query_as::<_, User>(
-            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE telegram_id = $1"
+        let user = time_query("users.find_by_telegram_id", sqlx::query_as::<_, User>(
+            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE telegram_id = $1 AND deleted_at IS NULL"
         )
         .bind(telegram_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&self.pool))
         .await?;
 
         Ok(user)
@@ -106,20 +146,63 @@ impl UserRepository {
         Ok(user)
     }
 
-    /// Delete user
-    pub async fn delete(&self, id: i64) -> Result<(), SwingBuddyError> {
-        sqlx::query("DELETE FROM users WHERE id = $1")
+    /// Move a user to the trash. Returns whether they were there to delete.
+    pub async fn delete(&self, id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("UPDATE users SET deleted_at = $2 WHERE id = $1 AND deleted_at IS NULL")
             .bind(id)
+            .bind(Utc::now())
             .execute(&self.pool)
             .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take a user out of the trash
+    pub async fn restore(&self, id: i64) -> Result<Option<User>, SwingBuddyError> {
+        let user = sqlx::query_as::<_, User>(
+            "UPDATE users SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL RETURNING id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// List deleted users, most recently deleted first
+    pub async fn list_deleted(&self, limit: i64, offset: i64) -> Result<Vec<TrashEntry>, SwingBuddyError> {
+        let users = sqlx::query_as::<_, TrashEntry>(
+            r#"
+            SELECT id,
+                   COALESCE(NULLIF(CONCAT_WS(' ', first_name, last_name), ''), '@' || username, telegram_id::TEXT) AS label,
+                   deleted_at
+            FROM users
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, id DESC
+            LIMIT $1 OFFSET $2
+            "#
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// Count deleted users
+    pub async fn count_deleted(&self) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE deleted_at IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count.0)
     }
 
     /// List all users with pagination
     pub async fn list(&self, limit: i64, offset: i64) -> Result<Vec<User>, SwingBuddyError> {
         let users = sqlx::query_as::<_, User>(
-            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE deleted_at IS NULL ORDER BY created_at DESC LIMIT $1 OFFSET $2"
         )
         .bind(limit)
         .bind(offset)
@@ -129,19 +212,135 @@ impl UserRepository {
         Ok(users)
     }
 
+    /// List all users in registration order, for exports
+    pub async fn list_all(&self) -> Result<Vec<User>, SwingBuddyError> {
+        let users = sqlx::query_as::<_, User>(
+            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE deleted_at IS NULL ORDER BY created_at, id"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// List users for the admin user list, newest registrations or most recent activity first
+    pub async fn list_entries(&self, sort: UserListSort, limit: i64, offset: i64) -> Result<Vec<UserListEntry>, SwingBuddyError> {
+        let query = match sort {
+            UserListSort::Registered => r#"
+                SELECT telegram_id, username, first_name, last_name, is_banned, created_at, last_active_at
+                FROM users
+                WHERE deleted_at IS NULL
+                ORDER BY created_at DESC, id DESC
+                LIMIT $1 OFFSET $2
+            "#,
+            UserListSort::LastActive => r#"
+                SELECT telegram_id, username, first_name, last_name, is_banned, created_at, last_active_at
+                FROM users
+                WHERE deleted_at IS NULL
+                ORDER BY last_active_at DESC NULLS LAST, id DESC
+                LIMIT $1 OFFSET $2
+            "#,
+        };
+
+        let users = sqlx::query_as::<_, UserListEntry>(query)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(users)
+    }
+
+    /// Record that a user was active, unless that was already done after `stale_before`.
+    /// A user who was unreachable can be messaged again.
+    pub async fn touch_last_active(&self, telegram_id: i64, now: DateTime<Utc>, stale_before: DateTime<Utc>) -> Result<(), SwingBuddyError> {
+        time_query("users.touch_last_active", sqlx::query(
+            r#"
+            UPDATE users SET last_active_at = $2, blocked_at = NULL
+            WHERE telegram_id = $1 AND (last_active_at IS NULL OR last_active_at < $3 OR blocked_at IS NOT NULL)
+            "#
+        )
+        .bind(telegram_id)
+        .bind(now)
+        .bind(stale_before)
+        .execute(&self.pool))
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record that the bot can't message a user any more. Returns whether they were reachable until now.
+    pub async fn mark_blocked(&self, telegram_id: i64) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query("UPDATE users SET blocked_at = $2 WHERE telegram_id = $1 AND blocked_at IS NULL")
+            .bind(telegram_id)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get which optional notifications a user gets
+    pub async fn get_notification_preferences(&self, telegram_id: i64) -> Result<Option<NotificationPreferences>, SwingBuddyError> {
+        let preferences = sqlx::query_as::<_, NotificationPreferences>(
+            "SELECT broadcasts_enabled, reminders_enabled FROM users WHERE telegram_id = $1 AND deleted_at IS NULL"
+        )
+        .bind(telegram_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(preferences)
+    }
+
+    /// Set which optional notifications a user gets. Returns whether the user was found.
+    pub async fn set_notification_preferences(&self, telegram_id: i64, preferences: NotificationPreferences) -> Result<bool, SwingBuddyError> {
+        let result = sqlx::query(
+            "UPDATE users SET broadcasts_enabled = $2, reminders_enabled = $3, updated_at = $4 WHERE telegram_id = $1 AND deleted_at IS NULL"
+        )
+        .bind(telegram_id)
+        .bind(preferences.broadcasts_enabled)
+        .bind(preferences.reminders_enabled)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count users the bot can't message any more
+    pub async fn count_blocked(&self) -> Result<i64, SwingBuddyError> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE blocked_at IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count.0)
+    }
+
     /// Count total users
     pub async fn count(&self) -> Result<i64, SwingBuddyError> {
-        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users")
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE deleted_at IS NULL")
             .fetch_one(&self.pool)
             .await?;
 
         Ok(count.0)
     }
 
+    /// Find user by exact username, ignoring case
+    pub async fn find_by_username(&self, username: &str) -> Result<Option<User>, SwingBuddyError> {
+        let user = sqlx::query_as::<_, User>(
+            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE LOWER(username) = LOWER($1) AND deleted_at IS NULL"
+        )
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
     /// Find users by username pattern
     pub async fn find_by_username_pattern(&self, pattern: &str) -> Result<Vec<User>, SwingBuddyError> {
         let users = sqlx::query_as::<_, User>(
-            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE username ILIKE $1"
+            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE username ILIKE $1 AND deleted_at IS NULL"
         )
         .bind(format!("%{}%", pattern))
         .fetch_all(&self.pool)
@@ -172,8 +371,26 @@ impl UserRepository {
     /// Get banned users
     pub async fn get_banned_users(&self) -> Result<Vec<User>, SwingBuddyError> {
         let users = sqlx::query_as::<_, User>(
-            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE is_banned = true ORDER BY updated_at DESC"
+            "SELECT id, telegram_id, username, first_name, last_name, language_code, location, is_banned, created_at, updated_at FROM users WHERE is_banned = true AND deleted_at IS NULL ORDER BY updated_at DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// Get users registered for an event
+    pub async fn get_event_participants(&self, event_id: i64) -> Result<Vec<User>, SwingBuddyError> {
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT u.id, u.telegram_id, u.username, u.first_name, u.last_name, u.language_code, u.location, u.is_banned, u.created_at, u.updated_at
+            FROM users u
+            INNER JOIN event_participants ep ON u.id = ep.user_id
+            WHERE ep.event_id = $1 AND u.deleted_at IS NULL
+            ORDER BY ep.registered_at ASC
+            "#
         )
+        .bind(event_id)
         .fetch_all(&self.pool)
         .await?;
 
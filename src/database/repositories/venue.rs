@@ -0,0 +1,69 @@
+//! Venue repository implementation
+
+use sqlx::PgPool;
+use crate::models::venue::{Venue, CreateVenueRequest};
+use crate::utils::errors::SwingBuddyError;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct VenueRepository {
+    pool: PgPool,
+}
+
+impl VenueRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Add a venue, or update the address and floor of a venue with the same
+    /// name in the city
+    pub async fn upsert(&self, request: CreateVenueRequest) -> Result<Venue, SwingBuddyError> {
+        let venue = sqlx::query_as::<_, Venue>(
+            r#"
+            INSERT INTO venues (city, name, address, floor_type, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (city, name)
+            DO UPDATE SET address = EXCLUDED.address, floor_type = EXCLUDED.floor_type
+            RETURNING id, city, name, address, floor_type, created_by, created_at
+            "#
+        )
+        .bind(request.city)
+        .bind(request.name)
+        .bind(request.address)
+        .bind(request.floor_type)
+        .bind(request.created_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(venue)
+    }
+
+    /// Get the venues of a city, or of all cities, sorted by city and name
+    pub async fn list(&self, city: Option<&str>) -> Result<Vec<Venue>, SwingBuddyError> {
+        let venues = sqlx::query_as::<_, Venue>(
+            r#"
+            SELECT id, city, name, address, floor_type, created_by, created_at
+            FROM venues
+            WHERE $1::text IS NULL OR LOWER(city) = LOWER($1)
+            ORDER BY LOWER(city), LOWER(name)
+            "#
+        )
+        .bind(city)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(venues)
+    }
+
+    /// Remove a venue
+    pub async fn delete(&self, id: i64) -> Result<Option<Venue>, SwingBuddyError> {
+        let venue = sqlx::query_as::<_, Venue>(
+            "DELETE FROM venues WHERE id = $1 RETURNING id, city, name, address, floor_type, created_by, created_at"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(venue)
+    }
+}
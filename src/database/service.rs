@@ -2,7 +2,7 @@
 //! 
 //! This module provides a high-level interface to database operations
 
-use crate::database::{DatabasePool, UserRepository, GroupRepository, EventRepository, AdminRepository};
+use crate::database::{DatabasePool, UserRepository, GroupRepository, EventRepository, AdminRepository, NoteRepository, ReferralRepository, BirthdayRepository, DanceStyleRepository, CaptchaRepository, ModerationRepository, CleanupRepository, QuietHoursRepository, StatsRepository, MemberActivityRepository, InviteLinkRepository, RoleRepository, BroadcastRepository, ScheduledMessageRepository, CalendarRepository, NotificationQueueRepository, NotificationTemplateRepository, GoogleAccountRepository, GoogleCalendarSyncRepository, EventSheetRepository, ActivityRepository, SongRequestRepository, VenueRepository};
 use crate::models::*;
 use crate::utils::errors::SwingBuddyError;
 
@@ -12,6 +12,31 @@ pub struct DatabaseService {
     pub groups: GroupRepository,
     pub events: EventRepository,
     pub admin: AdminRepository,
+    pub notes: NoteRepository,
+    pub referrals: ReferralRepository,
+    pub birthdays: BirthdayRepository,
+    pub dance_styles: DanceStyleRepository,
+    pub captchas: CaptchaRepository,
+    pub moderation: ModerationRepository,
+    pub cleanup: CleanupRepository,
+    pub quiet_hours: QuietHoursRepository,
+    pub stats: StatsRepository,
+    pub member_activity: MemberActivityRepository,
+    pub invite_links: InviteLinkRepository,
+    pub roles: RoleRepository,
+    pub broadcasts: BroadcastRepository,
+    pub scheduled_messages: ScheduledMessageRepository,
+    pub calendars: CalendarRepository,
+    pub notification_queue: NotificationQueueRepository,
+    pub notification_templates: NotificationTemplateRepository,
+    pub google_account: GoogleAccountRepository,
+    pub google_calendar_sync: GoogleCalendarSyncRepository,
+    pub event_sheets: EventSheetRepository,
+    pub activity: ActivityRepository,
+    pub song_requests: SongRequestRepository,
+    pub venues: VenueRepository,
+    /// Connection pool, for health checks
+    pub pool: DatabasePool,
 }
 
 impl DatabaseService {
@@ -20,7 +45,31 @@ impl DatabaseService {
             users: UserRepository::new(pool.clone()),
             groups: GroupRepository::new(pool.clone()),
             events: EventRepository::new(pool.clone()),
-            admin: AdminRepository::new(pool),
+            admin: AdminRepository::new(pool.clone()),
+            notes: NoteRepository::new(pool.clone()),
+            referrals: ReferralRepository::new(pool.clone()),
+            birthdays: BirthdayRepository::new(pool.clone()),
+            dance_styles: DanceStyleRepository::new(pool.clone()),
+            captchas: CaptchaRepository::new(pool.clone()),
+            moderation: ModerationRepository::new(pool.clone()),
+            cleanup: CleanupRepository::new(pool.clone()),
+            quiet_hours: QuietHoursRepository::new(pool.clone()),
+            stats: StatsRepository::new(pool.clone()),
+            member_activity: MemberActivityRepository::new(pool.clone()),
+            invite_links: InviteLinkRepository::new(pool.clone()),
+            roles: RoleRepository::new(pool.clone()),
+            broadcasts: BroadcastRepository::new(pool.clone()),
+            scheduled_messages: ScheduledMessageRepository::new(pool.clone()),
+            calendars: CalendarRepository::new(pool.clone()),
+            notification_queue: NotificationQueueRepository::new(pool.clone()),
+            notification_templates: NotificationTemplateRepository::new(pool.clone()),
+            google_account: GoogleAccountRepository::new(pool.clone()),
+            google_calendar_sync: GoogleCalendarSyncRepository::new(pool.clone()),
+            event_sheets: EventSheetRepository::new(pool.clone()),
+            activity: ActivityRepository::new(pool.clone()),
+            song_requests: SongRequestRepository::new(pool.clone()),
+            venues: VenueRepository::new(pool.clone()),
+            pool,
         }
     }
 
@@ -0,0 +1,121 @@
+//! Join captcha callback handlers
+//!
+//! Sends the captcha to new group members and handles their answers
+
+use std::collections::HashMap;
+use teloxide::{Bot, types::{ChatId, Me, User, UserId, InlineKeyboardMarkup, InlineKeyboardButton}, prelude::*};
+use tracing::{info, debug, warn};
+use crate::utils::errors::Result;
+use crate::services::{ServiceFactory, CaptchaOutcome};
+use crate::models::captcha::CaptchaMode;
+use crate::handlers::commands::moderation;
+use crate::handlers::callbacks::CallbackData;
+use crate::handlers::messages::greet_new_member;
+use crate::i18n::I18n;
+
+/// Restrict a new member and post their captcha, if the group uses one.
+/// Returns `false` if the group does not use the captcha.
+pub async fn send_captcha_challenge(
+    bot: &Bot,
+    chat_id: ChatId,
+    member: &User,
+    services: &ServiceFactory,
+    i18n: &I18n,
+) -> Result<bool> {
+    let Some(group) = services.captcha_service.captcha_group(chat_id).await? else {
+        return Ok(false);
+    };
+
+    let lang = group.language_code.as_str();
+    let challenge = services.captcha_service.begin_challenge(chat_id, member.id, group.captcha_mode()).await?;
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), member.first_name.clone());
+    params.insert("seconds".to_string(), challenge.timeout_seconds.to_string());
+
+    let (text, keyboard) = match (challenge.mode, challenge.question_key) {
+        (CaptchaMode::Question, Some(question_key)) => {
+            params.insert("question".to_string(), i18n.t(question_key, lang, None));
+            let buttons = challenge.options.iter().enumerate()
+                .map(|(index, option)| vec![InlineKeyboardButton::callback(
+                    option.to_string(),
+                    CallbackData::Captcha { user_id: member.id.0, answer: index },
+                )])
+                .collect::<Vec<_>>();
+            (i18n.t("captcha.question_prompt", lang, Some(&params)), InlineKeyboardMarkup::new(buttons))
+        }
+        _ => {
+            let button = InlineKeyboardButton::callback(
+                i18n.t("captcha.button", lang, None),
+                CallbackData::Captcha { user_id: member.id.0, answer: 0 },
+            );
+            (i18n.t("captcha.button_prompt", lang, Some(&params)), InlineKeyboardMarkup::new(vec![vec![button]]))
+        }
+    };
+
+    let message = bot.send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+    services.captcha_service.attach_message(challenge.pending_id, message.id).await?;
+
+    Ok(true)
+}
+
+/// Handle a captcha answer
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_captcha_callback(
+    bot: Bot,
+    me: &Me,
+    chat_id: ChatId,
+    user: &User,
+    target_user_id: u64,
+    answer: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    // Only the new member may answer their own captcha
+    if user.id != UserId(target_user_id) {
+        debug!(user_id = user.id.0, target_user_id = target_user_id, "Ignoring captcha answer from another user");
+        return Ok(());
+    }
+
+    let (message_id, passed) = match services.captcha_service.verify(chat_id, user.id, &answer).await? {
+        CaptchaOutcome::NotFound => return Ok(()),
+        CaptchaOutcome::Passed { message_id } => (message_id, true),
+        CaptchaOutcome::Failed { message_id } => (message_id, false),
+    };
+
+    if let Some(message_id) = message_id {
+        if let Err(e) = bot.delete_message(chat_id, message_id).await {
+            warn!(error = %e, "Failed to delete captcha message");
+        }
+    }
+
+    let welcomed = if passed {
+        match greet_new_member(&bot, me, chat_id, user, &services, &i18n).await {
+            Ok(sent) => sent,
+            Err(e) => {
+                warn!(error = %e, "Failed to greet new member");
+                false
+            }
+        }
+    } else {
+        false
+    };
+
+    // Groups without a welcome message get the generic confirmation
+    if passed && !welcomed {
+        let lang = match services.captcha_service.captcha_group(chat_id).await? {
+            Some(group) => group.language_code,
+            None => "en".to_string(),
+        };
+        let mut params = HashMap::new();
+        params.insert("name".to_string(), user.first_name.clone());
+        let reply = bot.send_message(chat_id, i18n.t("captcha.passed", &lang, Some(&params))).await?;
+        moderation::schedule_cleanup(&services, chat_id, reply.id).await;
+    }
+
+    info!(user_id = user.id.0, chat_id = ?chat_id, passed = passed, "Captcha answered");
+
+    Ok(())
+}
@@ -0,0 +1,805 @@
+//! Callback data of inline keyboard buttons
+//!
+//! Every button carries a [`CallbackData`] encoded as a format version, a
+//! one-letter tag and the fields of the action, e.g. `1e:42` to register for
+//! event 42. Free-text fields come last, so they may contain `:`. Telegram
+//! keeps at most 64 bytes per button.
+//!
+//! Buttons sent before the versioned format, like `event_register:42`, are
+//! still in chats and keep working: they are decoded by their old names.
+
+use std::str::FromStr;
+use teloxide::types::InlineKeyboardButton;
+use tracing::warn;
+use crate::models::activity::ActivityCategory;
+use crate::models::broadcast::BroadcastSegment;
+use crate::models::calendar::CalendarField;
+use crate::models::dance_style::DanceStyle;
+use crate::models::group::GroupFeature;
+use crate::models::moderation::SpamFilterKind;
+use crate::models::stats::GrowthInterval;
+use crate::models::trash::TrashKind;
+use crate::models::user::{ProfileField, UserListSort};
+use crate::services::feature_flags::FeatureFlag;
+use crate::state::WizardAction;
+
+/// Version of the encoding, the first character of all callback data
+pub const CALLBACK_DATA_VERSION: char = '1';
+
+/// Longest callback data Telegram accepts, in bytes
+pub const MAX_CALLBACK_DATA_LEN: usize = 64;
+
+/// What pressing an inline keyboard button does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallbackData {
+    /// Language picked during onboarding
+    Language(String),
+    /// City picked during onboarding; `None` skips the question
+    Location(Option<String>),
+    Scenario(ScenarioAction),
+    /// Events of a calendar; `None` goes back to the calendar list
+    Calendar(Option<i64>),
+    EventRegister(i64),
+    EventUnregister(i64),
+    /// Answer to a join captcha, by the index of the option
+    Captcha { user_id: u64, answer: usize },
+    ResolveReport(i64),
+    /// Toggle of a favorite dance style
+    Style(DanceStyle),
+    Song(SongAction),
+    /// Admin panel route, e.g. `user_list:active:0`
+    Admin(AdminAction),
+    /// Group setup route, e.g. `feature:captcha`
+    GroupSetup(GroupSetupAction),
+    /// Button of a wizard scenario
+    Wizard { scenario: String, action: WizardAction },
+}
+
+/// Buttons of the scenario a user is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioAction {
+    /// Go back to the previous step
+    Back,
+    /// Pick up a scenario after a step reminder
+    Continue,
+    /// Pick up the scenario an interrupting command put aside
+    Resume,
+    /// Drop the scenario an interrupting command put aside
+    Discard,
+}
+
+impl ScenarioAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Back => "back",
+            Self::Continue => "continue",
+            Self::Resume => "resume",
+            Self::Discard => "discard",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "back" => Some(Self::Back),
+            "continue" => Some(Self::Continue),
+            "resume" => Some(Self::Resume),
+            "discard" => Some(Self::Discard),
+            _ => None,
+        }
+    }
+}
+
+/// Buttons of a DJ's song request queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SongAction {
+    /// Mark a song request as played
+    Done(i64),
+    /// Take a song request off the queue without playing it
+    Skip(i64),
+    /// Redraw the queue of an event
+    Refresh(i64),
+}
+
+/// Screens and actions of the admin panel. Each is written as a route: its
+/// name, then its fields separated by `:`, e.g. `user_list:active:0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminAction {
+    /// Back to the main menu
+    Back,
+    Users,
+    UserList { sort: UserListSort, page: i64 },
+    /// Ask for the corrected value of a profile field
+    UserEdit { telegram_id: i64, field: ProfileField },
+    BanUser,
+    UnbanUser,
+    BanList(i64),
+    /// Lift a ban from the ban list; `chat_id` is `None` for a bot-level ban
+    BanLift { chat_id: Option<i64>, telegram_id: i64, page: i64 },
+    Groups,
+    ListGroups,
+    Events,
+    ListEvents,
+    /// Clear a conversation from /state, in a group chat or private
+    ClearState { telegram_id: i64, chat_id: Option<i64> },
+    CreateEvent,
+    Stats(GrowthInterval),
+    Settings,
+    Cache,
+    Backup,
+    ExportUsers,
+    ImportUsers,
+    Features,
+    FeatureToggle(FeatureFlag),
+    Audit(i64),
+    /// Page of the activity log; `category` is `None` for all of it
+    Activity { category: Option<ActivityCategory>, page: i64 },
+    Trash { kind: TrashKind, page: i64 },
+    /// Ask to confirm deleting a user or group, by Telegram ID, or an event
+    Delete { kind: TrashKind, id: i64 },
+    DeleteConfirm { kind: TrashKind, id: i64 },
+    /// Restore an entry of the trash, by database ID
+    Restore { kind: TrashKind, id: i64, page: i64 },
+    GroupSettings,
+    GroupFeatures(i64),
+    GroupToggle { chat_id: i64, feature: GroupFeature },
+    SpamFilters,
+    SpamGroup(i64),
+    /// Ask for the pattern of a new spam filter
+    SpamAdd { chat_id: i64, kind: SpamFilterKind },
+    SpamDelete(i64),
+    SpamWarn(i64),
+    Broadcast,
+    BroadcastSegments,
+    BroadcastCities,
+    BroadcastLanguages,
+    BroadcastEvents,
+    BroadcastSegment(BroadcastSegment),
+    BroadcastSend,
+    BroadcastCancel,
+    BroadcastSchedule,
+    Announce,
+    AnnounceGroup(i64),
+    Scheduled,
+    ScheduledCancel(i64),
+    Calendars,
+    Calendar(i64),
+    CalendarAdd,
+    CalendarEdit { calendar_id: i64, field: CalendarField },
+    CalendarDelete(i64),
+    CalendarDeleteConfirm(i64),
+    Templates,
+    Template(String),
+    TemplateEdit { key: String, language: String },
+    TemplateReset { key: String, language: String },
+    Google,
+    GoogleConnect,
+    GoogleDisconnect,
+}
+
+impl AdminAction {
+    /// Name of the route, also recorded in the audit trail
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Back => "back",
+            Self::Users => "users",
+            Self::UserList { .. } => "user_list",
+            Self::UserEdit { .. } => "user_edit",
+            Self::BanUser => "ban_user",
+            Self::UnbanUser => "unban_user",
+            Self::BanList(_) => "ban_list",
+            Self::BanLift { .. } => "ban_lift",
+            Self::Groups => "groups",
+            Self::ListGroups => "list_groups",
+            Self::Events => "events",
+            Self::ListEvents => "list_events",
+            Self::ClearState { .. } => "clear_state",
+            Self::CreateEvent => "create_event",
+            Self::Stats(_) => "stats",
+            Self::Settings => "settings",
+            Self::Cache => "cache",
+            Self::Backup => "backup",
+            Self::ExportUsers => "export_users",
+            Self::ImportUsers => "import_users",
+            Self::Features => "features",
+            Self::FeatureToggle(_) => "feature_toggle",
+            Self::Audit(_) => "audit",
+            Self::Activity { .. } => "activity",
+            Self::Trash { .. } => "trash",
+            Self::Delete { .. } => "delete",
+            Self::DeleteConfirm { .. } => "delete_confirm",
+            Self::Restore { .. } => "restore",
+            Self::GroupSettings => "group_settings",
+            Self::GroupFeatures(_) => "group_features",
+            Self::GroupToggle { .. } => "group_toggle",
+            Self::SpamFilters => "spam_filters",
+            Self::SpamGroup(_) => "spam_group",
+            Self::SpamAdd { .. } => "spam_add",
+            Self::SpamDelete(_) => "spam_del",
+            Self::SpamWarn(_) => "spam_warn",
+            Self::Broadcast => "broadcast",
+            Self::BroadcastSegments => "bc_segments",
+            Self::BroadcastCities => "bc_cities",
+            Self::BroadcastLanguages => "bc_languages",
+            Self::BroadcastEvents => "bc_events",
+            Self::BroadcastSegment(_) => "bc_segment",
+            Self::BroadcastSend => "bc_send",
+            Self::BroadcastCancel => "bc_cancel",
+            Self::BroadcastSchedule => "bc_schedule",
+            Self::Announce => "announce",
+            Self::AnnounceGroup(_) => "announce_group",
+            Self::Scheduled => "scheduled",
+            Self::ScheduledCancel(_) => "sched_cancel",
+            Self::Calendars => "manage_calendars",
+            Self::Calendar(_) => "calendar",
+            Self::CalendarAdd => "calendar_add",
+            Self::CalendarEdit { .. } => "calendar_edit",
+            Self::CalendarDelete(_) => "calendar_delete",
+            Self::CalendarDeleteConfirm(_) => "calendar_delete_confirm",
+            Self::Templates => "templates",
+            Self::Template(_) => "template",
+            Self::TemplateEdit { .. } => "template_edit",
+            Self::TemplateReset { .. } => "template_reset",
+            Self::Google => "google",
+            Self::GoogleConnect => "google_connect",
+            Self::GoogleDisconnect => "google_disconnect",
+        }
+    }
+
+    /// Fields of the route after its name, if it has any
+    pub fn arg(&self) -> Option<String> {
+        let arg = match self {
+            Self::UserList { sort, page } => format!("{}:{}", sort.as_str(), page),
+            Self::UserEdit { telegram_id, field } => format!("{}:{}", telegram_id, field.as_str()),
+            Self::BanList(page) | Self::Audit(page) => page.to_string(),
+            Self::BanLift { chat_id, telegram_id, page } => format!("{}:{}:{}", chat_id.unwrap_or(0), telegram_id, page),
+            Self::ClearState { telegram_id, chat_id: Some(chat_id) } => format!("{}:{}", telegram_id, chat_id),
+            Self::ClearState { telegram_id, chat_id: None } => telegram_id.to_string(),
+            Self::Stats(interval) => interval.as_str().to_string(),
+            Self::FeatureToggle(flag) => flag.as_str().to_string(),
+            Self::Activity { category, page } => format!("{}:{}", category.map_or("all", |category| category.as_str()), page),
+            Self::Trash { kind, page } => format!("{}:{}", kind.as_str(), page),
+            Self::Delete { kind, id } | Self::DeleteConfirm { kind, id } => format!("{}:{}", kind.as_str(), id),
+            Self::Restore { kind, id, page } => format!("{}:{}:{}", kind.as_str(), id, page),
+            Self::GroupToggle { chat_id, feature } => format!("{}:{}", chat_id, feature.as_str()),
+            Self::SpamAdd { chat_id, kind } => format!("{}:{}", chat_id, kind.as_str()),
+            Self::GroupFeatures(id)
+            | Self::SpamGroup(id)
+            | Self::SpamDelete(id)
+            | Self::SpamWarn(id)
+            | Self::AnnounceGroup(id)
+            | Self::ScheduledCancel(id)
+            | Self::Calendar(id)
+            | Self::CalendarDelete(id)
+            | Self::CalendarDeleteConfirm(id) => id.to_string(),
+            Self::BroadcastSegment(segment) => segment.to_string(),
+            Self::CalendarEdit { calendar_id, field } => format!("{}:{}", calendar_id, field.as_str()),
+            Self::Template(key) => key.clone(),
+            Self::TemplateEdit { key, language } | Self::TemplateReset { key, language } => format!("{}:{}", key, language),
+            _ => return None,
+        };
+        Some(arg)
+    }
+
+    /// The route, e.g. `user_list:active:0`
+    pub fn route(&self) -> String {
+        match self.arg() {
+            Some(arg) => format!("{}:{}", self.name(), arg),
+            None => self.name().to_string(),
+        }
+    }
+
+    /// Parse a route written by [`AdminAction::route`]
+    pub fn parse(route: &str) -> Option<Self> {
+        let (name, arg) = match route.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (route, None),
+        };
+
+        let action = match (name, arg) {
+            ("back", None) => Self::Back,
+            ("users", None) => Self::Users,
+            ("user_list", Some(arg)) => {
+                let (sort, page) = parse_pair(arg)?;
+                Self::UserList { sort, page }
+            }
+            ("user_edit", Some(arg)) => {
+                let (telegram_id, field) = parse_pair(arg)?;
+                Self::UserEdit { telegram_id, field }
+            }
+            ("ban_user", None) => Self::BanUser,
+            ("unban_user", None) => Self::UnbanUser,
+            ("ban_list", Some(page)) => Self::BanList(page.parse().ok()?),
+            ("ban_lift", Some(arg)) => {
+                let (chat_id, rest) = arg.split_once(':')?;
+                let chat_id: i64 = chat_id.parse().ok()?;
+                let (telegram_id, page) = parse_pair(rest)?;
+                Self::BanLift { chat_id: (chat_id != 0).then_some(chat_id), telegram_id, page }
+            }
+            ("groups", None) => Self::Groups,
+            ("list_groups", None) => Self::ListGroups,
+            ("events", None) => Self::Events,
+            ("list_events", None) => Self::ListEvents,
+            ("clear_state", Some(arg)) => match arg.split_once(':') {
+                Some(_) => {
+                    let (telegram_id, chat_id) = parse_pair(arg)?;
+                    Self::ClearState { telegram_id, chat_id: Some(chat_id) }
+                }
+                None => Self::ClearState { telegram_id: arg.parse().ok()?, chat_id: None },
+            },
+            ("create_event", None) => Self::CreateEvent,
+            // The main menu sent a bare "stats" before the interval was part of the route
+            ("stats", None) => Self::Stats(GrowthInterval::Day),
+            ("stats", Some(interval)) => Self::Stats(interval.parse().ok()?),
+            ("settings", None) => Self::Settings,
+            ("cache", None) => Self::Cache,
+            ("backup", None) => Self::Backup,
+            ("export_users", None) => Self::ExportUsers,
+            ("import_users", None) => Self::ImportUsers,
+            ("features", None) => Self::Features,
+            ("feature_toggle", Some(flag)) => Self::FeatureToggle(flag.parse().ok()?),
+            ("audit", Some(page)) => Self::Audit(page.parse().ok()?),
+            ("activity", Some(arg)) => {
+                let (category, page) = arg.split_once(':')?;
+                let category = match category {
+                    "all" => None,
+                    category => Some(category.parse().ok()?),
+                };
+                Self::Activity { category, page: page.parse().ok()? }
+            }
+            ("trash", Some(arg)) => {
+                let (kind, page) = parse_pair(arg)?;
+                Self::Trash { kind, page }
+            }
+            ("delete", Some(arg)) => {
+                let (kind, id) = parse_pair(arg)?;
+                Self::Delete { kind, id }
+            }
+            ("delete_confirm", Some(arg)) => {
+                let (kind, id) = parse_pair(arg)?;
+                Self::DeleteConfirm { kind, id }
+            }
+            ("restore", Some(arg)) => {
+                let (kind, rest) = arg.split_once(':')?;
+                let (id, page) = parse_pair(rest)?;
+                Self::Restore { kind: kind.parse().ok()?, id, page }
+            }
+            ("group_settings", None) => Self::GroupSettings,
+            ("group_features", Some(id)) => Self::GroupFeatures(id.parse().ok()?),
+            ("group_toggle", Some(arg)) => {
+                let (chat_id, feature) = parse_pair(arg)?;
+                Self::GroupToggle { chat_id, feature }
+            }
+            ("spam_filters", None) => Self::SpamFilters,
+            ("spam_group", Some(id)) => Self::SpamGroup(id.parse().ok()?),
+            ("spam_add", Some(arg)) => {
+                let (chat_id, kind) = parse_pair(arg)?;
+                Self::SpamAdd { chat_id, kind }
+            }
+            ("spam_del", Some(id)) => Self::SpamDelete(id.parse().ok()?),
+            ("spam_warn", Some(id)) => Self::SpamWarn(id.parse().ok()?),
+            ("broadcast", None) => Self::Broadcast,
+            ("bc_segments", None) => Self::BroadcastSegments,
+            ("bc_cities", None) => Self::BroadcastCities,
+            ("bc_languages", None) => Self::BroadcastLanguages,
+            ("bc_events", None) => Self::BroadcastEvents,
+            ("bc_segment", Some(segment)) => Self::BroadcastSegment(segment.parse().ok()?),
+            ("bc_send", None) => Self::BroadcastSend,
+            ("bc_cancel", None) => Self::BroadcastCancel,
+            ("bc_schedule", None) => Self::BroadcastSchedule,
+            ("announce", None) => Self::Announce,
+            ("announce_group", Some(id)) => Self::AnnounceGroup(id.parse().ok()?),
+            ("scheduled", None) => Self::Scheduled,
+            ("sched_cancel", Some(id)) => Self::ScheduledCancel(id.parse().ok()?),
+            ("manage_calendars", None) => Self::Calendars,
+            ("calendar", Some(id)) => Self::Calendar(id.parse().ok()?),
+            ("calendar_add", None) => Self::CalendarAdd,
+            ("calendar_edit", Some(arg)) => {
+                let (calendar_id, field) = parse_pair(arg)?;
+                Self::CalendarEdit { calendar_id, field }
+            }
+            ("calendar_delete", Some(id)) => Self::CalendarDelete(id.parse().ok()?),
+            ("calendar_delete_confirm", Some(id)) => Self::CalendarDeleteConfirm(id.parse().ok()?),
+            ("templates", None) => Self::Templates,
+            ("template", Some(key)) if !key.is_empty() => Self::Template(key.to_string()),
+            ("template_edit", Some(arg)) => {
+                let (key, language) = arg.split_once(':')?;
+                Self::TemplateEdit { key: key.to_string(), language: language.to_string() }
+            }
+            ("template_reset", Some(arg)) => {
+                let (key, language) = arg.split_once(':')?;
+                Self::TemplateReset { key: key.to_string(), language: language.to_string() }
+            }
+            ("google", None) => Self::Google,
+            ("google_connect", None) => Self::GoogleConnect,
+            ("google_disconnect", None) => Self::GoogleDisconnect,
+            _ => return None,
+        };
+        Some(action)
+    }
+}
+
+/// Buttons of the setup message the bot posts when added to a group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupSetupAction {
+    CheckPermissions,
+    Documentation,
+    Configure,
+    /// Show the language choices
+    Language,
+    /// Set the group's language, by code
+    SetLanguage(String),
+    /// Ask for the welcome message
+    Welcome,
+    /// Switch a group feature on or off
+    Feature(GroupFeature),
+    Done,
+    /// Delete the setup message
+    Dismiss,
+}
+
+impl GroupSetupAction {
+    /// The route, e.g. `feature:captcha`
+    pub fn route(&self) -> String {
+        match self {
+            Self::CheckPermissions => "check_permissions".to_string(),
+            Self::Documentation => "documentation".to_string(),
+            Self::Configure => "configure".to_string(),
+            Self::Language => "language".to_string(),
+            Self::SetLanguage(code) => format!("lang_{}", code),
+            Self::Welcome => "welcome".to_string(),
+            Self::Feature(feature) => format!("feature:{}", feature.as_str()),
+            Self::Done => "done".to_string(),
+            Self::Dismiss => "dismiss".to_string(),
+        }
+    }
+
+    /// Parse a route written by [`GroupSetupAction::route`]
+    pub fn parse(route: &str) -> Option<Self> {
+        let action = match route.split_once(':') {
+            Some(("feature", feature)) => Self::Feature(feature.parse().ok()?),
+            Some(_) => return None,
+            None => match route {
+                "check_permissions" => Self::CheckPermissions,
+                "documentation" => Self::Documentation,
+                "configure" => Self::Configure,
+                "language" => Self::Language,
+                "welcome" => Self::Welcome,
+                "done" => Self::Done,
+                "dismiss" => Self::Dismiss,
+                _ => Self::SetLanguage(route.strip_prefix("lang_").filter(|code| !code.is_empty())?.to_string()),
+            },
+        };
+        Some(action)
+    }
+}
+
+/// Parse `<a>:<b>`, the last field taking any further `:`
+fn parse_pair<A: FromStr, B: FromStr>(fields: &str) -> Option<(A, B)> {
+    let (a, b) = fields.split_once(':')?;
+    Some((a.parse().ok()?, b.parse().ok()?))
+}
+
+impl CallbackData {
+    /// Inline keyboard button carrying this callback data
+    pub fn button(self, text: impl Into<String>) -> InlineKeyboardButton {
+        InlineKeyboardButton::callback(text, self)
+    }
+
+    /// Encode for a button
+    pub fn encode(&self) -> String {
+        let (tag, fields) = match self {
+            Self::Language(code) => ('l', code.clone()),
+            Self::Location(city) => ('c', city.clone().unwrap_or_else(|| "skip".to_string())),
+            Self::Scenario(action) => ('s', action.as_str().to_string()),
+            Self::Calendar(calendar_id) => ('k', calendar_id.map_or_else(|| "back".to_string(), |id| id.to_string())),
+            Self::EventRegister(event_id) => ('e', event_id.to_string()),
+            Self::EventUnregister(event_id) => ('u', event_id.to_string()),
+            Self::Captcha { user_id, answer } => ('q', format!("{}:{}", user_id, answer)),
+            Self::ResolveReport(report_id) => ('r', report_id.to_string()),
+            Self::Style(style) => ('y', style.as_str().to_string()),
+            Self::Song(SongAction::Done(song_id)) => ('m', format!("done:{}", song_id)),
+            Self::Song(SongAction::Skip(song_id)) => ('m', format!("skip:{}", song_id)),
+            Self::Song(SongAction::Refresh(event_id)) => ('m', format!("refresh:{}", event_id)),
+            Self::Admin(action) => ('a', action.route()),
+            Self::GroupSetup(action) => ('g', action.route()),
+            Self::Wizard { scenario, action } => ('w', format!("{}:{}", scenario, action.as_data())),
+        };
+
+        let data = format!("{}{}:{}", CALLBACK_DATA_VERSION, tag, fields);
+        if data.len() > MAX_CALLBACK_DATA_LEN {
+            warn!(callback_data = %data, "Callback data is longer than Telegram allows");
+        }
+        data
+    }
+
+    /// Decode the data of a pressed button, in the current or the old format
+    pub fn decode(data: &str) -> Option<Self> {
+        match data.strip_prefix(CALLBACK_DATA_VERSION) {
+            Some(rest) => {
+                let (tag, fields) = rest.split_once(':')?;
+                let mut tag = tag.chars();
+                match (tag.next(), tag.next()) {
+                    (Some(tag), None) => Self::from_fields(tag, fields),
+                    _ => None,
+                }
+            }
+            None => Self::decode_legacy(data),
+        }
+    }
+
+    /// Decode `<action>:<fields>` buttons sent before the versioned format
+    fn decode_legacy(data: &str) -> Option<Self> {
+        let (action, fields) = data.split_once(':')?;
+        let tag = match action {
+            "lang" => 'l',
+            "location" => 'c',
+            "scenario" => 's',
+            "calendar" => 'k',
+            "event_register" => 'e',
+            "event_unregister" => 'u',
+            "captcha" => 'q',
+            "report" => return Self::from_fields('r', fields.strip_prefix("resolve:")?),
+            "style" => 'y',
+            "song" => 'm',
+            "admin" => 'a',
+            "group_setup" => 'g',
+            "wizard" => 'w',
+            _ => return None,
+        };
+        Self::from_fields(tag, fields)
+    }
+
+    fn from_fields(tag: char, fields: &str) -> Option<Self> {
+        let data = match tag {
+            'l' if !fields.is_empty() && !fields.contains(':') => Self::Language(fields.to_string()),
+            'c' if fields == "skip" => Self::Location(None),
+            'c' if !fields.is_empty() => Self::Location(Some(fields.to_string())),
+            's' => Self::Scenario(ScenarioAction::parse(fields)?),
+            'k' if fields == "back" => Self::Calendar(None),
+            'k' => Self::Calendar(Some(fields.parse().ok()?)),
+            'e' => Self::EventRegister(fields.parse().ok()?),
+            'u' => Self::EventUnregister(fields.parse().ok()?),
+            'q' => {
+                let (user_id, answer) = fields.split_once(':')?;
+                Self::Captcha { user_id: user_id.parse().ok()?, answer: answer.parse().ok()? }
+            }
+            'r' => Self::ResolveReport(fields.parse().ok()?),
+            'y' => Self::Style(fields.parse().ok()?),
+            'm' => {
+                let (action, id) = fields.split_once(':')?;
+                let id = id.parse().ok()?;
+                Self::Song(match action {
+                    "done" => SongAction::Done(id),
+                    "skip" => SongAction::Skip(id),
+                    "refresh" => SongAction::Refresh(id),
+                    _ => return None,
+                })
+            }
+            'a' => Self::Admin(AdminAction::parse(fields)?),
+            'g' => Self::GroupSetup(GroupSetupAction::parse(fields)?),
+            'w' => {
+                let (scenario, action) = fields.split_once(':')?;
+                Self::Wizard { scenario: scenario.to_string(), action: WizardAction::parse(action)? }
+            }
+            _ => return None,
+        };
+        Some(data)
+    }
+}
+
+impl From<CallbackData> for String {
+    fn from(data: CallbackData) -> Self {
+        data.encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let all = [
+            CallbackData::Language("ru".to_string()),
+            CallbackData::Location(Some("Saint Petersburg".to_string())),
+            CallbackData::Location(None),
+            CallbackData::Scenario(ScenarioAction::Resume),
+            CallbackData::Calendar(Some(3)),
+            CallbackData::Calendar(None),
+            CallbackData::EventRegister(42),
+            CallbackData::EventUnregister(42),
+            CallbackData::Captcha { user_id: 123456789, answer: 2 },
+            CallbackData::ResolveReport(7),
+            CallbackData::Style(DanceStyle::SoloJazz),
+            CallbackData::Song(SongAction::Refresh(5)),
+            CallbackData::Admin(AdminAction::ClearState { telegram_id: 123, chat_id: Some(-1001234567890) }),
+            CallbackData::Admin(AdminAction::BroadcastSegment(BroadcastSegment::City("Saint Petersburg".to_string()))),
+            CallbackData::GroupSetup(GroupSetupAction::Feature(GroupFeature::Captcha)),
+            CallbackData::GroupSetup(GroupSetupAction::SetLanguage("ru".to_string())),
+            CallbackData::Wizard { scenario: "event_creation".to_string(), action: WizardAction::Choose("create".to_string()) },
+        ];
+        for data in all {
+            let encoded = data.encode();
+            assert!(encoded.len() <= MAX_CALLBACK_DATA_LEN, "{} is too long", encoded);
+            assert_eq!(CallbackData::decode(&encoded), Some(data));
+        }
+        assert_eq!(CallbackData::EventRegister(42).encode(), "1e:42");
+    }
+
+    #[test]
+    fn test_decode_legacy() {
+        assert_eq!(CallbackData::decode("lang:en"), Some(CallbackData::Language("en".to_string())));
+        assert_eq!(CallbackData::decode("location:Saint Petersburg"), Some(CallbackData::Location(Some("Saint Petersburg".to_string()))));
+        assert_eq!(CallbackData::decode("location:skip"), Some(CallbackData::Location(None)));
+        assert_eq!(CallbackData::decode("report:resolve:7"), Some(CallbackData::ResolveReport(7)));
+        assert_eq!(
+            CallbackData::decode("admin:user_list:active:0"),
+            Some(CallbackData::Admin(AdminAction::UserList { sort: UserListSort::LastActive, page: 0 }))
+        );
+        assert_eq!(CallbackData::decode("admin:stats"), Some(CallbackData::Admin(AdminAction::Stats(GrowthInterval::Day))));
+        assert_eq!(CallbackData::decode("group_setup:lang_en"), Some(CallbackData::GroupSetup(GroupSetupAction::SetLanguage("en".to_string()))));
+        assert_eq!(
+            CallbackData::decode("wizard:event_creation:back"),
+            Some(CallbackData::Wizard { scenario: "event_creation".to_string(), action: WizardAction::Back })
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert_eq!(CallbackData::decode(""), None);
+        assert_eq!(CallbackData::decode("lang:"), None);
+        assert_eq!(CallbackData::decode("event_register:abc"), None);
+        assert_eq!(CallbackData::decode("1x:1"), None);
+        assert_eq!(CallbackData::decode("2e:42"), None);
+        assert_eq!(CallbackData::decode("unknown:1"), None);
+        assert_eq!(CallbackData::decode("1a:unknown"), None);
+        assert_eq!(CallbackData::decode("1a:users:1"), None);
+        assert_eq!(CallbackData::decode("1a:user_list:active"), None);
+        assert_eq!(CallbackData::decode("1a:group_toggle:-100:nope"), None);
+        assert_eq!(CallbackData::decode("1g:feature:nope"), None);
+        assert_eq!(CallbackData::decode("1g:lang_"), None);
+    }
+
+    /// Every admin panel route, with the longest values it is realistically sent with
+    fn longest_admin_actions() -> Vec<AdminAction> {
+        // Telegram IDs fit in 52 bits; group chat IDs are negative
+        let telegram_id = (1_i64 << 52) - 1;
+        let chat_id = -((1_i64 << 52) - 1);
+        let id = 9_999_999_999;
+        let page = 9_999;
+        let template = "scheduled_message_failed".to_string();
+        let kind = TrashKind::Events;
+
+        let mut actions = vec![
+            AdminAction::Back,
+            AdminAction::Users,
+            AdminAction::UserList { sort: UserListSort::Registered, page },
+            AdminAction::BanUser,
+            AdminAction::UnbanUser,
+            AdminAction::BanList(page),
+            AdminAction::BanLift { chat_id: Some(chat_id), telegram_id, page },
+            AdminAction::BanLift { chat_id: None, telegram_id, page },
+            AdminAction::Groups,
+            AdminAction::ListGroups,
+            AdminAction::Events,
+            AdminAction::ListEvents,
+            AdminAction::ClearState { telegram_id, chat_id: Some(chat_id) },
+            AdminAction::ClearState { telegram_id, chat_id: None },
+            AdminAction::CreateEvent,
+            AdminAction::Stats(GrowthInterval::Week),
+            AdminAction::Settings,
+            AdminAction::Cache,
+            AdminAction::Backup,
+            AdminAction::ExportUsers,
+            AdminAction::ImportUsers,
+            AdminAction::Features,
+            AdminAction::Audit(page),
+            AdminAction::Activity { category: None, page },
+            AdminAction::Delete { kind, id: chat_id },
+            AdminAction::DeleteConfirm { kind, id: chat_id },
+            AdminAction::GroupSettings,
+            AdminAction::GroupFeatures(chat_id),
+            AdminAction::SpamFilters,
+            AdminAction::SpamGroup(chat_id),
+            AdminAction::SpamDelete(id),
+            AdminAction::SpamWarn(chat_id),
+            AdminAction::Broadcast,
+            AdminAction::BroadcastSegments,
+            AdminAction::BroadcastCities,
+            AdminAction::BroadcastLanguages,
+            AdminAction::BroadcastEvents,
+            AdminAction::BroadcastSegment(BroadcastSegment::All),
+            AdminAction::BroadcastSegment(BroadcastSegment::Language("pt-br".to_string())),
+            AdminAction::BroadcastSegment(BroadcastSegment::EventParticipants(id)),
+            AdminAction::BroadcastSend,
+            AdminAction::BroadcastCancel,
+            AdminAction::BroadcastSchedule,
+            AdminAction::Announce,
+            AdminAction::AnnounceGroup(chat_id),
+            AdminAction::Scheduled,
+            AdminAction::ScheduledCancel(id),
+            AdminAction::Calendars,
+            AdminAction::Calendar(id),
+            AdminAction::CalendarAdd,
+            AdminAction::CalendarDelete(id),
+            AdminAction::CalendarDeleteConfirm(id),
+            AdminAction::Templates,
+            AdminAction::Template(template.clone()),
+            AdminAction::TemplateEdit { key: template.clone(), language: "ru".to_string() },
+            AdminAction::TemplateReset { key: template, language: "ru".to_string() },
+            AdminAction::Google,
+            AdminAction::GoogleConnect,
+            AdminAction::GoogleDisconnect,
+        ];
+        // Cities longer than these don't get a button, see show_broadcast_segment_choices
+        for city in ["Saint Petersburg", "Санкт-Петербург", "Ростов-на-Дону", "Rio de Janeiro"] {
+            actions.push(AdminAction::BroadcastSegment(BroadcastSegment::City(city.to_string())));
+        }
+        for field in [ProfileField::Name, ProfileField::City, ProfileField::Language] {
+            actions.push(AdminAction::UserEdit { telegram_id, field });
+        }
+        for flag in FeatureFlag::ALL {
+            actions.push(AdminAction::FeatureToggle(flag));
+        }
+        for category in ActivityCategory::ALL {
+            actions.push(AdminAction::Activity { category: Some(category), page });
+        }
+        for kind in TrashKind::ALL {
+            actions.push(AdminAction::Trash { kind, page });
+            actions.push(AdminAction::Restore { kind, id, page });
+        }
+        for feature in GroupFeature::ALL {
+            actions.push(AdminAction::GroupToggle { chat_id, feature });
+        }
+        for kind in [SpamFilterKind::Keyword, SpamFilterKind::Regex, SpamFilterKind::Domain] {
+            actions.push(AdminAction::SpamAdd { chat_id, kind });
+        }
+        for field in [CalendarField::Name, CalendarField::Description, CalendarField::GoogleCalendarId] {
+            actions.push(AdminAction::CalendarEdit { calendar_id: id, field });
+        }
+        actions
+    }
+
+    #[test]
+    fn test_longest_callback_data_fits() {
+        let mut all: Vec<CallbackData> = longest_admin_actions().into_iter().map(CallbackData::Admin).collect();
+
+        all.extend([
+            GroupSetupAction::CheckPermissions,
+            GroupSetupAction::Documentation,
+            GroupSetupAction::Configure,
+            GroupSetupAction::Language,
+            GroupSetupAction::SetLanguage("en".to_string()),
+            GroupSetupAction::Welcome,
+            GroupSetupAction::Done,
+            GroupSetupAction::Dismiss,
+        ].into_iter().map(CallbackData::GroupSetup));
+        all.extend(GroupFeature::ALL.map(|feature| CallbackData::GroupSetup(GroupSetupAction::Feature(feature))));
+
+        // Every choice of every built-in scenario becomes a wizard button
+        let scenario_manager = crate::state::ScenarioManager::new();
+        for scenario in scenario_manager.get_all_scenarios() {
+            for step in scenario.steps.values() {
+                if let Some(crate::state::InputType::Choice(options)) = step.validation.as_ref().map(|v| &v.input_type) {
+                    all.extend(options.iter().map(|option| CallbackData::Wizard {
+                        scenario: scenario.id.clone(),
+                        action: WizardAction::Choose(option.clone()),
+                    }));
+                }
+            }
+            all.extend([WizardAction::Skip, WizardAction::Back, WizardAction::Cancel, WizardAction::Done]
+                .map(|action| CallbackData::Wizard { scenario: scenario.id.clone(), action }));
+        }
+
+        all.extend(["Moscow", "Saint Petersburg"].map(|city| CallbackData::Location(Some(city.to_string()))));
+        all.extend(DanceStyle::ALL.map(CallbackData::Style));
+        all.push(CallbackData::Captcha { user_id: (1 << 52) - 1, answer: 9 });
+        all.push(CallbackData::Song(SongAction::Refresh(9_999_999_999)));
+
+        for data in all {
+            let encoded = data.encode();
+            assert!(encoded.len() <= MAX_CALLBACK_DATA_LEN, "{} is {} bytes", encoded, encoded.len());
+            assert_eq!(CallbackData::decode(&encoded), Some(data));
+        }
+    }
+}
@@ -3,48 +3,76 @@
 //! Handles bot configuration when added to groups
 
 use std::collections::HashMap;
-use teloxide::{Bot, types::{ChatId, InlineKeyboardMarkup, InlineKeyboardButton, ChatMemberStatus}, prelude::*};
+use teloxide::{Bot, types::{ChatId, InlineKeyboardMarkup, InlineKeyboardButton, Message}, prelude::*};
 use tracing::{info, debug, warn};
 use crate::utils::errors::Result;
-use crate::services::ServiceFactory;
+use crate::services::{BotPermission, ServiceFactory};
+use crate::models::group::{Group, GroupFeature};
+use crate::services::group::{render_welcome_message, WELCOME_PLACEHOLDERS};
+use crate::state::{ConversationContext, ScenarioManager, StateStorage};
+use crate::handlers::callbacks::{CallbackContext, CallbackData, GroupSetupAction};
 use crate::i18n::I18n;
 
+/// Maximum length of a group welcome message
+const WELCOME_MESSAGE_MAX_LENGTH: usize = 1000;
+
 /// Handle group setup callbacks
-pub async fn handle_group_setup_callback(
-    bot: Bot,
-    chat_id: ChatId,
-    user_id: i64,
-    action: String,
-    services: ServiceFactory,
-    i18n: I18n,
-) -> Result<()> {
-    debug!(user_id = user_id, chat_id = ?chat_id, action = %action, "Processing group setup callback");
+pub async fn handle_group_setup_callback(context: CallbackContext, action: GroupSetupAction) -> Result<()> {
+    let CallbackContext { bot, chat_id, message_id, user_id, services, scenario_manager, state_storage, i18n } = context;
+    debug!(user_id = user_id, chat_id = ?chat_id, action = ?action, "Processing group setup callback");
 
-    match action.as_str() {
-        "check_permissions" => {
+    match action {
+        GroupSetupAction::CheckPermissions => {
             check_bot_permissions(bot, chat_id, &services, &i18n).await?;
         }
-        "documentation" => {
+        GroupSetupAction::Documentation => {
             // This should open a URL, handled by inline keyboard URL button
             debug!("Documentation button clicked");
         }
-        "language" => {
-            show_language_selector(bot, chat_id, &i18n).await?;
+        GroupSetupAction::Configure => {
+            if is_group_admin(&bot, chat_id, user_id, &services, &i18n).await? {
+                show_configuration(bot, chat_id, &services, &i18n).await?;
+            }
         }
-        "lang_en" => {
-            set_group_language(bot, chat_id, "en".to_string(), &services, &i18n).await?;
+        GroupSetupAction::Language => {
+            if is_group_admin(&bot, chat_id, user_id, &services, &i18n).await? {
+                show_language_selector(bot, chat_id, &i18n).await?;
+            }
         }
-        "lang_ru" => {
-            set_group_language(bot, chat_id, "ru".to_string(), &services, &i18n).await?;
+        GroupSetupAction::SetLanguage(language_code) => {
+            if !i18n.supported_languages().contains(&language_code) {
+                warn!(language_code = %language_code, "Unsupported group language in setup");
+                return Ok(());
+            }
+            if is_group_admin(&bot, chat_id, user_id, &services, &i18n).await? {
+                set_group_language(bot, chat_id, language_code, &services, &i18n).await?;
+            }
+        }
+        GroupSetupAction::Welcome => {
+            prompt_welcome_message(bot, chat_id, user_id, &services, &scenario_manager, &state_storage, &i18n).await?;
         }
-        "dismiss" => {
-            // Delete the setup message
-            if let Err(e) = bot.delete_message(chat_id, teloxide::types::MessageId(0)).await {
-                warn!(error = %e, "Failed to delete setup message");
+        GroupSetupAction::Feature(feature) => {
+            if is_group_admin(&bot, chat_id, user_id, &services, &i18n).await? {
+                let group = register_group(&bot, chat_id, &services).await?;
+                let enabled = !group.feature_enabled(feature);
+                services.group_service.set_feature(chat_id.0, feature, enabled).await?;
+                show_configuration(bot, chat_id, &services, &i18n).await?;
             }
         }
-        _ => {
-            warn!(action = %action, "Unknown group setup action");
+        GroupSetupAction::Done => {
+            if is_group_admin(&bot, chat_id, user_id, &services, &i18n).await? {
+                finish_configuration(bot, chat_id, user_id, &services, &state_storage, &i18n).await?;
+            }
+        }
+        GroupSetupAction::Dismiss => {
+            // Delete the setup message the button belongs to
+            let Some(message_id) = message_id else {
+                debug!(chat_id = ?chat_id, "Setup message to dismiss is no longer available");
+                return Ok(());
+            };
+            if let Err(e) = bot.delete_message(chat_id, message_id).await {
+                warn!(chat_id = ?chat_id, message_id = message_id.0, error = %e, "Failed to delete setup message");
+            }
         }
     }
 
@@ -60,66 +88,137 @@ pub async fn handle_bot_added_to_group(
 ) -> Result<()> {
     info!(chat_id = ?chat_id, "Bot added to group");
 
+    // A group the bot was removed from earlier becomes active again
+    if let Some(group) = services.group_service.get_group(chat_id.0).await?.filter(|g| !g.is_active) {
+        services.group_service.set_active(group.telegram_id, true).await?;
+    }
+
     // Check bot permissions
     check_bot_permissions(bot, chat_id, &services, &i18n).await?;
 
     Ok(())
 }
 
+/// Handle bot being removed or kicked from a group: deactivate the group,
+/// drop its pending posts and let the bot admins know
+pub async fn handle_bot_removed_from_group(
+    chat_id: ChatId,
+    kicked_by: &teloxide::types::User,
+    mut services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    info!(chat_id = ?chat_id, kicked_by = kicked_by.id.0, "Bot removed from group");
+    services.moderation_service.forget_chat_admins(chat_id);
+
+    let Some(group) = services.group_service.set_active(chat_id.0, false).await? else {
+        debug!(chat_id = ?chat_id, "Bot removed from an unregistered group");
+        return Ok(());
+    };
+
+    let queued = services.quiet_hours_service.cancel_for_group(group.id).await?;
+    let deletions = services.cleanup_service.cancel_for_chat(chat_id).await?;
+    debug!(group_id = group.id, queued = queued, deletions = deletions, "Scheduled posts for removed group cancelled");
+
+    let mut params = HashMap::new();
+    params.insert("group".to_string(), group.title.clone());
+    params.insert("chat_id".to_string(), chat_id.0.to_string());
+    params.insert("user".to_string(), kicked_by.username.as_ref().map_or_else(|| kicked_by.full_name(), |u| format!("@{}", u)));
+    let text = i18n.t("group.removed.admin_notice", "en", Some(&params));
+    services.notification_service.send_admin_notification(&text).await?;
+
+    Ok(())
+}
+
+/// Handle the bot's admin rights changing in a group it is already in:
+/// report rights that are still missing, or confirm that all are granted
+pub async fn handle_bot_rights_changed(
+    bot: Bot,
+    chat_id: ChatId,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    info!(chat_id = ?chat_id, "Bot rights changed in group");
+
+    let lang = group_language(chat_id, &services).await?;
+    let missing = services.auth_service.missing_bot_permissions(chat_id).await?;
+
+    if missing.is_empty() {
+        bot.send_message(chat_id, i18n.t("group.setup.permissions_granted", &lang, None)).await?;
+    } else {
+        show_permission_request(bot, chat_id, &missing, &lang, &i18n).await?;
+    }
+
+    Ok(())
+}
+
 /// Check if bot has required permissions
 async fn check_bot_permissions(
     bot: Bot,
     chat_id: ChatId,
-    _services: &ServiceFactory,
+    services: &ServiceFactory,
     i18n: &I18n,
 ) -> Result<()> {
     debug!(chat_id = ?chat_id, "Checking bot permissions");
 
-    // Get bot's member status in the chat
-    let bot_user = bot.get_me().await?;
-    let member = bot.get_chat_member(chat_id, bot_user.id).await?;
+    let lang = group_language(chat_id, services).await?;
+    let missing = services.auth_service.missing_bot_permissions(chat_id).await?;
 
-    let has_required_permissions = match member.status() {
-        ChatMemberStatus::Administrator => {
-            // For now, assume administrator has required permissions
-            // In a real implementation, you'd check specific permissions
-            true
-        }
-        _ => false,
-    };
-
-    if has_required_permissions {
+    if missing.is_empty() {
         show_setup_success(bot, chat_id, i18n).await?;
     } else {
-        show_permission_request(bot, chat_id, i18n).await?;
+        show_permission_request(bot, chat_id, &missing, &lang, i18n).await?;
     }
 
     Ok(())
 }
 
+/// List the bot's admin rights, marking the missing ones
+pub fn format_bot_permissions(missing: &[BotPermission], language: &str, i18n: &I18n) -> String {
+    BotPermission::ALL
+        .iter()
+        .map(|permission| {
+            let mark = if missing.contains(permission) { "❌" } else { "✅" };
+            let name = i18n.t(&format!("group.permissions.{}", permission.as_str()), language, None);
+            format!("{} {}", mark, name)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Get a group's language, English for groups the bot has not registered
+async fn group_language(chat_id: ChatId, services: &ServiceFactory) -> Result<String> {
+    Ok(services.group_service.get_group(chat_id.0).await?
+        .map(|group| group.language_code)
+        .unwrap_or_else(|| "en".to_string()))
+}
+
 /// Show permission request message
 async fn show_permission_request(
     bot: Bot,
     chat_id: ChatId,
+    missing: &[BotPermission],
+    lang: &str,
     i18n: &I18n,
 ) -> Result<()> {
-    let message_text = i18n.t("group.setup.permission_request", "en", None);
+    let mut params = HashMap::new();
+    params.insert("permissions".to_string(), format_bot_permissions(missing, lang, i18n));
+    let message_text = i18n.t("group.setup.permission_request", lang, Some(&params));
     
     let keyboard = InlineKeyboardMarkup::new(vec![
         vec![
             InlineKeyboardButton::url(
-                i18n.t("buttons.group.documentation", "en", None),
+                i18n.t("buttons.group.documentation", lang, None),
                 reqwest::Url::parse("https://github.com/your-repo/swing-buddy/wiki/Bot-Setup")?
             ),
         ],
         vec![
             InlineKeyboardButton::callback(
-                i18n.t("buttons.group.check_again", "en", None),
-                "group_setup:check_permissions"
+                i18n.t("buttons.group.check_again", lang, None),
+                CallbackData::GroupSetup(GroupSetupAction::CheckPermissions)
             ),
             InlineKeyboardButton::callback(
-                i18n.t("buttons.group.language", "en", None),
-                "group_setup:language"
+                i18n.t("buttons.group.language", lang, None),
+                CallbackData::GroupSetup(GroupSetupAction::Language)
             ),
         ],
     ]);
@@ -140,10 +239,16 @@ async fn show_setup_success(
     let message_text = i18n.t("group.setup.success", "en", None);
     
     let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.group.configure", "en", None),
+                CallbackData::GroupSetup(GroupSetupAction::Configure)
+            ),
+        ],
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("buttons.group.got_it", "en", None),
-                "group_setup:dismiss"
+                CallbackData::GroupSetup(GroupSetupAction::Dismiss)
             ),
         ],
     ]);
@@ -169,17 +274,17 @@ async fn show_language_selector(
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("buttons.language.english", "en", None),
-                "group_setup:lang_en"
+                CallbackData::GroupSetup(GroupSetupAction::SetLanguage("en".to_string()))
             ),
             InlineKeyboardButton::callback(
                 i18n.t("buttons.language.russian", "ru", None),
-                "group_setup:lang_ru"
+                CallbackData::GroupSetup(GroupSetupAction::SetLanguage("ru".to_string()))
             ),
         ],
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("buttons.navigation.back", "en", None),
-                "group_setup:check_permissions"
+                CallbackData::GroupSetup(GroupSetupAction::Configure)
             ),
         ],
     ]);
@@ -201,20 +306,225 @@ async fn set_group_language(
 ) -> Result<()> {
     debug!(chat_id = ?chat_id, language_code = %language_code, "Setting group language");
 
-    // TODO: Store group language preference in database
-    // For now, just show confirmation and go back to permission check
-    
+    register_group(&bot, chat_id, services).await?;
+    services.group_service.set_language(chat_id.0, &language_code).await?;
+
     let mut params = HashMap::new();
-    params.insert("language".to_string(), 
-        if language_code == "ru" { "Russian" } else { "English" }.to_string()
-    );
-    
+    params.insert("language".to_string(), i18n.t(&format!("group.setup.languages.{}", language_code), &language_code, None));
+
     let confirmation_text = i18n.t("group.setup.language_set", &language_code, Some(&params));
     bot.send_message(chat_id, confirmation_text).await?;
 
-    // Go back to permission check
-    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-    check_bot_permissions(bot, chat_id, services, i18n).await?;
+    show_configuration(bot, chat_id, services, i18n).await?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Show the configuration wizard: group language, welcome message and feature switches
+async fn show_configuration(
+    bot: Bot,
+    chat_id: ChatId,
+    services: &ServiceFactory,
+    i18n: &I18n,
+) -> Result<()> {
+    let group = register_group(&bot, chat_id, services).await?;
+    let lang = group.language_code.as_str();
+
+    let mut params = HashMap::new();
+    params.insert("language".to_string(), i18n.t(&format!("group.setup.languages.{}", lang), lang, None));
+    params.insert("welcome".to_string(), group.welcome_message()
+        .map(str::to_string)
+        .unwrap_or_else(|| i18n.t("messages.group.welcome_message_none", lang, None)));
+    let text = i18n.t("group.setup.configuration", lang, Some(&params));
+
+    let mut keyboard = vec![
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.group.language", lang, None),
+                CallbackData::GroupSetup(GroupSetupAction::Language)
+            ),
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.group.welcome_message", lang, None),
+                CallbackData::GroupSetup(GroupSetupAction::Welcome)
+            ),
+        ],
+    ];
+
+    // Features switched off for the whole bot cannot be turned on for a group
+    for feature in GroupFeature::ALL {
+        let available = match feature {
+            GroupFeature::CasProtection => services.cas_service.is_enabled(),
+            GroupFeature::Captcha => services.captcha_service.is_enabled(),
+            GroupFeature::WeeklyDigest => services.digest_service.is_enabled(),
+            GroupFeature::PinnedAnnouncements => services.reminder_service.is_enabled(),
+            _ => true,
+        };
+        if !available {
+            continue;
+        }
+
+        let mark = if group.feature_enabled(feature) { "✅" } else { "❌" };
+        let label = i18n.t(&format!("commands.admin.group_settings.features.{}", feature.as_str()), lang, None);
+        keyboard.push(vec![InlineKeyboardButton::callback(
+            format!("{} {}", mark, label),
+            CallbackData::GroupSetup(GroupSetupAction::Feature(feature)),
+        )]);
+    }
+
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.group.done", lang, None),
+        CallbackData::GroupSetup(GroupSetupAction::Done)
+    )]);
+
+    bot.send_message(chat_id, text)
+        .reply_markup(InlineKeyboardMarkup::new(keyboard))
+        .await?;
+
+    Ok(())
+}
+
+/// Close the configuration wizard and sum up what the group uses
+async fn finish_configuration(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    services: &ServiceFactory,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+) -> Result<()> {
+    let group = register_group(&bot, chat_id, services).await?;
+    let lang = group.language_code.as_str();
+
+    // An unanswered welcome message prompt ends with the wizard
+    if let Some(context) = state_storage.load_chat_context(user_id, chat_id.0).await? {
+        if context.is_at("group_setup", "welcome_message_input") {
+            state_storage.delete_chat_context(user_id, chat_id.0).await?;
+        }
+    }
+
+    let features = GroupFeature::ALL.into_iter()
+        .filter(|feature| group.feature_enabled(*feature))
+        .map(|feature| i18n.t(&format!("commands.admin.group_settings.features.{}", feature.as_str()), lang, None))
+        .collect::<Vec<_>>();
+
+    let mut params = HashMap::new();
+    params.insert("features".to_string(), if features.is_empty() { "—".to_string() } else { features.join(", ") });
+    bot.send_message(chat_id, i18n.t("group.setup.complete", lang, Some(&params))).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, "Group configuration completed");
+
+    Ok(())
+}
+
+/// Check that the user is an admin of the group, telling them otherwise
+async fn is_group_admin(
+    bot: &Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    services: &ServiceFactory,
+    i18n: &I18n,
+) -> Result<bool> {
+    if services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        return Ok(true);
+    }
+
+    bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+    Ok(false)
+}
+
+/// Get the group being configured, registering it under its current title
+async fn register_group(bot: &Bot, chat_id: ChatId, services: &ServiceFactory) -> Result<Group> {
+    if let Some(group) = services.group_service.get_group(chat_id.0).await? {
+        return Ok(group);
+    }
+
+    let chat = bot.get_chat(chat_id).await?;
+    services.group_service.get_or_register_group(chat_id.0, chat.title().unwrap_or_default()).await
+}
+
+/// Ask a group admin for the welcome message; their next message in the group is taken as the text
+async fn prompt_welcome_message(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    services: &ServiceFactory,
+    scenario_manager: &ScenarioManager,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+) -> Result<()> {
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
+    }
+
+    let group = services.group_service.get_group(chat_id.0).await?;
+    let lang = group.as_ref().map_or("en", |g| g.language_code.as_str());
+
+    let mut context = ConversationContext::for_chat(user_id, chat_id.0);
+    scenario_manager.start_scenario(&mut context, "group_setup")?;
+    context.next_step("welcome_message_input")?;
+    state_storage.save_context(&mut context).await?;
+
+    let mut params = HashMap::new();
+    params.insert("placeholders".to_string(), WELCOME_PLACEHOLDERS.join(", "));
+    params.insert("current".to_string(), group.as_ref()
+        .and_then(|g| g.welcome_message())
+        .map(str::to_string)
+        .unwrap_or_else(|| i18n.t("messages.group.welcome_message_none", lang, None)));
+
+    bot.send_message(chat_id, i18n.t("messages.group.welcome_message_prompt", lang, Some(&params))).await?;
+
+    Ok(())
+}
+
+/// Save the welcome message an admin sent after pressing the welcome message button
+pub async fn handle_welcome_message_input(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    let title = msg.chat.title().unwrap_or_default();
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let Some(text) = msg.text().map(str::trim) else {
+        bot.send_message(chat_id, i18n.t("messages.group.welcome_message_text_only", &lang, None)).await?;
+        return Ok(());
+    };
+
+    if text.chars().count() > WELCOME_MESSAGE_MAX_LENGTH {
+        let mut params = HashMap::new();
+        params.insert("max".to_string(), WELCOME_MESSAGE_MAX_LENGTH.to_string());
+        bot.send_message(chat_id, i18n.t("messages.group.welcome_message_too_long", &lang, Some(&params))).await?;
+        return Ok(());
+    }
+
+    let template = (text != "-").then(|| text.to_string());
+    let group = services.group_service.set_welcome_message(chat_id.0, title, template).await?;
+    state_storage.delete_chat_context(user_id, chat_id.0).await?;
+
+    let reply = match group.welcome_message() {
+        Some(template) => {
+            let mut params = HashMap::new();
+            params.insert("preview".to_string(), render_welcome_message(template, user, &group.title));
+            i18n.t("messages.group.welcome_message_saved", &lang, Some(&params))
+        }
+        None => i18n.t("messages.group.welcome_message_disabled", &lang, None),
+    };
+    bot.send_message(chat_id, reply).await?;
+    show_configuration(bot, chat_id, &services, &i18n).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, "Group welcome message configured");
+
+    Ok(())
+}
@@ -1,21 +1,46 @@
 //! Callback query handlers module
 //! 
-//! This module contains handlers for all inline keyboard button callbacks
+//! This module contains handlers for all inline keyboard button callbacks.
+//! Buttons carry a [`CallbackData`], which the dispatcher routes by its variant.
 
+pub mod captcha;
+pub mod data;
 pub mod group_setup;
+pub mod wizard;
 
-use teloxide::{Bot, types::{CallbackQuery, ChatId}, prelude::*};
-use tracing::{info, warn, error};
+pub use data::{AdminAction, CallbackData, GroupSetupAction, ScenarioAction, SongAction};
+
+use teloxide::{Bot, types::{CallbackQuery, ChatId, Me, MessageId}, prelude::*};
+use tracing::{info, debug, warn, error};
 use crate::utils::errors::Result;
 use crate::services::ServiceFactory;
 use crate::state::{ScenarioManager, StateStorage};
 use crate::i18n::I18n;
-use crate::handlers::commands::{start, events, admin};
+use crate::handlers::commands::{start, events, admin, styles, moderation, songs};
+
+/// Seconds in which the same button tapped again by the same user is ignored
+const DUPLICATE_CALLBACK_SECONDS: u64 = 3;
+
+/// What a callback handler gets besides the action: who pressed a button of
+/// which message, and the bot's services
+#[derive(Clone)]
+pub struct CallbackContext {
+    pub bot: Bot,
+    pub chat_id: ChatId,
+    /// Message the button belongs to, unless Telegram no longer has it
+    pub message_id: Option<MessageId>,
+    pub user_id: i64,
+    pub services: ServiceFactory,
+    pub scenario_manager: ScenarioManager,
+    pub state_storage: StateStorage,
+    pub i18n: I18n,
+}
 
 /// Main callback query dispatcher
 pub async fn handle_callback_query(
     bot: Bot,
     query: CallbackQuery,
+    me: Me,
     services: ServiceFactory,
     scenario_manager: ScenarioManager,
     state_storage: StateStorage,
@@ -25,176 +50,227 @@ pub async fn handle_callback_query(
     let user_id = user.id.0 as i64;
     let chat_id = query.message.as_ref().map(|m| m.chat().id);
     
-    info!(user_id = user_id, chat_id = ?chat_id, callback_data = ?query.data, "🔍 CALLBACK DISPATCHER: Processing callback query");
+    debug!(user_id = user_id, chat_id = ?chat_id, callback_data = ?query.data, "Processing callback query");
 
     if let Some(data) = query.data {
-        info!(user_id = user_id, callback_data = %data, "🔍 CALLBACK DISPATCHER: Callback data received");
+        debug!(user_id = user_id, callback_data = %data, "Callback data received");
         
         // Answer the callback query first to remove loading state
         if let Err(e) = bot.answer_callback_query(query.id.clone()).await {
-            warn!(error = %e, callback_id = %query.id, "🔍 CALLBACK DISPATCHER: Failed to answer callback query");
+            warn!(error = %e, callback_id = %query.id, "Failed to answer callback query");
         } else {
-            info!(callback_id = %query.id, "🔍 CALLBACK DISPATCHER: Callback query answered successfully");
+            debug!(callback_id = %query.id, "Callback query answered successfully");
         }
 
         // Parse callback data and route to appropriate handler
-        let parts: Vec<&str> = data.split(':').collect();
-        info!(user_id = user_id, parts = ?parts, "🔍 CALLBACK DISPATCHER: Parsed callback data");
-        
-        if parts.is_empty() {
-            warn!(data = %data, "🔍 CALLBACK DISPATCHER: Invalid callback data format");
+        let Some(callback) = CallbackData::decode(&data) else {
+            warn!(user_id = user_id, callback_data = %data, "Invalid callback data");
+            return Ok(());
+        };
+        let chat_id = chat_id.unwrap_or_else(|| ChatId(user_id));
+        let message_id = query.message.as_ref().map(|m| m.id());
+
+        // A double tap sends the same callback twice; only the first is handled
+        if is_duplicate(&services, user_id, message_id, &data).await {
+            debug!(user_id = user_id, callback_data = %data, "Duplicate callback ignored");
             return Ok(());
         }
 
-        let action = parts[0];
-        let chat_id = chat_id.unwrap_or_else(|| ChatId(user_id));
-        
-        info!(user_id = user_id, action = %action, chat_id = ?chat_id, "🔍 CALLBACK DISPATCHER: Routing callback to handler");
+        debug!(user_id = user_id, callback = ?callback, chat_id = ?chat_id, "Routing callback to handler");
 
-        match action {
-            "lang" => {
+        match callback {
+            CallbackData::Language(language_code) => {
                 // Language selection callback
-                info!(user_id = user_id, callback_data = %data, "🔍 LANG CALLBACK: Language callback received - entering handler");
-                if parts.len() >= 2 {
-                    let language_code = parts[1].to_string();
-                    info!(user_id = user_id, language_code = %language_code, "🔍 LANG CALLBACK: Dispatching to language handler");
-                    match start::handle_language_callback(
-                        bot,
-                        chat_id,
-                        user_id,
-                        language_code.clone(),
-                        services,
-                        scenario_manager,
-                        state_storage,
-                        i18n,
-                    ).await {
-                        Ok(_) => {
-                            info!(user_id = user_id, language_code = %language_code, "🔍 LANG CALLBACK: Language callback handled successfully");
-                        },
-                        Err(e) => {
-                            error!(user_id = user_id, language_code = %language_code, error = %e, "🔍 LANG CALLBACK: Language callback failed");
-                            return Err(e);
-                        }
+                debug!(user_id = user_id, language_code = %language_code, "Dispatching to language handler");
+                match start::handle_language_callback(
+                    bot,
+                    chat_id,
+                    user_id,
+                    language_code.clone(),
+                    services,
+                    scenario_manager,
+                    state_storage,
+                    i18n,
+                ).await {
+                    Ok(_) => {
+                        debug!(user_id = user_id, language_code = %language_code, "Language callback handled successfully");
+                    },
+                    Err(e) => {
+                        error!(user_id = user_id, language_code = %language_code, error = %e, "Language callback failed");
+                        return Err(e);
                     }
-                } else {
-                    warn!(user_id = user_id, callback_data = %data, "🔍 LANG CALLBACK: Invalid language callback format");
                 }
             }
-            "location" => {
-                // Location selection callback
-                info!(user_id = user_id, callback_data = %data, "🔍 LOCATION CALLBACK: Location callback received");
-                if parts.len() >= 2 {
-                    let location = parts[1].to_string();
-                    info!(user_id = user_id, location = %location, "🔍 LOCATION CALLBACK: Dispatching to location handler");
-                    match start::handle_location_callback(
-                        bot,
-                        chat_id,
-                        user_id,
-                        location.clone(),
-                        services,
-                        scenario_manager,
-                        state_storage,
-                        i18n,
-                    ).await {
-                        Ok(_) => {
-                            info!(user_id = user_id, location = %location, "🔍 LOCATION CALLBACK: Location callback handled successfully");
-                        },
-                        Err(e) => {
-                            error!(user_id = user_id, location = %location, error = %e, "🔍 LOCATION CALLBACK: Location callback failed");
-                            return Err(e);
-                        }
+            CallbackData::Location(location) => {
+                // Location selection callback; the handler takes "skip" for no city
+                let location = location.unwrap_or_else(|| "skip".to_string());
+                debug!(user_id = user_id, location = %location, "Dispatching to location handler");
+                match start::handle_location_callback(
+                    bot,
+                    chat_id,
+                    user_id,
+                    location.clone(),
+                    services,
+                    scenario_manager,
+                    state_storage,
+                    i18n,
+                ).await {
+                    Ok(_) => {
+                        debug!(user_id = user_id, location = %location, "Location callback handled successfully");
+                    },
+                    Err(e) => {
+                        error!(user_id = user_id, location = %location, error = %e, "Location callback failed");
+                        return Err(e);
                     }
-                } else {
-                    warn!(user_id = user_id, callback_data = %data, "🔍 LOCATION CALLBACK: Invalid location callback format");
                 }
             }
-            "calendar" => {
-                // Calendar selection callback
-                if parts.len() >= 2 {
-                    let calendar_type = parts[1].to_string();
-                    if calendar_type == "back" {
-                        // Show calendar list again
-                        if let Some(teloxide::types::MaybeInaccessibleMessage::Regular(message)) = query.message {
-                            events::handle_events_list(bot, *message, services, i18n).await?;
-                        }
-                    } else {
-                        events::handle_calendar_callback(
-                            bot,
-                            chat_id,
-                            user_id,
-                            calendar_type,
-                            services,
-                            i18n,
-                        ).await?;
-                    }
+            CallbackData::Scenario(action) => {
+                // Back button of a scenario step, Continue of a step reminder, or the
+                // resume prompt after an interrupting command
+                match action {
+                    ScenarioAction::Back => start::handle_back(bot, chat_id, user_id, services, scenario_manager, state_storage, i18n).await?,
+                    ScenarioAction::Continue => start::handle_continue(bot, chat_id, user_id, services, scenario_manager, state_storage, i18n).await?,
+                    ScenarioAction::Resume => start::handle_resume(bot, chat_id, user_id, services, scenario_manager, state_storage, i18n).await?,
+                    ScenarioAction::Discard => start::handle_discard(bot, chat_id, user_id, services, state_storage, i18n).await?,
                 }
             }
-            "event_register" => {
-                // Event registration callback
-                if parts.len() >= 2 {
-                    if let Ok(event_id) = parts[1].parse::<i64>() {
-                        events::handle_event_register_callback(
-                            bot,
-                            chat_id,
-                            user_id,
-                            event_id,
-                            services,
-                            i18n,
-                        ).await?;
-                    }
+            CallbackData::Calendar(None) => {
+                // Show calendar list again
+                if let Some(teloxide::types::MaybeInaccessibleMessage::Regular(message)) = query.message {
+                    events::handle_events_list(bot, *message, me, services, i18n).await?;
                 }
             }
-            "event_unregister" => {
-                // Event unregistration callback
-                if parts.len() >= 2 {
-                    if let Ok(event_id) = parts[1].parse::<i64>() {
-                        events::handle_event_unregister_callback(
-                            bot,
-                            chat_id,
-                            user_id,
-                            event_id,
-                            services,
-                            i18n,
-                        ).await?;
-                    }
-                }
+            CallbackData::Calendar(Some(calendar_id)) => {
+                events::handle_calendar_callback(
+                    bot,
+                    chat_id,
+                    user_id,
+                    calendar_id,
+                    services,
+                    i18n,
+                ).await?;
             }
-            "admin" => {
-                // Admin panel callback
-                if parts.len() >= 2 {
-                    let admin_action = parts[1].to_string();
-                    admin::handle_admin_callback(
-                        bot,
-                        chat_id,
-                        user_id,
-                        admin_action,
-                        services,
-                        scenario_manager,
-                        state_storage,
-                        i18n,
-                    ).await?;
-                }
+            CallbackData::EventRegister(event_id) => {
+                events::handle_event_register_callback(
+                    bot,
+                    chat_id,
+                    user_id,
+                    event_id,
+                    services,
+                    i18n,
+                ).await?;
             }
-            "group_setup" => {
-                // Group setup callback
-                if parts.len() >= 2 {
-                    let setup_action = parts[1].to_string();
-                    group_setup::handle_group_setup_callback(
-                        bot,
-                        chat_id,
-                        user_id,
-                        setup_action,
-                        services,
-                        i18n,
-                    ).await?;
-                }
+            CallbackData::EventUnregister(event_id) => {
+                events::handle_event_unregister_callback(
+                    bot,
+                    chat_id,
+                    user_id,
+                    event_id,
+                    services,
+                    i18n,
+                ).await?;
+            }
+            CallbackData::Captcha { user_id: target_user_id, answer } => {
+                captcha::handle_captcha_callback(
+                    bot,
+                    &me,
+                    chat_id,
+                    &user,
+                    target_user_id,
+                    answer.to_string(),
+                    services,
+                    i18n,
+                ).await?;
+            }
+            CallbackData::ResolveReport(report_id) => {
+                moderation::handle_report_callback(
+                    bot,
+                    chat_id,
+                    message_id,
+                    &user,
+                    report_id,
+                    services,
+                    i18n,
+                ).await?;
+            }
+            CallbackData::Style(style) => {
+                styles::handle_style_callback(
+                    bot,
+                    chat_id,
+                    message_id,
+                    user_id,
+                    style,
+                    services,
+                    i18n,
+                ).await?;
             }
-            _ => {
-                warn!(action = %action, "Unknown callback action");
+            CallbackData::Song(action) => {
+                songs::handle_song_callback(
+                    bot,
+                    chat_id,
+                    message_id,
+                    user_id,
+                    action,
+                    services,
+                    i18n,
+                ).await?;
+            }
+            CallbackData::Admin(admin_action) => {
+                let context = CallbackContext {
+                    bot,
+                    chat_id,
+                    message_id,
+                    user_id,
+                    services,
+                    scenario_manager,
+                    state_storage,
+                    i18n,
+                };
+                admin::handle_admin_callback(context, admin_action).await?;
+            }
+            CallbackData::GroupSetup(setup_action) => {
+                let context = CallbackContext {
+                    bot,
+                    chat_id,
+                    message_id,
+                    user_id,
+                    services,
+                    scenario_manager,
+                    state_storage,
+                    i18n,
+                };
+                group_setup::handle_group_setup_callback(context, setup_action).await?;
+            }
+            CallbackData::Wizard { scenario, action } => {
+                wizard::handle_wizard_callback(
+                    bot,
+                    chat_id,
+                    message_id,
+                    user_id,
+                    scenario,
+                    action,
+                    services,
+                    scenario_manager,
+                    state_storage,
+                    i18n,
+                ).await?;
             }
         }
     }
 
     info!(user_id = user_id, "Callback query processed successfully");
     Ok(())
+}
+
+/// Whether the user tapped the same button of the same message moments ago.
+/// While Redis is unavailable every callback is handled.
+async fn is_duplicate(services: &ServiceFactory, user_id: i64, message_id: Option<MessageId>, data: &str) -> bool {
+    let key = format!("callback:{}:{}:{}", user_id, message_id.map_or(0, |id| id.0), data);
+    match services.redis_service.set_if_absent(&key, DUPLICATE_CALLBACK_SECONDS).await {
+        Ok(first) => !first,
+        Err(e) => {
+            warn!(user_id = user_id, error = %e, "Callback deduplication unavailable");
+            false
+        }
+    }
 }
\ No newline at end of file
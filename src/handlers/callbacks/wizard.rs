@@ -0,0 +1,219 @@
+//! Inline-keyboard wizard handlers
+//!
+//! Sends the steps of wizard scenarios, handles their buttons, typed answers
+//! and files, and hands finished wizards over to their scenario's handler.
+//! A button press replaces the message it belongs to with the next step.
+
+use std::collections::HashMap;
+use teloxide::{Bot, types::{ChatId, InlineKeyboardButton, InlineKeyboardMarkup, Message, MessageId}, prelude::*};
+use tracing::{debug, warn};
+use crate::utils::errors::Result;
+use crate::services::ServiceFactory;
+use crate::state::{ConversationContext, ContextScope, MediaItem, MediaKind, ScenarioManager, StateStorage, Wizard, WizardAction, WizardOutcome};
+use crate::i18n::I18n;
+use crate::handlers::commands::events;
+
+/// Scenarios run as wizards
+const WIZARD_SCENARIOS: &[&str] = &["event_creation"];
+
+/// Whether a scenario is run as a wizard
+pub fn is_wizard(scenario: &str) -> bool {
+    WIZARD_SCENARIOS.contains(&scenario)
+}
+
+/// Start a wizard for a user in a chat and send its first step. In groups the
+/// wizard is the user's conversation in that group only.
+#[allow(clippy::too_many_arguments)]
+pub async fn start_wizard(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    scenario_id: &str,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let mut context = if chat_id.is_user() {
+        ConversationContext::new(user_id)
+    } else {
+        ConversationContext::for_chat(user_id, chat_id.0)
+    };
+    let outcome = Wizard::new(&scenario_manager).start(&mut context, scenario_id)?;
+    debug!(user_id = user_id, scenario = %scenario_id, "Wizard started");
+
+    let language_code = user_language(&services, &i18n, user_id).await?;
+    proceed(bot, chat_id, None, context, outcome, &services, &scenario_manager, &state_storage, &i18n, &language_code).await
+}
+
+/// Handle a wizard button of a scenario
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_wizard_callback(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    user_id: i64,
+    scenario: String,
+    action: WizardAction,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let language_code = user_language(&services, &i18n, user_id).await?;
+
+    // Buttons of a wizard the user already left do nothing
+    let context = state_storage.load_scoped_context(wizard_scope(chat_id, user_id)).await?
+        .filter(|context| context.is_in_scenario(&scenario));
+    let Some(mut context) = context else {
+        bot.send_message(chat_id, i18n.t("messages.errors.session_expired", &language_code, None)).await?;
+        return Ok(());
+    };
+
+    let outcome = Wizard::new(&scenario_manager).act(&mut context, &action)?;
+    debug!(user_id = user_id, scenario = %scenario, action = ?action, outcome = ?outcome, "Wizard button pressed");
+    proceed(bot, chat_id, message_id, context, outcome, &services, &scenario_manager, &state_storage, &i18n, &language_code).await
+}
+
+/// Handle a typed answer or a file sent to the step a wizard is at
+pub async fn handle_wizard_input(
+    bot: Bot,
+    msg: Message,
+    mut context: ConversationContext,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let language_code = user_language(&services, &i18n, context.user_id).await?;
+    let wizard = Wizard::new(&scenario_manager);
+    let outcome = if let Some(text) = msg.text() {
+        wizard.answer(&mut context, text)?
+    } else if let Some(item) = media_item(&msg) {
+        wizard.add_media(&mut context, item)?
+    } else {
+        return show_wizard_step(bot, msg.chat.id, None, &context, &scenario_manager, &i18n, &language_code).await;
+    };
+    proceed(bot, msg.chat.id, None, context, outcome, &services, &scenario_manager, &state_storage, &i18n, &language_code).await
+}
+
+/// Send the step a wizard is at, or replace `message_id` with it
+pub async fn show_wizard_step(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    context: &ConversationContext,
+    scenario_manager: &ScenarioManager,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let screen = Wizard::new(scenario_manager).render(context, i18n, language_code)?;
+    let keyboard = wizard_keyboard(screen.keyboard);
+
+    match message_id {
+        Some(message_id) => {
+            bot.edit_message_text(chat_id, message_id, screen.text).reply_markup(keyboard).await?;
+        }
+        None => {
+            bot.send_message(chat_id, screen.text).reply_markup(keyboard).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Act on where the wizard is now
+#[allow(clippy::too_many_arguments)]
+async fn proceed(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    mut context: ConversationContext,
+    outcome: WizardOutcome,
+    services: &ServiceFactory,
+    scenario_manager: &ScenarioManager,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    match outcome {
+        WizardOutcome::Next => {
+            state_storage.save_context(&mut context).await?;
+            show_wizard_step(bot, chat_id, message_id, &context, scenario_manager, i18n, language_code).await
+        }
+        WizardOutcome::Collected(count) => {
+            state_storage.save_context(&mut context).await?;
+            let screen = Wizard::new(scenario_manager).render(&context, i18n, language_code)?;
+            let mut params = HashMap::new();
+            params.insert("count".to_string(), count.to_string());
+            bot.send_message(chat_id, i18n.t("messages.wizard.file_added", language_code, Some(&params)))
+                .reply_markup(wizard_keyboard(screen.keyboard))
+                .await?;
+            Ok(())
+        }
+        WizardOutcome::Invalid(reason) => {
+            bot.send_message(chat_id, i18n.t(&reason, language_code, None)).await?;
+            Ok(())
+        }
+        WizardOutcome::Cancelled => {
+            state_storage.delete_scoped_context(context.scope()).await?;
+            send_or_edit(bot, chat_id, message_id, i18n.t("messages.wizard.cancelled", language_code, None)).await
+        }
+        WizardOutcome::Finished(step) => {
+            // Saving first rejects a second press of the same button
+            state_storage.save_context(&mut context).await?;
+            if let Some(message_id) = message_id {
+                if let Err(e) = bot.edit_message_reply_markup(chat_id, message_id).await {
+                    debug!(error = %e, "Failed to remove wizard keyboard");
+                }
+            }
+
+            let scenario = context.scenario.clone().unwrap_or_default();
+            let result = match scenario.as_str() {
+                "event_creation" => events::finish_event_creation(&bot, chat_id, &context, &step, services, i18n, language_code).await,
+                _ => {
+                    warn!(scenario = %scenario, step = %step, "Wizard finished without a handler");
+                    Ok(())
+                }
+            };
+            state_storage.delete_scoped_context(context.scope()).await?;
+            result
+        }
+    }
+}
+
+async fn send_or_edit(bot: Bot, chat_id: ChatId, message_id: Option<MessageId>, text: String) -> Result<()> {
+    match message_id {
+        Some(message_id) => {
+            bot.edit_message_text(chat_id, message_id, text).await?;
+        }
+        None => {
+            bot.send_message(chat_id, text).await?;
+        }
+    }
+    Ok(())
+}
+
+fn wizard_keyboard(rows: Vec<Vec<(String, String)>>) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(rows.into_iter().map(|row| {
+        row.into_iter().map(|(label, data)| InlineKeyboardButton::callback(label, data)).collect::<Vec<_>>()
+    }))
+}
+
+/// File a message carries for a media step: the largest size of a photo, or a document
+fn media_item(msg: &Message) -> Option<MediaItem> {
+    if let Some(photo) = msg.photo().and_then(|sizes| sizes.last()) {
+        return Some(MediaItem { kind: MediaKind::Photo, file_id: photo.file.id.to_string() });
+    }
+    msg.document().map(|document| MediaItem { kind: MediaKind::Document, file_id: document.file.id.to_string() })
+}
+
+/// Context scope of a user's wizard in a chat
+fn wizard_scope(chat_id: ChatId, user_id: i64) -> ContextScope {
+    (user_id, (!chat_id.is_user()).then_some(chat_id.0))
+}
+
+async fn user_language(services: &ServiceFactory, i18n: &I18n, user_id: i64) -> Result<String> {
+    Ok(services.user_service.get_user_by_telegram_id(user_id).await?
+        .map(|user| user.language_code)
+        .unwrap_or_else(|| i18n.default_language().to_string()))
+}
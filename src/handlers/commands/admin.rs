@@ -1,13 +1,41 @@
 //! Admin command handlers
 
 use std::collections::HashMap;
-use teloxide::{Bot, types::{Message, InlineKeyboardMarkup, InlineKeyboardButton, ChatId}, prelude::*};
-use tracing::{info, debug, warn};
+use teloxide::{Bot, ApiError, RequestError, net::Download, types::{Message, MessageId, InlineKeyboardMarkup, InlineKeyboardButton, ChatId, ChatAction, InputFile, ParseMode, UserId}, prelude::*, utils::{html, render::RenderMessageTextHelper}};
+use tracing::{info, debug, warn, error};
 use crate::utils::errors::Result;
-use crate::services::ServiceFactory;
+use crate::services::{NotificationService, ServiceFactory};
 use crate::state::{ScenarioManager, StateStorage, ConversationContext};
+use crate::models::broadcast::{BroadcastSegment, CreateBroadcastRequest};
+use crate::models::calendar::CalendarField;
+use crate::models::scheduled_message::{CreateScheduledMessageRequest, ScheduledMessage, ScheduledTarget};
+use crate::models::group::GroupFeature;
+use crate::models::activity::{ActivityCategory, ActivityLogEntry};
+use crate::models::moderation::{BanListEntry, SpamFilterKind};
+use crate::models::stats::GrowthInterval;
+use crate::models::trash::{TrashEntry, TrashKind, TRASH_PAGE_SIZE};
+use crate::models::user::{ProfileField, UserListEntry, UserListSort};
+use crate::services::stats::{sparkline, GROWTH_PERIODS, STATS_PERIOD_DAYS};
+use crate::services::calendar::{validate_calendar_description, validate_calendar_name, validate_google_calendar_id};
+use crate::services::broadcast::{has_placeholders, render_broadcast};
+use crate::services::feature_flags::FeatureFlag;
+use crate::services::scheduled_message::{parse_send_at, SEND_AT_FORMAT};
+use crate::services::user::MAX_IMPORT_BYTES;
+use crate::handlers::callbacks::group_setup::format_bot_permissions;
+use crate::handlers::callbacks::wizard;
+use crate::handlers::callbacks::{AdminAction, CallbackContext, CallbackData};
+use crate::handlers::callbacks::data::MAX_CALLBACK_DATA_LEN;
 use crate::i18n::I18n;
 
+/// Most cities or events offered when narrowing a broadcast down
+const MAX_BROADCAST_CHOICES: i64 = 10;
+
+/// Most skipped line numbers listed after a user import
+const MAX_REPORTED_LINES: usize = 20;
+
+/// Most added or removed keys listed per language after reloading translations
+const MAX_REPORTED_KEYS: usize = 20;
+
 /// Handle /admin command - show admin panel
 pub async fn handle_admin_panel(
     bot: Bot,
@@ -43,68 +71,117 @@ pub async fn handle_admin_panel(
     // Start admin panel scenario
     let mut context = ConversationContext::new(user_id);
     scenario_manager.start_scenario(&mut context, "admin_panel")?;
-    state_storage.save_context(&context).await?;
+    state_storage.save_context(&mut context).await?;
 
     // Show admin main menu
-    show_admin_main_menu(bot, chat_id, &i18n, &user_lang).await?;
+    show_admin_main_menu(bot, chat_id, None, &i18n, &user_lang).await?;
 
     info!(user_id = user_id, "Admin accessed admin panel");
 
     Ok(())
 }
 
+/// Show an admin panel screen in place of the panel message that was clicked,
+/// or in a new message. When the old message can't be edited any more, e.g. a
+/// backup document or a message deleted meanwhile, the screen is sent anew.
+async fn show_panel(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    text: String,
+    keyboard: InlineKeyboardMarkup,
+    parse_mode: Option<ParseMode>,
+) -> Result<()> {
+    if let Some(message_id) = message_id {
+        let mut edit = bot.edit_message_text(chat_id, message_id, text.clone())
+            .reply_markup(keyboard.clone());
+        if let Some(parse_mode) = parse_mode {
+            edit = edit.parse_mode(parse_mode);
+        }
+        match edit.await {
+            // Clicking the button of the screen already shown changes nothing
+            Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => return Ok(()),
+            Err(RequestError::Api(e)) => {
+                debug!(chat_id = ?chat_id, message_id = message_id.0, error = %e, "Admin panel not edited, sending it anew");
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let mut send = bot.send_message(chat_id, text).reply_markup(keyboard);
+    if let Some(parse_mode) = parse_mode {
+        send = send.parse_mode(parse_mode);
+    }
+    send.await?;
+    Ok(())
+}
+
 /// Show admin main menu
-async fn show_admin_main_menu(bot: Bot, chat_id: ChatId, i18n: &I18n, language_code: &str) -> Result<()> {
+async fn show_admin_main_menu(bot: Bot, chat_id: ChatId, message_id: Option<MessageId>, i18n: &I18n, language_code: &str) -> Result<()> {
     let title_text = i18n.t("commands.admin.panel_title", language_code, None);
     
     let keyboard = InlineKeyboardMarkup::new(vec![
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("commands.admin.user_management", language_code, None),
-                "admin:users"
+                CallbackData::Admin(AdminAction::Users)
             ),
             InlineKeyboardButton::callback(
                 i18n.t("commands.admin.group_management", language_code, None),
-                "admin:groups"
+                CallbackData::Admin(AdminAction::Groups)
             ),
         ],
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("commands.admin.event_management", language_code, None),
-                "admin:events"
+                CallbackData::Admin(AdminAction::Events)
             ),
             InlineKeyboardButton::callback(
                 i18n.t("commands.admin.statistics", language_code, None),
-                "admin:stats"
+                CallbackData::Admin(AdminAction::Stats(GrowthInterval::Day))
             ),
         ],
         vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.broadcast", language_code, None),
+                CallbackData::Admin(AdminAction::Broadcast)
+            ),
             InlineKeyboardButton::callback(
                 i18n.t("commands.admin.system_settings", language_code, None),
-                "admin:settings"
+                CallbackData::Admin(AdminAction::Settings)
+            ),
+        ],
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.scheduled", language_code, None),
+                CallbackData::Admin(AdminAction::Scheduled)
+            ),
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.audit_log", language_code, None),
+                CallbackData::Admin(AdminAction::Audit(0))
+            ),
+        ],
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.activity_log", language_code, None),
+                CallbackData::Admin(AdminAction::Activity { category: None, page: 0 })
+            ),
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.trash", language_code, None),
+                CallbackData::Admin(AdminAction::Trash { kind: TrashKind::Users, page: 0 })
             ),
         ],
     ]);
     
-    bot.send_message(chat_id, title_text)
-        .reply_markup(keyboard)
-        .await?;
+    show_panel(&bot, chat_id, message_id, title_text, keyboard, None).await?;
     
     Ok(())
 }
 
 /// Handle admin panel callback
-pub async fn handle_admin_callback(
-    bot: Bot,
-    chat_id: ChatId,
-    user_id: i64,
-    action: String,
-    services: ServiceFactory,
-    _scenario_manager: ScenarioManager,
-    _state_storage: StateStorage,
-    i18n: I18n,
-) -> Result<()> {
-    debug!(user_id = user_id, action = %action, "Admin panel action");
+pub async fn handle_admin_callback(context: CallbackContext, action: AdminAction) -> Result<()> {
+    let CallbackContext { bot, chat_id, message_id, user_id, services, scenario_manager, state_storage, i18n } = context;
+    debug!(user_id = user_id, action = ?action, "Admin panel action");
 
     // Verify admin access
     if !services.auth_service.can_access_admin_panel(user_id).await? {
@@ -120,18 +197,353 @@ pub async fn handle_admin_callback(
         "en".to_string()
     };
 
-    match action.as_str() {
-        "users" => show_user_management(bot, chat_id, &services, &i18n, &user_lang).await?,
-        "groups" => show_group_management(bot, chat_id, &services, &i18n, &user_lang).await?,
-        "events" => show_event_management(bot, chat_id, &services, &i18n, &user_lang).await?,
-        "stats" => show_statistics(bot, chat_id, &services, &i18n, &user_lang).await?,
-        "settings" => show_system_settings(bot, chat_id, &services, &i18n, &user_lang).await?,
-        "back" => show_admin_main_menu(bot, chat_id, &i18n, &user_lang).await?,
+    // Paging through the audit trail, activity log and trash is not recorded
+    if !matches!(action, AdminAction::Audit(_) | AdminAction::Activity { .. } | AdminAction::Trash { .. }) {
+        services.admin_audit_service.record(user_id, action.name(), action.arg().as_deref()).await;
+    }
+
+    if let Some(step) = admin_panel_step(&action) {
+        track_admin_panel_step(user_id, step, &scenario_manager, &state_storage).await?;
+    }
+
+    match action {
+        AdminAction::Users => show_user_management(bot, chat_id, message_id, &services, &i18n, &user_lang).await?,
+        AdminAction::UserList { sort, page } => {
+            show_user_list(bot, chat_id, message_id, sort, page, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::UserEdit { telegram_id: target_id, field } => {
+            // The corrected value is asked for next
+            let mut context = ConversationContext::new(user_id);
+            scenario_manager.start_scenario(&mut context, "user_profile_edit")?;
+            context.set_data("target_id", target_id)?;
+            context.set_data("field", field.as_str())?;
+            state_storage.save_context(&mut context).await?;
+
+            let mut params = HashMap::new();
+            params.insert("languages".to_string(), i18n.supported_languages().join(", "));
+            let prompt_key = format!("commands.admin.edit_profile.prompt_{}", field.as_str());
+            bot.send_message(chat_id, i18n.t(&prompt_key, &user_lang, Some(&params))).await?;
+        }
+        AdminAction::BanUser | AdminAction::UnbanUser => {
+            let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+                i18n.t("buttons.navigation.back", &user_lang, None),
+                CallbackData::Admin(AdminAction::Users),
+            )]]);
+            let text = i18n.t(&format!("commands.admin.{}_prompt", action.name()), &user_lang, None);
+            show_panel(&bot, chat_id, message_id, text, keyboard, None).await?;
+        }
+        AdminAction::BanList(page) => {
+            show_ban_list(bot, chat_id, message_id, page, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::BanLift { chat_id: ban_chat_id, telegram_id: target_id, page } => {
+            let lifted = match ban_chat_id {
+                None => services.user_service.set_user_ban_status(target_id, false, user_id).await.map(|_| ()),
+                Some(ban_chat_id) => services.moderation_service.unban(ChatId(ban_chat_id), UserId(target_id as u64), user_id, true).await.map(|_| ()),
+            };
+            let key = match lifted {
+                Ok(()) => "commands.admin.ban_list.unbanned",
+                Err(e) => {
+                    warn!(user_id = user_id, target_id = target_id, chat_id = ?ban_chat_id, error = %e, "Failed to lift ban from the ban list");
+                    "commands.admin.ban_list.unban_failed"
+                }
+            };
+            bot.send_message(chat_id, i18n.t(key, &user_lang, None)).await?;
+            show_ban_list(bot, chat_id, message_id, page, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::Groups => show_group_management(bot, chat_id, message_id, &services, &i18n, &user_lang).await?,
+        AdminAction::Events => show_event_management(bot, chat_id, message_id, &services, &i18n, &user_lang).await?,
+        AdminAction::ClearState { telegram_id: target_id, chat_id: context_chat_id } => {
+            clear_state(bot, chat_id, user_id, target_id, context_chat_id, &state_storage, &i18n, &user_lang).await?;
+        }
+        AdminAction::CreateEvent => {
+            wizard::start_wizard(bot, chat_id, user_id, "event_creation", services, scenario_manager, state_storage, i18n).await?;
+        }
+        AdminAction::Stats(interval) => {
+            show_statistics(bot, chat_id, message_id, interval, &services, &state_storage, &i18n, &user_lang).await?;
+        }
+        AdminAction::Settings => show_system_settings(bot, chat_id, message_id, &services, &i18n, &user_lang).await?,
+        AdminAction::Backup => send_backup(bot, chat_id, &services, &i18n, &user_lang).await?,
+        AdminAction::ExportUsers => send_user_export(bot, chat_id, &services, &i18n, &user_lang).await?,
+        AdminAction::ImportUsers => {
+            // The CSV file is asked for next
+            let mut context = ConversationContext::new(user_id);
+            scenario_manager.start_scenario(&mut context, "user_import")?;
+            state_storage.save_context(&mut context).await?;
+
+            bot.send_message(chat_id, i18n.t("commands.admin.import_users.prompt", &user_lang, None)).await?;
+        }
+        AdminAction::Features => show_feature_flags(bot, chat_id, message_id, &services, &i18n, &user_lang).await?,
+        AdminAction::FeatureToggle(flag) => {
+            let enabled = !services.feature_flag_service.is_enabled(flag);
+            // admin_settings.updated_by refers to the internal user ID
+            let updated_by = services.user_service.get_user_by_telegram_id(user_id).await?.map(|user| user.id);
+            services.feature_flag_service.set_enabled(flag, enabled, updated_by).await?;
+            info!(admin_id = user_id, flag = flag.as_str(), enabled = enabled, "Feature flag changed by admin");
+            show_feature_flags(bot, chat_id, message_id, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::Back => show_admin_main_menu(bot, chat_id, message_id, &i18n, &user_lang).await?,
+        AdminAction::Audit(page) => {
+            show_audit_log(bot, chat_id, message_id, page, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::Activity { category, page } => {
+            show_activity_log(bot, chat_id, message_id, category, page, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::Trash { kind, page } => {
+            show_trash(bot, chat_id, message_id, kind, page, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::Delete { kind, id } => {
+            confirm_delete(bot, chat_id, kind, id, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::DeleteConfirm { kind, id } => {
+            // Users and groups by Telegram ID, events by their ID
+            let deleted = match kind {
+                TrashKind::Users => services.user_service.delete_user(id, user_id).await?,
+                TrashKind::Groups => services.group_service.delete_group(id).await?,
+                TrashKind::Events => services.event_service.delete_event(id, user_id).await?,
+            };
+            let key = if deleted { "commands.admin.trash.deleted" } else { "commands.admin.trash.not_found" };
+            bot.send_message(chat_id, i18n.t(key, &user_lang, None)).await?;
+        }
+        AdminAction::Restore { kind, id, page } => {
+            let restored = match kind {
+                TrashKind::Users => services.user_service.restore_user(id, user_id).await?
+                    .map(|user| user.first_name.or(user.username).unwrap_or_else(|| user.telegram_id.to_string())),
+                TrashKind::Groups => services.group_service.restore_group(id).await?.map(|group| group.title),
+                TrashKind::Events => services.event_service.restore_event(id, user_id).await?.map(|event| event.title),
+            };
+            let text = match restored {
+                Some(name) => {
+                    let mut params = HashMap::new();
+                    params.insert("name".to_string(), name);
+                    i18n.t("commands.admin.trash.restored", &user_lang, Some(&params))
+                }
+                None => i18n.t("commands.admin.trash.not_found", &user_lang, None),
+            };
+            bot.send_message(chat_id, text).await?;
+            show_trash(bot, chat_id, message_id, kind, page, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::GroupSettings => {
+            show_group_picker(bot, chat_id, message_id, AdminAction::GroupFeatures, "commands.admin.group_settings", &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::GroupFeatures(group_chat_id) => {
+            show_group_features(bot, chat_id, message_id, ChatId(group_chat_id), &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::GroupToggle { chat_id: group_chat_id, feature } => {
+            if let Some(group) = services.group_service.get_group(group_chat_id).await? {
+                let enabled = !group.feature_enabled(feature);
+                services.group_service.set_feature(group_chat_id, feature, enabled).await?;
+                info!(admin_id = user_id, group_chat_id = group_chat_id, feature = feature.as_str(), enabled = enabled, "Group feature changed by admin");
+            }
+            show_group_features(bot, chat_id, message_id, ChatId(group_chat_id), &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::SpamFilters => {
+            show_group_picker(bot, chat_id, message_id, AdminAction::SpamGroup, "commands.admin.spam_filters", &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::SpamGroup(group_chat_id) => {
+            show_spam_filters(bot, chat_id, message_id, ChatId(group_chat_id), &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::SpamAdd { chat_id: group_chat_id, kind } => {
+            // The pattern is asked for in the next message
+            let mut context = ConversationContext::new(user_id);
+            scenario_manager.start_scenario(&mut context, "spam_filter")?;
+            context.set_data("chat_id", group_chat_id)?;
+            context.set_data("kind", kind.as_str())?;
+            state_storage.save_context(&mut context).await?;
+
+            let prompt_key = format!("commands.admin.spam_filters.prompt_{}", kind.as_str());
+            bot.send_message(chat_id, i18n.t(&prompt_key, &user_lang, None)).await?;
+        }
+        AdminAction::SpamDelete(filter_id) => {
+            if let Some(filter) = services.moderation_service.remove_spam_filter(filter_id).await? {
+                info!(admin_id = user_id, filter_id = filter_id, "Spam filter removed by admin");
+                show_spam_filters(bot, chat_id, message_id, ChatId(filter.chat_telegram_id), &services, &i18n, &user_lang).await?;
+            }
+        }
+        AdminAction::SpamWarn(group_chat_id) => {
+            let enabled = services.group_service.get_group(group_chat_id).await?
+                .is_some_and(|group| !group.spam_filter_warns());
+            services.group_service.set_spam_filter_warn(group_chat_id, enabled).await?;
+            show_spam_filters(bot, chat_id, message_id, ChatId(group_chat_id), &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::Broadcast => {
+            // The message to broadcast is asked for next
+            let mut context = ConversationContext::new(user_id);
+            scenario_manager.start_scenario(&mut context, "broadcast")?;
+            state_storage.save_context(&mut context).await?;
+
+            bot.send_message(chat_id, i18n.t("commands.admin.broadcast.prompt", &user_lang, None)).await?;
+        }
+        AdminAction::BroadcastSegments => show_broadcast_segments(bot, chat_id, message_id, &services, &i18n, &user_lang).await?,
+        AdminAction::BroadcastCities | AdminAction::BroadcastLanguages | AdminAction::BroadcastEvents => {
+            show_broadcast_segment_choices(bot, chat_id, message_id, &action, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::BroadcastSegment(segment) => {
+            show_broadcast_preview(bot, chat_id, user_id, segment, &services, &state_storage, &i18n, &user_lang).await?;
+        }
+        AdminAction::BroadcastSend => send_broadcast(bot, chat_id, user_id, &services, &state_storage, &i18n, &user_lang).await?,
+        AdminAction::BroadcastCancel => {
+            if state_storage.load_context(user_id).await?.is_some_and(|context| context.is_in_scenario("broadcast")) {
+                state_storage.delete_context(user_id).await?;
+            }
+            bot.send_message(chat_id, i18n.t("commands.admin.broadcast.cancelled", &user_lang, None)).await?;
+        }
+        AdminAction::BroadcastSchedule => {
+            // The send time is asked for next
+            match broadcast_draft(user_id, &state_storage).await? {
+                Some(mut context) if context.get_string("segment").is_some() => {
+                    if context.is_at_step("segment_select") {
+                        scenario_manager.next_step(&mut context, "schedule_input")?;
+                        state_storage.save_context(&mut context).await?;
+                    }
+                    bot.send_message(chat_id, schedule_prompt(&i18n, &user_lang)).await?;
+                }
+                _ => {
+                    bot.send_message(chat_id, i18n.t("commands.admin.broadcast.expired", &user_lang, None)).await?;
+                }
+            }
+        }
+        AdminAction::Announce => {
+            show_group_picker(bot, chat_id, message_id, AdminAction::AnnounceGroup, "commands.admin.announcement", &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::AnnounceGroup(group_chat_id) => {
+            // The announcement is asked for in the next message
+            let mut context = ConversationContext::new(user_id);
+            scenario_manager.start_scenario(&mut context, "announcement")?;
+            context.set_data("chat_id", group_chat_id)?;
+            state_storage.save_context(&mut context).await?;
+
+            bot.send_message(chat_id, i18n.t("commands.admin.announcement.prompt", &user_lang, None)).await?;
+        }
+        AdminAction::Scheduled => show_scheduled_messages(bot, chat_id, message_id, &services, &i18n, &user_lang).await?,
+        AdminAction::Calendars
+        | AdminAction::Calendar(_)
+        | AdminAction::CalendarAdd
+        | AdminAction::CalendarEdit { .. }
+        | AdminAction::CalendarDelete(_)
+        | AdminAction::CalendarDeleteConfirm(_) => {
+            handle_calendar_management(bot, chat_id, message_id, user_id, action, &services, &scenario_manager, &state_storage, &i18n, &user_lang).await?;
+        }
+        AdminAction::Templates | AdminAction::Template(_) | AdminAction::TemplateEdit { .. } | AdminAction::TemplateReset { .. } => {
+            handle_template_management(bot, chat_id, message_id, user_id, action, &services, &scenario_manager, &state_storage, &i18n, &user_lang).await?;
+        }
+        AdminAction::Google | AdminAction::GoogleConnect | AdminAction::GoogleDisconnect => {
+            handle_google_account(bot, chat_id, message_id, user_id, action, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::ScheduledCancel(scheduled_id) => {
+            if !services.scheduled_message_service.cancel(scheduled_id).await? {
+                bot.send_message(chat_id, i18n.t("commands.admin.scheduled.not_pending", &user_lang, None)).await?;
+            }
+            show_scheduled_messages(bot, chat_id, message_id, &services, &i18n, &user_lang).await?;
+        }
+        AdminAction::ListGroups | AdminAction::ListEvents | AdminAction::Cache => {
+            warn!(user_id = user_id, action = action.name(), "Admin action not available");
+        }
+    }
+
+    Ok(())
+}
+
+/// Step of the admin_panel scenario an admin panel action leads to
+fn admin_panel_step(action: &AdminAction) -> Option<&'static str> {
+    match action {
+        AdminAction::Back => Some("main_menu"),
+        AdminAction::Users => Some("user_management"),
+        AdminAction::Groups => Some("group_management"),
+        AdminAction::Events => Some("event_management"),
+        AdminAction::Settings => Some("system_settings"),
+        AdminAction::Stats(_) => Some("statistics"),
+        AdminAction::BanUser => Some("ban_user_input"),
+        AdminAction::UnbanUser => Some("unban_user_input"),
+        _ => None,
+    }
+}
+
+/// Keep the stored admin_panel conversation in step with the menus. Steps waiting
+/// for text take over any other conversation; menus leave other flows alone.
+async fn track_admin_panel_step(
+    user_id: i64,
+    step: &str,
+    scenario_manager: &ScenarioManager,
+    state_storage: &StateStorage,
+) -> Result<()> {
+    let awaits_text = step.ends_with("_input");
+    let mut context = match state_storage.load_context(user_id).await? {
+        Some(context) if context.scenario.as_deref() == Some("admin_panel") => context,
+        Some(context) if context.scenario.is_some() && !awaits_text => return Ok(()),
         _ => {
-            warn!(user_id = user_id, action = %action, "Unknown admin action");
+            let mut context = ConversationContext::new(user_id);
+            scenario_manager.start_scenario(&mut context, "admin_panel")?;
+            context
         }
+    };
+
+    scenario_manager.go_to_step(&mut context, step)?;
+    state_storage.save_context(&mut context).await
+}
+
+/// Handle the Telegram ID or @username sent after "Ban user" or "Unban user"
+pub async fn handle_ban_user_input(
+    bot: Bot,
+    msg: Message,
+    context: ConversationContext,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+    let ban = context.step.as_deref() == Some("ban_user_input");
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
     }
 
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    // Wrong input keeps the step, so the admin can simply try again
+    let input = msg.text().unwrap_or_default().trim();
+    let target = match input.strip_prefix('@') {
+        Some(username) => services.user_service.get_user_by_username(username).await?,
+        None => match input.parse::<i64>() {
+            Ok(telegram_id) => services.user_service.get_user_by_telegram_id(telegram_id).await?,
+            Err(_) => {
+                bot.send_message(chat_id, i18n.t("commands.admin.ban_user_invalid", &user_lang, None)).await?;
+                return Ok(());
+            }
+        },
+    };
+    let Some(target) = target else {
+        bot.send_message(chat_id, i18n.t("commands.admin.user_not_found", &user_lang, None)).await?;
+        return Ok(());
+    };
+
+    services.user_service.set_user_ban_status(target.telegram_id, ban, user_id).await?;
+    let action = if ban { "user_banned" } else { "user_unbanned" };
+    services.admin_audit_service.record(user_id, action, Some(&target.telegram_id.to_string())).await;
+
+    let mut context = context;
+    scenario_manager.next_step(&mut context, "user_management")?;
+    state_storage.save_context(&mut context).await?;
+
+    let mut params = HashMap::new();
+    params.insert("user_name".to_string(), target.username.as_ref().map(|u| format!("@{}", u))
+        .or(target.first_name.clone())
+        .unwrap_or_else(|| target.telegram_id.to_string()));
+    let key = if ban { "commands.admin.ban_user_success" } else { "commands.admin.unban_user_success" };
+    bot.send_message(chat_id, i18n.t(key, &user_lang, Some(&params))).await?;
+    show_user_management(bot, chat_id, None, &services, &i18n, &user_lang).await?;
+
+    info!(admin_id = user_id, target_id = target.telegram_id, ban = ban, "User ban status changed from the admin panel");
     Ok(())
 }
 
@@ -139,6 +551,7 @@ pub async fn handle_admin_callback(
 async fn show_user_management(
     bot: Bot,
     chat_id: ChatId,
+    message_id: Option<MessageId>,
     services: &ServiceFactory,
     i18n: &I18n,
     language_code: &str,
@@ -157,283 +570,2700 @@ async fn show_user_management(
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("buttons.admin.ban", language_code, None),
-                "admin:ban_user"
+                CallbackData::Admin(AdminAction::BanUser)
             ),
             InlineKeyboardButton::callback(
                 i18n.t("buttons.admin.unban", language_code, None),
-                "admin:unban_user"
-            ),
-        ],
-        vec![
-            InlineKeyboardButton::callback(
-                i18n.t("buttons.navigation.back", language_code, None),
-                "admin:back"
+                CallbackData::Admin(AdminAction::UnbanUser)
             ),
         ],
-    ]);
-    
-    bot.send_message(chat_id, text)
-        .reply_markup(keyboard)
-        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-        .await?;
-    
-    Ok(())
-}
-
-/// Show group management panel
-async fn show_group_management(
-    bot: Bot,
-    chat_id: ChatId,
-    _services: &ServiceFactory,
-    i18n: &I18n,
-    language_code: &str,
-) -> Result<()> {
-    let text = format!(
-        "👥 **{}**\n\nGroup management features:\n• View active groups\n• Manage group settings\n• Monitor group activity",
-        i18n.t("commands.admin.group_management", language_code, None)
-    );
-    
-    let keyboard = InlineKeyboardMarkup::new(vec![
         vec![
             InlineKeyboardButton::callback(
-                "📋 List Groups",
-                "admin:list_groups"
-            ),
-            InlineKeyboardButton::callback(
-                "⚙️ Group Settings",
-                "admin:group_settings"
+                i18n.t("buttons.admin.user_list", language_code, None),
+                CallbackData::Admin(AdminAction::UserList { sort: UserListSort::Registered, page: 0 })
             ),
-        ],
-        vec![
             InlineKeyboardButton::callback(
-                i18n.t("buttons.navigation.back", language_code, None),
-                "admin:back"
+                i18n.t("buttons.admin.export_users", language_code, None),
+                CallbackData::Admin(AdminAction::ExportUsers)
             ),
         ],
-    ]);
-    
-    bot.send_message(chat_id, text)
-        .reply_markup(keyboard)
-        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-        .await?;
-    
-    Ok(())
-}
-
-/// Show event management panel
-async fn show_event_management(
-    bot: Bot,
-    chat_id: ChatId,
-    _services: &ServiceFactory,
-    i18n: &I18n,
-    language_code: &str,
-) -> Result<()> {
-    let text = format!(
-        "🎭 **{}**\n\nEvent management features:\n• Create new events\n• Edit existing events\n• Manage event calendars\n• View event statistics",
-        i18n.t("commands.admin.event_management", language_code, None)
-    );
-    
-    let keyboard = InlineKeyboardMarkup::new(vec![
         vec![
             InlineKeyboardButton::callback(
-                i18n.t("buttons.events.create", language_code, None),
-                "admin:create_event"
-            ),
-            InlineKeyboardButton::callback(
-                i18n.t("buttons.events.list", language_code, None),
-                "admin:list_events"
+                i18n.t("buttons.admin.import_users", language_code, None),
+                CallbackData::Admin(AdminAction::ImportUsers)
             ),
-        ],
-        vec![
             InlineKeyboardButton::callback(
-                "📅 Manage Calendars",
-                "admin:manage_calendars"
+                i18n.t("buttons.admin.ban_list", language_code, None),
+                CallbackData::Admin(AdminAction::BanList(0))
             ),
         ],
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("buttons.navigation.back", language_code, None),
-                "admin:back"
+                CallbackData::Admin(AdminAction::Back)
             ),
         ],
     ]);
     
-    bot.send_message(chat_id, text)
-        .reply_markup(keyboard)
-        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-        .await?;
+    show_panel(&bot, chat_id, message_id, text, keyboard, Some(ParseMode::MarkdownV2)).await?;
     
     Ok(())
 }
 
-/// Show system statistics
-async fn show_statistics(
+/// Show one page of the users, newest registrations or most recent activity first
+#[allow(clippy::too_many_arguments)]
+async fn show_user_list(
     bot: Bot,
     chat_id: ChatId,
+    message_id: Option<MessageId>,
+    sort: UserListSort,
+    page: i64,
     services: &ServiceFactory,
     i18n: &I18n,
     language_code: &str,
 ) -> Result<()> {
-    // Get various statistics
-    let user_stats = services.user_service.get_user_statistics().await?;
-    let health_status = services.health_check().await;
-    
-    let text = format!(
-        "📊 **{}**\n\n👥 Users:\n• Total: {}\n• Active: {}\n• Banned: {}\n\n� System:\n• Redis: {}\n• Google Calendar: {}\n• CAS Protection: {}",
-        i18n.t("commands.admin.statistics", language_code, None),
-        user_stats.get("total_users").unwrap_or(&0),
-        user_stats.get("active_users").unwrap_or(&0),
-        user_stats.get("banned_users").unwrap_or(&0),
-        if health_status.redis_healthy { "✅" } else { "❌" },
-        if health_status.google_enabled { "✅" } else { "❌" },
-        if health_status.cas_enabled { "✅" } else { "❌" }
-    );
-    
-    let keyboard = InlineKeyboardMarkup::new(vec![
-        vec![
-            InlineKeyboardButton::callback(
-                "🔄 Refresh",
-                "admin:stats"
-            ),
-            InlineKeyboardButton::callback(
-                i18n.t("buttons.admin.backup", language_code, None),
-                "admin:backup"
-            ),
-        ],
-        vec![
-            InlineKeyboardButton::callback(
-                i18n.t("buttons.navigation.back", language_code, None),
-                "admin:back"
-            ),
-        ],
-    ]);
-    
-    bot.send_message(chat_id, text)
-        .reply_markup(keyboard)
-        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-        .await?;
-    
+    let (users, pages) = services.user_service.list_users_page(sort, page).await?;
+    let page = page.clamp(0, pages - 1);
+
+    let sort_key = match sort {
+        UserListSort::Registered => "commands.admin.user_list.sort_registered",
+        UserListSort::LastActive => "commands.admin.user_list.sort_active",
+    };
+    let mut params = HashMap::new();
+    params.insert("page".to_string(), (page + 1).to_string());
+    params.insert("pages".to_string(), pages.to_string());
+    params.insert("sort".to_string(), i18n.t(sort_key, language_code, None));
+    let mut text = i18n.t("commands.admin.user_list.title", language_code, Some(&params));
+
+    if users.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.admin.user_list.empty", language_code, None));
+    }
+    let offset = page * crate::services::user::USER_LIST_PAGE_SIZE;
+    for (index, user) in users.iter().enumerate() {
+        text.push_str("\n\n");
+        text.push_str(&format_user_list_entry(offset + index as i64 + 1, user, i18n, language_code));
+    }
+
+    let mut navigation = Vec::new();
+    if page > 0 {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.previous", language_code, None),
+            CallbackData::Admin(AdminAction::UserList { sort, page: page - 1 }),
+        ));
+    }
+    if page + 1 < pages {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.next", language_code, None),
+            CallbackData::Admin(AdminAction::UserList { sort, page: page + 1 }),
+        ));
+    }
+
+    // Switching the order starts again at the first page
+    let (other_sort, other_key) = match sort {
+        UserListSort::Registered => (UserListSort::LastActive, "buttons.admin.sort_active"),
+        UserListSort::LastActive => (UserListSort::Registered, "buttons.admin.sort_registered"),
+    };
+    let mut keyboard = Vec::new();
+    if !navigation.is_empty() {
+        keyboard.push(navigation);
+    }
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t(other_key, language_code, None),
+        CallbackData::Admin(AdminAction::UserList { sort: other_sort, page: 0 }),
+    )]);
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Users),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
     Ok(())
 }
 
-/// Show system settings panel
-async fn show_system_settings(
+/// Format one line of the admin user list
+fn format_user_list_entry(number: i64, user: &UserListEntry, i18n: &I18n, language_code: &str) -> String {
+    let name = [user.first_name.as_deref(), user.last_name.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut params = HashMap::new();
+    params.insert("number".to_string(), number.to_string());
+    params.insert("name".to_string(), if name.is_empty() { user.telegram_id.to_string() } else { name });
+    params.insert("username".to_string(), user.username.as_ref().map(|u| format!(" @{}", u)).unwrap_or_default());
+    params.insert("telegram_id".to_string(), user.telegram_id.to_string());
+    params.insert("banned".to_string(), if user.is_banned { " 🚫".to_string() } else { String::new() });
+    params.insert("joined".to_string(), user.created_at.format("%d.%m.%Y").to_string());
+    params.insert("active".to_string(), match user.last_active_at {
+        Some(active) => active.format("%d.%m.%Y %H:%M").to_string(),
+        None => i18n.t("commands.admin.user_list.never", language_code, None),
+    });
+
+    i18n.t("commands.admin.user_list.item", language_code, Some(&params))
+}
+
+/// Show one page of the current bans with an unban button for each of them
+async fn show_ban_list(
     bot: Bot,
     chat_id: ChatId,
-    _services: &ServiceFactory,
+    message_id: Option<MessageId>,
+    page: i64,
+    services: &ServiceFactory,
     i18n: &I18n,
     language_code: &str,
 ) -> Result<()> {
-    let text = format!(
-        "⚙️ **{}**\n\nSystem configuration:\n• Feature toggles\n• API settings\n• Cache management\n• Backup & restore",
-        i18n.t("commands.admin.system_settings", language_code, None)
+    let (bans, pages) = services.moderation_service.list_bans_page(page).await?;
+    let page = page.clamp(0, pages - 1);
+
+    let mut params = HashMap::new();
+    params.insert("page".to_string(), (page + 1).to_string());
+    params.insert("pages".to_string(), pages.to_string());
+    let mut text = i18n.t("commands.admin.ban_list.title", language_code, Some(&params));
+
+    if bans.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.admin.ban_list.empty", language_code, None));
+    }
+    let offset = page * crate::services::moderation::BAN_LIST_PAGE_SIZE;
+    let mut unban_buttons = Vec::with_capacity(bans.len());
+    for (index, ban) in bans.iter().enumerate() {
+        let number = offset + index as i64 + 1;
+        text.push_str("\n\n");
+        text.push_str(&format_ban_list_entry(number, ban, i18n, language_code));
+
+        let mut params = HashMap::new();
+        params.insert("number".to_string(), number.to_string());
+        unban_buttons.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.admin.unban_number", language_code, Some(&params)),
+            CallbackData::Admin(AdminAction::BanLift { chat_id: ban.chat_telegram_id, telegram_id: ban.user_telegram_id, page }),
+        ));
+    }
+
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = unban_buttons.chunks(5).map(<[_]>::to_vec).collect();
+    let mut navigation = Vec::new();
+    if page > 0 {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.previous", language_code, None),
+            CallbackData::Admin(AdminAction::BanList(page - 1)),
+        ));
+    }
+    if page + 1 < pages {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.next", language_code, None),
+            CallbackData::Admin(AdminAction::BanList(page + 1)),
+        ));
+    }
+    if !navigation.is_empty() {
+        keyboard.push(navigation);
+    }
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Users),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Format one entry of the admin ban list with where the ban came from
+fn format_ban_list_entry(number: i64, ban: &BanListEntry, i18n: &I18n, language_code: &str) -> String {
+    let origin = match (ban.chat_telegram_id, ban.actor_telegram_id) {
+        (None, _) => i18n.t("commands.admin.ban_list.origin_bot", language_code, None),
+        (Some(_), _) if ban.is_cas() => i18n.t("commands.admin.ban_list.origin_cas", language_code, None),
+        (Some(_), Some(actor)) => {
+            let mut params = HashMap::new();
+            params.insert("admin".to_string(), actor.to_string());
+            i18n.t("commands.admin.ban_list.origin_admin", language_code, Some(&params))
+        }
+        (Some(_), None) => i18n.t("commands.admin.ban_list.origin_auto", language_code, None),
+    };
+
+    let mut params = HashMap::new();
+    params.insert("number".to_string(), number.to_string());
+    params.insert("name".to_string(), ban.first_name.clone().unwrap_or_else(|| ban.user_telegram_id.to_string()));
+    params.insert("username".to_string(), ban.username.as_ref().map(|u| format!(" @{}", u)).unwrap_or_default());
+    params.insert("telegram_id".to_string(), ban.user_telegram_id.to_string());
+    params.insert("origin".to_string(), origin);
+    params.insert("chat".to_string(), match ban.chat_telegram_id {
+        Some(chat) => ban.chat_title.clone().unwrap_or_else(|| chat.to_string()),
+        None => i18n.t("commands.admin.ban_list.whole_bot", language_code, None),
+    });
+    params.insert("reason".to_string(), ban.reason.clone()
+        .unwrap_or_else(|| i18n.t("commands.admin.ban_list.no_reason", language_code, None)));
+    params.insert("date".to_string(), ban.banned_at.format("%d.%m.%Y %H:%M").to_string());
+
+    i18n.t("commands.admin.ban_list.item", language_code, Some(&params))
+}
+
+/// Show one page of the admin audit trail, newest actions first
+/// Show a page of the activity log, optionally only one category of actions
+#[allow(clippy::too_many_arguments)]
+async fn show_activity_log(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    category: Option<ActivityCategory>,
+    page: i64,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let (entries, pages) = services.activity_log_service.list_page(category, page).await?;
+    let page = page.clamp(0, pages - 1);
+    let filter = category.map_or("all", |category| category.as_str());
+
+    let mut params = HashMap::new();
+    params.insert("page".to_string(), (page + 1).to_string());
+    params.insert("pages".to_string(), pages.to_string());
+    params.insert("filter".to_string(), i18n.t(&format!("commands.admin.activity.filters.{}", filter), language_code, None));
+    let mut text = i18n.t("commands.admin.activity.title", language_code, Some(&params));
+    text.push('\n');
+
+    if entries.is_empty() {
+        text.push('\n');
+        text.push_str(&i18n.t("commands.admin.activity.empty", language_code, None));
+    }
+    for entry in &entries {
+        text.push('\n');
+        text.push_str(&format_activity_entry(entry, i18n, language_code));
+    }
+
+    let filters = [None].into_iter()
+        .chain(ActivityCategory::ALL.into_iter().map(Some))
+        .map(|option| {
+            let name = option.map_or("all", |category| category.as_str());
+            let mut label = i18n.t(&format!("commands.admin.activity.filters.{}", name), language_code, None);
+            if option == category {
+                label = format!("• {}", label);
+            }
+            InlineKeyboardButton::callback(label, CallbackData::Admin(AdminAction::Activity { category: option, page: 0 }))
+        })
+        .collect::<Vec<_>>();
+
+    let mut navigation = Vec::new();
+    if page > 0 {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.previous", language_code, None),
+            CallbackData::Admin(AdminAction::Activity { category, page: page - 1 }),
+        ));
+    }
+    if page + 1 < pages {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.next", language_code, None),
+            CallbackData::Admin(AdminAction::Activity { category, page: page + 1 }),
+        ));
+    }
+
+    let mut keyboard = vec![filters];
+    if !navigation.is_empty() {
+        keyboard.push(navigation);
+    }
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Back),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// One line of the activity log
+fn format_activity_entry(entry: &ActivityLogEntry, i18n: &I18n, language_code: &str) -> String {
+    let actor = match (&entry.actor_name, &entry.actor_username, entry.actor_id) {
+        (_, Some(username), _) => format!("@{}", username),
+        (Some(name), None, _) => name.clone(),
+        (None, None, Some(actor_id)) => actor_id.to_string(),
+        (None, None, None) => i18n.t("commands.admin.activity.bot", language_code, None),
+    };
+    let action_key = format!("commands.admin.activity.actions.{}", entry.action);
+    let mut action = i18n.t(&action_key, language_code, None);
+    if action == action_key {
+        action = entry.action.clone();
+    }
+
+    let mut params = HashMap::new();
+    params.insert("time".to_string(), entry.created_at.format("%d.%m %H:%M").to_string());
+    params.insert("actor".to_string(), actor);
+    params.insert("action".to_string(), action);
+    let mut entity_params = HashMap::new();
+    entity_params.insert("id".to_string(), entry.entity_id.to_string());
+    let entity_key = format!("commands.admin.activity.entities.{}", entry.entity_type);
+    params.insert("entity".to_string(), i18n.t(&entity_key, language_code, Some(&entity_params)));
+    i18n.t("commands.admin.activity.item", language_code, Some(&params))
+}
+
+async fn show_audit_log(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    page: i64,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let (entries, pages) = services.admin_audit_service.list_page(page).await?;
+    let page = page.clamp(0, pages - 1);
+
+    let mut params = HashMap::new();
+    params.insert("page".to_string(), (page + 1).to_string());
+    params.insert("pages".to_string(), pages.to_string());
+    let mut text = i18n.t("commands.admin.audit.title", language_code, Some(&params));
+    text.push('\n');
+
+    if entries.is_empty() {
+        text.push('\n');
+        text.push_str(&i18n.t("commands.admin.audit.empty", language_code, None));
+    }
+    for entry in &entries {
+        let admin = match (&entry.admin_name, &entry.admin_username) {
+            (_, Some(username)) => format!("@{}", username),
+            (Some(name), None) => name.clone(),
+            (None, None) => entry.admin_id.to_string(),
+        };
+        let mut params = HashMap::new();
+        params.insert("time".to_string(), entry.created_at.format("%d.%m %H:%M").to_string());
+        params.insert("admin".to_string(), admin);
+        params.insert("action".to_string(), entry.action.clone());
+        params.insert("target".to_string(), entry.target.as_ref().map(|target| format!(" {}", target)).unwrap_or_default());
+        text.push('\n');
+        text.push_str(&i18n.t("commands.admin.audit.item", language_code, Some(&params)));
+    }
+
+    let mut navigation = Vec::new();
+    if page > 0 {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.previous", language_code, None),
+            CallbackData::Admin(AdminAction::Audit(page - 1)),
+        ));
+    }
+    if page + 1 < pages {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.next", language_code, None),
+            CallbackData::Admin(AdminAction::Audit(page + 1)),
+        ));
+    }
+
+    let mut keyboard = Vec::new();
+    if !navigation.is_empty() {
+        keyboard.push(navigation);
+    }
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Back),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Show one page of the deleted users, groups or events with a restore button for each
+#[allow(clippy::too_many_arguments)]
+async fn show_trash(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    kind: TrashKind,
+    page: i64,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let (entries, pages) = match kind {
+        TrashKind::Users => services.user_service.list_deleted_page(page).await?,
+        TrashKind::Groups => services.group_service.list_deleted_page(page).await?,
+        TrashKind::Events => services.event_service.list_deleted_page(page).await?,
+    };
+    let page = page.clamp(0, pages - 1);
+
+    let mut params = HashMap::new();
+    params.insert("kind".to_string(), i18n.t(&format!("commands.admin.trash.kinds.{}", kind.as_str()), language_code, None));
+    params.insert("page".to_string(), (page + 1).to_string());
+    params.insert("pages".to_string(), pages.to_string());
+    let mut text = i18n.t("commands.admin.trash.title", language_code, Some(&params));
+    text.push('\n');
+
+    if entries.is_empty() {
+        text.push('\n');
+        text.push_str(&i18n.t("commands.admin.trash.empty", language_code, None));
+    }
+    let offset = page * TRASH_PAGE_SIZE;
+    let mut restore_buttons = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.iter().enumerate() {
+        let number = offset + index as i64 + 1;
+        text.push('\n');
+        text.push_str(&format_trash_entry(number, entry, i18n, language_code));
+
+        let mut params = HashMap::new();
+        params.insert("number".to_string(), number.to_string());
+        restore_buttons.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.admin.restore_number", language_code, Some(&params)),
+            CallbackData::Admin(AdminAction::Restore { kind, id: entry.id, page }),
+        ));
+    }
+
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = restore_buttons.chunks(5).map(<[_]>::to_vec).collect();
+    let mut navigation = Vec::new();
+    if page > 0 {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.previous", language_code, None),
+            CallbackData::Admin(AdminAction::Trash { kind, page: page - 1 }),
+        ));
+    }
+    if page + 1 < pages {
+        navigation.push(InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.next", language_code, None),
+            CallbackData::Admin(AdminAction::Trash { kind, page: page + 1 }),
+        ));
+    }
+    if !navigation.is_empty() {
+        keyboard.push(navigation);
+    }
+    keyboard.push(TrashKind::ALL.into_iter()
+        .map(|other| {
+            let label = i18n.t(&format!("commands.admin.trash.kinds.{}", other.as_str()), language_code, None);
+            let label = if other == kind { format!("• {}", label) } else { label };
+            InlineKeyboardButton::callback(label, CallbackData::Admin(AdminAction::Trash { kind: other, page: 0 }))
+        })
+        .collect());
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Back),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Format one line of the admin trash
+fn format_trash_entry(number: i64, entry: &TrashEntry, i18n: &I18n, language_code: &str) -> String {
+    let mut params = HashMap::new();
+    params.insert("number".to_string(), number.to_string());
+    params.insert("name".to_string(), entry.label.clone());
+    params.insert("deleted".to_string(), entry.deleted_at.format("%d.%m.%Y %H:%M").to_string());
+    i18n.t("commands.admin.trash.item", language_code, Some(&params))
+}
+
+/// Ask before moving a user, group or event to the trash
+async fn confirm_delete(
+    bot: Bot,
+    chat_id: ChatId,
+    kind: TrashKind,
+    id: i64,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let name = match kind {
+        TrashKind::Users => services.user_service.get_user_by_telegram_id(id).await?
+            .map(|user| user.first_name.or(user.username).unwrap_or_else(|| user.telegram_id.to_string())),
+        TrashKind::Groups => services.group_service.get_group(id).await?.map(|group| group.title),
+        TrashKind::Events => services.event_service.get_event(id).await?.map(|event| event.title),
+    };
+    let Some(name) = name else {
+        bot.send_message(chat_id, i18n.t("commands.admin.trash.not_found", language_code, None)).await?;
+        return Ok(());
+    };
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), name);
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.confirm", language_code, None),
+            CallbackData::Admin(AdminAction::DeleteConfirm { kind, id }),
+        ),
+        InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.cancel", language_code, None),
+            CallbackData::Admin(AdminAction::Back),
+        ),
+    ]]);
+
+    let key = format!("commands.admin.trash.confirm_{}", kind.as_str());
+    bot.send_message(chat_id, i18n.t(&key, language_code, Some(&params)))
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Show group management panel
+async fn show_group_management(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    _services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let text = format!(
+        "👥 **{}**\n\nGroup management features:\n• View active groups\n• Manage group settings\n• Monitor group activity",
+        i18n.t("commands.admin.group_management", language_code, None)
     );
     
     let keyboard = InlineKeyboardMarkup::new(vec![
         vec![
             InlineKeyboardButton::callback(
-                "🔧 Features",
-                "admin:features"
+                "📋 List Groups",
+                CallbackData::Admin(AdminAction::ListGroups)
             ),
             InlineKeyboardButton::callback(
-                "🗄️ Cache",
-                "admin:cache"
+                i18n.t("buttons.admin.group_settings", language_code, None),
+                CallbackData::Admin(AdminAction::GroupSettings)
+            ),
+        ],
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.spam_filters", language_code, None),
+                CallbackData::Admin(AdminAction::SpamFilters)
+            ),
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.announcement", language_code, None),
+                CallbackData::Admin(AdminAction::Announce)
             ),
         ],
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("buttons.navigation.back", language_code, None),
-                "admin:back"
+                CallbackData::Admin(AdminAction::Back)
             ),
         ],
     ]);
     
-    bot.send_message(chat_id, text)
-        .reply_markup(keyboard)
-        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-        .await?;
+    show_panel(&bot, chat_id, message_id, text, keyboard, Some(ParseMode::MarkdownV2)).await?;
     
     Ok(())
 }
 
-/// Handle /stats command - show bot statistics
-pub async fn handle_stats(
+/// Show the active groups to pick one for an admin action.
+/// Each button goes to `action` of the group's chat ID; texts come from `<section>.choose_group` and `<section>.no_groups`.
+#[allow(clippy::too_many_arguments)]
+async fn show_group_picker(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    action: fn(i64) -> AdminAction,
+    section: &str,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let groups = services.group_service.get_active_groups().await?;
+
+    let mut keyboard = groups.iter()
+        .map(|group| vec![InlineKeyboardButton::callback(
+            group.title.clone(),
+            CallbackData::Admin(action(group.telegram_id)),
+        )])
+        .collect::<Vec<_>>();
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Groups),
+    )]);
+
+    let key = if groups.is_empty() { format!("{}.no_groups", section) } else { format!("{}.choose_group", section) };
+    show_panel(&bot, chat_id, message_id, i18n.t(&key, language_code, None), InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Show the feature switches of a group
+async fn show_group_features(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    group_chat_id: ChatId,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let Some(group) = services.group_service.get_group(group_chat_id.0).await? else {
+        bot.send_message(chat_id, i18n.t("commands.events.group_not_registered", language_code, None)).await?;
+        return Ok(());
+    };
+
+    let mut params = HashMap::new();
+    params.insert("group".to_string(), group.title.clone());
+    let text = i18n.t("commands.admin.group_settings.title", language_code, Some(&params));
+
+    // Features switched off for the whole bot cannot be turned on for a group
+    let mut keyboard = GroupFeature::ALL.into_iter()
+        .filter(|feature| match feature {
+            GroupFeature::CasProtection => services.cas_service.is_enabled(),
+            GroupFeature::Captcha => services.captcha_service.is_enabled(),
+            GroupFeature::WeeklyDigest => services.digest_service.is_enabled(),
+            GroupFeature::PinnedAnnouncements => services.reminder_service.is_enabled(),
+            _ => true,
+        })
+        .map(|feature| {
+            let mark = if group.feature_enabled(feature) { "✅" } else { "❌" };
+            let label = i18n.t(&format!("commands.admin.group_settings.features.{}", feature.as_str()), language_code, None);
+            vec![InlineKeyboardButton::callback(
+                format!("{} {}", mark, label),
+                CallbackData::Admin(AdminAction::GroupToggle { chat_id: group_chat_id.0, feature }),
+            )]
+        })
+        .collect::<Vec<_>>();
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.admin.delete", language_code, None),
+        CallbackData::Admin(AdminAction::Delete { kind: TrashKind::Groups, id: group_chat_id.0 }),
+    )]);
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::GroupSettings),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Show the spam filters of a group with buttons to change them
+async fn show_spam_filters(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    group_chat_id: ChatId,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let Some(group) = services.group_service.get_group(group_chat_id.0).await? else {
+        bot.send_message(chat_id, i18n.t("commands.events.group_not_registered", language_code, None)).await?;
+        return Ok(());
+    };
+    let filters = services.moderation_service.get_spam_filters(group_chat_id).await?;
+
+    let mut params = HashMap::new();
+    params.insert("group".to_string(), group.title.clone());
+    let mut text = i18n.t("commands.admin.spam_filters.title", language_code, Some(&params));
+    text.push_str("\n\n");
+    if filters.is_empty() {
+        text.push_str(&i18n.t("commands.admin.spam_filters.empty", language_code, None));
+    }
+    for (index, filter) in filters.iter().enumerate() {
+        text.push_str(&format!("{}. [{}] {}\n", index + 1, filter.kind, filter.pattern));
+    }
+
+    let mut keyboard = vec![vec![
+        InlineKeyboardButton::callback(
+            i18n.t("buttons.admin.add_keyword", language_code, None),
+            CallbackData::Admin(AdminAction::SpamAdd { chat_id: group_chat_id.0, kind: SpamFilterKind::Keyword }),
+        ),
+        InlineKeyboardButton::callback(
+            i18n.t("buttons.admin.add_regex", language_code, None),
+            CallbackData::Admin(AdminAction::SpamAdd { chat_id: group_chat_id.0, kind: SpamFilterKind::Regex }),
+        ),
+        InlineKeyboardButton::callback(
+            i18n.t("buttons.admin.add_domain", language_code, None),
+            CallbackData::Admin(AdminAction::SpamAdd { chat_id: group_chat_id.0, kind: SpamFilterKind::Domain }),
+        ),
+    ]];
+
+    // One delete button per filter, numbered like the list
+    for chunk in filters.iter().enumerate().collect::<Vec<_>>().chunks(4) {
+        keyboard.push(chunk.iter()
+            .map(|(index, filter)| InlineKeyboardButton::callback(
+                format!("🗑 {}", index + 1),
+                CallbackData::Admin(AdminAction::SpamDelete(filter.id)),
+            ))
+            .collect());
+    }
+
+    let warn_key = if group.spam_filter_warns() { "buttons.admin.spam_warn_on" } else { "buttons.admin.spam_warn_off" };
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t(warn_key, language_code, None),
+        CallbackData::Admin(AdminAction::SpamWarn(group_chat_id.0)),
+    )]);
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::SpamFilters),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Save the spam filter pattern an admin sent from the admin panel
+pub async fn handle_spam_filter_input(
+    bot: Bot,
+    msg: Message,
+    context: ConversationContext,
+    services: ServiceFactory,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let group_chat_id = context.get_i64("chat_id");
+    let kind = context.get_string("kind").and_then(|kind| kind.parse::<SpamFilterKind>().ok());
+    let (Some(group_chat_id), Some(kind)) = (group_chat_id, kind) else {
+        state_storage.delete_context(user_id).await?;
+        return Ok(());
+    };
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
+    }
+
+    let pattern = msg.text().unwrap_or_default();
+    let key = match services.moderation_service.add_spam_filter(ChatId(group_chat_id), kind, pattern, user_id).await {
+        Ok(Some(_)) => "commands.admin.spam_filters.added",
+        Ok(None) => "commands.admin.spam_filters.exists",
+        Err(crate::utils::errors::SwingBuddyError::InvalidInput(error)) => {
+            // Let the admin try again with a fixed pattern
+            let mut params = HashMap::new();
+            params.insert("error".to_string(), error);
+            bot.send_message(chat_id, i18n.t("commands.admin.spam_filters.invalid", &user_lang, Some(&params))).await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    state_storage.delete_context(user_id).await?;
+    services.admin_audit_service.record(user_id, "spam_filter_added", Some(&format!("{}:{}:{}", group_chat_id, kind.as_str(), pattern))).await;
+    bot.send_message(chat_id, i18n.t(key, &user_lang, None)).await?;
+    show_spam_filters(bot, chat_id, None, ChatId(group_chat_id), &services, &i18n, &user_lang).await?;
+
+    info!(admin_id = user_id, group_chat_id = group_chat_id, kind = %kind.as_str(), "Spam filter added by admin");
+
+    Ok(())
+}
+
+/// Take the message an admin composed for a broadcast and ask who should get it.
+/// Sending another message before confirming replaces the draft.
+pub async fn handle_broadcast_input(
+    bot: Bot,
+    msg: Message,
+    mut context: ConversationContext,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    context.set_data("message_id", msg.id.0)?;
+    context.remove_data("segment");
+    // Text with placeholders is rendered for every recipient instead of copied
+    match msg.html_text().or_else(|| msg.text().map(html::escape)).filter(|text| has_placeholders(text)) {
+        Some(text) => context.set_data("text", text)?,
+        None => {
+            context.remove_data("text");
+        }
+    }
+    if context.is_at_step("message_input") {
+        scenario_manager.next_step(&mut context, "segment_select")?;
+    }
+    state_storage.save_context(&mut context).await?;
+
+    show_broadcast_segments(bot, chat_id, None, &services, &i18n, &user_lang).await?;
+
+    debug!(admin_id = user_id, message_id = msg.id.0, "Broadcast message composed");
+
+    Ok(())
+}
+
+/// Show the segments a broadcast can be sent to
+async fn show_broadcast_segments(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let mut params = HashMap::new();
+    params.insert("count".to_string(), services.broadcast_service.count_recipients(&BroadcastSegment::All).await?.to_string());
+
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback(
+            i18n.t("commands.admin.broadcast.all_users", language_code, Some(&params)),
+            CallbackData::Admin(AdminAction::BroadcastSegment(BroadcastSegment::All)),
+        )],
+        vec![
+            InlineKeyboardButton::callback(i18n.t("commands.admin.broadcast.by_city", language_code, None), CallbackData::Admin(AdminAction::BroadcastCities)),
+            InlineKeyboardButton::callback(i18n.t("commands.admin.broadcast.by_language", language_code, None), CallbackData::Admin(AdminAction::BroadcastLanguages)),
+        ],
+        vec![InlineKeyboardButton::callback(
+            i18n.t("commands.admin.broadcast.by_event", language_code, None),
+            CallbackData::Admin(AdminAction::BroadcastEvents),
+        )],
+        vec![InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.cancel", language_code, None),
+            CallbackData::Admin(AdminAction::BroadcastCancel),
+        )],
+    ]);
+
+    show_panel(&bot, chat_id, message_id, i18n.t("commands.admin.broadcast.choose_segment", language_code, None), keyboard, None).await?;
+
+    Ok(())
+}
+
+/// Show the cities, languages or events to narrow a broadcast down to
+async fn show_broadcast_segment_choices(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    action: &AdminAction,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let choices: Vec<(String, BroadcastSegment)> = match action {
+        AdminAction::BroadcastCities => services.broadcast_service.get_top_cities(MAX_BROADCAST_CHOICES).await?
+            .into_iter()
+            .map(|(city, count)| (format!("{} ({})", city, count), BroadcastSegment::City(city)))
+            .collect(),
+        AdminAction::BroadcastLanguages => services.broadcast_service.get_languages().await?
+            .into_iter()
+            .map(|(language, count)| (format!("{} ({})", language, count), BroadcastSegment::Language(language)))
+            .collect(),
+        _ => services.broadcast_service.get_upcoming_events(MAX_BROADCAST_CHOICES).await?
+            .into_iter()
+            .map(|event| (format!("{} · {}", event.title, event.event_date.format("%d.%m")), BroadcastSegment::EventParticipants(event.id)))
+            .collect(),
+    };
+
+    // Telegram drops buttons whose callback data is longer than 64 bytes, e.g. for very long city names
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = choices.into_iter()
+        .map(|(label, segment)| (label, CallbackData::Admin(AdminAction::BroadcastSegment(segment)).encode()))
+        .filter(|(_, data)| data.len() <= MAX_CALLBACK_DATA_LEN)
+        .map(|(label, data)| vec![InlineKeyboardButton::callback(label, data)])
+        .collect();
+
+    let text = if keyboard.is_empty() {
+        i18n.t("commands.admin.broadcast.no_choices", language_code, None)
+    } else {
+        i18n.t("commands.admin.broadcast.choose", language_code, None)
+    };
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::BroadcastSegments),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Show the composed message as a recipient will get it, with the number of
+/// recipients on the send button. Personalized text is rendered for one of them.
+#[allow(clippy::too_many_arguments)]
+async fn show_broadcast_preview(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    segment: BroadcastSegment,
+    services: &ServiceFactory,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let Some(mut context) = broadcast_draft(user_id, state_storage).await? else {
+        bot.send_message(chat_id, i18n.t("commands.admin.broadcast.expired", language_code, None)).await?;
+        return Ok(());
+    };
+    let Some(message_id) = context.get_i64("message_id") else {
+        bot.send_message(chat_id, i18n.t("commands.admin.broadcast.expired", language_code, None)).await?;
+        return Ok(());
+    };
+
+    let count = services.broadcast_service.count_recipients(&segment).await?;
+    let mut params = HashMap::new();
+    params.insert("segment".to_string(), segment_label(&segment, services, i18n, language_code).await?);
+    params.insert("count".to_string(), count.to_string());
+
+    if count == 0 {
+        bot.send_message(chat_id, i18n.t("commands.admin.broadcast.no_recipients", language_code, Some(&params))).await?;
+        return show_broadcast_segments(bot, chat_id, None, services, i18n, language_code).await;
+    }
+
+    context.set_data("segment", segment.to_string())?;
+    state_storage.save_context(&mut context).await?;
+
+    let mut text = i18n.t("commands.admin.broadcast.preview", language_code, Some(&params));
+    match context.get_string("text") {
+        Some(template) => {
+            let sample = services.broadcast_service.sample_recipient(&segment).await?;
+            bot.send_message(chat_id, render_broadcast(&template, sample.as_ref()))
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await?;
+
+            if let Some(sample) = sample {
+                let mut params = HashMap::new();
+                let name = sample.first_name.clone()
+                    .or_else(|| sample.username.as_ref().map(|username| format!("@{}", username)))
+                    .unwrap_or_else(|| sample.telegram_id.to_string());
+                params.insert("name".to_string(), name);
+                text.push('\n');
+                text.push_str(&i18n.t("commands.admin.broadcast.preview_sample", language_code, Some(&params)));
+            }
+        }
+        None => {
+            bot.copy_message(chat_id, chat_id, teloxide::types::MessageId(message_id as i32)).await?;
+        }
+    }
+
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback(i18n.tp("buttons.admin.send_to_users", language_code, count as i32, None), CallbackData::Admin(AdminAction::BroadcastSend)),
+            InlineKeyboardButton::callback(i18n.t("buttons.admin.schedule", language_code, None), CallbackData::Admin(AdminAction::BroadcastSchedule)),
+        ],
+        vec![
+            InlineKeyboardButton::callback(i18n.t("buttons.navigation.back", language_code, None), CallbackData::Admin(AdminAction::BroadcastSegments)),
+            InlineKeyboardButton::callback(i18n.t("buttons.navigation.cancel", language_code, None), CallbackData::Admin(AdminAction::BroadcastCancel)),
+        ],
+    ]);
+    bot.send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Queue the confirmed broadcast
+async fn send_broadcast(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    services: &ServiceFactory,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let draft = broadcast_draft(user_id, state_storage).await?;
+    let message_id = draft.as_ref().and_then(|context| context.get_i64("message_id"));
+    let segment = draft.as_ref()
+        .and_then(|context| context.get_string("segment"))
+        .and_then(|segment| segment.parse::<BroadcastSegment>().ok());
+    let (Some(message_id), Some(segment)) = (message_id, segment) else {
+        bot.send_message(chat_id, i18n.t("commands.admin.broadcast.expired", language_code, None)).await?;
+        return Ok(());
+    };
+
+    let broadcast = services.broadcast_service.create_broadcast(CreateBroadcastRequest {
+        created_by: user_id,
+        language_code: language_code.to_string(),
+        segment,
+        source_chat_id: chat_id.0,
+        source_message_id: message_id as i32,
+        text: draft.as_ref().and_then(|context| context.get_string("text")),
+    }).await?;
+    state_storage.delete_context(user_id).await?;
+
+    let mut params = HashMap::new();
+    params.insert("id".to_string(), broadcast.id.to_string());
+    params.insert("count".to_string(), broadcast.total.to_string());
+    bot.send_message(chat_id, i18n.t("commands.admin.broadcast.queued", language_code, Some(&params))).await?;
+
+    info!(admin_id = user_id, broadcast_id = broadcast.id, "Broadcast confirmed by admin");
+
+    Ok(())
+}
+
+/// Take the announcement an admin composed for a group and ask when to post it.
+/// Sending another message before entering the time replaces the draft.
+pub async fn handle_announcement_input(
+    bot: Bot,
+    msg: Message,
+    mut context: ConversationContext,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    context.set_data("message_id", msg.id.0)?;
+    scenario_manager.next_step(&mut context, "schedule_input")?;
+    state_storage.save_context(&mut context).await?;
+
+    bot.send_message(chat_id, schedule_prompt(&i18n, &user_lang)).await?;
+
+    debug!(admin_id = user_id, message_id = msg.id.0, "Announcement composed");
+
+    Ok(())
+}
+
+/// Schedule the drafted broadcast or announcement for the time the admin entered
+pub async fn handle_schedule_input(
+    bot: Bot,
+    msg: Message,
+    context: ConversationContext,
+    services: ServiceFactory,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let target = if context.is_in_scenario("broadcast") {
+        context.get_string("segment")
+            .and_then(|segment| segment.parse::<BroadcastSegment>().ok())
+            .map(ScheduledTarget::Broadcast)
+    } else {
+        context.get_i64("chat_id").map(ScheduledTarget::Group)
+    };
+    let (Some(target), Some(message_id)) = (target, context.get_i64("message_id")) else {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.broadcast.expired", &user_lang, None)).await?;
+        return Ok(());
+    };
+
+    // Let the admin try again until the time is valid
+    let Some(send_at) = parse_send_at(msg.text().unwrap_or_default(), chrono::Utc::now()) else {
+        bot.send_message(chat_id, i18n.t("commands.admin.scheduled.invalid_time", &user_lang, None)).await?;
+        return Ok(());
+    };
+
+    let scheduled = services.scheduled_message_service.schedule(CreateScheduledMessageRequest {
+        created_by: user_id,
+        language_code: user_lang.clone(),
+        target,
+        source_chat_id: chat_id.0,
+        source_message_id: message_id as i32,
+        text: context.get_string("text"),
+        send_at,
+    }).await?;
+    state_storage.delete_context(user_id).await?;
+
+    let mut params = HashMap::new();
+    params.insert("id".to_string(), scheduled.id.to_string());
+    params.insert("time".to_string(), scheduled.send_at.format(SEND_AT_FORMAT).to_string());
+    bot.send_message(chat_id, i18n.t("commands.admin.scheduled.created", &user_lang, Some(&params))).await?;
+
+    info!(admin_id = user_id, scheduled_id = scheduled.id, "Message scheduled by admin");
+
+    Ok(())
+}
+
+/// Ask an admin when to send a scheduled message
+fn schedule_prompt(i18n: &I18n, language_code: &str) -> String {
+    let mut params = HashMap::new();
+    params.insert("example".to_string(), (chrono::Utc::now() + chrono::Duration::days(1)).format("%Y-%m-%d 19:00").to_string());
+    i18n.t("commands.admin.scheduled.prompt_time", language_code, Some(&params))
+}
+
+/// Show the messages waiting to be sent with buttons to cancel them
+async fn show_scheduled_messages(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let messages = services.scheduled_message_service.get_pending().await?;
+
+    let mut text = if messages.is_empty() {
+        i18n.t("commands.admin.scheduled.empty", language_code, None)
+    } else {
+        i18n.t("commands.admin.scheduled.title", language_code, None)
+    };
+    let mut keyboard = Vec::new();
+    for message in &messages {
+        let mut params = HashMap::new();
+        params.insert("id".to_string(), message.id.to_string());
+        params.insert("time".to_string(), message.send_at.format(SEND_AT_FORMAT).to_string());
+        params.insert("target".to_string(), scheduled_target_label(message, services, i18n, language_code).await?);
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.admin.scheduled.item", language_code, Some(&params)));
+
+        keyboard.push(vec![InlineKeyboardButton::callback(
+            i18n.t("commands.admin.scheduled.cancel_button", language_code, Some(&params)),
+            CallbackData::Admin(AdminAction::ScheduledCancel(message.id)),
+        )]);
+    }
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Back),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Describe where a scheduled message goes
+async fn scheduled_target_label(message: &ScheduledMessage, services: &ServiceFactory, i18n: &I18n, language_code: &str) -> Result<String> {
+    match message.target.parse::<ScheduledTarget>() {
+        Ok(ScheduledTarget::Broadcast(segment)) => segment_label(&segment, services, i18n, language_code).await,
+        Ok(ScheduledTarget::Group(group_chat_id)) => {
+            let title = services.group_service.get_group(group_chat_id).await?
+                .map(|group| group.title)
+                .unwrap_or_else(|| group_chat_id.to_string());
+            let mut params = HashMap::new();
+            params.insert("group".to_string(), title);
+            Ok(i18n.t("commands.admin.scheduled.target_group", language_code, Some(&params)))
+        }
+        Err(_) => Ok(message.target.clone()),
+    }
+}
+
+/// Load the admin's broadcast draft, if they are composing one
+async fn broadcast_draft(user_id: i64, state_storage: &StateStorage) -> Result<Option<ConversationContext>> {
+    Ok(state_storage.load_context(user_id).await?.filter(|context| context.is_in_scenario("broadcast")))
+}
+
+/// Describe a broadcast segment to the admin
+async fn segment_label(segment: &BroadcastSegment, services: &ServiceFactory, i18n: &I18n, language_code: &str) -> Result<String> {
+    let mut params = HashMap::new();
+    let key = match segment {
+        BroadcastSegment::All => "commands.admin.broadcast.segment_all",
+        BroadcastSegment::City(city) => {
+            params.insert("city".to_string(), city.clone());
+            "commands.admin.broadcast.segment_city"
+        }
+        BroadcastSegment::Language(language) => {
+            params.insert("language".to_string(), language.clone());
+            "commands.admin.broadcast.segment_language"
+        }
+        BroadcastSegment::EventParticipants(event_id) => {
+            let title = services.reminder_service.find_event(*event_id).await?
+                .map(|event| event.title)
+                .unwrap_or_else(|| event_id.to_string());
+            params.insert("event".to_string(), title);
+            "commands.admin.broadcast.segment_event"
+        }
+    };
+
+    Ok(i18n.t(key, language_code, Some(&params)))
+}
+
+/// Show event management panel
+async fn show_event_management(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    _services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let text = format!(
+        "🎭 **{}**\n\nEvent management features:\n• Create new events\n• Edit existing events\n• Manage event calendars\n• View event statistics",
+        i18n.t("commands.admin.event_management", language_code, None)
+    );
+    
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.events.create", language_code, None),
+                CallbackData::Admin(AdminAction::CreateEvent)
+            ),
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.events.list", language_code, None),
+                CallbackData::Admin(AdminAction::ListEvents)
+            ),
+        ],
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.calendars", language_code, None),
+                CallbackData::Admin(AdminAction::Calendars)
+            ),
+        ],
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.navigation.back", language_code, None),
+                CallbackData::Admin(AdminAction::Back)
+            ),
+        ],
+    ]);
+    
+    show_panel(&bot, chat_id, message_id, text, keyboard, Some(ParseMode::MarkdownV2)).await?;
+    
+    Ok(())
+}
+
+/// Show system statistics
+#[allow(clippy::too_many_arguments)]
+async fn show_statistics(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    interval: GrowthInterval,
+    services: &ServiceFactory,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    // Get various statistics
+    let user_stats = services.user_service.get_user_statistics().await?;
+    let health_status = services.health_check().await;
+    
+    let top_referrers = services.referral_service.get_top_referrers(5).await?;
+    
+    let mut text = format!(
+        "📊 **{}**\n\n👥 Users:\n• Total: {}\n• Active: {}\n• Banned: {}\n\n� System:\n• Redis: {}\n• Google Calendar: {}\n• CAS Protection: {}",
+        i18n.t("commands.admin.statistics", language_code, None),
+        user_stats.get("total_users").unwrap_or(&0),
+        user_stats.get("active_users").unwrap_or(&0),
+        user_stats.get("banned_users").unwrap_or(&0),
+        if health_status.redis_healthy { "✅" } else { "❌" },
+        if health_status.google_enabled { "✅" } else { "❌" },
+        if health_status.cas_enabled { "✅" } else { "❌" }
+    );
+
+    if !top_referrers.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&teloxide::utils::markdown::escape(&i18n.t("commands.admin.top_inviters", language_code, None)));
+        for (index, referrer) in top_referrers.iter().enumerate() {
+            let name = referrer.username.as_ref().map(|u| format!("@{}", u))
+                .or_else(|| referrer.first_name.clone())
+                .unwrap_or_else(|| referrer.telegram_id.to_string());
+            let line = format!("{}. {} — {}", index + 1, name, referrer.referral_count);
+            text.push('\n');
+            text.push_str(&teloxide::utils::markdown::escape(&line));
+        }
+    }
+
+    let queue_stats = services.notification_queue_service.get_stats().await?;
+    let mut params = HashMap::new();
+    params.insert("pending".to_string(), queue_stats.pending.to_string());
+    params.insert("sent".to_string(), queue_stats.sent.to_string());
+    params.insert("failed".to_string(), queue_stats.failed.to_string());
+    params.insert("blocked".to_string(), queue_stats.blocked_users.to_string());
+    text.push_str("\n\n");
+    text.push_str(&teloxide::utils::markdown::escape(&i18n.t("commands.admin.notification_queue", language_code, Some(&params))));
+
+    let mut params = HashMap::new();
+    params.insert("in_use".to_string(), health_status.database_pool_in_use().to_string());
+    params.insert("max".to_string(), health_status.database_pool_max.to_string());
+    params.insert("idle".to_string(), health_status.database_pool_idle.to_string());
+    params.insert("acquire".to_string(), match health_status.database_acquire_ms {
+        Some(acquire_ms) => format!("{} ms", acquire_ms),
+        None => i18n.t("commands.admin.database_unavailable", language_code, None),
+    });
+    params.insert("slow".to_string(), health_status.database_slow_queries.to_string());
+    params.insert("slow_ms".to_string(), health_status.database_slow_query_ms.to_string());
+    text.push('\n');
+    text.push_str(&teloxide::utils::markdown::escape(&i18n.t("commands.admin.database_pool", language_code, Some(&params))));
+    if health_status.is_database_pool_exhausted() {
+        text.push('\n');
+        text.push_str(&teloxide::utils::markdown::escape(&i18n.t("commands.admin.database_pool_exhausted", language_code, None)));
+    }
+
+    let archived = services.archive_service.count_archived().await?;
+    if archived > 0 {
+        let mut params = HashMap::new();
+        params.insert("count".to_string(), archived.to_string());
+        text.push('\n');
+        text.push_str(&teloxide::utils::markdown::escape(&i18n.t("commands.admin.archived_events", language_code, Some(&params))));
+    }
+
+    let storage_stats = state_storage.get_stats().await?;
+    let mut params = HashMap::new();
+    params.insert("active".to_string(), storage_stats.scenarios_count.values().sum::<u32>().to_string());
+    params.insert("paused".to_string(), storage_stats.paused_contexts.to_string());
+    params.insert("expired".to_string(), storage_stats.expired_contexts.to_string());
+    params.insert("cleaned".to_string(), storage_stats.cleaned_up.to_string());
+    text.push_str("\n\n");
+    text.push_str(&teloxide::utils::markdown::escape(&i18n.t("commands.admin.conversations", language_code, Some(&params))));
+    let mut scenarios: Vec<_> = storage_stats.scenarios_count.into_iter().collect();
+    scenarios.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (scenario, count) in scenarios {
+        text.push('\n');
+        text.push_str(&teloxide::utils::markdown::escape(&format!("• {}: {}", scenario, count)));
+    }
+
+    text.push_str("\n\n");
+    text.push_str(&teloxide::utils::markdown::escape(&format_growth(interval, services, i18n, language_code).await?));
+    
+    let (other_interval, other_key) = match interval {
+        GrowthInterval::Day => (GrowthInterval::Week, "buttons.admin.growth_weekly"),
+        GrowthInterval::Week => (GrowthInterval::Day, "buttons.admin.growth_daily"),
+    };
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback(
+                "🔄 Refresh",
+                CallbackData::Admin(AdminAction::Stats(interval))
+            ),
+            InlineKeyboardButton::callback(
+                i18n.t(other_key, language_code, None),
+                CallbackData::Admin(AdminAction::Stats(other_interval))
+            ),
+        ],
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.backup", language_code, None),
+                CallbackData::Admin(AdminAction::Backup)
+            ),
+        ],
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.navigation.back", language_code, None),
+                CallbackData::Admin(AdminAction::Back)
+            ),
+        ],
+    ]);
+    
+    show_panel(&bot, chat_id, message_id, text, keyboard, Some(ParseMode::MarkdownV2)).await?;
+    
+    Ok(())
+}
+
+/// Describe the bot-wide growth per day or week: the total of each series over the
+/// period and a sparkline of its trend
+async fn format_growth(interval: GrowthInterval, services: &ServiceFactory, i18n: &I18n, language_code: &str) -> Result<String> {
+    let points = services.stats_service.growth(interval).await?;
+
+    let mut params = HashMap::new();
+    params.insert("count".to_string(), GROWTH_PERIODS.to_string());
+    let title_key = format!("commands.admin.growth.title_{}", interval.as_str());
+    let mut text = i18n.t(&title_key, language_code, Some(&params));
+
+    let series = [
+        ("new_users", points.iter().map(|point| point.new_users).collect::<Vec<_>>()),
+        ("events_created", points.iter().map(|point| point.events_created).collect()),
+        ("registrations", points.iter().map(|point| point.registrations).collect()),
+        ("cas_bans", points.iter().map(|point| point.cas_bans).collect()),
+    ];
+    for (key, values) in series {
+        let mut params = HashMap::new();
+        params.insert("label".to_string(), i18n.t(&format!("commands.admin.growth.{}", key), language_code, None));
+        params.insert("total".to_string(), values.iter().sum::<i64>().to_string());
+        params.insert("trend".to_string(), sparkline(&values));
+        text.push('\n');
+        text.push_str(&i18n.t("commands.admin.growth.line", language_code, Some(&params)));
+    }
+
+    Ok(text)
+}
+
+/// Show system settings panel
+async fn show_system_settings(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    _services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let text = format!(
+        "⚙️ **{}**\n\nSystem configuration:\n• Feature toggles\n• API settings\n• Cache management\n• Backup & restore",
+        i18n.t("commands.admin.system_settings", language_code, None)
+    );
+    
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback(
+                "🔧 Features",
+                CallbackData::Admin(AdminAction::Features)
+            ),
+            InlineKeyboardButton::callback(
+                "🗄️ Cache",
+                CallbackData::Admin(AdminAction::Cache)
+            ),
+        ],
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.templates", language_code, None),
+                CallbackData::Admin(AdminAction::Templates)
+            ),
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.google", language_code, None),
+                CallbackData::Admin(AdminAction::Google)
+            ),
+        ],
+        vec![
+            InlineKeyboardButton::callback(
+                i18n.t("buttons.navigation.back", language_code, None),
+                CallbackData::Admin(AdminAction::Back)
+            ),
+        ],
+    ]);
+    
+    show_panel(&bot, chat_id, message_id, text, keyboard, Some(ParseMode::MarkdownV2)).await?;
+    
+    Ok(())
+}
+
+/// Export the database and send it to the admin as a compressed file
+async fn send_backup(
+    bot: Bot,
+    chat_id: ChatId,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    bot.send_chat_action(chat_id, ChatAction::UploadDocument).await?;
+
+    let backup = match services.backup_service.create_backup().await {
+        Ok(backup) => backup,
+        Err(e) => {
+            error!(error = %e, "Failed to create database backup");
+            bot.send_message(chat_id, i18n.t("commands.admin.backup_error", language_code, None)).await?;
+            return Ok(());
+        }
+    };
+
+    let mut params = HashMap::new();
+    for (table, count) in &backup.row_counts {
+        params.insert(table.clone(), count.to_string());
+    }
+    let caption = format!(
+        "{}\n{}",
+        i18n.t("commands.admin.backup_created", language_code, None),
+        i18n.t("commands.admin.backup_contents", language_code, Some(&params)),
+    );
+
+    bot.send_document(chat_id, InputFile::memory(backup.data).file_name(backup.file_name))
+        .caption(caption)
+        .await?;
+
+    Ok(())
+}
+
+/// Export the users as CSV and send it to the admin
+async fn send_user_export(
+    bot: Bot,
+    chat_id: ChatId,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    bot.send_chat_action(chat_id, ChatAction::UploadDocument).await?;
+
+    let data = match services.user_service.export_users_csv().await {
+        Ok(data) => data,
+        Err(e) => {
+            error!(error = %e, "Failed to export users");
+            bot.send_message(chat_id, i18n.t("commands.admin.export_users_error", language_code, None)).await?;
+            return Ok(());
+        }
+    };
+
+    let file_name = format!("swingbuddy-users-{}.csv", chrono::Utc::now().format("%Y%m%d-%H%M%S"));
+    bot.send_document(chat_id, InputFile::memory(data).file_name(file_name))
+        .caption(i18n.t("commands.admin.export_users_created", language_code, None))
+        .await?;
+
+    Ok(())
+}
+
+/// Import the users from the CSV file an admin uploaded
+pub async fn handle_user_import_input(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    // Let the admin try again until they send a usable file
+    let Some(document) = msg.document() else {
+        bot.send_message(chat_id, i18n.t("commands.admin.import_users.not_a_file", &user_lang, None)).await?;
+        return Ok(());
+    };
+    if document.file.size > MAX_IMPORT_BYTES {
+        let mut params = HashMap::new();
+        params.insert("max".to_string(), (MAX_IMPORT_BYTES / 1024).to_string());
+        bot.send_message(chat_id, i18n.t("commands.admin.import_users.too_large", &user_lang, Some(&params))).await?;
+        return Ok(());
+    }
+
+    let file = bot.get_file(document.file.id.clone()).await?;
+    let mut data = Vec::with_capacity(file.size as usize);
+    bot.download_file(&file.path, &mut data).await
+        .map_err(|e| crate::utils::errors::SwingBuddyError::Telegram(e.into()))?;
+
+    let report = match services.user_service.import_users_csv(&data).await {
+        Ok(report) => report,
+        Err(crate::utils::errors::SwingBuddyError::InvalidInput(error)) => {
+            let mut params = HashMap::new();
+            params.insert("error".to_string(), error);
+            bot.send_message(chat_id, i18n.t("commands.admin.import_users.invalid_file", &user_lang, Some(&params))).await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+    state_storage.delete_context(user_id).await?;
+
+    let mut params = HashMap::new();
+    params.insert("created".to_string(), report.created.to_string());
+    params.insert("updated".to_string(), report.updated.to_string());
+    params.insert("skipped".to_string(), report.skipped.len().to_string());
+    let mut text = i18n.t("commands.admin.import_users.done", &user_lang, Some(&params));
+    if !report.skipped.is_empty() {
+        let lines = report.skipped.iter().take(MAX_REPORTED_LINES).map(usize::to_string).collect::<Vec<_>>();
+        params.insert("lines".to_string(), lines.join(", "));
+        text.push('\n');
+        text.push_str(&i18n.t("commands.admin.import_users.skipped_lines", &user_lang, Some(&params)));
+    }
+    bot.send_message(chat_id, text).await?;
+
+    let summary = format!("{} created, {} updated, {} skipped", report.created, report.updated, report.skipped.len());
+    services.admin_audit_service.record(user_id, "users_imported", Some(&summary)).await;
+
+    Ok(())
+}
+
+/// Show the bot-wide feature flags with buttons to switch them
+async fn show_feature_flags(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let text = i18n.t("commands.admin.feature_flags.title", language_code, None);
+
+    let mut keyboard = FeatureFlag::ALL.into_iter()
+        .map(|flag| {
+            let mark = if services.feature_flag_service.is_enabled(flag) { "✅" } else { "❌" };
+            let label = i18n.t(&format!("commands.admin.feature_flags.flags.{}", flag.as_str()), language_code, None);
+            vec![InlineKeyboardButton::callback(
+                format!("{} {}", mark, label),
+                CallbackData::Admin(AdminAction::FeatureToggle(flag)),
+            )]
+        })
+        .collect::<Vec<_>>();
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Settings),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Handle /stats command - show bot statistics
+pub async fn handle_stats(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /stats command");
+
+    // Check if user is admin
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    // Get user language
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    // Show statistics directly
+    show_statistics(bot, chat_id, None, GrowthInterval::Day, &services, &state_storage, &i18n, &user_lang).await?;
+
+    Ok(())
+}
+
+/// Handle /reload_config command - reload the settings from disk without a restart
+pub async fn handle_reload_config(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /reload_config command");
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let text = match services.config_service.reload().await {
+        Ok(reload) => {
+            let mut params = HashMap::new();
+            params.insert("overrides".to_string(), reload.flag_overrides.to_string());
+            let mut text = i18n.t("commands.admin.reload_config.done", &user_lang, Some(&params));
+
+            if !reload.restart_required.is_empty() {
+                let mut params = HashMap::new();
+                params.insert("sections".to_string(), reload.restart_required.join(", "));
+                text.push_str("\n\n");
+                text.push_str(&i18n.t("commands.admin.reload_config.restart_required", &user_lang, Some(&params)));
+            }
+
+            services.admin_audit_service.record(user_id, "config_reloaded", None).await;
+            text
+        }
+        Err(e) => {
+            warn!(user_id = user_id, error = %e, "Configuration reload failed");
+            let mut params = HashMap::new();
+            params.insert("error".to_string(), e.to_string());
+            i18n.t("commands.admin.reload_config.failed", &user_lang, Some(&params))
+        }
+    };
+
+    bot.send_message(chat_id, text).await?;
+    Ok(())
+}
+
+/// Handle /reload_translations command - reload the locale files and report changed keys
+pub async fn handle_reload_translations(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    mut i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /reload_translations command");
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let changes = match i18n.reload_translations().await {
+        Ok(changes) => changes,
+        Err(e) => {
+            warn!(user_id = user_id, error = %e, "Translation reload failed");
+            let mut params = HashMap::new();
+            params.insert("error".to_string(), e.to_string());
+            bot.send_message(chat_id, i18n.t("commands.admin.reload_translations.failed", &user_lang, Some(&params))).await?;
+            return Ok(());
+        }
+    };
+
+    let mut text = i18n.t("commands.admin.reload_translations.done", &user_lang, None);
+    if changes.is_empty() {
+        text.push('\n');
+        text.push_str(&i18n.t("commands.admin.reload_translations.unchanged", &user_lang, None));
+    }
+    for language in &changes {
+        let mut params = HashMap::new();
+        params.insert("language".to_string(), language.code.clone());
+        params.insert("added".to_string(), language.added.len().to_string());
+        params.insert("removed".to_string(), language.removed.len().to_string());
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.admin.reload_translations.language", &user_lang, Some(&params)));
+
+        for (kind, keys) in [("added", &language.added), ("removed", &language.removed)] {
+            if keys.is_empty() {
+                continue;
+            }
+            let mut listed = keys.iter().take(MAX_REPORTED_KEYS).cloned().collect::<Vec<_>>();
+            if keys.len() > MAX_REPORTED_KEYS {
+                listed.push("…".to_string());
+            }
+            params.insert("keys".to_string(), listed.join(", "));
+            text.push('\n');
+            text.push_str(&i18n.t(&format!("commands.admin.reload_translations.{}", kind), &user_lang, Some(&params)));
+        }
+    }
+    bot.send_message(chat_id, text).await?;
+
+    let summary = changes.iter()
+        .map(|language| format!("{}: +{} -{}", language.code, language.added.len(), language.removed.len()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    services.admin_audit_service.record(user_id, "translations_reloaded", (!summary.is_empty()).then_some(summary.as_str())).await;
+    Ok(())
+}
+
+/// Handle /groupstats command - show a group's activity over the last week
+pub async fn handle_group_stats(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /groupstats command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let Some(group) = services.group_service.get_group(chat_id.0).await? else {
+        bot.send_message(chat_id, i18n.t("commands.events.group_not_registered", "en", None)).await?;
+        return Ok(());
+    };
+
+    let stats = services.stats_service.weekly_group_stats(group.id).await?;
+
+    let mut params = HashMap::new();
+    params.insert("group".to_string(), group.title.clone());
+    params.insert("days".to_string(), STATS_PERIOD_DAYS.to_string());
+    params.insert("new_members".to_string(), stats.new_members.to_string());
+    params.insert("messages".to_string(), stats.messages.to_string());
+    params.insert("cas_bans".to_string(), stats.cas_bans.to_string());
+    params.insert("event_registrations".to_string(), stats.event_registrations.to_string());
+    let text = i18n.t("commands.group_stats.summary", &group.language_code, Some(&params));
+    bot.send_message(chat_id, text).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, "Group stats shown");
+
+    Ok(())
+}
+
+/// Handle /checkperms command - show which admin rights the bot has in the group
+pub async fn handle_check_permissions(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /checkperms command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let missing = services.auth_service.missing_bot_permissions(chat_id).await?;
+
+    let mut params = HashMap::new();
+    params.insert("permissions".to_string(), format_bot_permissions(&missing, &lang, &i18n));
+    let key = if missing.is_empty() { "commands.checkperms.ok" } else { "commands.checkperms.missing" };
+    bot.send_message(chat_id, i18n.t(key, &lang, Some(&params))).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, missing = missing.len(), "Bot permissions checked on demand");
+
+    Ok(())
+}
+
+/// Handle /caswhitelist command - list, add or remove users exempt from CAS auto-bans
+pub async fn handle_cas_whitelist(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /caswhitelist command");
+
+    // The whitelist applies to every group, so only bot admins manage it
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let mut parts = args.trim().splitn(3, char::is_whitespace);
+    let action = parts.next().unwrap_or("").to_lowercase();
+    let target = parts.next().unwrap_or("");
+    let reason = parts.next().map(str::trim).filter(|reason| !reason.is_empty()).map(str::to_string);
+
+    if action.is_empty() {
+        let entries = services.cas_service.get_whitelist().await?;
+        let text = if entries.is_empty() {
+            i18n.t("commands.cas_whitelist.empty", &user_lang, None)
+        } else {
+            let mut text = i18n.t("commands.cas_whitelist.title", &user_lang, None);
+            for entry in entries {
+                text.push_str(&format!(
+                    "\n• {} ({}){}",
+                    entry.telegram_id,
+                    entry.created_at.format("%d.%m.%Y"),
+                    entry.reason.map(|reason| format!(" — {}", reason)).unwrap_or_default(),
+                ));
+            }
+            text
+        };
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    // Targets are Telegram IDs, or usernames of users the bot knows
+    let target_id = match target.parse::<i64>() {
+        Ok(target_id) => Some(target_id),
+        Err(_) if target.starts_with('@') => services.user_service.get_user_by_username(target).await?
+            .map(|user| user.telegram_id),
+        Err(_) => None,
+    };
+
+    let mut params = HashMap::new();
+    params.insert("user".to_string(), target.to_string());
+
+    let key = match (action.as_str(), target_id) {
+        ("add", Some(target_id)) => {
+            services.cas_service.add_to_whitelist(target_id, user_id, reason).await?;
+            "commands.cas_whitelist.added"
+        }
+        ("remove", Some(target_id)) => {
+            if services.cas_service.remove_from_whitelist(target_id).await? {
+                "commands.cas_whitelist.removed"
+            } else {
+                "commands.cas_whitelist.not_listed"
+            }
+        }
+        ("add" | "remove", None) if !target.is_empty() => "commands.warnings.user_not_found",
+        _ => "commands.cas_whitelist.usage",
+    };
+
+    bot.send_message(chat_id, i18n.t(key, &user_lang, Some(&params))).await?;
+
+    info!(user_id = user_id, action = %action, target = %target, "CAS whitelist command handled");
+
+    Ok(())
+}
+
+/// Handle calendar management actions from the admin panel:
+/// listing, adding, editing and deleting the calendars shown in /events
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_calendar_management(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    user_id: i64,
+    action: AdminAction,
+    services: &ServiceFactory,
+    scenario_manager: &ScenarioManager,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    debug!(user_id = user_id, action = ?action, "Calendar management action");
+
+    match action {
+        AdminAction::Calendars => show_calendars(bot, chat_id, message_id, services, i18n, language_code).await?,
+        AdminAction::Calendar(calendar_id) => {
+            show_calendar(bot, chat_id, message_id, calendar_id, services, i18n, language_code).await?;
+        }
+        AdminAction::CalendarAdd => {
+            // Name, description and Google Calendar ID are asked for one by one
+            let mut context = ConversationContext::new(user_id);
+            scenario_manager.start_scenario(&mut context, "calendar_creation")?;
+            state_storage.save_context(&mut context).await?;
+
+            bot.send_message(chat_id, i18n.t("commands.admin.calendars.prompt_name", language_code, None)).await?;
+        }
+        AdminAction::CalendarEdit { calendar_id, field } => {
+            // The new value is asked for next
+            let mut context = ConversationContext::new(user_id);
+            scenario_manager.start_scenario(&mut context, "calendar_edit")?;
+            context.set_data("calendar_id", calendar_id)?;
+            context.set_data("field", field.as_str())?;
+            state_storage.save_context(&mut context).await?;
+
+            let prompt_key = format!("commands.admin.calendars.edit_{}", field.as_str());
+            bot.send_message(chat_id, i18n.t(&prompt_key, language_code, None)).await?;
+        }
+        AdminAction::CalendarDelete(calendar_id) => {
+            let Some(calendar) = services.calendar_service.get_calendar(calendar_id).await? else {
+                bot.send_message(chat_id, i18n.t("commands.admin.calendars.not_found", language_code, None)).await?;
+                return Ok(());
+            };
+
+            let mut params = HashMap::new();
+            params.insert("name".to_string(), calendar.name);
+            let keyboard = InlineKeyboardMarkup::new(vec![vec![
+                InlineKeyboardButton::callback(
+                    i18n.t("buttons.navigation.confirm", language_code, None),
+                    CallbackData::Admin(AdminAction::CalendarDeleteConfirm(calendar_id)),
+                ),
+                InlineKeyboardButton::callback(
+                    i18n.t("buttons.navigation.cancel", language_code, None),
+                    CallbackData::Admin(AdminAction::Calendar(calendar_id)),
+                ),
+            ]]);
+
+            bot.send_message(chat_id, i18n.t("commands.admin.calendars.delete_confirm", language_code, Some(&params)))
+                .reply_markup(keyboard)
+                .await?;
+        }
+        AdminAction::CalendarDeleteConfirm(calendar_id) => {
+            let key = if services.calendar_service.delete_calendar(calendar_id).await? {
+                info!(admin_id = user_id, calendar_id = calendar_id, "Calendar deleted by admin");
+                "commands.admin.calendars.deleted"
+            } else {
+                "commands.admin.calendars.not_found"
+            };
+            bot.send_message(chat_id, i18n.t(key, language_code, None)).await?;
+            show_calendars(bot, chat_id, message_id, services, i18n, language_code).await?;
+        }
+        _ => {
+            warn!(user_id = user_id, action = action.name(), "Not a calendar management action");
+        }
+    }
+
+    Ok(())
+}
+
+/// Show the calendars with a button to open each and one to add a new one
+async fn show_calendars(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let calendars = services.calendar_service.list_calendars().await?;
+
+    let mut text = i18n.t("commands.admin.calendars.title", language_code, None);
+    if calendars.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.admin.calendars.empty", language_code, None));
+    }
+
+    let mut keyboard = calendars.iter()
+        .map(|calendar| vec![InlineKeyboardButton::callback(
+            calendar.name.clone(),
+            CallbackData::Admin(AdminAction::Calendar(calendar.id)),
+        )])
+        .collect::<Vec<_>>();
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.admin.add_calendar", language_code, None),
+        CallbackData::Admin(AdminAction::CalendarAdd),
+    )]);
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Events),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Show one calendar with buttons to edit its fields or delete it
+async fn show_calendar(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    calendar_id: i64,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let Some(calendar) = services.calendar_service.get_calendar(calendar_id).await? else {
+        bot.send_message(chat_id, i18n.t("commands.admin.calendars.not_found", language_code, None)).await?;
+        return Ok(());
+    };
+
+    let not_set = i18n.t("commands.admin.not_set", language_code, None);
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), calendar.name.clone());
+    params.insert("description".to_string(), calendar.description.clone().unwrap_or_else(|| not_set.clone()));
+    params.insert("google_id".to_string(), calendar.google_calendar_id.clone().unwrap_or(not_set));
+    let text = i18n.t("commands.admin.calendars.details", language_code, Some(&params));
+
+    let edit_button = |field: CalendarField| InlineKeyboardButton::callback(
+        i18n.t(&format!("buttons.admin.calendar_{}", field.as_str()), language_code, None),
+        CallbackData::Admin(AdminAction::CalendarEdit { calendar_id: calendar.id, field }),
+    );
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![edit_button(CalendarField::Name), edit_button(CalendarField::Description)],
+        vec![edit_button(CalendarField::GoogleCalendarId)],
+        vec![InlineKeyboardButton::callback(
+            i18n.t("buttons.events.delete", language_code, None),
+            CallbackData::Admin(AdminAction::CalendarDelete(calendar.id)),
+        )],
+        vec![InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.back", language_code, None),
+            CallbackData::Admin(AdminAction::Calendars),
+        )],
+    ]);
+
+    show_panel(&bot, chat_id, message_id, text, keyboard, None).await?;
+
+    Ok(())
+}
+
+/// Collect the fields of a new calendar, one message per step. A `-` leaves
+/// the optional description and Google Calendar ID empty.
+pub async fn handle_calendar_creation_input(
+    bot: Bot,
+    msg: Message,
+    mut context: ConversationContext,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let input = msg.text().unwrap_or_default().trim();
+    let optional = (input != "-").then_some(input);
+
+    // Check each answer right away so the admin can correct it
+    let checked = match context.step.as_deref() {
+        Some("name_input") => validate_calendar_name(input).map(Some),
+        Some("description_input") => optional.map(validate_calendar_description).transpose(),
+        _ => optional.map(validate_google_calendar_id).transpose(),
+    };
+    let value = match checked {
+        Ok(value) => value,
+        Err(crate::utils::errors::SwingBuddyError::InvalidInput(error)) => {
+            let mut params = HashMap::new();
+            params.insert("error".to_string(), error);
+            bot.send_message(chat_id, i18n.t("commands.admin.calendars.invalid", &user_lang, Some(&params))).await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    match context.step.as_deref() {
+        Some("name_input") => {
+            context.set_data("name", value)?;
+            scenario_manager.next_step(&mut context, "description_input")?;
+            state_storage.save_context(&mut context).await?;
+            bot.send_message(chat_id, i18n.t("commands.admin.calendars.prompt_description", &user_lang, None)).await?;
+        }
+        Some("description_input") => {
+            context.set_data("description", value)?;
+            scenario_manager.next_step(&mut context, "google_id_input")?;
+            state_storage.save_context(&mut context).await?;
+            bot.send_message(chat_id, i18n.t("commands.admin.calendars.prompt_google_id", &user_lang, None)).await?;
+        }
+        _ => {
+            let Some(name) = context.get_string("name") else {
+                state_storage.delete_context(user_id).await?;
+                return Ok(());
+            };
+            let description = context.get_string("description");
+
+            let calendar = services.calendar_service.create_calendar(user_id, &name, description.as_deref(), value.as_deref()).await?;
+            state_storage.delete_context(user_id).await?;
+            services.admin_audit_service.record(user_id, "calendar_created", Some(&calendar.id.to_string())).await;
+
+            let mut params = HashMap::new();
+            params.insert("name".to_string(), calendar.name.clone());
+            bot.send_message(chat_id, i18n.t("commands.admin.calendars.created", &user_lang, Some(&params))).await?;
+            show_calendar(bot, chat_id, None, calendar.id, &services, &i18n, &user_lang).await?;
+
+            info!(admin_id = user_id, calendar_id = calendar.id, "Calendar added by admin");
+        }
+    }
+
+    Ok(())
+}
+
+/// Change the calendar field the admin picked to the value they sent. A `-`
+/// clears the optional description and Google Calendar ID.
+pub async fn handle_calendar_edit_input(
+    bot: Bot,
+    msg: Message,
+    context: ConversationContext,
+    services: ServiceFactory,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    let calendar_id = context.get_i64("calendar_id");
+    let field = context.get_string("field").and_then(|field| field.parse::<CalendarField>().ok());
+    let (Some(calendar_id), Some(field)) = (calendar_id, field) else {
+        state_storage.delete_context(user_id).await?;
+        return Ok(());
+    };
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let input = msg.text().unwrap_or_default().trim();
+    let value = (input != "-").then_some(input);
+    let key = match services.calendar_service.update_field(calendar_id, field, value).await {
+        Ok(Some(_)) => "commands.admin.calendars.updated",
+        Ok(None) => "commands.admin.calendars.not_found",
+        Err(crate::utils::errors::SwingBuddyError::InvalidInput(error)) => {
+            // Let the admin try again with a fixed value
+            let mut params = HashMap::new();
+            params.insert("error".to_string(), error);
+            bot.send_message(chat_id, i18n.t("commands.admin.calendars.invalid", &user_lang, Some(&params))).await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    state_storage.delete_context(user_id).await?;
+    services.admin_audit_service.record(user_id, "calendar_updated", Some(&format!("{}:{}", calendar_id, field.as_str()))).await;
+    bot.send_message(chat_id, i18n.t(key, &user_lang, None)).await?;
+    if key == "commands.admin.calendars.updated" {
+        show_calendar(bot, chat_id, None, calendar_id, &services, &i18n, &user_lang).await?;
+    }
+
+    info!(admin_id = user_id, calendar_id = calendar_id, field = field.as_str(), "Calendar edited by admin");
+
+    Ok(())
+}
+
+/// Handle notification template actions from the admin panel: listing the
+/// templates, showing one, and editing or resetting its text per language
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_template_management(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    user_id: i64,
+    action: AdminAction,
+    services: &ServiceFactory,
+    scenario_manager: &ScenarioManager,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    debug!(user_id = user_id, action = ?action, "Template management action");
+
+    match action {
+        AdminAction::Templates => show_templates(bot, chat_id, message_id, services, i18n, language_code).await?,
+        AdminAction::Template(template_key) => {
+            show_template(bot, chat_id, message_id, &template_key, services, i18n, language_code).await?;
+        }
+        AdminAction::TemplateEdit { key: template_key, language: template_lang } => {
+            // The new text is asked for next
+            let Some(placeholders) = NotificationService::template_placeholders(&template_key) else {
+                bot.send_message(chat_id, i18n.t("commands.admin.templates.not_found", language_code, None)).await?;
+                return Ok(());
+            };
+
+            let mut context = ConversationContext::new(user_id);
+            scenario_manager.start_scenario(&mut context, "template_edit")?;
+            context.set_data("template_key", &template_key)?;
+            context.set_data("template_lang", &template_lang)?;
+            state_storage.save_context(&mut context).await?;
+
+            let mut params = HashMap::new();
+            params.insert("key".to_string(), template_key);
+            params.insert("language".to_string(), template_lang);
+            params.insert("placeholders".to_string(), format_placeholders(&placeholders, i18n, language_code));
+            bot.send_message(chat_id, i18n.t("commands.admin.templates.prompt", language_code, Some(&params))).await?;
+        }
+        AdminAction::TemplateReset { key: template_key, language: template_lang } => {
+            if services.notification_template_service.reset(&template_key, &template_lang).await? {
+                services.admin_audit_service.record(user_id, "template_reset", Some(&format!("{}:{}", template_key, template_lang))).await;
+                info!(admin_id = user_id, template_key = %template_key, language_code = %template_lang, "Notification template reset by admin");
+            }
+            bot.send_message(chat_id, i18n.t("commands.admin.templates.reset", language_code, None)).await?;
+            show_template(bot, chat_id, message_id, &template_key, services, i18n, language_code).await?;
+        }
+        _ => {
+            warn!(user_id = user_id, action = action.name(), "Not a template management action");
+        }
+    }
+
+    Ok(())
+}
+
+/// Show the notification templates with a button to open each
+async fn show_templates(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let mut keys = services.notification_service.get_template_keys();
+    keys.sort();
+
+    let mut keyboard = keys.into_iter()
+        .map(|key| vec![InlineKeyboardButton::callback(key.clone(), CallbackData::Admin(AdminAction::Template(key)))])
+        .collect::<Vec<_>>();
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Settings),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, i18n.t("commands.admin.templates.title", language_code, None), InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Show one template's text in every supported language, with buttons to
+/// edit each and to reset the edited ones
+async fn show_template(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    template_key: &str,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let (Some(template), Some(placeholders)) = (
+        services.notification_service.get_template(template_key),
+        NotificationService::template_placeholders(template_key),
+    ) else {
+        bot.send_message(chat_id, i18n.t("commands.admin.templates.not_found", language_code, None)).await?;
+        return Ok(());
+    };
+
+    let overrides = services.notification_template_service.list_overrides().await?;
+    let is_edited = |lang: &str| overrides.iter().any(|o| o.template_key == template_key && o.language_code == lang);
+
+    // The texts contain placeholders themselves, so they are appended rather than passed as params
+    let mut params = HashMap::new();
+    params.insert("key".to_string(), template_key.to_string());
+    params.insert("placeholders".to_string(), format_placeholders(&placeholders, i18n, language_code));
+    let mut text = i18n.t("commands.admin.templates.details", language_code, Some(&params));
+
+    let mut keyboard = Vec::new();
+    for lang in i18n.supported_languages() {
+        let status_key = if is_edited(lang) { "commands.admin.templates.edited" } else { "commands.admin.templates.default" };
+        let content = template.content.get(lang).cloned()
+            .unwrap_or_else(|| i18n.t("commands.admin.not_set", language_code, None));
+        text.push_str(&format!("\n\n[{}] {}\n{}", lang, i18n.t(status_key, language_code, None), truncate_preview(&content)));
+
+        let mut lang_params = HashMap::new();
+        lang_params.insert("language".to_string(), lang.clone());
+        let mut row = vec![InlineKeyboardButton::callback(
+            i18n.t("buttons.admin.template_edit", language_code, Some(&lang_params)),
+            CallbackData::Admin(AdminAction::TemplateEdit { key: template_key.to_string(), language: lang.to_string() }),
+        )];
+        if is_edited(lang) {
+            row.push(InlineKeyboardButton::callback(
+                i18n.t("buttons.admin.template_reset", language_code, Some(&lang_params)),
+                CallbackData::Admin(AdminAction::TemplateReset { key: template_key.to_string(), language: lang.to_string() }),
+            ));
+        }
+        keyboard.push(row);
+    }
+    keyboard.push(vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Templates),
+    )]);
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Longest template text shown in the detail view, so all languages fit in one message
+const TEMPLATE_PREVIEW_LENGTH: usize = 1000;
+
+fn truncate_preview(content: &str) -> String {
+    if content.chars().count() <= TEMPLATE_PREVIEW_LENGTH {
+        content.to_string()
+    } else {
+        format!("{}…", content.chars().take(TEMPLATE_PREVIEW_LENGTH).collect::<String>())
+    }
+}
+
+fn format_placeholders(placeholders: &std::collections::BTreeSet<String>, i18n: &I18n, language_code: &str) -> String {
+    if placeholders.is_empty() {
+        i18n.t("commands.admin.templates.no_placeholders", language_code, None)
+    } else {
+        placeholders.iter().map(|p| format!("{{{}}}", p)).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Store the new text of the template the admin picked, after checking its placeholders
+pub async fn handle_template_edit_input(
     bot: Bot,
     msg: Message,
+    context: ConversationContext,
     services: ServiceFactory,
+    state_storage: StateStorage,
     i18n: I18n,
 ) -> Result<()> {
     let user = msg.from.as_ref().ok_or_else(|| {
         crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
     })?;
-
     let user_id = user.id.0 as i64;
     let chat_id = msg.chat.id;
 
-    debug!(user_id = user_id, "Processing /stats command");
+    let (Some(template_key), Some(template_lang)) = (context.get_string("template_key"), context.get_string("template_lang")) else {
+        state_storage.delete_context(user_id).await?;
+        return Ok(());
+    };
 
-    // Check if user is admin
     if !services.auth_service.can_access_admin_panel(user_id).await? {
-        let error_text = i18n.t("commands.admin.access_denied", "en", None);
-        bot.send_message(chat_id, error_text).await?;
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
         return Ok(());
     }
 
-    // Get user language
     let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
         user_data.language_code
     } else {
         "en".to_string()
     };
 
-    // Show statistics directly
-    show_statistics(bot, chat_id, &services, &i18n, &user_lang).await?;
+    let content = msg.text().unwrap_or_default().trim();
+    match services.notification_template_service.set(&template_key, &template_lang, content, user_id).await {
+        Ok(()) => {}
+        Err(crate::utils::errors::SwingBuddyError::InvalidInput(error)) => {
+            // Let the admin try again with a fixed text
+            let mut params = HashMap::new();
+            params.insert("error".to_string(), error);
+            bot.send_message(chat_id, i18n.t("commands.admin.templates.invalid", &user_lang, Some(&params))).await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    }
+
+    state_storage.delete_context(user_id).await?;
+    services.admin_audit_service.record(user_id, "template_edited", Some(&format!("{}:{}", template_key, template_lang))).await;
+    bot.send_message(chat_id, i18n.t("commands.admin.templates.updated", &user_lang, None)).await?;
+    show_template(bot, chat_id, None, &template_key, &services, &i18n, &user_lang).await?;
+
+    info!(admin_id = user_id, template_key = %template_key, language_code = %template_lang, "Notification template edited by admin");
 
     Ok(())
 }
 
-/// Handle calendar management
-pub async fn handle_calendar_management(
+/// Connect, check or disconnect the community Google account
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_google_account(
     bot: Bot,
     chat_id: ChatId,
+    message_id: Option<MessageId>,
     user_id: i64,
-    action: String,
+    action: AdminAction,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    debug!(user_id = user_id, action = ?action, "Google account action");
+
+    match action {
+        AdminAction::GoogleConnect => {
+            let url = match services.google_oauth_service.authorization_url(user_id) {
+                Ok(url) => reqwest::Url::parse(&url)?,
+                Err(e) => {
+                    warn!(error = %e, "Google OAuth is not configured");
+                    bot.send_message(chat_id, i18n.t("commands.admin.google.not_configured", language_code, None)).await?;
+                    return Ok(());
+                }
+            };
+
+            let keyboard = InlineKeyboardMarkup::new(vec![
+                vec![InlineKeyboardButton::url(i18n.t("buttons.admin.google_consent", language_code, None), url)],
+                vec![InlineKeyboardButton::callback(i18n.t("buttons.admin.google_check", language_code, None), CallbackData::Admin(AdminAction::Google))],
+            ]);
+            bot.send_message(chat_id, i18n.t("commands.admin.google.connect_prompt", language_code, None))
+                .reply_markup(keyboard)
+                .await?;
+        }
+        AdminAction::GoogleDisconnect => {
+            if services.google_oauth_service.disconnect().await? {
+                services.admin_audit_service.record(user_id, "google_disconnect", None).await;
+                info!(admin_id = user_id, "Google account disconnected by admin");
+            }
+            bot.send_message(chat_id, i18n.t("commands.admin.google.disconnected", language_code, None)).await?;
+            show_google_account(bot, chat_id, message_id, services, i18n, language_code).await?;
+        }
+        _ => show_google_account(bot, chat_id, message_id, services, i18n, language_code).await?,
+    }
+
+    Ok(())
+}
+
+/// Show which Google account is connected, with buttons to connect or disconnect one
+async fn show_google_account(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let back = vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.navigation.back", language_code, None),
+        CallbackData::Admin(AdminAction::Settings),
+    )];
+
+    if !services.google_oauth_service.is_configured() {
+        show_panel(&bot, chat_id, message_id, i18n.t("commands.admin.google.not_configured", language_code, None), InlineKeyboardMarkup::new(vec![back]), None).await?;
+        return Ok(());
+    }
+
+    let (text, keyboard) = match services.google_oauth_service.account().await? {
+        Some(account) => {
+            let mut params = HashMap::new();
+            params.insert("email".to_string(), account.email.clone()
+                .unwrap_or_else(|| i18n.t("commands.admin.not_set", language_code, None)));
+            params.insert("connected_by".to_string(), account.connected_by.to_string());
+            params.insert("connected_at".to_string(), account.connected_at.format("%Y-%m-%d %H:%M UTC").to_string());
+            params.insert("scope".to_string(), account.scope.replace(' ', "\n"));
+            (
+                i18n.t("commands.admin.google.connected", language_code, Some(&params)),
+                vec![
+                    vec![
+                        InlineKeyboardButton::callback(i18n.t("buttons.admin.google_reconnect", language_code, None), CallbackData::Admin(AdminAction::GoogleConnect)),
+                        InlineKeyboardButton::callback(i18n.t("buttons.admin.google_disconnect", language_code, None), CallbackData::Admin(AdminAction::GoogleDisconnect)),
+                    ],
+                    back,
+                ],
+            )
+        }
+        None => (
+            i18n.t("commands.admin.google.not_connected", language_code, None),
+            vec![
+                vec![InlineKeyboardButton::callback(i18n.t("buttons.admin.google_connect", language_code, None), CallbackData::Admin(AdminAction::GoogleConnect))],
+                back,
+            ],
+        ),
+    };
+
+    show_panel(&bot, chat_id, message_id, text, InlineKeyboardMarkup::new(keyboard), None).await?;
+
+    Ok(())
+}
+
+/// Buttons under the user detail view for correcting the profile or deleting the user
+fn profile_edit_keyboard(target_id: i64, i18n: &I18n, language_code: &str) -> InlineKeyboardMarkup {
+    let buttons = [ProfileField::Name, ProfileField::City, ProfileField::Language]
+        .into_iter()
+        .map(|field| InlineKeyboardButton::callback(
+            i18n.t(&format!("buttons.admin.edit_{}", field.as_str()), language_code, None),
+            CallbackData::Admin(AdminAction::UserEdit { telegram_id: target_id, field }),
+        ))
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(vec![
+        buttons,
+        vec![InlineKeyboardButton::callback(
+            i18n.t("buttons.admin.delete", language_code, None),
+            CallbackData::Admin(AdminAction::Delete { kind: TrashKind::Users, id: target_id }),
+        )],
+    ])
+}
+
+/// Handle the corrected value of a user's profile field
+pub async fn handle_user_profile_edit_input(
+    bot: Bot,
+    msg: Message,
+    context: ConversationContext,
     services: ServiceFactory,
-    _i18n: I18n,
+    state_storage: StateStorage,
+    i18n: I18n,
 ) -> Result<()> {
-    debug!(user_id = user_id, action = %action, "Calendar management action");
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    let target_id = context.get_i64("target_id");
+    let field = context.get_string("field").and_then(|field| field.parse::<ProfileField>().ok());
+    let (Some(target_id), Some(field)) = (target_id, field) else {
+        state_storage.delete_context(user_id).await?;
+        return Ok(());
+    };
 
-    // Verify admin access
     if !services.auth_service.can_access_admin_panel(user_id).await? {
+        state_storage.delete_context(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", "en", None)).await?;
         return Ok(());
     }
 
-    // Get user language
-    let _user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
         user_data.language_code
     } else {
         "en".to_string()
     };
 
-    match action.as_str() {
-        "add" => {
-            let text = "➕ **Add New Calendar**\n\nTo add a new calendar, please provide:\n• Calendar name\n• Description\n• Google Calendar ID (optional)";
-            bot.send_message(chat_id, text)
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        "edit" => {
-            let text = "✏️ **Edit Calendar**\n\nSelect a calendar to edit from the list below:";
-            // TODO: Show list of existing calendars
-            bot.send_message(chat_id, text)
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .await?;
-        }
-        _ => {
-            warn!(user_id = user_id, action = %action, "Unknown calendar management action");
+    let input = msg.text().unwrap_or_default();
+    let key = match services.user_service.update_profile_field(target_id, field, input, user_id).await {
+        Ok(_) => "commands.admin.edit_profile.updated",
+        Err(crate::utils::errors::SwingBuddyError::UserNotFound { .. }) => "commands.admin.user_not_found",
+        Err(crate::utils::errors::SwingBuddyError::InvalidInput(error)) => {
+            // Let the admin try again with a fixed value
+            let mut params = HashMap::new();
+            params.insert("error".to_string(), error);
+            bot.send_message(chat_id, i18n.t("commands.admin.edit_profile.invalid", &user_lang, Some(&params))).await?;
+            return Ok(());
         }
+        Err(e) => return Err(e),
+    };
+
+    state_storage.delete_context(user_id).await?;
+    if key == "commands.admin.edit_profile.updated" {
+        services.admin_audit_service.record(user_id, "user_profile_edited", Some(&format!("{}:{}", target_id, field.as_str()))).await;
     }
 
+    let mut params = HashMap::new();
+    params.insert("telegram_id".to_string(), target_id.to_string());
+    bot.send_message(chat_id, i18n.t(key, &user_lang, Some(&params))).await?;
+
     Ok(())
 }
 
@@ -494,4 +3324,201 @@ pub async fn handle_user_moderation(
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Handle /user command - show user details with private notes
+pub async fn handle_user_detail(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /user command");
+
+    // Check if user is admin
+    if !services.auth_service.can_view_user_notes(user_id).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    // Get user language
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    // Notes are private, never show them in group chats
+    if !chat_id.is_user() {
+        let text = i18n.t("commands.notes.private_only", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    let Ok(target_id) = args.trim().parse::<i64>() else {
+        let text = i18n.t("commands.admin.user_detail_usage", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    let (target, notes) = match services.note_service.get_user_with_notes(target_id).await {
+        Ok(result) => result,
+        Err(crate::utils::errors::SwingBuddyError::UserNotFound { .. }) => {
+            let text = i18n.t("commands.admin.user_not_found", &user_lang, None);
+            bot.send_message(chat_id, text).await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    let none_text = i18n.t("commands.admin.not_set", &user_lang, None);
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), target.first_name.clone().unwrap_or_else(|| none_text.clone()));
+    params.insert("telegram_id".to_string(), target.telegram_id.to_string());
+    params.insert("username".to_string(), target.username.clone().map(|u| format!("@{}", u)).unwrap_or_else(|| none_text.clone()));
+    params.insert("language".to_string(), target.language_code.clone());
+    params.insert("location".to_string(), target.location.clone().unwrap_or_else(|| none_text.clone()));
+    params.insert("banned".to_string(), i18n.t(if target.is_banned { "commands.admin.yes" } else { "commands.admin.no" }, &user_lang, None));
+    params.insert("joined".to_string(), target.created_at.format("%Y-%m-%d").to_string());
+
+    let mut text = i18n.t("commands.admin.user_detail", &user_lang, Some(&params));
+    text.push_str("\n\n");
+    text.push_str(&i18n.t("commands.notes.title", &user_lang, None));
+    if notes.is_empty() {
+        text.push('\n');
+        text.push_str(&i18n.t("commands.notes.none", &user_lang, None));
+    }
+    for note in &notes {
+        text.push('\n');
+        text.push_str(&super::notes::format_note_line(&i18n, &user_lang, note));
+    }
+
+    bot.send_message(chat_id, text)
+        .reply_markup(profile_edit_keyboard(target.telegram_id, &i18n, &user_lang))
+        .await?;
+
+    info!(admin_id = user_id, target_user_id = target.id, "Admin viewed user details");
+
+    Ok(())
+}
+
+/// Longest data dump shown for a context, in characters, to stay within one message
+const STATE_DATA_MAX_CHARS: usize = 3000;
+
+/// Handle /state <telegram_id>: show a user's conversation contexts, each with
+/// a button to clear it when the user is stuck
+pub async fn handle_state(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /state command");
+
+    if !services.auth_service.can_access_admin_panel(user_id).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    // Contexts hold what users typed, never show them in group chats
+    if !chat_id.is_user() {
+        bot.send_message(chat_id, i18n.t("commands.admin.state_private_only", &user_lang, None)).await?;
+        return Ok(());
+    }
+
+    let Ok(target_id) = args.trim().parse::<i64>() else {
+        bot.send_message(chat_id, i18n.t("commands.admin.state_usage", &user_lang, None)).await?;
+        return Ok(());
+    };
+
+    let contexts = state_storage.load_user_contexts(target_id).await?;
+    if contexts.is_empty() {
+        let mut params = HashMap::new();
+        params.insert("telegram_id".to_string(), target_id.to_string());
+        bot.send_message(chat_id, i18n.t("commands.admin.state_none", &user_lang, Some(&params))).await?;
+        return Ok(());
+    }
+
+    for context in &contexts {
+        let none_text = i18n.t("commands.admin.not_set", &user_lang, None);
+        let mut data = serde_json::to_string_pretty(&context.data).unwrap_or_default();
+        if data.chars().count() > STATE_DATA_MAX_CHARS {
+            data = data.chars().take(STATE_DATA_MAX_CHARS).collect::<String>() + "…";
+        }
+
+        let mut params = HashMap::new();
+        params.insert("telegram_id".to_string(), target_id.to_string());
+        params.insert("chat".to_string(), match context.chat_id {
+            Some(context_chat_id) => context_chat_id.to_string(),
+            None => i18n.t("commands.admin.state_private_chat", &user_lang, None),
+        });
+        params.insert("scenario".to_string(), context.scenario.clone().unwrap_or_else(|| none_text.clone()));
+        params.insert("step".to_string(), context.step.clone().unwrap_or_else(|| none_text.clone()));
+        params.insert("history".to_string(), if context.history.is_empty() { none_text.clone() } else { context.history.join(" → ") });
+        params.insert("version".to_string(), context.version.to_string());
+        params.insert("updated_at".to_string(), context.updated_at.format("%Y-%m-%d %H:%M UTC").to_string());
+        params.insert("expires_at".to_string(), context.expires_at
+            .map(|expires_at| expires_at.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| none_text.clone()));
+        params.insert("data".to_string(), data);
+
+        let callback_data = CallbackData::Admin(AdminAction::ClearState { telegram_id: target_id, chat_id: context.chat_id });
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(i18n.t("buttons.admin.clear_state", &user_lang, None), callback_data),
+        ]]);
+
+        bot.send_message(chat_id, i18n.t("commands.admin.state_context", &user_lang, Some(&params)))
+            .reply_markup(keyboard)
+            .await?;
+    }
+
+    info!(admin_id = user_id, target_user_id = target_id, contexts = contexts.len(), "Admin viewed conversation state");
+
+    Ok(())
+}
+
+/// Clear a conversation context from /state, the one in a group chat or the private one
+#[allow(clippy::too_many_arguments)]
+async fn clear_state(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    target_id: i64,
+    context_chat_id: Option<i64>,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+    user_lang: &str,
+) -> Result<()> {
+    state_storage.delete_scoped_context((target_id, context_chat_id)).await?;
+    info!(admin_id = user_id, target_user_id = target_id, chat_id = ?context_chat_id, "Admin cleared conversation state");
+
+    let mut params = HashMap::new();
+    params.insert("telegram_id".to_string(), target_id.to_string());
+    bot.send_message(chat_id, i18n.t("commands.admin.state_cleared", user_lang, Some(&params))).await?;
+    Ok(())
+}
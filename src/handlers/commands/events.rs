@@ -1,17 +1,31 @@
 //! Event command handlers
 
 use std::collections::HashMap;
-use teloxide::{Bot, types::{Message, InlineKeyboardMarkup, InlineKeyboardButton, ChatId}, prelude::*};
-use tracing::{info, debug};
+use teloxide::{Bot, types::{Me, Message, InlineKeyboardMarkup, InlineKeyboardButton, ChatId, KeyboardButton, KeyboardMarkup, ButtonRequest, WebAppInfo}, prelude::*};
+use tracing::{info, debug, error};
 use crate::utils::errors::Result;
-use crate::services::ServiceFactory;
+use crate::services::{RegistrationOutcome, ServiceFactory, WebAppAction};
 use crate::i18n::I18n;
-use crate::models::event::Event;
+use crate::state::{ConversationContext, ScenarioManager, StateStorage};
+use crate::handlers::callbacks::wizard;
+use crate::handlers::callbacks::{AdminAction, CallbackData};
+use crate::models::event::{CreateEventRequest, Event};
+use crate::models::group::{GroupFeature, QuietHours};
+use crate::models::trash::TrashKind;
+use crate::services::google::calendar_sharing_url;
+use crate::services::sheet_export::spreadsheet_url;
+use crate::handlers::commands::start::EVENTS_START_PAYLOAD;
+use super::moderation;
 
-/// Handle /events command - list upcoming events in private chats
+/// Events listed when /events is sent in a group
+const GROUP_EVENTS_LIMIT: i64 = 3;
+
+/// Handle /events command - browse the calendars in private chats, or list
+/// the group's next events in a group
 pub async fn handle_events_list(
     bot: Bot,
     msg: Message,
+    me: Me,
     services: ServiceFactory,
     i18n: I18n,
 ) -> Result<()> {
@@ -24,11 +38,8 @@ pub async fn handle_events_list(
 
     debug!(user_id = user_id, chat_id = ?chat_id, "Processing /events command");
 
-    // Only allow in private chats
     if !chat_id.is_user() {
-        let text = i18n.t("messages.errors.invalid_command", "en", None);
-        bot.send_message(chat_id, text).await?;
-        return Ok(());
+        return show_group_events(bot, chat_id, &me, &services, &i18n).await;
     }
 
     // Get user language
@@ -39,47 +50,188 @@ pub async fn handle_events_list(
     };
 
     // Show available calendars as inline keyboard buttons
-    show_calendar_list(bot, chat_id, &services, &i18n, &user_lang).await?;
+    show_calendar_list(bot.clone(), chat_id, &services, &i18n, &user_lang).await?;
+
+    if let Some(url) = services.webapp_service.url() {
+        offer_webapp(&bot, chat_id, &url, &i18n, &user_lang).await?;
+    }
+
+    Ok(())
+}
+
+/// Offer the Mini App for browsing events. It has to be a reply keyboard
+/// button: only Mini Apps opened from one can send registrations back to the
+/// chat as `web_app_data`.
+async fn offer_webapp(bot: &Bot, chat_id: ChatId, url: &str, i18n: &I18n, language_code: &str) -> Result<()> {
+    let button = KeyboardButton::new(i18n.t("buttons.events.open_webapp", language_code, None))
+        .request(ButtonRequest::WebApp(WebAppInfo { url: reqwest::Url::parse(url)? }));
+
+    bot.send_message(chat_id, i18n.t("commands.events.webapp_prompt", language_code, None))
+        .reply_markup(KeyboardMarkup::new(vec![vec![button]]).resize_keyboard())
+        .await?;
+
+    Ok(())
+}
+
+/// Handle a registration sent back from the Mini App
+pub async fn handle_webapp_action(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    action: WebAppAction,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    debug!(user_id = user_id, action = ?action, "Mini App action");
+
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let outcome = match action {
+        WebAppAction::Register { event_id } => services.event_service.register(user_id, event_id).await?,
+        WebAppAction::Unregister { event_id } => services.event_service.unregister(user_id, event_id).await?,
+    };
+    send_registration_outcome(&bot, chat_id, &outcome, &i18n, &user_lang).await
+}
+
+/// Handle /nextevent command - show the nearest upcoming event with a register
+/// button. In a group it is the group's next event, otherwise the next one in
+/// the user's city.
+pub async fn handle_next_event(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /nextevent command");
+
+    let user_data = services.user_service.get_user_by_telegram_id(user_id).await?;
+    let user_lang = user_data.as_ref().map_or_else(|| "en".to_string(), |user| user.language_code.clone());
+    let city = user_data.and_then(|user| user.location);
+
+    let mut next = None;
+    if !chat_id.is_user() {
+        if let Some(group) = services.group_service.get_group(chat_id.0).await? {
+            next = services.event_service.get_next_event(None, Some(group.id)).await?;
+        }
+    }
+    if next.is_none() {
+        next = services.event_service.get_next_event(city.as_deref(), None).await?;
+    }
+
+    let Some((event, participant_count)) = next else {
+        let text = match &city {
+            Some(city) => {
+                let mut params = HashMap::new();
+                params.insert("city".to_string(), city.clone());
+                i18n.t("commands.events.no_next_event_in_city", &user_lang, Some(&params))
+            }
+            None => i18n.t("commands.events.no_events", &user_lang, None),
+        };
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    let mut params = HashMap::new();
+    params.insert("title".to_string(), event.title.clone());
+    params.insert("date".to_string(), event.event_date.format("%Y-%m-%d %H:%M UTC").to_string());
+    params.insert("location".to_string(), event.location.clone().unwrap_or_else(|| "TBD".to_string()));
+    params.insert("current".to_string(), participant_count.to_string());
+    params.insert("max".to_string(), event.max_participants.map(|m| m.to_string()).unwrap_or_else(|| "∞".to_string()));
+
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(
+            i18n.t("buttons.events.register", &user_lang, None),
+            CallbackData::EventRegister(event.id)
+        ),
+    ]]);
+
+    bot.send_message(chat_id, i18n.t("commands.events.next_event", &user_lang, Some(&params)))
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// List the next events of a group, or else of the city most of its members
+/// live in, with a button to browse the rest in a private chat
+async fn show_group_events(bot: Bot, chat_id: ChatId, me: &Me, services: &ServiceFactory, i18n: &I18n) -> Result<()> {
+    let group = services.group_service.get_group(chat_id.0).await?;
+    let lang = group.as_ref().map_or_else(|| "en".to_string(), |group| group.language_code.clone());
+
+    let mut events = Vec::new();
+    if let Some(group) = &group {
+        events = services.event_service.get_next_events(None, Some(group.id), GROUP_EVENTS_LIMIT).await?;
+        if events.is_empty() {
+            if let Some(city) = services.group_service.get_city(group.id).await? {
+                events = services.event_service.get_next_events(Some(&city), None, GROUP_EVENTS_LIMIT).await?;
+            }
+        }
+    }
+
+    let text = if events.is_empty() {
+        i18n.t("commands.events.group_no_events", &lang, None)
+    } else {
+        let lines: Vec<String> = events.iter()
+            .map(|event| match &event.location {
+                Some(location) => format!("• {} {} · {}", event.event_date.format("%d.%m %H:%M"), event.title, location),
+                None => format!("• {} {}", event.event_date.format("%d.%m %H:%M"), event.title),
+            })
+            .collect();
+        format!("{}\n{}", i18n.t("commands.events.group_title", &lang, None), lines.join("\n"))
+    };
+
+    let mut message = bot.send_message(chat_id, text);
+    if let Some(bot_username) = &me.username {
+        let url = reqwest::Url::parse(&format!("https://t.me/{}?start={}", bot_username, EVENTS_START_PAYLOAD))?;
+        message = message.reply_markup(InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::url(i18n.t("buttons.events.open_private", &lang, None), url),
+        ]]));
+    }
+    let reply = message.await?;
+    moderation::schedule_cleanup(services, chat_id, reply.id).await;
 
     Ok(())
 }
 
 /// Show available calendars as inline keyboard buttons
-async fn show_calendar_list(
+pub async fn show_calendar_list(
     bot: Bot,
     chat_id: ChatId,
-    _services: &ServiceFactory,
+    services: &ServiceFactory,
     i18n: &I18n,
     language_code: &str,
 ) -> Result<()> {
+    let calendars = services.calendar_service.list_calendars().await?;
+    if calendars.is_empty() {
+        bot.send_message(chat_id, i18n.t("commands.events.no_calendars", language_code, None)).await?;
+        return Ok(());
+    }
+
     let title_text = i18n.t("commands.events.list_title", language_code, None);
-    
-    // Create keyboard with available calendars
-    let keyboard = InlineKeyboardMarkup::new(vec![
-        vec![
-            InlineKeyboardButton::callback(
-                "📅 Swing Dance Events",
-                "calendar:swing_events"
-            ),
-        ],
-        vec![
-            InlineKeyboardButton::callback(
-                "🎭 Workshops & Classes",
-                "calendar:workshops"
-            ),
-        ],
-        vec![
-            InlineKeyboardButton::callback(
-                "🎉 Social Events",
-                "calendar:social"
-            ),
-        ],
-    ]);
-    
+
+    // One button per calendar
+    let keyboard = InlineKeyboardMarkup::new(calendars.iter()
+        .map(|calendar| vec![InlineKeyboardButton::callback(
+            calendar.name.clone(),
+            CallbackData::Calendar(Some(calendar.id)),
+        )])
+        .collect::<Vec<_>>());
+
     bot.send_message(chat_id, title_text)
         .reply_markup(keyboard)
         .await?;
-    
+
     Ok(())
 }
 
@@ -88,12 +240,12 @@ pub async fn handle_calendar_callback(
     bot: Bot,
     chat_id: ChatId,
     user_id: i64,
-    calendar_type: String,
+    calendar_id: i64,
     services: ServiceFactory,
     i18n: I18n,
 ) -> Result<()> {
-    debug!(user_id = user_id, calendar_type = %calendar_type, "User selected calendar");
-    
+    debug!(user_id = user_id, calendar_id = calendar_id, "User selected calendar");
+
     // Get user language
     let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
         user_data.language_code
@@ -102,7 +254,7 @@ pub async fn handle_calendar_callback(
     };
 
     // Show calendar description and "Add to Google Calendar" button
-    show_calendar_details(bot, chat_id, &calendar_type, &services, &i18n, &user_lang).await?;
+    show_calendar_details(bot, chat_id, calendar_id, &services, &i18n, &user_lang).await?;
 
     Ok(())
 }
@@ -111,69 +263,68 @@ pub async fn handle_calendar_callback(
 async fn show_calendar_details(
     bot: Bot,
     chat_id: ChatId,
-    calendar_type: &str,
+    calendar_id: i64,
     services: &ServiceFactory,
     i18n: &I18n,
     language_code: &str,
 ) -> Result<()> {
-    let (title, description) = match calendar_type {
-        "swing_events" => (
-            "📅 Swing Dance Events",
-            "Regular swing dance events, milongas, and dance parties. Perfect for social dancing and meeting other dancers in the community."
-        ),
-        "workshops" => (
-            "🎭 Workshops & Classes",
-            "Educational workshops, dance classes, and skill-building sessions. Learn new moves, techniques, and styles from experienced instructors."
-        ),
-        "social" => (
-            "🎉 Social Events",
-            "Community gatherings, meetups, and special celebrations. Connect with fellow dancers outside of regular dance events."
-        ),
-        _ => ("📅 Events", "Dance community events and activities."),
+    let Some(calendar) = services.calendar_service.get_calendar(calendar_id).await? else {
+        bot.send_message(chat_id, i18n.t("commands.events.calendar_not_found", language_code, None)).await?;
+        return Ok(());
     };
 
-    let message_text = format!("**{}**\n\n{}", title, description);
-    
+    let mut message_text = calendar.name.clone();
+    if let Some(description) = &calendar.description {
+        message_text.push_str("\n\n");
+        message_text.push_str(description);
+    }
+
     // Create keyboard - only add Google Calendar button if feature is enabled
     let mut keyboard_rows = vec![];
-    
-    // Check if Google Calendar integration is enabled
+
+    // A calendar without its own Google Calendar links to the configured one
     if services.google_service.is_enabled() {
-        if let Ok(calendar_url) = services.google_service.generate_calendar_sharing_url() {
+        let calendar_url = match &calendar.google_calendar_id {
+            Some(google_calendar_id) => Ok(calendar_sharing_url(google_calendar_id)),
+            None => services.google_service.generate_calendar_sharing_url(),
+        };
+        if let Ok(calendar_url) = calendar_url {
             if let Ok(parsed_url) = reqwest::Url::parse(&calendar_url) {
                 keyboard_rows.push(vec![
                     InlineKeyboardButton::url(
-                        "📅 Add to Google Calendar",
+                        i18n.t("buttons.events.add_to_google_calendar", language_code, None),
                         parsed_url
                     ),
                 ]);
             }
         }
     }
-    
+
     // Always add back button
     keyboard_rows.push(vec![
         InlineKeyboardButton::callback(
             i18n.t("buttons.navigation.back", language_code, None),
-            "calendar:back"
+            CallbackData::Calendar(None)
         ),
     ]);
-    
+
     let keyboard = InlineKeyboardMarkup::new(keyboard_rows);
-    
+
     bot.send_message(chat_id, message_text)
         .reply_markup(keyboard)
-        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
         .await?;
-    
+
     Ok(())
 }
 
-/// Handle event creation (admin only)
+/// Handle event creation (admin only): walks the admin through the event
+/// creation wizard
 pub async fn handle_create_event(
     bot: Bot,
     msg: Message,
     services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
     i18n: I18n,
 ) -> Result<()> {
     let user = msg.from.as_ref().ok_or_else(|| {
@@ -192,15 +343,61 @@ pub async fn handle_create_event(
         return Ok(());
     }
 
-    // Get user language
-    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
-        user_data.language_code
-    } else {
-        "en".to_string()
+    wizard::start_wizard(bot, chat_id, user_id, "event_creation", services, scenario_manager, state_storage, i18n).await
+}
+
+/// Create the event the creation wizard collected, unless the admin chose to
+/// cancel at the confirmation step. Events created in a group belong to it.
+pub async fn finish_event_creation(
+    bot: &Bot,
+    chat_id: ChatId,
+    context: &ConversationContext,
+    step: &str,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    if step != "create" {
+        bot.send_message(chat_id, i18n.t("messages.wizard.cancelled", language_code, None)).await?;
+        return Ok(());
+    }
+
+    let event_date = context.get_string("date")
+        .and_then(|date| chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok())
+        .zip(context.get_string("time").and_then(|time| chrono::NaiveTime::parse_from_str(&time, "%H:%M").ok()))
+        .map(|(date, time)| date.and_time(time).and_utc());
+    let (Some(title), Some(event_date)) = (context.get_string("title"), event_date) else {
+        bot.send_message(chat_id, i18n.t("commands.events.create_error", language_code, None)).await?;
+        return Ok(());
     };
 
-    let create_title = i18n.t("commands.events.create_title", &user_lang, None);
-    bot.send_message(chat_id, format!("✨ {}\n\nThis feature will be available in the admin panel.", create_title)).await?;
+    let created_by = services.user_service.get_user_by_telegram_id(context.user_id).await?.map(|user| user.id);
+    let group_id = match context.chat_id {
+        Some(group_chat_id) => services.group_service.get_group(group_chat_id).await?.map(|group| group.id),
+        None => None,
+    };
+
+    let request = CreateEventRequest {
+        title: title.clone(),
+        description: context.get_string("description"),
+        event_date,
+        location: context.get_string("location"),
+        max_participants: None,
+        created_by,
+        group_id,
+    };
+    match services.event_service.create_event(request).await {
+        Ok(event) => {
+            info!(user_id = context.user_id, event_id = event.id, "Event created with the wizard");
+            let mut params = HashMap::new();
+            params.insert("event_name".to_string(), title);
+            bot.send_message(chat_id, i18n.t("commands.events.create_success", language_code, Some(&params))).await?;
+        }
+        Err(e) => {
+            error!(user_id = context.user_id, error = %e, "Failed to create event");
+            bot.send_message(chat_id, i18n.t("commands.events.create_error", language_code, None)).await?;
+        }
+    }
 
     Ok(())
 }
@@ -252,17 +449,8 @@ pub async fn handle_event_register_callback(
         "en".to_string()
     };
 
-    // TODO: Implement actual event registration logic with database
-    // For now, show a success message
-    let mut params = HashMap::new();
-    params.insert("event_name".to_string(), format!("Event #{}", event_id));
-    
-    let success_text = i18n.t("commands.events.register_success", &user_lang, Some(&params));
-    bot.send_message(chat_id, success_text).await?;
-
-    info!(user_id = user_id, event_id = event_id, "User registered for event");
-
-    Ok(())
+    let outcome = services.event_service.register(user_id, event_id).await?;
+    send_registration_outcome(&bot, chat_id, &outcome, &i18n, &user_lang).await
 }
 
 /// Handle event unregistration callback
@@ -283,15 +471,33 @@ pub async fn handle_event_unregister_callback(
         "en".to_string()
     };
 
-    // TODO: Implement actual event unregistration logic with database
-    // For now, show a success message
-    let mut params = HashMap::new();
-    params.insert("event_name".to_string(), format!("Event #{}", event_id));
-    
-    let success_text = i18n.t("commands.events.unregister_success", &user_lang, Some(&params));
-    bot.send_message(chat_id, success_text).await?;
+    let outcome = services.event_service.unregister(user_id, event_id).await?;
+    send_registration_outcome(&bot, chat_id, &outcome, &i18n, &user_lang).await
+}
 
-    info!(user_id = user_id, event_id = event_id, "User unregistered from event");
+/// Tell the user how their registration or unregistration went
+async fn send_registration_outcome(
+    bot: &Bot,
+    chat_id: ChatId,
+    outcome: &RegistrationOutcome,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    let (key, event) = match outcome {
+        RegistrationOutcome::Registered(event) => ("commands.events.register_success", Some(event)),
+        RegistrationOutcome::Unregistered(event) => ("commands.events.unregister_success", Some(event)),
+        RegistrationOutcome::AlreadyRegistered(event) => ("commands.events.already_registered", Some(event)),
+        RegistrationOutcome::NotRegistered(event) => ("commands.events.not_registered", Some(event)),
+        RegistrationOutcome::Full(event) => ("commands.events.event_full", Some(event)),
+        RegistrationOutcome::EventNotFound => ("commands.events.not_found", None),
+        RegistrationOutcome::UserNotFound => ("commands.events.register_error", None),
+    };
+
+    let mut params = HashMap::new();
+    if let Some(event) = event {
+        params.insert("event_name".to_string(), event.title.clone());
+    }
+    bot.send_message(chat_id, i18n.t(key, language_code, Some(&params))).await?;
 
     Ok(())
 }
@@ -301,8 +507,8 @@ pub async fn show_event_details(
     bot: Bot,
     chat_id: ChatId,
     event: &Event,
-    _user_id: i64,
-    _services: &ServiceFactory,
+    user_id: i64,
+    services: &ServiceFactory,
     i18n: &I18n,
     language_code: &str,
 ) -> Result<()> {
@@ -318,24 +524,33 @@ pub async fn show_event_details(
     let details_text = i18n.t("commands.events.event_details", language_code, Some(&params));
     
     // Create registration keyboard
-    let keyboard = InlineKeyboardMarkup::new(vec![
+    let mut keyboard = vec![
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("buttons.events.register", language_code, None),
-                format!("event_register:{}", event.id)
+                CallbackData::EventRegister(event.id)
             ),
             InlineKeyboardButton::callback(
                 i18n.t("buttons.events.unregister", language_code, None),
-                format!("event_unregister:{}", event.id)
+                CallbackData::EventUnregister(event.id)
             ),
         ],
-        vec![
+    ];
+    if services.auth_service.can_access_admin_panel(user_id).await? {
+        keyboard.push(vec![
             InlineKeyboardButton::callback(
-                i18n.t("buttons.navigation.back", language_code, None),
-                "calendar:back"
+                i18n.t("buttons.admin.delete", language_code, None),
+                CallbackData::Admin(AdminAction::Delete { kind: TrashKind::Events, id: event.id })
             ),
-        ],
+        ]);
+    }
+    keyboard.push(vec![
+        InlineKeyboardButton::callback(
+            i18n.t("buttons.navigation.back", language_code, None),
+            CallbackData::Calendar(None)
+        ),
     ]);
+    let keyboard = InlineKeyboardMarkup::new(keyboard);
     
     bot.send_message(chat_id, details_text)
         .reply_markup(keyboard)
@@ -343,4 +558,471 @@ pub async fn show_event_details(
         .await?;
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Handle /doorlist command - list event participants with private notes
+pub async fn handle_door_list(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /doorlist command");
+
+    // Get user language
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    // Door list contains private notes, only allow in private chats
+    if !chat_id.is_user() {
+        let text = i18n.t("commands.notes.private_only", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    let Ok(event_id) = args.trim().parse::<i64>() else {
+        let text = i18n.t("commands.events.door_list_usage", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    // Admins and the event organizer only; others are denied whether the event exists or not
+    if !services.auth_service.can_view_user_notes(user_id).await? {
+        let is_organizer = match services.event_service.get_event(event_id).await? {
+            Some(event) => services.user_service.is_event_organizer(user_id, &event).await?,
+            None => false,
+        };
+        if !is_organizer {
+            let error_text = i18n.t("commands.admin.access_denied", &user_lang, None);
+            bot.send_message(chat_id, error_text).await?;
+            return Ok(());
+        }
+    }
+
+    let (event, entries) = match services.note_service.get_door_list(event_id).await {
+        Ok(result) => result,
+        Err(crate::utils::errors::SwingBuddyError::EventNotFound { .. }) => {
+            let text = i18n.t("commands.events.not_found", &user_lang, None);
+            bot.send_message(chat_id, text).await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut params = HashMap::new();
+    params.insert("title".to_string(), event.title.clone());
+    params.insert("date".to_string(), event.event_date.format("%Y-%m-%d %H:%M UTC").to_string());
+    params.insert("count".to_string(), entries.len().to_string());
+    let mut text = i18n.t("commands.events.door_list_title", &user_lang, Some(&params));
+
+    if entries.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.events.door_list_empty", &user_lang, None));
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let name = match (&entry.user.first_name, &entry.user.username) {
+            (Some(first_name), Some(username)) => format!("{} (@{})", first_name, username),
+            (Some(first_name), None) => first_name.clone(),
+            (None, Some(username)) => format!("@{}", username),
+            (None, None) => entry.user.telegram_id.to_string(),
+        };
+        text.push_str(&format!("\n\n{}. {}", index + 1, name));
+        for note in &entry.notes {
+            text.push_str("\n   ");
+            text.push_str(&super::notes::format_note_line(&i18n, &user_lang, note));
+        }
+    }
+
+    bot.send_message(chat_id, text).await?;
+
+    info!(user_id = user_id, event_id = event_id, "Door list viewed");
+
+    Ok(())
+}
+
+/// Handle /reminders command - turn day-of event reminders on or off for a group
+pub async fn handle_group_reminders_toggle(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /reminders command");
+
+    // Only group chats have group reminders
+    if chat_id.is_user() {
+        let lang = services.user_service.get_user_by_telegram_id(user_id).await?
+            .map_or_else(|| "en".to_string(), |user| user.language_code);
+        let text = i18n.t("commands.events.reminders_group_only", &lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    if !services.auth_service.can_manage_events(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", &lang, None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let Some(enabled) = parse_toggle(&args) else {
+        let text = i18n.t("commands.events.reminders_usage", &lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    let key = if !services.reminder_service.set_group_reminders_enabled(chat_id.0, enabled).await? {
+        "commands.events.group_not_registered"
+    } else if enabled {
+        "commands.events.reminders_enabled"
+    } else {
+        "commands.events.reminders_disabled"
+    };
+    bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, enabled = enabled, "Group reminders toggled");
+
+    Ok(())
+}
+
+/// Handle /eventreminder command - turn the group reminder for one event on or off
+pub async fn handle_event_reminder_toggle(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /eventreminder command");
+
+    // Get user language
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let mut parts = args.split_whitespace();
+    let event_id = parts.next().and_then(|id| id.parse::<i64>().ok());
+    let enabled = parts.next().and_then(parse_toggle);
+    let (Some(event_id), Some(enabled)) = (event_id, enabled) else {
+        let text = i18n.t("commands.events.event_reminder_usage", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    let Some(event) = services.reminder_service.find_event(event_id).await? else {
+        let text = i18n.t("commands.events.not_found", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    // Admins and the event organizer only
+    if !services.auth_service.can_manage_events(user_id, None).await?
+        && !services.user_service.is_event_organizer(user_id, &event).await?
+    {
+        let error_text = i18n.t("commands.admin.access_denied", &user_lang, None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    services.reminder_service.set_event_reminder_enabled(event.id, enabled).await?;
+
+    let mut params = HashMap::new();
+    params.insert("title".to_string(), event.title.clone());
+    let key = if enabled { "commands.events.event_reminder_enabled" } else { "commands.events.event_reminder_disabled" };
+    bot.send_message(chat_id, i18n.t(key, &user_lang, Some(&params))).await?;
+
+    info!(user_id = user_id, event_id = event_id, enabled = enabled, "Event group reminder toggled");
+
+    Ok(())
+}
+
+/// Handle /eventsheet command - mirror an event's participants into a Google Sheet
+pub async fn handle_event_sheet(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /eventsheet command");
+
+    // Get user language
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let args = args.trim();
+    let (event_id, action) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    let Ok(event_id) = event_id.parse::<i64>() else {
+        let text = i18n.t("commands.events.event_sheet_usage", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    let Some(event) = services.reminder_service.find_event(event_id).await? else {
+        let text = i18n.t("commands.events.not_found", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    // Admins and the event organizer only
+    if !services.auth_service.can_manage_events(user_id, None).await?
+        && !services.user_service.is_event_organizer(user_id, &event).await?
+    {
+        let error_text = i18n.t("commands.admin.access_denied", &user_lang, None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let mut params = HashMap::new();
+    params.insert("title".to_string(), event.title.clone());
+    params.insert("id".to_string(), event.id.to_string());
+
+    let action = action.trim();
+    if action.is_empty() {
+        let text = match services.sheet_export_service.get_sheet(event.id).await? {
+            Some(sheet) => {
+                params.insert("url".to_string(), spreadsheet_url(&sheet.spreadsheet_id));
+                params.insert("synced".to_string(), match sheet.last_synced_at {
+                    Some(at) if !sheet.needs_sync => at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                    _ => i18n.t("commands.events.event_sheet_pending", &user_lang, None),
+                });
+                let mut text = i18n.t("commands.events.event_sheet_status", &user_lang, Some(&params));
+                if let Some(error) = sheet.last_error {
+                    let mut error_params = HashMap::new();
+                    error_params.insert("error".to_string(), error);
+                    text.push_str("\n\n");
+                    text.push_str(&i18n.t("commands.events.event_sheet_failing", &user_lang, Some(&error_params)));
+                }
+                text
+            }
+            None => i18n.t("commands.events.event_sheet_none", &user_lang, Some(&params)),
+        };
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if action.eq_ignore_ascii_case("off") {
+        services.sheet_export_service.unlink_sheet(event.id).await?;
+        bot.send_message(chat_id, i18n.t("commands.events.event_sheet_removed", &user_lang, Some(&params))).await?;
+        info!(user_id = user_id, event_id = event.id, "Event sheet unlinked");
+        return Ok(());
+    }
+
+    if services.google_oauth_service.account().await?.is_none() {
+        bot.send_message(chat_id, i18n.t("commands.events.event_sheet_unavailable", &user_lang, None)).await?;
+        return Ok(());
+    }
+
+    let linked = if action.eq_ignore_ascii_case("new") {
+        services.sheet_export_service.create_sheet(&event, user_id).await
+    } else {
+        services.sheet_export_service.link_sheet(event.id, action, user_id).await
+    };
+
+    match linked {
+        Ok(sheet) => {
+            params.insert("url".to_string(), spreadsheet_url(&sheet.spreadsheet_id));
+            bot.send_message(chat_id, i18n.t("commands.events.event_sheet_linked", &user_lang, Some(&params))).await?;
+            info!(user_id = user_id, event_id = event.id, spreadsheet_id = %sheet.spreadsheet_id, "Event sheet linked");
+        }
+        Err(crate::utils::errors::SwingBuddyError::InvalidInput(_)) => {
+            bot.send_message(chat_id, i18n.t("commands.events.event_sheet_invalid", &user_lang, None)).await?;
+        }
+        Err(e) => {
+            error!(error = %e, event_id = event.id, "Failed to create event sheet");
+            let mut error_params = HashMap::new();
+            error_params.insert("error".to_string(), e.to_string());
+            bot.send_message(chat_id, i18n.t("commands.events.event_sheet_error", &user_lang, Some(&error_params))).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle /digest command - subscribe a group to the weekly event digest
+pub async fn handle_digest_toggle(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /digest command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_events(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    if !services.digest_service.is_enabled() {
+        bot.send_message(chat_id, i18n.t("commands.digest.not_configured", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let Some(enabled) = parse_toggle(&args) else {
+        bot.send_message(chat_id, i18n.t("commands.digest.usage", &lang, None)).await?;
+        return Ok(());
+    };
+
+    let key = if !services.group_service.set_feature(chat_id.0, GroupFeature::WeeklyDigest, enabled).await? {
+        "commands.events.group_not_registered"
+    } else if enabled {
+        "commands.digest.enabled"
+    } else {
+        "commands.digest.disabled"
+    };
+    bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, enabled = enabled, "Weekly digest toggled");
+
+    Ok(())
+}
+
+/// Handle /quiethours command - show or set the window during which group announcements are queued
+pub async fn handle_quiet_hours(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /quiethours command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let Some(group) = services.group_service.get_group(chat_id.0).await? else {
+        bot.send_message(chat_id, i18n.t("commands.events.group_not_registered", "en", None)).await?;
+        return Ok(());
+    };
+    let lang = group.language_code.clone();
+
+    let mut params = HashMap::new();
+    let key = match args.trim() {
+        "" => match group.quiet_hours() {
+            Some(hours) => {
+                params.insert("hours".to_string(), hours.to_string());
+                params.insert("queued".to_string(), services.quiet_hours_service.queued_count(group.id).await?.to_string());
+                "commands.quiet_hours.current"
+            }
+            None => "commands.quiet_hours.none",
+        },
+        "off" => {
+            services.group_service.set_quiet_hours(chat_id.0, None).await?;
+            "commands.quiet_hours.removed"
+        }
+        arg => match arg.parse::<QuietHours>() {
+            Ok(hours) => {
+                services.group_service.set_quiet_hours(chat_id.0, Some(hours)).await?;
+                params.insert("hours".to_string(), hours.to_string());
+                "commands.quiet_hours.set"
+            }
+            Err(_) => "commands.quiet_hours.usage",
+        },
+    };
+    bot.send_message(chat_id, i18n.t(key, &lang, Some(&params))).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, args = %args, "Processed /quiethours command");
+
+    Ok(())
+}
+
+/// Parse an on/off command argument
+pub(crate) fn parse_toggle(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toggle() {
+        assert_eq!(parse_toggle("on"), Some(true));
+        assert_eq!(parse_toggle(" OFF "), Some(false));
+        assert_eq!(parse_toggle("maybe"), None);
+        assert_eq!(parse_toggle(""), None);
+    }
+}
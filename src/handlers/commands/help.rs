@@ -1,18 +1,215 @@
 //! Help command handler
+//!
+//! Builds the help text from the registered bot commands in the user's
+//! language. `/help <command>` explains a single command. Commands for bot
+//! admins are only listed to them. The command menus Telegram shows are built
+//! from the same list at startup.
 
-use teloxide::{Bot, types::Message, prelude::*};
+use teloxide::{Bot, types::{BotCommand, BotCommandScope, Message, Recipient}, prelude::*};
+use tracing::{debug, info, warn};
 use crate::utils::errors::Result;
+use crate::services::ServiceFactory;
+use crate::i18n::I18n;
 
-/// Handle /help command
-pub async fn handle_help(bot: Bot, msg: Message) -> Result<()> {
-    let help_text = "🤖 SwingBuddy Help\n\n\
-        /start - Start the bot\n\
-        /help - Show this help message\n\
-        /events - List upcoming events\n\
-        /language - Change language\n\
-        /profile - Show your profile\n\n\
-        For more information, contact the administrators.";
-    
-    bot.send_message(msg.chat.id, help_text).await?;
+/// Who a command is meant for, and the help section it is listed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandSection {
+    User,
+    Events,
+    /// Commands for group admins, used in their groups
+    Group,
+    /// Commands for bot admins only
+    Admin,
+}
+
+impl CommandSection {
+    /// Sections in the order they are listed
+    const ALL: [CommandSection; 4] = [Self::User, Self::Events, Self::Group, Self::Admin];
+
+    /// Translation key of the section heading
+    fn title_key(&self) -> &'static str {
+        match self {
+            Self::User => "commands.help.user_commands",
+            Self::Events => "commands.help.event_commands",
+            Self::Group => "commands.help.group_commands",
+            Self::Admin => "commands.help.admin_commands",
+        }
+    }
+}
+
+/// Section of a command, by its name without the leading `/`
+pub fn command_section(command: &str) -> CommandSection {
+    match command {
+        "admin" | "stats" | "reload_config" | "reload_translations" | "caswhitelist" | "user" | "state"
+        | "note" | "editnote" | "notehistory" => CommandSection::Admin,
+        "events" | "nextevent" | "createevent" | "register" | "doorlist" | "eventreminder" | "eventstyles"
+        | "eventsheet" | "request" | "queue" | "playlist" | "venues" => CommandSection::Events,
+        "groupstats" | "checkperms" | "reminders" | "digest" | "quiethours" | "captcha" | "intro" | "warn"
+        | "ban" | "unban" | "mute" | "unmute" | "role" | "sharedbans" | "cleanup" | "topic" | "invite" => CommandSection::Group,
+        _ => CommandSection::User,
+    }
+}
+
+/// Short description of a command in a language, falling back to the
+/// registered English one
+pub fn command_summary(command: &BotCommand, i18n: &I18n, language_code: &str) -> String {
+    let name = command_name(command);
+    translated(i18n, &format!("commands.help.commands.{}.summary", name), language_code)
+        .unwrap_or_else(|| command.description.clone())
+}
+
+/// Handle /help command: list the commands, or explain the one named in `topic`
+pub async fn handle_help(
+    bot: Bot,
+    msg: Message,
+    topic: String,
+    commands: Vec<BotCommand>,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref();
+    let user_id = user.map_or(msg.chat.id.0, |user| user.id.0 as i64);
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, topic = %topic, "Processing /help command");
+
+    let user_lang = match services.user_service.get_user_by_telegram_id(user_id).await? {
+        Some(user_data) => user_data.language_code,
+        None => i18n.detect_user_language(user.and_then(|user| user.language_code.as_deref())),
+    };
+    let is_admin = services.auth_service.can_access_admin_panel(user_id).await?;
+
+    // Commands the user may see
+    let commands: Vec<BotCommand> = commands.into_iter()
+        .filter(|command| is_admin || command_section(command_name(command)) != CommandSection::Admin)
+        .collect();
+
+    let topic = topic.trim().trim_start_matches('/').to_lowercase();
+    let text = if topic.is_empty() {
+        help_overview(&commands, &i18n, &user_lang)
+    } else {
+        match commands.iter().find(|command| command_name(command) == topic) {
+            Some(command) => command_help(command, &i18n, &user_lang),
+            None => {
+                let mut params = std::collections::HashMap::new();
+                params.insert("command".to_string(), topic);
+                i18n.t("commands.help.unknown_command", &user_lang, Some(&params))
+            }
+        }
+    };
+
+    bot.send_message(chat_id, text).await?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Register the command menus Telegram shows, in every supported language:
+/// user and event commands everywhere, group commands for group admins, and
+/// all commands in the private chats of bot admins. Telegram only scopes
+/// single members within groups, so bot admins get theirs in their own chat.
+pub async fn register_command_menus(bot: &Bot, commands: &[BotCommand], i18n: &I18n, admin_ids: &[i64]) {
+    use CommandSection::*;
+
+    let mut menus = vec![
+        (BotCommandScope::Default, vec![User, Events]),
+        (BotCommandScope::AllGroupChats, vec![User, Events]),
+        (BotCommandScope::AllChatAdministrators, vec![User, Events, Group]),
+    ];
+    menus.extend(admin_ids.iter().map(|&admin_id| {
+        (BotCommandScope::Chat { chat_id: Recipient::Id(ChatId(admin_id)) }, vec![User, Events, Group, Admin])
+    }));
+
+    let mut registered = 0;
+    for (scope, sections) in menus {
+        let listed: Vec<&BotCommand> = commands.iter()
+            .filter(|command| sections.contains(&command_section(command_name(command))))
+            .collect();
+
+        // Menus without a language are shown to users of all other languages
+        let default_language = i18n.default_language().to_string();
+        let languages = std::iter::once(None).chain(
+            i18n.supported_languages().iter()
+                .filter(|language| **language != default_language)
+                .map(|language| Some(language.clone()))
+        );
+        for language in languages {
+            let menu: Vec<BotCommand> = listed.iter()
+                .map(|command| BotCommand::new(
+                    command_name(command),
+                    command_summary(command, i18n, language.as_deref().unwrap_or(&default_language)),
+                ))
+                .collect();
+            let mut request = bot.set_my_commands(menu).scope(scope.clone());
+            if let Some(language) = &language {
+                request = request.language_code(language.clone());
+            }
+            match request.await {
+                Ok(_) => registered += 1,
+                Err(e) => warn!(scope = ?scope, language = ?language, error = %e, "Failed to register command menu"),
+            }
+        }
+    }
+    info!(menus = registered, "Command menus registered");
+}
+
+/// All commands, grouped by section
+fn help_overview(commands: &[BotCommand], i18n: &I18n, language_code: &str) -> String {
+    let mut text = format!(
+        "{}\n\n{}",
+        i18n.t("commands.help.title", language_code, None),
+        i18n.t("commands.help.description", language_code, None)
+    );
+
+    for section in CommandSection::ALL {
+        let lines: Vec<String> = commands.iter()
+            .filter(|command| command_section(command_name(command)) == section)
+            .map(|command| format!("/{} - {}", command_name(command), command_summary(command, i18n, language_code)))
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+        text.push_str("\n\n");
+        text.push_str(&i18n.t(section.title_key(), language_code, None));
+        text.push('\n');
+        text.push_str(&lines.join("\n"));
+    }
+
+    text.push_str("\n\n");
+    text.push_str(&i18n.t("commands.help.details_hint", language_code, None));
+    text.push('\n');
+    text.push_str(&i18n.t("commands.help.general_help", language_code, None));
+    text
+}
+
+/// One command with its detailed description
+fn command_help(command: &BotCommand, i18n: &I18n, language_code: &str) -> String {
+    let name = command_name(command);
+    let summary = command_summary(command, i18n, language_code);
+    match translated(i18n, &format!("commands.help.commands.{}.details", name), language_code) {
+        Some(details) => format!("/{} - {}\n\n{}", name, summary, details),
+        None => format!("/{} - {}", name, summary),
+    }
+}
+
+/// Command name without the leading `/`
+fn command_name(command: &BotCommand) -> &str {
+    command.command.trim_start_matches('/')
+}
+
+/// Translation of a key in the language or the default language, if there is one
+fn translated(i18n: &I18n, key: &str, language_code: &str) -> Option<String> {
+    (i18n.has_translation(key, language_code) || i18n.has_translation(key, i18n.default_language()))
+        .then(|| i18n.t(key, language_code, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_section() {
+        assert_eq!(command_section("start"), CommandSection::User);
+        assert_eq!(command_section("nextevent"), CommandSection::Events);
+        assert_eq!(command_section("ban"), CommandSection::Group);
+        assert_eq!(command_section("reload_config"), CommandSection::Admin);
+    }
+}
@@ -0,0 +1,194 @@
+//! Invite link command handlers
+//!
+//! Handles /invite, which lets group admins manage tracked join-request links, and
+//! the join requests sent through those links
+
+use std::collections::HashMap;
+use teloxide::{Bot, types::{ChatJoinRequest, Message}, prelude::*};
+use tracing::{info, debug, warn};
+use crate::utils::errors::Result;
+use crate::services::ServiceFactory;
+use crate::services::invite_link::MAX_LINK_NAME_LENGTH;
+use crate::models::group::{Group, GroupFeature};
+use crate::models::invite_link::JoinRequestStatus;
+use crate::i18n::I18n;
+
+/// Handle /invite command - create, list, rotate and revoke tracked join-request links
+pub async fn handle_invite(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /invite command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let Some(group) = services.group_service.get_group(chat_id.0).await? else {
+        bot.send_message(chat_id, i18n.t("commands.events.group_not_registered", "en", None)).await?;
+        return Ok(());
+    };
+    let lang = group.language_code.clone();
+
+    let args = args.trim();
+    let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    let rest = rest.trim();
+
+    let mut params = HashMap::new();
+    let result = match action.to_lowercase().as_str() {
+        "" | "list" => {
+            let text = format_link_stats(&services, &group, &i18n).await?;
+            bot.send_message(chat_id, text).await?;
+            return Ok(());
+        }
+        "new" => {
+            if rest.is_empty() || rest.chars().count() > MAX_LINK_NAME_LENGTH {
+                params.insert("max".to_string(), MAX_LINK_NAME_LENGTH.to_string());
+                bot.send_message(chat_id, i18n.t("commands.invites.invalid_name", &lang, Some(&params))).await?;
+                return Ok(());
+            }
+            services.invite_link_service.create_link(&group, rest, user_id).await
+                .map(|link| Some(("commands.invites.created", link)))
+        }
+        "rotate" | "revoke" => {
+            let Ok(link_id) = rest.parse::<i64>() else {
+                bot.send_message(chat_id, i18n.t("commands.invites.usage", &lang, None)).await?;
+                return Ok(());
+            };
+            if action.eq_ignore_ascii_case("rotate") {
+                services.invite_link_service.rotate_link(&group, link_id).await
+                    .map(|link| link.map(|link| ("commands.invites.rotated", link)))
+            } else {
+                services.invite_link_service.revoke_link(&group, link_id).await
+                    .map(|link| link.map(|link| ("commands.invites.revoked", link)))
+            }
+        }
+        _ => {
+            bot.send_message(chat_id, i18n.t("commands.invites.usage", &lang, None)).await?;
+            return Ok(());
+        }
+    };
+
+    let text = match result {
+        Ok(Some((key, link))) => {
+            params.insert("id".to_string(), link.id.to_string());
+            params.insert("name".to_string(), link.name);
+            params.insert("link".to_string(), link.invite_link);
+            i18n.t(key, &lang, Some(&params))
+        }
+        Ok(None) => i18n.t("commands.invites.not_found", &lang, None),
+        Err(e) => {
+            // Usually the bot lacks the right to invite users
+            warn!(error = %e, chat_id = ?chat_id, "Failed to manage invite link");
+            i18n.t("commands.invites.failed", &lang, None)
+        }
+    };
+    bot.send_message(chat_id, text).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, action = action, "Invite link command handled");
+
+    Ok(())
+}
+
+/// List a group's active links with their join request counts
+async fn format_link_stats(services: &ServiceFactory, group: &Group, i18n: &I18n) -> Result<String> {
+    let stats = services.invite_link_service.get_link_stats(group).await?;
+    let lang = group.language_code.as_str();
+
+    if stats.is_empty() {
+        return Ok(i18n.t("commands.invites.empty", lang, None));
+    }
+
+    let mut text = i18n.t("commands.invites.list_title", lang, None);
+    for link in stats {
+        let mut params = HashMap::new();
+        params.insert("id".to_string(), link.id.to_string());
+        params.insert("name".to_string(), link.name);
+        params.insert("link".to_string(), link.invite_link);
+        params.insert("requests".to_string(), link.requests.to_string());
+        params.insert("approved".to_string(), link.approved.to_string());
+        params.insert("declined".to_string(), link.declined.to_string());
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.invites.list_item", lang, Some(&params)));
+    }
+
+    Ok(text)
+}
+
+/// Handle a join request sent through one of the bot's tracked links: members on the
+/// shared ban list or listed in CAS are declined, everyone else is approved.
+/// Requests through other links are left to the group admins.
+pub async fn handle_join_request(
+    bot: Bot,
+    request: ChatJoinRequest,
+    services: ServiceFactory,
+) -> Result<()> {
+    let Some(invite_link) = request.invite_link.as_ref() else {
+        return Ok(());
+    };
+    let Some(link) = services.invite_link_service.find_tracked_link(&invite_link.invite_link).await? else {
+        return Ok(());
+    };
+    let Some(group) = services.group_service.get_group(request.chat.id.0).await? else {
+        return Ok(());
+    };
+
+    let user_id = request.from.id.0 as i64;
+    debug!(user_id = user_id, chat_id = request.chat.id.0, link_id = link.id, "Processing join request");
+
+    let status = if is_unwelcome(&services, &group, user_id).await? {
+        bot.decline_chat_join_request(request.chat.id, request.from.id).await?;
+        JoinRequestStatus::Declined
+    } else {
+        bot.approve_chat_join_request(request.chat.id, request.from.id).await?;
+        JoinRequestStatus::Approved
+    };
+    services.invite_link_service.record_join_request(&link, user_id, status).await?;
+
+    info!(user_id = user_id, chat_id = request.chat.id.0, link_id = link.id, status = status.as_str(), "Join request handled");
+
+    Ok(())
+}
+
+/// Check if a member asking to join is on the shared ban list or listed in CAS
+async fn is_unwelcome(services: &ServiceFactory, group: &Group, user_id: i64) -> Result<bool> {
+    if group.shared_bans_enabled()
+        && services.moderation_service.get_shared_ban(teloxide::types::UserId(user_id as u64)).await?.is_some()
+    {
+        return Ok(true);
+    }
+
+    if !services.cas_service.is_enabled()
+        || !group.feature_enabled(GroupFeature::CasProtection)
+        || services.cas_service.is_whitelisted(user_id).await?
+    {
+        return Ok(false);
+    }
+
+    match services.cas_service.check_user(user_id).await {
+        Ok(result) => Ok(result.is_banned),
+        Err(e) => {
+            // Do not keep people waiting because CAS is unreachable
+            warn!(error = %e, user_id = user_id, "Failed to check join request against CAS");
+            Ok(false)
+        }
+    }
+}
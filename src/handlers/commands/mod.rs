@@ -6,8 +6,15 @@ pub mod start;
 pub mod help;
 pub mod events;
 pub mod admin;
+pub mod notes;
+pub mod styles;
+pub mod moderation;
+pub mod invites;
+pub mod songs;
+pub mod venues;
+pub mod subscription;
 
-use teloxide::{Bot, types::Message, utils::command::BotCommands};
+use teloxide::{Bot, types::{Me, Message}, utils::command::BotCommands};
 use crate::utils::errors::Result;
 use crate::services::ServiceFactory;
 use crate::state::{ScenarioManager, StateStorage};
@@ -19,10 +26,20 @@ use crate::i18n::I18n;
 pub enum Command {
     #[command(description = "Start the bot and show welcome message")]
     Start,
-    #[command(description = "Show help information")]
-    Help,
+    #[command(description = "Show help, or details of a command: /help <command>")]
+    Help(String),
     #[command(description = "List upcoming events")]
     Events,
+    #[command(description = "Show the next upcoming event")]
+    NextEvent,
+    #[command(description = "List dance venues in a city with their floor and upcoming events: /venues [city|all]")]
+    Venues(String),
+    #[command(description = "Ask the DJ for a song during an event: /request <track>")]
+    Request(String),
+    #[command(description = "Show the song request queue with played/skip buttons (organizers)")]
+    Queue(String),
+    #[command(description = "List the requested songs played at an event")]
+    Playlist(String),
     #[command(description = "Create a new event")]
     CreateEvent,
     #[command(description = "Register for an event")]
@@ -31,31 +48,165 @@ pub enum Command {
     Admin,
     #[command(description = "Set language preference")]
     Language,
+    #[command(description = "Go back to the previous step of what you are filling in")]
+    Back,
     #[command(description = "Show user profile")]
     Profile,
+    #[command(description = "Set your birthday (DD.MM) or allow announcing it in a group")]
+    Birthday(String),
+    #[command(description = "Choose your favorite dance styles")]
+    Styles,
+    #[command(description = "Stop broadcasts and reminders")]
+    Unsubscribe,
+    #[command(description = "Get broadcasts and reminders again")]
+    Subscribe,
     #[command(description = "Show bot statistics (admin only)")]
     Stats,
+    #[command(rename = "reload_config", description = "Reload the configuration without restarting (admin only)")]
+    ReloadConfig,
+    #[command(rename = "reload_translations", description = "Reload the translation files and list changed keys (admin only)")]
+    ReloadTranslations,
+    #[command(description = "Show this group's activity over the last week (group admins)")]
+    GroupStats,
+    #[command(description = "Check the bot's admin rights in this group (group admins)")]
+    CheckPerms,
+    #[command(description = "List, add or remove users exempt from CAS auto-bans (admin only)")]
+    CasWhitelist(String),
+    #[command(description = "Show user details and notes (admin only)")]
+    User(String),
+    #[command(description = "Show a user's conversation state, with buttons to clear it (admin only)")]
+    State(String),
+    #[command(description = "Add a private note to a user (admin only)")]
+    Note(String),
+    #[command(description = "Edit a private user note (admin only)")]
+    EditNote(String),
+    #[command(description = "Show edit history of a note (admin only)")]
+    NoteHistory(String),
+    #[command(description = "Show event door list with notes (admins and organizers)")]
+    DoorList(String),
+    #[command(description = "Turn group event reminders on or off (group admins)")]
+    Reminders(String),
+    #[command(description = "Post a weekly digest of upcoming events in this group: on|off (group admins)")]
+    Digest(String),
+    #[command(description = "Queue group announcements during quiet hours: <start>-<end> in UTC hours|off (group admins)")]
+    QuietHours(String),
+    #[command(description = "Turn the group reminder for an event on or off")]
+    EventReminder(String),
+    #[command(description = "Show or set the dance styles of an event")]
+    EventStyles(String),
+    #[command(description = "Mirror an event's participants into a Google Sheet")]
+    EventSheet(String),
+    #[command(description = "Configure the join captcha (group admins)")]
+    Captcha(String),
+    #[command(description = "Show the group rules; admins: set, clear, onjoin on|off")]
+    Rules(String),
+    #[command(description = "Send new members an intro card: group|dm|off (group admins)")]
+    Intro(String),
+    #[command(description = "Warn a group member: /warn @user [reason] (group admins)")]
+    Warn(String),
+    #[command(description = "Reply to a message with /report [reason] to alert the group admins")]
+    Report(String),
+    #[command(description = "Ban a group member; groups sharing the ban list ban them everywhere (group admins)")]
+    Ban(String),
+    #[command(description = "Lift a ban (group admins)")]
+    Unban(String),
+    #[command(description = "Mute a group member for a while: /mute @user 2h [reason] (group admins)")]
+    Mute(String),
+    #[command(description = "Lift a mute (group admins)")]
+    Unmute(String),
+    #[command(description = "List roles or give a member one: /role @user moderator|organizer|admin|owner|none (group admins)")]
+    Role(String),
+    #[command(description = "Share the bot-level ban list with other groups: on|off (group admins)")]
+    SharedBans(String),
+    #[command(description = "Auto-delete join/leave messages and temporary bot replies: on [seconds]|off (group admins)")]
+    Cleanup(String),
+    #[command(description = "Post event announcements or moderation notices in this forum topic: events|moderation [off] (group admins)")]
+    Topic(String),
+    #[command(description = "Manage tracked join-request invite links: new <name>|list|rotate <id>|revoke <id> (group admins)")]
+    Invite(String),
 }
 
 /// Main command dispatcher
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_command(
     bot: Bot,
     msg: Message,
+    me: Me,
     cmd: Command,
     services: ServiceFactory,
     scenario_manager: ScenarioManager,
     state_storage: StateStorage,
     i18n: I18n,
 ) -> Result<()> {
-    match cmd {
+    // Put the private conversation's scenario aside while another command runs;
+    // /start and /back act on it instead
+    let chat_id = msg.chat.id;
+    let paused = match msg.from.as_ref() {
+        Some(user) if chat_id.is_user() && !matches!(cmd, Command::Start | Command::Back) => {
+            start::pause_for_command(user.id.0 as i64, &scenario_manager, &state_storage).await
+        }
+        _ => None,
+    };
+    let resume = paused.map(|paused| (bot.clone(), services.clone(), state_storage.clone(), i18n.clone(), paused));
+
+    let result = match cmd {
         Command::Start => start::handle_start(bot, msg, services, scenario_manager, state_storage, i18n).await,
-        Command::Help => help::handle_help(bot, msg).await,
-        Command::Events => events::handle_events_list(bot, msg, services, i18n).await,
-        Command::CreateEvent => events::handle_create_event(bot, msg, services, i18n).await,
+        Command::Help(topic) => help::handle_help(bot, msg, topic, Command::bot_commands(), services, i18n).await,
+        Command::Events => events::handle_events_list(bot, msg, me, services, i18n).await,
+        Command::NextEvent => events::handle_next_event(bot, msg, services, i18n).await,
+        Command::Venues(args) => venues::handle_venues(bot, msg, args, services, i18n).await,
+        Command::Request(args) => songs::handle_song_request(bot, msg, args, services, i18n).await,
+        Command::Queue(args) => songs::handle_song_queue(bot, msg, args, services, i18n).await,
+        Command::Playlist(args) => songs::handle_playlist(bot, msg, args, services, i18n).await,
+        Command::CreateEvent => events::handle_create_event(bot, msg, services, scenario_manager, state_storage, i18n).await,
         Command::Register => events::handle_register(bot, msg, services, i18n).await,
         Command::Admin => admin::handle_admin_panel(bot, msg, services, scenario_manager, state_storage, i18n).await,
         Command::Language => start::handle_language_selection(bot, msg).await,
-        Command::Profile => start::handle_profile(bot, msg).await,
-        Command::Stats => admin::handle_stats(bot, msg, services, i18n).await,
+        Command::Back => {
+            let user_id = msg.from.as_ref().map_or(msg.chat.id.0, |user| user.id.0 as i64);
+            start::handle_back(bot, msg.chat.id, user_id, services, scenario_manager, state_storage, i18n).await
+        }
+        Command::Profile => start::handle_profile(bot, msg, me, services, i18n).await,
+        Command::Birthday(args) => start::handle_birthday(bot, msg, args, services, i18n).await,
+        Command::Unsubscribe => subscription::handle_unsubscribe(bot, msg, services, i18n).await,
+        Command::Subscribe => subscription::handle_subscribe(bot, msg, services, i18n).await,
+        Command::Styles => styles::handle_styles(bot, msg, services, i18n).await,
+        Command::Stats => admin::handle_stats(bot, msg, services, state_storage, i18n).await,
+        Command::ReloadConfig => admin::handle_reload_config(bot, msg, services, i18n).await,
+        Command::ReloadTranslations => admin::handle_reload_translations(bot, msg, services, i18n).await,
+        Command::GroupStats => admin::handle_group_stats(bot, msg, services, i18n).await,
+        Command::CheckPerms => admin::handle_check_permissions(bot, msg, services, i18n).await,
+        Command::CasWhitelist(args) => admin::handle_cas_whitelist(bot, msg, args, services, i18n).await,
+        Command::User(args) => admin::handle_user_detail(bot, msg, args, services, i18n).await,
+        Command::State(args) => admin::handle_state(bot, msg, args, services, state_storage, i18n).await,
+        Command::Note(args) => notes::handle_add_note(bot, msg, args, services, i18n).await,
+        Command::EditNote(args) => notes::handle_edit_note(bot, msg, args, services, i18n).await,
+        Command::NoteHistory(args) => notes::handle_note_history(bot, msg, args, services, i18n).await,
+        Command::DoorList(args) => events::handle_door_list(bot, msg, args, services, i18n).await,
+        Command::Reminders(args) => events::handle_group_reminders_toggle(bot, msg, args, services, i18n).await,
+        Command::Digest(args) => events::handle_digest_toggle(bot, msg, args, services, i18n).await,
+        Command::QuietHours(args) => events::handle_quiet_hours(bot, msg, args, services, i18n).await,
+        Command::EventReminder(args) => events::handle_event_reminder_toggle(bot, msg, args, services, i18n).await,
+        Command::EventStyles(args) => styles::handle_event_styles(bot, msg, args, services, i18n).await,
+        Command::EventSheet(args) => events::handle_event_sheet(bot, msg, args, services, i18n).await,
+        Command::Captcha(args) => moderation::handle_captcha_settings(bot, msg, args, services, i18n).await,
+        Command::Rules(args) => moderation::handle_rules(bot, msg, args, services, scenario_manager, state_storage, i18n).await,
+        Command::Intro(args) => moderation::handle_intro(bot, msg, args, services, i18n).await,
+        Command::Warn(args) => moderation::handle_warn(bot, msg, me, args, services, i18n).await,
+        Command::Report(args) => moderation::handle_report(bot, msg, args, services, i18n).await,
+        Command::Ban(args) => moderation::handle_ban(bot, msg, me, args, services, i18n).await,
+        Command::Unban(args) => moderation::handle_unban(bot, msg, args, services, i18n).await,
+        Command::Mute(args) => moderation::handle_mute(bot, msg, me, args, services, i18n).await,
+        Command::Unmute(args) => moderation::handle_unmute(bot, msg, args, services, i18n).await,
+        Command::Role(args) => moderation::handle_role(bot, msg, args, services, i18n).await,
+        Command::SharedBans(args) => moderation::handle_shared_bans_toggle(bot, msg, args, services, i18n).await,
+        Command::Cleanup(args) => moderation::handle_cleanup(bot, msg, args, services, i18n).await,
+        Command::Topic(args) => moderation::handle_topic(bot, msg, args, services, i18n).await,
+        Command::Invite(args) => invites::handle_invite(bot, msg, args, services, i18n).await,
+    };
+
+    if let Some((bot, services, state_storage, i18n, paused)) = resume {
+        start::offer_resume(bot, chat_id, paused, &services, &state_storage, &i18n).await?;
     }
+    result
 }
\ No newline at end of file
@@ -0,0 +1,1214 @@
+//! Moderation command handlers
+//!
+//! Handles group moderation: the join captcha, the group rules, warnings, bans, reports
+//! and the cleanup of service messages
+
+use std::collections::HashMap;
+use teloxide::{Bot, types::{ChatId, InlineKeyboardButton, InlineKeyboardMarkup, Me, Message, MessageEntityKind, MessageId, ReplyParameters, User, UserId}, prelude::*};
+use tracing::{info, debug, warn};
+use crate::utils::errors::Result;
+use crate::services::{ServiceFactory, Escalation};
+use crate::services::cleanup::{MIN_DELAY_SECONDS, MAX_DELAY_SECONDS};
+use crate::services::moderation::{parse_mute_duration, MAX_MUTE_DAYS};
+use crate::models::captcha::CaptchaMode;
+use crate::models::group::{GroupTopic, IntroCardMode};
+use crate::models::moderation::CreateReportRequest;
+use crate::models::role::Role;
+use crate::state::{ConversationContext, ScenarioManager, StateStorage};
+use crate::handlers::callbacks::CallbackData;
+use crate::i18n::I18n;
+use super::events::parse_toggle;
+
+/// Maximum length of the group rules
+const RULES_MAX_LENGTH: usize = 3000;
+
+/// Handle /captcha command - configure the join captcha of a group
+pub async fn handle_captcha_settings(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /captcha command");
+
+    // The captcha guards group chats only
+    if chat_id.is_user() {
+        let lang = services.user_service.get_user_by_telegram_id(user_id).await?
+            .map_or_else(|| "en".to_string(), |user| user.language_code);
+        let text = i18n.t("commands.moderation.group_only", &lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", &lang, None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    if !services.captcha_service.is_enabled() {
+        let text = i18n.t("commands.moderation.captcha_not_configured", &lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    let arg = args.trim().to_lowercase();
+    let (updated, key) = match arg.as_str() {
+        "button" => (
+            services.captcha_service.set_group_captcha_mode(chat_id.0, CaptchaMode::Button).await?,
+            "commands.moderation.captcha_mode_button",
+        ),
+        "question" => (
+            services.captcha_service.set_group_captcha_mode(chat_id.0, CaptchaMode::Question).await?,
+            "commands.moderation.captcha_mode_question",
+        ),
+        _ => match parse_toggle(&arg) {
+            Some(enabled) => (
+                services.captcha_service.set_group_captcha_enabled(chat_id.0, enabled).await?,
+                if enabled { "commands.moderation.captcha_enabled" } else { "commands.moderation.captcha_disabled" },
+            ),
+            None => {
+                let text = i18n.t("commands.moderation.captcha_usage", &lang, None);
+                bot.send_message(chat_id, text).await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let key = if updated { key } else { "commands.events.group_not_registered" };
+    bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, setting = %arg, "Group captcha settings changed");
+
+    Ok(())
+}
+
+/// Handle /rules command - show the group rules or let admins change them
+pub async fn handle_rules(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /rules command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    let group = services.group_service.get_group(chat_id.0).await?;
+    let lang = group.as_ref().map_or("en", |g| g.language_code.as_str()).to_string();
+
+    let mut parts = args.split_whitespace();
+    let action = parts.next().unwrap_or("").to_lowercase();
+
+    // Everyone can read the rules
+    if action.is_empty() {
+        let text = match group.as_ref().and_then(|g| g.rules().map(|rules| (g, rules))) {
+            Some((group, rules)) => {
+                let mut params = HashMap::new();
+                params.insert("group".to_string(), group.title.clone());
+                params.insert("rules".to_string(), rules.to_string());
+                i18n.t("commands.rules.header", &lang, Some(&params))
+            }
+            None => i18n.t("commands.rules.not_set", &lang, None),
+        };
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let key = match (action.as_str(), parts.next().and_then(parse_toggle)) {
+        ("set", _) => {
+            // The admin's next message in this group becomes the rules
+            let mut context = ConversationContext::for_chat(user_id, chat_id.0);
+            scenario_manager.start_scenario(&mut context, "group_rules")?;
+            state_storage.save_context(&mut context).await?;
+            "commands.rules.prompt"
+        }
+        ("clear", _) => {
+            services.group_service.set_rules(chat_id.0, msg.chat.title().unwrap_or_default(), None).await?;
+            "commands.rules.cleared"
+        }
+        ("onjoin", Some(enabled)) => {
+            if !services.group_service.set_rules_on_join(chat_id.0, enabled).await? {
+                "commands.events.group_not_registered"
+            } else if enabled {
+                "commands.rules.on_join_enabled"
+            } else {
+                "commands.rules.on_join_disabled"
+            }
+        }
+        _ => "commands.rules.usage",
+    };
+
+    bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, action = %action, "Group rules command handled");
+
+    Ok(())
+}
+
+/// Save the rules an admin sent after `/rules set`
+pub async fn handle_rules_input(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let Some(text) = msg.text().map(str::trim) else {
+        bot.send_message(chat_id, i18n.t("commands.rules.text_only", &lang, None)).await?;
+        return Ok(());
+    };
+
+    if text.chars().count() > RULES_MAX_LENGTH {
+        let mut params = HashMap::new();
+        params.insert("max".to_string(), RULES_MAX_LENGTH.to_string());
+        bot.send_message(chat_id, i18n.t("commands.rules.too_long", &lang, Some(&params))).await?;
+        return Ok(());
+    }
+
+    let rules = (text != "-").then(|| text.to_string());
+    let group = services.group_service.set_rules(chat_id.0, msg.chat.title().unwrap_or_default(), rules).await?;
+    state_storage.delete_chat_context(user_id, chat_id.0).await?;
+
+    let key = if group.rules().is_some() { "commands.rules.saved" } else { "commands.rules.cleared" };
+    bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, "Group rules configured");
+
+    Ok(())
+}
+
+/// Handle /warn command - warn a group member; repeated warnings mute or ban them
+pub async fn handle_warn(
+    bot: Bot,
+    msg: Message,
+    me: Me,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /warn command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_moderate(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let Some((target_id, name, reason)) = resolve_member_target(&msg, &args, &services).await? else {
+        let key = if args.trim().is_empty() { "commands.warnings.usage" } else { "commands.warnings.user_not_found" };
+        bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+        return Ok(());
+    };
+
+    // Admins and the bot itself are out of reach of warnings
+    if is_protected_member(&me, &services, chat_id, target_id).await? {
+        bot.send_message(chat_id, i18n.t("commands.warnings.cannot_warn_admin", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let outcome = services.moderation_service.warn(chat_id, target_id, user_id, reason.clone()).await?;
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), name);
+    params.insert("count".to_string(), outcome.count.to_string());
+
+    let mut text = match outcome.ban_after {
+        Some(ban_after) => {
+            params.insert("limit".to_string(), ban_after.to_string());
+            i18n.t("commands.warnings.warned_with_limit", &lang, Some(&params))
+        }
+        None => i18n.t("commands.warnings.warned", &lang, Some(&params)),
+    };
+
+    if let Some(reason) = reason {
+        params.insert("reason".to_string(), reason);
+        text.push('\n');
+        text.push_str(&i18n.t("commands.warnings.reason", &lang, Some(&params)));
+    }
+
+    match outcome.escalation {
+        Escalation::Muted { until } => {
+            params.insert("until".to_string(), until.format("%d.%m.%Y %H:%M UTC").to_string());
+            text.push_str("\n\n");
+            text.push_str(&i18n.t("commands.warnings.muted", &lang, Some(&params)));
+        }
+        Escalation::Banned => {
+            text.push_str("\n\n");
+            text.push_str(&i18n.t("commands.warnings.banned", &lang, Some(&params)));
+        }
+        Escalation::None => {}
+    }
+
+    bot.send_message(chat_id, text).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, target_id = target_id.0, count = outcome.count, "Warn command handled");
+
+    Ok(())
+}
+
+/// Find the member a moderation command targets: the sender of the message the command
+/// replies to, a mention of a user without a username, an `@username` of a known user,
+/// or a numeric Telegram ID. Returns the member, their display name and the optional
+/// reason that follows them (for replies, all of the arguments).
+async fn resolve_member_target(
+    msg: &Message,
+    args: &str,
+    services: &ServiceFactory,
+) -> Result<Option<(UserId, String, Option<String>)>> {
+    let non_empty = |text: &str| Some(text.trim()).filter(|text| !text.is_empty()).map(str::to_string);
+
+    if let Some(user) = msg.reply_to_message().and_then(replied_member) {
+        return Ok(Some((user.id, user.first_name.clone(), non_empty(args))));
+    }
+
+    // Mentions of users without a username carry the user itself
+    let mention = msg.parse_entities().unwrap_or_default().into_iter()
+        .find_map(|entity| match entity.kind() {
+            MessageEntityKind::TextMention { user } => Some((user.clone(), entity.end())),
+            _ => None,
+        });
+    if let Some((user, end)) = mention {
+        let reason = msg.text().and_then(|text| text.get(end..)).and_then(non_empty);
+        return Ok(Some((user.id, user.first_name, reason)));
+    }
+
+    let args = args.trim();
+    let (target, reason) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    let reason = non_empty(reason);
+
+    if target.starts_with('@') {
+        let user = services.user_service.get_user_by_username(target).await?;
+        return Ok(user.map(|user| (UserId(user.telegram_id as u64), target.to_string(), reason)));
+    }
+
+    let Ok(telegram_id) = target.parse::<u64>() else {
+        return Ok(None);
+    };
+    let name = services.user_service.get_user_by_telegram_id(telegram_id as i64).await?
+        .and_then(|user| user.first_name)
+        .unwrap_or_else(|| telegram_id.to_string());
+
+    Ok(Some((UserId(telegram_id), name, reason)))
+}
+
+/// The member who sent a replied-to message. Messages in forum topics reply to the
+/// topic's creation message when they are not a reply, and messages sent on behalf of
+/// a channel or anonymous admin have no member behind them, so neither counts.
+fn replied_member(reply: &Message) -> Option<&User> {
+    if reply.forum_topic_created().is_some() || reply.sender_chat.is_some() {
+        return None;
+    }
+    reply.from.as_ref()
+}
+
+/// Whether a member is out of reach of moderation: group admins, moderators and the bot itself
+async fn is_protected_member(me: &Me, services: &ServiceFactory, chat_id: ChatId, user_id: UserId) -> Result<bool> {
+    Ok(me.id == user_id || services.auth_service.can_moderate(user_id.0 as i64, Some(chat_id)).await?)
+}
+
+/// Handle /ban command - ban a member; in groups sharing the ban list, everywhere
+pub async fn handle_ban(
+    bot: Bot,
+    msg: Message,
+    me: Me,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /ban command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let Some((target_id, name, reason)) = resolve_member_target(&msg, &args, &services).await? else {
+        let key = if args.trim().is_empty() { "commands.bans.ban_usage" } else { "commands.warnings.user_not_found" };
+        bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+        return Ok(());
+    };
+
+    if is_protected_member(&me, &services, chat_id, target_id).await? {
+        bot.send_message(chat_id, i18n.t("commands.bans.cannot_ban_admin", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let outcome = services.moderation_service.ban(chat_id, target_id, user_id, reason).await?;
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), name);
+    params.insert("count".to_string(), outcome.other_groups.to_string());
+
+    let key = if outcome.shared { "commands.bans.banned_shared" } else { "commands.bans.banned" };
+    bot.send_message(chat_id, i18n.t(key, &lang, Some(&params))).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, target_id = target_id.0, shared = outcome.shared, "Ban command handled");
+
+    Ok(())
+}
+
+/// Handle /unban command - lift a ban; admins of the group that added the member to
+/// the shared ban list (and bot admins) also remove them from the list
+pub async fn handle_unban(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /unban command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let Some((target_id, name, _)) = resolve_member_target(&msg, &args, &services).await? else {
+        let key = if args.trim().is_empty() { "commands.bans.unban_usage" } else { "commands.warnings.user_not_found" };
+        bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+        return Ok(());
+    };
+
+    // One group must not lift a ban another group asked for
+    let shared_ban = services.moderation_service.get_shared_ban(target_id).await?;
+    let lift_shared = match &shared_ban {
+        Some(ban) => {
+            services.auth_service.is_bot_admin(user_id)
+                || services.auth_service.can_manage_users(user_id, Some(ChatId(ban.origin_chat_telegram_id))).await?
+        }
+        None => false,
+    };
+
+    let outcome = services.moderation_service.unban(chat_id, target_id, user_id, lift_shared).await?;
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), name);
+    params.insert("count".to_string(), outcome.other_groups.to_string());
+
+    let key = if outcome.removed_from_shared {
+        "commands.bans.unbanned_shared"
+    } else if shared_ban.is_some() {
+        "commands.bans.unbanned_still_shared"
+    } else {
+        "commands.bans.unbanned"
+    };
+    bot.send_message(chat_id, i18n.t(key, &lang, Some(&params))).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, target_id = target_id.0, lift_shared = lift_shared, "Unban command handled");
+
+    Ok(())
+}
+
+/// Handle /mute command - mute a member for a while: /mute @user 2h [reason]
+pub async fn handle_mute(
+    bot: Bot,
+    msg: Message,
+    me: Me,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /mute command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_moderate(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let Some((target_id, name, rest)) = resolve_member_target(&msg, &args, &services).await? else {
+        let key = if args.trim().is_empty() { "commands.mutes.mute_usage" } else { "commands.warnings.user_not_found" };
+        bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+        return Ok(());
+    };
+
+    // The duration comes right after the member, the reason after it
+    let rest = rest.unwrap_or_default();
+    let (duration_arg, reason) = rest.split_once(char::is_whitespace).unwrap_or((&rest, ""));
+    let Some(duration) = parse_mute_duration(duration_arg) else {
+        let mut params = HashMap::new();
+        params.insert("max_days".to_string(), MAX_MUTE_DAYS.to_string());
+        bot.send_message(chat_id, i18n.t("commands.mutes.invalid_duration", &lang, Some(&params))).await?;
+        return Ok(());
+    };
+    let reason = Some(reason.trim()).filter(|reason| !reason.is_empty()).map(str::to_string);
+
+    if is_protected_member(&me, &services, chat_id, target_id).await? {
+        bot.send_message(chat_id, i18n.t("commands.mutes.cannot_mute_admin", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let until = services.moderation_service.mute(chat_id, target_id, user_id, duration, reason).await?;
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), name);
+    params.insert("until".to_string(), until.format("%Y-%m-%d %H:%M UTC").to_string());
+    bot.send_message(chat_id, i18n.t("commands.mutes.muted", &lang, Some(&params))).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, target_id = target_id.0, until = %until, "Mute command handled");
+
+    Ok(())
+}
+
+/// Handle /unmute command - lift a member's mute before it runs out
+pub async fn handle_unmute(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /unmute command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_moderate(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let Some((target_id, name, _)) = resolve_member_target(&msg, &args, &services).await? else {
+        let key = if args.trim().is_empty() { "commands.mutes.unmute_usage" } else { "commands.warnings.user_not_found" };
+        bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+        return Ok(());
+    };
+
+    services.moderation_service.unmute(chat_id, target_id, Some(user_id)).await?;
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), name);
+    bot.send_message(chat_id, i18n.t("commands.mutes.unmuted", &lang, Some(&params))).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, target_id = target_id.0, "Unmute command handled");
+
+    Ok(())
+}
+
+/// Handle /role command - list the group's delegated roles, or give a member a role
+/// such as moderator or organizer. Only group owners grant or take away admin roles.
+pub async fn handle_role(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /role command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    if args.trim().is_empty() {
+        let text = format_group_roles(&services, chat_id, &lang, &i18n).await?;
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    // The role name follows the member like the reason of other moderation commands
+    let Some((target_id, name, Some(role_arg))) = resolve_member_target(&msg, &args, &services).await? else {
+        bot.send_message(chat_id, i18n.t("commands.roles.usage", &lang, None)).await?;
+        return Ok(());
+    };
+    let role_arg = role_arg.to_lowercase();
+    let new_role = match role_arg.as_str() {
+        "none" | "off" | "remove" => None,
+        other => match other.parse::<Role>() {
+            Ok(role) => Some(role),
+            Err(_) => {
+                bot.send_message(chat_id, i18n.t("commands.roles.usage", &lang, None)).await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let target_telegram_id = target_id.0 as i64;
+    let current_role = services.auth_service.get_group_role(chat_id, target_telegram_id).await?;
+    let privileged = new_role.is_some_and(|role| role.is_privileged()) || current_role.is_some_and(|role| role.is_privileged());
+    if privileged && !services.auth_service.can_manage_privileged_roles(user_id, chat_id).await? {
+        bot.send_message(chat_id, i18n.t("commands.roles.owner_only", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), name);
+
+    let key = match new_role {
+        Some(role) => {
+            services.auth_service.set_group_role(chat_id, target_telegram_id, role, user_id).await?;
+            params.insert("role".to_string(), i18n.t(&format!("commands.roles.names.{}", role.as_str()), &lang, None));
+            "commands.roles.granted"
+        }
+        None if services.auth_service.remove_group_role(chat_id, target_telegram_id, user_id).await? => "commands.roles.removed",
+        None => "commands.roles.no_role",
+    };
+    bot.send_message(chat_id, i18n.t(key, &lang, Some(&params))).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, target_id = target_telegram_id, role = ?new_role, "Role command handled");
+
+    Ok(())
+}
+
+/// List the delegated roles of a group
+async fn format_group_roles(services: &ServiceFactory, chat_id: ChatId, lang: &str, i18n: &I18n) -> Result<String> {
+    let roles = services.auth_service.list_group_roles(chat_id).await?;
+    if roles.is_empty() {
+        return Ok(i18n.t("commands.roles.empty", lang, None));
+    }
+
+    let mut text = i18n.t("commands.roles.list_title", lang, None);
+    for group_role in roles {
+        let Some(role) = group_role.role_kind() else {
+            continue;
+        };
+        let name = services.user_service.get_user_by_telegram_id(group_role.user_telegram_id).await?
+            .and_then(|user| user.username.map(|username| format!("@{}", username)).or(user.first_name))
+            .unwrap_or_else(|| group_role.user_telegram_id.to_string());
+
+        let mut params = HashMap::new();
+        params.insert("name".to_string(), name);
+        params.insert("role".to_string(), i18n.t(&format!("commands.roles.names.{}", role.as_str()), lang, None));
+        text.push('\n');
+        text.push_str(&i18n.t("commands.roles.list_item", lang, Some(&params)));
+    }
+
+    Ok(text)
+}
+
+/// Handle /sharedbans command - opt the group in or out of the shared ban list
+pub async fn handle_shared_bans_toggle(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /sharedbans command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let Some(enabled) = parse_toggle(args.trim()) else {
+        bot.send_message(chat_id, i18n.t("commands.bans.shared_usage", &lang, None)).await?;
+        return Ok(());
+    };
+
+    if !services.group_service.set_shared_bans(chat_id.0, enabled).await? {
+        bot.send_message(chat_id, i18n.t("commands.events.group_not_registered", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let text = if enabled {
+        let mut params = HashMap::new();
+        params.insert("count".to_string(), services.moderation_service.apply_shared_bans(chat_id).await?.to_string());
+        i18n.t("commands.bans.shared_enabled", &lang, Some(&params))
+    } else {
+        i18n.t("commands.bans.shared_disabled", &lang, None)
+    };
+    bot.send_message(chat_id, text).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, enabled = enabled, "Group shared ban list toggled");
+
+    Ok(())
+}
+
+/// Handle /cleanup command - delete join/leave messages and temporary bot replies after a delay
+pub async fn handle_cleanup(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /cleanup command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    if !services.cleanup_service.is_enabled() {
+        bot.send_message(chat_id, i18n.t("commands.cleanup.not_configured", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let mut parts = args.split_whitespace();
+    let Some(enabled) = parts.next().and_then(parse_toggle) else {
+        bot.send_message(chat_id, i18n.t("commands.cleanup.usage", &lang, None)).await?;
+        return Ok(());
+    };
+
+    let mut params = HashMap::new();
+    params.insert("min".to_string(), MIN_DELAY_SECONDS.to_string());
+    params.insert("max".to_string(), MAX_DELAY_SECONDS.to_string());
+
+    let delay_seconds = match parts.next().filter(|_| enabled) {
+        Some(arg) => match arg.parse::<u64>().ok().filter(|d| (MIN_DELAY_SECONDS..=MAX_DELAY_SECONDS).contains(d)) {
+            Some(delay) => Some(delay),
+            None => {
+                bot.send_message(chat_id, i18n.t("commands.cleanup.invalid_delay", &lang, Some(&params))).await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    if !services.cleanup_service.set_group_cleanup(chat_id.0, enabled, delay_seconds).await? {
+        bot.send_message(chat_id, i18n.t("commands.events.group_not_registered", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let text = match services.cleanup_service.group_delay(chat_id).await? {
+        Some(delay) => {
+            params.insert("seconds".to_string(), delay.as_secs().to_string());
+            i18n.t("commands.cleanup.enabled", &lang, Some(&params))
+        }
+        None => i18n.t("commands.cleanup.disabled", &lang, None),
+    };
+    let reply = bot.send_message(chat_id, text).await?;
+    schedule_cleanup(&services, chat_id, reply.id).await;
+
+    info!(user_id = user_id, chat_id = ?chat_id, enabled = enabled, delay = ?delay_seconds, "Group message cleanup toggled");
+
+    Ok(())
+}
+
+/// Handle /intro command - choose whether new members get an intro card in the group or by DM
+pub async fn handle_intro(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /intro command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let Ok(mode) = args.trim().to_lowercase().parse::<IntroCardMode>() else {
+        bot.send_message(chat_id, i18n.t("commands.intro.usage", &lang, None)).await?;
+        return Ok(());
+    };
+
+    let key = if !services.group_service.set_intro_card_mode(chat_id.0, mode).await? {
+        "commands.events.group_not_registered"
+    } else {
+        match mode {
+            IntroCardMode::Off => "commands.intro.off",
+            IntroCardMode::Group => "commands.intro.group",
+            IntroCardMode::Private => "commands.intro.private",
+        }
+    };
+    let reply = bot.send_message(chat_id, i18n.t(key, &lang, None)).await?;
+    schedule_cleanup(&services, chat_id, reply.id).await;
+
+    info!(user_id = user_id, chat_id = ?chat_id, mode = ?mode, "Group intro card mode changed");
+
+    Ok(())
+}
+
+/// Handle /topic command - bind event announcements or moderation notices to the current forum topic
+pub async fn handle_topic(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, thread_id = ?msg.thread_id, "Processing /topic command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    // Replies go to the topic the command was sent in
+    let thread_id = msg.thread_id.filter(|_| msg.is_topic_message);
+    let reply = |text: String| {
+        let request = bot.send_message(chat_id, text);
+        match thread_id {
+            Some(thread_id) => request.message_thread_id(thread_id),
+            None => request,
+        }
+    };
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        reply(i18n.t("commands.admin.access_denied", "en", None)).await?;
+        return Ok(());
+    }
+
+    let lang = match services.group_service.get_group(chat_id.0).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    let mut parts = args.split_whitespace();
+    let topic = parts.next().and_then(|topic| topic.to_lowercase().parse::<GroupTopic>().ok());
+    let clear = parts.next().and_then(parse_toggle) == Some(false);
+    let Some(topic) = topic else {
+        reply(i18n.t("commands.topic.usage", &lang, None)).await?;
+        return Ok(());
+    };
+
+    // Only messages inside a forum topic carry its ID; the general topic has none
+    let bound = match (clear, thread_id) {
+        (true, _) => None,
+        (false, Some(thread_id)) => Some(thread_id.0.0),
+        (false, None) => {
+            reply(i18n.t("commands.topic.not_in_topic", &lang, None)).await?;
+            return Ok(());
+        }
+    };
+
+    if !services.group_service.set_topic(chat_id.0, topic, bound).await? {
+        reply(i18n.t("commands.events.group_not_registered", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let key = match (topic, bound) {
+        (GroupTopic::Events, Some(_)) => "commands.topic.events_bound",
+        (GroupTopic::Moderation, Some(_)) => "commands.topic.moderation_bound",
+        (GroupTopic::Events, None) => "commands.topic.events_cleared",
+        (GroupTopic::Moderation, None) => "commands.topic.moderation_cleared",
+    };
+    reply(i18n.t(key, &lang, None)).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, topic = topic.as_str(), thread_id = ?bound, "Group topic changed");
+
+    Ok(())
+}
+
+/// Have a temporary message deleted later if the group uses cleanup, logging failures
+pub async fn schedule_cleanup(services: &ServiceFactory, chat_id: ChatId, message_id: MessageId) {
+    if let Err(e) = services.cleanup_service.schedule(chat_id, message_id).await {
+        warn!(error = %e, chat_id = chat_id.0, "Failed to schedule message for cleanup");
+    }
+}
+
+/// Handle /report command - members reply to a message to report it to the admins;
+/// admins choose where reports go with `/report chat <chat ID|off>`
+pub async fn handle_report(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /report command");
+
+    if chat_id.is_user() {
+        let text = i18n.t("commands.moderation.group_only", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    let group = services.group_service.get_group(chat_id.0).await?;
+    let lang = group.as_ref().map_or("en", |g| g.language_code.as_str()).to_string();
+
+    let args = args.trim();
+    if let Some(target) = args.strip_prefix("chat").filter(|_| msg.reply_to_message().is_none()) {
+        return set_report_chat(&bot, &msg, target.trim(), &services, &i18n, &lang).await;
+    }
+
+    let Some(reported) = msg.reply_to_message() else {
+        bot.send_message(chat_id, i18n.t("commands.report.usage", &lang, None)).await?;
+        return Ok(());
+    };
+
+    let reason = Some(args).filter(|reason| !reason.is_empty()).map(str::to_string);
+    let Some(report) = services.moderation_service.report(CreateReportRequest {
+        chat_telegram_id: chat_id.0,
+        message_id: reported.id.0,
+        reporter_telegram_id: user_id,
+        reported_telegram_id: reported.from.as_ref().map(|author| author.id.0 as i64),
+        reason: reason.clone(),
+    }).await? else {
+        let reply = bot.send_message(chat_id, i18n.t("commands.report.already_reported", &lang, None))
+            .reply_parameters(ReplyParameters::new(msg.id))
+            .await?;
+        schedule_cleanup(&services, chat_id, reply.id).await;
+        return Ok(());
+    };
+
+    let mut params = HashMap::new();
+    params.insert("id".to_string(), report.id.to_string());
+    params.insert("group".to_string(), msg.chat.title().unwrap_or_default().to_string());
+    params.insert("reporter".to_string(), member_name(user));
+    params.insert("author".to_string(), reported.from.as_ref().map_or_else(|| "—".to_string(), member_name));
+    params.insert("open".to_string(), services.moderation_service.count_open_reports(chat_id).await?.to_string());
+
+    let mut text = i18n.t("commands.report.notification", &lang, Some(&params));
+    if let Some(reason) = reason {
+        params.insert("reason".to_string(), reason);
+        text.push('\n');
+        text.push_str(&i18n.t("commands.report.reason", &lang, Some(&params)));
+    }
+    if let Some(url) = reported.url() {
+        text.push('\n');
+        text.push_str(url.as_str());
+    }
+
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        i18n.t("buttons.report.resolve", &lang, None),
+        CallbackData::ResolveReport(report.id),
+    )]]);
+
+    // Admins who never started the bot cannot be messaged; the others still get the report
+    let mut delivered = 0;
+    for recipient in services.moderation_service.report_recipients(chat_id, group.as_ref()).await? {
+        if let Err(e) = bot.forward_message(recipient, chat_id, reported.id).await {
+            warn!(error = %e, recipient = recipient.0, report_id = report.id, "Failed to forward reported message");
+        }
+        match bot.send_message(recipient, text.clone()).reply_markup(keyboard.clone()).await {
+            Ok(_) => delivered += 1,
+            Err(e) => warn!(error = %e, recipient = recipient.0, report_id = report.id, "Failed to deliver report"),
+        }
+    }
+
+    let key = if delivered > 0 { "commands.report.sent" } else { "commands.report.not_delivered" };
+    let reply = bot.send_message(chat_id, i18n.t(key, &lang, None))
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .await?;
+    schedule_cleanup(&services, chat_id, reply.id).await;
+
+    info!(user_id = user_id, chat_id = ?chat_id, report_id = report.id, delivered = delivered, "Report command handled");
+
+    Ok(())
+}
+
+/// Set or clear the chat that receives the reports of a group
+async fn set_report_chat(
+    bot: &Bot,
+    msg: &Message,
+    target: &str,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    lang: &str,
+) -> Result<()> {
+    let user_id = msg.from.as_ref().map_or(0, |user| user.id.0 as i64);
+    let chat_id = msg.chat.id;
+
+    if !services.auth_service.can_manage_users(user_id, Some(chat_id)).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let report_chat_id = match (target, target.parse::<i64>()) {
+        ("off", _) => None,
+        (_, Ok(report_chat_id)) => Some(report_chat_id),
+        _ => {
+            bot.send_message(chat_id, i18n.t("commands.report.usage", lang, None)).await?;
+            return Ok(());
+        }
+    };
+
+    // Make sure the bot can post there before sending reports into the void
+    if let Some(report_chat_id) = report_chat_id {
+        let mut params = HashMap::new();
+        params.insert("group".to_string(), msg.chat.title().unwrap_or_default().to_string());
+        if let Err(e) = bot.send_message(ChatId(report_chat_id), i18n.t("commands.report.chat_connected", lang, Some(&params))).await {
+            warn!(error = %e, report_chat_id = report_chat_id, "Cannot post to the report chat");
+            bot.send_message(chat_id, i18n.t("commands.report.chat_unreachable", lang, None)).await?;
+            return Ok(());
+        }
+    }
+
+    let key = if !services.group_service.set_report_chat(chat_id.0, report_chat_id).await? {
+        "commands.events.group_not_registered"
+    } else if report_chat_id.is_some() {
+        "commands.report.chat_set"
+    } else {
+        "commands.report.chat_cleared"
+    };
+    bot.send_message(chat_id, i18n.t(key, lang, None)).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, report_chat_id = ?report_chat_id, "Report chat changed");
+
+    Ok(())
+}
+
+/// Handle the "resolved" button of a delivered report
+pub async fn handle_report_callback(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    user: &User,
+    report_id: i64,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let Some(report) = services.moderation_service.get_report(report_id).await? else {
+        return Ok(());
+    };
+
+    let user_id = user.id.0 as i64;
+    let lang = match services.group_service.get_group(report.chat_telegram_id).await? {
+        Some(group) => group.language_code,
+        None => "en".to_string(),
+    };
+
+    if !services.auth_service.can_moderate(user_id, Some(ChatId(report.chat_telegram_id))).await? {
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", &lang, None)).await?;
+        return Ok(());
+    }
+
+    let mut params = HashMap::new();
+    params.insert("id".to_string(), report.id.to_string());
+    params.insert("name".to_string(), member_name(user));
+
+    let key = match services.moderation_service.resolve_report(report_id, user_id).await? {
+        Some(_) => "commands.report.resolved",
+        None => "commands.report.already_resolved",
+    };
+
+    if let Some(message_id) = message_id {
+        if let Err(e) = bot.edit_message_reply_markup(chat_id, message_id).await {
+            warn!(error = %e, report_id = report_id, "Failed to remove report keyboard");
+        }
+    }
+    bot.send_message(chat_id, i18n.t(key, &lang, Some(&params))).await?;
+
+    Ok(())
+}
+
+/// Name of a member for moderation messages, with their username if they have one
+fn member_name(user: &User) -> String {
+    match &user.username {
+        Some(username) => format!("{} (@{})", user.full_name(), username),
+        None => user.full_name(),
+    }
+}
@@ -0,0 +1,222 @@
+//! User note command handlers
+//!
+//! Notes are private: every command here is restricted to admins and only
+//! works in a private chat with the bot.
+
+use std::collections::HashMap;
+use teloxide::{Bot, types::{Message, ChatId}, prelude::*};
+use tracing::{info, debug};
+use crate::utils::errors::{Result, SwingBuddyError};
+use crate::services::ServiceFactory;
+use crate::services::note::MAX_NOTE_LENGTH;
+use crate::i18n::I18n;
+use crate::models::note::UserNote;
+
+/// Handle /note command - attach a note to a user
+pub async fn handle_add_note(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let Some((user_id, user_lang)) = authorize(&bot, &msg, &services, &i18n).await? else {
+        return Ok(());
+    };
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /note command");
+
+    let Some((target_id, content)) = split_id_and_text(&args) else {
+        let text = i18n.t("commands.notes.usage_add", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    match services.note_service.add_note(user_id, target_id, content).await {
+        Ok(note) => {
+            let mut params = HashMap::new();
+            params.insert("note_id".to_string(), note.id.to_string());
+            let text = i18n.t("commands.notes.added", &user_lang, Some(&params));
+            bot.send_message(chat_id, text).await?;
+            info!(admin_id = user_id, target_user_id = target_id, note_id = note.id, "Admin added user note");
+        }
+        Err(e) => send_note_error(&bot, chat_id, &i18n, &user_lang, e).await?,
+    }
+
+    Ok(())
+}
+
+/// Handle /editnote command - replace note content, keeping history
+pub async fn handle_edit_note(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let Some((user_id, user_lang)) = authorize(&bot, &msg, &services, &i18n).await? else {
+        return Ok(());
+    };
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /editnote command");
+
+    let Some((note_id, content)) = split_id_and_text(&args) else {
+        let text = i18n.t("commands.notes.usage_edit", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    match services.note_service.edit_note(user_id, note_id, content).await {
+        Ok(Some(note)) => {
+            let mut params = HashMap::new();
+            params.insert("note_id".to_string(), note.id.to_string());
+            let text = i18n.t("commands.notes.edited", &user_lang, Some(&params));
+            bot.send_message(chat_id, text).await?;
+            info!(admin_id = user_id, note_id = note.id, "Admin edited user note");
+        }
+        Ok(None) => {
+            let text = i18n.t("commands.notes.not_found", &user_lang, None);
+            bot.send_message(chat_id, text).await?;
+        }
+        Err(e) => send_note_error(&bot, chat_id, &i18n, &user_lang, e).await?,
+    }
+
+    Ok(())
+}
+
+/// Handle /notehistory command - show a note and its previous versions
+pub async fn handle_note_history(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let Some((user_id, user_lang)) = authorize(&bot, &msg, &services, &i18n).await? else {
+        return Ok(());
+    };
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /notehistory command");
+
+    let Ok(note_id) = args.trim().parse::<i64>() else {
+        let text = i18n.t("commands.notes.usage_history", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    let Some((note, revisions)) = services.note_service.get_note_history(note_id).await? else {
+        let text = i18n.t("commands.notes.not_found", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    let mut params = HashMap::new();
+    params.insert("note_id".to_string(), note.id.to_string());
+    let mut text = i18n.t("commands.notes.history_title", &user_lang, Some(&params));
+    text.push_str("\n\n");
+    text.push_str(&format_note_line(&i18n, &user_lang, &note));
+
+    if revisions.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.notes.no_history", &user_lang, None));
+    }
+
+    for revision in revisions {
+        let mut params = HashMap::new();
+        params.insert("date".to_string(), revision.edited_at.format("%Y-%m-%d %H:%M").to_string());
+        params.insert("editor".to_string(), revision.edited_by.to_string());
+        params.insert("content".to_string(), revision.content);
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.notes.revision", &user_lang, Some(&params)));
+    }
+
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+/// Format a single note for admin views
+pub fn format_note_line(i18n: &I18n, language_code: &str, note: &UserNote) -> String {
+    let mut params = HashMap::new();
+    params.insert("note_id".to_string(), note.id.to_string());
+    params.insert("date".to_string(), note.updated_at.format("%Y-%m-%d").to_string());
+    params.insert("author".to_string(), note.author_id.to_string());
+    params.insert("content".to_string(), note.content.clone());
+    i18n.t("commands.notes.line", language_code, Some(&params))
+}
+
+/// Check that the sender is an admin in a private chat, returning their ID and language
+async fn authorize(
+    bot: &Bot,
+    msg: &Message,
+    services: &ServiceFactory,
+    i18n: &I18n,
+) -> Result<Option<(i64, String)>> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    if !services.auth_service.can_view_user_notes(user_id).await? {
+        let error_text = i18n.t("commands.admin.access_denied", "en", None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(None);
+    }
+
+    // Get user language
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    // Notes must never leak into group chats
+    if !chat_id.is_user() {
+        let text = i18n.t("commands.notes.private_only", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(None);
+    }
+
+    Ok(Some((user_id, user_lang)))
+}
+
+/// Report a note operation error to the admin
+async fn send_note_error(bot: &Bot, chat_id: ChatId, i18n: &I18n, language_code: &str, error: SwingBuddyError) -> Result<()> {
+    let text = match error {
+        SwingBuddyError::UserNotFound { .. } => i18n.t("commands.admin.user_not_found", language_code, None),
+        SwingBuddyError::InvalidInput(_) => {
+            let mut params = HashMap::new();
+            params.insert("max".to_string(), MAX_NOTE_LENGTH.to_string());
+            i18n.t("commands.notes.invalid", language_code, Some(&params))
+        }
+        other => return Err(other),
+    };
+
+    bot.send_message(chat_id, text).await?;
+    Ok(())
+}
+
+/// Split "<id> <text>" command arguments
+fn split_id_and_text(args: &str) -> Option<(i64, &str)> {
+    let (id, text) = args.trim().split_once(char::is_whitespace)?;
+    let id = id.parse().ok()?;
+    Some((id, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_id_and_text() {
+        assert_eq!(split_id_and_text("42 volunteered at 3 events"), Some((42, "volunteered at 3 events")));
+        assert_eq!(split_id_and_text("  7\tpayment issue "), Some((7, "payment issue")));
+        assert_eq!(split_id_and_text("42"), None);
+        assert_eq!(split_id_and_text("abc text"), None);
+    }
+}
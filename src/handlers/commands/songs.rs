@@ -0,0 +1,318 @@
+//! Song request command handlers
+//!
+//! Handles /request, which puts a track in the DJ's queue while an event is on,
+//! /queue, which shows DJs the waiting tracks with played/skip buttons, and
+//! /playlist, which lists what was played at an event
+
+use std::collections::HashMap;
+use teloxide::{Bot, types::{Message, ChatId, MessageId, InlineKeyboardMarkup}, prelude::*};
+use tracing::{info, debug};
+use crate::utils::errors::{SwingBuddyError, Result};
+use crate::services::ServiceFactory;
+use crate::handlers::callbacks::{CallbackData, SongAction};
+use crate::services::song_request::{normalize_track, SongRequestOutcome, MAX_PENDING_PER_ATTENDEE, MAX_TRACK_LENGTH};
+use crate::models::event::Event;
+use crate::models::song_request::SongRequestStatus;
+use crate::i18n::I18n;
+
+/// Waiting tracks shown with buttons in the queue message
+const QUEUE_PAGE_SIZE: usize = 10;
+
+/// Handle /request command - put a track in the queue of the event that is on
+pub async fn handle_song_request(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /request command");
+
+    let user_lang = user_language(&services, user_id).await?;
+
+    let mut params = HashMap::new();
+    let track = if args.trim().is_empty() {
+        bot.send_message(chat_id, i18n.t("commands.songs.request_usage", &user_lang, None)).await?;
+        return Ok(());
+    } else if let Some(track) = normalize_track(&args) {
+        track
+    } else {
+        params.insert("max".to_string(), MAX_TRACK_LENGTH.to_string());
+        bot.send_message(chat_id, i18n.t("commands.songs.track_too_long", &user_lang, Some(&params))).await?;
+        return Ok(());
+    };
+
+    let Some(event) = current_event(&services, chat_id, user_id).await? else {
+        bot.send_message(chat_id, i18n.t("commands.songs.no_current_event", &user_lang, None)).await?;
+        return Ok(());
+    };
+
+    params.insert("title".to_string(), event.title.clone());
+    let text = match services.song_request_service.request_song(&event, user_id, &track).await? {
+        SongRequestOutcome::Queued(song, position) => {
+            params.insert("track".to_string(), song.track);
+            params.insert("position".to_string(), position.to_string());
+            i18n.t("commands.songs.queued", &user_lang, Some(&params))
+        }
+        SongRequestOutcome::AlreadyQueued(song) => {
+            params.insert("track".to_string(), song.track);
+            i18n.t("commands.songs.already_queued", &user_lang, Some(&params))
+        }
+        SongRequestOutcome::TooMany => {
+            params.insert("max".to_string(), MAX_PENDING_PER_ATTENDEE.to_string());
+            i18n.t("commands.songs.too_many", &user_lang, Some(&params))
+        }
+    };
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+/// Handle /queue command - show the DJ the waiting tracks of the event that is
+/// on, or of the event with the given ID
+pub async fn handle_song_queue(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /queue command");
+
+    let user_lang = user_language(&services, user_id).await?;
+
+    let Some(event) = find_event(&bot, chat_id, user_id, &args, &services, &i18n, &user_lang, "commands.songs.queue_usage").await? else {
+        return Ok(());
+    };
+
+    if !is_dj(&services, chat_id, user_id, &event).await? {
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", &user_lang, None)).await?;
+        return Ok(());
+    }
+
+    let (text, keyboard) = queue_message(&services, &event, &i18n, &user_lang).await?;
+    bot.send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Handle /playlist command - list the requested tracks played at an event
+pub async fn handle_playlist(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /playlist command");
+
+    let user_lang = user_language(&services, user_id).await?;
+
+    let Some(event) = find_event(&bot, chat_id, user_id, &args, &services, &i18n, &user_lang, "commands.songs.playlist_usage").await? else {
+        return Ok(());
+    };
+
+    let playlist = services.song_request_service.get_playlist(event.id).await?;
+
+    let mut params = HashMap::new();
+    params.insert("title".to_string(), event.title.clone());
+    let text = if playlist.is_empty() {
+        i18n.t("commands.songs.playlist_empty", &user_lang, Some(&params))
+    } else {
+        let tracks: Vec<String> = playlist.iter()
+            .enumerate()
+            .map(|(i, song)| format!("{}. {}", i + 1, song.track))
+            .collect();
+        format!("{}\n\n{}", i18n.t("commands.songs.playlist_title", &user_lang, Some(&params)), tracks.join("\n"))
+    };
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+/// Handle the played, skip and refresh buttons of the queue message. The
+/// queue is redrawn in place afterwards.
+pub async fn handle_song_callback(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    user_id: i64,
+    action: SongAction,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let (event_id, handled) = match action {
+        SongAction::Done(song_id) | SongAction::Skip(song_id) => {
+            let Some(song) = services.song_request_service.get_request(song_id).await? else {
+                return Ok(());
+            };
+            let status = match action {
+                SongAction::Done(_) => SongRequestStatus::Played,
+                _ => SongRequestStatus::Skipped,
+            };
+            (song.event_id, Some((song_id, status)))
+        }
+        SongAction::Refresh(event_id) => (event_id, None),
+    };
+    let Some(event) = services.event_service.get_event(event_id).await? else {
+        return Ok(());
+    };
+
+    let user_lang = user_language(&services, user_id).await?;
+
+    if !is_dj(&services, chat_id, user_id, &event).await? {
+        bot.send_message(chat_id, i18n.t("commands.admin.access_denied", &user_lang, None)).await?;
+        return Ok(());
+    }
+
+    // Another DJ may have handled the track already; the redrawn queue shows it
+    if let Some((song_id, status)) = handled {
+        services.song_request_service.handle_request(song_id, status, user_id).await?;
+    }
+
+    let (text, keyboard) = queue_message(&services, &event, &i18n, &user_lang).await?;
+    match message_id {
+        Some(message_id) => {
+            if let Err(e) = bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboard)
+                .await
+            {
+                // Refreshing an unchanged queue is rejected by Telegram
+                debug!(error = %e, event_id = event.id, "Song queue not redrawn");
+            }
+        }
+        None => {
+            bot.send_message(chat_id, text)
+                .reply_markup(keyboard)
+                .await?;
+        }
+    }
+
+    info!(user_id = user_id, event_id = event.id, action = ?action, "Song queue callback handled");
+
+    Ok(())
+}
+
+/// The queue text with played/skip buttons for the first waiting tracks
+async fn queue_message(services: &ServiceFactory, event: &Event, i18n: &I18n, lang: &str) -> Result<(String, InlineKeyboardMarkup)> {
+    let queue = services.song_request_service.get_queue(event.id).await?;
+
+    let mut params = HashMap::new();
+    params.insert("title".to_string(), event.title.clone());
+    params.insert("count".to_string(), queue.len().to_string());
+
+    let mut text = if queue.is_empty() {
+        i18n.t("commands.songs.queue_empty", lang, Some(&params))
+    } else {
+        i18n.t("commands.songs.queue_title", lang, Some(&params))
+    };
+
+    let mut keyboard = Vec::new();
+    for (i, song) in queue.iter().take(QUEUE_PAGE_SIZE).enumerate() {
+        let position = (i + 1).to_string();
+        text.push_str(&format!("\n{}. {}", position, song.track));
+
+        let mut params = HashMap::new();
+        params.insert("position".to_string(), position);
+        keyboard.push(vec![
+            CallbackData::Song(SongAction::Done(song.id)).button(i18n.t("buttons.songs.done", lang, Some(&params))),
+            CallbackData::Song(SongAction::Skip(song.id)).button(i18n.t("buttons.songs.skip", lang, Some(&params))),
+        ]);
+    }
+    if queue.len() > QUEUE_PAGE_SIZE {
+        params.insert("count".to_string(), (queue.len() - QUEUE_PAGE_SIZE).to_string());
+        text.push('\n');
+        text.push_str(&i18n.t("commands.songs.queue_more", lang, Some(&params)));
+    }
+    keyboard.push(vec![
+        CallbackData::Song(SongAction::Refresh(event.id)).button(i18n.t("buttons.songs.refresh", lang, None)),
+    ]);
+
+    Ok((text, InlineKeyboardMarkup::new(keyboard)))
+}
+
+/// The event a command is about: the one with the ID in `args`, or the one
+/// that is on right now. Tells the user when there is none.
+#[allow(clippy::too_many_arguments)]
+async fn find_event(
+    bot: &Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    args: &str,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    lang: &str,
+    usage_key: &str,
+) -> Result<Option<Event>> {
+    let args = args.trim();
+    if args.is_empty() {
+        let event = current_event(services, chat_id, user_id).await?;
+        if event.is_none() {
+            bot.send_message(chat_id, i18n.t(usage_key, lang, None)).await?;
+        }
+        return Ok(event);
+    }
+
+    let Ok(event_id) = args.parse::<i64>() else {
+        bot.send_message(chat_id, i18n.t(usage_key, lang, None)).await?;
+        return Ok(None);
+    };
+    let event = services.event_service.get_event(event_id).await?;
+    if event.is_none() {
+        bot.send_message(chat_id, i18n.t("commands.events.not_found", lang, None)).await?;
+    }
+    Ok(event)
+}
+
+/// The event on right now: the group's in a group chat, otherwise one the
+/// user registered for
+async fn current_event(services: &ServiceFactory, chat_id: ChatId, user_id: i64) -> Result<Option<Event>> {
+    let group_id = if chat_id.is_user() {
+        None
+    } else {
+        match services.group_service.get_group(chat_id.0).await? {
+            Some(group) => Some(group.id),
+            None => return Ok(None),
+        }
+    };
+    services.song_request_service.find_current_event(group_id, user_id).await
+}
+
+/// Whether a user may work the queue of an event: its organizer, or anyone
+/// who manages events here
+async fn is_dj(services: &ServiceFactory, chat_id: ChatId, user_id: i64, event: &Event) -> Result<bool> {
+    let chat = (!chat_id.is_user()).then_some(chat_id);
+    if services.auth_service.can_manage_events(user_id, chat).await? {
+        return Ok(true);
+    }
+    services.user_service.is_event_organizer(user_id, event).await
+}
+
+/// Language of a user, English until they register
+async fn user_language(services: &ServiceFactory, user_id: i64) -> Result<String> {
+    Ok(services.user_service.get_user_by_telegram_id(user_id).await?
+        .map_or_else(|| "en".to_string(), |user| user.language_code))
+}
@@ -3,13 +3,48 @@
 //! Handles the /start command and user onboarding flow
 
 use std::collections::HashMap;
-use teloxide::{Bot, types::{Message, InlineKeyboardMarkup, InlineKeyboardButton, ChatId}, prelude::*};
+use teloxide::{Bot, types::{Me, Message, InlineKeyboardMarkup, InlineKeyboardButton, ChatId}, prelude::*};
 use tracing::{info, debug, warn, error};
 use crate::utils::errors::Result;
 use crate::services::ServiceFactory;
 use crate::state::{ScenarioManager, StateStorage, ConversationContext};
 use crate::i18n::I18n;
 use crate::models::user::CreateUserRequest;
+use crate::models::activity::ActivityAction;
+use crate::services::referral::{parse_referral_payload, referral_payload};
+use super::events;
+use crate::handlers::callbacks::wizard;
+use crate::handlers::callbacks::{CallbackData, ScenarioAction};
+
+/// /start payload that opens the list of upcoming events
+pub const EVENTS_START_PAYLOAD: &str = "events";
+
+/// Onboarding context key of the /start payload to act on once onboarding completes
+const START_PAYLOAD_KEY: &str = "start_payload";
+
+/// What a /start deep link asks for, besides referral attribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StartPayload {
+    /// `events`: the event calendars
+    Events,
+    /// `event_<id>`: an event with its registration buttons
+    Event(i64),
+    /// `group_<chat id>`: the user came from a group's link
+    Group(i64),
+}
+
+impl StartPayload {
+    fn parse(payload: &str) -> Option<Self> {
+        let payload = payload.trim();
+        if payload == EVENTS_START_PAYLOAD {
+            return Some(Self::Events);
+        }
+        if let Some(event_id) = payload.strip_prefix("event_") {
+            return event_id.parse().ok().map(Self::Event);
+        }
+        payload.strip_prefix("group_")?.parse().ok().map(Self::Group)
+    }
+}
 
 /// Handle /start command - main entry point for user onboarding
 pub async fn handle_start(
@@ -36,6 +71,8 @@ pub async fn handle_start(
         return Ok(());
     }
 
+    let payload = msg.text().and_then(|text| text.split_whitespace().nth(1));
+
     // Check if user exists in database
     match services.user_service.get_user_by_telegram_id(user_id).await? {
         Some(existing_user) => {
@@ -50,6 +87,11 @@ pub async fn handle_start(
             
             let welcome_text = i18n.t("commands.start.returning_user", user_lang, Some(&params));
             bot.send_message(chat_id, welcome_text).await?;
+
+            // Deep links, e.g. the intro card's events button opening /start events
+            if let Some(payload) = payload.and_then(StartPayload::parse) {
+                run_start_payload(bot, chat_id, user_id, payload, &services, &i18n, user_lang).await?;
+            }
             
             info!(user_id = user_id, "Existing user started bot");
         }
@@ -67,41 +109,61 @@ pub async fn handle_start(
                 location: None,
             };
             
-            let _new_user = services.user_service.register_or_get_user(
+            let new_user = services.user_service.register_or_get_user(
                 user_id,
                 user.username.clone(),
                 Some(user.first_name.clone()),
                 user.last_name.clone(),
             ).await?;
+
+            // Attribute the new user to whoever shared the /start ref_<user_id> link
+            let referrer_id = payload.and_then(parse_referral_payload);
+            if let Some(referrer_id) = referrer_id {
+                if let Err(e) = services.referral_service.record_referral(referrer_id, new_user.id).await {
+                    warn!(user_id = user_id, referrer_id = referrer_id, error = %e, "Failed to record referral");
+                }
+            }
             
             // Start onboarding scenario
-            info!(user_id = user_id, "🔍 START HANDLER: Starting onboarding scenario for new user");
+            debug!(user_id = user_id, "Starting onboarding scenario for new user");
             let mut context = ConversationContext::new(user_id);
             
             match scenario_manager.start_scenario(&mut context, "onboarding") {
                 Ok(_) => {
-                    info!(user_id = user_id, scenario = ?context.scenario, step = ?context.step,
-                           "🔍 START HANDLER: Onboarding scenario started successfully");
+                    debug!(user_id = user_id, scenario = ?context.scenario, step = ?context.step,
+                           "Onboarding scenario started successfully");
                 },
                 Err(e) => {
-                    error!(user_id = user_id, error = %e, "🔍 START HANDLER: Failed to start onboarding scenario");
+                    error!(user_id = user_id, error = %e, "Failed to start onboarding scenario");
                     return Err(e);
                 }
             }
+
+            // Other deep links are acted on once onboarding completes
+            if let Some(payload) = payload.filter(|payload| StartPayload::parse(payload).is_some()) {
+                context.set_data(START_PAYLOAD_KEY, payload)?;
+            }
             
-            info!(user_id = user_id, "🔍 START HANDLER: Attempting to save context to storage");
-            match state_storage.save_context(&context).await {
+            debug!(user_id = user_id, "Attempting to save context to storage");
+            match state_storage.save_context(&mut context).await {
                 Ok(_) => {
-                    info!(user_id = user_id, "🔍 START HANDLER: Context saved successfully after starting onboarding");
+                    debug!(user_id = user_id, "Context saved successfully after starting onboarding");
                 },
                 Err(e) => {
-                    error!(user_id = user_id, error = %e, "🔍 START HANDLER: Failed to save context after starting onboarding - this could be the issue!");
+                    error!(user_id = user_id, error = %e, "Failed to save context after starting onboarding");
                     return Err(e);
                 }
             }
             
+            // Send configured welcome media before the greeting; never block onboarding on it
+            let detected_lang = i18n.detect_user_language(user.language_code.as_deref());
+            let mut notification_service = services.notification_service.clone();
+            if let Err(e) = notification_service.send_welcome_media(chat_id, &detected_lang).await {
+                warn!(user_id = user_id, error = %e, "Failed to send onboarding welcome media");
+            }
+
             // Show language selection
-            info!(user_id = user_id, "🔍 START HANDLER: Showing language selection to user");
+            debug!(user_id = user_id, "Showing language selection to user");
             show_language_selection(bot, chat_id, &i18n).await?;
         }
     }
@@ -111,35 +173,33 @@ pub async fn handle_start(
 
 /// Show language selection keyboard
 async fn show_language_selection(bot: Bot, chat_id: ChatId, i18n: &I18n) -> Result<()> {
-    info!(chat_id = ?chat_id, "🔍 LANG SELECTION: Creating language selection keyboard");
+    debug!(chat_id = ?chat_id, "Creating language selection keyboard");
     
     let welcome_text = i18n.t("commands.start.new_user_greeting", "en", None);
     let choose_lang_text = i18n.t("commands.start.choose_language", "en", None);
     
-    info!("🔍 LANG SELECTION: Creating keyboard buttons with callback data");
     let keyboard = InlineKeyboardMarkup::new(vec![
         vec![
             InlineKeyboardButton::callback(
                 i18n.t("buttons.language.english", "en", None),
-                "lang:en"
+                CallbackData::Language("en".to_string())
             ),
             InlineKeyboardButton::callback(
                 i18n.t("buttons.language.russian", "ru", None),
-                "lang:ru"
+                CallbackData::Language("ru".to_string())
             ),
         ]
     ]);
     
-    info!("🔍 LANG SELECTION: Buttons created - English: 'lang:en', Russian: 'lang:ru'");
     
     let full_text = format!("{}\n\n{}", welcome_text, choose_lang_text);
     
-    info!(chat_id = ?chat_id, "🔍 LANG SELECTION: Sending message with keyboard");
+    debug!(chat_id = ?chat_id, "Sending message with keyboard");
     bot.send_message(chat_id, full_text)
         .reply_markup(keyboard)
         .await?;
     
-    info!(chat_id = ?chat_id, "🔍 LANG SELECTION: Language selection message sent successfully");
+    debug!(chat_id = ?chat_id, "Language selection message sent successfully");
     Ok(())
 }
 
@@ -154,37 +214,37 @@ pub async fn handle_language_callback(
     state_storage: StateStorage,
     i18n: I18n,
 ) -> Result<()> {
-    info!(user_id = user_id, language_code = %language_code, "🔍 LANG HANDLER: User selected language - starting callback handler");
+    debug!(user_id = user_id, language_code = %language_code, "User selected language");
     
     // Check if language is supported
-    info!(user_id = user_id, language_code = %language_code, "🔍 LANG HANDLER: Checking if language is supported");
+    debug!(user_id = user_id, language_code = %language_code, "Checking if language is supported");
     if !i18n.is_language_supported(&language_code) {
-        warn!(user_id = user_id, language_code = %language_code, "🔍 LANG HANDLER: Unsupported language selected");
+        warn!(user_id = user_id, language_code = %language_code, "Unsupported language selected");
         let _error_text = i18n.t("messages.validation.invalid_name", "en", None);
         bot.send_message(chat_id, format!("❌ Unsupported language: {}", language_code)).await?;
         return Ok(());
     }
     
-    info!(user_id = user_id, "🔍 LANG HANDLER: Language is supported, loading user context");
+    debug!(user_id = user_id, "Language is supported, loading user context");
     
     // Load user context
     let context_result = state_storage.load_context(user_id).await;
-    info!(user_id = user_id, context_loaded = context_result.is_ok(), "🔍 LANG HANDLER: Context load result");
+    debug!(user_id = user_id, context_loaded = context_result.is_ok(), "Context load result");
     
     let mut context = match context_result {
         Ok(Some(ctx)) => {
-            info!(user_id = user_id, scenario = ?ctx.scenario, step = ?ctx.step, "🔍 LANG HANDLER: Context loaded successfully");
+            debug!(user_id = user_id, scenario = ?ctx.scenario, step = ?ctx.step, "Context loaded successfully");
             ctx
         },
         Ok(None) => {
-            error!(user_id = user_id, "🔍 LANG HANDLER: No context found for user - this is the likely issue!");
+            error!(user_id = user_id, "No context found for user");
             return Err(crate::utils::errors::SwingBuddyError::InvalidStateTransition {
                 from: "no_context".to_string(),
                 to: "language_selected".to_string(),
             });
         },
         Err(e) => {
-            error!(user_id = user_id, error = %e, "🔍 LANG HANDLER: Failed to load context - this could be the issue!");
+            error!(user_id = user_id, error = %e, "Failed to load context");
             return Err(e);
         }
     };
@@ -193,15 +253,15 @@ pub async fn handle_language_callback(
     let is_correct_state = context.is_at("onboarding", "language_selection");
     info!(user_id = user_id, is_correct_state = is_correct_state,
            current_scenario = ?context.scenario, current_step = ?context.step,
-           "🔍 LANG HANDLER: State validation result");
+           "State validation result");
     
     if !is_correct_state {
         error!(user_id = user_id, scenario = ?context.scenario, step = ?context.step,
-              "🔍 LANG HANDLER: User not in language selection step - this could be the issue!");
+              "User not in language selection step");
         return Ok(());
     }
     
-    info!(user_id = user_id, "🔍 LANG HANDLER: All validations passed, proceeding with language update");
+    debug!(user_id = user_id, "All validations passed, proceeding with language update");
     
     // Update user language preference
     services.user_service.set_language_preference(user_id, language_code.clone()).await?;
@@ -209,54 +269,296 @@ pub async fn handle_language_callback(
     // Store language in context
     context.set_data("language", &language_code)?;
     
-    // Move to next step
-    scenario_manager.next_step(&mut context, "name_input")?;
-    state_storage.save_context(&context).await?;
-    
-    // Show language confirmation and ask for name
+    // Show language confirmation and move on, usually to asking for the name
     let confirmation_text = i18n.t("commands.start.language_selected", &language_code, None);
     bot.send_message(chat_id, confirmation_text).await?;
     
-    // Ask for name with default suggestion
-    ask_for_name(bot, chat_id, user_id, &services, &i18n, &language_code).await?;
-    
+    advance_onboarding(bot, chat_id, user_id, context, services, &scenario_manager, state_storage, i18n, language_code).await
+}
+
+/// Move onboarding on to the step the scenario continues with and ask for its input.
+/// Scenario files may leave out the name or location step; any step the handlers
+/// don't know completes onboarding.
+#[allow(clippy::too_many_arguments)]
+async fn advance_onboarding(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    mut context: ConversationContext,
+    services: ServiceFactory,
+    scenario_manager: &ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+    language_code: String,
+) -> Result<()> {
+    match scenario_manager.following_step(&context).map(str::to_string).as_deref() {
+        Some(step @ ("name_input" | "location_input")) => {
+            scenario_manager.next_step(&mut context, step)?;
+            state_storage.save_context(&mut context).await?;
+
+            let prompt = scenario_manager.step_prompt("onboarding", step);
+            let back = scenario_manager.can_go_back(&context);
+            if step == "name_input" {
+                ask_for_name(bot, chat_id, user_id, &services, prompt, back, &i18n, &language_code).await
+            } else {
+                ask_for_location(bot, chat_id, prompt, back, &i18n, &language_code).await
+            }
+        }
+        _ => complete_onboarding(bot, chat_id, user_id, context, services, state_storage, i18n, language_code).await,
+    }
+}
+
+/// Handle /back and the Back buttons: return to the previous step of the current
+/// scenario, if its step allows it, and ask for that step's input again
+pub async fn handle_back(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user_lang = services.user_service.get_user_by_telegram_id(user_id).await?
+        .map(|user| user.language_code)
+        .unwrap_or_else(|| i18n.default_language().to_string());
+
+    let Some(mut context) = state_storage.load_context(user_id).await? else {
+        bot.send_message(chat_id, i18n.t("commands.back.unavailable", &user_lang, None)).await?;
+        return Ok(());
+    };
+    let Ok(step) = scenario_manager.back(&mut context) else {
+        bot.send_message(chat_id, i18n.t("commands.back.unavailable", &user_lang, None)).await?;
+        return Ok(());
+    };
+    state_storage.save_context(&mut context).await?;
+    debug!(user_id = user_id, scenario = ?context.scenario, step = %step, "User went back a step");
+
+    let language_code = context.get_string("language").unwrap_or(user_lang);
+    show_current_step(bot, chat_id, &context, &services, &scenario_manager, &i18n, &language_code, "commands.back.done").await
+}
+
+/// Handle the Continue button of a step reminder: give the user the scenario's
+/// full time again and ask for the input of the step they stopped at
+pub async fn handle_continue(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user_lang = services.user_service.get_user_by_telegram_id(user_id).await?
+        .map(|user| user.language_code)
+        .unwrap_or_else(|| i18n.default_language().to_string());
+
+    let context = state_storage.load_context(user_id).await?.filter(|context| context.scenario.is_some());
+    let Some(mut context) = context else {
+        bot.send_message(chat_id, i18n.t("messages.errors.session_expired", &user_lang, None)).await?;
+        return Ok(());
+    };
+    scenario_manager.renew_expiry(&mut context);
+    state_storage.save_context(&mut context).await?;
+    debug!(user_id = user_id, scenario = ?context.scenario, step = ?context.step, "User continued after a step reminder");
+
+    let language_code = context.get_string("language").unwrap_or(user_lang);
+    show_current_step(bot, chat_id, &context, &services, &scenario_manager, &i18n, &language_code, "commands.back.continued").await
+}
+
+/// Put the user's private scenario aside while a command runs, if the scenario
+/// allows interruptions, so the command and the scenario don't both act on
+/// the user's next messages. Returns the context holding the paused scenario.
+pub async fn pause_for_command(
+    user_id: i64,
+    scenario_manager: &ScenarioManager,
+    state_storage: &StateStorage,
+) -> Option<ConversationContext> {
+    let mut context = match state_storage.load_context(user_id).await {
+        Ok(context) => context?,
+        Err(e) => {
+            warn!(user_id = user_id, error = %e, "Failed to load context to pause");
+            return None;
+        }
+    };
+    if !context.scenario.as_deref().is_some_and(|scenario| scenario_manager.can_interrupt(scenario)) {
+        return None;
+    }
+
+    context.pause_scenario();
+    if let Err(e) = state_storage.save_context(&mut context).await {
+        warn!(user_id = user_id, error = %e, "Failed to pause scenario");
+        return None;
+    }
+    debug!(user_id = user_id, paused = ?context.paused.last().map(|paused| &paused.scenario), "Scenario paused for a command");
+    Some(context)
+}
+
+/// After an interrupting command, keep the paused scenarios with whatever the
+/// command left behind and ask whether to continue where the user left off
+pub async fn offer_resume(
+    bot: Bot,
+    chat_id: ChatId,
+    paused: ConversationContext,
+    services: &ServiceFactory,
+    state_storage: &StateStorage,
+    i18n: &I18n,
+) -> Result<()> {
+    let user_id = paused.user_id;
+    match state_storage.load_context(user_id).await? {
+        Some(context) if !context.paused.is_empty() => {}
+        // The command started a conversation of its own; the paused ones go under it
+        Some(mut context) => {
+            context.paused = paused.paused;
+            state_storage.save_context(&mut context).await?;
+        }
+        None => {
+            let mut context = ConversationContext { version: 0, ..paused };
+            state_storage.save_context(&mut context).await?;
+        }
+    }
+
+    let user_lang = services.user_service.get_user_by_telegram_id(user_id).await?
+        .map(|user| user.language_code)
+        .unwrap_or_else(|| i18n.default_language().to_string());
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(i18n.t("buttons.resume.continue", &user_lang, None), CallbackData::Scenario(ScenarioAction::Resume)),
+        InlineKeyboardButton::callback(i18n.t("buttons.resume.discard", &user_lang, None), CallbackData::Scenario(ScenarioAction::Discard)),
+    ]]);
+    bot.send_message(chat_id, i18n.t("commands.resume.prompt", &user_lang, None))
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Handle Continue of the resume prompt: go back to the paused scenario at the
+/// step it was left, pausing the one in progress, if any, in its place
+pub async fn handle_resume(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    services: ServiceFactory,
+    scenario_manager: ScenarioManager,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user_lang = services.user_service.get_user_by_telegram_id(user_id).await?
+        .map(|user| user.language_code)
+        .unwrap_or_else(|| i18n.default_language().to_string());
+
+    let context = state_storage.load_context(user_id).await?.filter(|context| !context.paused.is_empty());
+    let Some(mut context) = context else {
+        bot.send_message(chat_id, i18n.t("messages.errors.session_expired", &user_lang, None)).await?;
+        return Ok(());
+    };
+    context.resume_scenario();
+    scenario_manager.renew_expiry(&mut context);
+    state_storage.save_context(&mut context).await?;
+    debug!(user_id = user_id, scenario = ?context.scenario, step = ?context.step, "User resumed a paused scenario");
+
+    let language_code = context.get_string("language").unwrap_or(user_lang);
+    show_current_step(bot, chat_id, &context, &services, &scenario_manager, &i18n, &language_code, "commands.back.continued").await
+}
+
+/// Handle Discard of the resume prompt: drop the paused scenario
+pub async fn handle_discard(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    services: ServiceFactory,
+    state_storage: StateStorage,
+    i18n: I18n,
+) -> Result<()> {
+    let user_lang = services.user_service.get_user_by_telegram_id(user_id).await?
+        .map(|user| user.language_code)
+        .unwrap_or_else(|| i18n.default_language().to_string());
+
+    if let Some(mut context) = state_storage.load_context(user_id).await? {
+        if let Some(discarded) = context.discard_paused() {
+            debug!(user_id = user_id, scenario = %discarded.scenario, "User discarded a paused scenario");
+        }
+        if context.scenario.is_none() && context.paused.is_empty() {
+            state_storage.delete_context(user_id).await?;
+        } else {
+            state_storage.save_context(&mut context).await?;
+        }
+    }
+
+    bot.send_message(chat_id, i18n.t("commands.resume.discarded", &user_lang, None)).await?;
     Ok(())
 }
 
-/// Ask user for their name
+/// Ask again for the input of the step the user is at. Steps without their own
+/// screen show their configured prompt, or `fallback_key`.
+#[allow(clippy::too_many_arguments)]
+async fn show_current_step(
+    bot: Bot,
+    chat_id: ChatId,
+    context: &ConversationContext,
+    services: &ServiceFactory,
+    scenario_manager: &ScenarioManager,
+    i18n: &I18n,
+    language_code: &str,
+    fallback_key: &str,
+) -> Result<()> {
+    let (Some(scenario), Some(step)) = context.current_state() else {
+        return Ok(());
+    };
+    let prompt = scenario_manager.step_prompt(scenario, step);
+    let back = scenario_manager.can_go_back(context);
+    match (scenario, step) {
+        ("onboarding", "language_selection") => show_language_selection(bot, chat_id, i18n).await,
+        ("onboarding", "name_input") => ask_for_name(bot, chat_id, context.user_id, services, prompt, back, i18n, language_code).await,
+        ("onboarding", "location_input") => ask_for_location(bot, chat_id, prompt, back, i18n, language_code).await,
+        (scenario, _) if wizard::is_wizard(scenario) => {
+            wizard::show_wizard_step(bot, chat_id, None, context, scenario_manager, i18n, language_code).await
+        }
+        _ => {
+            bot.send_message(chat_id, i18n.t(prompt.unwrap_or(fallback_key), language_code, None)).await?;
+            Ok(())
+        }
+    }
+}
+
+/// Ask user for their name, with the scenario's prompt if it sets one
+#[allow(clippy::too_many_arguments)]
 async fn ask_for_name(
     bot: Bot,
     chat_id: ChatId,
     user_id: i64,
     services: &ServiceFactory,
+    prompt: Option<&str>,
+    back: bool,
     i18n: &I18n,
     language_code: &str,
 ) -> Result<()> {
-    let ask_name_text = i18n.t("commands.start.ask_name", language_code, None);
+    let mut text = i18n.t(prompt.unwrap_or("commands.start.ask_name"), language_code, None);
     
     // Get user's Telegram name as suggestion
-    if let Some(user) = services.user_service.get_user_by_telegram_id(user_id).await? {
-        if let Some(first_name) = &user.first_name {
-            let mut params = HashMap::new();
-            params.insert("name".to_string(), first_name.clone());
-            
-            let suggestion_text = format!("{}\n\n💡 Suggestion: {}", ask_name_text, first_name);
-            bot.send_message(chat_id, suggestion_text).await?;
-        } else {
-            bot.send_message(chat_id, ask_name_text).await?;
-        }
+    if let Some(first_name) = services.user_service.get_user_by_telegram_id(user_id).await?.and_then(|user| user.first_name) {
+        text = format!("{}\n\n💡 Suggestion: {}", text, first_name);
+    }
+
+    let request = bot.send_message(chat_id, text);
+    if back {
+        request.reply_markup(InlineKeyboardMarkup::new(vec![vec![back_button(i18n, language_code)]])).await?;
     } else {
-        bot.send_message(chat_id, ask_name_text).await?;
+        request.await?;
     }
     
     Ok(())
 }
 
+/// Inline button doing the same as /back
+fn back_button(i18n: &I18n, language_code: &str) -> InlineKeyboardButton {
+    InlineKeyboardButton::callback(i18n.t("buttons.navigation.back", language_code, None), CallbackData::Scenario(ScenarioAction::Back))
+}
+
 /// Handle name input during onboarding
 pub async fn handle_name_input(
     bot: Bot,
     msg: Message,
-    _services: ServiceFactory,
+    services: ServiceFactory,
     scenario_manager: ScenarioManager,
     state_storage: StateStorage,
     i18n: I18n,
@@ -282,39 +584,39 @@ pub async fn handle_name_input(
     let language_code = context.get_string("language").unwrap_or_else(|| "en".to_string());
     
     // Validate name input
-    if let Err(_e) = scenario_manager.validate_input(&context, name) {
-        let error_text = i18n.t("messages.validation.invalid_name", &language_code, None);
-        bot.send_message(chat_id, error_text).await?;
+    if scenario_manager.validate_input(&context, name).is_err() {
+        let error_key = scenario_manager.get_current_step(&context).ok()
+            .and_then(|step| step.validation.as_ref()?.error_message.clone())
+            .unwrap_or_else(|| "messages.validation.invalid_name".to_string());
+        bot.send_message(chat_id, i18n.t(&error_key, &language_code, None)).await?;
         return Ok(());
     }
     
     // Store name in context
     context.set_data("name", name)?;
     
-    // Move to location input
-    scenario_manager.next_step(&mut context, "location_input")?;
-    state_storage.save_context(&context).await?;
-    
-    // Ask for location
-    ask_for_location(bot, chat_id, &i18n, &language_code).await?;
-    
-    Ok(())
+    // Move on, usually to asking for the location
+    advance_onboarding(bot, chat_id, user_id, context, services, &scenario_manager, state_storage, i18n, language_code).await
 }
 
-/// Ask user for their location
-async fn ask_for_location(bot: Bot, chat_id: ChatId, i18n: &I18n, language_code: &str) -> Result<()> {
-    let ask_location_text = i18n.t("commands.start.ask_location", language_code, None);
+/// Ask user for their location, with the scenario's prompt if it sets one
+async fn ask_for_location(bot: Bot, chat_id: ChatId, prompt: Option<&str>, back: bool, i18n: &I18n, language_code: &str) -> Result<()> {
+    let ask_location_text = i18n.t(prompt.unwrap_or("commands.start.ask_location"), language_code, None);
     
     // Create keyboard with city suggestions
-    let keyboard = InlineKeyboardMarkup::new(vec![
+    let mut keyboard = vec![
         vec![
-            InlineKeyboardButton::callback("📍 Moscow", "location:Moscow"),
-            InlineKeyboardButton::callback("📍 Saint Petersburg", "location:Saint Petersburg"),
+            InlineKeyboardButton::callback("📍 Moscow", CallbackData::Location(Some("Moscow".to_string()))),
+            InlineKeyboardButton::callback("📍 Saint Petersburg", CallbackData::Location(Some("Saint Petersburg".to_string()))),
         ],
         vec![
-            InlineKeyboardButton::callback("⏭️ Skip", "location:skip"),
+            InlineKeyboardButton::callback("⏭️ Skip", CallbackData::Location(None)),
         ]
-    ]);
+    ];
+    if back {
+        keyboard.push(vec![back_button(i18n, language_code)]);
+    }
+    let keyboard = InlineKeyboardMarkup::new(keyboard);
     
     bot.send_message(chat_id, ask_location_text)
         .reply_markup(keyboard)
@@ -328,7 +630,7 @@ pub async fn handle_location_input(
     bot: Bot,
     msg: Message,
     services: ServiceFactory,
-    _scenario_manager: ScenarioManager,
+    scenario_manager: ScenarioManager,
     state_storage: StateStorage,
     i18n: I18n,
 ) -> Result<()> {
@@ -355,10 +657,8 @@ pub async fn handle_location_input(
     // Store location in context
     context.set_data("location", location)?;
     
-    // Complete onboarding
-    complete_onboarding(bot, chat_id, user_id, context, services, state_storage, i18n, language_code).await?;
-    
-    Ok(())
+    // Complete onboarding unless the scenario asks for more
+    advance_onboarding(bot, chat_id, user_id, context, services, &scenario_manager, state_storage, i18n, language_code).await
 }
 
 /// Handle location selection callback
@@ -368,7 +668,7 @@ pub async fn handle_location_callback(
     user_id: i64,
     location: String,
     services: ServiceFactory,
-    _scenario_manager: ScenarioManager,
+    scenario_manager: ScenarioManager,
     state_storage: StateStorage,
     i18n: I18n,
 ) -> Result<()> {
@@ -411,10 +711,8 @@ pub async fn handle_location_callback(
         context.set_data("location", &location)?;
     }
     
-    // Complete onboarding
-    complete_onboarding(bot, chat_id, user_id, context, services, state_storage, i18n, language_code).await?;
-    
-    Ok(())
+    // Complete onboarding unless the scenario asks for more
+    advance_onboarding(bot, chat_id, user_id, context, services, &scenario_manager, state_storage, i18n, language_code).await
 }
 
 /// Complete the onboarding process
@@ -428,13 +726,14 @@ async fn complete_onboarding(
     i18n: I18n,
     language_code: String,
 ) -> Result<()> {
-    info!(user_id = user_id, "🔍 COMPLETE ONBOARDING: Starting onboarding completion");
+    debug!(user_id = user_id, "Starting onboarding completion");
     
     // Get data from context
     let name = context.get_string("name");
     let location = context.get_string("location");
+    let start_payload = context.get_string(START_PAYLOAD_KEY);
     
-    info!(user_id = user_id, name = ?name, location = ?location, "🔍 COMPLETE ONBOARDING: Retrieved context data");
+    debug!(user_id = user_id, name = ?name, location = ?location, "Retrieved context data");
     
     // Update user profile
     let mut update_request = crate::models::user::UpdateUserRequest::default();
@@ -446,11 +745,17 @@ async fn complete_onboarding(
     }
     update_request.language_code = Some(language_code.clone());
     
-    info!(user_id = user_id, "🔍 COMPLETE ONBOARDING: Updating user profile in database");
+    debug!(user_id = user_id, "Updating user profile in database");
     services.user_service.update_user_profile(user_id, update_request).await?;
+    services.activity_log_service.record(
+        ActivityAction::UserOnboarded,
+        Some(user_id),
+        user_id,
+        serde_json::json!({ "language": language_code, "location": location, "start_payload": start_payload }),
+    ).await;
     
     // Complete scenario and clear context from storage
-    info!(user_id = user_id, "🔍 COMPLETE ONBOARDING: Clearing user context from state storage");
+    debug!(user_id = user_id, "Clearing user context from state storage");
     context.complete_scenario();
     
     // Delete the context from state storage to ensure it's completely cleared
@@ -462,12 +767,49 @@ async fn complete_onboarding(
     // Show completion message
     let completion_text = i18n.t("commands.start.setup_complete", &language_code, None);
     bot.send_message(chat_id, completion_text).await?;
+
+    if let Some(payload) = start_payload.as_deref().and_then(StartPayload::parse) {
+        run_start_payload(bot, chat_id, user_id, payload, &services, &i18n, &language_code).await?;
+    }
     
-    info!(user_id = user_id, "🔍 COMPLETE ONBOARDING: User onboarding completed successfully");
+    debug!(user_id = user_id, "User onboarding completed successfully");
     
     Ok(())
 }
 
+/// Act on a /start deep link: open the events or the event it points to, or
+/// greet the user on behalf of the group they came from
+async fn run_start_payload(
+    bot: Bot,
+    chat_id: ChatId,
+    user_id: i64,
+    payload: StartPayload,
+    services: &ServiceFactory,
+    i18n: &I18n,
+    language_code: &str,
+) -> Result<()> {
+    debug!(user_id = user_id, payload = ?payload, "Acting on /start payload");
+
+    match payload {
+        StartPayload::Events => events::show_calendar_list(bot, chat_id, services, i18n, language_code).await,
+        StartPayload::Event(event_id) => match services.event_service.get_event(event_id).await? {
+            Some(event) => events::show_event_details(bot, chat_id, &event, user_id, services, i18n, language_code).await,
+            None => {
+                bot.send_message(chat_id, i18n.t("commands.events.not_found", language_code, None)).await?;
+                Ok(())
+            }
+        },
+        StartPayload::Group(group_chat_id) => {
+            if let Some(group) = services.group_service.get_group(group_chat_id).await? {
+                let mut params = HashMap::new();
+                params.insert("title".to_string(), group.title);
+                bot.send_message(chat_id, i18n.t("commands.start.from_group", language_code, Some(&params))).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
 /// Handle /language command - show language selection
 pub async fn handle_language_selection(bot: Bot, msg: Message) -> Result<()> {
     let chat_id = msg.chat.id;
@@ -486,7 +828,7 @@ pub async fn handle_language_selection(bot: Bot, msg: Message) -> Result<()> {
 }
 
 /// Handle /profile command - show user profile
-pub async fn handle_profile(bot: Bot, msg: Message) -> Result<()> {
+pub async fn handle_profile(bot: Bot, msg: Message, me: Me, services: ServiceFactory, i18n: I18n) -> Result<()> {
     let user = msg.from.as_ref().ok_or_else(|| {
         crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
     })?;
@@ -500,7 +842,7 @@ pub async fn handle_profile(bot: Bot, msg: Message) -> Result<()> {
         return Ok(());
     }
     
-    let profile_text = format!(
+    let mut profile_text = format!(
         "👤 Your Profile\n\n\
         • Telegram ID: {}\n\
         • Username: {}\n\
@@ -512,8 +854,125 @@ pub async fn handle_profile(bot: Bot, msg: Message) -> Result<()> {
         &user.first_name,
         user.last_name.as_ref().map_or("Not set", |s| s.as_str())
     );
+
+    // Append referral stats and the user's invite link
+    if let Some(user_data) = services.user_service.get_user_by_telegram_id(user.id.0 as i64).await? {
+        let referral_count = services.referral_service.get_referral_count(user_data.id).await?;
+        let mut params = HashMap::new();
+        params.insert("count".to_string(), referral_count.to_string());
+        profile_text.push_str("\n\n");
+        profile_text.push_str(&i18n.t("commands.start.referral_count", &user_data.language_code, Some(&params)));
+
+        if let Some(bot_username) = &me.username {
+            let mut params = HashMap::new();
+            params.insert("link".to_string(), format!("https://t.me/{}?start={}", bot_username, referral_payload(user_data.id)));
+            profile_text.push('\n');
+            profile_text.push_str(&i18n.t("commands.start.referral_link", &user_data.language_code, Some(&params)));
+        }
+
+        let styles = services.dance_style_service.get_user_styles(user_data.telegram_id).await?;
+        let mut params = HashMap::new();
+        params.insert("styles".to_string(), crate::handlers::commands::styles::format_styles(&i18n, &user_data.language_code, &styles));
+        profile_text.push('\n');
+        profile_text.push_str(&i18n.t("commands.styles.profile_line", &user_data.language_code, Some(&params)));
+
+        if let Some(birthday) = services.birthday_service.get_birthday(user_data.telegram_id).await? {
+            let mut params = HashMap::new();
+            params.insert("date".to_string(), format!("{:02}.{:02}", birthday.birth_day, birthday.birth_month));
+            profile_text.push('\n');
+            profile_text.push_str(&i18n.t("commands.birthday.profile_line", &user_data.language_code, Some(&params)));
+        }
+    }
     
     bot.send_message(chat_id, profile_text).await?;
     
     Ok(())
-}
\ No newline at end of file
+}
+/// Handle /birthday command - set or clear a birthday in private chat,
+/// or allow announcing it in the current group
+pub async fn handle_birthday(bot: Bot, msg: Message, args: String, services: ServiceFactory, i18n: I18n) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        crate::utils::errors::SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+    let args = args.trim();
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /birthday command");
+
+    let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? else {
+        let text = i18n.t("commands.birthday.not_registered", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+    let lang = user_data.language_code.as_str();
+
+    // In groups the command only manages consent for announcements
+    if !chat_id.is_user() {
+        let key = match args.to_lowercase().as_str() {
+            "announce" => {
+                if services.birthday_service.set_announcement_group(user_id, Some(chat_id.0)).await? {
+                    "commands.birthday.announce_enabled"
+                } else {
+                    "commands.birthday.announce_failed"
+                }
+            }
+            "private" => {
+                services.birthday_service.set_announcement_group(user_id, None).await?;
+                "commands.birthday.announce_disabled"
+            }
+            _ => "commands.birthday.group_usage",
+        };
+        bot.send_message(chat_id, i18n.t(key, lang, None)).await?;
+        return Ok(());
+    }
+
+    if args.is_empty() {
+        let text = match services.birthday_service.get_birthday(user_id).await? {
+            Some(birthday) => {
+                let mut params = HashMap::new();
+                params.insert("date".to_string(), format!("{:02}.{:02}", birthday.birth_day, birthday.birth_month));
+                i18n.t("commands.birthday.current", lang, Some(&params))
+            }
+            None => i18n.t("commands.birthday.usage", lang, None),
+        };
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    if args.eq_ignore_ascii_case("clear") {
+        services.birthday_service.clear_birthday(user_id).await?;
+        bot.send_message(chat_id, i18n.t("commands.birthday.cleared", lang, None)).await?;
+        return Ok(());
+    }
+
+    match services.birthday_service.set_birthday(user_id, args).await {
+        Ok(birthday) => {
+            let mut params = HashMap::new();
+            params.insert("date".to_string(), format!("{:02}.{:02}", birthday.birth_day, birthday.birth_month));
+            bot.send_message(chat_id, i18n.t("commands.birthday.saved", lang, Some(&params))).await?;
+            info!(user_id = user_id, "Birthday saved");
+        }
+        Err(crate::utils::errors::SwingBuddyError::InvalidInput(_)) => {
+            bot.send_message(chat_id, i18n.t("commands.birthday.invalid_format", lang, None)).await?;
+        }
+        Err(e) => return Err(e),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_start_payload() {
+        assert_eq!(StartPayload::parse("events"), Some(StartPayload::Events));
+        assert_eq!(StartPayload::parse("event_5"), Some(StartPayload::Event(5)));
+        assert_eq!(StartPayload::parse("group_-100123"), Some(StartPayload::Group(-100123)));
+        assert_eq!(StartPayload::parse("event_"), None);
+        assert_eq!(StartPayload::parse("ref_42"), None);
+    }
+}
@@ -0,0 +1,190 @@
+//! Dance style command handlers
+//!
+//! Handles /styles (favorite styles on the profile) and /eventstyles (event style tags)
+
+use std::collections::HashMap;
+use teloxide::{Bot, types::{Message, ChatId, MessageId, InlineKeyboardMarkup, InlineKeyboardButton}, prelude::*};
+use tracing::{info, debug};
+use crate::utils::errors::{SwingBuddyError, Result};
+use crate::services::ServiceFactory;
+use crate::services::dance_style::parse_styles;
+use crate::models::dance_style::DanceStyle;
+use crate::handlers::callbacks::CallbackData;
+use crate::i18n::I18n;
+
+/// Handle /styles command - show the favorite style picker
+pub async fn handle_styles(bot: Bot, msg: Message, services: ServiceFactory, i18n: I18n) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /styles command");
+
+    let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? else {
+        let text = i18n.t("commands.styles.not_registered", "en", None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+    let lang = user_data.language_code.as_str();
+
+    // Only allow in private chats
+    if !chat_id.is_user() {
+        let text = i18n.t("commands.styles.private_only", lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    let styles = services.dance_style_service.get_user_styles(user_id).await?;
+
+    bot.send_message(chat_id, i18n.t("commands.styles.title", lang, None))
+        .reply_markup(styles_keyboard(&i18n, lang, &styles))
+        .await?;
+
+    Ok(())
+}
+
+/// Handle style toggle callback from the /styles picker
+pub async fn handle_style_callback(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: Option<MessageId>,
+    user_id: i64,
+    style: DanceStyle,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? else {
+        return Ok(());
+    };
+    let lang = user_data.language_code.as_str();
+
+    let styles = services.dance_style_service.toggle_user_style(user_id, style).await?;
+    let keyboard = styles_keyboard(&i18n, lang, &styles);
+
+    // Update the picker in place so the checkmarks follow the selection
+    match message_id {
+        Some(message_id) => {
+            bot.edit_message_reply_markup(chat_id, message_id)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        None => {
+            bot.send_message(chat_id, i18n.t("commands.styles.title", lang, None))
+                .reply_markup(keyboard)
+                .await?;
+        }
+    }
+
+    info!(user_id = user_id, style = %style, "Dance style toggled");
+
+    Ok(())
+}
+
+/// Handle /eventstyles command - show or set the styles an event is tagged with
+pub async fn handle_event_styles(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, "Processing /eventstyles command");
+
+    // Get user language
+    let user_lang = if let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? {
+        user_data.language_code
+    } else {
+        "en".to_string()
+    };
+
+    let args = args.trim();
+    let (event_id, styles_arg) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    let Ok(event_id) = event_id.parse::<i64>() else {
+        let text = i18n.t("commands.events.event_styles_usage", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    let Some(event) = services.reminder_service.find_event(event_id).await? else {
+        let text = i18n.t("commands.events.not_found", &user_lang, None);
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    // Admins and the event organizer only
+    if !services.auth_service.can_manage_events(user_id, None).await?
+        && !services.user_service.is_event_organizer(user_id, &event).await?
+    {
+        let error_text = i18n.t("commands.admin.access_denied", &user_lang, None);
+        bot.send_message(chat_id, error_text).await?;
+        return Ok(());
+    }
+
+    let styles_arg = styles_arg.trim();
+    let key = if styles_arg.is_empty() {
+        "commands.events.event_styles_current"
+    } else {
+        let styles = if styles_arg.eq_ignore_ascii_case("clear") {
+            Vec::new()
+        } else {
+            match parse_styles(styles_arg) {
+                Ok(styles) => styles,
+                Err(_) => {
+                    let text = i18n.t("commands.events.event_styles_invalid", &user_lang, None);
+                    bot.send_message(chat_id, text).await?;
+                    return Ok(());
+                }
+            }
+        };
+
+        services.dance_style_service.set_event_styles(event.id, &styles).await?;
+        info!(user_id = user_id, event_id = event.id, "Event dance styles set");
+        "commands.events.event_styles_updated"
+    };
+
+    let styles = services.dance_style_service.get_event_styles(event.id).await?;
+    let audience = services.dance_style_service.get_event_audience(event.id).await?;
+
+    let mut params = HashMap::new();
+    params.insert("title".to_string(), event.title.clone());
+    params.insert("styles".to_string(), format_styles(&i18n, &user_lang, &styles));
+    params.insert("count".to_string(), audience.len().to_string());
+    bot.send_message(chat_id, i18n.t(key, &user_lang, Some(&params))).await?;
+
+    Ok(())
+}
+
+/// Format styles as a comma-separated list of localized names
+pub fn format_styles(i18n: &I18n, language_code: &str, styles: &[DanceStyle]) -> String {
+    if styles.is_empty() {
+        return i18n.t("commands.styles.none", language_code, None);
+    }
+
+    styles.iter()
+        .map(|style| i18n.t(&format!("dance_styles.{}", style.as_str()), language_code, None))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Build the style picker keyboard, marking selected styles
+fn styles_keyboard(i18n: &I18n, language_code: &str, selected: &[DanceStyle]) -> InlineKeyboardMarkup {
+    let buttons: Vec<InlineKeyboardButton> = DanceStyle::ALL.iter()
+        .map(|style| {
+            let name = i18n.t(&format!("dance_styles.{}", style.as_str()), language_code, None);
+            let label = if selected.contains(style) { format!("✅ {}", name) } else { name };
+            CallbackData::Style(*style).button(label)
+        })
+        .collect();
+
+    InlineKeyboardMarkup::new(buttons.chunks(2).map(|row| row.to_vec()).collect::<Vec<_>>())
+}
@@ -0,0 +1,46 @@
+//! Subscription command handlers
+//!
+//! Handles /unsubscribe, which turns off every direct message the user did not
+//! ask for, and /subscribe, which turns them back on
+
+use teloxide::{Bot, types::Message, prelude::*};
+use tracing::{info, debug};
+use crate::utils::errors::{SwingBuddyError, Result};
+use crate::services::ServiceFactory;
+use crate::models::user::NotificationPreferences;
+use crate::i18n::I18n;
+
+/// Handle /unsubscribe command - opt out of broadcasts and reminders
+pub async fn handle_unsubscribe(bot: Bot, msg: Message, services: ServiceFactory, i18n: I18n) -> Result<()> {
+    set_subscribed(bot, msg, false, services, i18n).await
+}
+
+/// Handle /subscribe command - get broadcasts and reminders again
+pub async fn handle_subscribe(bot: Bot, msg: Message, services: ServiceFactory, i18n: I18n) -> Result<()> {
+    set_subscribed(bot, msg, true, services, i18n).await
+}
+
+async fn set_subscribed(bot: Bot, msg: Message, enabled: bool, services: ServiceFactory, i18n: I18n) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, enabled = enabled, "Processing subscription command");
+
+    let Some(user_data) = services.user_service.get_user_by_telegram_id(user_id).await? else {
+        bot.send_message(chat_id, i18n.t("commands.subscription.not_registered", "en", None)).await?;
+        return Ok(());
+    };
+
+    services.user_service.set_notification_preferences(user_id, NotificationPreferences::all(enabled)).await?;
+
+    let key = if enabled { "commands.subscription.subscribed" } else { "commands.subscription.unsubscribed" };
+    bot.send_message(chat_id, i18n.t(key, &user_data.language_code, None)).await?;
+
+    info!(user_id = user_id, enabled = enabled, "Optional notifications toggled");
+
+    Ok(())
+}
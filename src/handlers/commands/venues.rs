@@ -0,0 +1,184 @@
+//! Venue command handlers
+//!
+//! Handles /venues, which lists the dance venues of a city with their floor and
+//! upcoming events, and lets admins add and remove venues
+
+use std::collections::HashMap;
+use teloxide::{Bot, types::Message, prelude::*};
+use tracing::{info, debug};
+use crate::utils::errors::{SwingBuddyError, Result};
+use crate::services::ServiceFactory;
+use crate::models::event::Event;
+use crate::models::venue::{Venue, CreateVenueRequest};
+use crate::i18n::I18n;
+
+/// Handle /venues command - list the venues of a city, or add and remove venues
+pub async fn handle_venues(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let user = msg.from.as_ref().ok_or_else(|| {
+        SwingBuddyError::InvalidInput("No user in message".to_string())
+    })?;
+
+    let user_id = user.id.0 as i64;
+    let chat_id = msg.chat.id;
+
+    debug!(user_id = user_id, chat_id = ?chat_id, "Processing /venues command");
+
+    let user_data = services.user_service.get_user_by_telegram_id(user_id).await?;
+    let user_lang = user_data.as_ref().map_or_else(|| "en".to_string(), |user| user.language_code.clone());
+
+    let args = args.trim();
+    let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    let rest = rest.trim();
+
+    let mut params = HashMap::new();
+    let text = match action.to_lowercase().as_str() {
+        "add" | "remove" => {
+            if !services.auth_service.can_manage_events(user_id, None).await? {
+                bot.send_message(chat_id, i18n.t("commands.admin.access_denied", &user_lang, None)).await?;
+                return Ok(());
+            }
+
+            if action.eq_ignore_ascii_case("add") {
+                match parse_venue(rest, user_id) {
+                    Some(request) => {
+                        let venue = services.venue_service.save_venue(request).await?;
+                        params.insert("id".to_string(), venue.id.to_string());
+                        params.insert("name".to_string(), venue.name);
+                        params.insert("city".to_string(), venue.city);
+                        i18n.t("commands.venues.saved", &user_lang, Some(&params))
+                    }
+                    None => i18n.t("commands.venues.usage", &user_lang, None),
+                }
+            } else {
+                let Ok(venue_id) = rest.parse::<i64>() else {
+                    bot.send_message(chat_id, i18n.t("commands.venues.usage", &user_lang, None)).await?;
+                    return Ok(());
+                };
+                match services.venue_service.remove_venue(venue_id).await? {
+                    Some(venue) => {
+                        params.insert("name".to_string(), venue.name);
+                        params.insert("city".to_string(), venue.city);
+                        i18n.t("commands.venues.removed", &user_lang, Some(&params))
+                    }
+                    None => i18n.t("commands.venues.not_found", &user_lang, None),
+                }
+            }
+        }
+        _ => {
+            // The named city, otherwise the user's own; "all" lists every city
+            let city = if args.eq_ignore_ascii_case("all") {
+                None
+            } else if !args.is_empty() {
+                Some(args.to_string())
+            } else {
+                user_data.and_then(|user| user.location)
+            };
+            let venues = services.venue_service.list_venues(city.as_deref()).await?;
+            format_venues(&venues, city.as_deref(), &i18n, &user_lang)
+        }
+    };
+    bot.send_message(chat_id, text).await?;
+
+    info!(user_id = user_id, chat_id = ?chat_id, action = action, "Venues command handled");
+
+    Ok(())
+}
+
+/// The venue directory, grouped by city when more than one city is listed
+fn format_venues(venues: &[(Venue, Vec<Event>)], city: Option<&str>, i18n: &I18n, lang: &str) -> String {
+    let mut params = HashMap::new();
+    if venues.is_empty() {
+        return match city {
+            Some(city) => {
+                params.insert("city".to_string(), city.to_string());
+                i18n.t("commands.venues.empty_city", lang, Some(&params))
+            }
+            None => i18n.t("commands.venues.empty", lang, None),
+        };
+    }
+
+    let mut text = match city {
+        Some(city) => {
+            params.insert("city".to_string(), city.to_string());
+            i18n.t("commands.venues.title_city", lang, Some(&params))
+        }
+        None => i18n.t("commands.venues.title", lang, None),
+    };
+
+    let mut current_city: Option<&str> = None;
+    for (venue, events) in venues {
+        if city.is_none() && current_city.is_none_or(|current| !current.eq_ignore_ascii_case(&venue.city)) {
+            let mut params = HashMap::new();
+            params.insert("city".to_string(), venue.city.clone());
+            text.push_str("\n\n");
+            text.push_str(&i18n.t("commands.venues.city_heading", lang, Some(&params)));
+            current_city = Some(&venue.city);
+        }
+
+        let mut params = HashMap::new();
+        params.insert("id".to_string(), venue.id.to_string());
+        params.insert("name".to_string(), venue.name.clone());
+        params.insert("address".to_string(), venue.address.clone());
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("commands.venues.item", lang, Some(&params)));
+
+        if let Some(floor_type) = &venue.floor_type {
+            params.insert("floor".to_string(), floor_type.clone());
+            text.push('\n');
+            text.push_str(&i18n.t("commands.venues.floor", lang, Some(&params)));
+        }
+
+        if events.is_empty() {
+            text.push('\n');
+            text.push_str(&i18n.t("commands.venues.no_events", lang, None));
+        }
+        for event in events {
+            text.push_str(&format!("\n  • {} {}", event.event_date.format("%Y-%m-%d %H:%M UTC"), event.title));
+        }
+    }
+
+    text
+}
+
+/// Parse `<city> | <name> | <address> [| <floor type>]`
+fn parse_venue(args: &str, created_by: i64) -> Option<CreateVenueRequest> {
+    let fields: Vec<&str> = args.split('|').map(str::trim).collect();
+    let [city, name, address, rest @ ..] = fields.as_slice() else {
+        return None;
+    };
+    if city.is_empty() || name.is_empty() || address.is_empty() || rest.len() > 1 {
+        return None;
+    }
+
+    Some(CreateVenueRequest {
+        city: city.to_string(),
+        name: name.to_string(),
+        address: address.to_string(),
+        floor_type: rest.first().filter(|floor| !floor.is_empty()).map(|floor| floor.to_string()),
+        created_by: Some(created_by),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_venue() {
+        let venue = parse_venue("Moscow | Swing Loft | Tverskaya 1 | wood", 1).unwrap();
+        assert_eq!(venue.city, "Moscow");
+        assert_eq!(venue.name, "Swing Loft");
+        assert_eq!(venue.address, "Tverskaya 1");
+        assert_eq!(venue.floor_type.as_deref(), Some("wood"));
+
+        assert!(parse_venue("Moscow | Swing Loft | Tverskaya 1", 1).unwrap().floor_type.is_none());
+        assert!(parse_venue("Moscow | Swing Loft", 1).is_none());
+        assert!(parse_venue("Moscow | | Tverskaya 1", 1).is_none());
+    }
+}
@@ -1,14 +1,20 @@
 //! Message handlers module
 //! 
-//! Handles incoming text messages, new member events, and CAS API checking
+//! Handles incoming text messages, member join/leave events, and CAS API checking
 
-use teloxide::{Bot, types::Message, prelude::*};
+use std::collections::HashMap;
+use teloxide::{Bot, types::{ChatId, ChatMemberKind, ChatMemberUpdated, InlineKeyboardButton, InlineKeyboardMarkup, Me, Message, MessageEntityKind, MessageId, ThreadId, User}, prelude::*};
 use tracing::{info, debug, warn, error};
 use crate::utils::errors::Result;
-use crate::services::ServiceFactory;
+use crate::services::{ServiceFactory, Escalation, CachedCasResult, WebAppAction};
 use crate::state::{ScenarioManager, StateStorage};
 use crate::i18n::I18n;
-use crate::handlers::commands::start;
+use crate::handlers::commands::{admin, events, moderation, start};
+use crate::handlers::commands::start::EVENTS_START_PAYLOAD;
+use crate::handlers::callbacks::{captcha, group_setup, wizard};
+use crate::services::group::render_welcome_message;
+use crate::models::group::{Group, GroupFeature, GroupTopic, IntroCardMode};
+use crate::models::stats::GroupCounter;
 
 /// Handle incoming text messages
 pub async fn handle_message(
@@ -28,20 +34,49 @@ pub async fn handle_message(
 
     debug!(user_id = user_id, chat_id = ?chat_id, "Processing message");
 
-    // Check for CAS ban in groups
     if !chat_id.is_user() {
-        if let Err(e) = check_and_handle_cas_ban(&bot, &msg, &services).await {
-            error!(error = %e, user_id = user_id, "Failed to check CAS ban");
+        services.stats_service.record(chat_id, GroupCounter::Messages);
+        services.cas_recheck_service.record_activity(chat_id, user.id).await;
+
+        // Mute members flooding the group; their messages need no further handling
+        match check_and_handle_flood(&bot, &msg, &services, &i18n).await {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            Err(e) => error!(error = %e, user_id = user_id, "Failed to handle flood control"),
+        }
+
+        // Remove messages caught by the group's spam filters
+        match check_and_handle_spam(&bot, &msg, &services, &i18n).await {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            Err(e) => error!(error = %e, user_id = user_id, "Failed to apply spam filter"),
         }
+
+        // Check the author against CAS in the background; listed authors are banned
+        // and their messages deleted a moment later
+        services.cas_queue_service.enqueue(chat_id, user.id, msg.id);
     }
 
     // Handle state-based conversations in private chats
     if chat_id.is_user() {
-        if let Some(context) = state_storage.load_context(user_id).await? {
+        // A context holding only paused scenarios waits for the resume prompt
+        let context = state_storage.load_context(user_id).await?.filter(|context| context.scenario.is_some());
+        if let Some(context) = context {
             return handle_conversation_message(
                 bot, msg, context, services, scenario_manager, state_storage, i18n
             ).await;
         }
+    } else if let Some(context) = state_storage.load_chat_context(user_id, chat_id.0).await? {
+        // Group admins send texts like the welcome message right in the group being configured
+        if context.is_at("group_setup", "welcome_message_input") {
+            return group_setup::handle_welcome_message_input(bot, msg, services, state_storage, i18n).await;
+        }
+        if context.is_at("group_rules", "rules_input") {
+            return moderation::handle_rules_input(bot, msg, services, state_storage, i18n).await;
+        }
+        if context.scenario.as_deref().is_some_and(wizard::is_wizard) {
+            return wizard::handle_wizard_input(bot, msg, context, services, scenario_manager, state_storage, i18n).await;
+        }
     }
 
     // Handle regular messages (no active conversation)
@@ -52,71 +87,409 @@ pub async fn handle_message(
 pub async fn handle_new_chat_member(
     bot: Bot,
     msg: Message,
+    me: Me,
     services: ServiceFactory,
+    i18n: I18n,
 ) -> Result<()> {
     if let Some(new_members) = msg.new_chat_members() {
+        // When many users join at once, look them all up in parallel up front
+        let mut cas_results: HashMap<i64, CachedCasResult> = if new_members.len() > 1 && is_cas_protected(&services, msg.chat.id).await {
+            let user_ids = new_members.iter().map(|member| member.id.0 as i64).collect();
+            services.cas_service.check_users_batch(user_ids).await
+                .map(|results| results.into_iter().collect())
+                .unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+
         for member in new_members {
             let user_id = member.id.0 as i64;
             debug!(user_id = user_id, chat_id = ?msg.chat.id, "New member joined chat");
 
+            if !member.is_bot {
+                services.stats_service.record(msg.chat.id, GroupCounter::NewMembers);
+            }
+
+            // Members on the shared ban list are removed before anything else
+            match services.moderation_service.enforce_shared_ban(msg.chat.id, member.id).await {
+                Ok(true) => {
+                    if let Err(e) = bot.delete_message(msg.chat.id, msg.id).await {
+                        warn!(error = %e, "Failed to delete join message");
+                    }
+                    continue;
+                }
+                Ok(false) => {}
+                Err(e) => error!(error = %e, user_id = user_id, "Failed to check shared ban for new member"),
+            }
+
             // Check CAS ban for new member
-            match services.cas_service.check_user(user_id).await {
-                Ok(result) => {
-                    if result.is_banned {
-                        info!(user_id = user_id, "Banning user due to CAS listing");
+            let cas_banned = if !is_cas_protected(&services, msg.chat.id).await || is_cas_whitelisted(&services, user_id).await {
+                false
+            } else {
+                let checked = match cas_results.remove(&user_id) {
+                    Some(result) => Ok(result),
+                    None => services.cas_service.check_user(user_id).await,
+                };
+                match checked {
+                    Ok(result) => {
+                        if result.is_banned {
+                            info!(user_id = user_id, "Banning user due to CAS listing");
                         
-                        // Ban the user
-                        if let Err(e) = bot.ban_chat_member(msg.chat.id, member.id).await {
-                            error!(error = %e, user_id = user_id, "Failed to ban user");
-                        }
+                            // Ban the user
+                            if let Err(e) = bot.ban_chat_member(msg.chat.id, member.id).await {
+                                error!(error = %e, user_id = user_id, "Failed to ban user");
+                            } else {
+                                services.stats_service.record(msg.chat.id, GroupCounter::CasBans);
+                                if let Err(e) = services.moderation_service.log_cas_ban(msg.chat.id, user_id).await {
+                                    warn!(error = %e, user_id = user_id, "Failed to log CAS ban");
+                                }
+                            }
                         
-                        // Delete the join message
-                        if let Err(e) = bot.delete_message(msg.chat.id, msg.id).await {
-                            warn!(error = %e, "Failed to delete join message");
+                            // Delete the join message
+                            if let Err(e) = bot.delete_message(msg.chat.id, msg.id).await {
+                                warn!(error = %e, "Failed to delete join message");
+                            }
                         }
+                        result.is_banned
+                    }
+                    Err(e) => {
+                        error!(error = %e, user_id = user_id, "Failed to check CAS ban for new member");
+                        false
                     }
                 }
-                Err(e) => {
-                    error!(error = %e, user_id = user_id, "Failed to check CAS ban for new member");
+            };
+
+            // Gate remaining human members behind the captcha, if the group uses it
+            if !cas_banned && !member.is_bot {
+                let captcha_started = match captcha::send_captcha_challenge(&bot, msg.chat.id, member, &services, &i18n).await {
+                    Ok(started) => started,
+                    Err(e) => {
+                        error!(error = %e, user_id = user_id, "Failed to start captcha for new member");
+                        false
+                    }
+                };
+
+                // Members behind a captcha are greeted once they pass it
+                if !captcha_started {
+                    if let Err(e) = greet_new_member(&bot, &me, msg.chat.id, member, &services, &i18n).await {
+                        error!(error = %e, user_id = user_id, "Failed to greet new member");
+                    }
                 }
             }
         }
+
+        // The join message has served its purpose once the member is checked and greeted
+        moderation::schedule_cleanup(&services, msg.chat.id, msg.id).await;
     }
 
     Ok(())
 }
 
-/// Check and handle CAS ban for message author
-async fn check_and_handle_cas_ban(
+/// Handle members leaving the chat: the "left the group" message is only cleaned up
+pub async fn handle_left_chat_member(
+    msg: Message,
+    services: ServiceFactory,
+) -> Result<()> {
+    if let Some(member) = msg.left_chat_member() {
+        debug!(user_id = member.id.0, chat_id = ?msg.chat.id, "Member left chat");
+        moderation::schedule_cleanup(&services, msg.chat.id, msg.id).await;
+    }
+
+    Ok(())
+}
+
+/// Handle data the Mini App sent back to the chat, a registration for an event
+pub async fn handle_web_app_data(
+    bot: Bot,
+    msg: Message,
+    services: ServiceFactory,
+    i18n: I18n,
+) -> Result<()> {
+    let (Some(user), Some(data)) = (msg.from.as_ref(), msg.web_app_data()) else {
+        return Ok(());
+    };
+
+    let user_id = user.id.0 as i64;
+    match WebAppAction::parse(&data.data) {
+        Some(action) => events::handle_webapp_action(bot, msg.chat.id, user_id, action, services, i18n).await,
+        None => {
+            // Anyone can send arbitrary data here, not only our page
+            warn!(user_id = user_id, data = %data.data, "Unknown Mini App data");
+            Ok(())
+        }
+    }
+}
+
+/// Handle a change of a group member's status: keep their member record and
+/// the group's cached admins up to date as they join, leave, are removed,
+/// promoted or demoted
+pub async fn handle_chat_member_update(
+    update: ChatMemberUpdated,
+    services: ServiceFactory,
+) -> Result<()> {
+    let chat_id = update.chat.id;
+    let member = &update.new_chat_member;
+    if member.user.is_bot {
+        return Ok(());
+    }
+    let user_id = member.user.id.0 as i64;
+
+    let is_admin = member.is_privileged();
+    if update.old_chat_member.is_privileged() != is_admin {
+        info!(user_id = user_id, chat_id = ?chat_id, is_admin = is_admin, changed_by = update.from.id.0, "Group admin status changed");
+        services.moderation_service.update_chat_admin(chat_id, user_id, is_admin);
+    }
+
+    let role = match member.kind {
+        ChatMemberKind::Owner(_) => "owner",
+        ChatMemberKind::Administrator(_) => "administrator",
+        _ => "member",
+    };
+    let role = member.is_present().then_some(role);
+    debug!(user_id = user_id, chat_id = ?chat_id, old = ?update.old_chat_member.kind, new = ?member.kind, "Chat member updated");
+    services.group_service.sync_member(chat_id.0, user_id, role).await
+}
+
+/// Greet a new member with the group's welcome message and rules, if the group has them.
+/// Returns `false` if the group has no welcome message configured.
+pub async fn greet_new_member(
     bot: &Bot,
-    msg: &Message,
+    me: &Me,
+    chat_id: ChatId,
+    member: &User,
+    services: &ServiceFactory,
+    i18n: &I18n,
+) -> Result<bool> {
+    let Some(group) = services.group_service.get_group(chat_id.0).await?.filter(|g| g.is_active) else {
+        return Ok(false);
+    };
+
+    let welcomed = match group.welcome_message().filter(|_| group.feature_enabled(GroupFeature::WelcomeMessages)) {
+        Some(template) => {
+            let text = render_welcome_message(template, member, &group.title);
+            bot.send_message(chat_id, text).await?;
+            debug!(user_id = member.id.0, chat_id = ?chat_id, "Welcome message sent");
+            true
+        }
+        None => false,
+    };
+
+    if let Some(rules) = group.rules().filter(|_| group.rules_on_join()) {
+        let mut params = HashMap::new();
+        params.insert("name".to_string(), member.first_name.clone());
+        params.insert("rules".to_string(), rules.to_string());
+        bot.send_message(chat_id, i18n.t("commands.rules.new_member", &group.language_code, Some(&params))).await?;
+        debug!(user_id = member.id.0, chat_id = ?chat_id, "Rules sent to new member");
+    }
+
+    if let Err(e) = send_intro_card(bot, me, &group, member, services, i18n).await {
+        warn!(error = %e, user_id = member.id.0, chat_id = ?chat_id, "Failed to send intro card");
+    }
+
+    Ok(welcomed)
+}
+
+/// Send a new member the group's intro card: buttons to start the bot and see the
+/// upcoming events, plus the rules unless they were posted on join already
+async fn send_intro_card(
+    bot: &Bot,
+    me: &Me,
+    group: &Group,
+    member: &User,
     services: &ServiceFactory,
+    i18n: &I18n,
 ) -> Result<()> {
-    let user = msg.from.as_ref().unwrap();
-    let user_id = user.id.0 as i64;
+    let mode = group.intro_card_mode();
+    if mode == IntroCardMode::Off || member.is_bot {
+        return Ok(());
+    }
+    let Some(bot_username) = &me.username else {
+        return Ok(());
+    };
 
-    match services.cas_service.check_user(user_id).await {
-        Ok(result) => {
-            if result.is_banned {
-                info!(user_id = user_id, "Banning user due to CAS listing");
-                
-                // Ban the user
-                if let Err(e) = bot.ban_chat_member(msg.chat.id, user.id).await {
-                    error!(error = %e, user_id = user_id, "Failed to ban user");
-                }
-                
-                // Delete the message
-                if let Err(e) = bot.delete_message(msg.chat.id, msg.id).await {
-                    warn!(error = %e, "Failed to delete message from banned user");
-                }
+    let lang = &group.language_code;
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), member.first_name.clone());
+    params.insert("group".to_string(), group.title.clone());
+    let mut text = i18n.t("group.intro.card", lang, Some(&params));
+    if let Some(rules) = group.rules().filter(|_| !group.rules_on_join()) {
+        params.insert("rules".to_string(), rules.to_string());
+        text.push_str("\n\n");
+        text.push_str(&i18n.t("group.intro.rules", lang, Some(&params)));
+    }
+
+    let start_url = reqwest::Url::parse(&format!("https://t.me/{}?start", bot_username))?;
+    let events_url = reqwest::Url::parse(&format!("https://t.me/{}?start={}", bot_username, EVENTS_START_PAYLOAD))?;
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::url(i18n.t("buttons.group.intro_start", lang, None), start_url)],
+        vec![InlineKeyboardButton::url(i18n.t("buttons.group.intro_events", lang, None), events_url)],
+    ]);
+
+    // Members who never started the bot cannot be messaged; they get the card in the group
+    if mode == IntroCardMode::Private {
+        match bot.send_message(ChatId(member.id.0 as i64), text.clone()).reply_markup(keyboard.clone()).await {
+            Ok(_) => return Ok(()),
+            Err(e) => debug!(error = %e, user_id = member.id.0, "Intro card DM failed, posting in group"),
+        }
+    }
+
+    let chat_id = ChatId(group.telegram_id);
+    let reply = bot.send_message(chat_id, text).reply_markup(keyboard).await?;
+    moderation::schedule_cleanup(services, chat_id, reply.id).await;
+
+    debug!(user_id = member.id.0, chat_id = ?chat_id, mode = ?mode, "Intro card sent");
+    Ok(())
+}
+
+/// Mute the message author if they flood the group and notify the admins.
+/// Returns `true` if the author was muted.
+async fn check_and_handle_flood(
+    bot: &Bot,
+    msg: &Message,
+    services: &ServiceFactory,
+    i18n: &I18n,
+) -> Result<bool> {
+    let Some(user) = msg.from.as_ref() else {
+        return Ok(false);
+    };
+    let chat_id = msg.chat.id;
+
+    if !services.moderation_service.is_flooding(chat_id, user) {
+        return Ok(false);
+    }
+
+    // Group admins and moderators may post as much as they like
+    if services.auth_service.can_moderate(user.id.0 as i64, Some(chat_id)).await? {
+        return Ok(false);
+    }
+
+    let until = services.moderation_service.mute_for_flood(chat_id, user.id).await?;
+    let group = services.group_service.get_group(chat_id.0).await?;
+    let lang = group.as_ref().map_or("en", |g| g.language_code.as_str());
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), user.full_name());
+    params.insert("group".to_string(), msg.chat.title().unwrap_or_default().to_string());
+    params.insert("minutes".to_string(), ((until - chrono::Utc::now()).num_seconds() + 59).div_euclid(60).to_string());
+
+    let mut request = bot.send_message(chat_id, i18n.t("flood.muted", lang, Some(&params)));
+    if let Some(thread_id) = notice_thread(group.as_ref(), msg) {
+        request = request.message_thread_id(thread_id);
+    }
+    let reply = request.await?;
+    moderation::schedule_cleanup(services, chat_id, reply.id).await;
+
+    let notice = i18n.t("flood.admin_notice", lang, Some(&params));
+    for recipient in services.moderation_service.report_recipients(chat_id, group.as_ref()).await? {
+        if let Err(e) = bot.send_message(recipient, notice.clone()).await {
+            debug!(error = %e, recipient = recipient.0, "Failed to notify admin about flooding");
+        }
+    }
+
+    Ok(true)
+}
+
+/// Delete the message if it matches one of the group's spam filters, warning the
+/// author if the group asks for it. Returns `true` if the message was removed.
+async fn check_and_handle_spam(
+    bot: &Bot,
+    msg: &Message,
+    services: &ServiceFactory,
+    i18n: &I18n,
+) -> Result<bool> {
+    let (Some(user), Some(text)) = (msg.from.as_ref(), msg.text().or(msg.caption())) else {
+        return Ok(false);
+    };
+    let chat_id = msg.chat.id;
+
+    // Links behind formatted text do not show up in the text itself
+    let links = msg.parse_entities().or_else(|| msg.parse_caption_entities()).unwrap_or_default()
+        .into_iter()
+        .filter_map(|entity| match entity.kind() {
+            MessageEntityKind::TextLink { url } => Some(url.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let Some(filter) = services.moderation_service.find_spam_match(chat_id, text, &links).await? else {
+        return Ok(false);
+    };
+
+    if services.auth_service.can_moderate(user.id.0 as i64, Some(chat_id)).await? {
+        return Ok(false);
+    }
+
+    let group = services.group_service.get_group(chat_id.0).await?;
+    let warn_sender = group.as_ref().is_some_and(|g| g.spam_filter_warns());
+    let lang = group.as_ref().map_or("en", |g| g.language_code.as_str());
+
+    let outcome = services.moderation_service.remove_spam(chat_id, msg.id, user.id, &filter, warn_sender).await?;
+
+    let mut params = HashMap::new();
+    params.insert("name".to_string(), user.full_name());
+
+    let mut text = i18n.t("spam_filter.removed", lang, Some(&params));
+    if let Some(outcome) = outcome {
+        params.insert("count".to_string(), outcome.count.to_string());
+        text.push('\n');
+        text.push_str(&i18n.t("spam_filter.warned", lang, Some(&params)));
+
+        match outcome.escalation {
+            Escalation::Muted { until } => {
+                params.insert("until".to_string(), until.format("%d.%m.%Y %H:%M UTC").to_string());
+                text.push_str("\n\n");
+                text.push_str(&i18n.t("commands.warnings.muted", lang, Some(&params)));
             }
+            Escalation::Banned => {
+                text.push_str("\n\n");
+                text.push_str(&i18n.t("commands.warnings.banned", lang, Some(&params)));
+            }
+            Escalation::None => {}
         }
+    }
+
+    let mut request = bot.send_message(chat_id, text);
+    if let Some(thread_id) = notice_thread(group.as_ref(), msg) {
+        request = request.message_thread_id(thread_id);
+    }
+    let reply = request.await?;
+    moderation::schedule_cleanup(services, chat_id, reply.id).await;
+
+    Ok(true)
+}
+
+/// Forum topic for an automatic moderation notice: the group's moderation topic,
+/// or else the topic of the message that caused it
+fn notice_thread(group: Option<&Group>, msg: &Message) -> Option<ThreadId> {
+    group.and_then(|g| g.topic_id(GroupTopic::Moderation))
+        .map(|id| ThreadId(MessageId(id)))
+        .or(msg.thread_id.filter(|_| msg.is_topic_message))
+}
+
+/// Check if the group keeps CAS protection on; unknown groups are protected
+async fn is_cas_protected(services: &ServiceFactory, chat_id: ChatId) -> bool {
+    match services.group_service.get_group(chat_id.0).await {
+        Ok(group) => group.is_none_or(|g| g.feature_enabled(GroupFeature::CasProtection)),
         Err(e) => {
-            error!(error = %e, user_id = user_id, "Failed to check CAS ban");
+            warn!(error = %e, chat_id = chat_id.0, "Failed to check group CAS protection, checking anyway");
+            true
         }
     }
+}
 
-    Ok(())
+/// Check if a user is exempt from CAS auto-bans, logging the skipped check
+async fn is_cas_whitelisted(services: &ServiceFactory, user_id: i64) -> bool {
+    match services.cas_service.is_whitelisted(user_id).await {
+        Ok(true) => {
+            info!(user_id = user_id, "Skipping CAS check for whitelisted user");
+            true
+        }
+        Ok(false) => false,
+        Err(e) => {
+            error!(error = %e, user_id = user_id, "Failed to check CAS whitelist");
+            false
+        }
+    }
 }
 
 /// Handle conversation-based messages (when user is in a scenario)
@@ -141,6 +514,39 @@ async fn handle_conversation_message(
         ("onboarding", "location_input") => {
             start::handle_location_input(bot, msg, services, scenario_manager, state_storage, i18n).await
         }
+        ("spam_filter", "pattern_input") => {
+            admin::handle_spam_filter_input(bot, msg, context, services, state_storage, i18n).await
+        }
+        ("calendar_creation", _) => {
+            admin::handle_calendar_creation_input(bot, msg, context, services, scenario_manager, state_storage, i18n).await
+        }
+        ("calendar_edit", "value_input") => {
+            admin::handle_calendar_edit_input(bot, msg, context, services, state_storage, i18n).await
+        }
+        ("template_edit", "content_input") => {
+            admin::handle_template_edit_input(bot, msg, context, services, state_storage, i18n).await
+        }
+        ("admin_panel", "ban_user_input") | ("admin_panel", "unban_user_input") => {
+            admin::handle_ban_user_input(bot, msg, context, services, scenario_manager, state_storage, i18n).await
+        }
+        ("user_profile_edit", "value_input") => {
+            admin::handle_user_profile_edit_input(bot, msg, context, services, state_storage, i18n).await
+        }
+        ("user_import", "document_input") => {
+            admin::handle_user_import_input(bot, msg, services, state_storage, i18n).await
+        }
+        ("broadcast", "schedule_input") | ("announcement", "schedule_input") => {
+            admin::handle_schedule_input(bot, msg, context, services, state_storage, i18n).await
+        }
+        ("announcement", "message_input") => {
+            admin::handle_announcement_input(bot, msg, context, services, scenario_manager, state_storage, i18n).await
+        }
+        ("broadcast", _) => {
+            admin::handle_broadcast_input(bot, msg, context, services, scenario_manager, state_storage, i18n).await
+        }
+        (scenario, _) if wizard::is_wizard(scenario) => {
+            wizard::handle_wizard_input(bot, msg, context, services, scenario_manager, state_storage, i18n).await
+        }
         _ => {
             // Unknown scenario/step - clear context and handle as regular message
             warn!(scenario = scenario, step = step, "Unknown conversation state");
@@ -0,0 +1,330 @@
+//! Fluent translation backend
+//!
+//! Translations can also be written in [Fluent](https://projectfluent.org)
+//! files next to the JSON ones (`translations/<lang>.ftl`). Fluent messages take
+//! precedence over JSON keys, so a language can be migrated a few messages at a
+//! time. A dotted key maps to a message id with the dots replaced by hyphens
+//! (`commands.start.welcome` → `commands-start-welcome`); when there is no such
+//! message, the last part of the key is looked up as an attribute
+//! (`commands-start.welcome`). Fluent selectors handle plural forms, gender
+//! and any other variants in the translation itself.
+//!
+//! `SwingBuddy convert-translations <lang>` prints the FTL equivalent of a JSON
+//! file as a starting point for the migration.
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Write};
+use std::path::Path;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use fluent_syntax::ast;
+use serde_json::{Map, Value};
+use tokio::fs;
+use tracing::warn;
+use unic_langid::LanguageIdentifier;
+use crate::utils::errors::{SwingBuddyError, Result};
+use super::loader::TranslationParams;
+
+/// Plural categories a JSON object may use as keys
+const PLURAL_FORMS: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// Fluent messages of one language
+pub struct FluentLanguage {
+    bundle: FluentBundle<FluentResource>,
+    /// Message ids, and `id.attribute` for every attribute
+    keys: BTreeSet<String>,
+}
+
+impl fmt::Debug for FluentLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FluentLanguage").field("keys", &self.keys.len()).finish()
+    }
+}
+
+impl FluentLanguage {
+    /// Read and parse an FTL file
+    pub async fn load(file_path: &Path, lang_code: &str) -> Result<Self> {
+        let source = fs::read_to_string(file_path).await?;
+        Self::parse(source, lang_code)
+    }
+
+    /// Parse FTL source; syntax errors and duplicate messages reject the whole file
+    pub fn parse(source: String, lang_code: &str) -> Result<Self> {
+        let locale: LanguageIdentifier = lang_code.parse()
+            .map_err(|e| SwingBuddyError::Config(format!("Invalid language code {}: {}", lang_code, e)))?;
+
+        let resource = FluentResource::try_new(source).map_err(|(_, errors)| {
+            let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            SwingBuddyError::Config(format!("Invalid Fluent translations for {}: {}", lang_code, errors.join("; ")))
+        })?;
+        let keys = message_keys(&resource);
+
+        let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+        // Unicode isolation marks around placeables would show up in Telegram messages
+        bundle.set_use_isolating(false);
+        bundle.add_resource(resource).map_err(|errors| {
+            let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            SwingBuddyError::Config(format!("Invalid Fluent translations for {}: {}", lang_code, errors.join("; ")))
+        })?;
+
+        Ok(Self { bundle, keys })
+    }
+
+    /// Format the message or attribute for a dotted key, if there is one
+    pub fn format(&self, key: &str, params: Option<&TranslationParams>) -> Option<String> {
+        let message_id = fluent_id(key);
+        let pattern = match self.bundle.get_message(&message_id).and_then(|message| message.value()) {
+            Some(pattern) => pattern,
+            None => {
+                let (message_key, attribute) = key.rsplit_once('.')?;
+                self.bundle.get_message(&fluent_id(message_key))?.get_attribute(attribute)?.value()
+            }
+        };
+
+        let args = params.map(fluent_args);
+        let mut errors = Vec::new();
+        let text = self.bundle.format_pattern(pattern, args.as_ref(), &mut errors);
+        if !errors.is_empty() {
+            warn!(key = %key, errors = ?errors, "Errors formatting Fluent message");
+        }
+        Some(text.into_owned())
+    }
+
+    /// Message ids, and `id.attribute` for every attribute
+    pub fn keys(&self) -> &BTreeSet<String> {
+        &self.keys
+    }
+}
+
+/// Fluent message id for a dotted translation key
+pub fn fluent_id(key: &str) -> String {
+    key.replace('.', "-")
+}
+
+/// Translation parameters as Fluent arguments. Integers become numbers so
+/// selectors can pick plural forms from them.
+fn fluent_args(params: &TranslationParams) -> FluentArgs<'_> {
+    let mut args = FluentArgs::with_capacity(params.len());
+    for (name, value) in params {
+        match value.parse::<i64>() {
+            Ok(number) if number.to_string() == *value => args.set(name.as_str(), number),
+            _ => args.set(name.as_str(), FluentValue::from(value.as_str())),
+        }
+    }
+    args
+}
+
+fn message_keys(resource: &FluentResource) -> BTreeSet<String> {
+    let mut keys = BTreeSet::new();
+    for entry in resource.entries() {
+        if let ast::Entry::Message(message) = entry {
+            if message.value.is_some() {
+                keys.insert(message.id.name.to_string());
+            }
+            for attribute in &message.attributes {
+                keys.insert(format!("{}.{}", message.id.name, attribute.id.name));
+            }
+        }
+    }
+    keys
+}
+
+/// Convert a JSON translation file to FTL. Plural objects become selectors on
+/// `$count` and `{name}` placeholders become `{ $name }`. Values other than
+/// strings and plural objects can't be converted and are left as comments.
+pub fn json_to_ftl(translations: &Map<String, Value>) -> String {
+    let mut ftl = String::new();
+    write_entries(&mut ftl, translations, "");
+    ftl
+}
+
+fn write_entries(ftl: &mut String, map: &Map<String, Value>, prefix: &str) {
+    for (key, value) in map {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            Value::String(text) => write_message(ftl, &fluent_id(&path), text),
+            Value::Object(forms) if is_plural(forms) => write_plural(ftl, &fluent_id(&path), forms),
+            Value::Object(nested) => write_entries(ftl, nested, &path),
+            _ => {
+                let _ = writeln!(ftl, "# {}: only strings and plural forms can be converted", path);
+            }
+        }
+    }
+}
+
+fn write_message(ftl: &mut String, id: &str, text: &str) {
+    let lines = pattern_lines(text);
+    if let [line] = lines.as_slice() {
+        let _ = writeln!(ftl, "{} = {}", id, line);
+    } else {
+        let _ = writeln!(ftl, "{} =", id);
+        for line in lines {
+            let _ = writeln!(ftl, "    {}", line);
+        }
+    }
+}
+
+fn write_plural(ftl: &mut String, id: &str, forms: &Map<String, Value>) {
+    // `other` when there is one, else the last form
+    let default = PLURAL_FORMS.iter().rev().find(|form| forms.contains_key(**form)).copied().unwrap_or("other");
+
+    let _ = writeln!(ftl, "{} =", id);
+    ftl.push_str("    { $count ->\n");
+    for form in PLURAL_FORMS.iter().filter(|form| forms.contains_key(**form)) {
+        let text = forms[*form].as_str().unwrap_or_default();
+        let marker = if *form == default { "   *" } else { "    " };
+        let mut lines = pattern_lines(text).into_iter();
+        let _ = writeln!(ftl, "{}[{}] {}", marker, form, lines.next().unwrap_or_default());
+        for line in lines {
+            let _ = writeln!(ftl, "            {}", line);
+        }
+    }
+    ftl.push_str("    }\n");
+}
+
+fn is_plural(map: &Map<String, Value>) -> bool {
+    !map.is_empty() && map.iter().all(|(key, value)| PLURAL_FORMS.contains(&key.as_str()) && value.is_string())
+}
+
+/// Lines of a Fluent pattern with the same text as a JSON translation
+fn pattern_lines(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let last = lines.len() - 1;
+
+    lines.iter()
+        .enumerate()
+        .map(|(index, line)| {
+            // Fluent drops blank lines around a pattern and indentation inside it
+            if line.is_empty() && (index == 0 || index == last) {
+                return "{\"\"}".to_string();
+            }
+            let mut pattern = String::new();
+            let mut chars = line.char_indices().peekable();
+            while let Some((position, c)) = chars.next() {
+                match c {
+                    '{' => match placeholder(&line[position..]) {
+                        Some(name) => {
+                            let _ = write!(pattern, "{{ ${} }}", name);
+                            for _ in 0..name.len() + 1 {
+                                chars.next();
+                            }
+                        }
+                        None => pattern.push_str("{\"{\"}"),
+                    },
+                    '}' => pattern.push_str("{\"}\"}"),
+                    // A leading space would be trimmed, a leading [ * or . read as syntax
+                    ' ' | '[' | '*' | '.' if position == 0 => {
+                        let _ = write!(pattern, "{{\"{}\"}}", c);
+                    }
+                    ' ' if chars.peek().is_none() => pattern.push_str("{\" \"}"),
+                    _ => pattern.push(c),
+                }
+            }
+            pattern
+        })
+        .collect()
+}
+
+/// Name of the `{name}` placeholder at the start of `text`
+fn placeholder(text: &str) -> Option<&str> {
+    let end = text.find('}')?;
+    let name = &text[1..end];
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    valid.then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn params(pairs: &[(&str, &str)]) -> TranslationParams {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_plural_and_gender_selectors() {
+        let ftl = r#"
+events-found = { $count ->
+    [one] Найдено { $count } событие
+    [few] Найдено { $count } события
+   *[many] Найдено { $count } событий
+}
+user-joined = { $gender ->
+    [female] { $name } присоединилась
+   *[other] { $name } присоединился
+}
+"#;
+        let language = FluentLanguage::parse(ftl.to_string(), "ru").unwrap();
+
+        assert_eq!(language.format("events.found", Some(&params(&[("count", "1")]))).unwrap(), "Найдено 1 событие");
+        assert_eq!(language.format("events.found", Some(&params(&[("count", "3")]))).unwrap(), "Найдено 3 события");
+        assert_eq!(language.format("events.found", Some(&params(&[("count", "11")]))).unwrap(), "Найдено 11 событий");
+        assert_eq!(language.format("user.joined", Some(&params(&[("gender", "female"), ("name", "Аня")]))).unwrap(), "Аня присоединилась");
+        assert_eq!(language.format("user.joined", Some(&params(&[("name", "Петя")]))).unwrap(), "Петя присоединился");
+    }
+
+    #[test]
+    fn test_attributes() {
+        let ftl = "event-card = { $title }\n    .location = Where: { $place }\n";
+        let language = FluentLanguage::parse(ftl.to_string(), "en").unwrap();
+
+        assert_eq!(language.format("event.card", Some(&params(&[("title", "Social")]))).unwrap(), "Social");
+        assert_eq!(language.format("event.card.location", Some(&params(&[("place", "Loft")]))).unwrap(), "Where: Loft");
+        assert!(language.format("event.card.missing", None).is_none());
+        assert_eq!(language.keys().iter().collect::<Vec<_>>(), ["event-card", "event-card.location"]);
+    }
+
+    #[test]
+    fn test_invalid_files_are_rejected() {
+        assert!(FluentLanguage::parse("broken = {".to_string(), "en").is_err());
+        assert!(FluentLanguage::parse("twice = a\ntwice = b\n".to_string(), "en").is_err());
+    }
+
+    #[test]
+    fn test_plural_objects_become_selectors() {
+        let json = serde_json::json!({"plurals": {"days": {"one": "{count} день", "few": "{count} дня", "many": "{count} дней", "other": "{count} дня"}}});
+        let ftl = json_to_ftl(json.as_object().unwrap());
+        let language = FluentLanguage::parse(ftl, "ru").unwrap();
+
+        assert_eq!(language.format("plurals.days", Some(&params(&[("count", "1")]))).unwrap(), "1 день");
+        assert_eq!(language.format("plurals.days", Some(&params(&[("count", "4")]))).unwrap(), "4 дня");
+        assert_eq!(language.format("plurals.days", Some(&params(&[("count", "25")]))).unwrap(), "25 дней");
+    }
+
+    #[test]
+    fn test_converted_files_match_json() {
+        for lang in ["en", "ru"] {
+            let json: Value = serde_json::from_str(&std::fs::read_to_string(format!("translations/{}.json", lang)).unwrap()).unwrap();
+            let json = json.as_object().unwrap();
+            let language = FluentLanguage::parse(json_to_ftl(json), lang).unwrap();
+
+            let mut strings = HashMap::new();
+            collect_strings(json, "", &mut strings);
+            for (key, text) in strings {
+                let names: Vec<String> = text.split('{').skip(1)
+                    .filter_map(|rest| placeholder(&format!("{{{}", rest)).map(str::to_string))
+                    .collect();
+                let params: TranslationParams = names.iter().map(|name| (name.clone(), "X".to_string())).collect();
+                let expected = names.iter().fold(text.clone(), |text, name| text.replace(&format!("{{{}}}", name), "X"));
+
+                assert_eq!(language.format(&key, Some(&params)), Some(expected), "{} in {}", key, lang);
+            }
+        }
+    }
+
+    fn collect_strings(map: &Map<String, Value>, prefix: &str, strings: &mut HashMap<String, String>) {
+        for (key, value) in map {
+            let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            match value {
+                Value::String(text) => {
+                    strings.insert(path, text.clone());
+                }
+                Value::Object(nested) if !is_plural(nested) => collect_strings(nested, &path, strings),
+                _ => {}
+            }
+        }
+    }
+}
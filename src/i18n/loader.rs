@@ -3,19 +3,23 @@
 //! This module provides the core internationalization functionality including
 //! translation loading, caching, language detection, and message formatting.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
+use std::sync::{Arc, RwLock};
 use serde_json::{Value, Map};
 use tokio::fs;
 use tracing::{info, warn, error, debug};
 use crate::utils::errors::{SwingBuddyError, Result};
 use crate::config::I18nConfig;
+use super::fluent::FluentLanguage;
 
 /// Main internationalization manager
 #[derive(Debug, Clone)]
 pub struct I18n {
-    /// Loaded translations by language code
-    translations: HashMap<String, Map<String, Value>>,
+    /// Loaded translations by language code, shared by all clones so a reload reaches every handler
+    translations: Arc<RwLock<Translations>>,
+    /// Fluent messages by language code; they take precedence over the JSON keys
+    fluent: Arc<RwLock<FluentTranslations>>,
     /// Default language code
     default_language: String,
     /// Supported language codes
@@ -25,11 +29,16 @@ pub struct I18n {
 /// Translation parameters for message formatting
 pub type TranslationParams = HashMap<String, String>;
 
+type Translations = HashMap<String, Map<String, Value>>;
+
+type FluentTranslations = HashMap<String, FluentLanguage>;
+
 impl I18n {
     /// Create a new I18n instance
     pub fn new(config: &I18nConfig) -> Self {
         Self {
-            translations: HashMap::new(),
+            translations: Arc::new(RwLock::new(HashMap::new())),
+            fluent: Arc::new(RwLock::new(HashMap::new())),
             default_language: config.default_language.clone(),
             supported_languages: config.supported_languages.clone(),
         }
@@ -37,6 +46,14 @@ impl I18n {
 
     /// Load all translation files from the translations directory
     pub async fn load_translations(&mut self) -> Result<()> {
+        let (loaded, fluent) = self.read_translations().await?;
+        *self.translations.write().unwrap_or_else(|e| e.into_inner()) = loaded;
+        *self.fluent.write().unwrap_or_else(|e| e.into_inner()) = fluent;
+        Ok(())
+    }
+
+    /// Read the JSON and Fluent translation files of all supported languages
+    async fn read_translations(&self) -> Result<(Translations, FluentTranslations)> {
         let translations_dir = Path::new("translations");
         
         if !translations_dir.exists() {
@@ -44,13 +61,18 @@ impl I18n {
             fs::create_dir_all(translations_dir).await?;
         }
 
-        let supported_languages = self.supported_languages.clone();
-        for lang_code in &supported_languages {
+        let mut loaded = HashMap::new();
+        let mut fluent = HashMap::new();
+        for lang_code in &self.supported_languages {
             let file_path = translations_dir.join(format!("{}.json", lang_code));
+            let ftl_path = translations_dir.join(format!("{}.ftl", lang_code));
             
             if file_path.exists() {
                 match self.load_language_file(&file_path, lang_code).await {
-                    Ok(_) => info!("Loaded translations for language: {}", lang_code),
+                    Ok(map) => {
+                        info!("Loaded translations for language: {}", lang_code);
+                        loaded.insert(lang_code.clone(), map);
+                    }
                     Err(e) => {
                         error!("Failed to load translations for {}: {}", lang_code, e);
                         if lang_code == &self.default_language {
@@ -60,7 +82,7 @@ impl I18n {
                         }
                     }
                 }
-            } else {
+            } else if !ftl_path.exists() {
                 warn!("Translation file not found: {}", file_path.display());
                 if lang_code == &self.default_language {
                     return Err(SwingBuddyError::Config(
@@ -68,75 +90,116 @@ impl I18n {
                     ));
                 }
             }
+
+            if ftl_path.exists() {
+                match FluentLanguage::load(&ftl_path, lang_code).await {
+                    Ok(language) => {
+                        info!("Loaded {} Fluent messages for language: {}", language.keys().len(), lang_code);
+                        fluent.insert(lang_code.clone(), language);
+                    }
+                    Err(e) => {
+                        error!("Failed to load Fluent translations for {}: {}", lang_code, e);
+                        if lang_code == &self.default_language {
+                            return Err(SwingBuddyError::Config(
+                                format!("Failed to load default language translations: {}", e)
+                            ));
+                        }
+                    }
+                }
+            }
         }
 
-        Ok(())
+        Ok((loaded, fluent))
     }
 
     /// Load a single language file
-    async fn load_language_file(&mut self, file_path: &Path, lang_code: &str) -> Result<()> {
+    async fn load_language_file(&self, file_path: &Path, lang_code: &str) -> Result<Map<String, Value>> {
         let content = fs::read_to_string(file_path).await?;
         let translations: Value = serde_json::from_str(&content)?;
         
         if let Value::Object(map) = translations {
-            self.translations.insert(lang_code.to_string(), map);
-            debug!("Loaded {} translation keys for {}", 
-                   self.translations.get(lang_code).unwrap().len(), 
-                   lang_code);
+            debug!("Loaded {} translation keys for {}", map.len(), lang_code);
+            Ok(map)
         } else {
-            return Err(SwingBuddyError::Config(
+            Err(SwingBuddyError::Config(
                 format!("Invalid translation file format for {}", lang_code)
-            ));
+            ))
         }
-
-        Ok(())
     }
 
     /// Get a translated message
     pub fn t(&self, key: &str, lang: &str, params: Option<&TranslationParams>) -> String {
         let effective_lang = self.get_effective_language(lang);
-        
-        match self.get_translation_value(key, &effective_lang) {
-            Some(translation) => {
-                let text = self.extract_text_from_value(&translation);
-                self.format_message(&text, params)
-            }
-            None => {
-                // Fallback to default language if not found
-                if effective_lang != self.default_language {
-                    match self.get_translation_value(key, &self.default_language) {
-                        Some(translation) => {
-                            let text = self.extract_text_from_value(&translation);
-                            self.format_message(&text, params)
-                        }
-                        None => {
-                            warn!("Translation key '{}' not found in any language", key);
-                            key.to_string()
-                        }
-                    }
-                } else {
-                    warn!("Translation key '{}' not found in default language", key);
-                    key.to_string()
-                }
+
+        // Fallback to default language if not found
+        for lang in self.fallback_languages(&effective_lang) {
+            if let Some(text) = self.translate(key, lang, params) {
+                return text;
             }
         }
+
+        warn!("Translation key '{}' not found in any language", key);
+        key.to_string()
     }
 
-    /// Get a translated message with pluralization support
+    /// Get a translated message with pluralization support. Fluent messages
+    /// pick the plural form themselves from the numeric `$count`; JSON keys
+    /// have one nested key per plural form.
     pub fn tp(&self, key: &str, lang: &str, count: i32, params: Option<&TranslationParams>) -> String {
         let effective_lang = self.get_effective_language(lang);
-        let plural_key = self.get_plural_key(key, count, &effective_lang);
         
         let mut final_params = params.cloned().unwrap_or_default();
         final_params.insert("count".to_string(), count.to_string());
-        
-        self.t(&plural_key, &effective_lang, Some(&final_params))
+
+        for lang in self.fallback_languages(&effective_lang) {
+            if let Some(text) = self.format_fluent(key, lang, Some(&final_params)) {
+                return text;
+            }
+            let plural_key = self.get_plural_key(key, count, lang);
+            if let Some(text) = self.format_json(&plural_key, lang, Some(&final_params)) {
+                return text;
+            }
+        }
+
+        warn!("Translation key '{}' not found in any language", key);
+        self.get_plural_key(key, count, &effective_lang)
+    }
+
+    /// The language itself, then the default language
+    fn fallback_languages<'a>(&'a self, lang: &'a str) -> Vec<&'a str> {
+        if lang == self.default_language {
+            vec![lang]
+        } else {
+            vec![lang, &self.default_language]
+        }
+    }
+
+    /// Translate from one language only, Fluent first
+    fn translate(&self, key: &str, lang: &str, params: Option<&TranslationParams>) -> Option<String> {
+        self.format_fluent(key, lang, params)
+            .or_else(|| self.format_json(key, lang, params))
+    }
+
+    fn format_fluent(&self, key: &str, lang: &str, params: Option<&TranslationParams>) -> Option<String> {
+        self.fluent.read().unwrap_or_else(|e| e.into_inner()).get(lang)?.format(key, params)
+    }
+
+    fn format_json(&self, key: &str, lang: &str, params: Option<&TranslationParams>) -> Option<String> {
+        let translation = self.get_translation_value(key, lang)?;
+        let text = self.extract_text_from_value(&translation);
+        Some(self.format_message(&text, params))
+    }
+
+    /// Check if a key is translated in the given language, without falling back to the default language
+    pub fn has_translation(&self, key: &str, lang: &str) -> bool {
+        self.format_fluent(key, lang, None).is_some() || self.get_translation_value(key, lang).is_some()
     }
 
     /// Check if a language is supported
     pub fn is_language_supported(&self, lang: &str) -> bool {
         let is_supported = self.supported_languages.contains(&lang.to_string());
-        let has_translations = self.translations.contains_key(lang);
+        let has_translations = self.translations.read().unwrap_or_else(|e| e.into_inner()).contains_key(lang)
+            || self.fluent.read().unwrap_or_else(|e| e.into_inner()).contains_key(lang);
         debug!(language = %lang, is_supported = is_supported, has_translations = has_translations,
                supported_languages = ?self.supported_languages, "Language support check");
         is_supported && has_translations
@@ -144,7 +207,7 @@ impl I18n {
 
     /// Get the effective language (fallback to default if not supported)
     fn get_effective_language(&self, lang: &str) -> String {
-        if self.is_language_supported(lang) && self.translations.contains_key(lang) {
+        if self.is_language_supported(lang) {
             lang.to_string()
         } else {
             self.default_language.clone()
@@ -153,17 +216,17 @@ impl I18n {
 
     /// Get translation value from nested JSON structure
     fn get_translation_value(&self, key: &str, lang: &str) -> Option<Value> {
-        let translations = self.translations.get(lang)?;
+        let translations = self.translations.read().unwrap_or_else(|e| e.into_inner());
         
         // Support nested keys like "commands.start.welcome"
-        let keys: Vec<&str> = key.split('.').collect();
-        let mut current = Value::Object(translations.clone());
+        let mut keys = key.split('.');
+        let mut current = translations.get(lang)?.get(keys.next()?)?;
         
         for k in keys {
-            current = current.get(k)?.clone();
+            current = current.get(k)?;
         }
         
-        Some(current)
+        Some(current.clone())
     }
 
     /// Extract text from JSON value (handle both strings and objects with pluralization)
@@ -258,10 +321,19 @@ impl I18n {
         self.default_language.clone()
     }
 
-    /// Reload translations (useful for development or dynamic updates)
-    pub async fn reload_translations(&mut self) -> Result<()> {
-        self.translations.clear();
-        self.load_translations().await
+    /// Reload translations from disk for every clone of this instance.
+    /// The current translations stay in place if the files cannot be read.
+    pub async fn reload_translations(&mut self) -> Result<Vec<TranslationChanges>> {
+        let (loaded, fluent) = self.read_translations().await?;
+        let mut translations = self.translations.write().unwrap_or_else(|e| e.into_inner());
+        let mut current_fluent = self.fluent.write().unwrap_or_else(|e| e.into_inner());
+        let changes = translation_changes(
+            &language_keys(&translations, &current_fluent),
+            &language_keys(&loaded, &fluent),
+        );
+        *translations = loaded;
+        *current_fluent = fluent;
+        Ok(changes)
     }
 
     /// Get translation statistics
@@ -271,31 +343,21 @@ impl I18n {
             total_keys: 0,
         };
 
-        for (lang, translations) in &self.translations {
-            let key_count = self.count_keys(translations);
+        let translations = self.translations.read().unwrap_or_else(|e| e.into_inner());
+        let fluent = self.fluent.read().unwrap_or_else(|e| e.into_inner());
+        for (lang, keys) in language_keys(&translations, &fluent) {
+            let key_count = keys.len();
+            if lang == self.default_language {
+                stats.total_keys = key_count;
+            }
             stats.languages.push(LanguageStats {
-                code: lang.clone(),
+                code: lang,
                 key_count,
             });
-            if lang == &self.default_language {
-                stats.total_keys = key_count;
-            }
         }
 
         stats
     }
-
-    /// Recursively count translation keys
-    fn count_keys(&self, obj: &Map<String, Value>) -> usize {
-        let mut count = 0;
-        for value in obj.values() {
-            match value {
-                Value::Object(nested) => count += self.count_keys(nested),
-                _ => count += 1,
-            }
-        }
-        count
-    }
 }
 
 /// Translation statistics
@@ -312,6 +374,59 @@ pub struct LanguageStats {
     pub key_count: usize,
 }
 
+/// Keys added and removed in one language by a reload
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslationChanges {
+    pub code: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Keys of every language: dotted JSON paths and Fluent message ids
+fn language_keys(translations: &Translations, fluent: &FluentTranslations) -> HashMap<String, BTreeSet<String>> {
+    let mut keys: HashMap<String, BTreeSet<String>> = translations.iter()
+        .map(|(code, map)| (code.clone(), flatten_keys(map)))
+        .collect();
+    for (code, language) in fluent {
+        keys.entry(code.clone()).or_default().extend(language.keys().iter().cloned());
+    }
+    keys
+}
+
+/// Compare translations key by key; languages without changes are left out
+fn translation_changes(old: &HashMap<String, BTreeSet<String>>, new: &HashMap<String, BTreeSet<String>>) -> Vec<TranslationChanges> {
+    let languages: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+
+    languages.into_iter()
+        .filter_map(|code| {
+            let old_keys = old.get(code).cloned().unwrap_or_default();
+            let new_keys = new.get(code).cloned().unwrap_or_default();
+            let added: Vec<String> = new_keys.difference(&old_keys).cloned().collect();
+            let removed: Vec<String> = old_keys.difference(&new_keys).cloned().collect();
+
+            (!added.is_empty() || !removed.is_empty())
+                .then(|| TranslationChanges { code: code.clone(), added, removed })
+        })
+        .collect()
+}
+
+/// Collect the dotted paths of all translated strings
+fn flatten_keys(map: &Map<String, Value>) -> BTreeSet<String> {
+    fn collect(map: &Map<String, Value>, prefix: &str, keys: &mut BTreeSet<String>) {
+        for (key, value) in map {
+            let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            match value {
+                Value::Object(nested) => collect(nested, &path, keys),
+                _ => { keys.insert(path); }
+            }
+        }
+    }
+
+    let mut keys = BTreeSet::new();
+    collect(map, "", &mut keys);
+    keys
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -350,7 +465,7 @@ mod tests {
     #[test]
     fn test_language_detection() {
         let config = create_test_config();
-        let mut i18n = I18n::new(&config);
+        let i18n = I18n::new(&config);
         
         // Add mock translations to make languages "supported"
         use serde_json::Map;
@@ -359,8 +474,8 @@ mod tests {
         let mut ru_translations = Map::new();
         ru_translations.insert("test".to_string(), serde_json::Value::String("тест".to_string()));
         
-        i18n.translations.insert("en".to_string(), en_translations);
-        i18n.translations.insert("ru".to_string(), ru_translations);
+        i18n.translations.write().unwrap().insert("en".to_string(), en_translations);
+        i18n.translations.write().unwrap().insert("ru".to_string(), ru_translations);
         
         assert_eq!(i18n.detect_user_language(Some("en-US")), "en");
         assert_eq!(i18n.detect_user_language(Some("ru")), "ru");
@@ -380,4 +495,26 @@ mod tests {
         let result = i18n.format_message("Hello {name}, you have {count} messages", Some(&params));
         assert_eq!(result, "Hello John, you have 5 messages");
     }
+
+    #[test]
+    fn test_translation_changes() {
+        let old: Translations = HashMap::from([
+            ("en".to_string(), serde_json::json!({"a": "A", "b": {"one": "B", "other": "Bs"}}).as_object().unwrap().clone()),
+            ("ru".to_string(), serde_json::json!({"a": "А"}).as_object().unwrap().clone()),
+        ]);
+        let new: Translations = HashMap::from([
+            ("en".to_string(), serde_json::json!({"a": "A changed", "b": {"one": "B"}, "c": "C"}).as_object().unwrap().clone()),
+            ("ru".to_string(), serde_json::json!({"a": "А"}).as_object().unwrap().clone()),
+        ]);
+
+        let fluent = HashMap::new();
+        let old = language_keys(&old, &fluent);
+        let new = language_keys(&new, &fluent);
+        assert_eq!(translation_changes(&old, &new), vec![TranslationChanges {
+            code: "en".to_string(),
+            added: vec!["c".to_string()],
+            removed: vec!["b.other".to_string()],
+        }]);
+        assert!(translation_changes(&new, &new).is_empty());
+    }
 }
\ No newline at end of file
@@ -4,7 +4,8 @@
 //! It provides translation loading, language detection, message formatting,
 //! and pluralization support for multiple languages.
 
+pub mod fluent;
 pub mod loader;
 
 // Re-export commonly used i18n components
-pub use loader::{I18n, TranslationParams, TranslationStats, LanguageStats};
\ No newline at end of file
+pub use loader::{I18n, TranslationParams, TranslationStats, LanguageStats, TranslationChanges};
\ No newline at end of file
@@ -3,22 +3,23 @@
 //! Main application entry point
 
 use std::sync::Arc;
-use teloxide::{prelude::*, types::Update};
+use teloxide::{prelude::*, types::{Me, Update}};
 use teloxide::dispatching::UpdateHandler;
 use teloxide::utils::command::BotCommands as TeloxideBotCommands;
-use tracing::{info, warn, error};
+use tracing::{info, debug, warn, error};
 
 use SwingBuddy::{
     config::Settings,
-    utils::logging,
+    utils::{logging, redis_connection::RedisConnector},
+    middleware::{LoggingMiddleware, MetricsMiddleware, RateLimitMiddleware},
     database::{DatabaseService, connection::create_pool},
-    services::{ServiceFactory, redis::RedisService},
-    state::{ScenarioManager, StateStorage},
+    services::{ServiceFactory, StepReminderService, redis::RedisService},
+    state::{ScenarioManager, ScenarioSimulator, StateStorage},
     i18n::I18n,
     handlers::{
-        commands::{start, events, admin, help},
+        commands::{start, events, admin, help, notes, styles, moderation, invites, songs, venues, subscription},
         callbacks::handle_callback_query,
-        messages::{handle_message, handle_new_chat_member},
+        messages::{handle_message, handle_new_chat_member, handle_left_chat_member, handle_chat_member_update, handle_web_app_data},
     },
 };
 
@@ -26,6 +27,15 @@ type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Scenario simulation for translators runs without the bot and its services
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("simulate") {
+        return simulate_scenario(&args[2..]).await;
+    }
+    if args.get(1).map(String::as_str) == Some("convert-translations") {
+        return convert_translations(&args[2..]).await;
+    }
+
     // Load configuration
     let settings = Settings::new()?;
     settings.validate()?;
@@ -46,6 +56,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         max_lifetime: Some(std::time::Duration::from_secs(1800)),
     };
     let db_pool = create_pool(&db_config).await?;
+    SwingBuddy::utils::metrics::metrics()
+        .set_slow_query_threshold(std::time::Duration::from_millis(settings.database.slow_query_ms));
     
     // Run database migrations
     info!("Running database migrations...");
@@ -64,21 +76,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     i18n.load_translations().await?;
     
     // Initialize state management
-    let state_storage = StateStorage::new(settings.redis.clone()).await?;
-    let scenario_manager = ScenarioManager::new();
+    let state_storage = StateStorage::from_settings(&settings, database_service.pool.clone()).await?;
+    let scenario_manager = ScenarioManager::load(settings.scenarios.as_ref())?;
     
     // Initialize bot
     let bot = Bot::new(&settings.bot.token);
     
+    // The bot's own user; services keep its ID, handlers get `Me` from the dispatcher
+    let me = bot.get_me().await?;
+    info!("Running as @{}", me.username());
+    
     // Initialize services
     info!("Initializing services...");
-    let redis_client = ::redis::Client::open(settings.redis.url.clone())?;
-    let user_repository = database_service.users.clone();
+    let redis = RedisConnector::new(&settings.redis)?;
     let services = ServiceFactory::new(
         bot.clone(),
+        me.id,
         settings.clone(),
-        user_repository,
-        redis_client,
+        database_service.clone(),
+        redis.clone(),
     )?;
     
     info!("Setting up bot handlers...");
@@ -86,11 +102,98 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Debug: Log service factory creation
     info!("ServiceFactory created successfully");
     
+    // Apply the feature switches admins made in the admin panel
+    match services.feature_flag_service.load_overrides().await {
+        Ok(count) => info!("Applied {} feature flag overrides", count),
+        Err(e) => warn!("Failed to load feature flag overrides: {}", e),
+    }
+    
+    // Show each audience its commands in the Telegram menu
+    help::register_command_menus(&bot, &BotCommands::bot_commands(), &i18n, &settings.bot.admin_ids).await;
+    
+    // Start scheduled group reminders (no-op unless enabled in config)
+    let _reminder_task = services.reminder_service.clone().start();
+    
+    // Start daily birthday greetings (no-op unless enabled in config)
+    let _birthday_task = services.birthday_service.clone().start();
+    
+    // Remove new members who did not pass the join captcha (no-op unless configured)
+    let _captcha_task = services.captcha_service.clone().start();
+    
+    // Forget message counts of members who stopped posting (no-op without flood control)
+    let _flood_task = services.moderation_service.clone().start();
+    
+    // Lift timed mutes set with /mute when they run out
+    let _unmute_task = services.moderation_service.clone().start_unmute_scheduler();
+    
+    // Delete join/leave messages and temporary replies in groups that asked for it (no-op unless configured)
+    let _cleanup_task = services.cleanup_service.clone().start();
+    
+    // Move events past the retention period into the archive tables (no-op unless configured)
+    let _archive_task = services.archive_service.clone().start();
+    
+    // Remind users of scenarios they left unfinished before they expire (no-op unless configured)
+    let _step_reminder_task = StepReminderService::new(
+        bot.clone(),
+        state_storage.clone(),
+        scenario_manager.clone(),
+        services.user_service.clone(),
+        services.notification_service.clone(),
+        services.config_service.shared(),
+    ).start();
+    
+    // Delete expired conversation contexts kept in PostgreSQL (no-op with Redis)
+    let _state_cleanup_task = state_storage.clone().start_cleanup();
+    
+    // Post the weekly event digest in subscribed groups (no-op unless configured)
+    let _digest_task = services.digest_service.clone().start();
+    
+    // Post announcements held back during groups' quiet hours
+    let _quiet_hours_task = services.quiet_hours_service.clone().start();
+    
+    // Re-check recently active group members against CAS (no-op unless configured)
+    let _cas_recheck_task = services.cas_recheck_service.clone().start();
+
+    // Check the authors of group messages against CAS in batches (no-op with CAS off)
+    let _cas_queue_task = services.cas_queue_service.clone().start();
+
+    // Deliver broadcasts queued from the admin panel
+    let _broadcast_task = services.broadcast_service.clone().start();
+    
+    // Send broadcasts and group announcements admins scheduled for later
+    let _scheduled_message_task = services.scheduled_message_service.clone().start();
+    
+    // Apply notification template texts edited from the admin panel
+    match services.notification_template_service.load().await {
+        Ok(count) => info!("Loaded {} edited notification templates", count),
+        Err(e) => error!("Failed to load edited notification templates, using built-in texts: {}", e),
+    }
+    
+    // Send queued bulk notifications within the Telegram rate limits
+    let _notification_queue_task = services.notification_queue_service.clone().start();
+    
+    // Keep a Google Calendar push channel open and renew it (no-op unless configured)
+    let _google_watch_task = services.google_watch_service.clone().start();
+    
+    // Mirror event participant lists into their Google Sheets (no-op without [google.oauth])
+    let _sheet_export_task = services.sheet_export_service.clone().start();
+    
+    // Write group activity counters to the database once a minute
+    let _stats_task = services.stats_service.clone().start();
+    let stats_service = services.stats_service.clone();
+    
     // Wrap services in Arc for dependency injection
     let services_arc = Arc::new(services);
     let scenario_manager_arc = Arc::new(scenario_manager);
     let state_storage_arc = Arc::new(state_storage);
     let i18n_arc = Arc::new(i18n);
+    // Limits on commands and buttons from [rate_limits], counted in Redis
+    let rate_limiter_arc = Arc::new(RateLimitMiddleware::default().with_redis(redis, &settings.redis.prefix));
+    
+    // Serve /healthz and /readyz for container probes (only when configured)
+    let _health_server_task = settings.health.as_ref()
+        .and_then(|health| health.listen_address.parse().ok())
+        .map(|address| SwingBuddy::services::HealthServer::new(services_arc.clone(), address).start());
     
     // Create the handler
     let handler = create_handler();
@@ -101,7 +204,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             services_arc,
             scenario_manager_arc,
             state_storage_arc,
-            i18n_arc
+            i18n_arc,
+            rate_limiter_arc
         ])
         .default_handler(|upd| async move {
             warn!("Unhandled update: {:?}", upd);
@@ -114,58 +218,151 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("SwingBuddy bot is ready!");
     
     // Start the bot
-    if let Some(webhook_url) = &settings.bot.webhook_url {
-        info!("Webhook URL configured: {}", webhook_url);
-        info!("Note: Webhook setup not implemented in this version, falling back to polling");
+    match start_webhook(&bot, &settings).await {
+        Some(listener) => {
+            info!("Starting bot with webhook mode...");
+            dispatcher
+                .dispatch_with_listener(
+                    listener,
+                    LoggingErrorHandler::with_custom_text("An error from the webhook listener"),
+                )
+                .await;
+        }
+        None => {
+            info!("Starting bot with polling mode...");
+            dispatcher.dispatch().await;
+        }
     }
     
-    info!("Starting bot with polling mode...");
-    
-    // Configure allowed update types to include callback queries
-    
-    
-    
-    
-    
-    dispatcher.dispatch().await;
+    // Keep the counts of the last minute
+    if let Err(e) = stats_service.flush().await {
+        warn!("Failed to flush group activity counters: {}", e);
+    }
     
     info!("SwingBuddy bot has been shut down.");
     
     Ok(())
 }
 
+/// Register the webhook with Telegram and serve it, or `None` to poll instead.
+/// Any failure falls back to polling; polling removes the webhook again.
+async fn start_webhook(
+    bot: &Bot,
+    settings: &Settings,
+) -> Option<impl teloxide::update_listeners::UpdateListener<Err = std::convert::Infallible>> {
+    use teloxide::update_listeners::{webhooks, UpdateListener};
+
+    let mut options = match SwingBuddy::utils::webhook::webhook_options(settings) {
+        Ok(Some(options)) => options,
+        Ok(None) => return None,
+        Err(e) => {
+            error!("Invalid webhook configuration, falling back to polling: {}", e);
+            return None;
+        }
+    };
+
+    let address = options.address;
+    let tcp_listener = match tokio::net::TcpListener::bind(address).await {
+        Ok(tcp_listener) => tcp_listener,
+        Err(e) => {
+            error!("Couldn't listen for webhook requests on {}, falling back to polling: {}", address, e);
+            return None;
+        }
+    };
+
+    info!("Setting webhook to {} (listening on {}{})", options.url, address, options.path);
+    if let Err(e) = SwingBuddy::utils::webhook::register_webhook(bot, &mut options).await {
+        error!("Couldn't set the webhook, falling back to polling: {}", e);
+        return None;
+    }
+    let (mut listener, stop_flag, router) = webhooks::axum_no_setup(options);
+    let webhook_bot = bot.clone();
+    let stop_flag = async move {
+        stop_flag.await;
+        if let Err(e) = webhook_bot.delete_webhook().await {
+            error!("Couldn't delete the webhook: {}", e);
+        }
+    };
+
+    // Requests without the secret token header are rejected by the router
+    let stop_token = listener.stop_token();
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(tcp_listener, router).with_graceful_shutdown(stop_flag).await {
+            error!("Webhook server error: {}", e);
+            stop_token.stop();
+        }
+    });
+
+    Some(listener)
+}
+
 /// Create the main update handler
 fn create_handler() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
     use teloxide::dispatching::UpdateFilterExt;
     
+    // Tag everything logged while handling an update with its correlation ID
     dptree::entry()
+    .chain(LoggingMiddleware::update_layer())
     .branch(Update::filter_message()
         .branch(
             // Handle commands
             dptree::entry()
                 .filter_command::<BotCommands>()
+                .chain(MetricsMiddleware::layer("commands"))
+                .chain(RateLimitMiddleware::layer())
+                .chain(MetricsMiddleware::command_layer())
                 .endpoint(handle_commands)
         )
         .branch(
             // Handle new chat members
             dptree::filter(|msg: Message| msg.new_chat_members().is_some())
+                .chain(MetricsMiddleware::layer("new_members"))
                 .endpoint(handle_new_members)
         )
+        .branch(
+            // Handle members leaving the chat
+            dptree::filter(|msg: Message| msg.left_chat_member().is_some())
+                .chain(MetricsMiddleware::layer("left_members"))
+                .endpoint(handle_left_members)
+        )
+        .branch(
+            // Handle registrations sent back from the Mini App
+            dptree::filter(|msg: Message| msg.web_app_data().is_some())
+                .chain(MetricsMiddleware::layer("web_app_data"))
+                .chain(RateLimitMiddleware::layer())
+                .endpoint(handle_web_app_messages)
+        )
         .branch(
             // Handle regular messages
-            dptree::endpoint(handle_messages)
+            MetricsMiddleware::layer("messages")
+                .endpoint(handle_messages)
         )
   
     )
     .branch(// Handle callback queries
             Update::filter_callback_query()
+                .chain(MetricsMiddleware::layer("callbacks"))
+                .chain(RateLimitMiddleware::layer())
                 .endpoint(handle_callbacks)
     )
     .branch(
             // Handle my chat member updates (bot added/removed from groups)
             Update::filter_my_chat_member()
+                .chain(MetricsMiddleware::layer("chat_member_updates"))
                 .endpoint(handle_chat_member_updates)
     )
+    .branch(
+            // Handle other members joining, leaving, promoted or demoted
+            Update::filter_chat_member()
+                .chain(MetricsMiddleware::layer("member_updates"))
+                .endpoint(handle_member_updates)
+    )
+    .branch(
+            // Handle join requests sent through tracked invite links
+            Update::filter_chat_join_request()
+                .chain(MetricsMiddleware::layer("join_requests"))
+                .endpoint(handle_join_requests)
+    )
 }
 
 #[derive(TeloxideBotCommands, Clone)]
@@ -173,20 +370,106 @@ fn create_handler() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + '
 enum BotCommands {
     #[command(description = "Start the bot and user onboarding")]
     Start,
-    #[command(description = "Show help information")]
-    Help,
+    #[command(description = "Show help, or details of a command: /help <command>")]
+    Help(String),
     #[command(description = "Browse dance events and calendars")]
     Events,
+    #[command(description = "Show the next upcoming event with a register button")]
+    NextEvent,
+    #[command(description = "List dance venues in a city with their floor and upcoming events: /venues [city|all]")]
+    Venues(String),
+    #[command(description = "Ask the DJ for a song during an event: /request <track>")]
+    Request(String),
+    #[command(description = "Show the song request queue with played/skip buttons (organizers)")]
+    Queue(String),
+    #[command(description = "List the requested songs played at an event")]
+    Playlist(String),
+    #[command(description = "Show your profile and invite link")]
+    Profile,
+    #[command(description = "Set your birthday (DD.MM) or allow announcing it in a group")]
+    Birthday(String),
+    #[command(description = "Choose your favorite dance styles")]
+    Styles,
+    #[command(description = "Stop broadcasts and reminders")]
+    Unsubscribe,
+    #[command(description = "Get broadcasts and reminders again")]
+    Subscribe,
+    #[command(description = "Go back to the previous step of what you are filling in")]
+    Back,
     #[command(description = "Admin panel (admin only)")]
     Admin,
     #[command(description = "Show bot statistics (admin only)")]
     Stats,
+    #[command(rename = "reload_config", description = "Reload the configuration without restarting (admin only)")]
+    ReloadConfig,
+    #[command(rename = "reload_translations", description = "Reload the translation files and list changed keys (admin only)")]
+    ReloadTranslations,
+    #[command(description = "Show this group's activity over the last week (group admins)")]
+    GroupStats,
+    #[command(description = "Check the bot's admin rights in this group (group admins)")]
+    CheckPerms,
+    #[command(description = "List, add or remove users exempt from CAS auto-bans (admin only)")]
+    CasWhitelist(String),
+    #[command(description = "Show user details and notes (admin only)")]
+    User(String),
+    #[command(description = "Show a user's conversation state, with buttons to clear it (admin only)")]
+    State(String),
+    #[command(description = "Add a private note to a user (admin only)")]
+    Note(String),
+    #[command(description = "Edit a private user note (admin only)")]
+    EditNote(String),
+    #[command(description = "Show edit history of a note (admin only)")]
+    NoteHistory(String),
+    #[command(description = "Show event door list with notes (admins and organizers)")]
+    DoorList(String),
+    #[command(description = "Turn group event reminders on or off (group admins)")]
+    Reminders(String),
+    #[command(description = "Post a weekly digest of upcoming events in this group: on|off (group admins)")]
+    Digest(String),
+    #[command(description = "Queue group announcements during quiet hours: <start>-<end> in UTC hours|off (group admins)")]
+    QuietHours(String),
+    #[command(description = "Turn the group reminder for an event on or off")]
+    EventReminder(String),
+    #[command(description = "Show or set the dance styles of an event")]
+    EventStyles(String),
+    #[command(description = "Mirror an event's participants into a Google Sheet")]
+    EventSheet(String),
+    #[command(description = "Configure the join captcha (group admins)")]
+    Captcha(String),
+    #[command(description = "Show the group rules; admins: set, clear, onjoin on|off")]
+    Rules(String),
+    #[command(description = "Send new members an intro card: group|dm|off (group admins)")]
+    Intro(String),
+    #[command(description = "Warn a group member: /warn @user [reason], or reply to their message (group admins)")]
+    Warn(String),
+    #[command(description = "Reply to a message with /report [reason] to alert the group admins")]
+    Report(String),
+    #[command(description = "Ban a group member; groups sharing the ban list ban them everywhere (group admins)")]
+    Ban(String),
+    #[command(description = "Lift a ban (group admins)")]
+    Unban(String),
+    #[command(description = "Mute a group member for a while: /mute @user 2h [reason] (group admins)")]
+    Mute(String),
+    #[command(description = "Lift a mute (group admins)")]
+    Unmute(String),
+    #[command(description = "List roles or give a member one: /role @user moderator|organizer|admin|owner|none (group admins)")]
+    Role(String),
+    #[command(description = "Share the bot-level ban list with other groups: on|off (group admins)")]
+    SharedBans(String),
+    #[command(description = "Auto-delete join/leave messages and temporary bot replies: on [seconds]|off (group admins)")]
+    Cleanup(String),
+    #[command(description = "Post event announcements or moderation notices in this forum topic: events|moderation [off] (group admins)")]
+    Topic(String),
+    #[command(description = "Manage tracked join-request invite links: new <name>|list|rotate <id>|revoke <id> (group admins)")]
+    Invite(String),
 }
 
 /// Handle bot commands
+#[allow(clippy::too_many_arguments)]
 async fn handle_commands(
     bot: Bot,
     msg: Message,
+    me: Me,
     cmd: BotCommands,
     services: Arc<ServiceFactory>,
     scenario_manager: Arc<ScenarioManager>,
@@ -198,24 +481,172 @@ async fn handle_commands(
     let state_storage = (*state_storage).clone();
     let i18n = (*i18n).clone();
     
+    if let Some(user) = msg.from.as_ref() {
+        services.user_service.record_activity(user.id.0 as i64).await;
+    }
+    
+    // Put the private conversation's scenario aside while another command runs;
+    // /start and /back act on it instead
+    let chat_id = msg.chat.id;
+    let paused = match msg.from.as_ref() {
+        Some(user) if chat_id.is_user() && !matches!(cmd, BotCommands::Start | BotCommands::Back) => {
+            start::pause_for_command(user.id.0 as i64, &scenario_manager, &state_storage).await
+        }
+        _ => None,
+    };
+    let resume = paused.map(|paused| (bot.clone(), services.clone(), state_storage.clone(), i18n.clone(), paused));
+    
     let result = match cmd {
         BotCommands::Start => {
             start::handle_start(bot, msg, services, scenario_manager, state_storage, i18n).await
         }
-        BotCommands::Help => {
-            help::handle_help(bot, msg).await
+        BotCommands::Help(topic) => {
+            help::handle_help(bot, msg, topic, BotCommands::bot_commands(), services, i18n).await
         }
         BotCommands::Events => {
-            events::handle_events_list(bot, msg, services, i18n).await
+            events::handle_events_list(bot, msg, me, services, i18n).await
+        }
+        BotCommands::NextEvent => {
+            events::handle_next_event(bot, msg, services, i18n).await
+        }
+        BotCommands::Profile => {
+            start::handle_profile(bot, msg, me, services, i18n).await
+        }
+        BotCommands::Birthday(args) => {
+            start::handle_birthday(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Unsubscribe => {
+            subscription::handle_unsubscribe(bot, msg, services, i18n).await
+        }
+        BotCommands::Subscribe => {
+            subscription::handle_subscribe(bot, msg, services, i18n).await
+        }
+        BotCommands::Styles => {
+            styles::handle_styles(bot, msg, services, i18n).await
+        }
+        BotCommands::Back => {
+            let user_id = msg.from.as_ref().map_or(msg.chat.id.0, |user| user.id.0 as i64);
+            start::handle_back(bot, msg.chat.id, user_id, services, scenario_manager, state_storage, i18n).await
         }
         BotCommands::Admin => {
             admin::handle_admin_panel(bot, msg, services, scenario_manager, state_storage, i18n).await
         }
         BotCommands::Stats => {
-            admin::handle_stats(bot, msg, services, i18n).await
+            admin::handle_stats(bot, msg, services, state_storage, i18n).await
+        }
+        BotCommands::ReloadConfig => {
+            admin::handle_reload_config(bot, msg, services, i18n).await
+        }
+        BotCommands::ReloadTranslations => {
+            admin::handle_reload_translations(bot, msg, services, i18n).await
+        }
+        BotCommands::GroupStats => {
+            admin::handle_group_stats(bot, msg, services, i18n).await
+        }
+        BotCommands::CheckPerms => {
+            admin::handle_check_permissions(bot, msg, services, i18n).await
+        }
+        BotCommands::CasWhitelist(args) => {
+            admin::handle_cas_whitelist(bot, msg, args, services, i18n).await
+        }
+        BotCommands::User(args) => {
+            admin::handle_user_detail(bot, msg, args, services, i18n).await
+        }
+        BotCommands::State(args) => {
+            admin::handle_state(bot, msg, args, services, state_storage, i18n).await
+        }
+        BotCommands::Note(args) => {
+            notes::handle_add_note(bot, msg, args, services, i18n).await
+        }
+        BotCommands::EditNote(args) => {
+            notes::handle_edit_note(bot, msg, args, services, i18n).await
+        }
+        BotCommands::NoteHistory(args) => {
+            notes::handle_note_history(bot, msg, args, services, i18n).await
+        }
+        BotCommands::DoorList(args) => {
+            events::handle_door_list(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Reminders(args) => {
+            events::handle_group_reminders_toggle(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Digest(args) => {
+            events::handle_digest_toggle(bot, msg, args, services, i18n).await
+        }
+        BotCommands::QuietHours(args) => {
+            events::handle_quiet_hours(bot, msg, args, services, i18n).await
+        }
+        BotCommands::EventReminder(args) => {
+            events::handle_event_reminder_toggle(bot, msg, args, services, i18n).await
+        }
+        BotCommands::EventStyles(args) => {
+            styles::handle_event_styles(bot, msg, args, services, i18n).await
+        }
+        BotCommands::EventSheet(args) => {
+            events::handle_event_sheet(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Captcha(args) => {
+            moderation::handle_captcha_settings(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Rules(args) => {
+            moderation::handle_rules(bot, msg, args, services, scenario_manager, state_storage, i18n).await
+        }
+        BotCommands::Intro(args) => {
+            moderation::handle_intro(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Warn(args) => {
+            moderation::handle_warn(bot, msg, me, args, services, i18n).await
+        }
+        BotCommands::Report(args) => {
+            moderation::handle_report(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Ban(args) => {
+            moderation::handle_ban(bot, msg, me, args, services, i18n).await
+        }
+        BotCommands::Unban(args) => {
+            moderation::handle_unban(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Mute(args) => {
+            moderation::handle_mute(bot, msg, me, args, services, i18n).await
+        }
+        BotCommands::Unmute(args) => {
+            moderation::handle_unmute(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Role(args) => {
+            moderation::handle_role(bot, msg, args, services, i18n).await
+        }
+        BotCommands::SharedBans(args) => {
+            moderation::handle_shared_bans_toggle(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Cleanup(args) => {
+            moderation::handle_cleanup(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Topic(args) => {
+            moderation::handle_topic(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Invite(args) => {
+            invites::handle_invite(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Venues(args) => {
+            venues::handle_venues(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Request(args) => {
+            songs::handle_song_request(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Queue(args) => {
+            songs::handle_song_queue(bot, msg, args, services, i18n).await
+        }
+        BotCommands::Playlist(args) => {
+            songs::handle_playlist(bot, msg, args, services, i18n).await
         }
     };
     
+    if let Some((bot, services, state_storage, i18n, paused)) = resume {
+        if let Err(e) = start::offer_resume(bot, chat_id, paused, &services, &state_storage, &i18n).await {
+            warn!(error = %e, "Failed to offer resuming the paused scenario");
+        }
+    }
+    
     if let Err(e) = result {
         error!(error = %e, "Error handling command");
         return Err(e.into());
@@ -238,6 +669,10 @@ async fn handle_messages(
     let state_storage = (*state_storage).clone();
     let i18n = (*i18n).clone();
     
+    if let Some(user) = msg.from.as_ref() {
+        services.user_service.record_activity(user.id.0 as i64).await;
+    }
+    
     if let Err(e) = handle_message(bot, msg, services, scenario_manager, state_storage, i18n).await {
         error!(error = %e, "Error handling message");
         return Err(e.into());
@@ -250,11 +685,14 @@ async fn handle_messages(
 async fn handle_new_members(
     bot: Bot,
     msg: Message,
+    me: Me,
     services: Arc<ServiceFactory>,
+    i18n: Arc<I18n>,
 ) -> HandlerResult {
     let services = (*services).clone();
+    let i18n = (*i18n).clone();
     
-    if let Err(e) = handle_new_chat_member(bot, msg, services).await {
+    if let Err(e) = handle_new_chat_member(bot, msg, me, services, i18n).await {
         error!(error = %e, "Error handling new chat member");
         return Err(e.into());
     }
@@ -262,30 +700,147 @@ async fn handle_new_members(
     Ok(())
 }
 
+/// Handle members leaving a chat
+async fn handle_left_members(
+    msg: Message,
+    services: Arc<ServiceFactory>,
+) -> HandlerResult {
+    let services = (*services).clone();
+    
+    if let Err(e) = handle_left_chat_member(msg, services).await {
+        error!(error = %e, "Error handling left chat member");
+        return Err(e.into());
+    }
+    
+    Ok(())
+}
+
+/// Handle data sent back from the Mini App
+async fn handle_web_app_messages(
+    bot: Bot,
+    msg: Message,
+    services: Arc<ServiceFactory>,
+    i18n: Arc<I18n>,
+) -> HandlerResult {
+    let services = (*services).clone();
+    let i18n = (*i18n).clone();
+
+    if let Err(e) = handle_web_app_data(bot, msg, services, i18n).await {
+        error!(error = %e, "Error handling Mini App data");
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
 /// Handle callback queries
 async fn handle_callbacks(
     bot: Bot,
     query: teloxide::types::CallbackQuery,
+    me: Me,
     services: Arc<ServiceFactory>,
     scenario_manager: Arc<ScenarioManager>,
     state_storage: Arc<StateStorage>,
     i18n: Arc<I18n>,
 ) -> HandlerResult {
     let user_id = query.from.id.0 as i64;
-    info!(user_id = user_id, callback_data = ?query.data, "🔍 MAIN DISPATCHER: Callback query received in main handler");
+    debug!(user_id = user_id, callback_data = ?query.data, "Callback query received in main handler");
     
     let services = (*services).clone();
     let scenario_manager = (*scenario_manager).clone();
     let state_storage = (*state_storage).clone();
     let i18n = (*i18n).clone();
     
-    info!(user_id = user_id, "🔍 MAIN DISPATCHER: Dispatching to callback handler");
-    if let Err(e) = handle_callback_query(bot, query, services, scenario_manager, state_storage, i18n).await {
-        error!(user_id = user_id, error = %e, "🔍 MAIN DISPATCHER: Error handling callback query");
+    services.user_service.record_activity(user_id).await;
+    
+    match handle_callback_query(bot, query, me, services, scenario_manager, state_storage, i18n).await {
+        // A second press handled at the same time as the first one, which won
+        Err(SwingBuddy::SwingBuddyError::StaleContext { .. }) => {
+            warn!(user_id = user_id, "Ignoring callback query that raced another one");
+            Ok(())
+        }
+        Err(e) => {
+            error!(user_id = user_id, error = %e, "Error handling callback query");
+            Err(e.into())
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Print the localized texts and keyboards of a scenario in the terminal.
+///
+/// Usage: `SwingBuddy simulate <scenario> [--lang <code>] [--interactive]`
+async fn simulate_scenario(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let settings = Settings::new().unwrap_or_default();
+    let mut i18n = I18n::new(&settings.i18n);
+    i18n.load_translations().await?;
+    let scenario_manager = ScenarioManager::load(settings.scenarios.as_ref())?;
+
+    let mut scenario_id = None;
+    let mut language = i18n.default_language().to_string();
+    let mut interactive = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lang" => language = args.next().ok_or("Missing value for --lang")?.clone(),
+            "--interactive" | "-i" => interactive = true,
+            scenario => scenario_id = Some(scenario.to_string()),
+        }
+    }
+
+    let Some(scenario_id) = scenario_id else {
+        println!("Usage: SwingBuddy simulate <scenario> [--lang <code>] [--interactive]");
+        println!();
+        println!("Scenarios:");
+        let mut scenarios = scenario_manager.get_all_scenarios();
+        scenarios.sort_by(|a, b| a.id.cmp(&b.id));
+        for scenario in scenarios {
+            println!("  {:<16} {}", scenario.id, scenario.description);
+        }
+        return Ok(());
+    };
+
+    let simulator = ScenarioSimulator::new(&scenario_manager, &i18n, &language);
+    if interactive {
+        simulator.run_interactive(&scenario_id, std::io::stdin().lock(), std::io::stdout())?;
+    } else {
+        for step in simulator.walk(&scenario_id)? {
+            println!("{}", step);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle join requests
+async fn handle_join_requests(
+    bot: Bot,
+    request: teloxide::types::ChatJoinRequest,
+    services: Arc<ServiceFactory>,
+) -> HandlerResult {
+    let services = (*services).clone();
+
+    if let Err(e) = invites::handle_join_request(bot, request, services).await {
+        error!(error = %e, "Error handling join request");
         return Err(e.into());
     }
-    
-    info!(user_id = user_id, "🔍 MAIN DISPATCHER: Callback query handled successfully");
+
+    Ok(())
+}
+
+/// Handle status changes of group members other than the bot
+async fn handle_member_updates(
+    update: teloxide::types::ChatMemberUpdated,
+    services: Arc<ServiceFactory>,
+) -> HandlerResult {
+    let services = (*services).clone();
+
+    if let Err(e) = handle_chat_member_update(update, services).await {
+        error!(error = %e, "Error handling chat member update");
+        return Err(e.into());
+    }
+
     Ok(())
 }
 
@@ -293,6 +848,7 @@ async fn handle_callbacks(
 async fn handle_chat_member_updates(
     bot: Bot,
     update: teloxide::types::ChatMemberUpdated,
+    me: Me,
     services: Arc<ServiceFactory>,
     i18n: Arc<I18n>,
 ) -> HandlerResult {
@@ -301,9 +857,23 @@ async fn handle_chat_member_updates(
     let services = (*services).clone();
     let i18n = (*i18n).clone();
     
-    // Check if this is the bot being added to a group
-    let bot_user = bot.get_me().await?;
-    if update.new_chat_member.user.id == bot_user.id {
+    if update.new_chat_member.user.id != me.id {
+        return Ok(());
+    }
+
+    if update.new_chat_member.is_present() && update.old_chat_member.is_present() {
+        // The bot was promoted, demoted or its rights changed
+        if let Err(e) = group_setup::handle_bot_rights_changed(
+            bot,
+            update.chat.id,
+            services,
+            i18n,
+        ).await {
+            error!(error = %e, "Error handling bot rights change");
+            return Err(e.into());
+        }
+    } else if update.new_chat_member.is_present() {
+        // The bot was added to the group
         if let Err(e) = group_setup::handle_bot_added_to_group(
             bot,
             update.chat.id,
@@ -313,7 +883,30 @@ async fn handle_chat_member_updates(
             error!(error = %e, "Error handling bot added to group");
             return Err(e.into());
         }
+    } else if let Err(e) = group_setup::handle_bot_removed_from_group(
+        update.chat.id,
+        &update.from,
+        services,
+        i18n,
+    ).await {
+        // The bot was removed or kicked from the group
+        error!(error = %e, "Error handling bot removed from group");
+        return Err(e.into());
     }
     
     Ok(())
 }
+
+/// Print the Fluent equivalent of a JSON translation file
+async fn convert_translations(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(language) = args.first() else {
+        println!("Usage: SwingBuddy convert-translations <lang> > translations/<lang>.ftl");
+        return Ok(());
+    };
+
+    let content = tokio::fs::read_to_string(format!("translations/{}.json", language)).await?;
+    let translations: serde_json::Value = serde_json::from_str(&content)?;
+    let translations = translations.as_object().ok_or("Translation file must contain a JSON object")?;
+    print!("{}", SwingBuddy::i18n::fluent::json_to_ftl(translations));
+    Ok(())
+}
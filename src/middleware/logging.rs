@@ -2,12 +2,28 @@
 //! 
 //! This module provides logging middleware for tracking bot interactions,
 //! performance metrics, and debugging information.
-
+//!
+//! [`LoggingMiddleware::update_layer`] handles every update inside an `update`
+//! span carrying a fresh correlation ID. Everything logged while the update is
+//! handled, down to the services, database and Redis calls, is tagged with it,
+//! so one user's failing callback can be followed through busy logs.
+
+use std::error::Error;
+use std::ops::ControlFlow;
 use std::time::Instant;
+use teloxide::dispatching::UpdateHandler;
+use teloxide::dptree::di::{DependencyMap, DependencySupplier};
+use teloxide::dptree::Cont;
 use teloxide::types::{Message, Update, User};
-use tracing::{info, debug, warn, error, Span, instrument};
+use tracing::{info, debug, warn, error, Instrument, Span, instrument};
 use serde_json::json;
 
+type HandlerError = Box<dyn Error + Send + Sync + 'static>;
+type HandlerResult = Result<(), HandlerError>;
+
+/// Length of a correlation ID in hex digits
+const CORRELATION_ID_LENGTH: usize = 12;
+
 /// Logging middleware for bot interactions
 #[derive(Clone)]
 pub struct LoggingMiddleware {
@@ -26,6 +42,30 @@ impl LoggingMiddleware {
         }
     }
 
+    /// Layer handling the update in an `update` span with a new correlation ID
+    pub fn update_layer() -> UpdateHandler<HandlerError> {
+        teloxide::dptree::from_fn(|deps: DependencyMap, cont: Cont<'static, DependencyMap, HandlerResult>| async move {
+            let update: std::sync::Arc<Update> = deps.get();
+            let span = tracing::info_span!(
+                "update",
+                correlation_id = %new_correlation_id(),
+                update_id = update.id.0,
+                user_id = update.from().map(|user| user.id.0),
+                chat_id = update.chat().map(|chat| chat.id.0),
+            );
+
+            async move {
+                let flow = cont(deps).await;
+                if let ControlFlow::Break(Err(ref e)) = flow {
+                    error!(error = %e, "Failed to handle update");
+                }
+                flow
+            }
+            .instrument(span)
+            .await
+        })
+    }
+
     /// Log incoming update
     #[instrument(skip(self, update))]
     pub fn log_update(&self, update: &Update) {
@@ -254,6 +294,13 @@ impl LoggingMiddleware {
     }
 }
 
+/// A short random ID to tell the log lines of one update from the others
+pub fn new_correlation_id() -> String {
+    let mut id = uuid::Uuid::new_v4().simple().to_string();
+    id.truncate(CORRELATION_ID_LENGTH);
+    id
+}
+
 impl Default for LoggingMiddleware {
     fn default() -> Self {
         Self::new(true, true, true)
@@ -322,6 +369,14 @@ mod tests {
         assert!(middleware.log_errors);
     }
 
+    #[test]
+    fn test_new_correlation_id() {
+        let id = new_correlation_id();
+        assert_eq!(id.len(), CORRELATION_ID_LENGTH);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(id, new_correlation_id());
+    }
+
     #[test]
     fn test_performance_tracker() {
         let tracker = PerformanceTracker::new("test_operation".to_string());
@@ -0,0 +1,98 @@
+//! Metrics middleware
+//!
+//! Handler layers that wrap the dispatcher branches and record update
+//! counts, command latencies and Telegram API errors in the metrics registry.
+
+use std::error::Error;
+use std::ops::ControlFlow;
+use std::time::Instant;
+use teloxide::dispatching::UpdateHandler;
+use teloxide::dptree::di::{DependencyMap, DependencySupplier};
+use teloxide::dptree::Cont;
+use teloxide::types::Message;
+use teloxide::RequestError;
+use crate::utils::errors::SwingBuddyError;
+use crate::utils::metrics::metrics;
+
+type HandlerError = Box<dyn Error + Send + Sync + 'static>;
+type HandlerResult = Result<(), HandlerError>;
+
+/// Metrics middleware for the update dispatcher
+pub struct MetricsMiddleware;
+
+impl MetricsMiddleware {
+    /// Layer counting the updates handled by the wrapped branch as `handler`
+    pub fn layer(handler: &'static str) -> UpdateHandler<HandlerError> {
+        teloxide::dptree::from_fn(move |deps: DependencyMap, cont: Cont<'static, DependencyMap, HandlerResult>| async move {
+            let flow = cont(deps).await;
+            if let ControlFlow::Break(ref result) = flow {
+                metrics().update_handled(handler);
+                if let Err(e) = result {
+                    Self::record_handler_error(e.as_ref());
+                }
+            }
+            flow
+        })
+    }
+
+    /// Layer timing the commands handled by the wrapped branch
+    pub fn command_layer() -> UpdateHandler<HandlerError> {
+        teloxide::dptree::from_fn(|deps: DependencyMap, cont: Cont<'static, DependencyMap, HandlerResult>| async move {
+            let message: std::sync::Arc<Message> = deps.get();
+            let command = message.text().and_then(command_name).unwrap_or_else(|| "unknown".to_string());
+            let started = Instant::now();
+            let flow = cont(deps).await;
+            if let ControlFlow::Break(_) = flow {
+                metrics().observe_command(&command, started.elapsed());
+            }
+            flow
+        })
+    }
+
+    /// Count an error if it came from the Telegram API
+    pub fn record_error(error: &SwingBuddyError) {
+        if let SwingBuddyError::Telegram(e) | SwingBuddyError::RecipientUnavailable(e) = error {
+            metrics().telegram_api_error(request_error_kind(e));
+        }
+    }
+
+    fn record_handler_error(error: &(dyn Error + Send + Sync + 'static)) {
+        if let Some(e) = error.downcast_ref::<SwingBuddyError>() {
+            Self::record_error(e);
+        } else if let Some(e) = error.downcast_ref::<RequestError>() {
+            metrics().telegram_api_error(request_error_kind(e));
+        }
+    }
+}
+
+/// Command name without the slash and bot mention, e.g. `start` for `/start@bot now`
+pub fn command_name(text: &str) -> Option<String> {
+    let command = text.split_whitespace().next()?.strip_prefix('/')?;
+    let command = command.split('@').next().unwrap_or(command);
+    (!command.is_empty()).then(|| command.to_lowercase())
+}
+
+/// Short label for a Telegram API error
+pub fn request_error_kind(error: &RequestError) -> &'static str {
+    match error {
+        RequestError::Api(_) => "api",
+        RequestError::MigrateToChatId(_) => "migrate_to_chat_id",
+        RequestError::RetryAfter(_) => "retry_after",
+        RequestError::Network(_) => "network",
+        RequestError::InvalidJson { .. } => "invalid_json",
+        RequestError::Io(_) => "io",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_name() {
+        assert_eq!(command_name("/start"), Some("start".to_string()));
+        assert_eq!(command_name("/Events@SwingBuddyBot week"), Some("events".to_string()));
+        assert_eq!(command_name("hello"), None);
+        assert_eq!(command_name("/"), None);
+    }
+}
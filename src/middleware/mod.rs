@@ -4,8 +4,11 @@
 
 pub mod auth;
 pub mod logging;
+pub mod metrics;
 pub mod rate_limit;
 
 // Re-export commonly used middleware
 pub use auth::AuthMiddleware;
+pub use logging::LoggingMiddleware;
+pub use metrics::MetricsMiddleware;
 pub use rate_limit::RateLimitMiddleware;
\ No newline at end of file
@@ -2,13 +2,32 @@
 //! 
 //! This module provides rate limiting functionality to prevent abuse
 //! and ensure fair usage of the bot's resources.
+//!
+//! [`RateLimitMiddleware::layer`] enforces the `[rate_limits]` config section
+//! on commands and button presses. Those counters live in Redis, so the limits
+//! hold across bot instances; while Redis is unreachable they are counted in
+//! memory instead.
 
 use std::collections::HashMap;
+use std::error::Error;
+use std::ops::ControlFlow;
 use std::time::{Duration, Instant};
 use std::sync::{Arc, Mutex};
-use teloxide::types::User;
+use teloxide::dispatching::UpdateHandler;
+use teloxide::dptree::di::{DependencyMap, DependencySupplier};
+use teloxide::dptree::Cont;
+use teloxide::prelude::*;
+use teloxide::types::{Update, UpdateKind, User};
 use tracing::{debug, warn, info};
+use crate::config::settings::RateLimitsConfig;
+use crate::i18n::I18n;
+use crate::middleware::metrics::command_name;
+use crate::services::ServiceFactory;
 use crate::utils::errors::{SwingBuddyError, Result};
+use crate::utils::redis_connection::RedisConnector;
+
+type HandlerError = Box<dyn Error + Send + Sync + 'static>;
+type HandlerResult = std::result::Result<(), HandlerError>;
 
 /// Rate limit configuration
 #[derive(Debug, Clone)]
@@ -86,13 +105,40 @@ impl RateLimitEntry {
     }
 }
 
+/// Requests counted in the current fixed window of a key
+#[derive(Debug, Clone, Copy)]
+struct WindowCounter {
+    started: Instant,
+    window: Duration,
+    count: u32,
+}
+
+/// Whether a request is within its limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    Allowed,
+    Limited {
+        /// Time until the window ends
+        retry_after: Duration,
+        /// First request over the limit in this window; only that one gets a reply
+        notify: bool,
+    },
+}
+
 /// Rate limiting middleware
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct RateLimitMiddleware {
     config: RateLimitConfig,
     entries: Arc<Mutex<HashMap<i64, RateLimitEntry>>>,
+    /// Per-chat entries for flood control, keyed by (chat ID, user ID)
+    chat_entries: Arc<Mutex<HashMap<(i64, i64), RateLimitEntry>>>,
     admin_exempt: bool,
     admin_ids: Vec<i64>,
+    /// Shared counters for [`Self::hit`]; `None` counts in memory only
+    redis: Option<RedisConnector>,
+    redis_prefix: String,
+    /// Counters for [`Self::hit`] while Redis is unavailable
+    window_counters: Arc<Mutex<HashMap<String, WindowCounter>>>,
 }
 
 impl RateLimitMiddleware {
@@ -101,11 +147,146 @@ impl RateLimitMiddleware {
         Self {
             config,
             entries: Arc::new(Mutex::new(HashMap::new())),
+            chat_entries: Arc::new(Mutex::new(HashMap::new())),
             admin_exempt,
             admin_ids,
+            redis: None,
+            redis_prefix: String::new(),
+            window_counters: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Keep the counters of [`Self::hit`] in Redis under `prefix`
+    pub fn with_redis(mut self, redis: RedisConnector, prefix: &str) -> Self {
+        self.redis = Some(redis);
+        self.redis_prefix = prefix.to_string();
+        self
+    }
+
+    /// Layer enforcing the `[rate_limits]` section on the wrapped branch. Needs
+    /// an `Arc<RateLimitMiddleware>` in the dispatcher dependencies.
+    pub fn layer() -> UpdateHandler<HandlerError> {
+        teloxide::dptree::from_fn(|deps: DependencyMap, cont: Cont<'static, DependencyMap, HandlerResult>| async move {
+            let limiter: Arc<RateLimitMiddleware> = deps.get();
+            let services: Arc<ServiceFactory> = deps.get();
+            let update: Arc<Update> = deps.get();
+
+            let settings = services.config_service.current();
+            let (Some(limits), Some(user)) = (settings.rate_limits.as_ref(), update.from()) else {
+                return cont(deps).await;
+            };
+            let user_id = user.id.0 as i64;
+            if settings.bot.admin_ids.contains(&user_id) {
+                return cont(deps).await;
+            }
+
+            let command = match &update.kind {
+                UpdateKind::Message(message) => message.text().and_then(command_name),
+                _ => None,
+            };
+
+            match limiter.check_update(limits, user_id, command.as_deref()).await {
+                RateLimitDecision::Allowed => cont(deps).await,
+                RateLimitDecision::Limited { retry_after, notify } => {
+                    warn!(user_id = user_id, command = ?command, "Rate limit exceeded");
+                    let bot: Arc<Bot> = deps.get();
+                    if notify {
+                        let i18n: Arc<I18n> = deps.get();
+                        let result = Self::notify_limited(&bot, &services, &i18n, &update, user_id, retry_after).await;
+                        if let Err(e) = result {
+                            warn!(user_id = user_id, error = %e, "Failed to tell user to slow down");
+                        }
+                    } else if let UpdateKind::CallbackQuery(query) = &update.kind {
+                        // Stop the button's loading indicator without repeating the notice
+                        if let Err(e) = bot.answer_callback_query(query.id.clone()).await {
+                            warn!(user_id = user_id, error = %e, "Failed to answer limited callback query");
+                        }
+                    }
+                    ControlFlow::Break(Ok(()))
+                }
+            }
+        })
+    }
+
+    /// Count an update against the user's limit and the limit of its command
+    pub async fn check_update(&self, limits: &RateLimitsConfig, user_id: i64, command: Option<&str>) -> RateLimitDecision {
+        let window = Duration::from_secs(limits.window_seconds);
+        let decision = self.hit(&format!("user:{}", user_id), limits.max_requests, window).await;
+        if decision != RateLimitDecision::Allowed {
+            return decision;
+        }
+
+        match command.and_then(|command| Some((command, limits.commands.get(command)?))) {
+            Some((command, limit)) => {
+                let window = Duration::from_secs(limit.window_seconds);
+                self.hit(&format!("command:{}:{}", command, user_id), limit.max_requests, window).await
+            }
+            None => RateLimitDecision::Allowed,
         }
     }
 
+    /// Count a request for `key` in a fixed window of `window`
+    pub async fn hit(&self, key: &str, max_requests: u32, window: Duration) -> RateLimitDecision {
+        if let Some(redis) = &self.redis {
+            match self.hit_redis(redis, key, window).await {
+                Ok((count, ttl)) => return decide(count, max_requests, ttl),
+                Err(e) => warn!(error = %e, "Counting rate limits in memory while Redis is unavailable"),
+            }
+        }
+
+        let now = Instant::now();
+        let mut counters = self.window_counters.lock().unwrap_or_else(|e| e.into_inner());
+        counters.retain(|_, counter| now.duration_since(counter.started) < counter.window);
+        let counter = counters.entry(key.to_string()).or_insert(WindowCounter { started: now, window, count: 0 });
+        counter.count = counter.count.saturating_add(1);
+        let remaining = window.saturating_sub(now.duration_since(counter.started));
+        decide(counter.count, max_requests, remaining)
+    }
+
+    /// Increment the Redis counter, starting its window if there is none
+    async fn hit_redis(&self, redis: &RedisConnector, key: &str, window: Duration) -> Result<(u32, Duration)> {
+        let mut connection = redis.connection_manager().await?;
+        let full_key = format!("{}rate_limit:{}", self.redis_prefix, key);
+
+        let (count, ttl): (u32, i64) = redis::pipe()
+            .cmd("SET").arg(&full_key).arg(0).arg("EX").arg(window.as_secs().max(1)).arg("NX").ignore()
+            .incr(&full_key, 1)
+            .ttl(&full_key)
+            .query_async(&mut connection)
+            .await
+            .map_err(SwingBuddyError::Redis)?;
+
+        Ok((count, Duration::from_secs(ttl.max(0) as u64)))
+    }
+
+    /// Ask the user to slow down, in their language
+    async fn notify_limited(
+        bot: &Bot,
+        services: &ServiceFactory,
+        i18n: &I18n,
+        update: &Update,
+        user_id: i64,
+        retry_after: Duration,
+    ) -> Result<()> {
+        let language = services.user_service.get_user_by_telegram_id(user_id).await?
+            .map(|user| user.language_code)
+            .unwrap_or_else(|| "en".to_string());
+        let mut params = HashMap::new();
+        params.insert("seconds".to_string(), retry_after.as_secs().max(1).to_string());
+        let text = i18n.t("messages.errors.slow_down", &language, Some(&params));
+
+        match &update.kind {
+            UpdateKind::CallbackQuery(query) => {
+                bot.answer_callback_query(query.id.clone()).text(text).await?;
+            }
+            UpdateKind::Message(message) => {
+                bot.send_message(message.chat.id, text).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Check if user is rate limited
     pub fn check_rate_limit(&self, user: &User) -> Result<()> {
         let user_id = user.id.0 as i64;
@@ -133,6 +314,38 @@ impl RateLimitMiddleware {
         }
     }
 
+    /// Check if user is rate limited in a specific chat; every chat counts separately
+    pub fn check_chat_rate_limit(&self, chat_id: i64, user: &User) -> Result<()> {
+        let user_id = user.id.0 as i64;
+
+        if self.admin_exempt && self.admin_ids.contains(&user_id) {
+            debug!(user_id = user_id, "Admin user exempt from rate limiting");
+            return Ok(());
+        }
+
+        let mut entries = self.chat_entries.lock().unwrap();
+        let entry = entries.entry((chat_id, user_id)).or_insert_with(RateLimitEntry::new);
+
+        if entry.is_allowed(&self.config) {
+            entry.record_request();
+            Ok(())
+        } else {
+            warn!(
+                user_id = user_id,
+                chat_id = chat_id,
+                username = user.username.as_deref().unwrap_or("none"),
+                "Chat rate limit exceeded"
+            );
+            Err(SwingBuddyError::RateLimitExceeded)
+        }
+    }
+
+    /// Clear rate limit for a user in a specific chat
+    pub fn clear_chat_rate_limit(&self, chat_id: i64, user_id: i64) -> bool {
+        let mut entries = self.chat_entries.lock().unwrap();
+        entries.remove(&(chat_id, user_id)).is_some()
+    }
+
     /// Get current rate limit status for user
     pub fn get_rate_limit_status(&self, user_id: i64) -> RateLimitStatus {
         let entries = self.entries.lock().unwrap();
@@ -228,8 +441,13 @@ impl RateLimitMiddleware {
         entries.retain(|_, entry| {
             entry.requests.iter().any(|&time| time > cutoff)
         });
+
+        let mut chat_entries = self.chat_entries.lock().unwrap();
+        chat_entries.retain(|_, entry| {
+            entry.requests.iter().any(|&time| time > cutoff)
+        });
         
-        debug!(remaining_entries = entries.len(), "Cleaned up old rate limit entries");
+        debug!(remaining_entries = entries.len(), remaining_chat_entries = chat_entries.len(), "Cleaned up old rate limit entries");
     }
 
     /// Update configuration
@@ -239,6 +457,15 @@ impl RateLimitMiddleware {
     }
 }
 
+/// Decide on the `count`th request of a window with `remaining` time left
+fn decide(count: u32, max_requests: u32, remaining: Duration) -> RateLimitDecision {
+    if count <= max_requests {
+        RateLimitDecision::Allowed
+    } else {
+        RateLimitDecision::Limited { retry_after: remaining, notify: count == max_requests + 1 }
+    }
+}
+
 impl Default for RateLimitMiddleware {
     fn default() -> Self {
         Self::new(RateLimitConfig::default(), true, vec![])
@@ -330,6 +557,73 @@ mod tests {
         assert!(middleware.check_rate_limit(&regular_user).is_err());
     }
 
+    #[test]
+    fn test_chat_rate_limit() {
+        let config = RateLimitConfig {
+            max_requests: 2,
+            window_duration: Duration::from_secs(60),
+            burst_allowance: 0,
+        };
+
+        let middleware = RateLimitMiddleware::new(config, false, vec![]);
+        let user = create_test_user(123);
+
+        assert!(middleware.check_chat_rate_limit(-1, &user).is_ok());
+        assert!(middleware.check_chat_rate_limit(-1, &user).is_ok());
+        assert!(middleware.check_chat_rate_limit(-1, &user).is_err());
+
+        // Other chats and the global limit are counted separately
+        assert!(middleware.check_chat_rate_limit(-2, &user).is_ok());
+        assert!(middleware.check_rate_limit(&user).is_ok());
+
+        assert!(middleware.clear_chat_rate_limit(-1, 123));
+        assert!(middleware.check_chat_rate_limit(-1, &user).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_hit_counts_fixed_windows() {
+        let middleware = RateLimitMiddleware::default();
+        let window = Duration::from_secs(60);
+
+        assert_eq!(middleware.hit("user:1", 2, window).await, RateLimitDecision::Allowed);
+        assert_eq!(middleware.hit("user:1", 2, window).await, RateLimitDecision::Allowed);
+        assert!(matches!(middleware.hit("user:1", 2, window).await, RateLimitDecision::Limited { notify: true, .. }));
+        assert!(matches!(middleware.hit("user:1", 2, window).await, RateLimitDecision::Limited { notify: false, .. }));
+
+        // Other keys are counted separately
+        assert_eq!(middleware.hit("user:2", 2, window).await, RateLimitDecision::Allowed);
+
+        // A new window starts once the old one is over
+        let short = Duration::from_millis(20);
+        assert_eq!(middleware.hit("user:3", 1, short).await, RateLimitDecision::Allowed);
+        assert!(matches!(middleware.hit("user:3", 1, short).await, RateLimitDecision::Limited { .. }));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(middleware.hit("user:3", 1, short).await, RateLimitDecision::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_check_update_applies_command_limits() {
+        let middleware = RateLimitMiddleware::default();
+        let mut limits = RateLimitsConfig {
+            max_requests: 5,
+            window_seconds: 60,
+            commands: HashMap::new(),
+        };
+        limits.commands.insert("events".to_string(), crate::config::settings::CommandRateLimitConfig {
+            max_requests: 1,
+            window_seconds: 60,
+        });
+
+        assert_eq!(middleware.check_update(&limits, 1, Some("events")).await, RateLimitDecision::Allowed);
+        assert!(matches!(middleware.check_update(&limits, 1, Some("events")).await, RateLimitDecision::Limited { .. }));
+
+        // Other commands only count against the overall limit
+        assert_eq!(middleware.check_update(&limits, 1, Some("help")).await, RateLimitDecision::Allowed);
+        assert_eq!(middleware.check_update(&limits, 1, None).await, RateLimitDecision::Allowed);
+        assert_eq!(middleware.check_update(&limits, 1, None).await, RateLimitDecision::Allowed);
+        assert!(matches!(middleware.check_update(&limits, 1, None).await, RateLimitDecision::Limited { .. }));
+    }
+
     #[test]
     fn test_rate_limit_status() {
         let config = RateLimitConfig {
@@ -0,0 +1,130 @@
+//! Activity log model
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use crate::models::moderation::ModerationActionKind;
+
+/// Significant action recorded in the activity log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityAction {
+    /// A user finished the /start onboarding
+    UserOnboarded,
+    /// A bot admin banned a user from the bot
+    UserBanned,
+    /// A bot admin lifted a bot ban
+    UserUnbanned,
+    EventRegistered,
+    EventUnregistered,
+    /// A moderation action in a group
+    Moderation(ModerationActionKind),
+}
+
+impl ActivityAction {
+    /// Value stored in the database, `<category>.<action>`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UserOnboarded => "user.onboarded",
+            Self::UserBanned => "user.banned",
+            Self::UserUnbanned => "user.unbanned",
+            Self::EventRegistered => "event.registered",
+            Self::EventUnregistered => "event.unregistered",
+            Self::Moderation(ModerationActionKind::Warn) => "moderation.warn",
+            Self::Moderation(ModerationActionKind::Mute) => "moderation.mute",
+            Self::Moderation(ModerationActionKind::Unmute) => "moderation.unmute",
+            Self::Moderation(ModerationActionKind::Ban) => "moderation.ban",
+            Self::Moderation(ModerationActionKind::Unban) => "moderation.unban",
+            Self::Moderation(ModerationActionKind::Delete) => "moderation.delete",
+        }
+    }
+
+    /// What the action is done to
+    pub fn entity_type(&self) -> ActivityEntity {
+        match self {
+            Self::EventRegistered | Self::EventUnregistered => ActivityEntity::Event,
+            _ => ActivityEntity::User,
+        }
+    }
+}
+
+impl fmt::Display for ActivityAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Group of actions the admin panel filters the log by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityCategory {
+    User,
+    Event,
+    Moderation,
+}
+
+impl ActivityCategory {
+    pub const ALL: [Self; 3] = [Self::User, Self::Event, Self::Moderation];
+
+    /// Prefix of the action names in this category
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Event => "event",
+            Self::Moderation => "moderation",
+        }
+    }
+}
+
+impl FromStr for ActivityCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter()
+            .find(|category| category.as_str() == s)
+            .ok_or_else(|| format!("Unknown activity category: {}", s))
+    }
+}
+
+/// Kind of thing `entity_id` refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityEntity {
+    /// A user, by Telegram ID
+    User,
+    /// An event, by ID
+    Event,
+}
+
+impl ActivityEntity {
+    /// Value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Event => "event",
+        }
+    }
+}
+
+/// An activity log entry
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ActivityLogEntry {
+    pub id: i64,
+    pub action: String,
+    /// Telegram ID of whoever took the action; `None` for the bot itself
+    pub actor_id: Option<i64>,
+    /// Name and username of the actor, if they use the bot
+    pub actor_name: Option<String>,
+    pub actor_username: Option<String>,
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub metadata: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateActivityLogEntryRequest {
+    pub action: ActivityAction,
+    pub actor_id: Option<i64>,
+    pub entity_id: i64,
+    pub metadata: serde_json::Value,
+}
@@ -33,6 +33,29 @@ pub struct CasCheck {
     pub checked_at: DateTime<Utc>,
 }
 
+/// A user CAS listings are ignored for
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CasWhitelistEntry {
+    pub id: i64,
+    pub telegram_id: i64,
+    pub added_by: i64,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An admin panel action in the audit trail
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AdminAuditEntry {
+    pub id: i64,
+    pub admin_id: i64,
+    /// Name and username of the admin, if they use the bot themselves
+    pub admin_name: Option<String>,
+    pub admin_username: Option<String>,
+    pub action: String,
+    pub target: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateAdminSettingRequest {
     pub key: String,
@@ -69,4 +92,18 @@ pub struct CreateCasCheckRequest {
     pub telegram_id: i64,
     pub is_banned: bool,
     pub ban_reason: Option<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCasWhitelistEntryRequest {
+    pub telegram_id: i64,
+    pub added_by: i64,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAdminAuditEntryRequest {
+    pub admin_id: i64,
+    pub action: String,
+    pub target: Option<String>,
+}
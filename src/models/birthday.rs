@@ -0,0 +1,22 @@
+//! User birthday model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserBirthday {
+    pub user_id: i64,
+    pub birth_day: i16,
+    pub birth_month: i16,
+    pub announce_group_id: Option<i64>,
+    pub last_greeted_year: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetBirthdayRequest {
+    pub user_id: i64,
+    pub birth_day: i16,
+    pub birth_month: i16,
+}
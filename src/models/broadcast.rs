@@ -0,0 +1,101 @@
+//! Broadcast model
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// A message an admin sent to a segment of the bot's users
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Broadcast {
+    pub id: i64,
+    pub created_by: i64,
+    /// Language of the delivery report sent to the admin
+    pub language_code: String,
+    pub segment: String,
+    /// The composed message, copied to every recipient
+    pub source_chat_id: i64,
+    pub source_message_id: i32,
+    /// HTML text or caption with placeholders, rendered for every recipient
+    pub text: Option<String>,
+    pub total: i32,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateBroadcastRequest {
+    pub created_by: i64,
+    pub language_code: String,
+    pub segment: BroadcastSegment,
+    pub source_chat_id: i64,
+    pub source_message_id: i32,
+    pub text: Option<String>,
+}
+
+/// Delivery counts of a broadcast
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct BroadcastReport {
+    pub sent: i64,
+    pub failed: i64,
+    pub pending: i64,
+}
+
+/// Users a broadcast is sent to; banned users are never included
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BroadcastSegment {
+    All,
+    /// Users whose location matches, ignoring case
+    City(String),
+    Language(String),
+    /// Users registered for an event
+    EventParticipants(i64),
+}
+
+impl fmt::Display for BroadcastSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::City(city) => write!(f, "city:{}", city),
+            Self::Language(language) => write!(f, "language:{}", language),
+            Self::EventParticipants(event_id) => write!(f, "event:{}", event_id),
+        }
+    }
+}
+
+impl FromStr for BroadcastSegment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "all" => Ok(Self::All),
+            Some(("city", city)) if !city.is_empty() => Ok(Self::City(city.to_string())),
+            Some(("language", language)) if !language.is_empty() => Ok(Self::Language(language.to_string())),
+            Some(("event", event_id)) => event_id.parse()
+                .map(Self::EventParticipants)
+                .map_err(|_| format!("Invalid event ID: {}", event_id)),
+            _ => Err(format!("Unknown broadcast segment: {}", s)),
+        }
+    }
+}
+
+/// Delivery state of one broadcast recipient
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Pending,
+    Sent,
+    Failed,
+}
+
+impl DeliveryStatus {
+    /// Value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Event calendar model
+
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// A calendar users can pick in /events
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Calendar {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    /// Public Google Calendar linked to this calendar
+    pub google_calendar_id: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCalendarRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub google_calendar_id: Option<String>,
+    pub created_by: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCalendarRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub google_calendar_id: Option<String>,
+}
+
+/// Calendar field an admin can edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CalendarField {
+    Name,
+    Description,
+    GoogleCalendarId,
+}
+
+impl CalendarField {
+    /// Value used in callback data
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Description => "description",
+            Self::GoogleCalendarId => "google_id",
+        }
+    }
+}
+
+impl FromStr for CalendarField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(Self::Name),
+            "description" => Ok(Self::Description),
+            "google_id" => Ok(Self::GoogleCalendarId),
+            _ => Err(format!("Unknown calendar field: {}", s)),
+        }
+    }
+}
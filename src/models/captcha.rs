@@ -0,0 +1,35 @@
+//! Join captcha model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PendingCaptcha {
+    pub id: i64,
+    pub chat_telegram_id: i64,
+    pub user_telegram_id: i64,
+    pub message_id: Option<i32>,
+    pub answer: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePendingCaptchaRequest {
+    pub chat_telegram_id: i64,
+    pub user_telegram_id: i64,
+    pub answer: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// How new members prove they are human
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptchaMode {
+    /// Press an "I'm human" button
+    #[default]
+    Button,
+    /// Answer a simple swing question
+    Question,
+}
@@ -0,0 +1,21 @@
+//! Message cleanup model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScheduledDeletion {
+    pub id: i64,
+    pub chat_telegram_id: i64,
+    pub message_id: i32,
+    pub delete_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateScheduledDeletionRequest {
+    pub chat_telegram_id: i64,
+    pub message_id: i32,
+    pub delete_at: DateTime<Utc>,
+}
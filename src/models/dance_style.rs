@@ -0,0 +1,56 @@
+//! Dance style model
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// Swing dance styles users can pick for their profile and events can be tagged with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DanceStyle {
+    LindyHop,
+    Balboa,
+    Blues,
+    SoloJazz,
+}
+
+impl DanceStyle {
+    /// All supported styles, in display order
+    pub const ALL: [DanceStyle; 4] = [
+        DanceStyle::LindyHop,
+        DanceStyle::Balboa,
+        DanceStyle::Blues,
+        DanceStyle::SoloJazz,
+    ];
+
+    /// Database and callback identifier of the style
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DanceStyle::LindyHop => "lindy_hop",
+            DanceStyle::Balboa => "balboa",
+            DanceStyle::Blues => "blues",
+            DanceStyle::SoloJazz => "solo_jazz",
+        }
+    }
+}
+
+impl fmt::Display for DanceStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DanceStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase().replace(['-', ' '], "_");
+        match normalized.as_str() {
+            "lindy_hop" | "lindy" | "lindyhop" => Ok(DanceStyle::LindyHop),
+            "balboa" | "bal" => Ok(DanceStyle::Balboa),
+            "blues" => Ok(DanceStyle::Blues),
+            "solo_jazz" | "solo" | "solojazz" => Ok(DanceStyle::SoloJazz),
+            _ => Err(format!("Unknown dance style: {}", s)),
+        }
+    }
+}
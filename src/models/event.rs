@@ -29,6 +29,46 @@ pub struct EventParticipant {
     pub registered_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EventGroupReminder {
+    pub event_id: i64,
+    pub is_enabled: bool,
+    pub sent_at: Option<DateTime<Utc>>,
+    /// Posted reminder message, kept while it is being refreshed
+    pub message_id: Option<i32>,
+    /// Participant count the posted reminder was last rendered with
+    pub participant_count: Option<i32>,
+    pub is_pinned: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Google Sheet mirroring an event's participant list
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EventSheet {
+    pub event_id: i64,
+    pub spreadsheet_id: String,
+    /// Participants changed since the last export
+    pub needs_sync: bool,
+    pub sync_requested_at: DateTime<Utc>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    /// Why the last export failed; cleared by the next successful one
+    pub last_error: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A participant as written to the event's sheet
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ParticipantExportRow {
+    pub telegram_id: i64,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub location: Option<String>,
+    pub status: Option<String>,
+    pub registered_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateEventRequest {
     pub title: String,
@@ -40,7 +80,7 @@ pub struct CreateEventRequest {
     pub group_id: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateEventRequest {
     pub title: Option<String>,
     pub description: Option<String>,
@@ -0,0 +1,69 @@
+//! Google account model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// The community Google account the bot acts as, with its OAuth tokens
+#[derive(Clone, Serialize, Deserialize, FromRow)]
+pub struct GoogleAccount {
+    pub email: Option<String>,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: DateTime<Utc>,
+    /// Space-separated scopes the account granted
+    pub scope: String,
+    pub connected_by: i64,
+    pub connected_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Tokens are kept out of logs
+impl std::fmt::Debug for GoogleAccount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GoogleAccount")
+            .field("email", &self.email)
+            .field("expires_at", &self.expires_at)
+            .field("scope", &self.scope)
+            .field("connected_by", &self.connected_by)
+            .field("connected_at", &self.connected_at)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Tokens of a newly connected account; no `Debug` so they can't end up in logs
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConnectGoogleAccountRequest {
+    pub email: Option<String>,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: DateTime<Utc>,
+    pub scope: String,
+    pub connected_by: i64,
+}
+
+/// Push channel and sync state of a watched Google Calendar
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GoogleCalendarSync {
+    pub calendar_id: String,
+    pub channel_id: Option<String>,
+    pub resource_id: Option<String>,
+    /// Secret Google sends back with every notification
+    pub channel_token: Option<String>,
+    /// URL the channel posts to
+    pub address: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub sync_token: Option<String>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveWatchChannelRequest {
+    pub calendar_id: String,
+    pub channel_id: String,
+    pub resource_id: String,
+    pub channel_token: String,
+    pub address: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -1,8 +1,11 @@
 //! Group model
 
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Timelike, Utc};
 use sqlx::FromRow;
+use std::fmt;
+use std::str::FromStr;
+use super::captcha::CaptchaMode;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Group {
@@ -17,6 +20,288 @@ pub struct Group {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Group {
+    /// Whether day-of event reminders are posted in this group (enabled unless turned off)
+    pub fn event_reminders_enabled(&self) -> bool {
+        self.feature_enabled(GroupFeature::EventReminders)
+    }
+
+    /// Whether new members must pass a captcha (disabled unless turned on)
+    pub fn captcha_enabled(&self) -> bool {
+        self.feature_enabled(GroupFeature::Captcha)
+    }
+
+    /// Whether a per-group feature is switched on, falling back to the feature's default
+    pub fn feature_enabled(&self, feature: GroupFeature) -> bool {
+        self.settings
+            .get(feature.as_str())
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(feature.default_enabled())
+    }
+
+    /// Welcome message template for new members, if the group has one
+    pub fn welcome_message(&self) -> Option<&str> {
+        self.settings
+            .get("welcome_message")
+            .and_then(serde_json::Value::as_str)
+            .filter(|text| !text.trim().is_empty())
+    }
+
+    /// Group rules set by the admins, if any
+    pub fn rules(&self) -> Option<&str> {
+        self.settings
+            .get("rules")
+            .and_then(serde_json::Value::as_str)
+            .filter(|text| !text.trim().is_empty())
+    }
+
+    /// Whether the rules are posted for new members (disabled unless turned on)
+    pub fn rules_on_join(&self) -> bool {
+        self.settings
+            .get("rules_on_join")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Whether the group shares the bot-level ban list (disabled unless turned on)
+    pub fn shared_bans_enabled(&self) -> bool {
+        self.settings
+            .get("shared_bans")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Whether senders of messages removed by the spam filter get a warning
+    pub fn spam_filter_warns(&self) -> bool {
+        self.settings
+            .get("spam_filter_warn")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Whether join/leave messages and temporary bot replies are deleted (disabled unless turned on)
+    pub fn cleanup_enabled(&self) -> bool {
+        self.settings
+            .get("cleanup")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Cleanup delay chosen for this group, if it overrides the configured default
+    pub fn cleanup_delay_seconds(&self) -> Option<u64> {
+        self.settings
+            .get("cleanup_delay")
+            .and_then(serde_json::Value::as_u64)
+    }
+
+    /// Forum topic that receives the given kind of bot posts, if the group binds one
+    pub fn topic_id(&self, topic: GroupTopic) -> Option<i32> {
+        self.settings
+            .get(topic.as_str())
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id as i32)
+    }
+
+    /// Where new members get the intro card (not sent unless turned on)
+    pub fn intro_card_mode(&self) -> IntroCardMode {
+        self.settings
+            .get("intro_card")
+            .and_then(|mode| serde_json::from_value(mode.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Daily window during which announcements are held back, if the group set one
+    pub fn quiet_hours(&self) -> Option<QuietHours> {
+        self.settings
+            .get("quiet_hours")
+            .and_then(|hours| serde_json::from_value(hours.clone()).ok())
+    }
+
+    /// Whether the group's quiet hours are in effect at `now`
+    pub fn is_quiet_at(&self, now: DateTime<Utc>) -> bool {
+        self.quiet_hours().is_some_and(|hours| hours.contains(now.hour()))
+    }
+
+    /// Chat that receives member reports instead of the admins' private chats
+    pub fn report_chat_id(&self) -> Option<i64> {
+        self.settings
+            .get("report_chat_id")
+            .and_then(serde_json::Value::as_i64)
+    }
+
+    /// Captcha mode chosen for this group
+    pub fn captcha_mode(&self) -> CaptchaMode {
+        self.settings
+            .get("captcha_mode")
+            .and_then(|mode| serde_json::from_value(mode.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Features that can be switched on or off for each group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupFeature {
+    /// Ban members listed by the CAS anti-spam service
+    CasProtection,
+    /// Greet new members with the group's welcome message
+    WelcomeMessages,
+    /// Post day-of reminders for upcoming events
+    EventReminders,
+    /// Make new members pass a captcha
+    Captcha,
+    /// Post a weekly digest of upcoming events
+    WeeklyDigest,
+    /// Pin event reminders and keep their registration count up to date
+    PinnedAnnouncements,
+}
+
+impl GroupFeature {
+    /// All features in the order they are shown to admins
+    pub const ALL: [GroupFeature; 6] = [
+        Self::CasProtection,
+        Self::WelcomeMessages,
+        Self::EventReminders,
+        Self::Captcha,
+        Self::WeeklyDigest,
+        Self::PinnedAnnouncements,
+    ];
+
+    /// Key of the switch in the group settings
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CasProtection => "cas_protection",
+            Self::WelcomeMessages => "welcome_messages",
+            Self::EventReminders => "event_reminders",
+            Self::Captcha => "captcha",
+            Self::WeeklyDigest => "weekly_digest",
+            Self::PinnedAnnouncements => "pinned_announcements",
+        }
+    }
+
+    /// Whether the feature is on for groups that never changed it
+    pub fn default_enabled(&self) -> bool {
+        !matches!(self, Self::Captcha | Self::WeeklyDigest | Self::PinnedAnnouncements)
+    }
+}
+
+impl FromStr for GroupFeature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter()
+            .find(|feature| feature.as_str() == s)
+            .ok_or_else(|| format!("Unknown group feature: {}", s))
+    }
+}
+
+/// Kinds of bot posts that forum groups can bind to a topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupTopic {
+    /// Event announcements and reminders
+    Events,
+    /// Automatic moderation notices, such as flood and spam removals
+    Moderation,
+}
+
+impl GroupTopic {
+    /// Key of the topic in the group settings
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Events => "events_topic_id",
+            Self::Moderation => "moderation_topic_id",
+        }
+    }
+}
+
+impl FromStr for GroupTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "events" => Ok(Self::Events),
+            "moderation" => Ok(Self::Moderation),
+            other => Err(format!("Unknown group topic: {}", other)),
+        }
+    }
+}
+
+/// Where the intro card for new members is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntroCardMode {
+    /// No intro card
+    #[default]
+    Off,
+    /// Posted in the group
+    Group,
+    /// Sent by DM, or posted in the group if the member never started the bot
+    Private,
+}
+
+impl FromStr for IntroCardMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Self::Off),
+            "group" => Ok(Self::Group),
+            "dm" | "private" => Ok(Self::Private),
+            other => Err(format!("Unknown intro card mode: {}", other)),
+        }
+    }
+}
+
+/// Daily window in UTC hours during which the bot queues group announcements.
+/// The window may wrap around midnight, e.g. 22 to 8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl QuietHours {
+    /// Whether the given UTC hour falls inside the window
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:00–{:02}:00 UTC", self.start_hour, self.end_hour)
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = String;
+
+    /// Parse a window written as `start-end` in whole hours, e.g. `22-8`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s.trim().split_once('-')
+            .ok_or_else(|| format!("Invalid quiet hours: {}", s))?;
+        let parse_hour = |value: &str| value.trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|hour| *hour < 24)
+            .ok_or_else(|| format!("Invalid hour: {}", value));
+
+        let hours = Self {
+            start_hour: parse_hour(start)?,
+            end_hour: parse_hour(end)?,
+        };
+        if hours.start_hour == hours.end_hour {
+            return Err("Quiet hours must not be empty".to_string());
+        }
+        Ok(hours)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct GroupMember {
     pub id: i64,
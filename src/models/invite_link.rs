@@ -0,0 +1,63 @@
+//! Invite link model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// A join-request invite link the bot created for a group
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InviteLink {
+    pub id: i64,
+    pub group_id: i64,
+    /// Label shown to admins, e.g. the promo channel the link is posted in
+    pub name: String,
+    pub invite_link: String,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub rotated_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl InviteLink {
+    /// Whether the link still lets people ask to join
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateInviteLinkRequest {
+    pub group_id: i64,
+    pub name: String,
+    pub invite_link: String,
+    pub created_by: i64,
+}
+
+/// Join requests sent through an invite link
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InviteLinkStats {
+    pub id: i64,
+    pub name: String,
+    pub invite_link: String,
+    pub requests: i64,
+    pub approved: i64,
+    pub declined: i64,
+}
+
+/// Outcome of a join request sent through an invite link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JoinRequestStatus {
+    Approved,
+    Declined,
+}
+
+impl JoinRequestStatus {
+    /// Value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Approved => "approved",
+            Self::Declined => "declined",
+        }
+    }
+}
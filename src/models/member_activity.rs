@@ -0,0 +1,13 @@
+//! Member activity model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MemberActivity {
+    pub chat_telegram_id: i64,
+    pub user_telegram_id: i64,
+    pub last_seen_at: DateTime<Utc>,
+    pub cas_checked_at: Option<DateTime<Utc>>,
+}
@@ -6,9 +6,51 @@ pub mod user;
 pub mod group;
 pub mod event;
 pub mod admin;
+pub mod note;
+pub mod referral;
+pub mod birthday;
+pub mod dance_style;
+pub mod captcha;
+pub mod moderation;
+pub mod cleanup;
+pub mod quiet_hours;
+pub mod stats;
+pub mod member_activity;
+pub mod invite_link;
+pub mod role;
+pub mod broadcast;
+pub mod scheduled_message;
+pub mod calendar;
+pub mod notification;
+pub mod google;
+pub mod activity;
+pub mod trash;
+pub mod song_request;
+pub mod venue;
 
 // Re-export commonly used models
-pub use user::{User, CreateUserRequest, UpdateUserRequest};
-pub use group::{Group, GroupMember, CreateGroupRequest, UpdateGroupRequest, AddMemberRequest};
-pub use event::{Event, EventParticipant, CreateEventRequest, UpdateEventRequest, RegisterParticipantRequest, ParticipantStatus};
-pub use admin::{AdminSettings, UserState, CasCheck, CreateAdminSettingRequest, UpdateAdminSettingRequest, CreateUserStateRequest, UpdateUserStateRequest, CreateCasCheckRequest};
\ No newline at end of file
+pub use user::{User, CreateUserRequest, UpdateUserRequest, UserListEntry, UserListSort, ProfileField};
+pub use group::{Group, GroupFeature, GroupTopic, IntroCardMode, QuietHours, GroupMember, CreateGroupRequest, UpdateGroupRequest, AddMemberRequest};
+pub use event::{Event, EventParticipant, EventGroupReminder, EventSheet, ParticipantExportRow, CreateEventRequest, UpdateEventRequest, RegisterParticipantRequest, ParticipantStatus};
+pub use admin::{AdminSettings, UserState, CasCheck, CreateAdminSettingRequest, UpdateAdminSettingRequest, CreateUserStateRequest, UpdateUserStateRequest, CreateCasCheckRequest, CasWhitelistEntry, CreateCasWhitelistEntryRequest, AdminAuditEntry, CreateAdminAuditEntryRequest};
+pub use note::{UserNote, UserNoteRevision, CreateUserNoteRequest, UpdateUserNoteRequest};
+pub use referral::{Referral, TopReferrer, CreateReferralRequest};
+pub use birthday::{UserBirthday, SetBirthdayRequest};
+pub use dance_style::DanceStyle;
+pub use captcha::{PendingCaptcha, CreatePendingCaptchaRequest, CaptchaMode};
+pub use moderation::{Warning, CreateWarningRequest, ModerationAction, CreateModerationActionRequest, ModerationActionKind, Report, CreateReportRequest, SharedBan, CreateSharedBanRequest, ScheduledUnmute, SpamFilter, CreateSpamFilterRequest, SpamFilterKind, BanListEntry};
+pub use cleanup::{ScheduledDeletion, CreateScheduledDeletionRequest};
+pub use quiet_hours::{QueuedPost, CreateQueuedPostRequest};
+pub use stats::{GroupCounter, GroupStats, GrowthInterval, GrowthPoint};
+pub use member_activity::MemberActivity;
+pub use invite_link::{InviteLink, CreateInviteLinkRequest, InviteLinkStats, JoinRequestStatus};
+pub use role::{GroupRole, SetGroupRoleRequest, Role};
+pub use broadcast::{Broadcast, CreateBroadcastRequest, BroadcastReport, BroadcastSegment, DeliveryStatus};
+pub use scheduled_message::{ScheduledMessage, CreateScheduledMessageRequest, ScheduledTarget, ScheduledMessageStatus};
+pub use calendar::{Calendar, CreateCalendarRequest, UpdateCalendarRequest, CalendarField};
+pub use notification::{QueuedNotification, CreateQueuedNotificationRequest, NotificationQueueStats, NotificationTemplateOverride};
+pub use google::{GoogleAccount, ConnectGoogleAccountRequest, GoogleCalendarSync, SaveWatchChannelRequest};
+pub use activity::{ActivityLogEntry, CreateActivityLogEntryRequest, ActivityAction, ActivityCategory, ActivityEntity};
+pub use trash::{TrashEntry, TrashKind};
+pub use song_request::{SongRequest, CreateSongRequestRequest, SongRequestStatus};
+pub use venue::{Venue, CreateVenueRequest};
\ No newline at end of file
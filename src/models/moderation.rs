@@ -0,0 +1,228 @@
+//! Moderation model
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Warning {
+    pub id: i64,
+    pub chat_telegram_id: i64,
+    pub user_telegram_id: i64,
+    pub issued_by: i64,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWarningRequest {
+    pub chat_telegram_id: i64,
+    pub user_telegram_id: i64,
+    pub issued_by: i64,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ModerationAction {
+    pub id: i64,
+    pub chat_telegram_id: i64,
+    pub target_telegram_id: i64,
+    /// Admin who took the action; `None` for automatic escalations
+    pub actor_telegram_id: Option<i64>,
+    pub action: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateModerationActionRequest {
+    pub chat_telegram_id: i64,
+    pub target_telegram_id: i64,
+    pub actor_telegram_id: Option<i64>,
+    pub action: ModerationActionKind,
+    pub reason: Option<String>,
+}
+
+/// A message reported by a group member
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Report {
+    pub id: i64,
+    pub chat_telegram_id: i64,
+    pub message_id: i32,
+    pub reporter_telegram_id: i64,
+    /// Author of the reported message; `None` for anonymous senders
+    pub reported_telegram_id: Option<i64>,
+    pub reason: Option<String>,
+    pub status: String,
+    pub resolved_by: Option<i64>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Report {
+    /// Whether an admin has already handled the report
+    pub fn is_resolved(&self) -> bool {
+        self.status == "resolved"
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateReportRequest {
+    pub chat_telegram_id: i64,
+    pub message_id: i32,
+    pub reporter_telegram_id: i64,
+    pub reported_telegram_id: Option<i64>,
+    pub reason: Option<String>,
+}
+
+/// A member banned in every group that shares its ban list
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SharedBan {
+    pub id: i64,
+    pub user_telegram_id: i64,
+    pub banned_by: i64,
+    /// Group where the member was banned first
+    pub origin_chat_telegram_id: i64,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSharedBanRequest {
+    pub user_telegram_id: i64,
+    pub banned_by: i64,
+    pub origin_chat_telegram_id: i64,
+    pub reason: Option<String>,
+}
+
+/// A muted member to unmute once the mute runs out
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScheduledUnmute {
+    pub chat_telegram_id: i64,
+    pub user_telegram_id: i64,
+    pub unmute_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A pattern that marks group messages as spam
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SpamFilter {
+    pub id: i64,
+    pub chat_telegram_id: i64,
+    pub kind: String,
+    pub pattern: String,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SpamFilter {
+    /// Kind of the filter; unknown kinds are treated as keywords
+    pub fn filter_kind(&self) -> SpamFilterKind {
+        self.kind.parse().unwrap_or(SpamFilterKind::Keyword)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSpamFilterRequest {
+    pub chat_telegram_id: i64,
+    pub kind: SpamFilterKind,
+    pub pattern: String,
+    pub created_by: i64,
+}
+
+/// How a spam filter pattern is matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpamFilterKind {
+    /// Case-insensitive text fragment
+    Keyword,
+    /// Regular expression
+    Regex,
+    /// Domain of a link, including its subdomains
+    Domain,
+}
+
+impl SpamFilterKind {
+    /// Value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Regex => "regex",
+            Self::Domain => "domain",
+        }
+    }
+}
+
+impl FromStr for SpamFilterKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keyword" => Ok(Self::Keyword),
+            "regex" => Ok(Self::Regex),
+            "domain" => Ok(Self::Domain),
+            other => Err(format!("Unknown spam filter kind: {}", other)),
+        }
+    }
+}
+
+/// Reason logged for bans triggered by a CAS listing
+pub const CAS_BAN_REASON: &str = "CAS listing";
+
+/// A current ban shown in the admin ban list: the latest ban of a member in a
+/// group from the moderation log, or a bot-level ban of a registered user
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BanListEntry {
+    pub user_telegram_id: i64,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    /// Group the member is banned in; `None` for bot-level bans
+    pub chat_telegram_id: Option<i64>,
+    pub chat_title: Option<String>,
+    /// Admin who issued the ban; `None` for automatic bans
+    pub actor_telegram_id: Option<i64>,
+    pub reason: Option<String>,
+    pub banned_at: DateTime<Utc>,
+}
+
+impl BanListEntry {
+    /// Whether the ban was triggered by a CAS listing
+    pub fn is_cas(&self) -> bool {
+        self.actor_telegram_id.is_none()
+            && self.reason.as_deref().is_some_and(|reason| reason.starts_with(CAS_BAN_REASON))
+    }
+}
+
+/// Kind of moderation action recorded in the audit trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationActionKind {
+    Warn,
+    Mute,
+    Unmute,
+    Ban,
+    Unban,
+    Delete,
+}
+
+impl ModerationActionKind {
+    /// Value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Warn => "warn",
+            Self::Mute => "mute",
+            Self::Unmute => "unmute",
+            Self::Ban => "ban",
+            Self::Unban => "unban",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+impl fmt::Display for ModerationActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
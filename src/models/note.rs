@@ -0,0 +1,37 @@
+//! User note model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserNote {
+    pub id: i64,
+    pub user_id: i64,
+    pub author_id: i64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserNoteRevision {
+    pub id: i64,
+    pub note_id: i64,
+    pub content: String,
+    pub edited_by: i64,
+    pub edited_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateUserNoteRequest {
+    pub user_id: i64,
+    pub author_id: i64,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateUserNoteRequest {
+    pub content: String,
+    pub edited_by: i64,
+}
@@ -0,0 +1,52 @@
+//! Notification queue model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// A notification waiting in the queue, or already sent or failed
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QueuedNotification {
+    pub id: i64,
+    pub chat_id: i64,
+    pub thread_id: Option<i32>,
+    pub text: String,
+    /// "HTML" or "MarkdownV2"; plain text if `None`
+    pub parse_mode: Option<String>,
+    pub disable_link_preview: bool,
+    pub status: String,
+    /// Failed send attempts so far
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateQueuedNotificationRequest {
+    pub chat_id: i64,
+    pub thread_id: Option<i32>,
+    pub text: String,
+    pub parse_mode: Option<String>,
+    pub disable_link_preview: bool,
+}
+
+/// Notification counts by status
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct NotificationQueueStats {
+    pub pending: i64,
+    pub sent: i64,
+    pub failed: i64,
+    /// Users left out of bulk sends because the bot can't message them
+    pub blocked_users: i64,
+}
+
+/// Admin-edited text of a notification template in one language
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NotificationTemplateOverride {
+    pub template_key: String,
+    pub language_code: String,
+    pub content: String,
+    pub updated_by: Option<i64>,
+    pub updated_at: DateTime<Utc>,
+}
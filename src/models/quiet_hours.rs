@@ -0,0 +1,21 @@
+//! Quiet hours model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QueuedPost {
+    pub id: i64,
+    pub group_id: i64,
+    pub thread_id: Option<i32>,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateQueuedPostRequest {
+    pub group_id: i64,
+    pub thread_id: Option<i32>,
+    pub text: String,
+}
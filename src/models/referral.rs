@@ -0,0 +1,29 @@
+//! Referral model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Referral {
+    pub id: i64,
+    pub referrer_id: i64,
+    pub referred_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Inviter with the number of users they brought in
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TopReferrer {
+    pub user_id: i64,
+    pub telegram_id: i64,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub referral_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateReferralRequest {
+    pub referrer_id: i64,
+    pub referred_id: i64,
+}
@@ -0,0 +1,86 @@
+//! Group role model
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// A member's bot role in a group
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GroupRole {
+    pub chat_telegram_id: i64,
+    pub user_telegram_id: i64,
+    pub role: String,
+    pub granted_by: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl GroupRole {
+    /// Role of the member; `None` for values this version does not know
+    pub fn role_kind(&self) -> Option<Role> {
+        self.role.parse().ok()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetGroupRoleRequest {
+    pub chat_telegram_id: i64,
+    pub user_telegram_id: i64,
+    pub role: Role,
+    pub granted_by: i64,
+}
+
+/// Roles group admins can delegate, from most to least powerful
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Everything an admin can do, plus granting the admin and owner roles
+    Owner,
+    /// Group settings, bans and events
+    Admin,
+    /// Warnings, mutes and reports
+    Moderator,
+    /// Events only
+    Organizer,
+}
+
+impl Role {
+    /// All roles, from most to least powerful
+    pub const ALL: [Role; 4] = [Self::Owner, Self::Admin, Self::Moderator, Self::Organizer];
+
+    /// Value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Admin => "admin",
+            Self::Moderator => "moderator",
+            Self::Organizer => "organizer",
+        }
+    }
+
+    /// Whether only group owners may grant or take away this role
+    pub fn is_privileged(&self) -> bool {
+        matches!(self, Self::Owner | Self::Admin)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(Self::Owner),
+            "admin" => Ok(Self::Admin),
+            "moderator" | "mod" => Ok(Self::Moderator),
+            "organizer" | "organiser" => Ok(Self::Organizer),
+            other => Err(format!("Unknown role: {}", other)),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Scheduled message model
+
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use crate::models::broadcast::BroadcastSegment;
+
+/// A broadcast or group announcement an admin scheduled for later
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScheduledMessage {
+    pub id: i64,
+    pub created_by: i64,
+    /// Language of the notices sent to the admin
+    pub language_code: String,
+    pub target: String,
+    /// The composed message, copied when it is due
+    pub source_chat_id: i64,
+    pub source_message_id: i32,
+    /// Personalized text of a broadcast, see `Broadcast::text`
+    pub text: Option<String>,
+    pub send_at: DateTime<Utc>,
+    pub status: String,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateScheduledMessageRequest {
+    pub created_by: i64,
+    pub language_code: String,
+    pub target: ScheduledTarget,
+    pub source_chat_id: i64,
+    pub source_message_id: i32,
+    pub text: Option<String>,
+    pub send_at: DateTime<Utc>,
+}
+
+/// Where a scheduled message goes when it is due
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduledTarget {
+    /// Queued as a broadcast to a segment of the users
+    Broadcast(BroadcastSegment),
+    /// Posted in a group, by its Telegram chat ID
+    Group(i64),
+}
+
+impl fmt::Display for ScheduledTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Broadcast(segment) => write!(f, "broadcast:{}", segment),
+            Self::Group(chat_id) => write!(f, "group:{}", chat_id),
+        }
+    }
+}
+
+impl FromStr for ScheduledTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("broadcast", segment)) => segment.parse().map(Self::Broadcast),
+            Some(("group", chat_id)) => chat_id.parse()
+                .map(Self::Group)
+                .map_err(|_| format!("Invalid group chat ID: {}", chat_id)),
+            _ => Err(format!("Unknown scheduled message target: {}", s)),
+        }
+    }
+}
+
+/// State of a scheduled message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduledMessageStatus {
+    Pending,
+    Sent,
+    Failed,
+    Cancelled,
+}
+
+impl ScheduledMessageStatus {
+    /// Value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
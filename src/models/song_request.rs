@@ -0,0 +1,54 @@
+//! Song request model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// A track an attendee asked the DJ to play during an event
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SongRequest {
+    pub id: i64,
+    pub event_id: i64,
+    /// Telegram ID of the attendee who asked for the track
+    pub requested_by: i64,
+    pub track: String,
+    pub status: String,
+    /// Telegram ID of the DJ who played or skipped the track
+    pub handled_by: Option<i64>,
+    pub handled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SongRequest {
+    /// Whether the track is still waiting in the queue
+    pub fn is_pending(&self) -> bool {
+        self.status == SongRequestStatus::Pending.as_str()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSongRequestRequest {
+    pub event_id: i64,
+    pub requested_by: i64,
+    pub track: String,
+}
+
+/// Where a song request is in the DJ's queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SongRequestStatus {
+    Pending,
+    Played,
+    Skipped,
+}
+
+impl SongRequestStatus {
+    /// Value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Played => "played",
+            Self::Skipped => "skipped",
+        }
+    }
+}
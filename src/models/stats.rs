@@ -0,0 +1,78 @@
+//! Group statistics model
+
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+use sqlx::FromRow;
+
+/// Group activity tracked in the daily counters table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupCounter {
+    /// Members who joined the group
+    NewMembers,
+    /// Messages the bot handled in the group
+    Messages,
+    /// Members banned because of a CAS listing
+    CasBans,
+}
+
+impl GroupCounter {
+    /// Counter name stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NewMembers => "new_members",
+            Self::Messages => "messages",
+            Self::CasBans => "cas_bans",
+        }
+    }
+}
+
+/// Activity of a group over a period
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct GroupStats {
+    pub new_members: i64,
+    pub messages: i64,
+    pub cas_bans: i64,
+    pub event_registrations: i64,
+}
+
+/// Length of the periods growth statistics are grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrowthInterval {
+    Day,
+    Week,
+}
+
+impl GrowthInterval {
+    /// Field name understood by Postgres `date_trunc`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+        }
+    }
+}
+
+impl std::str::FromStr for GrowthInterval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Self::Day),
+            "week" => Ok(Self::Week),
+            other => Err(format!("Unknown growth interval: {}", other)),
+        }
+    }
+}
+
+/// Bot-wide activity in one day or week
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct GrowthPoint {
+    /// First day of the period
+    pub period: NaiveDate,
+    pub new_users: i64,
+    pub events_created: i64,
+    pub registrations: i64,
+    pub cas_bans: i64,
+}
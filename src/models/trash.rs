@@ -0,0 +1,53 @@
+//! Trash model: soft-deleted users, groups and events
+
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// Entries shown per page of the admin trash
+pub const TRASH_PAGE_SIZE: i64 = 10;
+
+/// What the admin trash lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrashKind {
+    Users,
+    Groups,
+    Events,
+}
+
+impl TrashKind {
+    pub const ALL: [TrashKind; 3] = [TrashKind::Users, TrashKind::Groups, TrashKind::Events];
+
+    /// Value used in callback data and translation keys
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Users => "users",
+            Self::Groups => "groups",
+            Self::Events => "events",
+        }
+    }
+}
+
+impl FromStr for TrashKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "users" => Ok(Self::Users),
+            "groups" => Ok(Self::Groups),
+            "events" => Ok(Self::Events),
+            other => Err(format!("Unknown trash kind: {}", other)),
+        }
+    }
+}
+
+/// A soft-deleted user, group or event
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TrashEntry {
+    /// Database ID, used to restore it
+    pub id: i64,
+    /// Name of the user, title of the group or title and date of the event
+    pub label: String,
+    pub deleted_at: DateTime<Utc>,
+}
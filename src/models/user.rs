@@ -1,5 +1,6 @@
 //! User model
 
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use sqlx::FromRow;
@@ -36,4 +37,150 @@ pub struct UpdateUserRequest {
     pub language_code: Option<String>,
     pub location: Option<String>,
     pub is_banned: Option<bool>,
+}
+
+/// A user as shown in the admin user list
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserListEntry {
+    pub telegram_id: i64,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub is_banned: bool,
+    pub created_at: DateTime<Utc>,
+    /// Last message, command or button press; `None` for users not seen since this was tracked
+    pub last_active_at: Option<DateTime<Utc>>,
+}
+
+/// Direct messages a user may opt out of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct NotificationPreferences {
+    pub broadcasts_enabled: bool,
+    pub reminders_enabled: bool,
+}
+
+impl NotificationPreferences {
+    /// Every optional notification on, or every one off
+    pub fn all(enabled: bool) -> Self {
+        Self {
+            broadcasts_enabled: enabled,
+            reminders_enabled: enabled,
+        }
+    }
+}
+
+/// Order of the admin user list, newest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UserListSort {
+    Registered,
+    LastActive,
+}
+
+impl UserListSort {
+    /// Value used in callback data
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Registered => "registered",
+            Self::LastActive => "active",
+        }
+    }
+}
+
+/// Outcome of a bulk user import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserImportReport {
+    pub created: usize,
+    pub updated: usize,
+    /// Line numbers of rows that could not be imported
+    pub skipped: Vec<usize>,
+}
+
+/// Columns of the CSV user export; deployments may leave some out for privacy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserExportColumn {
+    TelegramId,
+    Name,
+    City,
+    Language,
+    RegisteredAt,
+    Banned,
+}
+
+impl UserExportColumn {
+    /// All columns in export order
+    pub const ALL: [UserExportColumn; 6] = [
+        Self::TelegramId,
+        Self::Name,
+        Self::City,
+        Self::Language,
+        Self::RegisteredAt,
+        Self::Banned,
+    ];
+
+    /// Column name in the configuration and the CSV header
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TelegramId => "telegram_id",
+            Self::Name => "name",
+            Self::City => "city",
+            Self::Language => "language",
+            Self::RegisteredAt => "registered_at",
+            Self::Banned => "banned",
+        }
+    }
+}
+
+impl FromStr for UserExportColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter()
+            .find(|column| column.as_str() == s)
+            .ok_or_else(|| format!("Unknown user export column: {}", s))
+    }
+}
+
+impl FromStr for UserListSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "registered" => Ok(Self::Registered),
+            "active" => Ok(Self::LastActive),
+            other => Err(format!("Unknown user list order: {}", other)),
+        }
+    }
+}
+
+/// Profile fields an admin can correct from the user detail view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProfileField {
+    Name,
+    City,
+    Language,
+}
+
+impl ProfileField {
+    /// Value used in callback data
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::City => "city",
+            Self::Language => "language",
+        }
+    }
+}
+
+impl FromStr for ProfileField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(Self::Name),
+            "city" => Ok(Self::City),
+            "language" => Ok(Self::Language),
+            other => Err(format!("Unknown profile field: {}", other)),
+        }
+    }
 }
\ No newline at end of file
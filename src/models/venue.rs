@@ -0,0 +1,28 @@
+//! Venue model
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// A place people dance at, listed with /venues
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Venue {
+    pub id: i64,
+    pub city: String,
+    /// Name events mention in their location
+    pub name: String,
+    pub address: String,
+    /// Kind of dance floor, e.g. wood or parquet
+    pub floor_type: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateVenueRequest {
+    pub city: String,
+    pub name: String,
+    pub address: String,
+    pub floor_type: Option<String>,
+    pub created_by: Option<i64>,
+}
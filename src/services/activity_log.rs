@@ -0,0 +1,59 @@
+//! Activity log service implementation
+//!
+//! Keeps a structured record of the significant things that happen in the bot:
+//! users finishing onboarding, event registrations and moderation actions. Each
+//! entry has an actor, the user or event it was done to and free-form metadata,
+//! and admins browse the log page by page from the admin panel.
+
+use tracing::warn;
+use crate::database::repositories::ActivityRepository;
+use crate::models::activity::{ActivityLogEntry, CreateActivityLogEntryRequest, ActivityAction, ActivityCategory};
+use crate::services::user::page_count;
+use crate::utils::errors::Result;
+
+/// Activity log entries shown per page in the admin panel
+pub const ACTIVITY_PAGE_SIZE: i64 = 15;
+
+/// Activity log service
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ActivityLogService {
+    activity_repository: ActivityRepository,
+}
+
+impl ActivityLogService {
+    /// Create a new ActivityLogService instance
+    pub fn new(activity_repository: ActivityRepository) -> Self {
+        Self { activity_repository }
+    }
+
+    /// Record an action; failures are only logged so the action itself goes through.
+    /// `entity_id` is a Telegram user ID or an event ID, depending on the action.
+    pub async fn record(&self, action: ActivityAction, actor_id: Option<i64>, entity_id: i64, metadata: serde_json::Value) {
+        let request = CreateActivityLogEntryRequest {
+            action,
+            actor_id,
+            entity_id,
+            metadata,
+        };
+
+        if let Err(e) = self.activity_repository.log(request).await {
+            warn!(action = %action, actor_id = ?actor_id, entity_id = entity_id, error = %e, "Failed to record activity");
+        }
+    }
+
+    /// Get a page of the log, newest first, and the number of pages; pages start at 0
+    pub async fn list_page(&self, category: Option<ActivityCategory>, page: i64) -> Result<(Vec<ActivityLogEntry>, i64)> {
+        let total = self.activity_repository.count(category).await?;
+        let pages = page_count(total, ACTIVITY_PAGE_SIZE);
+        let page = page.clamp(0, pages - 1);
+        let entries = self.activity_repository.list(category, ACTIVITY_PAGE_SIZE, page * ACTIVITY_PAGE_SIZE).await?;
+
+        Ok((entries, pages))
+    }
+
+    /// Latest actions done by or to a user
+    pub async fn for_user(&self, telegram_id: i64, limit: i64) -> Result<Vec<ActivityLogEntry>> {
+        self.activity_repository.list_for_user(telegram_id, limit).await
+    }
+}
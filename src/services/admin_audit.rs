@@ -0,0 +1,50 @@
+//! Admin audit service implementation
+//!
+//! Every action taken in the admin panel is recorded with the admin who took it
+//! and its target, so several admins can follow what the others changed.
+
+use tracing::warn;
+use crate::database::repositories::AdminRepository;
+use crate::models::admin::{AdminAuditEntry, CreateAdminAuditEntryRequest};
+use crate::services::user::page_count;
+use crate::utils::errors::Result;
+
+/// Audit trail entries shown per page in the admin panel
+pub const AUDIT_PAGE_SIZE: i64 = 15;
+
+/// Admin audit service for the admin panel action trail
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct AdminAuditService {
+    admin_repository: AdminRepository,
+}
+
+impl AdminAuditService {
+    /// Create a new AdminAuditService instance
+    pub fn new(admin_repository: AdminRepository) -> Self {
+        Self { admin_repository }
+    }
+
+    /// Record an admin panel action; failures are only logged so the action itself goes through
+    pub async fn record(&self, admin_id: i64, action: &str, target: Option<&str>) {
+        let request = CreateAdminAuditEntryRequest {
+            admin_id,
+            action: action.to_string(),
+            target: target.filter(|target| !target.is_empty()).map(str::to_string),
+        };
+
+        if let Err(e) = self.admin_repository.log_admin_action(request).await {
+            warn!(admin_id = admin_id, action = action, error = %e, "Failed to record admin action");
+        }
+    }
+
+    /// Get a page of the audit trail, newest first, and the number of pages; pages start at 0
+    pub async fn list_page(&self, page: i64) -> Result<(Vec<AdminAuditEntry>, i64)> {
+        let total = self.admin_repository.count_admin_actions().await?;
+        let pages = page_count(total, AUDIT_PAGE_SIZE);
+        let page = page.clamp(0, pages - 1);
+        let entries = self.admin_repository.list_admin_actions(AUDIT_PAGE_SIZE, page * AUDIT_PAGE_SIZE).await?;
+
+        Ok((entries, pages))
+    }
+}
@@ -0,0 +1,98 @@
+//! Event archival service implementation
+//!
+//! Events pile up week after week while almost every query only looks at the
+//! upcoming ones. Once a day events that took place longer ago than the
+//! configured retention are moved with their participants into the archive
+//! tables, which the growth statistics still read.
+
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use tracing::{info, error};
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::EventRepository;
+use crate::utils::errors::Result;
+
+/// Event archival service
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ArchiveService {
+    event_repository: EventRepository,
+    settings: SharedSettings,
+}
+
+impl ArchiveService {
+    /// Create a new ArchiveService instance
+    pub fn new(event_repository: EventRepository, settings: SharedSettings) -> Self {
+        Self {
+            event_repository,
+            settings,
+        }
+    }
+
+    /// Archive all events past the retention period, a batch at a time.
+    /// Returns the number of events archived; nothing happens unless configured.
+    pub async fn archive_due(&self) -> Result<u64> {
+        let Some(config) = self.settings.get().archive.clone() else {
+            return Ok(0);
+        };
+        let before = archive_cutoff(Utc::now(), config.retention_days);
+
+        let mut archived = 0;
+        loop {
+            let moved = self.event_repository.archive_before(before, config.batch_size).await?;
+            archived += moved;
+            if moved < config.batch_size as u64 {
+                break;
+            }
+        }
+
+        if archived > 0 {
+            info!(archived = archived, before = %before, "Archived old events");
+        }
+        Ok(archived)
+    }
+
+    /// Number of events in the archive
+    pub async fn count_archived(&self) -> Result<i64> {
+        self.event_repository.count_archived().await
+    }
+
+    /// Start the background task that archives old events
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = self.settings.get().archive.clone()?;
+        let interval = Duration::from_secs(config.check_interval_seconds);
+
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(interval);
+
+            loop {
+                check_interval.tick().await;
+
+                if let Err(e) = self.archive_due().await {
+                    error!("Event archival task failed: {}", e);
+                }
+            }
+        });
+
+        info!("Started event archival task with interval {:?}", interval);
+        Some(handle)
+    }
+}
+
+/// Events that took place before this time are archived
+fn archive_cutoff(now: DateTime<Utc>, retention_days: u64) -> DateTime<Utc> {
+    now - chrono::Duration::days(retention_days as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_archive_cutoff() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(archive_cutoff(now, 365), Utc.with_ymd_and_hms(2023, 3, 2, 12, 0, 0).unwrap());
+        assert_eq!(archive_cutoff(now, 1), Utc.with_ymd_and_hms(2024, 2, 29, 12, 0, 0).unwrap());
+    }
+}
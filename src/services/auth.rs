@@ -8,8 +8,10 @@ use std::collections::HashSet;
 use teloxide::types::{ChatId, ChatMemberKind, UserId};
 use teloxide::{Bot, requests::Requester, prelude::Request};
 use tracing::{info, warn, debug};
-use crate::config::settings::Settings;
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::RoleRepository;
 use crate::models::User;
+use crate::models::role::{GroupRole, Role, SetGroupRoleRequest};
 use crate::utils::errors::{SwingBuddyError, Result};
 
 /// Permission levels for different operations
@@ -17,6 +19,9 @@ use crate::utils::errors::{SwingBuddyError, Result};
 pub enum Permission {
     /// Basic user permissions
     User,
+    /// Event management in a group, granted with the organizer role;
+    /// outside the linear hierarchy
+    EventOrganizer,
     /// Group moderator permissions
     GroupModerator,
     /// Group admin permissions
@@ -27,6 +32,58 @@ pub enum Permission {
     SuperAdmin,
 }
 
+/// Admin rights the bot needs in a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BotPermission {
+    /// Remove spam, captcha messages and temporary replies
+    DeleteMessages,
+    /// Ban and mute members
+    RestrictMembers,
+    /// Pin the weekly digest
+    PinMessages,
+    /// Create invite links
+    InviteUsers,
+}
+
+impl BotPermission {
+    /// All rights the bot asks for
+    pub const ALL: [BotPermission; 4] = [
+        Self::DeleteMessages,
+        Self::RestrictMembers,
+        Self::PinMessages,
+        Self::InviteUsers,
+    ];
+
+    /// Name used in translation keys
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DeleteMessages => "delete_messages",
+            Self::RestrictMembers => "restrict_members",
+            Self::PinMessages => "pin_messages",
+            Self::InviteUsers => "invite_users",
+        }
+    }
+
+    /// Check if a chat member has this right; owners have all of them
+    pub fn is_granted(&self, kind: &ChatMemberKind) -> bool {
+        match kind {
+            ChatMemberKind::Owner(_) => true,
+            ChatMemberKind::Administrator(admin) => match self {
+                Self::DeleteMessages => admin.can_delete_messages,
+                Self::RestrictMembers => admin.can_restrict_members,
+                Self::PinMessages => admin.can_pin_messages,
+                Self::InviteUsers => admin.can_invite_users,
+            },
+            _ => false,
+        }
+    }
+
+    /// Get the rights a chat member lacks
+    pub fn missing_for(kind: &ChatMemberKind) -> Vec<BotPermission> {
+        Self::ALL.into_iter().filter(|permission| !permission.is_granted(kind)).collect()
+    }
+}
+
 /// Authentication context for a user
 #[derive(Debug, Clone)]
 pub struct AuthContext {
@@ -35,7 +92,10 @@ pub struct AuthContext {
     pub permissions: HashSet<Permission>,
     pub is_bot_admin: bool,
     pub is_group_admin: bool,
+    pub is_group_owner: bool,
     pub is_group_member: bool,
+    /// Role delegated with /role in the group, if any
+    pub group_role: Option<Role>,
 }
 
 /// Authentication service for managing permissions and access control
@@ -43,23 +103,26 @@ pub struct AuthContext {
 #[derive(Debug)]
 pub struct AuthService {
     bot: Bot,
-    settings: Settings,
+    /// The bot's own user ID, from getMe at startup
+    bot_id: UserId,
+    role_repository: RoleRepository,
+    settings: SharedSettings,
 }
 
 impl AuthService {
     /// Create a new AuthService instance
-    pub fn new(bot: Bot, settings: Settings) -> Self {
-        Self { bot, settings }
+    pub fn new(bot: Bot, bot_id: UserId, role_repository: RoleRepository, settings: SharedSettings) -> Self {
+        Self { bot, bot_id, role_repository, settings }
     }
 
     /// Check if user is a bot admin
     pub fn is_bot_admin(&self, user_id: i64) -> bool {
-        self.settings.bot.admin_ids.contains(&user_id)
+        self.settings.get().bot.admin_ids.contains(&user_id)
     }
 
     /// Check if user is a super admin (first admin in the list)
     pub fn is_super_admin(&self, user_id: i64) -> bool {
-        self.settings.bot.admin_ids.first() == Some(&user_id)
+        self.settings.get().bot.admin_ids.first() == Some(&user_id)
     }
 
     /// Get authentication context for a user
@@ -83,14 +146,17 @@ impl AuthService {
         }
 
         let mut is_group_admin = false;
+        let mut is_group_owner = false;
         let mut is_group_member = false;
+        let mut group_role = None;
 
         // Check group-specific permissions if chat_id is provided
         if let Some(chat_id) = chat_id {
             match self.get_chat_member_status(chat_id, user_id).await {
-                Ok((is_member, is_admin)) => {
+                Ok((is_member, is_admin, is_owner)) => {
                     is_group_member = is_member;
                     is_group_admin = is_admin;
+                    is_group_owner = is_owner;
                     
                     if is_member {
                         if is_admin {
@@ -103,6 +169,14 @@ impl AuthService {
                     warn!(user_id = user_id, chat_id = ?chat_id, error = %e, "Failed to get chat member status");
                 }
             }
+
+            // Roles delegated with /role add to the Telegram status while the user is in the group
+            if is_group_member {
+                group_role = self.get_group_role(chat_id, user_id).await?;
+                if let Some(role) = group_role {
+                    permissions.extend(Self::role_permissions(role));
+                }
+            }
         }
 
         let context = AuthContext {
@@ -111,7 +185,9 @@ impl AuthService {
             permissions,
             is_bot_admin,
             is_group_admin,
+            is_group_owner,
             is_group_member,
+            group_role,
         };
 
         debug!(user_id = user_id, permissions = ?context.permissions, "Authentication context created");
@@ -146,15 +222,17 @@ impl AuthService {
             return Ok(true);
         }
 
-        // Group admins can manage events in their groups
-        if chat_id.is_some() && context.is_group_admin {
+        // Group admins and organizers can manage events in their groups
+        if chat_id.is_some()
+            && (context.permissions.contains(&Permission::GroupAdmin) || context.permissions.contains(&Permission::EventOrganizer))
+        {
             return Ok(true);
         }
 
         Ok(false)
     }
 
-    /// Check if user can manage users (ban/unban)
+    /// Check if user can manage users (ban/unban) and group settings
     pub async fn can_manage_users(&self, user_id: i64, chat_id: Option<ChatId>) -> Result<bool> {
         let context = self.get_auth_context(user_id, chat_id).await?;
         
@@ -164,18 +242,83 @@ impl AuthService {
         }
 
         // Group admins can manage users in their groups
-        if chat_id.is_some() && context.is_group_admin {
+        if chat_id.is_some() && context.permissions.contains(&Permission::GroupAdmin) {
             return Ok(true);
         }
 
         Ok(false)
     }
 
+    /// Check if user can warn and mute members and handle reports in a group
+    pub async fn can_moderate(&self, user_id: i64, chat_id: Option<ChatId>) -> Result<bool> {
+        let context = self.get_auth_context(user_id, chat_id).await?;
+
+        if context.is_bot_admin {
+            return Ok(true);
+        }
+
+        Ok(chat_id.is_some() && context.permissions.contains(&Permission::GroupModerator))
+    }
+
+    /// Check if user may grant and take away the admin and owner roles in a group:
+    /// the Telegram group owner, members with the owner role and bot admins
+    pub async fn can_manage_privileged_roles(&self, user_id: i64, chat_id: ChatId) -> Result<bool> {
+        let context = self.get_auth_context(user_id, Some(chat_id)).await?;
+        Ok(context.is_bot_admin || context.is_group_owner || context.group_role == Some(Role::Owner))
+    }
+
+    /// Get a member's delegated role in a group
+    pub async fn get_group_role(&self, chat_id: ChatId, user_id: i64) -> Result<Option<Role>> {
+        Ok(self.role_repository.find_role(chat_id.0, user_id).await?
+            .and_then(|role| role.role_kind()))
+    }
+
+    /// Get all delegated roles in a group
+    pub async fn list_group_roles(&self, chat_id: ChatId) -> Result<Vec<GroupRole>> {
+        self.role_repository.list_roles(chat_id.0).await
+    }
+
+    /// Give a member a role in a group, replacing their previous one
+    pub async fn set_group_role(&self, chat_id: ChatId, user_id: i64, role: Role, granted_by: i64) -> Result<GroupRole> {
+        let group_role = self.role_repository.set_role(SetGroupRoleRequest {
+            chat_telegram_id: chat_id.0,
+            user_telegram_id: user_id,
+            role,
+            granted_by,
+        }).await?;
+
+        info!(chat_id = chat_id.0, user_id = user_id, role = %role, granted_by = granted_by, "Group role granted");
+        Ok(group_role)
+    }
+
+    /// Take a member's role in a group away; returns whether they had one
+    pub async fn remove_group_role(&self, chat_id: ChatId, user_id: i64, removed_by: i64) -> Result<bool> {
+        let removed = self.role_repository.remove_role(chat_id.0, user_id).await?;
+        if removed {
+            info!(chat_id = chat_id.0, user_id = user_id, removed_by = removed_by, "Group role removed");
+        }
+        Ok(removed)
+    }
+
+    /// Get the permissions a delegated role grants
+    pub fn role_permissions(role: Role) -> Vec<Permission> {
+        match role {
+            Role::Owner | Role::Admin => vec![Permission::GroupAdmin, Permission::GroupModerator, Permission::EventOrganizer],
+            Role::Moderator => vec![Permission::GroupModerator],
+            Role::Organizer => vec![Permission::EventOrganizer],
+        }
+    }
+
     /// Check if user can access admin panel
     pub async fn can_access_admin_panel(&self, user_id: i64) -> Result<bool> {
         Ok(self.is_bot_admin(user_id))
     }
 
+    /// Check if user can read and write private user notes
+    pub async fn can_view_user_notes(&self, user_id: i64) -> Result<bool> {
+        Ok(self.is_bot_admin(user_id))
+    }
+
     /// Check if user can modify bot settings
     pub async fn can_modify_settings(&self, user_id: i64) -> Result<bool> {
         Ok(self.is_super_admin(user_id))
@@ -197,8 +340,17 @@ impl AuthService {
         Ok(true)
     }
 
+    /// Get the admin rights the bot lacks in a group
+    pub async fn missing_bot_permissions(&self, chat_id: ChatId) -> Result<Vec<BotPermission>> {
+        let member = self.bot.get_chat_member(chat_id, self.bot_id).send().await?;
+        let missing = BotPermission::missing_for(&member.kind);
+
+        debug!(chat_id = ?chat_id, missing = ?missing, "Bot permissions checked");
+        Ok(missing)
+    }
+
     /// Get chat member status
-    async fn get_chat_member_status(&self, chat_id: ChatId, user_id: i64) -> Result<(bool, bool)> {
+    async fn get_chat_member_status(&self, chat_id: ChatId, user_id: i64) -> Result<(bool, bool, bool)> {
         match self.bot.get_chat_member(chat_id, UserId(user_id as u64)).send().await {
             Ok(chat_member) => {
                 let is_member = !matches!(chat_member.kind, ChatMemberKind::Left | ChatMemberKind::Banned(_));
@@ -206,6 +358,7 @@ impl AuthService {
                     chat_member.kind,
                     ChatMemberKind::Owner(_) | ChatMemberKind::Administrator(_)
                 );
+                let is_owner = matches!(chat_member.kind, ChatMemberKind::Owner(_));
                 
                 debug!(
                     user_id = user_id,
@@ -216,12 +369,12 @@ impl AuthService {
                     "Chat member status retrieved"
                 );
                 
-                Ok((is_member, is_admin))
+                Ok((is_member, is_admin, is_owner))
             }
             Err(e) => {
                 // If we can't get member status, assume they're not a member
                 debug!(user_id = user_id, chat_id = ?chat_id, error = %e, "Could not get chat member status");
-                Ok((false, false))
+                Ok((false, false, false))
             }
         }
     }
@@ -273,8 +426,8 @@ impl AuthService {
     }
 
     /// Get all admin user IDs
-    pub fn get_admin_ids(&self) -> &[i64] {
-        &self.settings.bot.admin_ids
+    pub fn get_admin_ids(&self) -> Vec<i64> {
+        self.settings.get().bot.admin_ids.clone()
     }
 
     /// Add admin user ID (only super admin can do this)
@@ -285,8 +438,10 @@ impl AuthService {
             ));
         }
 
-        if !self.settings.bot.admin_ids.contains(&new_admin_id) {
-            self.settings.bot.admin_ids.push(new_admin_id);
+        let mut settings = (*self.settings.get()).clone();
+        if !settings.bot.admin_ids.contains(&new_admin_id) {
+            settings.bot.admin_ids.push(new_admin_id);
+            self.settings.replace(settings);
             info!(requester_id = requester_id, new_admin_id = new_admin_id, "New admin added");
         }
 
@@ -307,7 +462,9 @@ impl AuthService {
             ));
         }
 
-        self.settings.bot.admin_ids.retain(|&id| id != admin_id);
+        let mut settings = (*self.settings.get()).clone();
+        settings.bot.admin_ids.retain(|&id| id != admin_id);
+        self.settings.replace(settings);
         info!(requester_id = requester_id, removed_admin_id = admin_id, "Admin removed");
 
         Ok(())
@@ -343,6 +500,7 @@ impl AuthMiddleware {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::settings::Settings;
 
     #[test]
     fn test_permission_hierarchy() {
@@ -353,25 +511,59 @@ mod tests {
     }
 
     #[test]
-    fn test_bot_admin_check() {
+    fn test_missing_bot_permissions() {
+        let admin: teloxide::types::Administrator = serde_json::from_value(serde_json::json!({
+            "can_be_edited": false,
+            "is_anonymous": false,
+            "can_manage_chat": true,
+            "can_change_info": false,
+            "can_delete_messages": true,
+            "can_manage_video_chats": false,
+            "can_invite_users": false,
+            "can_restrict_members": true,
+            "can_promote_members": false,
+            "can_pin_messages": false
+        })).unwrap();
+
+        let missing = BotPermission::missing_for(&ChatMemberKind::Administrator(admin));
+        assert_eq!(missing, vec![BotPermission::PinMessages, BotPermission::InviteUsers]);
+        assert_eq!(BotPermission::missing_for(&ChatMemberKind::Member), BotPermission::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_role_permissions() {
+        let moderator = AuthService::role_permissions(Role::Moderator);
+        assert!(moderator.contains(&Permission::GroupModerator));
+        assert!(!moderator.contains(&Permission::GroupAdmin));
+        assert!(!moderator.contains(&Permission::EventOrganizer));
+
+        let organizer = AuthService::role_permissions(Role::Organizer);
+        assert_eq!(organizer, vec![Permission::EventOrganizer]);
+
+        let admin = AuthService::role_permissions(Role::Admin);
+        assert!(admin.contains(&Permission::GroupAdmin) && admin.contains(&Permission::GroupModerator));
+    }
+
+    #[tokio::test]
+    async fn test_bot_admin_check() {
         let bot = teloxide::Bot::new("test_token");
         let mut settings = Settings::default();
         settings.bot.admin_ids = vec![123456789, 987654321];
         
-        let auth_service = AuthService::new(bot, settings);
+        let auth_service = AuthService::new(bot, UserId(1), RoleRepository::new_for_testing(), SharedSettings::new(settings));
         
         assert!(auth_service.is_bot_admin(123456789));
         assert!(auth_service.is_bot_admin(987654321));
         assert!(!auth_service.is_bot_admin(111111111));
     }
 
-    #[test]
-    fn test_super_admin_check() {
+    #[tokio::test]
+    async fn test_super_admin_check() {
         let bot = teloxide::Bot::new("test_token");
         let mut settings = Settings::default();
         settings.bot.admin_ids = vec![123456789, 987654321];
         
-        let auth_service = AuthService::new(bot, settings);
+        let auth_service = AuthService::new(bot, UserId(1), RoleRepository::new_for_testing(), SharedSettings::new(settings));
         
         assert!(auth_service.is_super_admin(123456789)); // First admin is super admin
         assert!(!auth_service.is_super_admin(987654321)); // Second admin is not super admin
@@ -384,7 +576,7 @@ mod tests {
         let mut settings = Settings::default();
         settings.bot.admin_ids = vec![123456789];
         
-        let auth_service = AuthService::new(bot, settings);
+        let auth_service = AuthService::new(bot, UserId(1), RoleRepository::new_for_testing(), SharedSettings::new(settings));
         
         // Test bot admin context
         let context = auth_service.get_auth_context(123456789, None).await.unwrap();
@@ -0,0 +1,101 @@
+//! Backup service implementation
+//!
+//! Bot admins can export the main tables (users, groups, events and event
+//! participants) from the admin panel. The export is a single gzip-compressed
+//! JSON document with one array of rows per table, sent to the admin as a file.
+
+use std::collections::HashMap;
+use std::io::Write;
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use tracing::info;
+use crate::database::repositories::AdminRepository;
+use crate::database::repositories::admin::EXPORT_TABLES;
+use crate::utils::errors::Result;
+
+/// A compressed database export ready to be sent as a document
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub file_name: String,
+    pub data: Vec<u8>,
+    pub row_counts: HashMap<String, usize>,
+}
+
+/// Backup service for database exports
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct BackupService {
+    admin_repository: AdminRepository,
+}
+
+impl BackupService {
+    /// Create a new BackupService instance
+    pub fn new(admin_repository: AdminRepository) -> Self {
+        Self { admin_repository }
+    }
+
+    /// Export the backed up tables into a compressed JSON bundle
+    pub async fn create_backup(&self) -> Result<Backup> {
+        let created_at = Utc::now();
+        let mut tables = serde_json::Map::new();
+        let mut row_counts = HashMap::new();
+
+        for table in EXPORT_TABLES {
+            let rows = self.admin_repository.export_table(table).await?;
+            row_counts.insert(table.to_string(), rows.as_array().map_or(0, Vec::len));
+            tables.insert(table.to_string(), rows);
+        }
+
+        let bundle = serde_json::json!({
+            "created_at": created_at,
+            "tables": tables,
+        });
+        let data = compress_bundle(&bundle)?;
+
+        info!(size = data.len(), "Database backup created");
+        Ok(Backup {
+            file_name: backup_file_name(created_at),
+            data,
+            row_counts,
+        })
+    }
+}
+
+/// Serialize a backup bundle and compress it with gzip
+pub fn compress_bundle(bundle: &serde_json::Value) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&serde_json::to_vec(bundle)?)?;
+    Ok(encoder.finish()?)
+}
+
+/// Get the name of the backup file, e.g. `swingbuddy-backup-20240315-120000.json.gz`
+pub fn backup_file_name(created_at: DateTime<Utc>) -> String {
+    format!("swingbuddy-backup-{}.json.gz", created_at.format("%Y%m%d-%H%M%S"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use chrono::TimeZone;
+    use flate2::read::GzDecoder;
+
+    #[test]
+    fn test_compress_bundle_round_trip() {
+        let bundle = serde_json::json!({
+            "tables": { "users": [{ "id": 1, "first_name": "Frankie" }] }
+        });
+
+        let data = compress_bundle(&bundle).unwrap();
+
+        let mut json = String::new();
+        GzDecoder::new(data.as_slice()).read_to_string(&mut json).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&json).unwrap(), bundle);
+    }
+
+    #[test]
+    fn test_backup_file_name() {
+        let created_at = Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+        assert_eq!(backup_file_name(created_at), "swingbuddy-backup-20240315-120000.json.gz");
+    }
+}
@@ -0,0 +1,259 @@
+//! Birthday service implementation
+//!
+//! This service stores optional user birthdays, sends a daily greeting by DM and,
+//! if the user agreed to it, announces the birthday in their chosen group.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use chrono::{Datelike, NaiveDate, Timelike, Utc};
+use teloxide::types::{ChatId, ParseMode};
+use teloxide::utils::html;
+use tracing::{info, warn, error, debug};
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::{BirthdayRepository, UserRepository, GroupRepository};
+use crate::models::birthday::{UserBirthday, SetBirthdayRequest};
+use crate::models::user::User;
+use crate::services::notification::{NotificationService, NotificationRequest};
+use crate::services::quiet_hours::QuietHoursService;
+use crate::utils::errors::{SwingBuddyError, Result};
+
+/// Birthday service for collecting birthdays and sending greetings
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct BirthdayService {
+    birthday_repository: BirthdayRepository,
+    user_repository: UserRepository,
+    group_repository: GroupRepository,
+    notification_service: NotificationService,
+    quiet_hours_service: QuietHoursService,
+    settings: SharedSettings,
+}
+
+impl BirthdayService {
+    /// Create a new BirthdayService instance
+    pub fn new(
+        birthday_repository: BirthdayRepository,
+        user_repository: UserRepository,
+        group_repository: GroupRepository,
+        notification_service: NotificationService,
+        quiet_hours_service: QuietHoursService,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            birthday_repository,
+            user_repository,
+            group_repository,
+            notification_service,
+            quiet_hours_service,
+            settings,
+        }
+    }
+
+    /// Set a user's birthday from "DD.MM" input
+    pub async fn set_birthday(&self, telegram_id: i64, input: &str) -> Result<UserBirthday> {
+        let (birth_day, birth_month) = parse_birthday(input)?;
+        let user = self.find_user(telegram_id).await?;
+
+        let birthday = self.birthday_repository.upsert(SetBirthdayRequest {
+            user_id: user.id,
+            birth_day,
+            birth_month,
+        }).await?;
+
+        info!(user_id = user.id, "Birthday set");
+        Ok(birthday)
+    }
+
+    /// Get a user's birthday
+    pub async fn get_birthday(&self, telegram_id: i64) -> Result<Option<UserBirthday>> {
+        let user = self.find_user(telegram_id).await?;
+        self.birthday_repository.find_by_user(user.id).await
+    }
+
+    /// Remove a user's birthday
+    pub async fn clear_birthday(&self, telegram_id: i64) -> Result<()> {
+        let user = self.find_user(telegram_id).await?;
+        self.birthday_repository.delete(user.id).await?;
+
+        info!(user_id = user.id, "Birthday removed");
+        Ok(())
+    }
+
+    /// Allow (or revoke with `None`) announcing the user's birthday in a group.
+    /// Returns `false` if the user has no birthday set or the group is unknown.
+    pub async fn set_announcement_group(&self, telegram_id: i64, group_telegram_id: Option<i64>) -> Result<bool> {
+        let user = self.find_user(telegram_id).await?;
+
+        let group_id = match group_telegram_id {
+            Some(group_telegram_id) => match self.group_repository.find_by_telegram_id(group_telegram_id).await? {
+                Some(group) => Some(group.id),
+                None => return Ok(false),
+            },
+            None => None,
+        };
+
+        let updated = self.birthday_repository.set_announce_group(user.id, group_id).await?;
+        Ok(updated.is_some())
+    }
+
+    /// Start the background task that sends birthday greetings
+    pub fn start(mut self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = self.settings.get().birthdays.clone().filter(|b| b.enabled)?;
+        let interval = Duration::from_secs(config.check_interval_seconds);
+
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(interval);
+
+            loop {
+                check_interval.tick().await;
+
+                match self.send_due_greetings().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("Birthday task greeted {} users", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Birthday task failed: {}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Started birthday greeting task with interval {:?}", interval);
+        Some(handle)
+    }
+
+    /// Greet everyone whose birthday is today and who was not greeted this year
+    pub async fn send_due_greetings(&mut self) -> Result<usize> {
+        let Some(config) = self.settings.get().birthdays.clone() else {
+            return Ok(0);
+        };
+
+        let now = Utc::now();
+        if now.hour() < config.greeting_hour_utc {
+            return Ok(0);
+        }
+
+        let today = now.date_naive();
+        let pending = self.birthday_repository
+            .get_pending_greetings(today.month() as i16, &birthday_days_for(today), today.year())
+            .await?;
+        let mut greeted = 0;
+
+        for birthday in pending {
+            let Some(user) = self.user_repository.find_by_id(birthday.user_id).await? else {
+                continue;
+            };
+
+            if !user.is_banned {
+                self.greet_user(&user, &birthday).await;
+                greeted += 1;
+            }
+
+            // Mark as greeted even if sending failed, so blocked chats are not retried all day
+            self.birthday_repository.mark_greeted(user.id, today.year()).await?;
+        }
+
+        Ok(greeted)
+    }
+
+    /// Send the DM greeting and the group announcement, if the user agreed to it
+    async fn greet_user(&mut self, user: &User, birthday: &UserBirthday) {
+        let name = user.first_name.clone()
+            .or_else(|| user.username.as_ref().map(|u| format!("@{}", u)))
+            .unwrap_or_else(|| "Friend".to_string());
+        let mut parameters = HashMap::new();
+        parameters.insert("name".to_string(), html::escape(&name));
+
+        let request = NotificationRequest {
+            chat_id: ChatId(user.telegram_id),
+            thread_id: None,
+            template_key: "birthday_greeting".to_string(),
+            language: user.language_code.clone(),
+            parameters: parameters.clone(),
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        };
+        if let Err(e) = self.notification_service.send_notification(request).await {
+            warn!(user_id = user.id, error = %e, "Failed to send birthday greeting");
+        }
+
+        let Some(group_id) = birthday.announce_group_id else {
+            return;
+        };
+
+        match self.group_repository.find_by_id(group_id).await {
+            Ok(Some(group)) if group.is_active => {
+                let request = NotificationRequest {
+                    chat_id: ChatId(group.telegram_id),
+                    thread_id: None,
+                    template_key: "group_birthday".to_string(),
+                    language: group.language_code.clone(),
+                    parameters,
+                    parse_mode: Some(ParseMode::Html),
+                    disable_web_page_preview: true,
+                };
+                if let Err(e) = self.quiet_hours_service.send_or_queue(&group, request).await {
+                    warn!(user_id = user.id, group_id = group_id, error = %e, "Failed to announce birthday in group");
+                }
+            }
+            Ok(_) => debug!(user_id = user.id, group_id = group_id, "Birthday group inactive or missing"),
+            Err(e) => warn!(user_id = user.id, group_id = group_id, error = %e, "Failed to load birthday group"),
+        }
+    }
+
+    /// Find a registered user by Telegram ID
+    async fn find_user(&self, telegram_id: i64) -> Result<User> {
+        self.user_repository.find_by_telegram_id(telegram_id).await?
+            .ok_or(SwingBuddyError::UserNotFound { user_id: telegram_id })
+    }
+}
+
+/// Parse a "DD.MM" (or "DD/MM") birthday into (day, month)
+pub fn parse_birthday(input: &str) -> Result<(i16, i16)> {
+    let invalid = || SwingBuddyError::InvalidInput(format!("Invalid birthday: {}", input));
+
+    let (day, month) = input.trim().split_once(['.', '/']).ok_or_else(invalid)?;
+    let day: u32 = day.parse().map_err(|_| invalid())?;
+    let month: u32 = month.parse().map_err(|_| invalid())?;
+
+    // 2000 is a leap year, so 29.02 is accepted
+    NaiveDate::from_ymd_opt(2000, month, day).ok_or_else(invalid)?;
+
+    Ok((day as i16, month as i16))
+}
+
+/// Birthdays (days of the current month) to celebrate on a date.
+/// 29 February birthdays are celebrated on 28 February in non-leap years.
+fn birthday_days_for(date: NaiveDate) -> Vec<i16> {
+    let mut days = vec![date.day() as i16];
+    let is_leap_year = NaiveDate::from_ymd_opt(date.year(), 2, 29).is_some();
+    if date.month() == 2 && date.day() == 28 && !is_leap_year {
+        days.push(29);
+    }
+    days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_birthday() {
+        assert_eq!(parse_birthday("17.05").unwrap(), (17, 5));
+        assert_eq!(parse_birthday(" 1/12 ").unwrap(), (1, 12));
+        assert_eq!(parse_birthday("29.02").unwrap(), (29, 2));
+        assert!(parse_birthday("31.04").is_err());
+        assert!(parse_birthday("13").is_err());
+        assert!(parse_birthday("aa.bb").is_err());
+    }
+
+    #[test]
+    fn test_birthday_days_for_leap_day() {
+        assert_eq!(birthday_days_for(NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()), vec![28, 29]);
+        assert_eq!(birthday_days_for(NaiveDate::from_ymd_opt(2024, 2, 28).unwrap()), vec![28]);
+        assert_eq!(birthday_days_for(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()), vec![29]);
+        assert_eq!(birthday_days_for(NaiveDate::from_ymd_opt(2023, 5, 17).unwrap()), vec![17]);
+    }
+}
@@ -0,0 +1,286 @@
+//! Broadcast service implementation
+//!
+//! Bot admins compose a message in their private chat and send a copy of it to a
+//! segment of the users: everyone, a city, a language or the participants of an
+//! event. Deliveries are queued in the database and sent at a pace below the
+//! Telegram limits, waiting out flood control when Telegram asks for it. The admin
+//! gets a delivery report once the queue of a broadcast is empty.
+//!
+//! Text broadcasts can be personalized with placeholders like `{first_name}`;
+//! those are rendered for every recipient instead of being copied.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use teloxide::{Bot, prelude::*, types::{ChatId, MessageId, ParseMode}, utils::html};
+use tracing::{info, warn, error, debug};
+use crate::database::repositories::{BroadcastRepository, EventRepository, UserRepository};
+use crate::models::broadcast::{Broadcast, CreateBroadcastRequest, BroadcastSegment, DeliveryStatus};
+use crate::models::event::Event;
+use crate::models::user::User;
+use crate::services::notification::NotificationService;
+use crate::utils::errors::{SwingBuddyError, Result};
+use crate::utils::retry::RetryPolicy;
+
+/// How often the delivery queue is checked for new broadcasts
+const QUEUE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Pause between two deliveries, keeping the bot below 30 messages per second
+const SEND_INTERVAL: Duration = Duration::from_millis(40);
+
+/// Recipients loaded from the queue at once
+const BATCH_SIZE: i64 = 100;
+
+/// Placeholders a text broadcast can contain
+pub const BROADCAST_PLACEHOLDERS: [&str; 4] = ["{first_name}", "{last_name}", "{username}", "{city}"];
+
+/// Broadcast service for segmented admin announcements
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct BroadcastService {
+    bot: Bot,
+    broadcast_repository: BroadcastRepository,
+    event_repository: EventRepository,
+    user_repository: UserRepository,
+    notification_service: NotificationService,
+    retry: RetryPolicy,
+}
+
+impl BroadcastService {
+    /// Create a new BroadcastService instance
+    pub fn new(
+        bot: Bot,
+        broadcast_repository: BroadcastRepository,
+        event_repository: EventRepository,
+        user_repository: UserRepository,
+        notification_service: NotificationService,
+    ) -> Self {
+        Self {
+            bot,
+            broadcast_repository,
+            event_repository,
+            user_repository,
+            notification_service,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Count the users a broadcast to the segment would reach
+    pub async fn count_recipients(&self, segment: &BroadcastSegment) -> Result<i64> {
+        self.broadcast_repository.count_recipients(segment).await
+    }
+
+    /// Get a user of the segment to preview a personalized broadcast with
+    pub async fn sample_recipient(&self, segment: &BroadcastSegment) -> Result<Option<User>> {
+        let Some(user_id) = self.broadcast_repository.sample_recipient(segment).await? else {
+            return Ok(None);
+        };
+        self.user_repository.find_by_telegram_id(user_id).await
+    }
+
+    /// Get the most common user cities to offer as segments
+    pub async fn get_top_cities(&self, limit: i64) -> Result<Vec<(String, i64)>> {
+        self.broadcast_repository.get_top_cities(limit).await
+    }
+
+    /// Get the user languages to offer as segments
+    pub async fn get_languages(&self) -> Result<Vec<(String, i64)>> {
+        self.broadcast_repository.get_languages().await
+    }
+
+    /// Get upcoming events whose participants can be messaged
+    pub async fn get_upcoming_events(&self, limit: i64) -> Result<Vec<Event>> {
+        self.event_repository.get_upcoming_events(Some(limit)).await
+    }
+
+    /// Queue a broadcast for every user in its segment
+    pub async fn create_broadcast(&self, request: CreateBroadcastRequest) -> Result<Broadcast> {
+        let broadcast = self.broadcast_repository.create(request).await?;
+
+        info!(broadcast_id = broadcast.id, created_by = broadcast.created_by, segment = %broadcast.segment, total = broadcast.total, "Broadcast queued");
+        Ok(broadcast)
+    }
+
+    /// Start the background task that delivers queued broadcasts
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(QUEUE_INTERVAL);
+
+            loop {
+                check_interval.tick().await;
+
+                match self.process_queue().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("Broadcast task delivered {} messages", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Broadcast task failed: {}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Started broadcast task with interval {:?}", QUEUE_INTERVAL);
+        handle
+    }
+
+    /// Deliver the queued broadcasts one after another. Returns the number of messages sent.
+    pub async fn process_queue(&self) -> Result<usize> {
+        let mut delivered = 0;
+
+        for broadcast in self.broadcast_repository.get_unfinished().await? {
+            loop {
+                let recipients = self.broadcast_repository.get_pending_recipients(broadcast.id, BATCH_SIZE).await?;
+                if recipients.is_empty() {
+                    break;
+                }
+
+                for user_id in recipients {
+                    let text = match &broadcast.text {
+                        Some(template) => {
+                            let user = self.user_repository.find_by_telegram_id(user_id).await?;
+                            Some(render_broadcast(template, user.as_ref()))
+                        }
+                        None => None,
+                    };
+
+                    let (status, error) = match self.deliver(&broadcast, user_id, text.as_deref()).await {
+                        Ok(()) => {
+                            delivered += 1;
+                            (DeliveryStatus::Sent, None)
+                        }
+                        // Usually the user blocked the bot; leave them out of later broadcasts
+                        Err(e) => {
+                            debug!(broadcast_id = broadcast.id, user_id = user_id, error = %e, "Broadcast delivery failed");
+                            if matches!(e, SwingBuddyError::RecipientUnavailable(_)) {
+                                self.user_repository.mark_blocked(user_id).await?;
+                            }
+                            (DeliveryStatus::Failed, Some(e.to_string()))
+                        }
+                    };
+                    self.broadcast_repository.set_delivery_status(broadcast.id, user_id, status, error.as_deref()).await?;
+
+                    tokio::time::sleep(SEND_INTERVAL).await;
+                }
+            }
+
+            self.broadcast_repository.finish(broadcast.id).await?;
+            self.send_report(&broadcast).await;
+        }
+
+        Ok(delivered)
+    }
+
+    /// Send the personalized text, or copy the composed message as it is. Transient
+    /// failures and flood control waits are retried.
+    async fn deliver(&self, broadcast: &Broadcast, user_id: i64, text: Option<&str>) -> Result<()> {
+        match text {
+            Some(text) => self.retry.send(&self.bot.send_message(ChatId(user_id), text)
+                .parse_mode(ParseMode::Html))
+                .await
+                .map(|_| ()),
+            None => self.retry.send(&self.bot.copy_message(
+                ChatId(user_id),
+                ChatId(broadcast.source_chat_id),
+                MessageId(broadcast.source_message_id),
+            )).await.map(|_| ()),
+        }
+    }
+
+    /// Tell the admin who sent a broadcast how the delivery went
+    async fn send_report(&self, broadcast: &Broadcast) {
+        let report = match self.broadcast_repository.get_report(broadcast.id).await {
+            Ok(report) => report,
+            Err(e) => {
+                error!(broadcast_id = broadcast.id, error = %e, "Failed to count broadcast deliveries");
+                return;
+            }
+        };
+
+        let mut parameters = HashMap::new();
+        parameters.insert("id".to_string(), broadcast.id.to_string());
+        parameters.insert("segment".to_string(), broadcast.segment.clone());
+        parameters.insert("total".to_string(), broadcast.total.to_string());
+        parameters.insert("sent".to_string(), report.sent.to_string());
+        parameters.insert("failed".to_string(), report.failed.to_string());
+
+        let text = match self.notification_service.format_message("broadcast_report", &broadcast.language_code, &parameters) {
+            Ok(text) => text,
+            Err(e) => {
+                error!(error = %e, "Failed to format broadcast report");
+                return;
+            }
+        };
+
+        if let Err(e) = self.bot.send_message(ChatId(broadcast.created_by), text).await {
+            warn!(broadcast_id = broadcast.id, error = %e, "Failed to send broadcast report");
+        }
+
+        info!(broadcast_id = broadcast.id, sent = report.sent, failed = report.failed, "Broadcast finished");
+    }
+}
+
+/// Check whether a broadcast text needs to be rendered for every recipient
+pub fn has_placeholders(text: &str) -> bool {
+    BROADCAST_PLACEHOLDERS.iter().any(|placeholder| text.contains(placeholder))
+}
+
+/// Fill in the placeholders of an HTML broadcast text for a recipient. The
+/// first name falls back to the username; unknown values are left empty.
+pub fn render_broadcast(template: &str, user: Option<&User>) -> String {
+    let first_name = user.and_then(|user| user.first_name.as_deref().or(user.username.as_deref()));
+    let values = [
+        ("{first_name}", first_name),
+        ("{last_name}", user.and_then(|user| user.last_name.as_deref())),
+        ("{username}", user.and_then(|user| user.username.as_deref())),
+        ("{city}", user.and_then(|user| user.location.as_deref())),
+    ];
+
+    values.iter().fold(template.to_string(), |text, (placeholder, value)| {
+        text.replace(placeholder, &html::escape(value.unwrap_or_default()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn user(first_name: Option<&str>, username: Option<&str>, location: Option<&str>) -> User {
+        User {
+            id: 1,
+            telegram_id: 12345,
+            username: username.map(str::to_string),
+            first_name: first_name.map(str::to_string),
+            last_name: None,
+            language_code: "en".to_string(),
+            location: location.map(str::to_string),
+            is_banned: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_has_placeholders() {
+        assert!(has_placeholders("Hi {first_name}!"));
+        assert!(has_placeholders("See you in {city}"));
+        assert!(!has_placeholders("Hi everyone! {unknown}"));
+    }
+
+    #[test]
+    fn test_render_broadcast() {
+        let frankie = user(Some("Frankie"), Some("frankie"), Some("Berlin"));
+        assert_eq!(
+            render_broadcast("<b>Hi {first_name}</b>, see you in {city}!", Some(&frankie)),
+            "<b>Hi Frankie</b>, see you in Berlin!"
+        );
+
+        // Falls back to the username and escapes the values
+        let nameless = user(None, Some("lindy<3"), None);
+        assert_eq!(render_broadcast("Hi {first_name} from {city}", Some(&nameless)), "Hi lindy&lt;3 from ");
+
+        assert_eq!(render_broadcast("Hi {first_name}!", None), "Hi !");
+    }
+}
@@ -0,0 +1,171 @@
+//! Calendar service implementation
+//!
+//! Bot admins manage the calendars users browse in /events from the admin
+//! panel. A calendar can be linked to a public Google Calendar, which the
+//! "Add to Google Calendar" button then points at.
+
+use tracing::info;
+use crate::database::repositories::CalendarRepository;
+use crate::models::calendar::{Calendar, CreateCalendarRequest, UpdateCalendarRequest, CalendarField};
+use crate::utils::errors::{SwingBuddyError, Result};
+
+/// Maximum length of a calendar name
+pub const MAX_CALENDAR_NAME_LENGTH: usize = 100;
+
+/// Maximum length of a calendar description
+pub const MAX_CALENDAR_DESCRIPTION_LENGTH: usize = 1000;
+
+/// Calendar service for managing event calendars
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct CalendarService {
+    calendar_repository: CalendarRepository,
+}
+
+impl CalendarService {
+    /// Create a new CalendarService instance
+    pub fn new(calendar_repository: CalendarRepository) -> Self {
+        Self { calendar_repository }
+    }
+
+    /// Get all calendars
+    pub async fn list_calendars(&self) -> Result<Vec<Calendar>> {
+        self.calendar_repository.list().await
+    }
+
+    /// Get a calendar by ID
+    pub async fn get_calendar(&self, id: i64) -> Result<Option<Calendar>> {
+        self.calendar_repository.find_by_id(id).await
+    }
+
+    /// Create a calendar after validating its fields
+    pub async fn create_calendar(
+        &self,
+        created_by: i64,
+        name: &str,
+        description: Option<&str>,
+        google_calendar_id: Option<&str>,
+    ) -> Result<Calendar> {
+        let calendar = self.calendar_repository.create(CreateCalendarRequest {
+            name: validate_calendar_name(name)?,
+            description: description.map(validate_calendar_description).transpose()?,
+            google_calendar_id: google_calendar_id.map(validate_google_calendar_id).transpose()?,
+            created_by,
+        }).await?;
+
+        info!(calendar_id = calendar.id, created_by = created_by, "Calendar created");
+        Ok(calendar)
+    }
+
+    /// Change one field of a calendar; `None` clears an optional field.
+    /// Returns `None` if the calendar does not exist.
+    pub async fn update_field(&self, id: i64, field: CalendarField, value: Option<&str>) -> Result<Option<Calendar>> {
+        let Some(calendar) = self.calendar_repository.find_by_id(id).await? else {
+            return Ok(None);
+        };
+
+        let mut request = UpdateCalendarRequest {
+            name: calendar.name,
+            description: calendar.description,
+            google_calendar_id: calendar.google_calendar_id,
+        };
+        match field {
+            CalendarField::Name => {
+                let name = value.ok_or_else(|| SwingBuddyError::InvalidInput("Calendar name cannot be empty".to_string()))?;
+                request.name = validate_calendar_name(name)?;
+            }
+            CalendarField::Description => {
+                request.description = value.map(validate_calendar_description).transpose()?;
+            }
+            CalendarField::GoogleCalendarId => {
+                request.google_calendar_id = value.map(validate_google_calendar_id).transpose()?;
+            }
+        }
+
+        let calendar = self.calendar_repository.update(id, request).await?;
+
+        info!(calendar_id = id, field = field.as_str(), "Calendar updated");
+        Ok(Some(calendar))
+    }
+
+    /// Delete a calendar; returns false if it did not exist
+    pub async fn delete_calendar(&self, id: i64) -> Result<bool> {
+        let deleted = self.calendar_repository.delete(id).await?;
+        if deleted {
+            info!(calendar_id = id, "Calendar deleted");
+        }
+        Ok(deleted)
+    }
+}
+
+/// Check a calendar name and trim it
+pub fn validate_calendar_name(name: &str) -> Result<String> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(SwingBuddyError::InvalidInput("Calendar name cannot be empty".to_string()));
+    }
+
+    if name.chars().count() > MAX_CALENDAR_NAME_LENGTH {
+        return Err(SwingBuddyError::InvalidInput(format!("Calendar name cannot exceed {} characters", MAX_CALENDAR_NAME_LENGTH)));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Check a calendar description and trim it
+pub fn validate_calendar_description(description: &str) -> Result<String> {
+    let description = description.trim();
+
+    if description.chars().count() > MAX_CALENDAR_DESCRIPTION_LENGTH {
+        return Err(SwingBuddyError::InvalidInput(format!("Description cannot exceed {} characters", MAX_CALENDAR_DESCRIPTION_LENGTH)));
+    }
+
+    Ok(description.to_string())
+}
+
+/// Check that a Google Calendar ID looks like one, e.g.
+/// `abc123@group.calendar.google.com` or the owner's address
+pub fn validate_google_calendar_id(calendar_id: &str) -> Result<String> {
+    let calendar_id = calendar_id.trim();
+    let invalid = || SwingBuddyError::InvalidInput(format!("Not a Google Calendar ID: {}", calendar_id));
+
+    if calendar_id.len() > 255 || calendar_id.chars().any(char::is_whitespace) {
+        return Err(invalid());
+    }
+
+    let (account, domain) = calendar_id.split_once('@').ok_or_else(invalid)?;
+    let domain_valid = domain.split('.').count() >= 2 && domain.split('.').all(|label| !label.is_empty());
+    if account.is_empty() || domain.contains('@') || !domain_valid {
+        return Err(invalid());
+    }
+
+    Ok(calendar_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_calendar_name() {
+        assert_eq!(validate_calendar_name("  Lindy Socials ").unwrap(), "Lindy Socials");
+        assert!(validate_calendar_name("   ").is_err());
+        assert!(validate_calendar_name(&"a".repeat(MAX_CALENDAR_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_google_calendar_id() {
+        assert_eq!(
+            validate_google_calendar_id(" c_1a2b3c@group.calendar.google.com ").unwrap(),
+            "c_1a2b3c@group.calendar.google.com"
+        );
+        assert!(validate_google_calendar_id("swingbuddy.events@gmail.com").is_ok());
+        assert!(validate_google_calendar_id("group.calendar.google.com").is_err());
+        assert!(validate_google_calendar_id("@group.calendar.google.com").is_err());
+        assert!(validate_google_calendar_id("abc@localhost").is_err());
+        assert!(validate_google_calendar_id("abc@group..com").is_err());
+        assert!(validate_google_calendar_id("a b@group.calendar.google.com").is_err());
+        assert!(validate_google_calendar_id("a@b@group.calendar.google.com").is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! Captcha service implementation
+//!
+//! This service implements the optional join-gate for groups: new members are
+//! restricted until they press a button or answer a simple swing question, and
+//! are removed from the group if they fail or do not answer in time.
+
+use std::time::Duration;
+use chrono::Utc;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use teloxide::{Bot, prelude::*, types::{ChatId, ChatPermissions, MessageId, UserId}};
+use tracing::{info, warn, error, debug};
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::{CaptchaRepository, GroupRepository};
+use crate::models::captcha::{CaptchaMode, CreatePendingCaptchaRequest};
+use crate::models::group::Group;
+use crate::services::feature_flags::{FeatureFlag, FeatureFlags};
+use crate::utils::errors::Result;
+
+/// Swing questions for the question mode: (translation key, correct answer, wrong answers).
+/// Answers are proper names, so they do not need translation.
+const CAPTCHA_QUESTIONS: [(&str, &str, [&str; 2]); 3] = [
+    ("captcha.questions.birthplace", "Harlem", ["Buenos Aires", "Havana"]),
+    ("captcha.questions.ballroom", "Savoy Ballroom", ["Moulin Rouge", "Royal Albert Hall"]),
+    ("captcha.questions.ambassador", "Frankie Manning", ["Fred Astaire", "Elvis Presley"]),
+];
+
+/// A captcha a new member has to pass
+#[derive(Debug, Clone)]
+pub struct CaptchaChallenge {
+    pub pending_id: i64,
+    pub mode: CaptchaMode,
+    /// Translation key of the question (question mode only)
+    pub question_key: Option<&'static str>,
+    /// Answer options in display order; the button mode has none
+    pub options: Vec<&'static str>,
+    pub timeout_seconds: u64,
+}
+
+/// Result of a captcha answer
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaptchaOutcome {
+    /// The member has no pending captcha in this chat
+    NotFound,
+    /// The member passed and was unrestricted
+    Passed { message_id: Option<MessageId> },
+    /// The member answered wrong and was removed
+    Failed { message_id: Option<MessageId> },
+}
+
+/// Captcha service for verifying new group members
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct CaptchaService {
+    bot: Bot,
+    captcha_repository: CaptchaRepository,
+    group_repository: GroupRepository,
+    feature_flags: FeatureFlags,
+    settings: SharedSettings,
+}
+
+impl CaptchaService {
+    /// Create a new CaptchaService instance
+    pub fn new(
+        bot: Bot,
+        captcha_repository: CaptchaRepository,
+        group_repository: GroupRepository,
+        feature_flags: FeatureFlags,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            bot,
+            captcha_repository,
+            group_repository,
+            feature_flags,
+            settings,
+        }
+    }
+
+    /// Check if the captcha is configured for the bot and not switched off by an admin
+    pub fn is_enabled(&self) -> bool {
+        self.settings.get().captcha.is_some() && self.feature_flags.is_enabled(FeatureFlag::Captcha)
+    }
+
+    /// Get the group if new members there must pass a captcha
+    pub async fn captcha_group(&self, chat_id: ChatId) -> Result<Option<Group>> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+
+        let group = self.group_repository.find_by_telegram_id(chat_id.0).await?;
+        Ok(group.filter(|g| g.is_active && g.captcha_enabled()))
+    }
+
+    /// Restrict a new member and create their captcha
+    pub async fn begin_challenge(&self, chat_id: ChatId, user_id: UserId, mode: CaptchaMode) -> Result<CaptchaChallenge> {
+        let timeout_seconds = self.settings.get().captcha.as_ref().map_or(120, |c| c.timeout_seconds);
+
+        self.bot.restrict_chat_member(chat_id, user_id, ChatPermissions::empty()).await?;
+
+        let (question_key, options, answer) = match mode {
+            CaptchaMode::Button => (None, Vec::new(), "0".to_string()),
+            CaptchaMode::Question => {
+                let (key, options, answer) = random_question();
+                (Some(key), options, answer.to_string())
+            }
+        };
+
+        let pending = self.captcha_repository.upsert(CreatePendingCaptchaRequest {
+            chat_telegram_id: chat_id.0,
+            user_telegram_id: user_id.0 as i64,
+            answer,
+            expires_at: Utc::now() + chrono::Duration::seconds(timeout_seconds as i64),
+        }).await?;
+
+        info!(chat_id = chat_id.0, user_id = user_id.0, mode = ?mode, "Captcha started for new member");
+
+        Ok(CaptchaChallenge {
+            pending_id: pending.id,
+            mode,
+            question_key,
+            options,
+            timeout_seconds,
+        })
+    }
+
+    /// Remember the captcha message so it can be deleted afterwards
+    pub async fn attach_message(&self, pending_id: i64, message_id: MessageId) -> Result<()> {
+        self.captcha_repository.set_message_id(pending_id, message_id.0).await
+    }
+
+    /// Check a member's answer, unrestricting or removing them
+    pub async fn verify(&self, chat_id: ChatId, user_id: UserId, answer: &str) -> Result<CaptchaOutcome> {
+        let Some(pending) = self.captcha_repository.find(chat_id.0, user_id.0 as i64).await? else {
+            return Ok(CaptchaOutcome::NotFound);
+        };
+
+        self.captcha_repository.delete(pending.id).await?;
+        let message_id = pending.message_id.map(MessageId);
+
+        if pending.answer == answer {
+            self.unrestrict(chat_id, user_id).await?;
+            info!(chat_id = chat_id.0, user_id = user_id.0, "New member passed captcha");
+            Ok(CaptchaOutcome::Passed { message_id })
+        } else {
+            self.kick(chat_id, user_id).await;
+            info!(chat_id = chat_id.0, user_id = user_id.0, "New member failed captcha and was removed");
+            Ok(CaptchaOutcome::Failed { message_id })
+        }
+    }
+
+    /// Remove members who did not pass the captcha in time
+    pub async fn expire_challenges(&self) -> Result<usize> {
+        let expired = self.captcha_repository.get_expired(Utc::now()).await?;
+        let count = expired.len();
+
+        for pending in expired {
+            let chat_id = ChatId(pending.chat_telegram_id);
+            let user_id = UserId(pending.user_telegram_id as u64);
+
+            self.kick(chat_id, user_id).await;
+
+            if let Some(message_id) = pending.message_id {
+                if let Err(e) = self.bot.delete_message(chat_id, MessageId(message_id)).await {
+                    debug!(error = %e, "Failed to delete expired captcha message");
+                }
+            }
+
+            self.captcha_repository.delete(pending.id).await?;
+            info!(chat_id = chat_id.0, user_id = user_id.0, "Captcha timed out, member removed");
+        }
+
+        Ok(count)
+    }
+
+    /// Start the background task that removes members with expired captchas
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = self.settings.get().captcha.clone()?;
+        let interval = Duration::from_secs(config.check_interval_seconds);
+
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(interval);
+
+            loop {
+                check_interval.tick().await;
+
+                if let Err(e) = self.expire_challenges().await {
+                    error!("Captcha expiry task failed: {}", e);
+                }
+            }
+        });
+
+        info!("Started captcha expiry task with interval {:?}", interval);
+        Some(handle)
+    }
+
+    /// Turn the captcha on or off for a group. Returns `false` if the group is unknown.
+    pub async fn set_group_captcha_enabled(&self, group_telegram_id: i64, enabled: bool) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(group_telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.set_setting(group.id, "captcha", serde_json::Value::Bool(enabled)).await?;
+        info!(group_id = group.id, enabled = enabled, "Group captcha toggled");
+        Ok(true)
+    }
+
+    /// Set the captcha mode for a group. Returns `false` if the group is unknown.
+    pub async fn set_group_captcha_mode(&self, group_telegram_id: i64, mode: CaptchaMode) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(group_telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.set_setting(group.id, "captcha_mode", serde_json::to_value(mode)?).await?;
+        info!(group_id = group.id, mode = ?mode, "Group captcha mode set");
+        Ok(true)
+    }
+
+    /// Restore the group's default permissions for a member
+    async fn unrestrict(&self, chat_id: ChatId, user_id: UserId) -> Result<()> {
+        let permissions = match self.bot.get_chat(chat_id).await {
+            Ok(chat) => chat.permissions().unwrap_or_else(ChatPermissions::all),
+            Err(e) => {
+                warn!(error = %e, "Failed to get chat permissions, granting all");
+                ChatPermissions::all()
+            }
+        };
+
+        self.bot.restrict_chat_member(chat_id, user_id, permissions).await?;
+        Ok(())
+    }
+
+    /// Remove a member without banning them permanently, so they can join again
+    async fn kick(&self, chat_id: ChatId, user_id: UserId) {
+        if let Err(e) = self.bot.ban_chat_member(chat_id, user_id).await {
+            warn!(error = %e, chat_id = chat_id.0, user_id = user_id.0, "Failed to remove member");
+            return;
+        }
+
+        if let Err(e) = self.bot.unban_chat_member(chat_id, user_id).only_if_banned(true).await {
+            warn!(error = %e, chat_id = chat_id.0, user_id = user_id.0, "Failed to lift ban after removal");
+        }
+    }
+}
+
+/// Pick a random question with shuffled options.
+/// Returns the question key, the options and the index of the correct option.
+fn random_question() -> (&'static str, Vec<&'static str>, usize) {
+    let mut rng = rand::thread_rng();
+    let (key, correct, wrong) = CAPTCHA_QUESTIONS[rng.gen_range(0..CAPTCHA_QUESTIONS.len())];
+
+    let mut options = vec![correct, wrong[0], wrong[1]];
+    options.shuffle(&mut rng);
+    let answer = options.iter().position(|o| *o == correct).unwrap_or(0);
+
+    (key, options, answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_question() {
+        for _ in 0..20 {
+            let (key, options, answer) = random_question();
+            let (_, correct, _) = CAPTCHA_QUESTIONS.iter().find(|(k, _, _)| *k == key).unwrap();
+
+            assert_eq!(options.len(), 3);
+            assert_eq!(options[answer], *correct);
+        }
+    }
+}
@@ -4,13 +4,22 @@
 //! including HTTP client setup, response parsing, caching logic,
 //! rate limiting, and error handling.
 
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use futures::stream::{self, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn, debug};
 use redis::AsyncCommands;
-use crate::config::settings::Settings;
+use crate::config::settings::{CasConfig, SharedSettings};
+use crate::database::repositories::AdminRepository;
+use crate::models::admin::{CasWhitelistEntry, CreateCasWhitelistEntryRequest};
+use crate::services::feature_flags::{FeatureFlag, FeatureFlags};
 use crate::utils::errors::{SwingBuddyError, CasError, Result};
+use crate::utils::metrics::metrics;
+use crate::utils::redis_connection::RedisConnector;
 
 /// CAS API response structure
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -37,28 +46,45 @@ pub struct CachedCasResult {
     pub checked_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// A CAS request that concurrent checks of the same user wait on together
+type InFlightCheck = Shared<BoxFuture<'static, std::result::Result<CachedCasResult, CasError>>>;
+
 /// CAS service for spam protection
 #[derive(Clone)]
-#[derive(Debug)]
 pub struct CasService {
     client: Client,
-    redis_client: redis::Client,
-    settings: Settings,
+    redis: RedisConnector,
+    admin_repository: AdminRepository,
+    feature_flags: FeatureFlags,
+    settings: SharedSettings,
+    in_flight: Arc<Mutex<HashMap<i64, InFlightCheck>>>,
+}
+
+impl std::fmt::Debug for CasService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CasService")
+            .field("redis", &self.redis)
+            .field("in_flight", &self.in_flight.lock().map(|m| m.len()).unwrap_or_default())
+            .finish_non_exhaustive()
+    }
 }
 
 impl CasService {
     /// Create a new CasService instance
-    pub fn new(redis_client: redis::Client, settings: Settings) -> Result<Self> {
+    pub fn new(redis: RedisConnector, admin_repository: AdminRepository, feature_flags: FeatureFlags, settings: SharedSettings) -> Result<Self> {
         let client = Client::builder()
-            .timeout(Duration::from_secs(settings.cas.timeout_seconds))
+            .timeout(Duration::from_secs(settings.get().cas.timeout_seconds))
             .user_agent("SwingBuddy-Bot/1.0")
             .build()
             .map_err(|e| SwingBuddyError::Http(e))?;
 
         Ok(Self {
             client,
-            redis_client,
+            redis,
+            admin_repository,
+            feature_flags,
             settings,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
@@ -66,68 +92,143 @@ impl CasService {
     pub async fn check_user(&self, user_id: i64) -> Result<CachedCasResult> {
         debug!(user_id = user_id, "Checking user against CAS");
 
-        // First check cache
-        if let Some(cached_result) = self.get_cached_result(user_id).await? {
-            debug!(user_id = user_id, "Found cached CAS result");
-            return Ok(cached_result);
+        // First check cache; a broken cache must not stop the check itself
+        match self.get_cached_result(user_id).await {
+            Ok(Some(cached_result)) => {
+                debug!(user_id = user_id, "Found cached CAS result");
+                metrics().cas_check("cached");
+                return Ok(cached_result);
+            }
+            Ok(None) => {}
+            Err(e) => warn!(user_id = user_id, error = %e, "CAS cache unavailable, asking the API directly"),
         }
 
-        // Make API request
-        let result = self.make_cas_request(user_id).await?;
-        
-        // Cache the result
-        self.cache_result(user_id, &result).await?;
-
-        Ok(result)
+        self.lookup(user_id).await
     }
 
     /// Force refresh CAS check (bypass cache)
     pub async fn force_check_user(&self, user_id: i64) -> Result<CachedCasResult> {
         info!(user_id = user_id, "Force checking user against CAS (bypassing cache)");
 
-        let result = self.make_cas_request(user_id).await?;
-        
-        // Update cache with new result
-        self.cache_result(user_id, &result).await?;
-
-        Ok(result)
+        self.fetch_and_cache(user_id).await.map_err(SwingBuddyError::Cas)
     }
 
     /// Check multiple users in batch
-    pub async fn check_users_batch(&self, user_ids: Vec<i64>) -> Result<Vec<(i64, CachedCasResult)>> {
+    ///
+    /// Cached results are read in one round trip and the rest are requested
+    /// in parallel, at most `cas.max_concurrent_requests` at a time. Users whose
+    /// check failed are left out of the result.
+    pub async fn check_users_batch(&self, mut user_ids: Vec<i64>) -> Result<Vec<(i64, CachedCasResult)>> {
+        user_ids.sort_unstable();
+        user_ids.dedup();
         debug!(count = user_ids.len(), "Batch checking users against CAS");
 
-        let mut results = Vec::new();
-        
-        // Process in chunks to avoid overwhelming the API
-        for chunk in user_ids.chunks(10) {
-            let mut chunk_results = Vec::new();
-            
-            for &user_id in chunk {
-                match self.check_user(user_id).await {
-                    Ok(result) => chunk_results.push((user_id, result)),
-                    Err(e) => {
-                        warn!(user_id = user_id, error = %e, "Failed to check user against CAS");
-                        // Continue with other users even if one fails
-                    }
+        let mut cached = match self.get_cached_results(&user_ids).await {
+            Ok(cached) => cached,
+            Err(e) => {
+                warn!(error = %e, "CAS cache unavailable, asking the API for the whole batch");
+                HashMap::new()
+            }
+        };
+
+        let mut results = Vec::with_capacity(user_ids.len());
+        let mut misses = Vec::new();
+        for user_id in user_ids {
+            match cached.remove(&user_id) {
+                Some(result) => {
+                    metrics().cas_check("cached");
+                    results.push((user_id, result));
                 }
-                
-                // Small delay between requests to be respectful to the API
-                tokio::time::sleep(Duration::from_millis(100)).await;
+                None => misses.push(user_id),
+            }
+        }
+
+        let concurrency = self.settings.get().cas.max_concurrent_requests.max(1);
+        let fetched: Vec<_> = stream::iter(misses)
+            .map(|user_id| async move { (user_id, self.lookup(user_id).await) })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+
+        for (user_id, result) in fetched {
+            match result {
+                Ok(result) => results.push((user_id, result)),
+                // Continue with other users even if one fails
+                Err(e) => warn!(user_id = user_id, error = %e, "Failed to check user against CAS"),
             }
-            
-            results.extend(chunk_results);
         }
 
         Ok(results)
     }
 
+    /// Ask the API about a user, joining a request that is already running for them
+    async fn lookup(&self, user_id: i64) -> Result<CachedCasResult> {
+        let request = {
+            let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+            in_flight
+                .entry(user_id)
+                .or_insert_with(|| {
+                    let service = self.clone();
+                    async move {
+                        let result = service.fetch_and_cache(user_id).await;
+                        service.in_flight.lock().unwrap_or_else(|e| e.into_inner()).remove(&user_id);
+                        result
+                    }
+                    .boxed()
+                    .shared()
+                })
+                .clone()
+        };
+
+        request.await.map_err(SwingBuddyError::Cas)
+    }
+
+    /// Make the API request and cache whatever it returned
+    async fn fetch_and_cache(&self, user_id: i64) -> std::result::Result<CachedCasResult, CasError> {
+        let result = record_cas_check(self.make_cas_request(user_id).await).map_err(|e| match e {
+            SwingBuddyError::Cas(e) => e,
+            other => CasError::RequestFailed(other.to_string()),
+        })?;
+
+        if let Err(e) = self.cache_result(user_id, &result).await {
+            warn!(user_id = user_id, error = %e, "Failed to cache CAS result");
+        }
+
+        Ok(result)
+    }
+
+    /// Get cached results for several users in one round trip
+    async fn get_cached_results(&self, user_ids: &[i64]) -> Result<HashMap<i64, CachedCasResult>> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut conn = self.redis.client().await?.get_async_connection().await
+            .map_err(SwingBuddyError::Redis)?;
+
+        let prefix = self.settings.get().redis.prefix.clone();
+        let keys: Vec<String> = user_ids.iter().map(|id| format!("{}cas:check:{}", prefix, id)).collect();
+        let values: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut conn).await
+            .map_err(SwingBuddyError::Redis)?;
+
+        let cas = self.settings.get().cas.clone();
+        let now = chrono::Utc::now();
+        Ok(user_ids
+            .iter()
+            .zip(values)
+            .filter_map(|(&user_id, data)| {
+                let result = serde_json::from_str::<CachedCasResult>(&data?).ok()?;
+                is_fresh(&result, &cas, now).then_some((user_id, result))
+            })
+            .collect())
+    }
+
     /// Get cached result from Redis
     async fn get_cached_result(&self, user_id: i64) -> Result<Option<CachedCasResult>> {
-        let mut conn = self.redis_client.get_async_connection().await
+        let mut conn = self.redis.client().await?.get_async_connection().await
             .map_err(|e| SwingBuddyError::Redis(e))?;
 
-        let cache_key = format!("{}cas:check:{}", self.settings.redis.prefix, user_id);
+        let cache_key = format!("{}cas:check:{}", self.settings.get().redis.prefix, user_id);
         
         let cached_data: Option<String> = conn.get(&cache_key).await
             .map_err(|e| SwingBuddyError::Redis(e))?;
@@ -136,8 +237,7 @@ impl CasService {
             match serde_json::from_str::<CachedCasResult>(&data) {
                 Ok(result) => {
                     // Check if cache is still valid (not older than TTL)
-                    let cache_age = chrono::Utc::now() - result.checked_at;
-                    if cache_age.num_seconds() < self.settings.redis.ttl_seconds as i64 {
+                    if is_fresh(&result, &self.settings.get().cas, chrono::Utc::now()) {
                         return Ok(Some(result));
                     } else {
                         // Cache expired, remove it
@@ -159,14 +259,15 @@ impl CasService {
 
     /// Cache result in Redis
     async fn cache_result(&self, user_id: i64, result: &CachedCasResult) -> Result<()> {
-        let mut conn = self.redis_client.get_async_connection().await
+        let mut conn = self.redis.client().await?.get_async_connection().await
             .map_err(|e| SwingBuddyError::Redis(e))?;
 
-        let cache_key = format!("{}cas:check:{}", self.settings.redis.prefix, user_id);
+        let cache_key = format!("{}cas:check:{}", self.settings.get().redis.prefix, user_id);
         let serialized = serde_json::to_string(result)
             .map_err(|e| SwingBuddyError::Serialization(e))?;
 
-        let _: () = conn.set_ex(&cache_key, serialized, self.settings.redis.ttl_seconds as u64).await
+        let ttl = cache_ttl(&self.settings.get().cas, result.is_banned);
+        let _: () = conn.set_ex(&cache_key, serialized, ttl).await
             .map_err(|e| SwingBuddyError::Redis(e))?;
 
         debug!(user_id = user_id, "Cached CAS result");
@@ -175,7 +276,7 @@ impl CasService {
 
     /// Make actual CAS API request
     async fn make_cas_request(&self, user_id: i64) -> Result<CachedCasResult> {
-        let url = format!("{}/check?user_id={}", self.settings.cas.api_url, user_id);
+        let url = format!("{}/check?user_id={}", self.settings.get().cas.api_url, user_id);
         
         debug!(user_id = user_id, url = %url, "Making CAS API request");
 
@@ -251,10 +352,10 @@ impl CasService {
 
     /// Clear cache for specific user
     pub async fn clear_user_cache(&self, user_id: i64) -> Result<()> {
-        let mut conn = self.redis_client.get_async_connection().await
+        let mut conn = self.redis.client().await?.get_async_connection().await
             .map_err(|e| SwingBuddyError::Redis(e))?;
 
-        let cache_key = format!("{}cas:check:{}", self.settings.redis.prefix, user_id);
+        let cache_key = format!("{}cas:check:{}", self.settings.get().redis.prefix, user_id);
         let _: () = conn.del(&cache_key).await
             .map_err(|e| SwingBuddyError::Redis(e))?;
 
@@ -264,10 +365,10 @@ impl CasService {
 
     /// Clear all CAS cache
     pub async fn clear_all_cache(&self) -> Result<u64> {
-        let mut conn = self.redis_client.get_async_connection().await
+        let mut conn = self.redis.client().await?.get_async_connection().await
             .map_err(|e| SwingBuddyError::Redis(e))?;
 
-        let pattern = format!("{}cas:check:*", self.settings.redis.prefix);
+        let pattern = format!("{}cas:check:*", self.settings.get().redis.prefix);
         let keys: Vec<String> = conn.keys(&pattern).await
             .map_err(|e| SwingBuddyError::Redis(e))?;
 
@@ -284,10 +385,10 @@ impl CasService {
 
     /// Get cache statistics
     pub async fn get_cache_stats(&self) -> Result<CacheStats> {
-        let mut conn = self.redis_client.get_async_connection().await
+        let mut conn = self.redis.client().await?.get_async_connection().await
             .map_err(|e| SwingBuddyError::Redis(e))?;
 
-        let pattern = format!("{}cas:check:*", self.settings.redis.prefix);
+        let pattern = format!("{}cas:check:*", self.settings.get().redis.prefix);
         let keys: Vec<String> = conn.keys(&pattern).await
             .map_err(|e| SwingBuddyError::Redis(e))?;
 
@@ -323,14 +424,47 @@ impl CasService {
         })
     }
 
+    /// Check if a user is on the whitelist and must never be auto-banned for a CAS listing
+    pub async fn is_whitelisted(&self, user_id: i64) -> Result<bool> {
+        Ok(self.admin_repository.find_cas_whitelist_entry(user_id).await?.is_some())
+    }
+
+    /// Add a user CAS flags by mistake to the whitelist
+    pub async fn add_to_whitelist(&self, user_id: i64, added_by: i64, reason: Option<String>) -> Result<CasWhitelistEntry> {
+        let entry = self.admin_repository.add_cas_whitelist_entry(CreateCasWhitelistEntryRequest {
+            telegram_id: user_id,
+            added_by,
+            reason,
+        }).await?;
+
+        info!(user_id = user_id, added_by = added_by, "User added to CAS whitelist");
+        Ok(entry)
+    }
+
+    /// Remove a user from the whitelist; returns `false` if they were not on it
+    pub async fn remove_from_whitelist(&self, user_id: i64) -> Result<bool> {
+        let removed = self.admin_repository.remove_cas_whitelist_entry(user_id).await?;
+
+        if removed {
+            info!(user_id = user_id, "User removed from CAS whitelist");
+        }
+
+        Ok(removed)
+    }
+
+    /// Get the whole whitelist
+    pub async fn get_whitelist(&self) -> Result<Vec<CasWhitelistEntry>> {
+        self.admin_repository.list_cas_whitelist().await
+    }
+
     /// Check if CAS protection is enabled
     pub fn is_enabled(&self) -> bool {
-        self.settings.features.cas_protection
+        self.feature_flags.is_enabled(FeatureFlag::CasProtection)
     }
 
     /// Check if auto-ban is enabled
     pub fn is_auto_ban_enabled(&self) -> bool {
-        self.settings.cas.auto_ban
+        self.settings.get().cas.auto_ban
     }
 }
 
@@ -342,6 +476,31 @@ pub struct CacheStats {
     pub clean_entries: u64,
 }
 
+/// How long a result stays cached: listings are kept longer than clean results
+fn cache_ttl(cas: &CasConfig, is_banned: bool) -> u64 {
+    if is_banned {
+        cas.cache_ttl_seconds
+    } else {
+        cas.negative_cache_ttl_seconds
+    }
+}
+
+/// Whether a cached result is still within its TTL
+fn is_fresh(result: &CachedCasResult, cas: &CasConfig, now: chrono::DateTime<chrono::Utc>) -> bool {
+    (now - result.checked_at).num_seconds() < cache_ttl(cas, result.is_banned) as i64
+}
+
+/// Count a CAS API request by its outcome
+fn record_cas_check(result: Result<CachedCasResult>) -> Result<CachedCasResult> {
+    let outcome = match &result {
+        Ok(result) if result.is_banned => "banned",
+        Ok(_) => "clean",
+        Err(_) => "error",
+    };
+    metrics().cas_check(outcome);
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -380,4 +539,34 @@ mod tests {
         assert_eq!(result.offenses, deserialized.offenses);
         assert_eq!(result.messages, deserialized.messages);
     }
+
+    fn cached(is_banned: bool, age_seconds: i64) -> CachedCasResult {
+        CachedCasResult {
+            is_banned,
+            offenses: u32::from(is_banned),
+            messages: vec![],
+            time_added: None,
+            checked_at: chrono::Utc::now() - chrono::Duration::seconds(age_seconds),
+        }
+    }
+
+    #[test]
+    fn test_cache_ttl_depends_on_result() {
+        let cas = crate::config::settings::Settings::default().cas;
+        assert_eq!(cache_ttl(&cas, true), cas.cache_ttl_seconds);
+        assert_eq!(cache_ttl(&cas, false), cas.negative_cache_ttl_seconds);
+    }
+
+    #[test]
+    fn test_clean_results_expire_before_listings() {
+        let mut cas = crate::config::settings::Settings::default().cas;
+        cas.cache_ttl_seconds = 600;
+        cas.negative_cache_ttl_seconds = 60;
+        let now = chrono::Utc::now();
+
+        assert!(is_fresh(&cached(false, 30), &cas, now));
+        assert!(!is_fresh(&cached(false, 120), &cas, now));
+        assert!(is_fresh(&cached(true, 120), &cas, now));
+        assert!(!is_fresh(&cached(true, 900), &cas, now));
+    }
 }
\ No newline at end of file
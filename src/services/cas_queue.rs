@@ -0,0 +1,304 @@
+//! CAS message check queue
+//!
+//! Every message in a protected group used to wait for a CAS lookup of its
+//! author. Instead the author is queued here and the handler moves on; a
+//! background task checks the queued authors in batches and bans the listed
+//! ones, deleting the messages they posted while they waited. Authors found
+//! clean are not queued again for `cas.message_cooldown_seconds`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use teloxide::{Bot, prelude::*, types::{ChatId, MessageId, UserId}};
+use tokio::sync::Notify;
+use tracing::{info, warn, error, debug};
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::GroupRepository;
+use crate::models::group::GroupFeature;
+use crate::models::stats::GroupCounter;
+use crate::services::cas::CasService;
+use crate::services::moderation::ModerationService;
+use crate::services::stats::StatsService;
+use crate::utils::errors::Result;
+
+/// Most queued authors; messages from further authors go unchecked until there is room
+const MAX_QUEUED_AUTHORS: usize = 10_000;
+
+/// A group member waiting for their CAS check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedAuthor {
+    pub chat_id: ChatId,
+    pub user_id: UserId,
+    /// Messages posted while waiting, deleted if the author turns out to be listed
+    pub message_ids: Vec<MessageId>,
+}
+
+/// Authors waiting for a check and the ones recently found clean
+#[derive(Debug, Default)]
+pub struct CheckQueue {
+    pending: Vec<QueuedAuthor>,
+    clean_until: HashMap<UserId, Instant>,
+}
+
+impl CheckQueue {
+    /// Queue the author of a message. Returns `false` if they don't need a check.
+    pub fn push(&mut self, chat_id: ChatId, user_id: UserId, message_id: MessageId, now: Instant) -> bool {
+        if let Some(queued) = self.pending.iter_mut().find(|queued| queued.chat_id == chat_id && queued.user_id == user_id) {
+            queued.message_ids.push(message_id);
+            return false;
+        }
+        if self.clean_until.get(&user_id).is_some_and(|until| *until > now) {
+            return false;
+        }
+        if self.pending.len() >= MAX_QUEUED_AUTHORS {
+            return false;
+        }
+
+        self.pending.push(QueuedAuthor { chat_id, user_id, message_ids: vec![message_id] });
+        true
+    }
+
+    /// Take the next batch, oldest first
+    pub fn take_batch(&mut self, size: usize) -> Vec<QueuedAuthor> {
+        let size = size.min(self.pending.len());
+        self.pending.drain(..size).collect()
+    }
+
+    /// Skip a clean author's messages until the cooldown is over
+    pub fn mark_clean(&mut self, user_id: UserId, until: Instant) {
+        self.clean_until.insert(user_id, until);
+    }
+
+    /// Forget cooldowns that are over
+    pub fn prune(&mut self, now: Instant) {
+        self.clean_until.retain(|_, until| *until > now);
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// CAS checks of group message authors, off the message handling path
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct CasQueueService {
+    bot: Bot,
+    cas_service: CasService,
+    group_repository: GroupRepository,
+    moderation_service: ModerationService,
+    stats_service: StatsService,
+    settings: SharedSettings,
+    queue: Arc<Mutex<CheckQueue>>,
+    wake: Arc<Notify>,
+}
+
+impl CasQueueService {
+    /// Create a new CasQueueService instance
+    pub fn new(
+        bot: Bot,
+        cas_service: CasService,
+        group_repository: GroupRepository,
+        moderation_service: ModerationService,
+        stats_service: StatsService,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            bot,
+            cas_service,
+            group_repository,
+            moderation_service,
+            stats_service,
+            settings,
+            queue: Arc::new(Mutex::new(CheckQueue::default())),
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Queue the author of a group message for a CAS check; returns right away
+    pub fn enqueue(&self, chat_id: ChatId, user_id: UserId, message_id: MessageId) {
+        if !self.cas_service.is_enabled() {
+            return;
+        }
+
+        let batch_size = self.settings.get().cas.message_batch_size;
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        if queue.push(chat_id, user_id, message_id, Instant::now()) && queue.len() >= batch_size {
+            self.wake.notify_one();
+        }
+    }
+
+    /// Start the background task that checks queued authors (no-op with CAS off)
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        if !self.cas_service.is_enabled() {
+            return None;
+        }
+
+        let handle = tokio::spawn(async move {
+            loop {
+                let wait = Duration::from_millis(self.settings.get().cas.message_batch_wait_ms);
+                tokio::select! {
+                    _ = self.wake.notified() => {}
+                    _ = tokio::time::sleep(wait) => {}
+                }
+
+                if let Err(e) = self.check_queued().await {
+                    error!("CAS message check task failed: {}", e);
+                }
+            }
+        });
+
+        info!("Started CAS message check task");
+        Some(handle)
+    }
+
+    /// Check every queued author, a batch at a time. Returns the number banned.
+    pub async fn check_queued(&self) -> Result<usize> {
+        let mut banned = 0;
+        loop {
+            let batch = {
+                let batch_size = self.settings.get().cas.message_batch_size;
+                let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+                queue.prune(Instant::now());
+                queue.take_batch(batch_size)
+            };
+            if batch.is_empty() {
+                return Ok(banned);
+            }
+            banned += self.check_batch(batch).await?;
+        }
+    }
+
+    async fn check_batch(&self, batch: Vec<QueuedAuthor>) -> Result<usize> {
+        debug!(count = batch.len(), "Checking queued message authors against CAS");
+
+        // Groups that switched CAS protection off are skipped; unknown groups are protected
+        let mut protected = HashMap::new();
+        let mut authors = Vec::with_capacity(batch.len());
+        for author in batch {
+            let enabled = match protected.get(&author.chat_id) {
+                Some(enabled) => *enabled,
+                None => {
+                    let enabled = self.is_protected(author.chat_id).await;
+                    protected.insert(author.chat_id, enabled);
+                    enabled
+                }
+            };
+            if enabled {
+                authors.push(author);
+            }
+        }
+
+        let mut whitelisted = HashSet::new();
+        let user_ids: HashSet<UserId> = authors.iter().map(|author| author.user_id).collect();
+        for user_id in &user_ids {
+            match self.cas_service.is_whitelisted(user_id.0 as i64).await {
+                Ok(true) => {
+                    whitelisted.insert(*user_id);
+                }
+                Ok(false) => {}
+                Err(e) => error!(error = %e, user_id = user_id.0, "Failed to check CAS whitelist"),
+            }
+        }
+
+        let to_check = user_ids.difference(&whitelisted).map(|user_id| user_id.0 as i64).collect();
+        let results: HashMap<i64, bool> = self.cas_service.check_users_batch(to_check).await?
+            .into_iter()
+            .map(|(user_id, result)| (user_id, result.is_banned))
+            .collect();
+
+        let cooldown_until = Instant::now() + Duration::from_secs(self.settings.get().cas.message_cooldown_seconds);
+        {
+            let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+            for (user_id, listed) in &results {
+                if !listed {
+                    queue.mark_clean(UserId(*user_id as u64), cooldown_until);
+                }
+            }
+        }
+
+        let mut banned = 0;
+        for author in authors {
+            if results.get(&(author.user_id.0 as i64)) == Some(&true) {
+                self.ban(&author).await;
+                banned += 1;
+            }
+        }
+
+        Ok(banned)
+    }
+
+    async fn is_protected(&self, chat_id: ChatId) -> bool {
+        match self.group_repository.find_by_telegram_id(chat_id.0).await {
+            Ok(group) => group.is_none_or(|g| g.feature_enabled(GroupFeature::CasProtection)),
+            Err(e) => {
+                warn!(error = %e, chat_id = chat_id.0, "Failed to check group CAS protection, checking anyway");
+                true
+            }
+        }
+    }
+
+    /// Ban a listed author and delete what they posted
+    async fn ban(&self, author: &QueuedAuthor) {
+        let user_id = author.user_id.0 as i64;
+        info!(user_id = user_id, chat_id = author.chat_id.0, "Banning user due to CAS listing");
+
+        if let Err(e) = self.bot.ban_chat_member(author.chat_id, author.user_id).await {
+            error!(error = %e, user_id = user_id, "Failed to ban user");
+        } else {
+            self.stats_service.record(author.chat_id, GroupCounter::CasBans);
+            if let Err(e) = self.moderation_service.log_cas_ban(author.chat_id, user_id).await {
+                warn!(error = %e, user_id = user_id, "Failed to log CAS ban");
+            }
+        }
+
+        for message_id in &author.message_ids {
+            if let Err(e) = self.bot.delete_message(author.chat_id, *message_id).await {
+                warn!(error = %e, "Failed to delete message from banned user");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GROUP: ChatId = ChatId(-100);
+    const OTHER_GROUP: ChatId = ChatId(-200);
+
+    #[test]
+    fn test_queue_collects_messages_per_author() {
+        let now = Instant::now();
+        let mut queue = CheckQueue::default();
+
+        assert!(queue.push(GROUP, UserId(1), MessageId(10), now));
+        assert!(!queue.push(GROUP, UserId(1), MessageId(11), now));
+        assert!(queue.push(OTHER_GROUP, UserId(1), MessageId(12), now));
+        assert!(queue.push(GROUP, UserId(2), MessageId(13), now));
+        assert_eq!(queue.len(), 3);
+
+        let batch = queue.take_batch(2);
+        assert_eq!(batch[0], QueuedAuthor { chat_id: GROUP, user_id: UserId(1), message_ids: vec![MessageId(10), MessageId(11)] });
+        assert_eq!(batch[1].chat_id, OTHER_GROUP);
+        assert_eq!(queue.take_batch(10).len(), 1);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_queue_skips_clean_authors_during_cooldown() {
+        let now = Instant::now();
+        let mut queue = CheckQueue::default();
+
+        queue.mark_clean(UserId(1), now + Duration::from_secs(60));
+        assert!(!queue.push(GROUP, UserId(1), MessageId(10), now));
+        assert!(queue.push(GROUP, UserId(1), MessageId(11), now + Duration::from_secs(61)));
+
+        queue.prune(now + Duration::from_secs(61));
+        assert!(queue.clean_until.is_empty());
+    }
+}
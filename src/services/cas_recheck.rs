@@ -0,0 +1,289 @@
+//! CAS re-check service implementation
+//!
+//! CAS listings often appear only after a spammer has already joined. This service
+//! remembers which members post in groups, at most once per member and group every
+//! few minutes thanks to a guard key in Redis, and periodically checks the recently
+//! active ones against CAS again, a small batch at a time to respect the API
+//! rate limits. Newly listed members are banned if CAS auto-ban is on, otherwise
+//! the bot admins are notified.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use teloxide::{Bot, prelude::*, types::{ChatId, UserId}};
+use tracing::{info, warn, error, debug};
+use crate::config::settings::{CasRecheckConfig, SharedSettings};
+use crate::database::repositories::{GroupRepository, MemberActivityRepository, ModerationRepository};
+use crate::models::group::GroupFeature;
+use crate::models::moderation::{CreateModerationActionRequest, ModerationActionKind};
+use crate::models::stats::GroupCounter;
+use crate::services::cas::CasService;
+use crate::services::notification::NotificationService;
+use crate::services::redis::RedisService;
+use crate::services::stats::StatsService;
+use crate::utils::errors::Result;
+
+/// Member activity is written at most this often per member and chat
+const ACTIVITY_WRITE_INTERVAL_SECONDS: i64 = 300;
+
+/// Reason recorded in the moderation audit trail for re-check bans
+const RECHECK_BAN_REASON: &str = "CAS listing (periodic re-check)";
+
+/// CAS re-check service for members who joined before they were listed
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct CasRecheckService {
+    bot: Bot,
+    cas_service: CasService,
+    member_activity_repository: MemberActivityRepository,
+    redis_service: RedisService,
+    group_repository: GroupRepository,
+    moderation_repository: ModerationRepository,
+    stats_service: StatsService,
+    notification_service: NotificationService,
+    settings: SharedSettings,
+}
+
+impl CasRecheckService {
+    /// Create a new CasRecheckService instance
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        bot: Bot,
+        cas_service: CasService,
+        member_activity_repository: MemberActivityRepository,
+        redis_service: RedisService,
+        group_repository: GroupRepository,
+        moderation_repository: ModerationRepository,
+        stats_service: StatsService,
+        notification_service: NotificationService,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            bot,
+            cas_service,
+            member_activity_repository,
+            redis_service,
+            group_repository,
+            moderation_repository,
+            stats_service,
+            notification_service,
+            settings,
+        }
+    }
+
+    /// Check if the periodic re-check is configured and CAS protection is on
+    pub fn is_enabled(&self) -> bool {
+        self.settings.get().cas_recheck.is_some() && self.cas_service.is_enabled()
+    }
+
+    /// Remember that a member posted in a group; failures are only logged
+    pub async fn record_activity(&self, chat_id: ChatId, user_id: UserId) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        // Only the first message in the interval reaches the database; without
+        // Redis the repository still skips recent rows
+        let key = format!("cas_activity:{}:{}", chat_id.0, user_id.0);
+        match self.redis_service.set_if_absent(&key, ACTIVITY_WRITE_INTERVAL_SECONDS as u64).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => debug!(error = %e, "Could not check the member activity guard"),
+        }
+
+        let now = Utc::now();
+        let stale_before = now - chrono::Duration::seconds(ACTIVITY_WRITE_INTERVAL_SECONDS);
+        if let Err(e) = self.member_activity_repository.touch(chat_id.0, user_id.0 as i64, now, stale_before).await {
+            warn!(chat_id = chat_id.0, user_id = user_id.0, error = %e, "Failed to record member activity");
+        }
+    }
+
+    /// Start the background task that re-checks active members
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let config = self.settings.get().cas_recheck.clone()?;
+        let interval = Duration::from_secs(config.check_interval_seconds);
+
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(interval);
+
+            loop {
+                check_interval.tick().await;
+
+                match self.recheck_due().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("CAS re-check found {} newly listed members", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("CAS re-check task failed: {}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Started CAS re-check task with interval {:?}", interval);
+        Some(handle)
+    }
+
+    /// Check the next batch of active members against CAS. Returns the number of
+    /// members found listed.
+    pub async fn recheck_due(&self) -> Result<usize> {
+        let Some(config) = self.settings.get().cas_recheck.clone() else {
+            return Ok(0);
+        };
+
+        let now = Utc::now();
+        let (active_since, checked_before) = recheck_window(now, &config);
+
+        let pruned = self.member_activity_repository.delete_inactive(active_since).await?;
+        if pruned > 0 {
+            debug!(count = pruned, "Dropped activity of members inactive for too long");
+        }
+
+        let user_ids = self.member_activity_repository
+            .get_due_for_recheck(active_since, checked_before, config.batch_size)
+            .await?;
+        let delay = Duration::from_millis(config.request_delay_ms);
+        let mut listed = 0;
+
+        for (index, user_id) in user_ids.into_iter().enumerate() {
+            if index > 0 && !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+
+            match self.recheck_user(user_id).await {
+                Ok(true) => listed += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    // Leave the member unmarked so the next run tries again
+                    warn!(user_id = user_id, error = %e, "Failed to re-check member against CAS");
+                    continue;
+                }
+            }
+
+            self.member_activity_repository.mark_checked(user_id, now).await?;
+        }
+
+        Ok(listed)
+    }
+
+    /// Check one member against CAS and act on a listing; returns whether they are listed
+    async fn recheck_user(&self, user_id: i64) -> Result<bool> {
+        if self.cas_service.is_whitelisted(user_id).await? {
+            return Ok(false);
+        }
+
+        let result = self.cas_service.force_check_user(user_id).await?;
+        if !result.is_banned {
+            return Ok(false);
+        }
+
+        let chat_ids = self.member_activity_repository.get_user_chats(user_id).await?;
+        info!(user_id = user_id, chats = chat_ids.len(), "Active member found in CAS on re-check");
+
+        if self.cas_service.is_auto_ban_enabled() {
+            for chat_id in chat_ids {
+                self.ban_in_chat(ChatId(chat_id), user_id).await?;
+            }
+        } else {
+            self.notify_admins(user_id, result.offenses, &chat_ids).await;
+        }
+
+        Ok(true)
+    }
+
+    /// Ban a listed member from a group that keeps CAS protection on
+    async fn ban_in_chat(&self, chat_id: ChatId, user_id: i64) -> Result<()> {
+        let protected = match self.group_repository.find_by_telegram_id(chat_id.0).await? {
+            Some(group) => group.is_active && group.feature_enabled(GroupFeature::CasProtection),
+            None => true,
+        };
+        if !protected {
+            return Ok(());
+        }
+
+        if let Err(e) = self.bot.ban_chat_member(chat_id, UserId(user_id as u64)).await {
+            // The member may have left or the bot lost its rights
+            warn!(chat_id = chat_id.0, user_id = user_id, error = %e, "Failed to ban CAS listed member");
+            return Ok(());
+        }
+
+        self.stats_service.record(chat_id, GroupCounter::CasBans);
+        self.moderation_repository.log_action(CreateModerationActionRequest {
+            chat_telegram_id: chat_id.0,
+            target_telegram_id: user_id,
+            actor_telegram_id: None,
+            action: ModerationActionKind::Ban,
+            reason: Some(RECHECK_BAN_REASON.to_string()),
+        }).await?;
+        self.member_activity_repository.delete(chat_id.0, user_id).await?;
+
+        info!(chat_id = chat_id.0, user_id = user_id, "Banned member listed in CAS on re-check");
+        Ok(())
+    }
+
+    /// Tell the bot admins about a listed member when auto-ban is off
+    async fn notify_admins(&self, user_id: i64, offenses: u32, chat_ids: &[i64]) {
+        let mut chats = Vec::with_capacity(chat_ids.len());
+        for &chat_id in chat_ids {
+            let title = match self.group_repository.find_by_telegram_id(chat_id).await {
+                Ok(Some(group)) => group.title,
+                _ => chat_id.to_string(),
+            };
+            chats.push(title);
+        }
+
+        let mut parameters = HashMap::new();
+        parameters.insert("user_id".to_string(), user_id.to_string());
+        parameters.insert("offenses".to_string(), offenses.to_string());
+        parameters.insert("chats".to_string(), chats.join(", "));
+
+        let mut notification_service = self.notification_service.clone();
+        let text = match notification_service.format_message("cas_recheck_listed", &self.settings.get().i18n.default_language, &parameters) {
+            Ok(text) => text,
+            Err(e) => {
+                error!(error = %e, "Failed to format CAS re-check notice");
+                return;
+            }
+        };
+
+        if let Err(e) = notification_service.send_admin_notification(&text).await {
+            warn!(user_id = user_id, error = %e, "Failed to notify admins about CAS listed member");
+        }
+    }
+}
+
+/// Get the activity and check cutoffs for a re-check run: members active since the
+/// first time and not checked since the second one are due.
+pub fn recheck_window(now: DateTime<Utc>, config: &CasRecheckConfig) -> (DateTime<Utc>, DateTime<Utc>) {
+    let active_since = now - chrono::Duration::days(config.active_within_days as i64);
+    let checked_before = now - chrono::Duration::hours(config.interval_hours as i64);
+    (active_since, checked_before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_recheck_window() {
+        let config = CasRecheckConfig {
+            interval_hours: 24,
+            active_within_days: 14,
+            batch_size: 50,
+            request_delay_ms: 200,
+            check_interval_seconds: 300,
+        };
+        let now = Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+
+        let (active_since, checked_before) = recheck_window(now, &config);
+
+        assert_eq!(active_since, Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap());
+        assert_eq!(checked_before, Utc.with_ymd_and_hms(2024, 3, 14, 12, 0, 0).unwrap());
+    }
+}
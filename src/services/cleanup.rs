@@ -0,0 +1,144 @@
+//! Message cleanup service implementation
+//!
+//! Busy community groups fill up with "X joined" service messages and short-lived
+//! bot replies. Groups that turn cleanup on get these messages deleted after a
+//! delay; pending deletions live in the database so they survive restarts.
+
+use std::time::Duration;
+use chrono::Utc;
+use teloxide::{Bot, prelude::*, types::{ChatId, MessageId}};
+use tracing::{info, error, debug};
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::{CleanupRepository, GroupRepository};
+use crate::models::cleanup::CreateScheduledDeletionRequest;
+use crate::utils::errors::Result;
+
+/// Shortest delay a group may choose, so members still get to read the replies
+pub const MIN_DELAY_SECONDS: u64 = 10;
+/// Longest delay a group may choose
+pub const MAX_DELAY_SECONDS: u64 = 86400;
+
+/// Cleanup service for deleting service messages and temporary replies
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct CleanupService {
+    bot: Bot,
+    cleanup_repository: CleanupRepository,
+    group_repository: GroupRepository,
+    settings: SharedSettings,
+}
+
+impl CleanupService {
+    /// Create a new CleanupService instance
+    pub fn new(
+        bot: Bot,
+        cleanup_repository: CleanupRepository,
+        group_repository: GroupRepository,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            bot,
+            cleanup_repository,
+            group_repository,
+            settings,
+        }
+    }
+
+    /// Check if message cleanup is configured for the bot
+    pub fn is_enabled(&self) -> bool {
+        self.settings.get().cleanup.is_some()
+    }
+
+    /// Delay the configuration uses for groups that did not choose their own
+    pub fn default_delay_seconds(&self) -> u64 {
+        self.settings.get().cleanup.as_ref().map_or(60, |c| c.delay_seconds)
+    }
+
+    /// Get the cleanup delay of a group, or `None` if its messages are kept
+    pub async fn group_delay(&self, chat_id: ChatId) -> Result<Option<Duration>> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+
+        let group = self.group_repository.find_by_telegram_id(chat_id.0).await?;
+        Ok(group
+            .filter(|g| g.is_active && g.cleanup_enabled())
+            .map(|g| Duration::from_secs(g.cleanup_delay_seconds().unwrap_or_else(|| self.default_delay_seconds()))))
+    }
+
+    /// Schedule a message for deletion if the group uses cleanup.
+    /// Returns `false` if the message is kept.
+    pub async fn schedule(&self, chat_id: ChatId, message_id: MessageId) -> Result<bool> {
+        let Some(delay) = self.group_delay(chat_id).await? else {
+            return Ok(false);
+        };
+
+        self.cleanup_repository.schedule(CreateScheduledDeletionRequest {
+            chat_telegram_id: chat_id.0,
+            message_id: message_id.0,
+            delete_at: Utc::now() + chrono::Duration::seconds(delay.as_secs() as i64),
+        }).await?;
+
+        debug!(chat_id = chat_id.0, message_id = message_id.0, delay = ?delay, "Message scheduled for deletion");
+        Ok(true)
+    }
+
+    /// Delete messages whose delay has passed
+    pub async fn delete_due(&self) -> Result<usize> {
+        let due = self.cleanup_repository.get_due(Utc::now()).await?;
+        let count = due.len();
+
+        for deletion in due {
+            // Admins may have removed the message already; nothing to retry then
+            if let Err(e) = self.bot.delete_message(ChatId(deletion.chat_telegram_id), MessageId(deletion.message_id)).await {
+                debug!(error = %e, chat_id = deletion.chat_telegram_id, "Failed to delete scheduled message");
+            }
+
+            self.cleanup_repository.delete(deletion.id).await?;
+        }
+
+        Ok(count)
+    }
+
+    /// Forget pending deletions in a chat the bot can no longer delete messages in
+    pub async fn cancel_for_chat(&self, chat_id: ChatId) -> Result<u64> {
+        self.cleanup_repository.delete_for_chat(chat_id.0).await
+    }
+
+    /// Start the background task that deletes scheduled messages
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = self.settings.get().cleanup.clone()?;
+        let interval = Duration::from_secs(config.check_interval_seconds);
+
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(interval);
+
+            loop {
+                check_interval.tick().await;
+
+                if let Err(e) = self.delete_due().await {
+                    error!("Message cleanup task failed: {}", e);
+                }
+            }
+        });
+
+        info!("Started message cleanup task with interval {:?}", interval);
+        Some(handle)
+    }
+
+    /// Turn cleanup on or off for a group, optionally with its own delay.
+    /// Returns `false` if the group is unknown.
+    pub async fn set_group_cleanup(&self, group_telegram_id: i64, enabled: bool, delay_seconds: Option<u64>) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(group_telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.set_setting(group.id, "cleanup", serde_json::Value::Bool(enabled)).await?;
+        if let Some(delay) = delay_seconds {
+            self.group_repository.set_setting(group.id, "cleanup_delay", serde_json::Value::from(delay)).await?;
+        }
+
+        info!(group_id = group.id, enabled = enabled, delay = ?delay_seconds, "Group message cleanup toggled");
+        Ok(true)
+    }
+}
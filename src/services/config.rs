@@ -0,0 +1,122 @@
+//! Configuration reload service implementation
+//!
+//! Admins can reload the configuration with `/reload_config` instead of
+//! restarting the bot. The new settings are loaded and validated first; if
+//! anything is wrong the running configuration is kept. Services read their
+//! values from the shared settings, so most changes apply right away. A few
+//! sections are only read at startup (connections, logging, the bot token),
+//! and the reload report lists them when they changed.
+
+use serde::Serialize;
+use tracing::info;
+use crate::config::settings::{Settings, SharedSettings};
+use crate::services::feature_flags::FeatureFlagService;
+use crate::utils::errors::{Result, SwingBuddyError};
+
+/// Outcome of a successful configuration reload
+#[derive(Debug, Clone)]
+pub struct ConfigReload {
+    /// Sections that changed but only take effect after a restart
+    pub restart_required: Vec<&'static str>,
+    /// Feature flag overrides re-applied on top of the new configuration
+    pub flag_overrides: usize,
+}
+
+/// Configuration service for reloading settings at runtime
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ConfigService {
+    settings: SharedSettings,
+    feature_flag_service: FeatureFlagService,
+}
+
+impl ConfigService {
+    /// Create a new ConfigService instance
+    pub fn new(settings: SharedSettings, feature_flag_service: FeatureFlagService) -> Self {
+        Self { settings, feature_flag_service }
+    }
+
+    /// Get the current settings
+    pub fn current(&self) -> std::sync::Arc<Settings> {
+        self.settings.get()
+    }
+
+    /// Get the handle services created outside the factory read their settings through
+    pub fn shared(&self) -> SharedSettings {
+        self.settings.clone()
+    }
+
+    /// Load the settings from disk and the environment, validate and apply them
+    pub async fn reload(&self) -> Result<ConfigReload> {
+        let settings = Settings::new().map_err(|e| SwingBuddyError::Config(e.to_string()))?;
+        settings.validate()?;
+
+        let features = settings.features.clone();
+        let previous = self.settings.replace(settings);
+        let restart_required = restart_required(&previous, &self.settings.get());
+        let flag_overrides = self.feature_flag_service.reload(features).await?;
+
+        info!(restart_required = ?restart_required, flag_overrides = flag_overrides, "Configuration reloaded");
+        Ok(ConfigReload { restart_required, flag_overrides })
+    }
+}
+
+/// Sections that are only read at startup and whose change needs a restart
+pub fn restart_required(old: &Settings, new: &Settings) -> Vec<&'static str> {
+    let mut sections = Vec::new();
+
+    if old.bot.token != new.bot.token {
+        sections.push("bot.token");
+    }
+    if old.bot.webhook_url != new.bot.webhook_url {
+        sections.push("bot.webhook_url");
+    }
+    if differs(&old.webhook, &new.webhook) {
+        sections.push("webhook");
+    }
+    if differs(&old.database, &new.database) {
+        sections.push("database");
+    }
+    if differs(&old.redis, &new.redis) {
+        sections.push("redis");
+    }
+    if differs(&old.logging, &new.logging) {
+        sections.push("logging");
+    }
+    if differs(&old.i18n, &new.i18n) {
+        sections.push("i18n");
+    }
+    if old.cas.timeout_seconds != new.cas.timeout_seconds {
+        sections.push("cas.timeout_seconds");
+    }
+    if differs(&old.flood, &new.flood) {
+        sections.push("flood");
+    }
+
+    sections
+}
+
+fn differs<T: Serialize>(old: &T, new: &T) -> bool {
+    serde_json::to_value(old).ok() != serde_json::to_value(new).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_required() {
+        let old = Settings::default();
+        let mut new = old.clone();
+        assert!(restart_required(&old, &new).is_empty());
+
+        new.bot.admin_ids.push(42);
+        new.features.captcha = !new.features.captcha;
+        assert!(restart_required(&old, &new).is_empty());
+
+        new.bot.token = "another_token".to_string();
+        new.redis.prefix = "other:".to_string();
+        new.cas.timeout_seconds += 1;
+        assert_eq!(restart_required(&old, &new), vec!["bot.token", "redis", "cas.timeout_seconds"]);
+    }
+}
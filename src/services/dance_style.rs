@@ -0,0 +1,113 @@
+//! Dance style service implementation
+//!
+//! This service manages users' favorite dance styles and event style tags,
+//! and matches them to find the audience for targeted digests and announcements.
+
+use tracing::info;
+use crate::database::repositories::{DanceStyleRepository, UserRepository};
+use crate::models::dance_style::DanceStyle;
+use crate::models::user::User;
+use crate::utils::errors::{SwingBuddyError, Result};
+
+/// Dance style service for profile styles and event tags
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct DanceStyleService {
+    dance_style_repository: DanceStyleRepository,
+    user_repository: UserRepository,
+}
+
+impl DanceStyleService {
+    /// Create a new DanceStyleService instance
+    pub fn new(dance_style_repository: DanceStyleRepository, user_repository: UserRepository) -> Self {
+        Self {
+            dance_style_repository,
+            user_repository,
+        }
+    }
+
+    /// Get a user's favorite styles
+    pub async fn get_user_styles(&self, telegram_id: i64) -> Result<Vec<DanceStyle>> {
+        let user = self.find_user(telegram_id).await?;
+        self.dance_style_repository.get_user_styles(user.id).await
+    }
+
+    /// Add the style to the user's profile or remove it if already selected.
+    /// Returns the updated list of styles.
+    pub async fn toggle_user_style(&self, telegram_id: i64, style: DanceStyle) -> Result<Vec<DanceStyle>> {
+        let user = self.find_user(telegram_id).await?;
+
+        // One statement per toggle, so concurrent taps never rewrite each other's styles
+        if !self.dance_style_repository.remove_user_style(user.id, style).await? {
+            self.dance_style_repository.add_user_style(user.id, style).await?;
+        }
+        info!(user_id = user.id, style = %style, "User dance styles updated");
+
+        self.dance_style_repository.get_user_styles(user.id).await
+    }
+
+    /// Get the styles an event is tagged with
+    pub async fn get_event_styles(&self, event_id: i64) -> Result<Vec<DanceStyle>> {
+        self.dance_style_repository.get_event_styles(event_id).await
+    }
+
+    /// Replace the styles an event is tagged with
+    pub async fn set_event_styles(&self, event_id: i64, styles: &[DanceStyle]) -> Result<()> {
+        self.dance_style_repository.set_event_styles(event_id, styles).await?;
+        info!(event_id = event_id, styles = ?styles, "Event dance styles updated");
+        Ok(())
+    }
+
+    /// Get users who like at least one of the given styles
+    pub async fn find_users_by_styles(&self, styles: &[DanceStyle]) -> Result<Vec<User>> {
+        if styles.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.dance_style_repository.find_users_by_styles(styles).await
+    }
+
+    /// Get users whose favorite styles match the event's tags
+    pub async fn get_event_audience(&self, event_id: i64) -> Result<Vec<User>> {
+        self.dance_style_repository.find_users_for_event(event_id).await
+    }
+
+    /// Find a registered user by Telegram ID
+    async fn find_user(&self, telegram_id: i64) -> Result<User> {
+        self.user_repository.find_by_telegram_id(telegram_id).await?
+            .ok_or(SwingBuddyError::UserNotFound { user_id: telegram_id })
+    }
+}
+
+/// Parse a comma-separated list of styles, e.g. "lindy hop, balboa"
+pub fn parse_styles(input: &str) -> Result<Vec<DanceStyle>> {
+    let mut styles = Vec::new();
+
+    for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let style: DanceStyle = part.parse().map_err(SwingBuddyError::InvalidInput)?;
+        if !styles.contains(&style) {
+            styles.push(style);
+        }
+    }
+
+    if styles.is_empty() {
+        return Err(SwingBuddyError::InvalidInput("No dance styles given".to_string()));
+    }
+
+    Ok(styles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_styles() {
+        assert_eq!(parse_styles("lindy hop, balboa").unwrap(), vec![DanceStyle::LindyHop, DanceStyle::Balboa]);
+        assert_eq!(parse_styles("solo_jazz,Solo-Jazz").unwrap(), vec![DanceStyle::SoloJazz]);
+        for style in DanceStyle::ALL {
+            assert_eq!(parse_styles(style.as_str()).unwrap(), vec![style]);
+        }
+        assert!(parse_styles("blues, tango").is_err());
+        assert!(parse_styles(" , ").is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! Digest service implementation
+//!
+//! This service posts a weekly digest of the next seven days' events into the
+//! groups that subscribed to it. The digest can be pinned and is kept up to date
+//! by editing it when the week's events change.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::Duration;
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc, Weekday};
+use teloxide::{Bot, prelude::*, types::{ChatId, MessageId, ParseMode, ThreadId}};
+use teloxide::sugar::request::RequestLinkPreviewExt;
+use teloxide::utils::html;
+use tracing::{info, warn, error, debug};
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::{EventRepository, GroupRepository};
+use crate::models::event::Event;
+use crate::models::group::{Group, GroupFeature, GroupTopic};
+use crate::services::notification::NotificationService;
+use crate::utils::errors::Result;
+
+/// Digest service for the weekly event overview in groups
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct DigestService {
+    bot: Bot,
+    event_repository: EventRepository,
+    group_repository: GroupRepository,
+    notification_service: NotificationService,
+    settings: SharedSettings,
+}
+
+impl DigestService {
+    /// Create a new DigestService instance
+    pub fn new(
+        bot: Bot,
+        event_repository: EventRepository,
+        group_repository: GroupRepository,
+        notification_service: NotificationService,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            bot,
+            event_repository,
+            group_repository,
+            notification_service,
+            settings,
+        }
+    }
+
+    /// Check if the weekly digest is configured for the bot
+    pub fn is_enabled(&self) -> bool {
+        self.settings.get().digest.is_some()
+    }
+
+    /// Start the background task that posts and updates digests
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = self.settings.get().digest.clone()?;
+        let interval = Duration::from_secs(config.check_interval_seconds);
+
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(interval);
+
+            loop {
+                check_interval.tick().await;
+
+                match self.post_due_digests().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("Digest task posted {} weekly digests", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Digest task failed: {}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Started weekly digest task with interval {:?}", interval);
+        Some(handle)
+    }
+
+    /// Post the digest in subscribed groups that have not got this week's one yet,
+    /// and refresh the ones already posted if configured. Returns the number of new digests.
+    pub async fn post_due_digests(&self) -> Result<usize> {
+        let Some(config) = self.settings.get().digest.clone() else {
+            return Ok(0);
+        };
+        let Some(weekday) = weekday_from_number(config.weekday) else {
+            return Ok(0);
+        };
+
+        let now = Utc::now();
+        let start = digest_period_start(now, weekday, config.hour_utc);
+        let from = start.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let until = from + chrono::Duration::days(7);
+        let period = start.to_string();
+        let mut posted = 0;
+
+        for group in self.group_repository.get_active_groups_with_flag(GroupFeature::WeeklyDigest.as_str()).await? {
+            let already_posted = group.settings.get("digest_period").and_then(serde_json::Value::as_str) == Some(period.as_str());
+            if already_posted && !config.update_during_week {
+                continue;
+            }
+            // A new digest waits for the group's quiet hours to end; edits are silent
+            if !already_posted && group.is_quiet_at(now) {
+                continue;
+            }
+
+            let events = self.event_repository.get_group_events_between(group.id, from, until).await?;
+            let text = self.render_digest(&group, start, &events).await?;
+            let fingerprint = fingerprint(&text);
+
+            let result = if already_posted {
+                self.update_digest(&group, &text, &fingerprint).await
+            } else {
+                self.post_digest(&group, &period, &text, &fingerprint, config.pin).await.map(|_| posted += 1)
+            };
+
+            if let Err(e) = result {
+                warn!(group_id = group.id, error = %e, "Failed to post weekly digest");
+            }
+        }
+
+        Ok(posted)
+    }
+
+    /// Post a new digest, pinning it in place of the previous one if configured
+    async fn post_digest(&self, group: &Group, period: &str, text: &str, fingerprint: &str, pin: bool) -> Result<()> {
+        let chat_id = ChatId(group.telegram_id);
+
+        let mut request = self.bot.send_message(chat_id, text)
+            .parse_mode(ParseMode::Html)
+            .disable_link_preview(true);
+        if let Some(thread_id) = group.topic_id(GroupTopic::Events) {
+            request = request.message_thread_id(ThreadId(MessageId(thread_id)));
+        }
+        let message = request.await?;
+
+        if pin {
+            if let Some(previous) = digest_message_id(group) {
+                if let Err(e) = self.bot.unpin_chat_message(chat_id).message_id(previous).await {
+                    debug!(group_id = group.id, error = %e, "Failed to unpin previous digest");
+                }
+            }
+            if let Err(e) = self.bot.pin_chat_message(chat_id, message.id).disable_notification(true).await {
+                warn!(group_id = group.id, error = %e, "Failed to pin weekly digest");
+            }
+        }
+
+        self.group_repository.set_setting(group.id, "digest_period", serde_json::Value::from(period)).await?;
+        self.group_repository.set_setting(group.id, "digest_message_id", serde_json::Value::from(message.id.0)).await?;
+        self.group_repository.set_setting(group.id, "digest_fingerprint", serde_json::Value::from(fingerprint)).await?;
+
+        info!(group_id = group.id, period = period, "Weekly digest posted");
+        Ok(())
+    }
+
+    /// Edit the posted digest if the week's events changed since it was last written
+    async fn update_digest(&self, group: &Group, text: &str, fingerprint: &str) -> Result<()> {
+        if group.settings.get("digest_fingerprint").and_then(serde_json::Value::as_str) == Some(fingerprint) {
+            return Ok(());
+        }
+        let Some(message_id) = digest_message_id(group) else {
+            return Ok(());
+        };
+
+        self.bot.edit_message_text(ChatId(group.telegram_id), message_id, text)
+            .parse_mode(ParseMode::Html)
+            .disable_link_preview(true)
+            .await?;
+        self.group_repository.set_setting(group.id, "digest_fingerprint", serde_json::Value::from(fingerprint)).await?;
+
+        info!(group_id = group.id, "Weekly digest updated");
+        Ok(())
+    }
+
+    /// Compose the digest text in the group's language
+    async fn render_digest(&self, group: &Group, start: NaiveDate, events: &[Event]) -> Result<String> {
+        let language = &group.language_code;
+        let mut lines = Vec::with_capacity(events.len());
+
+        for event in events {
+            let participant_count = self.event_repository.get_participant_count(event.id).await?;
+            let mut parameters = HashMap::new();
+            parameters.insert("event_title".to_string(), html::escape(&event.title));
+            parameters.insert("event_time".to_string(), event.event_date.format("%d.%m %H:%M UTC").to_string());
+            parameters.insert("event_location".to_string(), html::escape(event.location.as_deref().unwrap_or("TBD")));
+            parameters.insert("participant_count".to_string(), participant_count.to_string());
+            lines.push(self.notification_service.format_message("weekly_digest_event", language, &parameters)?);
+        }
+
+        let events_text = if lines.is_empty() {
+            self.notification_service.format_message("weekly_digest_empty", language, &HashMap::new())?
+        } else {
+            lines.join("\n\n")
+        };
+
+        let end = start + chrono::Duration::days(6);
+        let mut parameters = HashMap::new();
+        parameters.insert("period".to_string(), format!("{} – {}", start.format("%d.%m"), end.format("%d.%m")));
+        parameters.insert("events".to_string(), events_text);
+        self.notification_service.format_message("weekly_digest", language, &parameters)
+    }
+}
+
+/// Message ID of the group's last digest
+fn digest_message_id(group: &Group) -> Option<MessageId> {
+    group.settings
+        .get("digest_message_id")
+        .and_then(serde_json::Value::as_i64)
+        .map(|id| MessageId(id as i32))
+}
+
+/// Short fingerprint of a digest text, to tell whether it needs editing
+fn fingerprint(text: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+/// Convert a configured weekday (1 = Monday ... 7 = Sunday)
+fn weekday_from_number(number: u32) -> Option<Weekday> {
+    number.checked_sub(1).and_then(|n| Weekday::try_from(n as u8).ok())
+}
+
+/// First day of the period covered by the latest digest due at `now`.
+/// A digest posted on `weekday` after `hour` UTC covers the seven days that follow.
+fn digest_period_start(now: DateTime<Utc>, weekday: Weekday, hour: u32) -> NaiveDate {
+    let today = now.date_naive();
+    let days_back = (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    let mut posting_day = today - chrono::Duration::days(days_back as i64);
+
+    if days_back == 0 && now.hour() < hour {
+        posting_day -= chrono::Duration::days(7);
+    }
+
+    posting_day + chrono::Duration::days(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_digest_period_start() {
+        // Sunday 18:00 digests: Sunday 2024-05-19 is the posting day
+        let sunday_evening = Utc.with_ymd_and_hms(2024, 5, 19, 19, 0, 0).unwrap();
+        let sunday_morning = Utc.with_ymd_and_hms(2024, 5, 19, 9, 0, 0).unwrap();
+        let wednesday = Utc.with_ymd_and_hms(2024, 5, 22, 12, 0, 0).unwrap();
+
+        let next_monday = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let this_monday = NaiveDate::from_ymd_opt(2024, 5, 13).unwrap();
+
+        assert_eq!(digest_period_start(sunday_evening, Weekday::Sun, 18), next_monday);
+        assert_eq!(digest_period_start(sunday_morning, Weekday::Sun, 18), this_monday);
+        assert_eq!(digest_period_start(wednesday, Weekday::Sun, 18), next_monday);
+    }
+
+    #[test]
+    fn test_weekday_from_number() {
+        assert_eq!(weekday_from_number(1), Some(Weekday::Mon));
+        assert_eq!(weekday_from_number(7), Some(Weekday::Sun));
+        assert_eq!(weekday_from_number(0), None);
+        assert_eq!(weekday_from_number(8), None);
+    }
+}
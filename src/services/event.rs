@@ -0,0 +1,232 @@
+//! Event service implementation
+//!
+//! Creates, updates and cancels events, registers users for them and takes them off again,
+//! enforcing the participant limit, and lets integrations that mirror
+//! participant lists or listen to webhooks know about the change.
+//! Registrations are also written to the activity log.
+
+use tracing::{info, warn};
+use crate::database::repositories::{EventRepository, UserRepository};
+use crate::models::event::{Event, CreateEventRequest, UpdateEventRequest, RegisterParticipantRequest, ParticipantStatus};
+use crate::models::user::User;
+use crate::models::activity::ActivityAction;
+use crate::models::trash::{TrashEntry, TRASH_PAGE_SIZE};
+use crate::services::activity_log::ActivityLogService;
+use crate::services::outgoing_webhook::{OutgoingWebhookService, WebhookEvent};
+use crate::services::sheet_export::SheetExportService;
+use crate::services::user::page_count;
+use crate::utils::errors::Result;
+
+/// What came of a registration or unregistration
+#[derive(Debug, Clone)]
+pub enum RegistrationOutcome {
+    Registered(Event),
+    Unregistered(Event),
+    AlreadyRegistered(Event),
+    NotRegistered(Event),
+    Full(Event),
+    /// The event doesn't exist or is no longer active
+    EventNotFound,
+    /// The user hasn't started the bot yet
+    UserNotFound,
+}
+
+/// Event registration service
+#[derive(Debug, Clone)]
+pub struct EventService {
+    event_repository: EventRepository,
+    user_repository: UserRepository,
+    sheet_export_service: SheetExportService,
+    webhook_service: Option<OutgoingWebhookService>,
+    activity_log: Option<ActivityLogService>,
+}
+
+impl EventService {
+    /// Create a new EventService instance
+    pub fn new(event_repository: EventRepository, user_repository: UserRepository, sheet_export_service: SheetExportService) -> Self {
+        Self {
+            event_repository,
+            user_repository,
+            sheet_export_service,
+            webhook_service: None,
+            activity_log: None,
+        }
+    }
+
+    /// Announce event changes and registrations through outgoing webhooks
+    pub fn with_webhooks(mut self, webhook_service: OutgoingWebhookService) -> Self {
+        self.webhook_service = Some(webhook_service);
+        self
+    }
+
+    /// Record registrations in the activity log
+    pub fn with_activity_log(mut self, activity_log: ActivityLogService) -> Self {
+        self.activity_log = Some(activity_log);
+        self
+    }
+
+    /// Create an event
+    pub async fn create_event(&self, request: CreateEventRequest) -> Result<Event> {
+        let event = self.event_repository.create(request).await?;
+        info!(event_id = event.id, created_by = ?event.created_by, "Event created");
+
+        self.event_changed(WebhookEvent::EventCreated, &event);
+        Ok(event)
+    }
+
+    /// Update an event; turning it inactive announces it as cancelled
+    pub async fn update_event(&self, event_id: i64, request: UpdateEventRequest) -> Result<Event> {
+        let cancelled = request.is_active == Some(false);
+        let event = self.event_repository.update(event_id, request).await?;
+        info!(event_id = event.id, cancelled = cancelled, "Event updated");
+
+        let webhook_event = if cancelled { WebhookEvent::EventCancelled } else { WebhookEvent::EventUpdated };
+        self.event_changed(webhook_event, &event);
+        Ok(event)
+    }
+
+    /// Cancel an event: it stays in the database but no longer takes registrations
+    pub async fn cancel_event(&self, event_id: i64) -> Result<Event> {
+        self.update_event(event_id, UpdateEventRequest { is_active: Some(false), ..Default::default() }).await
+    }
+
+    /// Get an event that hasn't been deleted
+    pub async fn get_event(&self, event_id: i64) -> Result<Option<Event>> {
+        self.event_repository.find_by_id(event_id).await
+    }
+
+    /// Get the event linked to a Google Calendar event
+    pub async fn get_event_by_google_id(&self, google_calendar_id: &str) -> Result<Option<Event>> {
+        self.event_repository.find_by_google_calendar_id(google_calendar_id).await
+    }
+
+    /// Get the nearest upcoming event, of a group and in a city when these are
+    /// given, with its number of participants
+    pub async fn get_next_event(&self, city: Option<&str>, group_id: Option<i64>) -> Result<Option<(Event, i64)>> {
+        let Some(event) = self.event_repository.get_next_event(city, group_id).await? else {
+            return Ok(None);
+        };
+        let participant_count = self.event_repository.get_participant_count(event.id).await?;
+        Ok(Some((event, participant_count)))
+    }
+
+    /// Get the nearest upcoming events, of a group and in a city when these are given
+    pub async fn get_next_events(&self, city: Option<&str>, group_id: Option<i64>, limit: i64) -> Result<Vec<Event>> {
+        self.event_repository.get_next_events(city, group_id, limit).await
+    }
+
+    /// Move an event to the trash; registrations stay for the statistics.
+    /// Returns whether there was an event to delete.
+    pub async fn delete_event(&self, event_id: i64, admin_id: i64) -> Result<bool> {
+        let Some(event) = self.event_repository.find_by_id(event_id).await? else {
+            return Ok(false);
+        };
+        let deleted = self.event_repository.delete(event_id).await?;
+        if deleted {
+            info!(event_id = event_id, admin_id = admin_id, "Event moved to trash");
+            self.event_changed(WebhookEvent::EventCancelled, &event);
+        }
+        Ok(deleted)
+    }
+
+    /// Take an event out of the trash
+    pub async fn restore_event(&self, event_id: i64, admin_id: i64) -> Result<Option<Event>> {
+        let event = self.event_repository.restore(event_id).await?;
+        if let Some(event) = &event {
+            info!(event_id = event_id, admin_id = admin_id, "Event restored from trash");
+            self.event_changed(WebhookEvent::EventUpdated, event);
+        }
+        Ok(event)
+    }
+
+    /// Get a page of deleted events and the number of pages; pages start at 0
+    pub async fn list_deleted_page(&self, page: i64) -> Result<(Vec<TrashEntry>, i64)> {
+        let pages = page_count(self.event_repository.count_deleted().await?, TRASH_PAGE_SIZE);
+        let page = page.clamp(0, pages - 1);
+        let events = self.event_repository.list_deleted(TRASH_PAGE_SIZE, page * TRASH_PAGE_SIZE).await?;
+        Ok((events, pages))
+    }
+
+    /// Register a user for an event
+    pub async fn register(&self, telegram_id: i64, event_id: i64) -> Result<RegistrationOutcome> {
+        let Some(event) = self.event_repository.find_by_id(event_id).await?.filter(|event| event.is_active) else {
+            return Ok(RegistrationOutcome::EventNotFound);
+        };
+        let Some(user) = self.user_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(RegistrationOutcome::UserNotFound);
+        };
+
+        if self.event_repository.is_registered(event.id, user.id).await? {
+            return Ok(RegistrationOutcome::AlreadyRegistered(event));
+        }
+        if let Some(max_participants) = event.max_participants {
+            if self.event_repository.get_participant_count(event.id).await? >= max_participants as i64 {
+                return Ok(RegistrationOutcome::Full(event));
+            }
+        }
+
+        self.event_repository.register_participant(RegisterParticipantRequest {
+            event_id: event.id,
+            user_id: user.id,
+            status: Some(ParticipantStatus::Registered.to_string()),
+        }).await?;
+
+        info!(user_id = telegram_id, event_id = event.id, "User registered for event");
+        self.participants_changed(WebhookEvent::ParticipantRegistered, &event, &user).await;
+        Ok(RegistrationOutcome::Registered(event))
+    }
+
+    /// Take a user off an event's participant list
+    pub async fn unregister(&self, telegram_id: i64, event_id: i64) -> Result<RegistrationOutcome> {
+        let Some(event) = self.event_repository.find_by_id(event_id).await? else {
+            return Ok(RegistrationOutcome::EventNotFound);
+        };
+        let Some(user) = self.user_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(RegistrationOutcome::UserNotFound);
+        };
+
+        if !self.event_repository.is_registered(event.id, user.id).await? {
+            return Ok(RegistrationOutcome::NotRegistered(event));
+        }
+
+        self.event_repository.unregister_participant(event.id, user.id).await?;
+
+        info!(user_id = telegram_id, event_id = event.id, "User unregistered from event");
+        self.participants_changed(WebhookEvent::ParticipantUnregistered, &event, &user).await;
+        Ok(RegistrationOutcome::Unregistered(event))
+    }
+
+    /// Announce a created, changed or cancelled event through outgoing webhooks
+    fn event_changed(&self, webhook_event: WebhookEvent, event: &Event) {
+        if let Some(webhook_service) = &self.webhook_service {
+            webhook_service.emit(webhook_event, &serde_json::json!({ "event": event }));
+        }
+    }
+
+    /// Pass a participant list change on; failures here must not undo the registration
+    async fn participants_changed(&self, webhook_event: WebhookEvent, event: &Event, user: &User) {
+        if let Err(e) = self.sheet_export_service.request_sync(event.id).await {
+            warn!(event_id = event.id, error = %e, "Failed to flag event sheet for export");
+        }
+
+        if let Some(activity_log) = &self.activity_log {
+            let action = match webhook_event {
+                WebhookEvent::ParticipantUnregistered => ActivityAction::EventUnregistered,
+                _ => ActivityAction::EventRegistered,
+            };
+            activity_log.record(action, Some(user.telegram_id), event.id, serde_json::json!({ "title": event.title })).await;
+        }
+
+        if let Some(webhook_service) = &self.webhook_service {
+            webhook_service.emit(webhook_event, &serde_json::json!({
+                "event_id": event.id,
+                "user": {
+                    "telegram_id": user.telegram_id,
+                    "username": user.username,
+                    "first_name": user.first_name,
+                    "last_name": user.last_name,
+                }
+            }));
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Feature flag service implementation
+//!
+//! The `[features]` section of the configuration holds the defaults. Admins can
+//! switch features on and off in the admin panel; their choice is stored in the
+//! `admin_settings` table and applied on top of the configuration at startup, so it
+//! survives restarts without a redeploy. Services share one `FeatureFlags` handle
+//! and see a change right away.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+use crate::config::settings::FeaturesConfig;
+use crate::database::repositories::AdminRepository;
+use crate::models::admin::{CreateAdminSettingRequest, UpdateAdminSettingRequest};
+use crate::utils::errors::Result;
+
+/// Bot-wide features admins can switch at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureFlag {
+    CasProtection,
+    GoogleCalendar,
+    Captcha,
+}
+
+impl FeatureFlag {
+    /// All flags in the order they are shown to admins
+    pub const ALL: [FeatureFlag; 3] = [Self::CasProtection, Self::GoogleCalendar, Self::Captcha];
+
+    /// Name of the flag in the `[features]` config section
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CasProtection => "cas_protection",
+            Self::GoogleCalendar => "google_calendar",
+            Self::Captcha => "captcha",
+        }
+    }
+
+    /// Key of the stored override in `admin_settings`
+    pub fn setting_key(&self) -> String {
+        format!("features.{}", self.as_str())
+    }
+}
+
+impl fmt::Display for FeatureFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FeatureFlag {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL.into_iter()
+            .find(|flag| flag.as_str() == s)
+            .ok_or_else(|| format!("Unknown feature flag: {}", s))
+    }
+}
+
+/// Current feature flags, shared by all services
+#[derive(Debug, Clone)]
+pub struct FeatureFlags {
+    flags: Arc<RwLock<FeaturesConfig>>,
+}
+
+impl FeatureFlags {
+    /// Start from the configured defaults
+    pub fn new(config: FeaturesConfig) -> Self {
+        Self { flags: Arc::new(RwLock::new(config)) }
+    }
+
+    /// Check if a feature is switched on
+    pub fn is_enabled(&self, flag: FeatureFlag) -> bool {
+        let flags = self.flags.read().unwrap_or_else(|e| e.into_inner());
+        match flag {
+            FeatureFlag::CasProtection => flags.cas_protection,
+            FeatureFlag::GoogleCalendar => flags.google_calendar,
+            FeatureFlag::Captcha => flags.captcha,
+        }
+    }
+
+    /// Replace all flags with freshly loaded configuration
+    pub fn replace(&self, config: FeaturesConfig) {
+        let mut flags = self.flags.write().unwrap_or_else(|e| e.into_inner());
+        *flags = config;
+    }
+
+    fn set(&self, flag: FeatureFlag, enabled: bool) {
+        let mut flags = self.flags.write().unwrap_or_else(|e| e.into_inner());
+        match flag {
+            FeatureFlag::CasProtection => flags.cas_protection = enabled,
+            FeatureFlag::GoogleCalendar => flags.google_calendar = enabled,
+            FeatureFlag::Captcha => flags.captcha = enabled,
+        }
+    }
+}
+
+/// Feature flag service for switching features from the admin panel
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct FeatureFlagService {
+    flags: FeatureFlags,
+    admin_repository: AdminRepository,
+}
+
+impl FeatureFlagService {
+    /// Create a new FeatureFlagService instance
+    pub fn new(flags: FeatureFlags, admin_repository: AdminRepository) -> Self {
+        Self { flags, admin_repository }
+    }
+
+    /// Check if a feature is switched on
+    pub fn is_enabled(&self, flag: FeatureFlag) -> bool {
+        self.flags.is_enabled(flag)
+    }
+
+    /// Apply the switches stored by admins on top of the configuration.
+    /// Returns the number of overrides applied.
+    pub async fn load_overrides(&self) -> Result<usize> {
+        let mut applied = 0;
+
+        for flag in FeatureFlag::ALL {
+            let Some(setting) = self.admin_repository.get_setting(&flag.setting_key()).await? else {
+                continue;
+            };
+            match setting.value.as_bool() {
+                Some(enabled) => {
+                    self.flags.set(flag, enabled);
+                    applied += 1;
+                }
+                None => warn!(flag = flag.as_str(), value = %setting.value, "Ignoring invalid feature flag override"),
+            }
+        }
+
+        Ok(applied)
+    }
+
+    /// Reset the flags to a reloaded configuration and re-apply the admins' overrides.
+    /// Returns the number of overrides applied.
+    pub async fn reload(&self, config: FeaturesConfig) -> Result<usize> {
+        self.flags.replace(config);
+        self.load_overrides().await
+    }
+
+    /// Switch a feature on or off and store the choice; `updated_by` is the admin's user ID
+    pub async fn set_enabled(&self, flag: FeatureFlag, enabled: bool, updated_by: Option<i64>) -> Result<()> {
+        let key = flag.setting_key();
+        let value = serde_json::Value::Bool(enabled);
+
+        if self.admin_repository.get_setting(&key).await?.is_some() {
+            self.admin_repository.update_setting(&key, UpdateAdminSettingRequest { value, updated_by }).await?;
+        } else {
+            self.admin_repository.create_setting(CreateAdminSettingRequest { key, value, updated_by }).await?;
+        }
+        self.flags.set(flag, enabled);
+
+        info!(flag = flag.as_str(), enabled = enabled, "Feature flag changed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_flags_are_shared() {
+        let flags = FeatureFlags::new(FeaturesConfig {
+            cas_protection: true,
+            google_calendar: false,
+            admin_panel: true,
+            captcha: true,
+        });
+        let shared = flags.clone();
+
+        flags.set(FeatureFlag::CasProtection, false);
+
+        assert!(!shared.is_enabled(FeatureFlag::CasProtection));
+        assert!(!shared.is_enabled(FeatureFlag::GoogleCalendar));
+        assert!(shared.is_enabled(FeatureFlag::Captcha));
+    }
+
+    #[test]
+    fn test_feature_flag_from_str() {
+        assert_eq!("captcha".parse::<FeatureFlag>(), Ok(FeatureFlag::Captcha));
+        assert_eq!(FeatureFlag::GoogleCalendar.setting_key(), "features.google_calendar");
+        assert!("admin_panel".parse::<FeatureFlag>().is_err());
+    }
+}
@@ -3,25 +3,74 @@
 //! This service handles Google Calendar API integration for event creation,
 //! management, calendar sharing functionality, OAuth2 authentication,
 //! and event URL generation for "Add to Calendar" functionality.
+//!
+//! Events go to the Calendar API as the account connected through
+//! [`GoogleOAuthService`]; without one they are only simulated.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tracing::{info, debug};
-use crate::config::settings::Settings;
+use crate::config::settings::SharedSettings;
 use crate::models::event::Event;
+use crate::services::feature_flags::{FeatureFlag, FeatureFlags};
+use crate::services::google_oauth::GoogleOAuthService;
 use crate::utils::errors::{SwingBuddyError, GoogleError, GoogleResult, Result};
 
+const CALENDAR_API_URL: &str = "https://www.googleapis.com/calendar/v3";
+
 /// Google Calendar event structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoogleCalendarEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub start: GoogleDateTime,
     pub end: GoogleDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub attendees: Option<Vec<GoogleAttendee>>,
+    #[serde(rename = "htmlLink", skip_serializing_if = "Option::is_none")]
     pub html_link: Option<String>,
+    /// `cancelled` for events deleted since the last sync
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+/// Calendar API events list response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleEventList {
+    #[serde(default)]
+    items: Vec<GoogleCalendarEvent>,
+    next_page_token: Option<String>,
+    next_sync_token: Option<String>,
+}
+
+/// Events changed since the last sync
+#[derive(Debug, Clone)]
+pub struct GoogleEventChanges {
+    pub events: Vec<GoogleCalendarEvent>,
+    /// Token to pass to the next incremental sync
+    pub next_sync_token: Option<String>,
+}
+
+/// Push notification channel registered for the calendar's events
+#[derive(Debug, Clone)]
+pub struct GoogleWatchChannel {
+    pub resource_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Calendar API watch response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WatchResponse {
+    resource_id: String,
+    /// Milliseconds since the epoch, as a string
+    expiration: Option<String>,
 }
 
 /// Google Calendar date/time structure
@@ -47,14 +96,15 @@ pub struct GoogleAttendee {
 #[derive(Clone)]
 #[derive(Debug)]
 pub struct GoogleCalendarService {
-    settings: Settings,
-    #[allow(dead_code)]
+    feature_flags: FeatureFlags,
+    settings: SharedSettings,
     http_client: reqwest::Client,
+    oauth: Option<GoogleOAuthService>,
 }
 
 impl GoogleCalendarService {
     /// Create a new GoogleCalendarService instance
-    pub fn new(settings: Settings) -> Result<Self> {
+    pub fn new(feature_flags: FeatureFlags, settings: SharedSettings) -> Result<Self> {
         let http_client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(30))
             .user_agent("SwingBuddy-Bot/1.0")
@@ -62,11 +112,19 @@ impl GoogleCalendarService {
             .map_err(|e| SwingBuddyError::Http(e))?;
 
         Ok(Self {
+            feature_flags,
             settings,
             http_client,
+            oauth: None,
         })
     }
 
+    /// Call the Calendar API as the account connected through OAuth
+    pub fn with_oauth(mut self, oauth: GoogleOAuthService) -> Self {
+        self.oauth = Some(oauth);
+        self
+    }
+
     /// Create a new event in Google Calendar
     pub async fn create_event(&self, event: &Event) -> GoogleResult<String> {
         if !self.is_enabled() {
@@ -77,9 +135,16 @@ impl GoogleCalendarService {
 
         let google_event = self.convert_to_google_event(event)?;
         
-        // For now, return a mock calendar ID since we don't have actual Google API integration
-        // In a real implementation, this would make an actual API call
-        let calendar_id = self.create_mock_calendar_event(&google_event).await?;
+        let calendar_id = match self.access_token().await? {
+            Some(token) => {
+                let created: GoogleCalendarEvent = self.api_request(
+                    self.http_client.post(self.events_url()?).json(&google_event),
+                    &token,
+                ).await?.json().await.map_err(|e| GoogleError::EventCreationFailed(e.to_string()))?;
+                created.id.ok_or_else(|| GoogleError::EventCreationFailed("Created event has no ID".to_string()))?
+            }
+            None => self.create_mock_calendar_event(&google_event).await?,
+        };
         
         info!(event_id = event.id, calendar_id = %calendar_id, "Google Calendar event created");
         Ok(calendar_id)
@@ -93,10 +158,16 @@ impl GoogleCalendarService {
 
         info!(event_id = event.id, calendar_id = %calendar_id, "Updating Google Calendar event");
 
-        let google_event = self.convert_to_google_event(event)?;
+        let mut google_event = self.convert_to_google_event(event)?;
+        google_event.id = None;
         
-        // Mock update - in real implementation, this would call Google Calendar API
-        self.update_mock_calendar_event(calendar_id, &google_event).await?;
+        match self.access_token().await? {
+            Some(token) => {
+                let url = format!("{}/{}", self.events_url()?, urlencoding::encode(calendar_id));
+                self.api_request(self.http_client.put(url).json(&google_event), &token).await?;
+            }
+            None => self.update_mock_calendar_event(calendar_id, &google_event).await?,
+        }
         
         info!(event_id = event.id, calendar_id = %calendar_id, "Google Calendar event updated");
         Ok(())
@@ -110,8 +181,13 @@ impl GoogleCalendarService {
 
         info!(calendar_id = %calendar_id, "Deleting Google Calendar event");
 
-        // Mock deletion - in real implementation, this would call Google Calendar API
-        self.delete_mock_calendar_event(calendar_id).await?;
+        match self.access_token().await? {
+            Some(token) => {
+                let url = format!("{}/{}", self.events_url()?, urlencoding::encode(calendar_id));
+                self.api_request(self.http_client.delete(url), &token).await?;
+            }
+            None => self.delete_mock_calendar_event(calendar_id).await?,
+        }
         
         info!(calendar_id = %calendar_id, "Google Calendar event deleted");
         Ok(())
@@ -152,15 +228,11 @@ impl GoogleCalendarService {
 
     /// Generate calendar sharing URL
     pub fn generate_calendar_sharing_url(&self) -> Result<String> {
-        let google_config = self.settings.google.as_ref()
+        let settings = self.settings.get();
+        let google_config = settings.google.as_ref()
             .ok_or_else(|| GoogleError::ApiError("Google Calendar not configured".to_string()))?;
 
-        let sharing_url = format!(
-            "https://calendar.google.com/calendar/embed?src={}",
-            urlencoding::encode(&google_config.calendar_id)
-        );
-
-        Ok(sharing_url)
+        Ok(calendar_sharing_url(&google_config.calendar_id))
     }
 
     /// Get calendar events for a date range
@@ -171,16 +243,137 @@ impl GoogleCalendarService {
 
         debug!(start_date = %start_date, end_date = %end_date, "Getting calendar events");
 
-        // Mock implementation - in real implementation, this would call Google Calendar API
-        let events = self.get_mock_calendar_events(start_date, end_date).await?;
+        let events = match self.access_token().await? {
+            Some(token) => {
+                let request = self.http_client.get(self.events_url()?).query(&[
+                    ("timeMin", start_date.to_rfc3339()),
+                    ("timeMax", end_date.to_rfc3339()),
+                    ("singleEvents", "true".to_string()),
+                    ("orderBy", "startTime".to_string()),
+                ]);
+                let list: GoogleEventList = self.api_request(request, &token).await?
+                    .json().await.map_err(|e| GoogleError::ApiError(e.to_string()))?;
+                list.items
+            }
+            None => self.get_mock_calendar_events(start_date, end_date).await?,
+        };
         
         debug!(count = events.len(), "Retrieved calendar events");
         Ok(events)
     }
 
+    /// Events changed since `sync_token`, or all events for a full sync. Fails
+    /// with [`GoogleError::SyncTokenExpired`] when Google wants a full sync again.
+    pub async fn list_changed_events(&self, sync_token: Option<&str>) -> GoogleResult<GoogleEventChanges> {
+        let token = self.require_access_token().await?;
+        let mut changes = GoogleEventChanges { events: Vec::new(), next_sync_token: None };
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut query = vec![("showDeleted", "true".to_string())];
+            if let Some(sync_token) = sync_token {
+                query.push(("syncToken", sync_token.to_string()));
+            }
+            if let Some(page_token) = &page_token {
+                query.push(("pageToken", page_token.clone()));
+            }
+
+            let response = self.http_client.get(self.events_url()?).query(&query)
+                .bearer_auth(&token).send().await
+                .map_err(|e| GoogleError::ApiError(e.to_string()))?;
+            // Only here does 410 mean the sync token is no longer valid
+            if sync_token.is_some() && response.status() == reqwest::StatusCode::GONE {
+                return Err(GoogleError::SyncTokenExpired);
+            }
+            let list: GoogleEventList = check_status(response).await?
+                .json().await.map_err(|e| GoogleError::ApiError(e.to_string()))?;
+
+            changes.events.extend(list.items);
+            match list.next_page_token {
+                Some(next) => page_token = Some(next),
+                None => {
+                    changes.next_sync_token = list.next_sync_token;
+                    break;
+                }
+            }
+        }
+
+        debug!(count = changes.events.len(), incremental = sync_token.is_some(), "Listed changed calendar events");
+        Ok(changes)
+    }
+
+    /// Ask Google to post to `address` whenever the calendar's events change
+    pub async fn watch_events(&self, channel_id: &str, channel_token: &str, address: &str) -> GoogleResult<GoogleWatchChannel> {
+        let token = self.require_access_token().await?;
+        let body = serde_json::json!({
+            "id": channel_id,
+            "type": "web_hook",
+            "address": address,
+            "token": channel_token,
+        });
+
+        let request = self.http_client.post(format!("{}/watch", self.events_url()?)).json(&body);
+        let response: WatchResponse = self.api_request(request, &token).await?
+            .json().await.map_err(|e| GoogleError::ApiError(e.to_string()))?;
+
+        let expires_at = response.expiration
+            .and_then(|ms| ms.parse::<i64>().ok())
+            .and_then(DateTime::<Utc>::from_timestamp_millis)
+            .unwrap_or_else(|| Utc::now() + chrono::Duration::days(7));
+
+        info!(channel_id = %channel_id, expires_at = %expires_at, "Registered Google Calendar watch channel");
+        Ok(GoogleWatchChannel { resource_id: response.resource_id, expires_at })
+    }
+
+    /// Stop push notifications of a channel
+    pub async fn stop_channel(&self, channel_id: &str, resource_id: &str) -> GoogleResult<()> {
+        let token = self.require_access_token().await?;
+        let body = serde_json::json!({ "id": channel_id, "resourceId": resource_id });
+
+        let request = self.http_client.post(format!("{}/channels/stop", CALENDAR_API_URL)).json(&body);
+        self.api_request(request, &token).await?;
+
+        debug!(channel_id = %channel_id, "Stopped Google Calendar watch channel");
+        Ok(())
+    }
+
     /// Check if Google Calendar integration is enabled
     pub fn is_enabled(&self) -> bool {
-        self.settings.features.google_calendar && self.settings.google.is_some()
+        self.feature_flags.is_enabled(FeatureFlag::GoogleCalendar) && self.settings.get().google.is_some()
+    }
+
+    /// Access token of the connected account; `None` means the API is simulated
+    async fn access_token(&self) -> GoogleResult<Option<String>> {
+        let Some(oauth) = &self.oauth else {
+            return Ok(None);
+        };
+
+        oauth.access_token().await.map_err(|e| match e {
+            SwingBuddyError::Google(e) => e,
+            other => GoogleError::AuthenticationFailed(other.to_string()),
+        })
+    }
+
+    /// Access token for calls that only make sense against the real API
+    async fn require_access_token(&self) -> GoogleResult<String> {
+        self.access_token().await?
+            .ok_or_else(|| GoogleError::AuthenticationFailed("No Google account connected".to_string()))
+    }
+
+    /// Events endpoint of the configured calendar
+    fn events_url(&self) -> GoogleResult<String> {
+        let settings = self.settings.get();
+        let google_config = settings.google.as_ref()
+            .ok_or_else(|| GoogleError::ApiError("Google Calendar not configured".to_string()))?;
+
+        Ok(format!("{}/calendars/{}/events", CALENDAR_API_URL, urlencoding::encode(&google_config.calendar_id)))
+    }
+
+    /// Send a Calendar API request, turning error statuses into errors
+    async fn api_request(&self, request: reqwest::RequestBuilder, token: &str) -> GoogleResult<reqwest::Response> {
+        let response = request.bearer_auth(token).send().await
+            .map_err(|e| GoogleError::ApiError(e.to_string()))?;
+        check_status(response).await
     }
 
     /// Convert SwingBuddy event to Google Calendar event format
@@ -203,6 +396,7 @@ impl GoogleCalendarService {
             location: event.location.clone(),
             attendees: None, // Could be populated with event participants
             html_link: None,
+            status: None,
         })
     }
 
@@ -300,15 +494,44 @@ pub struct CalendarStats {
     pub last_sync: Option<DateTime<Utc>>,
 }
 
+/// Get the public sharing URL of a Google Calendar
+pub fn calendar_sharing_url(calendar_id: &str) -> String {
+    format!(
+        "https://calendar.google.com/calendar/embed?src={}",
+        urlencoding::encode(calendar_id)
+    )
+}
+
+/// Pass a successful Calendar API response through, or turn its status into an error
+async fn check_status(response: reqwest::Response) -> GoogleResult<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    Err(status_error(status, body))
+}
+
+/// Error for a failed Calendar API call; a deleted event (410) counts as not found
+fn status_error(status: reqwest::StatusCode, body: String) -> GoogleError {
+    match status {
+        reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE => GoogleError::CalendarNotFound(body),
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => GoogleError::AuthenticationFailed(body),
+        _ => GoogleError::ApiError(format!("HTTP {}: {}", status, body)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::settings::Settings;
     use chrono::Utc;
 
     #[test]
     fn test_generate_add_to_calendar_url() {
         let settings = Settings::default();
-        let service = GoogleCalendarService::new(settings).unwrap();
+        let service = GoogleCalendarService::new(FeatureFlags::new(settings.features.clone()), SharedSettings::new(settings)).unwrap();
         
         let event = Event {
             id: 1,
@@ -335,7 +558,7 @@ mod tests {
     #[test]
     fn test_generate_ical() {
         let settings = Settings::default();
-        let service = GoogleCalendarService::new(settings).unwrap();
+        let service = GoogleCalendarService::new(FeatureFlags::new(settings.features.clone()), SharedSettings::new(settings)).unwrap();
         
         let event = Event {
             id: 1,
@@ -365,7 +588,7 @@ mod tests {
     #[test]
     fn test_convert_to_google_event() {
         let settings = Settings::default();
-        let service = GoogleCalendarService::new(settings).unwrap();
+        let service = GoogleCalendarService::new(FeatureFlags::new(settings.features.clone()), SharedSettings::new(settings)).unwrap();
         
         let event = Event {
             id: 1,
@@ -389,4 +612,12 @@ mod tests {
         assert!(google_event.start.date_time.is_some());
         assert!(google_event.end.date_time.is_some());
     }
+
+    #[test]
+    fn test_status_error() {
+        assert!(matches!(status_error(reqwest::StatusCode::GONE, String::new()), GoogleError::CalendarNotFound(_)));
+        assert!(matches!(status_error(reqwest::StatusCode::NOT_FOUND, String::new()), GoogleError::CalendarNotFound(_)));
+        assert!(matches!(status_error(reqwest::StatusCode::FORBIDDEN, String::new()), GoogleError::AuthenticationFailed(_)));
+        assert!(matches!(status_error(reqwest::StatusCode::BAD_REQUEST, String::new()), GoogleError::ApiError(_)));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,324 @@
+//! Google OAuth service implementation
+//!
+//! Connects one community Google account for the bot. An admin opens the
+//! consent page from the admin panel, Google redirects back to
+//! `/oauth/google/callback` on the health server, and the code is exchanged
+//! for an offline refresh token. Access tokens are refreshed shortly before
+//! they expire, and disconnecting revokes the token at Google.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+use crate::config::settings::{GoogleOAuthConfig, SharedSettings};
+use crate::database::repositories::GoogleAccountRepository;
+use crate::models::google::{GoogleAccount, ConnectGoogleAccountRequest};
+use crate::utils::errors::{SwingBuddyError, GoogleError, Result};
+
+const AUTHORIZATION_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const REVOKE_URL: &str = "https://oauth2.googleapis.com/revoke";
+const PRIMARY_CALENDAR_URL: &str = "https://www.googleapis.com/calendar/v3/calendars/primary";
+
+/// How long a consent link stays usable
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Access tokens are refreshed this long before they expire
+const REFRESH_MARGIN_SECONDS: i64 = 60;
+
+/// Token endpoint response
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: i64,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+}
+
+/// Token endpoint error body
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// A consent link an admin opened and Google hasn't redirected back from yet
+#[derive(Debug, Clone, Copy)]
+struct PendingAuthorization {
+    admin_id: i64,
+    created_at: Instant,
+}
+
+/// Google OAuth service for the community account
+#[derive(Clone)]
+pub struct GoogleOAuthService {
+    http_client: reqwest::Client,
+    repository: GoogleAccountRepository,
+    settings: SharedSettings,
+    pending: Arc<Mutex<HashMap<String, PendingAuthorization>>>,
+    /// Held while refreshing so concurrent callers don't refresh twice
+    refresh_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl std::fmt::Debug for GoogleOAuthService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GoogleOAuthService")
+            .field("repository", &self.repository)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GoogleOAuthService {
+    /// Create a new GoogleOAuthService instance
+    pub fn new(repository: GoogleAccountRepository, settings: SharedSettings) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("SwingBuddy-Bot/1.0")
+            .build()
+            .map_err(SwingBuddyError::Http)?;
+
+        Ok(Self {
+            http_client,
+            repository,
+            settings,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            refresh_lock: Arc::new(tokio::sync::Mutex::new(())),
+        })
+    }
+
+    /// Whether an OAuth client is configured in `[google.oauth]`
+    pub fn is_configured(&self) -> bool {
+        self.oauth_config().is_ok()
+    }
+
+    /// Consent page link for an admin; it works once, within 15 minutes
+    pub fn authorization_url(&self, admin_id: i64) -> Result<String> {
+        let config = self.oauth_config()?;
+        let state = uuid::Uuid::new_v4().simple().to_string();
+
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.retain(|_, authorization| authorization.created_at.elapsed() < AUTHORIZATION_TIMEOUT);
+        pending.insert(state.clone(), PendingAuthorization { admin_id, created_at: Instant::now() });
+
+        Ok(build_authorization_url(&config, &state))
+    }
+
+    /// Exchange the code Google redirected back with and store the account.
+    /// Returns the admin who opened the consent link along with the account.
+    pub async fn complete_authorization(&self, state: &str, code: &str) -> Result<(i64, GoogleAccount)> {
+        let authorization = self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(state)
+            .filter(|authorization| authorization.created_at.elapsed() < AUTHORIZATION_TIMEOUT)
+            .ok_or_else(|| GoogleError::AuthenticationFailed("Unknown or expired authorization request".to_string()))?;
+
+        let config = self.oauth_config()?;
+        let tokens = self.request_tokens(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("client_id", config.client_id.as_str()),
+            ("client_secret", config.client_secret.as_str()),
+            ("redirect_uri", config.redirect_uri.as_str()),
+        ]).await?;
+
+        // Google only sends a refresh token with prompt=consent, which the link always asks for
+        let refresh_token = tokens.refresh_token
+            .ok_or_else(|| GoogleError::AuthenticationFailed("Google did not return a refresh token".to_string()))?;
+        let email = self.fetch_account_email(&tokens.access_token).await;
+
+        let account = self.repository.save(ConnectGoogleAccountRequest {
+            email,
+            access_token: tokens.access_token,
+            refresh_token,
+            expires_at: Utc::now() + chrono::Duration::seconds(tokens.expires_in),
+            scope: tokens.scope.unwrap_or_else(|| config.scopes.join(" ")),
+            connected_by: authorization.admin_id,
+        }).await?;
+
+        info!(admin_id = authorization.admin_id, email = ?account.email, "Google account connected");
+        Ok((authorization.admin_id, account))
+    }
+
+    /// The connected account, if any
+    pub async fn account(&self) -> Result<Option<GoogleAccount>> {
+        self.repository.get().await
+    }
+
+    /// A valid access token of the connected account, refreshed if needed.
+    /// `None` if no account is connected.
+    pub async fn access_token(&self) -> Result<Option<String>> {
+        let Some(account) = self.repository.get().await? else {
+            return Ok(None);
+        };
+        if !expires_soon(account.expires_at, Utc::now()) {
+            return Ok(Some(account.access_token));
+        }
+
+        let _guard = self.refresh_lock.lock().await;
+
+        // Another caller may have refreshed while we waited
+        let Some(account) = self.repository.get().await? else {
+            return Ok(None);
+        };
+        if !expires_soon(account.expires_at, Utc::now()) {
+            return Ok(Some(account.access_token));
+        }
+
+        debug!("Refreshing Google access token");
+        let config = self.oauth_config()?;
+        let tokens = match self.request_tokens(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", account.refresh_token.as_str()),
+            ("client_id", config.client_id.as_str()),
+            ("client_secret", config.client_secret.as_str()),
+        ]).await {
+            Ok(tokens) => tokens,
+            Err(TokenError::InvalidGrant(description)) => {
+                // Access was revoked on Google's side; the account has to be connected again
+                warn!(error = %description, "Google refresh token was rejected, disconnecting the account");
+                self.repository.delete().await?;
+                return Err(GoogleError::AuthenticationFailed(description).into());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let expires_at = Utc::now() + chrono::Duration::seconds(tokens.expires_in);
+        self.repository.update_access_token(&tokens.access_token, expires_at).await?;
+
+        Ok(Some(tokens.access_token))
+    }
+
+    /// Revoke the tokens at Google and forget the account. Returns whether one was connected.
+    pub async fn disconnect(&self) -> Result<bool> {
+        let Some(account) = self.repository.get().await? else {
+            return Ok(false);
+        };
+
+        // Forget the account even if Google can't be reached; the token is useless to us then
+        match self.http_client.post(REVOKE_URL).form(&[("token", account.refresh_token.as_str())]).send().await {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => warn!(status = %response.status(), "Google did not accept the token revocation"),
+            Err(e) => warn!(error = %e, "Failed to revoke Google token"),
+        }
+
+        let removed = self.repository.delete().await?;
+        info!(email = ?account.email, "Google account disconnected");
+        Ok(removed)
+    }
+
+    fn oauth_config(&self) -> Result<GoogleOAuthConfig> {
+        self.settings.get().google.as_ref()
+            .and_then(|google| google.oauth.clone())
+            .ok_or_else(|| GoogleError::AuthenticationFailed("Google OAuth is not configured".to_string()).into())
+    }
+
+    async fn request_tokens(&self, form: &[(&str, &str)]) -> std::result::Result<TokenResponse, TokenError> {
+        let response = self.http_client.post(TOKEN_URL).form(form).send().await
+            .map_err(|e| TokenError::Request(e.to_string()))?;
+
+        if response.status().is_success() {
+            return response.json::<TokenResponse>().await
+                .map_err(|e| TokenError::Request(format!("Invalid token response: {}", e)));
+        }
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        match serde_json::from_str::<TokenErrorResponse>(&body) {
+            Ok(error) if error.error == "invalid_grant" => {
+                Err(TokenError::InvalidGrant(error.error_description.unwrap_or(error.error)))
+            }
+            Ok(error) => Err(TokenError::Request(format!(
+                "{}: {}",
+                error.error,
+                error.error_description.unwrap_or_default()
+            ))),
+            Err(_) => Err(TokenError::Request(format!("HTTP {}: {}", status, body))),
+        }
+    }
+
+    /// The account's address, which is the ID of its primary calendar
+    async fn fetch_account_email(&self, access_token: &str) -> Option<String> {
+        #[derive(Deserialize)]
+        struct PrimaryCalendar {
+            id: String,
+        }
+
+        let response = self.http_client.get(PRIMARY_CALENDAR_URL).bearer_auth(access_token).send().await.ok()?;
+        if !response.status().is_success() {
+            debug!(status = %response.status(), "Couldn't look up the connected Google account");
+            return None;
+        }
+        response.json::<PrimaryCalendar>().await.ok().map(|calendar| calendar.id)
+    }
+}
+
+/// Why the token endpoint didn't hand out tokens
+enum TokenError {
+    /// The code or refresh token is no longer valid
+    InvalidGrant(String),
+    Request(String),
+}
+
+impl From<TokenError> for SwingBuddyError {
+    fn from(error: TokenError) -> Self {
+        match error {
+            TokenError::InvalidGrant(description) | TokenError::Request(description) => {
+                GoogleError::AuthenticationFailed(description).into()
+            }
+        }
+    }
+}
+
+/// Consent page link that asks for offline access, so a refresh token is returned
+fn build_authorization_url(config: &GoogleOAuthConfig, state: &str) -> String {
+    format!(
+        "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&access_type=offline&prompt=consent&include_granted_scopes=true&state={}",
+        AUTHORIZATION_URL,
+        urlencoding::encode(&config.client_id),
+        urlencoding::encode(&config.redirect_uri),
+        urlencoding::encode(&config.scopes.join(" ")),
+        state,
+    )
+}
+
+fn expires_soon(expires_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    expires_at - now < chrono::Duration::seconds(REFRESH_MARGIN_SECONDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> GoogleOAuthConfig {
+        GoogleOAuthConfig {
+            client_id: "client.apps.googleusercontent.com".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uri: "https://bot.example.com/oauth/google/callback".to_string(),
+            scopes: vec![
+                "https://www.googleapis.com/auth/calendar".to_string(),
+                "https://www.googleapis.com/auth/spreadsheets".to_string(),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_authorization_url_asks_for_offline_access() {
+        let url = build_authorization_url(&config(), "abc123");
+
+        assert!(url.starts_with(AUTHORIZATION_URL));
+        assert!(url.contains("access_type=offline"));
+        assert!(url.contains("prompt=consent"));
+        assert!(url.contains("state=abc123"));
+        assert!(url.contains("redirect_uri=https%3A%2F%2Fbot.example.com%2Foauth%2Fgoogle%2Fcallback"));
+        assert!(url.contains("auth%2Fcalendar%20https"));
+        assert!(!url.contains("secret"));
+    }
+
+    #[test]
+    fn test_expires_soon() {
+        let now = Utc::now();
+        assert!(expires_soon(now - chrono::Duration::seconds(5), now));
+        assert!(expires_soon(now + chrono::Duration::seconds(30), now));
+        assert!(!expires_soon(now + chrono::Duration::minutes(30), now));
+    }
+}
@@ -0,0 +1,320 @@
+//! Google Calendar watch service implementation
+//!
+//! Keeps a push notification channel open on the configured Google Calendar
+//! and renews it before Google expires it. When a change notification reaches
+//! the health server, the events changed since the last sync are fetched with
+//! the stored sync token and applied to the bot events linked to them.
+
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use tracing::{debug, error, info, warn};
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::GoogleCalendarSyncRepository;
+use crate::models::event::{Event, UpdateEventRequest};
+use crate::models::google::{GoogleCalendarSync, SaveWatchChannelRequest};
+use crate::services::event::EventService;
+use crate::services::google::{GoogleCalendarEvent, GoogleCalendarService};
+use crate::services::google_oauth::GoogleOAuthService;
+use crate::utils::errors::{GoogleError, Result};
+
+/// Google Calendar push channel and incremental sync
+#[derive(Debug, Clone)]
+pub struct GoogleCalendarWatchService {
+    google_service: GoogleCalendarService,
+    oauth_service: GoogleOAuthService,
+    sync_repository: GoogleCalendarSyncRepository,
+    /// Applies the changes, so they are announced like changes made in the bot
+    event_service: EventService,
+    settings: SharedSettings,
+    /// Notifications arriving together are synced one after another
+    sync_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl GoogleCalendarWatchService {
+    /// Create a new GoogleCalendarWatchService instance
+    pub fn new(
+        google_service: GoogleCalendarService,
+        oauth_service: GoogleOAuthService,
+        sync_repository: GoogleCalendarSyncRepository,
+        event_service: EventService,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            google_service,
+            oauth_service,
+            sync_repository,
+            event_service,
+            settings,
+            sync_lock: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// Start the background task that registers and renews the channel
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = self.settings.get().google.as_ref()?.watch.clone()?;
+        let interval = Duration::from_secs(config.check_interval_seconds);
+
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(interval);
+
+            loop {
+                check_interval.tick().await;
+
+                if let Err(e) = self.ensure_channel().await {
+                    error!("Google Calendar watch task failed: {}", e);
+                }
+            }
+        });
+
+        info!("Started Google Calendar watch task with interval {:?}", interval);
+        Some(handle)
+    }
+
+    /// Register a push channel if there is none or it expires soon. Returns
+    /// whether a new channel was registered.
+    pub async fn ensure_channel(&self) -> Result<bool> {
+        let Some(google) = self.settings.get().google.clone() else {
+            return Ok(false);
+        };
+        let Some(watch) = google.watch else {
+            return Ok(false);
+        };
+        let current = self.sync_repository.get(&google.calendar_id).await?;
+
+        // Channels of a disconnected account can't be renewed or stopped any more
+        if !self.google_service.is_enabled() || self.oauth_service.account().await?.is_none() {
+            if current.as_ref().is_some_and(|sync| sync.channel_id.is_some()) {
+                self.sync_repository.clear_channel(&google.calendar_id).await?;
+            }
+            return Ok(false);
+        }
+
+        let renew_before = chrono::Duration::hours(watch.renew_before_hours as i64);
+        if current.as_ref().is_some_and(|sync| !needs_renewal(sync, &watch.notification_url, renew_before, Utc::now())) {
+            return Ok(false);
+        }
+
+        let channel_id = uuid::Uuid::new_v4().simple().to_string();
+        let channel_token = uuid::Uuid::new_v4().simple().to_string();
+        let channel = self.google_service.watch_events(&channel_id, &channel_token, &watch.notification_url).await?;
+        self.sync_repository.save_channel(SaveWatchChannelRequest {
+            calendar_id: google.calendar_id.clone(),
+            channel_id,
+            resource_id: channel.resource_id,
+            channel_token,
+            address: watch.notification_url,
+            expires_at: channel.expires_at,
+        }).await?;
+
+        if let Some(previous) = &current {
+            if let (Some(channel_id), Some(resource_id)) = (&previous.channel_id, &previous.resource_id) {
+                if let Err(e) = self.google_service.stop_channel(channel_id, resource_id).await {
+                    warn!(channel_id = %channel_id, error = %e, "Failed to stop the previous Google Calendar channel");
+                }
+            }
+        }
+
+        // Later notifications are synced incrementally from this point
+        if current.and_then(|sync| sync.sync_token).is_none() {
+            self.sync().await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Handle a push notification. Returns `false` if it doesn't come from our
+    /// current channel; otherwise the changes are synced in the background.
+    pub async fn handle_notification(&self, channel_id: &str, channel_token: Option<&str>, resource_state: &str) -> Result<bool> {
+        let Some(sync) = self.sync_repository.find_by_channel(channel_id).await? else {
+            return Ok(false);
+        };
+        if sync.channel_token.as_deref() != channel_token {
+            return Ok(false);
+        }
+
+        // "sync" only confirms that the channel was set up
+        if resource_state != "sync" && self.google_service.is_enabled() {
+            debug!(channel_id = %channel_id, resource_state = %resource_state, "Google Calendar changed");
+            let service = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = service.sync().await {
+                    error!("Google Calendar sync failed: {}", e);
+                }
+            });
+        }
+
+        Ok(true)
+    }
+
+    /// Apply the events changed since the last sync. Returns the number of
+    /// bot events that were updated.
+    pub async fn sync(&self) -> Result<usize> {
+        let _guard = self.sync_lock.lock().await;
+
+        let Some(calendar_id) = self.settings.get().google.as_ref().map(|google| google.calendar_id.clone()) else {
+            return Ok(0);
+        };
+        let sync_token = self.sync_repository.get(&calendar_id).await?.and_then(|sync| sync.sync_token);
+
+        let changes = match self.google_service.list_changed_events(sync_token.as_deref()).await {
+            Err(GoogleError::SyncTokenExpired) => {
+                info!("Google Calendar sync token expired, syncing all events");
+                self.google_service.list_changed_events(None).await?
+            }
+            result => result?,
+        };
+
+        let mut updated = 0;
+        for google_event in &changes.events {
+            if self.apply_change(google_event).await? {
+                updated += 1;
+            }
+        }
+
+        self.sync_repository.save_sync_token(&calendar_id, changes.next_sync_token.as_deref()).await?;
+
+        if updated > 0 {
+            info!(changed = changes.events.len(), updated = updated, "Synced Google Calendar changes");
+        }
+        Ok(updated)
+    }
+
+    /// Update the bot event linked to a changed Google event, if any
+    async fn apply_change(&self, google_event: &GoogleCalendarEvent) -> Result<bool> {
+        let Some(google_id) = &google_event.id else {
+            return Ok(false);
+        };
+        let Some(event) = self.event_service.get_event_by_google_id(google_id).await? else {
+            return Ok(false);
+        };
+        let Some(update) = event_update(&event, google_event) else {
+            return Ok(false);
+        };
+
+        let event = self.event_service.update_event(event.id, update).await?;
+        info!(event_id = event.id, google_id = %google_id, "Event updated from Google Calendar");
+        Ok(true)
+    }
+}
+
+/// Whether the channel is missing, points elsewhere or expires within `renew_before`
+fn needs_renewal(sync: &GoogleCalendarSync, address: &str, renew_before: chrono::Duration, now: DateTime<Utc>) -> bool {
+    sync.channel_id.is_none()
+        || sync.address.as_deref() != Some(address)
+        || sync.expires_at.is_none_or(|expires_at| expires_at - now < renew_before)
+}
+
+/// Changes to make to a bot event so it matches its Google event, or `None` if it already does
+fn event_update(event: &Event, google_event: &GoogleCalendarEvent) -> Option<UpdateEventRequest> {
+    if google_event.status.as_deref() == Some("cancelled") {
+        return event.is_active.then(|| UpdateEventRequest {
+            is_active: Some(false),
+            ..Default::default()
+        });
+    }
+
+    let event_date = google_event.start.date_time.as_deref()
+        .and_then(|date_time| DateTime::parse_from_rfc3339(date_time).ok())
+        .map(|date_time| date_time.with_timezone(&Utc));
+
+    let update = UpdateEventRequest {
+        title: Some(google_event.summary.clone()).filter(|title| *title != event.title),
+        description: google_event.description.clone().filter(|description| event.description.as_ref() != Some(description)),
+        event_date: event_date.filter(|date| *date != event.event_date),
+        location: google_event.location.clone().filter(|location| event.location.as_ref() != Some(location)),
+        ..Default::default()
+    };
+
+    let changed = update.title.is_some() || update.description.is_some() || update.event_date.is_some() || update.location.is_some();
+    changed.then_some(update)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::google::GoogleDateTime;
+
+    fn event() -> Event {
+        Event {
+            id: 1,
+            title: "Lindy Social".to_string(),
+            description: None,
+            event_date: DateTime::parse_from_rfc3339("2025-06-01T19:00:00Z").unwrap().with_timezone(&Utc),
+            location: Some("Studio".to_string()),
+            max_participants: None,
+            google_calendar_id: Some("abc".to_string()),
+            created_by: None,
+            group_id: None,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn google_event(summary: &str, start: &str) -> GoogleCalendarEvent {
+        GoogleCalendarEvent {
+            id: Some("abc".to_string()),
+            summary: summary.to_string(),
+            description: None,
+            start: GoogleDateTime { date_time: Some(start.to_string()), time_zone: None },
+            end: GoogleDateTime { date_time: None, time_zone: None },
+            location: Some("Studio".to_string()),
+            attendees: None,
+            html_link: None,
+            status: Some("confirmed".to_string()),
+        }
+    }
+
+    fn sync(address: &str, expires_at: Option<DateTime<Utc>>) -> GoogleCalendarSync {
+        GoogleCalendarSync {
+            calendar_id: "cal".to_string(),
+            channel_id: Some("channel".to_string()),
+            resource_id: Some("resource".to_string()),
+            channel_token: Some("token".to_string()),
+            address: Some(address.to_string()),
+            expires_at,
+            sync_token: None,
+            last_synced_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_unchanged_event_needs_no_update() {
+        assert!(event_update(&event(), &google_event("Lindy Social", "2025-06-01T21:00:00+02:00")).is_none());
+    }
+
+    #[test]
+    fn test_changed_fields_are_updated() {
+        let update = event_update(&event(), &google_event("Lindy Social!", "2025-06-01T20:00:00Z")).unwrap();
+        assert_eq!(update.title.as_deref(), Some("Lindy Social!"));
+        assert!(update.event_date.is_some());
+        assert!(update.location.is_none());
+        assert!(update.is_active.is_none());
+    }
+
+    #[test]
+    fn test_cancelled_event_is_deactivated_once() {
+        let mut cancelled = google_event("Lindy Social", "2025-06-01T19:00:00Z");
+        cancelled.status = Some("cancelled".to_string());
+
+        assert_eq!(event_update(&event(), &cancelled).unwrap().is_active, Some(false));
+
+        let mut inactive = event();
+        inactive.is_active = false;
+        assert!(event_update(&inactive, &cancelled).is_none());
+    }
+
+    #[test]
+    fn test_channel_renewal() {
+        let now = Utc::now();
+        let renew_before = chrono::Duration::hours(24);
+        let url = "https://bot.example.com/google/calendar/notifications";
+
+        assert!(!needs_renewal(&sync(url, Some(now + chrono::Duration::days(3))), url, renew_before, now));
+        assert!(needs_renewal(&sync(url, Some(now + chrono::Duration::hours(2))), url, renew_before, now));
+        assert!(needs_renewal(&sync("https://old.example.com/hook", Some(now + chrono::Duration::days(3))), url, renew_before, now));
+        assert!(needs_renewal(&sync(url, None), url, renew_before, now));
+    }
+}
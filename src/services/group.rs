@@ -0,0 +1,320 @@
+//! Group service implementation
+//!
+//! This service registers groups the bot works in and manages their
+//! group-specific configuration, such as the welcome message and the rules.
+//! Deleted groups stay in the trash until restored or until the bot sees
+//! them again. Members who started the bot are recorded as they join and leave,
+//! and roles delegated with /role end when the member leaves.
+
+use tracing::{debug, info};
+use crate::database::repositories::{GroupRepository, RoleRepository};
+use crate::models::group::{Group, GroupFeature, GroupTopic, IntroCardMode, QuietHours, CreateGroupRequest, UpdateGroupRequest};
+use crate::models::trash::{TrashEntry, TRASH_PAGE_SIZE};
+use crate::services::user::page_count;
+use crate::utils::errors::Result;
+
+/// Placeholders available in welcome messages
+pub const WELCOME_PLACEHOLDERS: [&str; 4] = ["{first_name}", "{last_name}", "{username}", "{group_title}"];
+
+/// Group service for group registration and settings
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct GroupService {
+    group_repository: GroupRepository,
+    role_repository: RoleRepository,
+}
+
+impl GroupService {
+    /// Create a new GroupService instance
+    pub fn new(group_repository: GroupRepository, role_repository: RoleRepository) -> Self {
+        Self { group_repository, role_repository }
+    }
+
+    /// Get a group by its Telegram chat ID
+    pub async fn get_group(&self, telegram_id: i64) -> Result<Option<Group>> {
+        self.group_repository.find_by_telegram_id(telegram_id).await
+    }
+
+    /// Get the city of a group: the one most of its registered members live in
+    pub async fn get_city(&self, group_id: i64) -> Result<Option<String>> {
+        self.group_repository.get_member_city(group_id).await
+    }
+
+    /// Get all groups the bot is active in
+    pub async fn get_active_groups(&self) -> Result<Vec<Group>> {
+        self.group_repository.get_active_groups().await
+    }
+
+    /// Get a group by its Telegram chat ID, registering it if the bot has not seen it yet
+    pub async fn get_or_register_group(&self, telegram_id: i64, title: &str) -> Result<Group> {
+        if let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? {
+            return Ok(group);
+        }
+
+        let group = self.group_repository.create(CreateGroupRequest {
+            telegram_id,
+            title: title.to_string(),
+            description: None,
+            language_code: None,
+            settings: None,
+        }).await?;
+
+        info!(group_id = group.id, telegram_id = telegram_id, "Group registered");
+        Ok(group)
+    }
+
+    /// Keep a group's member record in step with Telegram: `role` is the member's
+    /// status, or `None` once they left or were removed. Members who never
+    /// started the bot have no record; their delegated role goes either way.
+    pub async fn sync_member(&self, telegram_id: i64, user_telegram_id: i64, role: Option<&str>) -> Result<()> {
+        match role {
+            Some(role) => {
+                if self.group_repository.save_member(telegram_id, user_telegram_id, role).await? {
+                    debug!(chat_id = telegram_id, user_id = user_telegram_id, role = role, "Group member recorded");
+                }
+            }
+            None => {
+                if self.group_repository.forget_member(telegram_id, user_telegram_id).await? {
+                    info!(chat_id = telegram_id, user_id = user_telegram_id, "Group member removed");
+                }
+                if self.role_repository.remove_role(telegram_id, user_telegram_id).await? {
+                    info!(chat_id = telegram_id, user_id = user_telegram_id, "Delegated role removed with the member");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Mark a group active or inactive, e.g. when the bot is added back or removed.
+    /// Returns the updated group, or `None` if the group is unknown.
+    pub async fn set_active(&self, telegram_id: i64, active: bool) -> Result<Option<Group>> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(None);
+        };
+
+        let group = self.group_repository.update(group.id, UpdateGroupRequest {
+            title: None,
+            description: None,
+            language_code: None,
+            settings: None,
+            is_active: Some(active),
+        }).await?;
+
+        info!(group_id = group.id, active = active, "Group activity changed");
+        Ok(Some(group))
+    }
+
+    /// Move a group to the trash. If the bot is still in it, the group comes back
+    /// with its settings the next time it is seen. Returns `false` if the group is unknown.
+    pub async fn delete_group(&self, telegram_id: i64) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        let deleted = self.group_repository.delete(group.id).await?;
+        if deleted {
+            info!(group_id = group.id, telegram_id = telegram_id, "Group moved to trash");
+        }
+        Ok(deleted)
+    }
+
+    /// Take a group out of the trash by database ID
+    pub async fn restore_group(&self, group_id: i64) -> Result<Option<Group>> {
+        let group = self.group_repository.restore(group_id).await?;
+        if let Some(group) = &group {
+            info!(group_id = group.id, telegram_id = group.telegram_id, "Group restored from trash");
+        }
+        Ok(group)
+    }
+
+    /// Get a page of deleted groups and the number of pages; pages start at 0
+    pub async fn list_deleted_page(&self, page: i64) -> Result<(Vec<TrashEntry>, i64)> {
+        let pages = page_count(self.group_repository.count_deleted().await?, TRASH_PAGE_SIZE);
+        let page = page.clamp(0, pages - 1);
+        let groups = self.group_repository.list_deleted(TRASH_PAGE_SIZE, page * TRASH_PAGE_SIZE).await?;
+        Ok((groups, pages))
+    }
+
+    /// Set the language the bot uses in the group. Returns `false` if the group is unknown.
+    pub async fn set_language(&self, telegram_id: i64, language_code: &str) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.update(group.id, UpdateGroupRequest {
+            title: None,
+            description: None,
+            language_code: Some(language_code.to_string()),
+            settings: None,
+            is_active: None,
+        }).await?;
+        info!(group_id = group.id, language_code = language_code, "Group language set");
+        Ok(true)
+    }
+
+    /// Set the welcome message for new members, or turn it off with `None`
+    pub async fn set_welcome_message(&self, telegram_id: i64, title: &str, template: Option<String>) -> Result<Group> {
+        let group = self.set_text_setting(telegram_id, title, "welcome_message", template).await?;
+        info!(group_id = group.id, enabled = group.welcome_message().is_some(), "Group welcome message updated");
+        Ok(group)
+    }
+
+    /// Set the group rules, or remove them with `None`
+    pub async fn set_rules(&self, telegram_id: i64, title: &str, rules: Option<String>) -> Result<Group> {
+        let group = self.set_text_setting(telegram_id, title, "rules", rules).await?;
+        info!(group_id = group.id, enabled = group.rules().is_some(), "Group rules updated");
+        Ok(group)
+    }
+
+    /// Turn posting the rules for new members on or off. Returns `false` if the group is unknown.
+    pub async fn set_rules_on_join(&self, telegram_id: i64, enabled: bool) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.set_setting(group.id, "rules_on_join", serde_json::Value::Bool(enabled)).await?;
+        info!(group_id = group.id, enabled = enabled, "Group rules for new members toggled");
+        Ok(true)
+    }
+
+    /// Turn sharing the bot-level ban list on or off. Returns `false` if the group is unknown.
+    pub async fn set_shared_bans(&self, telegram_id: i64, enabled: bool) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.set_setting(group.id, "shared_bans", serde_json::Value::Bool(enabled)).await?;
+        info!(group_id = group.id, enabled = enabled, "Group shared ban list toggled");
+        Ok(true)
+    }
+
+    /// Switch a per-group feature on or off. Returns `false` if the group is unknown.
+    pub async fn set_feature(&self, telegram_id: i64, feature: GroupFeature, enabled: bool) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.set_setting(group.id, feature.as_str(), serde_json::Value::Bool(enabled)).await?;
+        info!(group_id = group.id, feature = feature.as_str(), enabled = enabled, "Group feature toggled");
+        Ok(true)
+    }
+
+    /// Bind a kind of bot posts to a forum topic, or back to the general chat with `None`.
+    /// Returns `false` if the group is unknown.
+    pub async fn set_topic(&self, telegram_id: i64, topic: GroupTopic, thread_id: Option<i32>) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        let value = thread_id.map_or(serde_json::Value::Null, serde_json::Value::from);
+        self.group_repository.set_setting(group.id, topic.as_str(), value).await?;
+        info!(group_id = group.id, topic = topic.as_str(), thread_id = ?thread_id, "Group topic updated");
+        Ok(true)
+    }
+
+    /// Choose where new members get the intro card. Returns `false` if the group is unknown.
+    pub async fn set_intro_card_mode(&self, telegram_id: i64, mode: IntroCardMode) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.set_setting(group.id, "intro_card", serde_json::to_value(mode)?).await?;
+        info!(group_id = group.id, mode = ?mode, "Group intro card mode updated");
+        Ok(true)
+    }
+
+    /// Set the group's quiet hours, or remove them with `None`.
+    /// Returns `false` if the group is unknown.
+    pub async fn set_quiet_hours(&self, telegram_id: i64, hours: Option<QuietHours>) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        let value = match hours {
+            Some(hours) => serde_json::to_value(hours)?,
+            None => serde_json::Value::Null,
+        };
+        self.group_repository.set_setting(group.id, "quiet_hours", value).await?;
+        info!(group_id = group.id, quiet_hours = ?hours, "Group quiet hours updated");
+        Ok(true)
+    }
+
+    /// Turn warnings for senders of messages removed by the spam filter on or off.
+    /// Returns `false` if the group is unknown.
+    pub async fn set_spam_filter_warn(&self, telegram_id: i64, enabled: bool) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.set_setting(group.id, "spam_filter_warn", serde_json::Value::Bool(enabled)).await?;
+        info!(group_id = group.id, enabled = enabled, "Group spam filter warnings toggled");
+        Ok(true)
+    }
+
+    /// Send member reports to a separate chat, or back to the admins with `None`.
+    /// Returns `false` if the group is unknown.
+    pub async fn set_report_chat(&self, telegram_id: i64, report_chat_id: Option<i64>) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        let value = report_chat_id.map_or(serde_json::Value::Null, serde_json::Value::from);
+        self.group_repository.set_setting(group.id, "report_chat_id", value).await?;
+        info!(group_id = group.id, report_chat_id = ?report_chat_id, "Group report chat updated");
+        Ok(true)
+    }
+
+    /// Store a text setting, registering the group if needed; `None` removes the text
+    async fn set_text_setting(&self, telegram_id: i64, title: &str, key: &str, text: Option<String>) -> Result<Group> {
+        let group = self.get_or_register_group(telegram_id, title).await?;
+        let value = text.map_or(serde_json::Value::Null, serde_json::Value::String);
+        self.group_repository.set_setting(group.id, key, value).await
+    }
+}
+
+/// Fill the placeholders of a welcome message template.
+/// `{username}` falls back to the first name for users without a username.
+pub fn render_welcome_message(template: &str, user: &teloxide::types::User, group_title: &str) -> String {
+    let username = user.username.as_ref()
+        .map(|username| format!("@{}", username))
+        .unwrap_or_else(|| user.first_name.clone());
+
+    template
+        .replace("{first_name}", &user.first_name)
+        .replace("{last_name}", user.last_name.as_deref().unwrap_or(""))
+        .replace("{username}", &username)
+        .replace("{group_title}", group_title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teloxide::types::UserId;
+
+    fn user(username: Option<&str>) -> teloxide::types::User {
+        teloxide::types::User {
+            id: UserId(42),
+            is_bot: false,
+            first_name: "Frankie".to_string(),
+            last_name: None,
+            username: username.map(str::to_string),
+            language_code: None,
+            is_premium: false,
+            added_to_attachment_menu: false,
+        }
+    }
+
+    #[test]
+    fn test_render_welcome_message() {
+        let template = "Welcome to {group_title}, {first_name} ({username})!{last_name}";
+
+        assert_eq!(
+            render_welcome_message(template, &user(Some("frankie")), "Savoy"),
+            "Welcome to Savoy, Frankie (@frankie)!"
+        );
+        assert_eq!(
+            render_welcome_message(template, &user(None), "Savoy"),
+            "Welcome to Savoy, Frankie (Frankie)!"
+        );
+    }
+}
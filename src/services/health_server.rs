@@ -0,0 +1,252 @@
+//! Health server implementation
+//!
+//! A small HTTP server for Kubernetes or docker-compose probes. `/healthz`
+//! answers as long as the process is running; `/readyz` also checks the
+//! database and Redis and returns 503 while either is unreachable. Both
+//! respond with the service health status as JSON. `/metrics` exports the
+//! metrics registry for Prometheus. `/oauth/google/callback` is where Google
+//! sends admins back after they connect the community Google account, and
+//! `/google/calendar/notifications` receives Google Calendar push notifications.
+//! `/api/v1/*` is the read-only JSON API for the community website; it needs
+//! one of the keys from the `[api]` config section. `/webapp` is the Telegram
+//! Mini App opened from /events; it loads `/webapp/events` with the init data
+//! Telegram signed for the user.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info, warn};
+use crate::services::{ServiceFactory, ServiceHealthStatus};
+use crate::utils::errors::Result;
+
+/// HTTP server exposing the health and readiness endpoints
+#[derive(Clone)]
+pub struct HealthServer {
+    services: Arc<ServiceFactory>,
+    address: SocketAddr,
+}
+
+impl HealthServer {
+    /// Create a new HealthServer instance
+    pub fn new(services: Arc<ServiceFactory>, address: SocketAddr) -> Self {
+        Self { services, address }
+    }
+
+    /// Router with the `/healthz`, `/readyz`, `/metrics`, Google, public API and Mini App endpoints
+    pub fn router(&self) -> Router {
+        let api = Router::new()
+            .route("/events", get(api_events))
+            .route("/events/{id}", get(api_event))
+            .route("/calendars", get(api_calendars))
+            .route("/stats", get(api_stats))
+            .route_layer(middleware::from_fn_with_state(self.services.clone(), require_api_key));
+
+        Router::new()
+            .nest("/api/v1", api)
+            .route("/healthz", get(healthz))
+            .route("/readyz", get(readyz))
+            .route("/metrics", get(prometheus_metrics))
+            .route("/oauth/google/callback", get(google_oauth_callback))
+            .route("/google/calendar/notifications", post(google_calendar_notification))
+            .route("/webapp", get(webapp_page))
+            .route("/webapp/events", get(webapp_events))
+            .with_state(self.services.clone())
+    }
+
+    /// Start the server in the background
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        let address = self.address;
+        let handle = tokio::spawn(async move {
+            let listener = match tokio::net::TcpListener::bind(self.address).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Health server couldn't listen on {}: {}", self.address, e);
+                    return;
+                }
+            };
+
+            if let Err(e) = axum::serve(listener, self.router()).await {
+                error!("Health server failed: {}", e);
+            }
+        });
+
+        info!("Started health server on {}", address);
+        handle
+    }
+}
+
+async fn healthz(State(services): State<Arc<ServiceFactory>>) -> (StatusCode, Json<ServiceHealthStatus>) {
+    let status = services.health_check().await;
+    (status_code(status.is_healthy()), Json(status))
+}
+
+async fn readyz(State(services): State<Arc<ServiceFactory>>) -> (StatusCode, Json<ServiceHealthStatus>) {
+    let status = services.health_check().await;
+    (status_code(status.is_ready()), Json(status))
+}
+
+async fn prometheus_metrics() -> ([(header::HeaderName, &'static str); 1], String) {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::utils::metrics::metrics().render(),
+    )
+}
+
+/// Query Google redirects back with
+#[derive(Deserialize)]
+struct OAuthCallback {
+    state: Option<String>,
+    code: Option<String>,
+    error: Option<String>,
+}
+
+async fn google_oauth_callback(
+    State(services): State<Arc<ServiceFactory>>,
+    Query(callback): Query<OAuthCallback>,
+) -> (StatusCode, Html<&'static str>) {
+    let (Some(state), Some(code)) = (callback.state, callback.code) else {
+        warn!(error = ?callback.error, "Google account connection was cancelled");
+        return (StatusCode::BAD_REQUEST, Html(OAUTH_CANCELLED_PAGE));
+    };
+
+    match services.google_oauth_service.complete_authorization(&state, &code).await {
+        Ok((admin_id, account)) => {
+            services.admin_audit_service.record(admin_id, "google_connect", account.email.as_deref()).await;
+
+            // Open the calendar push channel right away instead of at the next check
+            let watch_service = services.google_watch_service.clone();
+            tokio::spawn(async move {
+                if let Err(e) = watch_service.ensure_channel().await {
+                    error!(error = %e, "Failed to register Google Calendar watch channel");
+                }
+            });
+            (StatusCode::OK, Html(OAUTH_CONNECTED_PAGE))
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to connect Google account");
+            (StatusCode::BAD_REQUEST, Html(OAUTH_FAILED_PAGE))
+        }
+    }
+}
+
+async fn google_calendar_notification(
+    State(services): State<Arc<ServiceFactory>>,
+    headers: HeaderMap,
+) -> StatusCode {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let (Some(channel_id), Some(resource_state)) = (header("x-goog-channel-id"), header("x-goog-resource-state")) else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    match services.google_watch_service.handle_notification(channel_id, header("x-goog-channel-token"), resource_state).await {
+        Ok(true) => StatusCode::OK,
+        Ok(false) => {
+            warn!(channel_id = %channel_id, "Google Calendar notification for an unknown channel");
+            StatusCode::NOT_FOUND
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to handle Google Calendar notification");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Body of an API error response
+#[derive(Serialize)]
+struct ApiError {
+    error: &'static str,
+}
+
+fn api_error(status: StatusCode, error: &'static str) -> Response {
+    (status, Json(ApiError { error })).into_response()
+}
+
+/// Let API requests through only with a configured key
+async fn require_api_key(State(services): State<Arc<ServiceFactory>>, request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let key = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| headers.get("x-api-key").and_then(|value| value.to_str().ok()));
+
+    match key.and_then(|key| services.public_api_service.authorize(key.trim())) {
+        Some(client) => {
+            debug!(client = %client, path = %request.uri().path(), "Public API request");
+            next.run(request).await
+        }
+        None => api_error(StatusCode::UNAUTHORIZED, "invalid or missing API key"),
+    }
+}
+
+/// Turn a service result into a JSON response
+fn api_response<T: Serialize>(result: Result<T>) -> Response {
+    match result {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            error!(error = %e, "Public API request failed");
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "internal error")
+        }
+    }
+}
+
+/// Query of `/api/v1/events`
+#[derive(Deserialize)]
+struct EventsQuery {
+    limit: Option<i64>,
+}
+
+async fn api_events(State(services): State<Arc<ServiceFactory>>, Query(query): Query<EventsQuery>) -> Response {
+    api_response(services.public_api_service.upcoming_events(query.limit).await)
+}
+
+async fn api_event(State(services): State<Arc<ServiceFactory>>, Path(id): Path<i64>) -> Response {
+    match services.public_api_service.event(id).await {
+        Ok(None) => api_error(StatusCode::NOT_FOUND, "event not found"),
+        result => api_response(result),
+    }
+}
+
+async fn api_calendars(State(services): State<Arc<ServiceFactory>>) -> Response {
+    api_response(services.public_api_service.calendars().await)
+}
+
+async fn api_stats(State(services): State<Arc<ServiceFactory>>) -> Response {
+    api_response(services.public_api_service.stats().await)
+}
+
+async fn webapp_page() -> Html<&'static str> {
+    Html(WEBAPP_PAGE)
+}
+
+/// Events for the Mini App, for the user the `X-Telegram-Init-Data` header was signed for
+async fn webapp_events(State(services): State<Arc<ServiceFactory>>, headers: HeaderMap) -> Response {
+    let init_data = headers.get("x-telegram-init-data").and_then(|value| value.to_str().ok());
+    let Some(telegram_id) = init_data.and_then(|init_data| services.webapp_service.authenticate(init_data)) else {
+        return api_error(StatusCode::UNAUTHORIZED, "invalid or missing init data");
+    };
+
+    debug!(user_id = telegram_id, "Mini App events request");
+    api_response(services.webapp_service.events(telegram_id).await)
+}
+
+const WEBAPP_PAGE: &str = include_str!("webapp.html");
+const OAUTH_CONNECTED_PAGE: &str = "<!DOCTYPE html><html><body><h1>Google account connected</h1><p>You can close this page and go back to the bot.</p></body></html>";
+const OAUTH_CANCELLED_PAGE: &str = "<!DOCTYPE html><html><body><h1>Connection cancelled</h1><p>No Google account was connected.</p></body></html>";
+const OAUTH_FAILED_PAGE: &str = "<!DOCTYPE html><html><body><h1>Connection failed</h1><p>The link may have expired. Open a new one from the admin panel.</p></body></html>";
+
+fn status_code(ok: bool) -> StatusCode {
+    if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
@@ -0,0 +1,101 @@
+//! Invite link service implementation
+//!
+//! This service creates join-request invite links for groups, rotates and revokes
+//! them, and counts the join requests sent through each link so admins can see
+//! which promo channel brings new dancers in.
+
+use teloxide::{Bot, prelude::*, types::ChatId};
+use tracing::info;
+use crate::database::repositories::InviteLinkRepository;
+use crate::models::group::Group;
+use crate::models::invite_link::{InviteLink, CreateInviteLinkRequest, InviteLinkStats, JoinRequestStatus};
+use crate::utils::errors::Result;
+
+/// Longest invite link name Telegram accepts
+pub const MAX_LINK_NAME_LENGTH: usize = 32;
+
+/// Invite link service for tracked join-request links
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct InviteLinkService {
+    bot: Bot,
+    invite_link_repository: InviteLinkRepository,
+}
+
+impl InviteLinkService {
+    /// Create a new InviteLinkService instance
+    pub fn new(bot: Bot, invite_link_repository: InviteLinkRepository) -> Self {
+        Self { bot, invite_link_repository }
+    }
+
+    /// Create a join-request invite link for a group
+    pub async fn create_link(&self, group: &Group, name: &str, created_by: i64) -> Result<InviteLink> {
+        let invite_link = self.create_telegram_link(group, name).await?;
+
+        let link = self.invite_link_repository.create(CreateInviteLinkRequest {
+            group_id: group.id,
+            name: name.to_string(),
+            invite_link,
+            created_by,
+        }).await?;
+
+        info!(group_id = group.id, link_id = link.id, created_by = created_by, "Invite link created");
+        Ok(link)
+    }
+
+    /// Get a group's active invite links with their join request counts
+    pub async fn get_link_stats(&self, group: &Group) -> Result<Vec<InviteLinkStats>> {
+        self.invite_link_repository.get_stats_for_group(group.id).await
+    }
+
+    /// Replace a link with a new URL, keeping its name and statistics; returns
+    /// `None` if the group has no active link with this ID
+    pub async fn rotate_link(&self, group: &Group, link_id: i64) -> Result<Option<InviteLink>> {
+        let Some(link) = self.find_active_link(group, link_id).await? else {
+            return Ok(None);
+        };
+
+        let invite_link = self.create_telegram_link(group, &link.name).await?;
+        self.bot.revoke_chat_invite_link(ChatId(group.telegram_id), link.invite_link).await?;
+        let rotated = self.invite_link_repository.update_link(link.id, &invite_link).await?;
+
+        info!(group_id = group.id, link_id = link_id, "Invite link rotated");
+        Ok(rotated)
+    }
+
+    /// Revoke a link so it no longer works; returns `None` if the group has no
+    /// active link with this ID
+    pub async fn revoke_link(&self, group: &Group, link_id: i64) -> Result<Option<InviteLink>> {
+        let Some(link) = self.find_active_link(group, link_id).await? else {
+            return Ok(None);
+        };
+
+        self.bot.revoke_chat_invite_link(ChatId(group.telegram_id), link.invite_link.clone()).await?;
+        self.invite_link_repository.revoke(link.id).await?;
+
+        info!(group_id = group.id, link_id = link_id, "Invite link revoked");
+        Ok(Some(link))
+    }
+
+    /// Find the tracked link a join request was sent through
+    pub async fn find_tracked_link(&self, invite_link: &str) -> Result<Option<InviteLink>> {
+        self.invite_link_repository.find_active_by_link(invite_link).await
+    }
+
+    /// Count a join request sent through a tracked link
+    pub async fn record_join_request(&self, link: &InviteLink, user_id: i64, status: JoinRequestStatus) -> Result<()> {
+        self.invite_link_repository.record_request(link.id, user_id, status).await
+    }
+
+    async fn find_active_link(&self, group: &Group, link_id: i64) -> Result<Option<InviteLink>> {
+        Ok(self.invite_link_repository.find_by_id(group.id, link_id).await?.filter(InviteLink::is_active))
+    }
+
+    async fn create_telegram_link(&self, group: &Group, name: &str) -> Result<String> {
+        let link = self.bot.create_chat_invite_link(ChatId(group.telegram_id))
+            .name(name.to_string())
+            .creates_join_request(true)
+            .await?;
+        Ok(link.invite_link)
+    }
+}
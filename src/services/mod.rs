@@ -2,25 +2,101 @@
 //!
 //! This module contains business logic services
 
+pub mod activity_log;
+pub mod admin_audit;
 pub mod auth;
+pub mod backup;
+pub mod birthday;
+pub mod broadcast;
+pub mod calendar;
+pub mod captcha;
 pub mod cas;
+pub mod cas_queue;
+pub mod cas_recheck;
+pub mod cleanup;
+pub mod archive;
+pub mod step_reminder;
+pub mod config;
+pub mod dance_style;
+pub mod digest;
+pub mod event;
+pub mod feature_flags;
+pub mod quiet_hours;
+pub mod stats;
 pub mod google;
+pub mod google_oauth;
+pub mod google_watch;
+pub mod group;
+pub mod health_server;
+pub mod invite_link;
+pub mod moderation;
+pub mod note;
 pub mod notification;
+pub mod notification_queue;
+pub mod notification_template;
+pub mod outgoing_webhook;
+pub mod public_api;
 pub mod redis;
+pub mod referral;
+pub mod reminder;
+pub mod scheduled_message;
+pub mod sheet_export;
+pub mod song_request;
 pub mod user;
+pub mod venue;
+pub mod webapp;
 
 // Re-export commonly used services
-pub use auth::{AuthService, AuthContext, Permission, AuthMiddleware};
+pub use activity_log::ActivityLogService;
+pub use admin_audit::AdminAuditService;
+pub use auth::{AuthService, AuthContext, Permission, BotPermission, AuthMiddleware};
+pub use backup::{Backup, BackupService};
+pub use birthday::BirthdayService;
+pub use broadcast::BroadcastService;
+pub use calendar::CalendarService;
+pub use captcha::{CaptchaService, CaptchaChallenge, CaptchaOutcome};
 pub use cas::{CasService, CachedCasResult, CacheStats as CasCacheStats};
+pub use cas_queue::CasQueueService;
+pub use cas_recheck::CasRecheckService;
+pub use cleanup::CleanupService;
+pub use archive::ArchiveService;
+pub use step_reminder::StepReminderService;
+pub use config::{ConfigService, ConfigReload};
+pub use dance_style::DanceStyleService;
+pub use digest::DigestService;
+pub use feature_flags::{FeatureFlag, FeatureFlags, FeatureFlagService};
+pub use quiet_hours::QuietHoursService;
+pub use stats::StatsService;
 pub use google::{GoogleCalendarService, GoogleCalendarEvent, CalendarStats};
+pub use google_oauth::GoogleOAuthService;
+pub use google_watch::GoogleCalendarWatchService;
+pub use event::{EventService, RegistrationOutcome};
+pub use sheet_export::SheetExportService;
+pub use song_request::{SongRequestService, SongRequestOutcome};
+pub use group::GroupService;
+pub use health_server::HealthServer;
+pub use invite_link::InviteLinkService;
+pub use moderation::{ModerationService, WarnOutcome, Escalation, BanOutcome, UnbanOutcome};
 pub use notification::{NotificationService, MessageTemplate, NotificationRequest, BulkNotificationRequest, NotificationStats};
+pub use note::{NoteService, DoorListEntry};
+pub use notification_queue::{NotificationQueueService, SendRateLimiter};
+pub use notification_template::NotificationTemplateService;
+pub use outgoing_webhook::{OutgoingWebhookService, WebhookEvent};
+pub use public_api::PublicApiService;
+pub use referral::ReferralService;
+pub use reminder::ReminderService;
+pub use scheduled_message::ScheduledMessageService;
 pub use redis::{RedisService, CacheEntry, CacheStats as RedisCacheStats};
 pub use user::UserService;
+pub use venue::VenueService;
+pub use webapp::{WebAppService, WebAppAction};
 
-use crate::config::settings::Settings;
-use crate::database::repositories::UserRepository;
+use crate::config::settings::{Settings, SharedSettings};
+use crate::database::{DatabasePool, DatabaseService};
 use crate::utils::errors::Result;
-use teloxide::Bot;
+use crate::utils::redis_connection::RedisConnector;
+use serde::Serialize;
+use teloxide::{types::UserId, Bot};
 
 /// Service factory for creating and managing all services
 #[derive(Debug, Clone)]
@@ -29,32 +105,184 @@ pub struct ServiceFactory {
     pub auth_service: AuthService,
     pub cas_service: CasService,
     pub google_service: GoogleCalendarService,
+    pub google_oauth_service: GoogleOAuthService,
+    pub google_watch_service: GoogleCalendarWatchService,
+    pub sheet_export_service: SheetExportService,
+    pub event_service: EventService,
     pub notification_service: NotificationService,
     pub redis_service: RedisService,
+    pub note_service: NoteService,
+    pub referral_service: ReferralService,
+    pub reminder_service: ReminderService,
+    pub birthday_service: BirthdayService,
+    pub dance_style_service: DanceStyleService,
+    pub captcha_service: CaptchaService,
+    pub group_service: GroupService,
+    pub moderation_service: ModerationService,
+    pub cleanup_service: CleanupService,
+    pub archive_service: ArchiveService,
+    pub digest_service: DigestService,
+    pub quiet_hours_service: QuietHoursService,
+    pub stats_service: StatsService,
+    pub cas_recheck_service: CasRecheckService,
+    pub cas_queue_service: CasQueueService,
+    pub invite_link_service: InviteLinkService,
+    pub song_request_service: SongRequestService,
+    pub venue_service: VenueService,
+    pub broadcast_service: BroadcastService,
+    pub admin_audit_service: AdminAuditService,
+    pub activity_log_service: ActivityLogService,
+    pub feature_flag_service: FeatureFlagService,
+    pub backup_service: BackupService,
+    pub scheduled_message_service: ScheduledMessageService,
+    pub calendar_service: CalendarService,
+    pub config_service: ConfigService,
+    pub notification_queue_service: NotificationQueueService,
+    pub notification_template_service: NotificationTemplateService,
+    pub public_api_service: PublicApiService,
+    pub webapp_service: WebAppService,
+    pub outgoing_webhook_service: OutgoingWebhookService,
+    pub database_pool: DatabasePool,
 }
 
 impl ServiceFactory {
     /// Create a new ServiceFactory with all services initialized
     pub fn new(
         bot: Bot,
+        bot_id: UserId,
         settings: Settings,
-        user_repository: UserRepository,
-        redis_client: ::redis::Client,
+        database: DatabaseService,
+        redis: RedisConnector,
     ) -> Result<Self> {
-        let user_service = UserService::new(user_repository, settings.clone());
-        let auth_service = AuthService::new(bot.clone(), settings.clone());
-        let cas_service = CasService::new(redis_client.clone(), settings.clone())?;
-        let google_service = GoogleCalendarService::new(settings.clone())?;
-        let notification_service = NotificationService::new(bot, settings.clone());
-        let redis_service = RedisService::new(settings)?;
+        let shared_settings = SharedSettings::new(settings.clone());
+        let database_pool = database.pool.clone();
+        let feature_flags = FeatureFlags::new(settings.features.clone());
+        let feature_flag_service = FeatureFlagService::new(feature_flags.clone(), database.admin.clone());
+        let outgoing_webhook_service = OutgoingWebhookService::new(shared_settings.clone())?;
+        let activity_log_service = ActivityLogService::new(database.activity.clone());
+        let user_service = UserService::new(database.users.clone(), shared_settings.clone())
+            .with_webhooks(outgoing_webhook_service.clone())
+            .with_activity_log(activity_log_service.clone());
+        let auth_service = AuthService::new(bot.clone(), bot_id, database.roles.clone(), shared_settings.clone());
+        let captcha_service = CaptchaService::new(bot.clone(), database.captchas, database.groups.clone(), feature_flags.clone(), shared_settings.clone());
+        let moderation_service = ModerationService::new(bot.clone(), bot_id, database.moderation.clone(), database.groups.clone(), shared_settings.clone())
+            .with_webhooks(outgoing_webhook_service.clone())
+            .with_activity_log(activity_log_service.clone());
+        let cleanup_service = CleanupService::new(bot.clone(), database.cleanup, database.groups.clone(), shared_settings.clone());
+        let archive_service = ArchiveService::new(database.events.clone(), shared_settings.clone());
+        let cas_service = CasService::new(redis, database.admin.clone(), feature_flags.clone(), shared_settings.clone())?;
+        let admin_audit_service = AdminAuditService::new(database.admin.clone());
+        let backup_service = BackupService::new(database.admin.clone());
+        let stats_service = StatsService::new(database.stats);
+        let google_oauth_service = GoogleOAuthService::new(database.google_account, shared_settings.clone())?;
+        let google_service = GoogleCalendarService::new(feature_flags, shared_settings.clone())?.with_oauth(google_oauth_service.clone());
+        let sheet_export_service = SheetExportService::new(google_oauth_service.clone(), database.event_sheets, database.events.clone())?;
+        let event_service = EventService::new(database.events.clone(), database.users.clone(), sheet_export_service.clone())
+            .with_webhooks(outgoing_webhook_service.clone())
+            .with_activity_log(activity_log_service.clone());
+        let google_watch_service = GoogleCalendarWatchService::new(
+            google_service.clone(),
+            google_oauth_service.clone(),
+            database.google_calendar_sync,
+            event_service.clone(),
+            shared_settings.clone(),
+        );
+        let notification_service = NotificationService::new(bot.clone(), shared_settings.clone()).with_user_repository(database.users.clone());
+        let redis_service = RedisService::new(settings.clone())?;
+        let note_service = NoteService::new(database.notes, database.users.clone(), database.events.clone());
+        let referral_service = ReferralService::new(database.referrals, database.users.clone());
+        let invite_link_service = InviteLinkService::new(bot.clone(), database.invite_links);
+        let song_request_service = SongRequestService::new(database.song_requests, database.events.clone(), database.users.clone());
+        let venue_service = VenueService::new(database.venues, database.events.clone());
+        let broadcast_service = BroadcastService::new(bot.clone(), database.broadcasts, database.events.clone(), database.users.clone(), notification_service.clone());
+        let public_api_service = PublicApiService::new(
+            database.events.clone(),
+            database.calendars.clone(),
+            database.users.clone(),
+            database.groups.clone(),
+            shared_settings.clone(),
+        );
+        let webapp_service = WebAppService::new(public_api_service.clone(), database.events.clone(), database.users.clone(), shared_settings.clone());
+        let calendar_service = CalendarService::new(database.calendars);
+        let notification_queue_service = NotificationQueueService::new(bot.clone(), database.notification_queue, database.users.clone(), notification_service.clone());
+        let notification_template_service = NotificationTemplateService::new(database.notification_templates, notification_service.clone(), shared_settings.clone());
+        let config_service = ConfigService::new(shared_settings.clone(), feature_flag_service.clone());
+        let scheduled_message_service = ScheduledMessageService::new(
+            bot.clone(),
+            database.scheduled_messages,
+            database.groups.clone(),
+            broadcast_service.clone(),
+            notification_service.clone(),
+        );
+        let cas_recheck_service = CasRecheckService::new(
+            bot.clone(),
+            cas_service.clone(),
+            database.member_activity,
+            redis_service.clone(),
+            database.groups.clone(),
+            database.moderation,
+            stats_service.clone(),
+            notification_service.clone(),
+            shared_settings.clone(),
+        );
+        let cas_queue_service = CasQueueService::new(
+            bot.clone(),
+            cas_service.clone(),
+            database.groups.clone(),
+            moderation_service.clone(),
+            stats_service.clone(),
+            shared_settings.clone(),
+        );
+        let quiet_hours_service = QuietHoursService::new(bot.clone(), notification_service.clone(), database.quiet_hours, database.groups.clone());
+        let digest_service = DigestService::new(bot.clone(), database.events.clone(), database.groups.clone(), notification_service.clone(), shared_settings.clone());
+        let reminder_service = ReminderService::new(bot, database.events, database.groups.clone(), notification_service.clone(), quiet_hours_service.clone(), shared_settings.clone());
+        let birthday_service = BirthdayService::new(database.birthdays, database.users.clone(), database.groups.clone(), notification_service.clone(), quiet_hours_service.clone(), shared_settings.clone());
+        let group_service = GroupService::new(database.groups, database.roles);
+        let dance_style_service = DanceStyleService::new(database.dance_styles, database.users);
 
         Ok(Self {
             user_service,
             auth_service,
             cas_service,
             google_service,
+            google_oauth_service,
+            google_watch_service,
+            sheet_export_service,
+            event_service,
             notification_service,
             redis_service,
+            note_service,
+            referral_service,
+            reminder_service,
+            birthday_service,
+            dance_style_service,
+            captcha_service,
+            group_service,
+            moderation_service,
+            cleanup_service,
+            archive_service,
+            digest_service,
+            quiet_hours_service,
+            stats_service,
+            cas_recheck_service,
+            cas_queue_service,
+            invite_link_service,
+            song_request_service,
+            venue_service,
+            broadcast_service,
+            admin_audit_service,
+            activity_log_service,
+            feature_flag_service,
+            backup_service,
+            scheduled_message_service,
+            calendar_service,
+            config_service,
+            notification_queue_service,
+            notification_template_service,
+            public_api_service,
+            webapp_service,
+            outgoing_webhook_service,
+            database_pool,
         })
     }
 
@@ -65,11 +293,27 @@ impl ServiceFactory {
 
     /// Health check for all services
     pub async fn health_check(&self) -> ServiceHealthStatus {
-        let redis_healthy = self.redis_service.health_check().await.unwrap_or(false);
+        let acquire_wait = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, crate::database::health_check(&self.database_pool)).await {
+            Ok(Ok(acquire_wait)) => Some(acquire_wait),
+            _ => None,
+        };
+        let redis_healthy = matches!(
+            tokio::time::timeout(HEALTH_CHECK_TIMEOUT, self.redis_service.health_check()).await,
+            Ok(Ok(true))
+        );
         let google_enabled = self.google_service.is_enabled();
         let cas_enabled = self.cas_service.is_enabled();
 
+        let metrics = crate::utils::metrics::metrics();
+
         ServiceHealthStatus {
+            database_healthy: acquire_wait.is_some(),
+            database_pool_size: self.database_pool.size(),
+            database_pool_idle: self.database_pool.num_idle(),
+            database_pool_max: self.database_pool.options().get_max_connections(),
+            database_acquire_ms: acquire_wait.map(|wait| wait.as_millis() as u64),
+            database_slow_queries: metrics.slow_query_count(),
+            database_slow_query_ms: metrics.slow_query_threshold().as_millis() as u64,
             redis_healthy,
             google_enabled,
             cas_enabled,
@@ -80,9 +324,22 @@ impl ServiceFactory {
     }
 }
 
+/// How long a database or Redis health check may take before it counts as failed
+const HEALTH_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
 /// Health status for all services
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ServiceHealthStatus {
+    pub database_healthy: bool,
+    /// Open connections, idle or in use
+    pub database_pool_size: u32,
+    pub database_pool_idle: usize,
+    pub database_pool_max: u32,
+    /// Time the health check waited for a connection; `None` if it failed
+    pub database_acquire_ms: Option<u64>,
+    /// Queries slower than `database_slow_query_ms` since startup
+    pub database_slow_queries: u64,
+    pub database_slow_query_ms: u64,
     pub redis_healthy: bool,
     pub google_enabled: bool,
     pub cas_enabled: bool,
@@ -97,10 +354,31 @@ impl ServiceHealthStatus {
         self.user_service_ready && self.auth_service_ready && self.notification_service_ready
     }
 
+    /// Check if the bot can serve updates: critical services plus database and Redis
+    pub fn is_ready(&self) -> bool {
+        self.is_healthy() && self.database_healthy && self.redis_healthy
+    }
+
+    /// Connections in use; the pool is exhausted once this reaches `database_pool_max`
+    pub fn database_pool_in_use(&self) -> u32 {
+        self.database_pool_size.saturating_sub(self.database_pool_idle as u32)
+    }
+
+    /// Whether every connection the pool may open is busy, so queries wait for one
+    pub fn is_database_pool_exhausted(&self) -> bool {
+        self.database_pool_max > 0 && self.database_pool_in_use() >= self.database_pool_max
+    }
+
     /// Get list of unhealthy services
     pub fn get_issues(&self) -> Vec<String> {
         let mut issues = Vec::new();
 
+        if !self.database_healthy {
+            issues.push("Database connection failed".to_string());
+        }
+        if self.is_database_pool_exhausted() {
+            issues.push("All database connections are in use".to_string());
+        }
         if !self.redis_healthy {
             issues.push("Redis connection failed".to_string());
         }
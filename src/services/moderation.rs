@@ -0,0 +1,826 @@
+//! Moderation service implementation
+//!
+//! This service records warnings issued by group admins and escalates them:
+//! after the configured number of warnings a member is muted for a while, and
+//! after more warnings they are banned. Every step is written to the audit trail.
+//! Members can also report messages, which are delivered to the group admins.
+//! Groups that opt in share a ban list: a ban in one of them applies to all.
+//! Members flooding a group are muted for a short while, and messages matching
+//! a group's spam filters are removed. Admins can mute members for a chosen time;
+//! a scheduler lifts the mute when it runs out.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use chrono::{Duration, Utc, DateTime};
+use regex::{Regex, RegexBuilder};
+use teloxide::{Bot, prelude::*, types::{ChatId, ChatPermissions, MessageId, UserId}};
+use tracing::{info, warn, error, debug};
+use crate::config::settings::{SharedSettings, WarningsConfig};
+use crate::database::repositories::{ModerationRepository, GroupRepository};
+use crate::middleware::rate_limit::{RateLimitConfig, RateLimitMiddleware};
+use crate::models::group::Group;
+use crate::models::moderation::{CreateWarningRequest, CreateModerationActionRequest, ModerationActionKind, Report, CreateReportRequest, SharedBan, CreateSharedBanRequest, SpamFilter, CreateSpamFilterRequest, SpamFilterKind, BanListEntry, CAS_BAN_REASON};
+use crate::models::activity::ActivityAction;
+use crate::services::activity_log::ActivityLogService;
+use crate::services::outgoing_webhook::{OutgoingWebhookService, WebhookEvent};
+use crate::services::user::page_count;
+use crate::utils::errors::{Result, SwingBuddyError};
+
+/// Longest mute admins can set; Telegram treats longer restrictions as permanent
+pub const MAX_MUTE_DAYS: i64 = 365;
+/// How often the scheduler looks for mutes that ran out
+/// Bans shown per page of the admin ban list
+pub const BAN_LIST_PAGE_SIZE: i64 = 10;
+
+const UNMUTE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+/// How long a chat's admin list is trusted without member updates refreshing it
+const CHAT_ADMINS_TTL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Human admins of a chat, as last fetched from Telegram
+#[derive(Debug, Clone)]
+struct CachedAdmins {
+    user_ids: Vec<i64>,
+    fetched_at: std::time::Instant,
+}
+
+/// Action taken automatically after a warning
+#[derive(Debug, Clone, PartialEq)]
+pub enum Escalation {
+    /// The warning was only recorded
+    None,
+    /// The member was muted until the given time
+    Muted { until: DateTime<Utc> },
+    /// The member was banned from the group
+    Banned,
+}
+
+/// Result of warning a member
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarnOutcome {
+    /// Warnings the member now has in the group
+    pub count: i64,
+    /// Warnings that lead to a ban, if banning is configured
+    pub ban_after: Option<u32>,
+    pub escalation: Escalation,
+}
+
+/// Result of banning a member
+#[derive(Debug, Clone, PartialEq)]
+pub struct BanOutcome {
+    /// Whether the member was added to the shared ban list
+    pub shared: bool,
+    /// Other groups the member was banned in through the shared list
+    pub other_groups: usize,
+}
+
+/// Result of unbanning a member
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnbanOutcome {
+    /// Whether the member was removed from the shared ban list
+    pub removed_from_shared: bool,
+    /// Other groups the member was unbanned in
+    pub other_groups: usize,
+}
+
+/// Longest spam filter pattern admins may add
+const SPAM_PATTERN_MAX_LENGTH: usize = 200;
+
+/// A spam filter prepared for matching
+#[derive(Debug, Clone)]
+pub enum SpamMatcher {
+    Keyword(String),
+    Regex(Regex),
+    Domain(String),
+}
+
+impl SpamMatcher {
+    /// Prepare a filter pattern; fails for invalid regular expressions and domains
+    pub fn new(kind: SpamFilterKind, pattern: &str) -> Result<Self> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() || pattern.chars().count() > SPAM_PATTERN_MAX_LENGTH {
+            return Err(SwingBuddyError::InvalidInput(format!(
+                "Spam filter pattern must be between 1 and {} characters", SPAM_PATTERN_MAX_LENGTH
+            )));
+        }
+
+        match kind {
+            SpamFilterKind::Keyword => Ok(Self::Keyword(pattern.to_lowercase())),
+            SpamFilterKind::Regex => RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .size_limit(1 << 20)
+                .build()
+                .map(Self::Regex)
+                .map_err(|e| SwingBuddyError::InvalidInput(format!("Invalid regular expression: {}", e))),
+            SpamFilterKind::Domain => link_host(pattern)
+                .filter(|host| host.contains('.'))
+                .map(Self::Domain)
+                .ok_or_else(|| SwingBuddyError::InvalidInput(format!("Invalid domain: {}", pattern))),
+        }
+    }
+
+    /// Pattern as stored: keywords lowercased, domains without scheme and path
+    pub fn pattern(&self) -> &str {
+        match self {
+            Self::Keyword(keyword) => keyword,
+            Self::Regex(regex) => regex.as_str(),
+            Self::Domain(domain) => domain,
+        }
+    }
+
+    /// Check a message text and the links hidden behind its formatting
+    pub fn is_match(&self, text: &str, links: &[String]) -> bool {
+        match self {
+            Self::Keyword(keyword) => text.to_lowercase().contains(keyword.as_str()),
+            Self::Regex(regex) => regex.is_match(text),
+            Self::Domain(domain) => text.split_whitespace()
+                .chain(links.iter().map(String::as_str))
+                .filter_map(link_host)
+                .any(|host| host == *domain || host.ends_with(&format!(".{}", domain))),
+        }
+    }
+}
+
+/// Spam filters of a chat with their prepared matchers
+type ChatSpamFilters = Arc<Vec<(SpamFilter, SpamMatcher)>>;
+
+/// Host of a link-like token, lowercased and without `www.`
+fn link_host(token: &str) -> Option<String> {
+    let token = token.trim_matches(|c: char| !c.is_alphanumeric() && c != '/' && c != ':' && c != '.');
+    let without_scheme = token.split_once("://").map_or(token, |(_, rest)| rest);
+    let host = without_scheme
+        .split(['/', '?', '#', ':'])
+        .next()?
+        .trim_end_matches('.')
+        .to_lowercase();
+    let host = host.strip_prefix("www.").map(str::to_string).unwrap_or(host);
+
+    (host.contains('.') && host.chars().all(|c| c.is_alphanumeric() || c == '.' || c == '-')).then_some(host)
+}
+
+/// Moderation service for warnings, bans and reports
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ModerationService {
+    bot: Bot,
+    /// The bot's own user ID, from getMe at startup
+    bot_id: UserId,
+    moderation_repository: ModerationRepository,
+    group_repository: GroupRepository,
+    /// Counts group messages per member; `None` without flood control configured
+    flood_limiter: Option<RateLimitMiddleware>,
+    /// Prepared spam filters per chat, loaded on first use
+    spam_filters: Arc<RwLock<HashMap<i64, ChatSpamFilters>>>,
+    /// Human admins per chat for delivering reports, loaded on first use
+    chat_admins: Arc<RwLock<HashMap<i64, CachedAdmins>>>,
+    settings: SharedSettings,
+    webhook_service: Option<OutgoingWebhookService>,
+    activity_log: Option<ActivityLogService>,
+}
+
+impl ModerationService {
+    /// Create a new ModerationService instance
+    pub fn new(
+        bot: Bot,
+        bot_id: UserId,
+        moderation_repository: ModerationRepository,
+        group_repository: GroupRepository,
+        settings: SharedSettings,
+    ) -> Self {
+        let current = settings.get();
+        let flood_limiter = current.flood.as_ref().map(|flood| RateLimitMiddleware::new(
+            RateLimitConfig {
+                max_requests: flood.max_messages,
+                window_duration: std::time::Duration::from_secs(flood.window_seconds),
+                burst_allowance: 0,
+            },
+            true,
+            current.bot.admin_ids.clone(),
+        ));
+
+        Self {
+            bot,
+            bot_id,
+            moderation_repository,
+            group_repository,
+            flood_limiter,
+            spam_filters: Arc::new(RwLock::new(HashMap::new())),
+            chat_admins: Arc::new(RwLock::new(HashMap::new())),
+            settings,
+            webhook_service: None,
+            activity_log: None,
+        }
+    }
+
+    /// Announce bans and unbans through outgoing webhooks
+    pub fn with_webhooks(mut self, webhook_service: OutgoingWebhookService) -> Self {
+        self.webhook_service = Some(webhook_service);
+        self
+    }
+
+    /// Copy moderation actions into the activity log
+    pub fn with_activity_log(mut self, activity_log: ActivityLogService) -> Self {
+        self.activity_log = Some(activity_log);
+        self
+    }
+
+    /// Warn a group member and mute or ban them if they reached a threshold
+    pub async fn warn(&self, chat_id: ChatId, user_id: UserId, issued_by: i64, reason: Option<String>) -> Result<WarnOutcome> {
+        let user_telegram_id = user_id.0 as i64;
+
+        self.moderation_repository.create_warning(CreateWarningRequest {
+            chat_telegram_id: chat_id.0,
+            user_telegram_id,
+            issued_by,
+            reason: reason.clone(),
+        }).await?;
+        self.log_action(chat_id, user_telegram_id, Some(issued_by), ModerationActionKind::Warn, reason).await?;
+
+        let settings = self.settings.get();
+        let config = settings.warnings.as_ref();
+        let since = config
+            .filter(|config| config.expire_days > 0)
+            .map(|config| Utc::now() - Duration::days(config.expire_days as i64));
+        let count = self.moderation_repository.count_warnings(chat_id.0, user_telegram_id, since).await?;
+
+        info!(chat_id = chat_id.0, user_id = user_telegram_id, issued_by = issued_by, count = count, "Member warned");
+
+        let escalation = match config.and_then(|config| escalation_for(count, config)) {
+            Some(ModerationActionKind::Ban) => {
+                self.bot.ban_chat_member(chat_id, user_id).await?;
+                self.log_action(chat_id, user_telegram_id, None, ModerationActionKind::Ban, Some(format!("{} warnings", count))).await?;
+                info!(chat_id = chat_id.0, user_id = user_telegram_id, "Member banned after warnings");
+                Escalation::Banned
+            }
+            Some(ModerationActionKind::Mute) => {
+                let minutes = config.map_or(0, |config| config.mute_minutes);
+                let until = Utc::now() + Duration::minutes(minutes as i64);
+                self.bot.restrict_chat_member(chat_id, user_id, ChatPermissions::empty())
+                    .until_date(until)
+                    .await?;
+                self.log_action(chat_id, user_telegram_id, None, ModerationActionKind::Mute, Some(format!("{} warnings", count))).await?;
+                info!(chat_id = chat_id.0, user_id = user_telegram_id, until = %until, "Member muted after warnings");
+                Escalation::Muted { until }
+            }
+            _ => Escalation::None,
+        };
+
+        Ok(WarnOutcome {
+            count,
+            ban_after: config.map(|config| config.ban_after).filter(|ban_after| *ban_after > 0),
+            escalation,
+        })
+    }
+
+    /// Record a member report; returns `None` if the member already reported the message
+    pub async fn report(&self, request: CreateReportRequest) -> Result<Option<Report>> {
+        let report = self.moderation_repository.create_report(request).await?;
+
+        if let Some(report) = &report {
+            info!(report_id = report.id, chat_id = report.chat_telegram_id, reporter = report.reporter_telegram_id, "Message reported");
+        }
+
+        Ok(report)
+    }
+
+    /// Get a report by ID
+    pub async fn get_report(&self, report_id: i64) -> Result<Option<Report>> {
+        self.moderation_repository.find_report(report_id).await
+    }
+
+    /// Mark a report as handled; returns `None` if someone resolved it already
+    pub async fn resolve_report(&self, report_id: i64, resolved_by: i64) -> Result<Option<Report>> {
+        let report = self.moderation_repository.resolve_report(report_id, resolved_by).await?;
+
+        if report.is_some() {
+            info!(report_id = report_id, resolved_by = resolved_by, "Report resolved");
+        }
+
+        Ok(report)
+    }
+
+    /// Count the reports in a group no admin has handled yet
+    pub async fn count_open_reports(&self, chat_id: ChatId) -> Result<i64> {
+        self.moderation_repository.count_open_reports(chat_id.0).await
+    }
+
+    /// Chats that receive reports from a group: its report chat if one is set,
+    /// otherwise the private chats of its human admins
+    pub async fn report_recipients(&self, chat_id: ChatId, group: Option<&Group>) -> Result<Vec<ChatId>> {
+        if let Some(report_chat_id) = group.and_then(Group::report_chat_id) {
+            return Ok(vec![ChatId(report_chat_id)]);
+        }
+
+        let cached = self.chat_admins.read().unwrap().get(&chat_id.0)
+            .filter(|admins| admins.fetched_at.elapsed() < CHAT_ADMINS_TTL)
+            .map(|admins| admins.user_ids.clone());
+        let user_ids = match cached {
+            Some(user_ids) => user_ids,
+            None => {
+                let admins = match self.bot.get_chat_administrators(chat_id).await {
+                    Ok(admins) => admins,
+                    Err(e) => {
+                        warn!(error = %e, chat_id = chat_id.0, "Failed to get chat administrators for a report");
+                        return Ok(Vec::new());
+                    }
+                };
+                let user_ids: Vec<i64> = admins.into_iter()
+                    .filter(|admin| !admin.user.is_bot)
+                    .map(|admin| admin.user.id.0 as i64)
+                    .collect();
+                self.chat_admins.write().unwrap().insert(chat_id.0, CachedAdmins {
+                    user_ids: user_ids.clone(),
+                    fetched_at: std::time::Instant::now(),
+                });
+                user_ids
+            }
+        };
+
+        Ok(user_ids.into_iter().map(ChatId).collect())
+    }
+
+    /// Note that a member of a chat was promoted to admin or lost the admin
+    /// status, in the chat's cached admin list if there is one
+    pub fn update_chat_admin(&self, chat_id: ChatId, user_id: i64, is_admin: bool) {
+        let mut chat_admins = self.chat_admins.write().unwrap();
+        let Some(admins) = chat_admins.get_mut(&chat_id.0) else {
+            return;
+        };
+        admins.user_ids.retain(|&admin_id| admin_id != user_id);
+        if is_admin {
+            admins.user_ids.push(user_id);
+        }
+        debug!(chat_id = chat_id.0, user_id = user_id, is_admin = is_admin, "Cached chat admins updated");
+    }
+
+    /// Drop the cached admin list of a chat, e.g. when the bot leaves it
+    pub fn forget_chat_admins(&self, chat_id: ChatId) {
+        self.chat_admins.write().unwrap().remove(&chat_id.0);
+    }
+
+    /// Ban a member from a group. If the group shares the ban list, the member is
+    /// added to it and banned in every other group that shares it as well.
+    pub async fn ban(&self, chat_id: ChatId, user_id: UserId, banned_by: i64, reason: Option<String>) -> Result<BanOutcome> {
+        let user_telegram_id = user_id.0 as i64;
+
+        self.bot.ban_chat_member(chat_id, user_id).await?;
+        self.log_action(chat_id, user_telegram_id, Some(banned_by), ModerationActionKind::Ban, reason.clone()).await?;
+        info!(chat_id = chat_id.0, user_id = user_telegram_id, banned_by = banned_by, "Member banned");
+
+        let shares = self.group_repository.find_by_telegram_id(chat_id.0).await?
+            .is_some_and(|group| group.shared_bans_enabled());
+        if !shares {
+            return Ok(BanOutcome { shared: false, other_groups: 0 });
+        }
+
+        self.moderation_repository.add_shared_ban(CreateSharedBanRequest {
+            user_telegram_id,
+            banned_by,
+            origin_chat_telegram_id: chat_id.0,
+            reason: reason.clone(),
+        }).await?;
+
+        let mut other_groups = 0;
+        for group in self.shared_ban_groups(chat_id).await? {
+            let group_chat_id = ChatId(group.telegram_id);
+            match self.bot.ban_chat_member(group_chat_id, user_id).await {
+                Ok(_) => {
+                    self.log_action(group_chat_id, user_telegram_id, Some(banned_by), ModerationActionKind::Ban, reason.clone()).await?;
+                    other_groups += 1;
+                }
+                Err(e) => warn!(error = %e, chat_id = group.telegram_id, user_id = user_telegram_id, "Failed to apply shared ban"),
+            }
+        }
+
+        info!(user_id = user_telegram_id, other_groups = other_groups, "Member added to the shared ban list");
+        Ok(BanOutcome { shared: true, other_groups })
+    }
+
+    /// Unban a member from a group. With `lift_shared`, the member is also removed
+    /// from the shared ban list and unbanned in the groups that share it.
+    pub async fn unban(&self, chat_id: ChatId, user_id: UserId, unbanned_by: i64, lift_shared: bool) -> Result<UnbanOutcome> {
+        let user_telegram_id = user_id.0 as i64;
+
+        self.bot.unban_chat_member(chat_id, user_id).only_if_banned(true).await?;
+        self.log_action(chat_id, user_telegram_id, Some(unbanned_by), ModerationActionKind::Unban, None).await?;
+        info!(chat_id = chat_id.0, user_id = user_telegram_id, unbanned_by = unbanned_by, "Member unbanned");
+
+        if !lift_shared || !self.moderation_repository.remove_shared_ban(user_telegram_id).await? {
+            return Ok(UnbanOutcome { removed_from_shared: false, other_groups: 0 });
+        }
+
+        let mut other_groups = 0;
+        for group in self.shared_ban_groups(chat_id).await? {
+            let group_chat_id = ChatId(group.telegram_id);
+            match self.bot.unban_chat_member(group_chat_id, user_id).only_if_banned(true).await {
+                Ok(_) => {
+                    self.log_action(group_chat_id, user_telegram_id, Some(unbanned_by), ModerationActionKind::Unban, None).await?;
+                    other_groups += 1;
+                }
+                Err(e) => warn!(error = %e, chat_id = group.telegram_id, user_id = user_telegram_id, "Failed to lift shared ban"),
+            }
+        }
+
+        info!(user_id = user_telegram_id, other_groups = other_groups, "Member removed from the shared ban list");
+        Ok(UnbanOutcome { removed_from_shared: true, other_groups })
+    }
+
+    /// Record a ban triggered by a CAS listing in the moderation log
+    pub async fn log_cas_ban(&self, chat_id: ChatId, user_telegram_id: i64) -> Result<()> {
+        self.log_action(chat_id, user_telegram_id, None, ModerationActionKind::Ban, Some(CAS_BAN_REASON.to_string())).await
+    }
+
+    /// Get one page of the current bans and the number of pages
+    pub async fn list_bans_page(&self, page: i64) -> Result<(Vec<BanListEntry>, i64)> {
+        debug!(page = page, "Listing current bans for admin");
+
+        let total = self.moderation_repository.count_current_bans().await?;
+        let pages = page_count(total, BAN_LIST_PAGE_SIZE);
+        let page = page.clamp(0, pages - 1);
+        let bans = self.moderation_repository.list_current_bans(BAN_LIST_PAGE_SIZE, page * BAN_LIST_PAGE_SIZE).await?;
+
+        Ok((bans, pages))
+    }
+
+    /// Get the shared ban of a member
+    pub async fn get_shared_ban(&self, user_id: UserId) -> Result<Option<SharedBan>> {
+        self.moderation_repository.find_shared_ban(user_id.0 as i64).await
+    }
+
+    /// Ban a new member who is on the shared ban list, if the group shares it.
+    /// Returns `true` if the member was banned.
+    pub async fn enforce_shared_ban(&self, chat_id: ChatId, user_id: UserId) -> Result<bool> {
+        let shares = self.group_repository.find_by_telegram_id(chat_id.0).await?
+            .is_some_and(|group| group.shared_bans_enabled());
+        if !shares {
+            return Ok(false);
+        }
+
+        let Some(ban) = self.moderation_repository.find_shared_ban(user_id.0 as i64).await? else {
+            return Ok(false);
+        };
+
+        self.bot.ban_chat_member(chat_id, user_id).await?;
+        self.log_action(chat_id, ban.user_telegram_id, None, ModerationActionKind::Ban, Some("shared ban list".to_string())).await?;
+        info!(chat_id = chat_id.0, user_id = ban.user_telegram_id, "Shared ban applied to a new member");
+
+        Ok(true)
+    }
+
+    /// Ban everyone on the shared ban list in a group that just opted in.
+    /// Returns how many members were banned.
+    pub async fn apply_shared_bans(&self, chat_id: ChatId) -> Result<usize> {
+        let mut applied = 0;
+        for ban in self.moderation_repository.list_shared_bans().await? {
+            match self.bot.ban_chat_member(chat_id, UserId(ban.user_telegram_id as u64)).await {
+                Ok(_) => applied += 1,
+                Err(e) => warn!(error = %e, chat_id = chat_id.0, user_id = ban.user_telegram_id, "Failed to apply shared ban"),
+            }
+        }
+
+        info!(chat_id = chat_id.0, applied = applied, "Shared ban list applied to group");
+        Ok(applied)
+    }
+
+    /// Groups sharing the ban list, except the given one
+    async fn shared_ban_groups(&self, except: ChatId) -> Result<Vec<Group>> {
+        let groups = self.group_repository.get_active_groups_with_flag("shared_bans").await?;
+        Ok(groups.into_iter().filter(|group| group.telegram_id != except.0).collect())
+    }
+
+    /// Count a group message and tell whether its author is flooding the group
+    pub fn is_flooding(&self, chat_id: ChatId, user: &teloxide::types::User) -> bool {
+        self.flood_limiter.as_ref()
+            .is_some_and(|limiter| limiter.check_chat_rate_limit(chat_id.0, user).is_err())
+    }
+
+    /// Mute a flooding member for the configured time and start counting their messages anew.
+    /// Returns when the mute ends.
+    pub async fn mute_for_flood(&self, chat_id: ChatId, user_id: UserId) -> Result<DateTime<Utc>> {
+        let mute_seconds = self.settings.get().flood.as_ref().map_or(60, |flood| flood.mute_seconds);
+        let until = Utc::now() + Duration::seconds(mute_seconds as i64);
+
+        self.bot.restrict_chat_member(chat_id, user_id, ChatPermissions::empty())
+            .until_date(until)
+            .await?;
+        if let Some(limiter) = &self.flood_limiter {
+            limiter.clear_chat_rate_limit(chat_id.0, user_id.0 as i64);
+        }
+        self.log_action(chat_id, user_id.0 as i64, None, ModerationActionKind::Mute, Some("flood".to_string())).await?;
+
+        info!(chat_id = chat_id.0, user_id = user_id.0, until = %until, "Member muted for flooding");
+        Ok(until)
+    }
+
+    /// Mute a member for the given time; the scheduler unmutes them afterwards.
+    /// Returns when the mute ends.
+    pub async fn mute(&self, chat_id: ChatId, user_id: UserId, muted_by: i64, duration: Duration, reason: Option<String>) -> Result<DateTime<Utc>> {
+        let user_telegram_id = user_id.0 as i64;
+        let until = Utc::now() + duration;
+
+        self.bot.restrict_chat_member(chat_id, user_id, ChatPermissions::empty())
+            .until_date(until)
+            .await?;
+        self.moderation_repository.schedule_unmute(chat_id.0, user_telegram_id, until).await?;
+        self.log_action(chat_id, user_telegram_id, Some(muted_by), ModerationActionKind::Mute, reason).await?;
+
+        info!(chat_id = chat_id.0, user_id = user_telegram_id, muted_by = muted_by, until = %until, "Member muted");
+        Ok(until)
+    }
+
+    /// Lift a member's mute, giving them the group's default permissions back.
+    /// `unmuted_by` is `None` when the mute ran out.
+    pub async fn unmute(&self, chat_id: ChatId, user_id: UserId, unmuted_by: Option<i64>) -> Result<()> {
+        let user_telegram_id = user_id.0 as i64;
+
+        let permissions = self.bot.get_chat(chat_id).await?
+            .permissions()
+            .unwrap_or_else(ChatPermissions::all);
+        self.bot.restrict_chat_member(chat_id, user_id, permissions).await?;
+        self.moderation_repository.delete_scheduled_unmute(chat_id.0, user_telegram_id).await?;
+        self.log_action(chat_id, user_telegram_id, unmuted_by, ModerationActionKind::Unmute, None).await?;
+
+        info!(chat_id = chat_id.0, user_id = user_telegram_id, unmuted_by = ?unmuted_by, "Member unmuted");
+        Ok(())
+    }
+
+    /// Unmute the members whose mute ran out
+    pub async fn unmute_due(&self) -> Result<usize> {
+        let due = self.moderation_repository.get_due_unmutes(Utc::now()).await?;
+        let mut unmuted = 0;
+
+        for unmute in due {
+            let chat_id = ChatId(unmute.chat_telegram_id);
+            let user_id = UserId(unmute.user_telegram_id as u64);
+            match self.unmute(chat_id, user_id, None).await {
+                Ok(()) => unmuted += 1,
+                Err(e) => {
+                    // The member may have left or the bot lost its rights; Telegram lifts the mute anyway
+                    warn!(error = %e, chat_id = unmute.chat_telegram_id, user_id = unmute.user_telegram_id, "Failed to unmute member");
+                    self.moderation_repository.delete_scheduled_unmute(unmute.chat_telegram_id, unmute.user_telegram_id).await?;
+                }
+            }
+        }
+
+        Ok(unmuted)
+    }
+
+    /// Start the background task that unmutes members whose mute ran out
+    pub fn start_unmute_scheduler(self) -> tokio::task::JoinHandle<()> {
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(UNMUTE_CHECK_INTERVAL);
+
+            loop {
+                check_interval.tick().await;
+
+                match self.unmute_due().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("Unmute scheduler lifted {} mutes", count);
+                        }
+                    }
+                    Err(e) => error!("Unmute scheduler failed: {}", e),
+                }
+            }
+        });
+
+        info!("Started unmute scheduler with interval {:?}", UNMUTE_CHECK_INTERVAL);
+        handle
+    }
+
+    /// Add a spam filter to a group; returns `None` if the group already has it
+    pub async fn add_spam_filter(&self, chat_id: ChatId, kind: SpamFilterKind, pattern: &str, created_by: i64) -> Result<Option<SpamFilter>> {
+        let matcher = SpamMatcher::new(kind, pattern)?;
+
+        let filter = self.moderation_repository.create_spam_filter(CreateSpamFilterRequest {
+            chat_telegram_id: chat_id.0,
+            kind,
+            pattern: matcher.pattern().to_string(),
+            created_by,
+        }).await?;
+        self.forget_spam_filters(chat_id);
+
+        if let Some(filter) = &filter {
+            info!(chat_id = chat_id.0, filter_id = filter.id, kind = %filter.kind, "Spam filter added");
+        }
+
+        Ok(filter)
+    }
+
+    /// Get the spam filters of a group
+    pub async fn get_spam_filters(&self, chat_id: ChatId) -> Result<Vec<SpamFilter>> {
+        self.moderation_repository.find_spam_filters(chat_id.0).await
+    }
+
+    /// Remove a spam filter, returning it if it existed
+    pub async fn remove_spam_filter(&self, filter_id: i64) -> Result<Option<SpamFilter>> {
+        let filter = self.moderation_repository.delete_spam_filter(filter_id).await?;
+
+        if let Some(filter) = &filter {
+            self.forget_spam_filters(ChatId(filter.chat_telegram_id));
+            info!(chat_id = filter.chat_telegram_id, filter_id = filter.id, "Spam filter removed");
+        }
+
+        Ok(filter)
+    }
+
+    /// Find the first spam filter of a group a message matches
+    pub async fn find_spam_match(&self, chat_id: ChatId, text: &str, links: &[String]) -> Result<Option<SpamFilter>> {
+        let cached = self.spam_filters.read().unwrap().get(&chat_id.0).cloned();
+        let filters = match cached {
+            Some(filters) => filters,
+            None => {
+                let filters = self.moderation_repository.find_spam_filters(chat_id.0).await?
+                    .into_iter()
+                    .filter_map(|filter| SpamMatcher::new(filter.filter_kind(), &filter.pattern).ok().map(|matcher| (filter, matcher)))
+                    .collect::<Vec<_>>();
+                let filters = Arc::new(filters);
+                self.spam_filters.write().unwrap().insert(chat_id.0, filters.clone());
+                filters
+            }
+        };
+
+        Ok(filters.iter()
+            .find(|(_, matcher)| matcher.is_match(text, links))
+            .map(|(filter, _)| filter.clone()))
+    }
+
+    /// Delete a message caught by a spam filter and, with `warn_sender`, warn its author
+    pub async fn remove_spam(
+        &self,
+        chat_id: ChatId,
+        message_id: MessageId,
+        user_id: UserId,
+        filter: &SpamFilter,
+        warn_sender: bool,
+    ) -> Result<Option<WarnOutcome>> {
+        let reason = format!("spam filter: {} {}", filter.kind, filter.pattern);
+
+        self.bot.delete_message(chat_id, message_id).await?;
+        self.log_action(chat_id, user_id.0 as i64, None, ModerationActionKind::Delete, Some(reason.clone())).await?;
+        info!(chat_id = chat_id.0, user_id = user_id.0, filter_id = filter.id, "Message removed by spam filter");
+
+        if !warn_sender {
+            return Ok(None);
+        }
+
+        self.warn(chat_id, user_id, self.bot_id.0 as i64, Some(reason)).await.map(Some)
+    }
+
+    /// Drop the prepared spam filters of a chat after they changed
+    fn forget_spam_filters(&self, chat_id: ChatId) {
+        self.spam_filters.write().unwrap().remove(&chat_id.0);
+    }
+
+    /// Start the background task that drops stale flood control counters
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        let limiter = self.flood_limiter?;
+        let interval = std::time::Duration::from_secs(self.settings.get().flood.as_ref()?.window_seconds * 2);
+
+        let handle = tokio::spawn(async move {
+            let mut cleanup_interval = tokio::time::interval(interval);
+
+            loop {
+                cleanup_interval.tick().await;
+                limiter.cleanup_old_entries();
+                debug!("Flood control counters cleaned up");
+            }
+        });
+
+        info!("Started flood control cleanup task with interval {:?}", interval);
+        Some(handle)
+    }
+
+    /// Write a moderation action to the audit trail
+    async fn log_action(
+        &self,
+        chat_id: ChatId,
+        target_telegram_id: i64,
+        actor_telegram_id: Option<i64>,
+        action: ModerationActionKind,
+        reason: Option<String>,
+    ) -> Result<()> {
+        self.moderation_repository.log_action(CreateModerationActionRequest {
+            chat_telegram_id: chat_id.0,
+            target_telegram_id,
+            actor_telegram_id,
+            action,
+            reason: reason.clone(),
+        }).await?;
+
+        if let Some(activity_log) = &self.activity_log {
+            activity_log.record(
+                ActivityAction::Moderation(action),
+                actor_telegram_id,
+                target_telegram_id,
+                serde_json::json!({ "chat_id": chat_id.0, "reason": reason }),
+            ).await;
+        }
+
+        let webhook_event = match action {
+            ModerationActionKind::Ban => Some(WebhookEvent::UserBanned),
+            ModerationActionKind::Unban => Some(WebhookEvent::UserUnbanned),
+            _ => None,
+        };
+        if let (Some(webhook_service), Some(webhook_event)) = (&self.webhook_service, webhook_event) {
+            webhook_service.emit(webhook_event, &serde_json::json!({
+                "chat_id": chat_id.0,
+                "telegram_id": target_telegram_id,
+                "actor_telegram_id": actor_telegram_id,
+                "reason": reason,
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a mute duration such as `30m`, `2h` or `7d`, up to [`MAX_MUTE_DAYS`]
+pub fn parse_mute_duration(input: &str) -> Option<Duration> {
+    let input = input.trim().to_lowercase();
+    let unit = input.chars().last()?;
+    let amount = input[..input.len() - unit.len_utf8()].parse::<i64>().ok().filter(|amount| *amount > 0)?;
+
+    let duration = match unit {
+        'm' => Duration::minutes(amount),
+        'h' => Duration::hours(amount),
+        'd' => Duration::days(amount),
+        _ => return None,
+    };
+    (duration <= Duration::days(MAX_MUTE_DAYS)).then_some(duration)
+}
+
+/// Decide the automatic action for a member with `count` warnings.
+/// Muting happens once, when the member reaches the threshold; a ban applies
+/// from its threshold on, in case the member came back.
+pub fn escalation_for(count: i64, config: &WarningsConfig) -> Option<ModerationActionKind> {
+    if config.ban_after > 0 && count >= config.ban_after as i64 {
+        Some(ModerationActionKind::Ban)
+    } else if config.mute_after > 0 && count == config.mute_after as i64 {
+        Some(ModerationActionKind::Mute)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mute_after: u32, ban_after: u32) -> WarningsConfig {
+        WarningsConfig {
+            mute_after,
+            mute_minutes: 60,
+            ban_after,
+            expire_days: 0,
+        }
+    }
+
+    #[test]
+    fn test_escalation_for() {
+        let config = config(3, 5);
+
+        assert_eq!(escalation_for(1, &config), None);
+        assert_eq!(escalation_for(3, &config), Some(ModerationActionKind::Mute));
+        assert_eq!(escalation_for(4, &config), None);
+        assert_eq!(escalation_for(5, &config), Some(ModerationActionKind::Ban));
+        assert_eq!(escalation_for(6, &config), Some(ModerationActionKind::Ban));
+    }
+
+    #[test]
+    fn test_parse_mute_duration() {
+        assert_eq!(parse_mute_duration("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_mute_duration("2H"), Some(Duration::hours(2)));
+        assert_eq!(parse_mute_duration("365d"), Some(Duration::days(365)));
+        assert_eq!(parse_mute_duration("366d"), None);
+        assert_eq!(parse_mute_duration("0h"), None);
+        assert_eq!(parse_mute_duration("2w"), None);
+        assert_eq!(parse_mute_duration("h"), None);
+        assert_eq!(parse_mute_duration(""), None);
+    }
+
+    #[test]
+    fn test_spam_matcher() {
+        let keyword = SpamMatcher::new(SpamFilterKind::Keyword, "Free Crypto").unwrap();
+        assert!(keyword.is_match("Get FREE crypto now", &[]));
+        assert!(!keyword.is_match("Free lindy lesson", &[]));
+
+        let regex = SpamMatcher::new(SpamFilterKind::Regex, r"earn \d+\$").unwrap();
+        assert!(regex.is_match("EARN 500$ a day", &[]));
+        assert!(SpamMatcher::new(SpamFilterKind::Regex, "(unclosed").is_err());
+
+        let domain = SpamMatcher::new(SpamFilterKind::Domain, "https://www.Spam.example/path").unwrap();
+        assert_eq!(domain.pattern(), "spam.example");
+        assert!(domain.is_match("visit spam.example!", &[]));
+        assert!(domain.is_match("see https://promo.spam.example/x?y=1", &[]));
+        assert!(domain.is_match("click here", &["http://spam.example".to_string()]));
+        assert!(!domain.is_match("notspam.example is fine", &[]));
+        assert!(SpamMatcher::new(SpamFilterKind::Domain, "localhost").is_err());
+    }
+
+    #[test]
+    fn test_escalation_for_disabled_thresholds() {
+        assert_eq!(escalation_for(3, &config(0, 3)), Some(ModerationActionKind::Ban));
+        assert_eq!(escalation_for(10, &config(3, 0)), None);
+        assert_eq!(escalation_for(10, &config(0, 0)), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! User note service implementation
+//!
+//! This service manages private notes that admins and event organizers attach
+//! to users, including edit history and door-list views for events.
+
+use std::collections::HashMap;
+use tracing::{info, debug};
+use crate::database::repositories::{NoteRepository, UserRepository, EventRepository};
+use crate::models::event::Event;
+use crate::models::note::{UserNote, UserNoteRevision, CreateUserNoteRequest, UpdateUserNoteRequest};
+use crate::models::user::User;
+use crate::utils::errors::{SwingBuddyError, Result};
+
+/// Maximum length of a single note
+pub const MAX_NOTE_LENGTH: usize = 1000;
+
+/// Door list entry: a registered participant with their notes
+#[derive(Debug, Clone)]
+pub struct DoorListEntry {
+    pub user: User,
+    pub notes: Vec<UserNote>,
+}
+
+/// Note service for managing private user notes
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct NoteService {
+    note_repository: NoteRepository,
+    user_repository: UserRepository,
+    event_repository: EventRepository,
+}
+
+impl NoteService {
+    /// Create a new NoteService instance
+    pub fn new(note_repository: NoteRepository, user_repository: UserRepository, event_repository: EventRepository) -> Self {
+        Self {
+            note_repository,
+            user_repository,
+            event_repository,
+        }
+    }
+
+    /// Attach a note to a user identified by Telegram ID
+    pub async fn add_note(&self, author_telegram_id: i64, target_telegram_id: i64, content: &str) -> Result<UserNote> {
+        let content = validate_note_content(content)?;
+
+        let user = self.user_repository.find_by_telegram_id(target_telegram_id).await?
+            .ok_or(SwingBuddyError::UserNotFound { user_id: target_telegram_id })?;
+
+        let note = self.note_repository.create(CreateUserNoteRequest {
+            user_id: user.id,
+            author_id: author_telegram_id,
+            content,
+        }).await?;
+
+        info!(note_id = note.id, user_id = user.id, author_id = author_telegram_id, "User note created");
+        Ok(note)
+    }
+
+    /// Edit an existing note, keeping the old content in its history.
+    /// Returns `None` if the note does not exist.
+    pub async fn edit_note(&self, editor_telegram_id: i64, note_id: i64, content: &str) -> Result<Option<UserNote>> {
+        let content = validate_note_content(content)?;
+
+        if self.note_repository.find_by_id(note_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let note = self.note_repository.update(note_id, UpdateUserNoteRequest {
+            content,
+            edited_by: editor_telegram_id,
+        }).await?;
+
+        info!(note_id = note.id, editor_id = editor_telegram_id, "User note edited");
+        Ok(Some(note))
+    }
+
+    /// Get a note together with its previous versions
+    pub async fn get_note_history(&self, note_id: i64) -> Result<Option<(UserNote, Vec<UserNoteRevision>)>> {
+        let Some(note) = self.note_repository.find_by_id(note_id).await? else {
+            return Ok(None);
+        };
+        let revisions = self.note_repository.get_revisions(note_id).await?;
+
+        debug!(note_id = note_id, revisions = revisions.len(), "Loaded note history");
+        Ok(Some((note, revisions)))
+    }
+
+    /// Get a user and their notes by Telegram ID
+    pub async fn get_user_with_notes(&self, telegram_id: i64) -> Result<(User, Vec<UserNote>)> {
+        let user = self.user_repository.find_by_telegram_id(telegram_id).await?
+            .ok_or(SwingBuddyError::UserNotFound { user_id: telegram_id })?;
+        let notes = self.note_repository.get_user_notes(user.id).await?;
+
+        Ok((user, notes))
+    }
+
+    /// Build the door list for an event: every participant with their notes
+    pub async fn get_door_list(&self, event_id: i64) -> Result<(Event, Vec<DoorListEntry>)> {
+        let event = self.event_repository.find_by_id(event_id).await?
+            .ok_or(SwingBuddyError::EventNotFound { event_id })?;
+
+        let participants = self.user_repository.get_event_participants(event_id).await?;
+        let mut notes_by_user: HashMap<i64, Vec<UserNote>> = HashMap::new();
+        for note in self.note_repository.get_event_participant_notes(event_id).await? {
+            notes_by_user.entry(note.user_id).or_default().push(note);
+        }
+
+        let entries = participants
+            .into_iter()
+            .map(|user| {
+                let notes = notes_by_user.remove(&user.id).unwrap_or_default();
+                DoorListEntry { user, notes }
+            })
+            .collect();
+
+        Ok((event, entries))
+    }
+}
+
+/// Trim and validate note content
+fn validate_note_content(content: &str) -> Result<String> {
+    let content = content.trim();
+
+    if content.is_empty() {
+        return Err(SwingBuddyError::InvalidInput("Note cannot be empty".to_string()));
+    }
+
+    if content.chars().count() > MAX_NOTE_LENGTH {
+        return Err(SwingBuddyError::InvalidInput(format!("Note cannot exceed {} characters", MAX_NOTE_LENGTH)));
+    }
+
+    Ok(content.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_note_content() {
+        assert_eq!(validate_note_content("  volunteered at 3 events ").unwrap(), "volunteered at 3 events");
+        assert!(validate_note_content("   ").is_err());
+        assert!(validate_note_content(&"a".repeat(MAX_NOTE_LENGTH + 1)).is_err());
+        assert!(validate_note_content(&"я".repeat(MAX_NOTE_LENGTH)).is_ok());
+    }
+}
@@ -4,13 +4,16 @@
 //! bulk notification handling, message templating system, and integration with teloxide
 //! for message sending.
 
-use std::collections::HashMap;
-use teloxide::{Bot, types::{ChatId, Message, ParseMode}, requests::Requester, prelude::Request, payloads::SendMessageSetters, sugar::request::RequestLinkPreviewExt};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, RwLock};
+use teloxide::{Bot, types::{ChatId, InputFile, Message, ParseMode, ThreadId}, requests::Requester, payloads::{SendMessageSetters, SendPhotoSetters, SendVideoSetters, SendAnimationSetters}, sugar::request::RequestLinkPreviewExt};
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn, error, debug};
-use crate::config::settings::Settings;
-use crate::models::{User, Event, Group};
+use crate::config::settings::{SharedSettings, MediaKind};
+use crate::database::repositories::UserRepository;
+use crate::models::{User, Group, NotificationTemplateOverride};
 use crate::utils::errors::{SwingBuddyError, Result};
+use crate::utils::retry::RetryPolicy;
 
 /// Message template structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +28,8 @@ pub struct MessageTemplate {
 #[derive(Debug, Clone)]
 pub struct NotificationRequest {
     pub chat_id: ChatId,
+    /// Forum topic to post in; the general chat if `None`
+    pub thread_id: Option<ThreadId>,
     pub template_key: String,
     pub language: String,
     pub parameters: HashMap<String, String>,
@@ -32,7 +37,7 @@ pub struct NotificationRequest {
     pub disable_web_page_preview: bool,
 }
 
-/// Bulk notification request structure
+/// Bulk notification request structure; sent through the notification queue
 #[derive(Debug, Clone)]
 pub struct BulkNotificationRequest {
     pub chat_ids: Vec<ChatId>,
@@ -43,11 +48,24 @@ pub struct BulkNotificationRequest {
     pub disable_web_page_preview: bool,
 }
 
+/// Media notification request structure
+#[derive(Debug, Clone)]
+pub struct MediaNotificationRequest {
+    pub chat_id: ChatId,
+    pub kind: MediaKind,
+    pub source: String, // Telegram file_id or HTTP(S) URL
+    pub caption: Option<String>,
+    pub language: String,
+    pub parse_mode: Option<ParseMode>,
+}
+
 /// Notification statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationStats {
     pub total_sent: u64,
     pub total_failed: u64,
+    /// Failures because the recipient blocked the bot or is gone
+    pub total_blocked: u64,
     pub sent_by_language: HashMap<String, u64>,
     pub sent_by_template: HashMap<String, u64>,
 }
@@ -57,18 +75,23 @@ pub struct NotificationStats {
 #[derive(Debug)]
 pub struct NotificationService {
     bot: Bot,
-    settings: Settings,
-    templates: HashMap<String, MessageTemplate>,
+    settings: SharedSettings,
+    /// Shared between clones so template edits reach every holder
+    templates: Arc<RwLock<HashMap<String, MessageTemplate>>>,
     stats: NotificationStats,
+    retry: RetryPolicy,
+    /// Where users who blocked the bot are marked as unreachable
+    user_repository: Option<UserRepository>,
 }
 
 impl NotificationService {
     /// Create a new NotificationService instance
-    pub fn new(bot: Bot, settings: Settings) -> Self {
+    pub fn new(bot: Bot, settings: SharedSettings) -> Self {
         let templates = Self::load_default_templates();
         let stats = NotificationStats {
             total_sent: 0,
             total_failed: 0,
+            total_blocked: 0,
             sent_by_language: HashMap::new(),
             sent_by_template: HashMap::new(),
         };
@@ -76,11 +99,19 @@ impl NotificationService {
         Self {
             bot,
             settings,
-            templates,
+            templates: Arc::new(RwLock::new(templates)),
             stats,
+            retry: RetryPolicy::default(),
+            user_repository: None,
         }
     }
 
+    /// Mark users as unreachable in the database when a direct message to them fails for good
+    pub fn with_user_repository(mut self, user_repository: UserRepository) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
     /// Send a notification using a template
     pub async fn send_notification(&mut self, request: NotificationRequest) -> Result<Message> {
         debug!(chat_id = ?request.chat_id, template_key = %request.template_key, "Sending notification");
@@ -97,7 +128,11 @@ impl NotificationService {
             send_request = send_request.disable_link_preview(true);
         }
 
-        match send_request.send().await {
+        if let Some(thread_id) = request.thread_id {
+            send_request = send_request.message_thread_id(thread_id);
+        }
+
+        match self.retry.send(&send_request).await {
             Ok(message) => {
                 self.update_stats_success(&request.template_key, &request.language);
                 info!(chat_id = ?request.chat_id, template_key = %request.template_key, "Notification sent successfully");
@@ -105,49 +140,105 @@ impl NotificationService {
             }
             Err(e) => {
                 self.update_stats_failure();
-                error!(chat_id = ?request.chat_id, template_key = %request.template_key, error = %e, "Failed to send notification");
-                Err(SwingBuddyError::Telegram(e))
+                log_send_failure(request.chat_id, &e, "Failed to send notification");
+                if matches!(e, SwingBuddyError::RecipientUnavailable(_)) {
+                    self.record_unreachable(request.chat_id).await;
+                }
+                Err(e)
             }
         }
     }
 
-    /// Send bulk notifications
-    pub async fn send_bulk_notifications(&mut self, request: BulkNotificationRequest) -> Result<Vec<Result<Message>>> {
-        info!(count = request.chat_ids.len(), template_key = %request.template_key, "Sending bulk notifications");
+    /// Send a photo, video or animation with an optional caption
+    pub async fn send_media(&mut self, request: MediaNotificationRequest) -> Result<Message> {
+        debug!(chat_id = ?request.chat_id, kind = ?request.kind, "Sending media notification");
 
-        let message_text = self.format_message(&request.template_key, &request.language, &request.parameters)?;
-        let mut results = Vec::new();
+        let file = Self::resolve_input_file(&request.source)?;
 
-        for chat_id in request.chat_ids {
-            let mut send_request = self.bot.send_message(chat_id, message_text.clone());
-            
-            if let Some(parse_mode) = request.parse_mode {
-                send_request = send_request.parse_mode(parse_mode);
+        let result = match request.kind {
+            MediaKind::Photo => {
+                let mut send_request = self.bot.send_photo(request.chat_id, file);
+                if let Some(caption) = request.caption {
+                    send_request = send_request.caption(caption);
+                }
+                if let Some(parse_mode) = request.parse_mode {
+                    send_request = send_request.parse_mode(parse_mode);
+                }
+                self.retry.send(&send_request).await
             }
-            
-            if request.disable_web_page_preview {
-                send_request = send_request.disable_link_preview(true);
+            MediaKind::Video => {
+                let mut send_request = self.bot.send_video(request.chat_id, file);
+                if let Some(caption) = request.caption {
+                    send_request = send_request.caption(caption);
+                }
+                if let Some(parse_mode) = request.parse_mode {
+                    send_request = send_request.parse_mode(parse_mode);
+                }
+                self.retry.send(&send_request).await
             }
-
-            match send_request.send().await {
-                Ok(message) => {
-                    self.update_stats_success(&request.template_key, &request.language);
-                    debug!(chat_id = ?chat_id, "Bulk notification sent successfully");
-                    results.push(Ok(message));
+            MediaKind::Animation => {
+                let mut send_request = self.bot.send_animation(request.chat_id, file);
+                if let Some(caption) = request.caption {
+                    send_request = send_request.caption(caption);
                 }
-                Err(e) => {
-                    self.update_stats_failure();
-                    warn!(chat_id = ?chat_id, error = %e, "Failed to send bulk notification");
-                    results.push(Err(SwingBuddyError::Telegram(e)));
+                if let Some(parse_mode) = request.parse_mode {
+                    send_request = send_request.parse_mode(parse_mode);
                 }
+                self.retry.send(&send_request).await
             }
+        };
 
-            // Small delay between messages to avoid rate limiting
-            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        match result {
+            Ok(message) => {
+                self.update_stats_success("media", &request.language);
+                info!(chat_id = ?request.chat_id, kind = ?request.kind, "Media notification sent successfully");
+                Ok(message)
+            }
+            Err(e) => {
+                self.update_stats_failure();
+                log_send_failure(request.chat_id, &e, "Failed to send media notification");
+                if matches!(e, SwingBuddyError::RecipientUnavailable(_)) {
+                    self.record_unreachable(request.chat_id).await;
+                }
+                Err(e)
+            }
         }
+    }
 
-        info!(total = results.len(), successful = results.iter().filter(|r| r.is_ok()).count(), "Bulk notifications completed");
-        Ok(results)
+    /// Send the configured onboarding welcome media, if any
+    pub async fn send_welcome_media(&mut self, chat_id: ChatId, language: &str) -> Result<Option<Message>> {
+        let Some(media) = self.settings.get().onboarding.as_ref().and_then(|o| o.welcome_media.clone()) else {
+            return Ok(None);
+        };
+
+        let request = MediaNotificationRequest {
+            chat_id,
+            kind: media.kind,
+            source: media.source,
+            caption: self.welcome_media_caption(language),
+            language: language.to_string(),
+            parse_mode: None,
+        };
+
+        self.send_media(request).await.map(Some)
+    }
+
+    /// Get welcome media caption for a language, falling back to the default language
+    fn welcome_media_caption(&self, language: &str) -> Option<String> {
+        let settings = self.settings.get();
+        let media = settings.onboarding.as_ref()?.welcome_media.as_ref()?;
+        media.captions.get(language)
+            .or_else(|| media.captions.get(&settings.i18n.default_language))
+            .cloned()
+    }
+
+    /// Treat HTTP(S) sources as URLs and everything else as a Telegram file_id
+    fn resolve_input_file(source: &str) -> Result<InputFile> {
+        if source.starts_with("http://") || source.starts_with("https://") {
+            Ok(InputFile::url(url::Url::parse(source)?))
+        } else {
+            Ok(InputFile::file_id(source))
+        }
     }
 
     /// Send welcome message to new user
@@ -163,6 +254,7 @@ impl NotificationService {
 
         let request = NotificationRequest {
             chat_id,
+            thread_id: None,
             template_key: "welcome".to_string(),
             language: user.language_code.clone(),
             parameters,
@@ -173,41 +265,6 @@ impl NotificationService {
         self.send_notification(request).await
     }
 
-    /// Send event notification
-    pub async fn send_event_notification(&mut self, users: &[User], event: &Event, notification_type: &str) -> Result<Vec<Result<Message>>> {
-        let chat_ids: Vec<ChatId> = users.iter().map(|u| ChatId(u.telegram_id)).collect();
-        let mut parameters = HashMap::new();
-        
-        parameters.insert("event_title".to_string(), event.title.clone());
-        parameters.insert("event_date".to_string(), event.event_date.format("%Y-%m-%d %H:%M UTC").to_string());
-        
-        if let Some(location) = &event.location {
-            parameters.insert("event_location".to_string(), location.clone());
-        }
-        
-        if let Some(description) = &event.description {
-            parameters.insert("event_description".to_string(), description.clone());
-        }
-
-        // Use the first user's language as default, or fallback to default language
-        let language = users.first()
-            .map(|u| u.language_code.clone())
-            .unwrap_or_else(|| self.settings.i18n.default_language.clone());
-
-        let template_key = format!("event_{}", notification_type);
-        
-        let request = BulkNotificationRequest {
-            chat_ids,
-            template_key,
-            language,
-            parameters,
-            parse_mode: Some(ParseMode::Html),
-            disable_web_page_preview: false,
-        };
-
-        self.send_bulk_notifications(request).await
-    }
-
     /// Send group notification
     pub async fn send_group_notification(&mut self, chat_id: ChatId, group: &Group, notification_type: &str, parameters: HashMap<String, String>) -> Result<Message> {
         let mut params = parameters;
@@ -221,6 +278,7 @@ impl NotificationService {
         
         let request = NotificationRequest {
             chat_id,
+            thread_id: None,
             template_key,
             language: group.language_code.clone(),
             parameters: params,
@@ -233,7 +291,7 @@ impl NotificationService {
 
     /// Send admin notification
     pub async fn send_admin_notification(&mut self, message: &str) -> Result<Vec<Result<Message>>> {
-        let admin_chat_ids: Vec<ChatId> = self.settings.bot.admin_ids
+        let admin_chat_ids: Vec<ChatId> = self.settings.get().bot.admin_ids
             .iter()
             .map(|&id| ChatId(id))
             .collect();
@@ -246,14 +304,14 @@ impl NotificationService {
         let mut results = Vec::new();
         
         for chat_id in admin_chat_ids {
-            match self.bot.send_message(chat_id, message).send().await {
+            match self.retry.send(&self.bot.send_message(chat_id, message)).await {
                 Ok(msg) => {
                     debug!(chat_id = ?chat_id, "Admin notification sent successfully");
                     results.push(Ok(msg));
                 }
                 Err(e) => {
-                    warn!(chat_id = ?chat_id, error = %e, "Failed to send admin notification");
-                    results.push(Err(SwingBuddyError::Telegram(e)));
+                    log_send_failure(chat_id, &e, "Failed to send admin notification");
+                    results.push(Err(e));
                 }
             }
         }
@@ -262,12 +320,13 @@ impl NotificationService {
     }
 
     /// Format message using template and parameters
-    fn format_message(&self, template_key: &str, language: &str, parameters: &HashMap<String, String>) -> Result<String> {
-        let template = self.templates.get(template_key)
+    pub fn format_message(&self, template_key: &str, language: &str, parameters: &HashMap<String, String>) -> Result<String> {
+        let templates = self.templates.read().unwrap_or_else(|e| e.into_inner());
+        let template = templates.get(template_key)
             .ok_or_else(|| SwingBuddyError::InvalidInput(format!("Template not found: {}", template_key)))?;
 
         let content = template.content.get(language)
-            .or_else(|| template.content.get(&self.settings.i18n.default_language))
+            .or_else(|| template.content.get(&self.settings.get().i18n.default_language))
             .ok_or_else(|| SwingBuddyError::InvalidInput(format!("Template content not found for language: {}", language)))?;
 
         let mut formatted = content.clone();
@@ -281,6 +340,16 @@ impl NotificationService {
         Ok(formatted)
     }
 
+    /// Count a recipient that can't be reached and, for a private chat, mark the user
+    async fn record_unreachable(&mut self, chat_id: ChatId) {
+        self.stats.total_blocked += 1;
+        if let (true, Some(user_repository)) = (chat_id.is_user(), &self.user_repository) {
+            if let Err(e) = user_repository.mark_blocked(chat_id.0).await {
+                error!(chat_id = ?chat_id, error = %e, "Failed to mark user as unreachable");
+            }
+        }
+    }
+
     /// Update success statistics
     fn update_stats_success(&mut self, template_key: &str, language: &str) {
         self.stats.total_sent += 1;
@@ -303,6 +372,7 @@ impl NotificationService {
         self.stats = NotificationStats {
             total_sent: 0,
             total_failed: 0,
+            total_blocked: 0,
             sent_by_language: HashMap::new(),
             sent_by_template: HashMap::new(),
         };
@@ -310,17 +380,68 @@ impl NotificationService {
 
     /// Add or update a message template
     pub fn add_template(&mut self, template: MessageTemplate) {
-        self.templates.insert(template.key.clone(), template);
+        self.templates.write().unwrap_or_else(|e| e.into_inner()).insert(template.key.clone(), template);
     }
 
     /// Remove a message template
     pub fn remove_template(&mut self, template_key: &str) -> Option<MessageTemplate> {
-        self.templates.remove(template_key)
+        self.templates.write().unwrap_or_else(|e| e.into_inner()).remove(template_key)
     }
 
     /// Get available template keys
     pub fn get_template_keys(&self) -> Vec<String> {
-        self.templates.keys().cloned().collect()
+        self.templates.read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect()
+    }
+
+    /// Get a template as currently in use, with any admin edits applied
+    pub fn get_template(&self, template_key: &str) -> Option<MessageTemplate> {
+        self.templates.read().unwrap_or_else(|e| e.into_inner()).get(template_key).cloned()
+    }
+
+    /// Replace the templates with the built-in ones plus the given edits.
+    /// Edits for unknown templates are skipped.
+    pub fn apply_overrides(&self, overrides: &[NotificationTemplateOverride]) {
+        let mut templates = Self::load_default_templates();
+        for template_override in overrides {
+            match templates.get_mut(&template_override.template_key) {
+                Some(template) => {
+                    template.content.insert(template_override.language_code.clone(), template_override.content.clone());
+                }
+                None => warn!(template_key = %template_override.template_key, "Skipping edit of unknown notification template"),
+            }
+        }
+        *self.templates.write().unwrap_or_else(|e| e.into_inner()) = templates;
+    }
+
+    /// Placeholders a built-in template may use, taken from its default texts
+    pub fn template_placeholders(template_key: &str) -> Option<BTreeSet<String>> {
+        let templates = Self::load_default_templates();
+        let template = templates.get(template_key)?;
+        Some(template.content.values().flat_map(|content| extract_placeholders(content)).collect())
+    }
+
+    /// Check a new text for a built-in template before storing it
+    pub fn validate_template_content(template_key: &str, content: &str) -> Result<()> {
+        let allowed = Self::template_placeholders(template_key)
+            .ok_or_else(|| SwingBuddyError::InvalidInput(format!("Template not found: {}", template_key)))?;
+
+        if content.trim().is_empty() {
+            return Err(SwingBuddyError::InvalidInput("Template text cannot be empty".to_string()));
+        }
+        if content.chars().count() > MAX_TEMPLATE_LENGTH {
+            return Err(SwingBuddyError::InvalidInput(format!("Template text cannot be longer than {} characters", MAX_TEMPLATE_LENGTH)));
+        }
+
+        let unknown: Vec<String> = extract_placeholders(content)
+            .into_iter()
+            .filter(|placeholder| !allowed.contains(placeholder))
+            .map(|placeholder| format!("{{{}}}", placeholder))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(SwingBuddyError::InvalidInput(format!("Unknown placeholders: {}", unknown.join(", "))));
+        }
+
+        Ok(())
     }
 
     /// Load default message templates
@@ -369,6 +490,166 @@ impl NotificationService {
             disable_web_page_preview: false,
         });
 
+        // Delivery report sent to the admin who queued a broadcast
+        let mut broadcast_report_content = HashMap::new();
+        broadcast_report_content.insert("en".to_string(), 
+            "📢 Broadcast #{id} ({segment}) is done: {sent} of {total} delivered, {failed} failed.".to_string());
+        broadcast_report_content.insert("ru".to_string(), 
+            "📢 Рассылка #{id} ({segment}) завершена: доставлено {sent} из {total}, ошибок: {failed}.".to_string());
+
+        templates.insert("broadcast_report".to_string(), MessageTemplate {
+            key: "broadcast_report".to_string(),
+            content: broadcast_report_content,
+            parse_mode: None,
+            disable_web_page_preview: true,
+        });
+
+        // Notice sent to the admin when a scheduled message could not be sent
+        let mut scheduled_message_failed_content = HashMap::new();
+        scheduled_message_failed_content.insert("en".to_string(), 
+            "⚠️ Scheduled message #{id} ({target}) could not be sent.".to_string());
+        scheduled_message_failed_content.insert("ru".to_string(), 
+            "⚠️ Не удалось отправить запланированное сообщение #{id} ({target}).".to_string());
+
+        templates.insert("scheduled_message_failed".to_string(), MessageTemplate {
+            key: "scheduled_message_failed".to_string(),
+            content: scheduled_message_failed_content,
+            parse_mode: None,
+            disable_web_page_preview: true,
+        });
+
+        // Event group reminder template
+        let mut event_group_reminder_content = HashMap::new();
+        event_group_reminder_content.insert("en".to_string(), 
+            "⏰ <b>{event_day}: {event_title}</b> at {event_time}\n📍 {event_location}\n👥 {participant_count} going{spots}".to_string());
+        event_group_reminder_content.insert("ru".to_string(), 
+            "⏰ <b>{event_day}: {event_title}</b> в {event_time}\n📍 {event_location}\n👥 Идут: {participant_count}{spots}".to_string());
+
+        templates.insert("event_group_reminder".to_string(), MessageTemplate {
+            key: "event_group_reminder".to_string(),
+            content: event_group_reminder_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
+        // Day of a group reminder's event
+        let mut event_day_today_content = HashMap::new();
+        event_day_today_content.insert("en".to_string(), "Today".to_string());
+        event_day_today_content.insert("ru".to_string(), "Сегодня".to_string());
+
+        templates.insert("event_day_today".to_string(), MessageTemplate {
+            key: "event_day_today".to_string(),
+            content: event_day_today_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
+        let mut event_day_tomorrow_content = HashMap::new();
+        event_day_tomorrow_content.insert("en".to_string(), "Tomorrow".to_string());
+        event_day_tomorrow_content.insert("ru".to_string(), "Завтра".to_string());
+
+        templates.insert("event_day_tomorrow".to_string(), MessageTemplate {
+            key: "event_day_tomorrow".to_string(),
+            content: event_day_tomorrow_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
+        // Spots line of a group reminder for events with a participant limit
+        let mut event_spots_left_content = HashMap::new();
+        event_spots_left_content.insert("en".to_string(), 
+            "\n🎟 {spots_left}/{max_participants} spots left".to_string());
+        event_spots_left_content.insert("ru".to_string(), 
+            "\n🎟 Свободно мест: {spots_left}/{max_participants}".to_string());
+
+        templates.insert("event_spots_left".to_string(), MessageTemplate {
+            key: "event_spots_left".to_string(),
+            content: event_spots_left_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
+        let mut event_spots_full_content = HashMap::new();
+        event_spots_full_content.insert("en".to_string(), 
+            "\n🎟 Fully booked".to_string());
+        event_spots_full_content.insert("ru".to_string(), 
+            "\n🎟 Мест нет".to_string());
+
+        templates.insert("event_spots_full".to_string(), MessageTemplate {
+            key: "event_spots_full".to_string(),
+            content: event_spots_full_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
+        // Weekly event digest posted to groups
+        let mut weekly_digest_content = HashMap::new();
+        weekly_digest_content.insert("en".to_string(), 
+            "📅 <b>Swing events {period}</b>\n\n{events}".to_string());
+        weekly_digest_content.insert("ru".to_string(), 
+            "📅 <b>Свинг-события {period}</b>\n\n{events}".to_string());
+
+        templates.insert("weekly_digest".to_string(), MessageTemplate {
+            key: "weekly_digest".to_string(),
+            content: weekly_digest_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
+        let mut weekly_digest_event_content = HashMap::new();
+        weekly_digest_event_content.insert("en".to_string(), 
+            "• <b>{event_title}</b>\n  🕗 {event_time}\n  📍 {event_location}\n  👥 {participant_count} going".to_string());
+        weekly_digest_event_content.insert("ru".to_string(), 
+            "• <b>{event_title}</b>\n  🕗 {event_time}\n  📍 {event_location}\n  👥 Идут: {participant_count}".to_string());
+
+        templates.insert("weekly_digest_event".to_string(), MessageTemplate {
+            key: "weekly_digest_event".to_string(),
+            content: weekly_digest_event_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
+        let mut weekly_digest_empty_content = HashMap::new();
+        weekly_digest_empty_content.insert("en".to_string(), 
+            "No events are planned yet. Know of one? Tell the organizers! 💃🕺".to_string());
+        weekly_digest_empty_content.insert("ru".to_string(), 
+            "Событий пока не запланировано. Знаете о каком-то? Расскажите организаторам! 💃🕺".to_string());
+
+        templates.insert("weekly_digest_empty".to_string(), MessageTemplate {
+            key: "weekly_digest_empty".to_string(),
+            content: weekly_digest_empty_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
+        // Birthday greeting template (sent by DM)
+        let mut birthday_greeting_content = HashMap::new();
+        birthday_greeting_content.insert("en".to_string(), 
+            "🎂 Happy birthday, <b>{name}</b>!\n\nWishing you a year full of great music, fun partners and lots of dancing! 💃🕺".to_string());
+        birthday_greeting_content.insert("ru".to_string(), 
+            "🎂 С днём рождения, <b>{name}</b>!\n\nЖелаем года, полного отличной музыки, прекрасных партнёров и танцев! 💃🕺".to_string());
+
+        templates.insert("birthday_greeting".to_string(), MessageTemplate {
+            key: "birthday_greeting".to_string(),
+            content: birthday_greeting_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
+        // Group birthday announcement template
+        let mut group_birthday_content = HashMap::new();
+        group_birthday_content.insert("en".to_string(), 
+            "🎉 Today is <b>{name}</b>'s birthday! Time for a birthday jam! 🎂".to_string());
+        group_birthday_content.insert("ru".to_string(), 
+            "🎉 Сегодня день рождения у <b>{name}</b>! Время для birthday jam! 🎂".to_string());
+
+        templates.insert("group_birthday".to_string(), MessageTemplate {
+            key: "group_birthday".to_string(),
+            content: group_birthday_content,
+            parse_mode: Some(ParseMode::Html),
+            disable_web_page_preview: true,
+        });
+
         // Group welcome template
         let mut group_welcome_content = HashMap::new();
         group_welcome_content.insert("en".to_string(), 
@@ -383,20 +664,87 @@ impl NotificationService {
             disable_web_page_preview: false,
         });
 
+        // Admin notice about a group member found in CAS by the periodic re-check
+        let mut cas_recheck_listed_content = HashMap::new();
+        cas_recheck_listed_content.insert("en".to_string(), 
+            "🚫 CAS re-check: user {user_id} is now listed in CAS ({offenses} offenses). They were active in: {chats}. Auto-ban is off, please review.".to_string());
+        cas_recheck_listed_content.insert("ru".to_string(), 
+            "🚫 Повторная проверка CAS: пользователь {user_id} теперь в списке CAS (нарушений: {offenses}). Был активен в: {chats}. Автобан выключен, проверьте вручную.".to_string());
+
+        templates.insert("cas_recheck_listed".to_string(), MessageTemplate {
+            key: "cas_recheck_listed".to_string(),
+            content: cas_recheck_listed_content,
+            parse_mode: None,
+            disable_web_page_preview: true,
+        });
+
+        // Reminder of a scenario step the user didn't finish, and its button
+        let mut step_reminder_content = HashMap::new();
+        step_reminder_content.insert("en".to_string(), 
+            "⏳ You didn't finish what you started here. Tap Continue within {minutes} min to pick up where you left off, or your answers will be discarded.".to_string());
+        step_reminder_content.insert("ru".to_string(), 
+            "⏳ Вы не закончили начатое. Нажмите «Продолжить» в течение {minutes} мин., чтобы вернуться к тому же шагу, иначе ответы будут сброшены.".to_string());
+
+        templates.insert("step_reminder".to_string(), MessageTemplate {
+            key: "step_reminder".to_string(),
+            content: step_reminder_content,
+            parse_mode: None,
+            disable_web_page_preview: true,
+        });
+
+        let mut step_reminder_button_content = HashMap::new();
+        step_reminder_button_content.insert("en".to_string(), "▶️ Continue".to_string());
+        step_reminder_button_content.insert("ru".to_string(), "▶️ Продолжить".to_string());
+
+        templates.insert("step_reminder_button".to_string(), MessageTemplate {
+            key: "step_reminder_button".to_string(),
+            content: step_reminder_button_content,
+            parse_mode: None,
+            disable_web_page_preview: true,
+        });
+
         templates
     }
 }
 
+/// Telegram's limit on message length
+const MAX_TEMPLATE_LENGTH: usize = 4096;
+
+/// Names of the `{placeholder}`s used in a template text
+fn extract_placeholders(content: &str) -> Vec<String> {
+    let mut placeholders = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('}') else { break };
+        let name = &rest[..end];
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            placeholders.push(name.to_string());
+            rest = &rest[end + 1..];
+        }
+    }
+    placeholders
+}
+
+/// Log a failed send; unreachable recipients are expected and only worth a warning
+fn log_send_failure(chat_id: ChatId, error: &SwingBuddyError, message: &str) {
+    match error {
+        SwingBuddyError::RecipientUnavailable(_) => warn!(chat_id = ?chat_id, error = %error, "{}", message),
+        _ => error!(chat_id = ?chat_id, error = %error, "{}", message),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::settings::Settings;
     use teloxide::Bot;
 
     #[test]
     fn test_format_message() {
         let bot = Bot::new("test_token");
         let settings = Settings::default();
-        let service = NotificationService::new(bot, settings);
+        let service = NotificationService::new(bot, SharedSettings::new(settings));
 
         let mut parameters = HashMap::new();
         parameters.insert("first_name".to_string(), "John".to_string());
@@ -410,7 +758,7 @@ mod tests {
     fn test_template_management() {
         let bot = Bot::new("test_token");
         let settings = Settings::default();
-        let mut service = NotificationService::new(bot, settings);
+        let mut service = NotificationService::new(bot, SharedSettings::new(settings));
 
         let mut content = HashMap::new();
         content.insert("en".to_string(), "Test message".to_string());
@@ -430,11 +778,35 @@ mod tests {
         assert!(!service.get_template_keys().contains(&"test".to_string()));
     }
 
+    #[test]
+    fn test_welcome_media_caption() {
+        use crate::config::settings::{OnboardingConfig, WelcomeMediaConfig};
+
+        let bot = Bot::new("test_token");
+        let mut settings = Settings::default();
+        let mut captions = HashMap::new();
+        captions.insert("en".to_string(), "Welcome!".to_string());
+        captions.insert("ru".to_string(), "Добро пожаловать!".to_string());
+        settings.onboarding = Some(OnboardingConfig {
+            welcome_media: Some(WelcomeMediaConfig {
+                kind: MediaKind::Photo,
+                source: "https://example.com/welcome.jpg".to_string(),
+                captions,
+            }),
+        });
+        let service = NotificationService::new(bot, SharedSettings::new(settings));
+
+        assert_eq!(service.welcome_media_caption("ru").as_deref(), Some("Добро пожаловать!"));
+        assert_eq!(service.welcome_media_caption("de").as_deref(), Some("Welcome!"));
+        assert!(NotificationService::resolve_input_file("https://example.com/welcome.jpg").is_ok());
+        assert!(NotificationService::resolve_input_file("AgACAgIAAxkBAAIB").is_ok());
+    }
+
     #[test]
     fn test_stats_update() {
         let bot = Bot::new("test_token");
         let settings = Settings::default();
-        let mut service = NotificationService::new(bot, settings);
+        let mut service = NotificationService::new(bot, SharedSettings::new(settings));
 
         service.update_stats_success("welcome", "en");
         service.update_stats_success("welcome", "ru");
@@ -447,4 +819,42 @@ mod tests {
         assert_eq!(stats.sent_by_language.get("ru"), Some(&1));
         assert_eq!(stats.sent_by_template.get("welcome"), Some(&2));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_apply_overrides() {
+        let bot = Bot::new("test_token");
+        let service = NotificationService::new(bot, SharedSettings::new(Settings::default()));
+        let clone = service.clone();
+
+        service.apply_overrides(&[NotificationTemplateOverride {
+            template_key: "welcome".to_string(),
+            language_code: "en".to_string(),
+            content: "Hi {first_name}".to_string(),
+            updated_by: Some(1),
+            updated_at: chrono::Utc::now(),
+        }]);
+
+        let mut parameters = HashMap::new();
+        parameters.insert("first_name".to_string(), "John".to_string());
+        assert_eq!(clone.format_message("welcome", "en", &parameters).unwrap(), "Hi John");
+        assert!(clone.format_message("welcome", "ru", &parameters).unwrap().contains("SwingBuddy"));
+
+        service.apply_overrides(&[]);
+        assert!(clone.format_message("welcome", "en", &parameters).unwrap().contains("Welcome to SwingBuddy"));
+    }
+
+    #[test]
+    fn test_validate_template_content() {
+        assert!(NotificationService::validate_template_content("welcome", "Hello {first_name}!").is_ok());
+        assert!(NotificationService::validate_template_content("welcome", "Hello {event_title}!").is_err());
+        assert!(NotificationService::validate_template_content("welcome", "  ").is_err());
+        assert!(NotificationService::validate_template_content("unknown", "Hello").is_err());
+        assert!(NotificationService::validate_template_content("welcome", &"a".repeat(MAX_TEMPLATE_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_extract_placeholders() {
+        assert_eq!(extract_placeholders("{a} and {b_c}, not { spaced } or {"), vec!["a", "b_c"]);
+        assert_eq!(extract_placeholders("{{x}}"), vec!["x"]);
+    }
+}
@@ -0,0 +1,335 @@
+//! Notification queue service implementation
+//!
+//! Bulk notifications are queued in the database instead of being sent from
+//! the handler. A background task sends them while staying below Telegram's
+//! limits of about 30 messages per second overall and one message per second
+//! to the same chat. Transient failures are retried with backoff, flood
+//! control waits are honoured, and every notification ends up sent or failed
+//! with the reason recorded. Users who blocked the bot are marked as
+//! unreachable, their remaining notifications fail right away and they are
+//! left out of later bulk sends.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use teloxide::{Bot, prelude::*, types::{ChatId, MessageId, ParseMode, ThreadId}};
+use teloxide::sugar::request::RequestLinkPreviewExt;
+use tracing::{info, warn, error, debug};
+use crate::database::repositories::{NotificationQueueRepository, UserRepository};
+use crate::models::broadcast::DeliveryStatus;
+use crate::models::event::Event;
+use crate::models::notification::{QueuedNotification, CreateQueuedNotificationRequest, NotificationQueueStats};
+use crate::models::user::User;
+use crate::services::notification::{NotificationService, BulkNotificationRequest};
+use crate::utils::errors::{SwingBuddyError, Result};
+use crate::utils::retry::{telegram_error, RetryPolicy};
+
+/// How often the queue is checked for due notifications
+const QUEUE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Notifications loaded from the queue at once
+const BATCH_SIZE: i64 = 100;
+
+/// Messages per second the bot may send overall
+const GLOBAL_MESSAGES_PER_SECOND: u32 = 30;
+
+/// Minimum pause between two messages to the same chat
+const PER_CHAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long sent and failed notifications are kept for the statistics
+const KEEP_FINISHED_DAYS: i64 = 7;
+
+/// How often finished notifications are cleaned up
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Keeps outgoing messages within the global and per-chat rate limits
+#[derive(Debug)]
+pub struct SendRateLimiter {
+    global_interval: Duration,
+    per_chat_interval: Duration,
+    last_sent: Option<Instant>,
+    last_sent_to_chat: HashMap<i64, Instant>,
+}
+
+impl SendRateLimiter {
+    /// Create a limiter allowing `messages_per_second` overall and one message
+    /// per `per_chat_interval` to each chat
+    pub fn new(messages_per_second: u32, per_chat_interval: Duration) -> Self {
+        Self {
+            global_interval: Duration::from_secs(1) / messages_per_second.max(1),
+            per_chat_interval,
+            last_sent: None,
+            last_sent_to_chat: HashMap::new(),
+        }
+    }
+
+    /// Time to wait before the next message can be sent to any chat
+    pub fn global_wait(&self, now: Instant) -> Duration {
+        self.last_sent
+            .map(|sent| (sent + self.global_interval).saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    /// Time to wait before the next message can be sent to `chat_id`
+    pub fn chat_wait(&self, chat_id: i64, now: Instant) -> Duration {
+        self.last_sent_to_chat
+            .get(&chat_id)
+            .map(|&sent| (sent + self.per_chat_interval).saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    /// Record a message sent to `chat_id`
+    pub fn record(&mut self, chat_id: i64, now: Instant) {
+        self.last_sent = Some(now);
+        self.last_sent_to_chat.insert(chat_id, now);
+        let per_chat_interval = self.per_chat_interval;
+        self.last_sent_to_chat.retain(|_, &mut sent| now.saturating_duration_since(sent) < per_chat_interval);
+    }
+}
+
+impl Default for SendRateLimiter {
+    fn default() -> Self {
+        Self::new(GLOBAL_MESSAGES_PER_SECOND, PER_CHAT_INTERVAL)
+    }
+}
+
+/// Notification queue service for rate-limited bulk sending
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct NotificationQueueService {
+    bot: Bot,
+    repository: NotificationQueueRepository,
+    user_repository: UserRepository,
+    notification_service: NotificationService,
+    retry: RetryPolicy,
+}
+
+impl NotificationQueueService {
+    /// Create a new NotificationQueueService instance
+    pub fn new(bot: Bot, repository: NotificationQueueRepository, user_repository: UserRepository, notification_service: NotificationService) -> Self {
+        Self {
+            bot,
+            repository,
+            user_repository,
+            notification_service,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Queue a templated notification for several chats. Returns the number queued.
+    pub async fn enqueue_bulk(&self, request: BulkNotificationRequest) -> Result<u64> {
+        let text = self.notification_service.format_message(&request.template_key, &request.language, &request.parameters)?;
+        let requests: Vec<_> = request.chat_ids.iter()
+            .map(|chat_id| CreateQueuedNotificationRequest {
+                chat_id: chat_id.0,
+                thread_id: None,
+                text: text.clone(),
+                parse_mode: request.parse_mode.map(|mode| parse_mode_name(mode).to_string()),
+                disable_link_preview: request.disable_web_page_preview,
+            })
+            .collect();
+
+        let queued = self.repository.enqueue(&requests).await?;
+        info!(count = queued, template_key = %request.template_key, "Queued bulk notifications");
+        Ok(queued)
+    }
+
+    /// Queue an event notification for its participants, each in their own language
+    pub async fn enqueue_event_notification(&self, users: &[User], event: &Event, notification_type: &str) -> Result<u64> {
+        let mut parameters = HashMap::new();
+        parameters.insert("event_title".to_string(), event.title.clone());
+        parameters.insert("event_date".to_string(), event.event_date.format("%Y-%m-%d %H:%M UTC").to_string());
+
+        if let Some(location) = &event.location {
+            parameters.insert("event_location".to_string(), location.clone());
+        }
+
+        if let Some(description) = &event.description {
+            parameters.insert("event_description".to_string(), description.clone());
+        }
+
+        let template_key = format!("event_{}", notification_type);
+        let mut requests = Vec::with_capacity(users.len());
+        for user in users {
+            requests.push(CreateQueuedNotificationRequest {
+                chat_id: user.telegram_id,
+                thread_id: None,
+                text: self.notification_service.format_message(&template_key, &user.language_code, &parameters)?,
+                parse_mode: Some(parse_mode_name(ParseMode::Html).to_string()),
+                disable_link_preview: false,
+            });
+        }
+
+        self.repository.enqueue(&requests).await
+    }
+
+    /// Count the queued notifications by status
+    pub async fn get_stats(&self) -> Result<NotificationQueueStats> {
+        self.repository.get_stats().await
+    }
+
+    /// Start the background task that sends queued notifications
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(QUEUE_INTERVAL);
+            let mut limiter = SendRateLimiter::default();
+            let mut last_cleanup: Option<Instant> = None;
+
+            loop {
+                check_interval.tick().await;
+
+                match self.process_queue(&mut limiter).await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("Notification queue sent {} messages", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Notification queue task failed: {}", e);
+                    }
+                }
+
+                if last_cleanup.is_none_or(|cleaned| cleaned.elapsed() >= CLEANUP_INTERVAL) {
+                    last_cleanup = Some(Instant::now());
+                    let before = chrono::Utc::now() - chrono::Duration::days(KEEP_FINISHED_DAYS);
+                    match self.repository.delete_finished(before).await {
+                        Ok(count) if count > 0 => debug!("Deleted {} finished notifications", count),
+                        Ok(_) => {}
+                        Err(e) => error!("Failed to clean up finished notifications: {}", e),
+                    }
+                }
+            }
+        });
+
+        info!("Started notification queue task with interval {:?}", QUEUE_INTERVAL);
+        handle
+    }
+
+    /// Send the due notifications within the rate limits. Notifications to a chat
+    /// that was just messaged wait for the next round. Returns the number sent.
+    pub async fn process_queue(&self, limiter: &mut SendRateLimiter) -> Result<usize> {
+        let mut sent = 0;
+
+        for notification in self.repository.get_due(chrono::Utc::now(), BATCH_SIZE).await? {
+            if !limiter.chat_wait(notification.chat_id, Instant::now()).is_zero() {
+                continue;
+            }
+
+            let wait = limiter.global_wait(Instant::now());
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+
+            let result = self.send(&notification).await;
+            limiter.record(notification.chat_id, Instant::now());
+
+            match result {
+                Ok(()) => {
+                    sent += 1;
+                    self.repository.finish(notification.id, DeliveryStatus::Sent, None).await?;
+                }
+                Err(e) => {
+                    let retry = notification.attempts as u32 + 1;
+                    match self.retry.delay_for(&e, retry) {
+                        Some(delay) => {
+                            debug!(id = notification.id, chat_id = notification.chat_id, error = %e, retry = retry, "Notification failed, retrying later");
+                            let next_attempt_at = chrono::Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default();
+                            self.repository.reschedule(notification.id, &e.to_string(), next_attempt_at).await?;
+                        }
+                        None => {
+                            let error = telegram_error(e);
+                            warn!(id = notification.id, chat_id = notification.chat_id, error = %error, "Notification failed");
+                            self.repository.finish(notification.id, DeliveryStatus::Failed, Some(&error.to_string())).await?;
+                            if matches!(error, SwingBuddyError::RecipientUnavailable(_)) {
+                                self.handle_unreachable(notification.chat_id, &error).await?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+
+    /// Stop sending to a chat the bot can't reach: fail its other pending
+    /// notifications and, for a private chat, mark the user as unreachable
+    async fn handle_unreachable(&self, chat_id: i64, error: &SwingBuddyError) -> Result<()> {
+        let failed = self.repository.fail_pending_for_chat(chat_id, &error.to_string()).await?;
+        if ChatId(chat_id).is_user() && self.user_repository.mark_blocked(chat_id).await? {
+            info!(user_id = chat_id, "User can't be messaged any more, leaving them out of bulk sends");
+        }
+        if failed > 0 {
+            debug!(chat_id = chat_id, count = failed, "Failed pending notifications to unreachable chat");
+        }
+        Ok(())
+    }
+
+    /// Make one attempt to send a notification
+    async fn send(&self, notification: &QueuedNotification) -> std::result::Result<(), teloxide::RequestError> {
+        let mut request = self.bot.send_message(ChatId(notification.chat_id), &notification.text);
+
+        if let Some(parse_mode) = notification.parse_mode.as_deref().and_then(parse_mode_from_name) {
+            request = request.parse_mode(parse_mode);
+        }
+
+        if notification.disable_link_preview {
+            request = request.disable_link_preview(true);
+        }
+
+        if let Some(thread_id) = notification.thread_id {
+            request = request.message_thread_id(ThreadId(MessageId(thread_id)));
+        }
+
+        request.await.map(|_| ())
+    }
+}
+
+/// Name a parse mode is stored under
+pub fn parse_mode_name(parse_mode: ParseMode) -> &'static str {
+    #[allow(deprecated)]
+    match parse_mode {
+        ParseMode::Html => "HTML",
+        ParseMode::MarkdownV2 => "MarkdownV2",
+        ParseMode::Markdown => "Markdown",
+    }
+}
+
+/// Parse mode stored under `name`
+pub fn parse_mode_from_name(name: &str) -> Option<ParseMode> {
+    #[allow(deprecated)]
+    match name {
+        "HTML" => Some(ParseMode::Html),
+        "MarkdownV2" => Some(ParseMode::MarkdownV2),
+        "Markdown" => Some(ParseMode::Markdown),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = SendRateLimiter::new(10, Duration::from_secs(1));
+        let start = Instant::now();
+        assert!(limiter.global_wait(start).is_zero());
+        assert!(limiter.chat_wait(1, start).is_zero());
+
+        limiter.record(1, start);
+        assert_eq!(limiter.global_wait(start), Duration::from_millis(100));
+        assert!(limiter.global_wait(start + Duration::from_millis(100)).is_zero());
+        assert_eq!(limiter.chat_wait(1, start + Duration::from_millis(400)), Duration::from_millis(600));
+        assert!(limiter.chat_wait(2, start).is_zero());
+
+        limiter.record(2, start + Duration::from_secs(2));
+        assert!(limiter.chat_wait(1, start + Duration::from_secs(2)).is_zero());
+    }
+
+    #[test]
+    fn test_parse_mode_names() {
+        assert_eq!(parse_mode_from_name(parse_mode_name(ParseMode::Html)), Some(ParseMode::Html));
+        assert_eq!(parse_mode_from_name(parse_mode_name(ParseMode::MarkdownV2)), Some(ParseMode::MarkdownV2));
+        assert_eq!(parse_mode_from_name("plain"), None);
+    }
+}
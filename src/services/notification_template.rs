@@ -0,0 +1,71 @@
+//! Notification template service implementation
+//!
+//! The built-in templates in `NotificationService` define which templates
+//! exist and which placeholders they take. Admins can replace the text of a
+//! template per language from the admin panel; edits are stored in the
+//! database and pushed into the shared template cache on every change.
+
+use tracing::info;
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::NotificationTemplateRepository;
+use crate::models::NotificationTemplateOverride;
+use crate::services::notification::NotificationService;
+use crate::utils::errors::{SwingBuddyError, Result};
+
+/// Notification template service for admin-edited template texts
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct NotificationTemplateService {
+    repository: NotificationTemplateRepository,
+    notification_service: NotificationService,
+    settings: SharedSettings,
+}
+
+impl NotificationTemplateService {
+    /// Create a new NotificationTemplateService instance
+    pub fn new(repository: NotificationTemplateRepository, notification_service: NotificationService, settings: SharedSettings) -> Self {
+        Self { repository, notification_service, settings }
+    }
+
+    /// Load the stored edits into the template cache. Returns how many were applied.
+    pub async fn load(&self) -> Result<usize> {
+        let overrides = self.repository.list().await?;
+        self.notification_service.apply_overrides(&overrides);
+        Ok(overrides.len())
+    }
+
+    /// Get all stored edits
+    pub async fn list_overrides(&self) -> Result<Vec<NotificationTemplateOverride>> {
+        self.repository.list().await
+    }
+
+    /// Replace the text of a template in one language
+    pub async fn set(&self, template_key: &str, language_code: &str, content: &str, admin_id: i64) -> Result<()> {
+        self.check_language(language_code)?;
+        NotificationService::validate_template_content(template_key, content)?;
+
+        self.repository.upsert(template_key, language_code, content, admin_id).await?;
+        self.load().await?;
+
+        info!(template_key = %template_key, language_code = %language_code, admin_id = admin_id, "Notification template edited");
+        Ok(())
+    }
+
+    /// Go back to the built-in text of a template in one language. Returns whether it was edited.
+    pub async fn reset(&self, template_key: &str, language_code: &str) -> Result<bool> {
+        let removed = self.repository.delete(template_key, language_code).await?;
+        if removed {
+            self.load().await?;
+            info!(template_key = %template_key, language_code = %language_code, "Notification template reset");
+        }
+        Ok(removed)
+    }
+
+    fn check_language(&self, language_code: &str) -> Result<()> {
+        if self.settings.get().i18n.supported_languages.iter().any(|lang| lang == language_code) {
+            Ok(())
+        } else {
+            Err(SwingBuddyError::InvalidInput(format!("Unsupported language: {}", language_code)))
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Outgoing webhook service implementation
+//!
+//! Sends an HTTP POST to the endpoints in the `[outgoing_webhooks]` config
+//! section when something happens in the bot, so the website, a Discord bridge
+//! or analytics can react without polling. Every delivery is signed with the
+//! endpoint's secret and retried with a growing delay while the receiver is
+//! unreachable or answers with a server error. Deliveries are kept in memory
+//! only and are lost on restart.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use sha2::Sha256;
+use tracing::{debug, warn, Instrument};
+use uuid::Uuid;
+use crate::config::settings::{SharedSettings, WebhookEndpointConfig};
+use crate::utils::errors::{SwingBuddyError, Result};
+use crate::utils::metrics::metrics;
+
+/// Something that happened in the bot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    EventCreated,
+    EventUpdated,
+    EventCancelled,
+    ParticipantRegistered,
+    ParticipantUnregistered,
+    UserRegistered,
+    UserBanned,
+    UserUnbanned,
+}
+
+impl WebhookEvent {
+    /// Name sent in the payload and used in the config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EventCreated => "event.created",
+            Self::EventUpdated => "event.updated",
+            Self::EventCancelled => "event.cancelled",
+            Self::ParticipantRegistered => "participant.registered",
+            Self::ParticipantUnregistered => "participant.unregistered",
+            Self::UserRegistered => "user.registered",
+            Self::UserBanned => "user.banned",
+            Self::UserUnbanned => "user.unbanned",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "event.created" => Ok(Self::EventCreated),
+            "event.updated" => Ok(Self::EventUpdated),
+            "event.cancelled" => Ok(Self::EventCancelled),
+            "participant.registered" => Ok(Self::ParticipantRegistered),
+            "participant.unregistered" => Ok(Self::ParticipantUnregistered),
+            "user.registered" => Ok(Self::UserRegistered),
+            "user.banned" => Ok(Self::UserBanned),
+            "user.unbanned" => Ok(Self::UserUnbanned),
+            _ => Err(format!("Unknown webhook event: {}", s)),
+        }
+    }
+}
+
+/// Body of a delivery
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a, T: Serialize> {
+    id: Uuid,
+    #[serde(rename = "type")]
+    event: &'static str,
+    created_at: DateTime<Utc>,
+    data: &'a T,
+}
+
+/// Outgoing webhook service
+#[derive(Debug, Clone)]
+pub struct OutgoingWebhookService {
+    client: Client,
+    settings: SharedSettings,
+}
+
+impl OutgoingWebhookService {
+    /// Create a new OutgoingWebhookService instance
+    pub fn new(settings: SharedSettings) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent("SwingBuddy-Bot/1.0")
+            .build()
+            .map_err(SwingBuddyError::Http)?;
+
+        Ok(Self { client, settings })
+    }
+
+    /// Send `data` to every endpoint subscribed to `event`. Returns right away;
+    /// the deliveries run in the background.
+    pub fn emit<T: Serialize>(&self, event: WebhookEvent, data: &T) {
+        let settings = self.settings.get();
+        let Some(config) = settings.outgoing_webhooks.as_ref() else {
+            return;
+        };
+
+        let endpoints: Vec<WebhookEndpointConfig> = config.endpoints.iter()
+            .filter(|endpoint| subscribes(endpoint, event))
+            .cloned()
+            .collect();
+        if endpoints.is_empty() {
+            return;
+        }
+
+        let id = Uuid::new_v4();
+        let payload = WebhookPayload { id, event: event.as_str(), created_at: Utc::now(), data };
+        let body = match serde_json::to_string(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(error = %e, event = %event, "Failed to serialize webhook payload");
+                return;
+            }
+        };
+
+        for endpoint in endpoints {
+            let delivery = Delivery {
+                client: self.client.clone(),
+                endpoint,
+                id,
+                event,
+                body: body.clone(),
+                timeout: Duration::from_secs(config.timeout_seconds),
+                max_attempts: config.max_attempts,
+            };
+            tokio::spawn(delivery.run().in_current_span());
+        }
+    }
+}
+
+/// One payload on its way to one endpoint
+struct Delivery {
+    client: Client,
+    endpoint: WebhookEndpointConfig,
+    id: Uuid,
+    event: WebhookEvent,
+    body: String,
+    timeout: Duration,
+    max_attempts: u32,
+}
+
+impl Delivery {
+    async fn run(self) {
+        for attempt in 1..=self.max_attempts {
+            match self.send().await {
+                Ok(()) => {
+                    debug!(endpoint = %self.endpoint.name, event = %self.event, delivery_id = %self.id, attempt = attempt, "Webhook delivered");
+                    metrics().webhook_delivery("delivered");
+                    return;
+                }
+                Err(retry) => {
+                    if !retry || attempt == self.max_attempts {
+                        break;
+                    }
+                    tokio::time::sleep(retry_delay(attempt)).await;
+                }
+            }
+        }
+
+        warn!(endpoint = %self.endpoint.name, event = %self.event, delivery_id = %self.id, "Giving up on webhook delivery");
+        metrics().webhook_delivery("failed");
+    }
+
+    /// Send once; the error tells whether trying again could help
+    async fn send(&self) -> std::result::Result<(), bool> {
+        let timestamp = Utc::now().timestamp();
+        let response = self.client
+            .post(&self.endpoint.url)
+            .timeout(self.timeout)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-SwingBuddy-Event", self.event.as_str())
+            .header("X-SwingBuddy-Delivery", self.id.to_string())
+            .header("X-SwingBuddy-Timestamp", timestamp.to_string())
+            .header("X-SwingBuddy-Signature", signature(&self.endpoint.secret, timestamp, &self.body))
+            .body(self.body.clone())
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) => {
+                warn!(endpoint = %self.endpoint.name, status = %response.status(), delivery_id = %self.id, "Webhook endpoint rejected delivery");
+                Err(is_retryable(response.status()))
+            }
+            Err(e) => {
+                warn!(endpoint = %self.endpoint.name, error = %e, delivery_id = %self.id, "Webhook delivery failed");
+                Err(true)
+            }
+        }
+    }
+}
+
+/// Whether an endpoint gets deliveries of `event`
+pub fn subscribes(endpoint: &WebhookEndpointConfig, event: WebhookEvent) -> bool {
+    endpoint.events.is_empty() || endpoint.events.iter().any(|name| name == event.as_str())
+}
+
+/// `X-SwingBuddy-Signature` header: HMAC-SHA256 of `<timestamp>.<body>`
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Server errors and rate limiting are worth retrying, other rejections aren't
+pub fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::REQUEST_TIMEOUT
+}
+
+/// Wait before the attempt after `attempt`: 2s, 4s, 8s, ... up to 5 minutes
+pub fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs(2u64.saturating_pow(attempt).min(300))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(events: &[&str]) -> WebhookEndpointConfig {
+        WebhookEndpointConfig {
+            name: "website".to_string(),
+            url: "https://example.com/hooks".to_string(),
+            secret: "0123456789abcdef".to_string(),
+            events: events.iter().map(|event| event.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_event_names_round_trip() {
+        for event in [
+            WebhookEvent::EventCreated,
+            WebhookEvent::EventUpdated,
+            WebhookEvent::EventCancelled,
+            WebhookEvent::ParticipantRegistered,
+            WebhookEvent::ParticipantUnregistered,
+            WebhookEvent::UserRegistered,
+            WebhookEvent::UserBanned,
+            WebhookEvent::UserUnbanned,
+        ] {
+            assert_eq!(event.as_str().parse::<WebhookEvent>(), Ok(event));
+        }
+        assert!("event.deleted".parse::<WebhookEvent>().is_err());
+    }
+
+    #[test]
+    fn test_subscribes() {
+        assert!(subscribes(&endpoint(&[]), WebhookEvent::UserBanned));
+        assert!(subscribes(&endpoint(&["user.banned", "event.created"]), WebhookEvent::UserBanned));
+        assert!(!subscribes(&endpoint(&["event.created"]), WebhookEvent::UserBanned));
+    }
+
+    #[test]
+    fn test_signature() {
+        // echo -n '1700000000.{"a":1}' | openssl dgst -sha256 -hmac 0123456789abcdef
+        assert_eq!(
+            signature("0123456789abcdef", 1_700_000_000, r#"{"a":1}"#),
+            "sha256=9eb18f493f8ec135d9eb2dad817c369bb4e9cbfa818657897a7437c1cd8c3a23"
+        );
+        assert_ne!(
+            signature("0123456789abcdef", 1_700_000_001, r#"{"a":1}"#),
+            signature("0123456789abcdef", 1_700_000_000, r#"{"a":1}"#)
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::UNAUTHORIZED));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Duration::from_secs(2));
+        assert_eq!(retry_delay(2), Duration::from_secs(4));
+        assert_eq!(retry_delay(5), Duration::from_secs(32));
+        assert_eq!(retry_delay(20), Duration::from_secs(300));
+    }
+}
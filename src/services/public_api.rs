@@ -0,0 +1,204 @@
+//! Public API service implementation
+//!
+//! Read-only data for the community website: upcoming events with their
+//! participant counts, the calendars shown in /events and a few totals.
+//! Clients authenticate with one of the keys from the `[api]` config section;
+//! nothing personal about users is ever returned.
+
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::{CalendarRepository, EventRepository, GroupRepository, UserRepository};
+use crate::models::calendar::Calendar;
+use crate::models::event::Event;
+use crate::services::google::calendar_sharing_url;
+use crate::services::reminder::spots_left;
+use crate::utils::errors::Result;
+
+/// Events returned when the client doesn't ask for a number
+pub const DEFAULT_EVENT_LIMIT: i64 = 50;
+
+/// Most events returned at once
+pub const MAX_EVENT_LIMIT: i64 = 200;
+
+/// An event as the website sees it
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiEvent {
+    pub id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub event_date: DateTime<Utc>,
+    pub location: Option<String>,
+    pub max_participants: Option<i32>,
+    pub participant_count: i64,
+    /// `None` when the event has no participant limit
+    pub spots_left: Option<i64>,
+}
+
+/// A calendar as the website sees it
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiCalendar {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub google_calendar_id: Option<String>,
+    /// Link to subscribe to the linked Google Calendar
+    pub subscribe_url: Option<String>,
+}
+
+/// Public totals
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiStats {
+    pub users: i64,
+    pub groups: i64,
+    pub events: i64,
+    pub upcoming_events: i64,
+}
+
+/// Public API service
+#[derive(Debug, Clone)]
+pub struct PublicApiService {
+    event_repository: EventRepository,
+    calendar_repository: CalendarRepository,
+    user_repository: UserRepository,
+    group_repository: GroupRepository,
+    settings: SharedSettings,
+}
+
+impl PublicApiService {
+    /// Create a new PublicApiService instance
+    pub fn new(
+        event_repository: EventRepository,
+        calendar_repository: CalendarRepository,
+        user_repository: UserRepository,
+        group_repository: GroupRepository,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            event_repository,
+            calendar_repository,
+            user_repository,
+            group_repository,
+            settings,
+        }
+    }
+
+    /// Name of the client a key belongs to, if it is a configured key
+    pub fn authorize(&self, key: &str) -> Option<String> {
+        let settings = self.settings.get();
+        let keys = settings.api.as_ref()?.keys.iter().map(|client| (client.name.as_str(), client.key.as_str()));
+        find_client(keys, key).map(str::to_string)
+    }
+
+    /// Upcoming active events, soonest first
+    pub async fn upcoming_events(&self, limit: Option<i64>) -> Result<Vec<ApiEvent>> {
+        let events = self.event_repository.get_upcoming_events(Some(event_limit(limit))).await?;
+        let ids: Vec<i64> = events.iter().map(|event| event.id).collect();
+        let counts: HashMap<i64, i64> = self.event_repository.get_participant_counts(&ids).await?.into_iter().collect();
+
+        Ok(events
+            .into_iter()
+            .map(|event| {
+                let participant_count = counts.get(&event.id).copied().unwrap_or(0);
+                api_event(event, participant_count)
+            })
+            .collect())
+    }
+
+    /// A single active event
+    pub async fn event(&self, event_id: i64) -> Result<Option<ApiEvent>> {
+        let Some(event) = self.event_repository.find_by_id(event_id).await?.filter(|event| event.is_active) else {
+            return Ok(None);
+        };
+        let participant_count = self.event_repository.get_participant_count(event.id).await?;
+        Ok(Some(api_event(event, participant_count)))
+    }
+
+    /// All calendars
+    pub async fn calendars(&self) -> Result<Vec<ApiCalendar>> {
+        Ok(self.calendar_repository.list().await?.into_iter().map(api_calendar).collect())
+    }
+
+    /// Totals shown on the website
+    pub async fn stats(&self) -> Result<ApiStats> {
+        Ok(ApiStats {
+            users: self.user_repository.count().await?,
+            groups: self.group_repository.count().await?,
+            events: self.event_repository.count().await?,
+            upcoming_events: self.event_repository.count_upcoming().await?,
+        })
+    }
+}
+
+/// Number of events to return for the `limit` a client asked for
+pub fn event_limit(requested: Option<i64>) -> i64 {
+    requested.unwrap_or(DEFAULT_EVENT_LIMIT).clamp(1, MAX_EVENT_LIMIT)
+}
+
+/// Client whose key matches; every key is compared in full so the timing
+/// doesn't tell how much of a guess was right
+pub fn find_client<'a>(clients: impl IntoIterator<Item = (&'a str, &'a str)>, key: &str) -> Option<&'a str> {
+    clients
+        .into_iter()
+        .fold(None, |found, (name, client_key)| {
+            if keys_equal(client_key.as_bytes(), key.as_bytes()) {
+                Some(name)
+            } else {
+                found
+            }
+        })
+}
+
+fn keys_equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn api_event(event: Event, participant_count: i64) -> ApiEvent {
+    ApiEvent {
+        spots_left: spots_left(event.max_participants, participant_count),
+        id: event.id,
+        title: event.title,
+        description: event.description,
+        event_date: event.event_date,
+        location: event.location,
+        max_participants: event.max_participants,
+        participant_count,
+    }
+}
+
+fn api_calendar(calendar: Calendar) -> ApiCalendar {
+    ApiCalendar {
+        subscribe_url: calendar.google_calendar_id.as_deref().map(calendar_sharing_url),
+        id: calendar.id,
+        name: calendar.name,
+        description: calendar.description,
+        google_calendar_id: calendar.google_calendar_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_limit() {
+        assert_eq!(event_limit(None), DEFAULT_EVENT_LIMIT);
+        assert_eq!(event_limit(Some(10)), 10);
+        assert_eq!(event_limit(Some(0)), 1);
+        assert_eq!(event_limit(Some(-5)), 1);
+        assert_eq!(event_limit(Some(10_000)), MAX_EVENT_LIMIT);
+    }
+
+    #[test]
+    fn test_find_client() {
+        let clients = [("website", "website-key-0123456789"), ("app", "app-key-0123456789ab")];
+
+        assert_eq!(find_client(clients, "website-key-0123456789"), Some("website"));
+        assert_eq!(find_client(clients, "app-key-0123456789ab"), Some("app"));
+        assert_eq!(find_client(clients, "website-key-012345678"), None);
+        assert_eq!(find_client(clients, "website-key-0123456780"), None);
+        assert_eq!(find_client(clients, ""), None);
+        assert_eq!(find_client([], "website-key-0123456789"), None);
+    }
+}
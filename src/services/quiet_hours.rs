@@ -0,0 +1,163 @@
+//! Quiet hours service implementation
+//!
+//! Groups can set a daily window during which the bot does not post announcements.
+//! Posts due in that window are queued in the database and flushed in order once
+//! the window ends.
+
+use std::time::Duration;
+use chrono::Utc;
+use teloxide::{Bot, prelude::*, types::{ChatId, Message, MessageId, ParseMode, ThreadId}};
+use teloxide::sugar::request::RequestLinkPreviewExt;
+use tracing::{info, warn, error, debug};
+use crate::database::repositories::{GroupRepository, QuietHoursRepository};
+use crate::models::group::Group;
+use crate::models::quiet_hours::CreateQueuedPostRequest;
+use crate::services::notification::{NotificationRequest, NotificationService};
+use crate::utils::errors::Result;
+
+/// How often queued posts are checked against the groups' quiet hours
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Quiet hours service for holding back group announcements
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct QuietHoursService {
+    bot: Bot,
+    notification_service: NotificationService,
+    quiet_hours_repository: QuietHoursRepository,
+    group_repository: GroupRepository,
+}
+
+impl QuietHoursService {
+    /// Create a new QuietHoursService instance
+    pub fn new(
+        bot: Bot,
+        notification_service: NotificationService,
+        quiet_hours_repository: QuietHoursRepository,
+        group_repository: GroupRepository,
+    ) -> Self {
+        Self {
+            bot,
+            notification_service,
+            quiet_hours_repository,
+            group_repository,
+        }
+    }
+
+    /// Post a notification in a group, or queue it if the group's quiet hours are on.
+    /// Returns the sent message, or `None` if the post was queued.
+    pub async fn send_or_queue(&mut self, group: &Group, request: NotificationRequest) -> Result<Option<Message>> {
+        if !group.is_quiet_at(Utc::now()) {
+            return self.notification_service.send_notification(request).await.map(Some);
+        }
+
+        let text = self.notification_service.format_message(&request.template_key, &request.language, &request.parameters)?;
+        self.quiet_hours_repository.enqueue(CreateQueuedPostRequest {
+            group_id: group.id,
+            thread_id: request.thread_id.map(|thread_id| thread_id.0.0),
+            text,
+        }).await?;
+
+        debug!(group_id = group.id, template_key = %request.template_key, "Group post queued for quiet hours");
+        Ok(None)
+    }
+
+    /// Number of posts waiting for the group's quiet hours to end
+    pub async fn queued_count(&self, group_id: i64) -> Result<i64> {
+        self.quiet_hours_repository.count_for_group(group_id).await
+    }
+
+    /// Drop the queued posts of a group, e.g. after the bot was removed from it
+    pub async fn cancel_for_group(&self, group_id: i64) -> Result<u64> {
+        self.quiet_hours_repository.delete_for_group(group_id).await
+    }
+
+    /// Post the queued announcements of groups whose quiet hours are over
+    pub async fn flush_due(&self) -> Result<usize> {
+        let now = Utc::now();
+        let mut flushed = 0;
+
+        for group_id in self.quiet_hours_repository.get_queued_group_ids().await? {
+            let group = self.group_repository.find_by_id(group_id).await?;
+            let active_group = group.filter(|g| g.is_active);
+            if active_group.as_ref().is_some_and(|g| g.is_quiet_at(now)) {
+                continue;
+            }
+
+            for post in self.quiet_hours_repository.get_for_group(group_id).await? {
+                // Posts for groups the bot left are dropped
+                if let Some(group) = &active_group {
+                    let mut request = self.bot.send_message(ChatId(group.telegram_id), post.text)
+                        .parse_mode(ParseMode::Html)
+                        .disable_link_preview(true);
+                    if let Some(thread_id) = post.thread_id {
+                        request = request.message_thread_id(ThreadId(MessageId(thread_id)));
+                    }
+
+                    match request.await {
+                        Ok(_) => flushed += 1,
+                        Err(e) => warn!(group_id = group_id, error = %e, "Failed to post queued announcement"),
+                    }
+                }
+
+                self.quiet_hours_repository.delete(post.id).await?;
+            }
+        }
+
+        Ok(flushed)
+    }
+
+    /// Start the background task that flushes queued posts
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(FLUSH_INTERVAL);
+
+            loop {
+                check_interval.tick().await;
+
+                match self.flush_due().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("Quiet hours task posted {} queued announcements", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Quiet hours task failed: {}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Started quiet hours task with interval {:?}", FLUSH_INTERVAL);
+        handle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::group::QuietHours;
+
+    #[test]
+    fn test_quiet_hours_window() {
+        let night: QuietHours = "22-8".parse().unwrap();
+        assert!(night.contains(23));
+        assert!(night.contains(0));
+        assert!(night.contains(7));
+        assert!(!night.contains(8));
+        assert!(!night.contains(21));
+
+        let afternoon: QuietHours = "13-15".parse().unwrap();
+        assert!(afternoon.contains(13));
+        assert!(!afternoon.contains(15));
+        assert!(!afternoon.contains(3));
+    }
+
+    #[test]
+    fn test_parse_quiet_hours() {
+        assert_eq!("22-8".parse::<QuietHours>(), Ok(QuietHours { start_hour: 22, end_hour: 8 }));
+        assert_eq!(" 0 - 6 ".parse::<QuietHours>(), Ok(QuietHours { start_hour: 0, end_hour: 6 }));
+        assert!("24-6".parse::<QuietHours>().is_err());
+        assert!("8-8".parse::<QuietHours>().is_err());
+        assert!("night".parse::<QuietHours>().is_err());
+    }
+}
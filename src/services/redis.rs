@@ -4,17 +4,18 @@
 //! user state caching for conversation flows, cache invalidation strategies,
 //! and performance optimization for database queries.
 
-use redis::{Client, AsyncCommands, RedisResult};
+use redis::{AsyncCommands, RedisResult};
 use serde::{Serialize, Deserialize};
 use tracing::{info, warn, debug};
 use crate::config::settings::Settings;
 use crate::utils::errors::{SwingBuddyError, Result};
+use crate::utils::redis_connection::RedisConnector;
 
 /// Redis service for caching and state management
 #[derive(Clone)]
 #[derive(Debug)]
 pub struct RedisService {
-    client: Client,
+    redis: RedisConnector,
     settings: Settings,
 }
 
@@ -29,15 +30,14 @@ pub struct CacheEntry<T> {
 impl RedisService {
     /// Create a new RedisService instance
     pub fn new(settings: Settings) -> Result<Self> {
-        let client = Client::open(settings.redis.url.as_str())
-            .map_err(|e| SwingBuddyError::Redis(e))?;
+        let redis = RedisConnector::new(&settings.redis)?;
 
-        Ok(Self { client, settings })
+        Ok(Self { redis, settings })
     }
 
     /// Get Redis connection
     async fn get_connection(&self) -> Result<redis::aio::Connection> {
-        self.client.get_async_connection().await
+        self.redis.client().await?.get_async_connection().await
             .map_err(|e| SwingBuddyError::Redis(e))
     }
 
@@ -109,6 +109,20 @@ impl RedisService {
         Ok(exists)
     }
 
+    /// Set a key with TTL unless it exists; true if it was set
+    pub async fn set_if_absent(&self, key: &str, ttl_seconds: u64) -> Result<bool> {
+        let mut conn = self.get_connection().await?;
+        let full_key = format!("{}{}", self.settings.redis.prefix, key);
+
+        let set: Option<String> = redis::cmd("SET").arg(&full_key).arg(1).arg("EX").arg(ttl_seconds).arg("NX")
+            .query_async(&mut conn)
+            .await
+            .map_err(SwingBuddyError::Redis)?;
+
+        debug!(key = %full_key, set = set.is_some(), ttl = ttl_seconds, "Key set if absent");
+        Ok(set.is_some())
+    }
+
     /// Set TTL for an existing key
     pub async fn expire(&self, key: &str, ttl_seconds: u64) -> Result<bool> {
         let mut conn = self.get_connection().await?;
@@ -0,0 +1,90 @@
+//! Referral service implementation
+//!
+//! This service handles `/start ref_<user_id>` deep links: attributing new users
+//! to the member who invited them and reporting referral counts.
+
+use tracing::{info, debug};
+use crate::database::repositories::{ReferralRepository, UserRepository};
+use crate::models::referral::{Referral, TopReferrer, CreateReferralRequest};
+use crate::utils::errors::Result;
+
+/// Prefix of the /start payload used for referral links
+const REFERRAL_PREFIX: &str = "ref_";
+
+/// Referral service for invite attribution
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ReferralService {
+    referral_repository: ReferralRepository,
+    user_repository: UserRepository,
+}
+
+impl ReferralService {
+    /// Create a new ReferralService instance
+    pub fn new(referral_repository: ReferralRepository, user_repository: UserRepository) -> Self {
+        Self {
+            referral_repository,
+            user_repository,
+        }
+    }
+
+    /// Attribute a newly joined user to the referrer from the deep link.
+    /// Returns `None` for self-referrals, unknown referrers or users already attributed.
+    pub async fn record_referral(&self, referrer_id: i64, referred_id: i64) -> Result<Option<Referral>> {
+        if referrer_id == referred_id {
+            debug!(user_id = referred_id, "Ignoring self-referral");
+            return Ok(None);
+        }
+
+        if self.user_repository.find_by_id(referrer_id).await?.is_none() {
+            debug!(referrer_id = referrer_id, "Ignoring referral from unknown user");
+            return Ok(None);
+        }
+
+        let referral = self.referral_repository.create(CreateReferralRequest {
+            referrer_id,
+            referred_id,
+        }).await?;
+
+        if referral.is_some() {
+            info!(referrer_id = referrer_id, referred_id = referred_id, "Referral recorded");
+        }
+
+        Ok(referral)
+    }
+
+    /// Get number of users invited by a user
+    pub async fn get_referral_count(&self, user_id: i64) -> Result<i64> {
+        self.referral_repository.count_by_referrer(user_id).await
+    }
+
+    /// Get users with the most referrals
+    pub async fn get_top_referrers(&self, limit: i64) -> Result<Vec<TopReferrer>> {
+        self.referral_repository.get_top_referrers(limit).await
+    }
+}
+
+/// Build the /start payload for a user's referral link
+pub fn referral_payload(user_id: i64) -> String {
+    format!("{}{}", REFERRAL_PREFIX, user_id)
+}
+
+/// Extract the referrer ID from a /start payload
+pub fn parse_referral_payload(payload: &str) -> Option<i64> {
+    payload.trim().strip_prefix(REFERRAL_PREFIX)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_referral_payload_roundtrip() {
+        assert_eq!(referral_payload(42), "ref_42");
+        assert_eq!(parse_referral_payload("ref_42"), Some(42));
+        assert_eq!(parse_referral_payload(&referral_payload(7)), Some(7));
+        assert_eq!(parse_referral_payload("ref_"), None);
+        assert_eq!(parse_referral_payload("promo_42"), None);
+        assert_eq!(parse_referral_payload(""), None);
+    }
+}
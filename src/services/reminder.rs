@@ -0,0 +1,354 @@
+//! Reminder service implementation
+//!
+//! This service posts event reminders into the event's linked group chat, a few
+//! hours ahead, saying whether the event is today or tomorrow. A reminder that
+//! cannot be posted is retried a few times and then given up.
+//! Reminders can be turned off per group (group settings) and per event. Groups with
+//! pinned announcements get the reminder pinned, and the posted message is edited as
+//! registrations come in until the event starts.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use teloxide::{Bot, prelude::*, types::{ChatId, MessageId, ParseMode, ThreadId}};
+use teloxide::sugar::request::RequestLinkPreviewExt;
+use teloxide::utils::html;
+use tracing::{info, warn, error, debug};
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::{EventRepository, GroupRepository};
+use crate::models::event::{Event, EventGroupReminder};
+use crate::models::group::{Group, GroupFeature, GroupTopic};
+use crate::services::notification::{NotificationRequest, NotificationService};
+use crate::services::quiet_hours::QuietHoursService;
+use crate::utils::errors::Result;
+
+/// Failed posts after which a group reminder is given up
+const MAX_GROUP_REMINDER_ATTEMPTS: i32 = 3;
+
+/// Reminder service for scheduled group announcements
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ReminderService {
+    bot: Bot,
+    event_repository: EventRepository,
+    group_repository: GroupRepository,
+    notification_service: NotificationService,
+    quiet_hours_service: QuietHoursService,
+    settings: SharedSettings,
+}
+
+impl ReminderService {
+    /// Create a new ReminderService instance
+    pub fn new(
+        bot: Bot,
+        event_repository: EventRepository,
+        group_repository: GroupRepository,
+        notification_service: NotificationService,
+        quiet_hours_service: QuietHoursService,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            bot,
+            event_repository,
+            group_repository,
+            notification_service,
+            quiet_hours_service,
+            settings,
+        }
+    }
+
+    /// Check if group reminders are enabled in configuration
+    pub fn is_enabled(&self) -> bool {
+        self.settings.get().reminders.as_ref().is_some_and(|r| r.group_reminders)
+    }
+
+    /// Start the background task that posts due reminders
+    pub fn start(mut self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = self.settings.get().reminders.clone().filter(|r| r.group_reminders)?;
+        let interval = Duration::from_secs(config.check_interval_seconds);
+
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(interval);
+
+            loop {
+                check_interval.tick().await;
+
+                match self.send_due_group_reminders().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("Reminder task posted {} group reminders", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Reminder task failed: {}", e);
+                    }
+                }
+
+                if let Err(e) = self.refresh_announcements().await {
+                    error!("Failed to refresh event announcements: {}", e);
+                }
+            }
+        });
+
+        info!("Started group reminder task with interval {:?}", interval);
+        Some(handle)
+    }
+
+    /// Post reminders for events starting within the configured lead time
+    pub async fn send_due_group_reminders(&mut self) -> Result<usize> {
+        let Some(config) = self.settings.get().reminders.clone() else {
+            return Ok(0);
+        };
+
+        let until = Utc::now() + chrono::Duration::hours(config.group_reminder_lead_hours);
+        let events = self.event_repository.get_pending_group_reminders(until, MAX_GROUP_REMINDER_ATTEMPTS).await?;
+        let mut sent = 0;
+
+        for event in events {
+            let Some(group_id) = event.group_id else {
+                continue;
+            };
+
+            let Some(group) = self.group_repository.find_by_id(group_id).await? else {
+                continue;
+            };
+
+            if !group.is_active || !group.event_reminders_enabled() {
+                debug!(event_id = event.id, group_id = group_id, "Group reminders disabled, skipping");
+                continue;
+            }
+
+            // A queued reminder could not be pinned, so pinned ones wait for quiet hours to end
+            let pin = group.feature_enabled(GroupFeature::PinnedAnnouncements);
+            if pin && group.is_quiet_at(Utc::now()) {
+                debug!(event_id = event.id, group_id = group_id, "Quiet hours, holding back pinned reminder");
+                continue;
+            }
+
+            let participant_count = self.event_repository.get_participant_count(event.id).await?;
+            let request = NotificationRequest {
+                chat_id: ChatId(group.telegram_id),
+                thread_id: group.topic_id(GroupTopic::Events).map(|id| ThreadId(MessageId(id))),
+                template_key: "event_group_reminder".to_string(),
+                language: group.language_code.clone(),
+                parameters: self.reminder_parameters(&event, participant_count, &group.language_code)?,
+                parse_mode: Some(ParseMode::Html),
+                disable_web_page_preview: true,
+            };
+
+            // Reminders queued for the group's quiet hours count as sent
+            match self.quiet_hours_service.send_or_queue(&group, request).await {
+                Ok(message) => {
+                    self.event_repository.mark_group_reminder_sent(event.id).await?;
+                    if let Some(message) = message {
+                        self.track_announcement(&group, &event, message.id, participant_count, pin).await?;
+                    }
+                    sent += 1;
+                }
+                Err(e) => {
+                    let attempts = self.event_repository.record_group_reminder_failure(event.id).await?;
+                    warn!(event_id = event.id, group_id = group_id, attempts = attempts, error = %e, "Failed to post group reminder");
+                    if attempts >= MAX_GROUP_REMINDER_ATTEMPTS {
+                        error!(event_id = event.id, group_id = group_id, "Giving up on group reminder");
+                    }
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+
+    /// Remember a posted reminder so it is kept up to date, pinning it if the group wants that
+    async fn track_announcement(&self, group: &Group, event: &Event, message_id: MessageId, participant_count: i64, pin: bool) -> Result<()> {
+        let mut is_pinned = false;
+        if pin {
+            match self.bot.pin_chat_message(ChatId(group.telegram_id), message_id).disable_notification(true).await {
+                Ok(_) => is_pinned = true,
+                Err(e) => warn!(event_id = event.id, group_id = group.id, error = %e, "Failed to pin event announcement"),
+            }
+        }
+
+        self.event_repository
+            .set_group_reminder_message(event.id, message_id.0, participant_count as i32, is_pinned)
+            .await
+    }
+
+    /// Edit posted reminders whose participant count changed, and let go of the ones
+    /// for events that started. Returns the number of edited messages.
+    pub async fn refresh_announcements(&self) -> Result<usize> {
+        let mut refreshed = 0;
+
+        for reminder in self.event_repository.get_tracked_group_reminders().await? {
+            match self.refresh_announcement(&reminder).await {
+                Ok(true) => refreshed += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    warn!(event_id = reminder.event_id, error = %e, "Failed to refresh event announcement");
+                }
+            }
+        }
+
+        Ok(refreshed)
+    }
+
+    /// Refresh one posted reminder; returns whether the message was edited
+    async fn refresh_announcement(&self, reminder: &EventGroupReminder) -> Result<bool> {
+        let Some(message_id) = reminder.message_id.map(MessageId) else {
+            return Ok(false);
+        };
+        let event = self.event_repository.find_by_id(reminder.event_id).await?;
+        let group = match event.as_ref().and_then(|event| event.group_id) {
+            Some(group_id) => self.group_repository.find_by_id(group_id).await?,
+            None => None,
+        };
+
+        let (Some(event), Some(group)) = (event, group) else {
+            self.event_repository.clear_group_reminder_message(reminder.event_id).await?;
+            return Ok(false);
+        };
+        let chat_id = ChatId(group.telegram_id);
+
+        if !event.is_active || event.event_date <= Utc::now() || !group.is_active {
+            if reminder.is_pinned {
+                if let Err(e) = self.bot.unpin_chat_message(chat_id).message_id(message_id).await {
+                    debug!(event_id = event.id, error = %e, "Failed to unpin event announcement");
+                }
+            }
+            self.event_repository.clear_group_reminder_message(event.id).await?;
+            debug!(event_id = event.id, "Stopped refreshing event announcement");
+            return Ok(false);
+        }
+
+        let participant_count = self.event_repository.get_participant_count(event.id).await?;
+        if reminder.participant_count == Some(participant_count as i32) {
+            return Ok(false);
+        }
+
+        let parameters = self.reminder_parameters(&event, participant_count, &group.language_code)?;
+        let text = self.notification_service.format_message("event_group_reminder", &group.language_code, &parameters)?;
+        self.bot.edit_message_text(chat_id, message_id, text)
+            .parse_mode(ParseMode::Html)
+            .disable_link_preview(true)
+            .await?;
+        self.event_repository.set_group_reminder_count(event.id, participant_count as i32).await?;
+
+        info!(event_id = event.id, participant_count = participant_count, "Event announcement refreshed");
+        Ok(true)
+    }
+
+    /// Find event by ID
+    pub async fn find_event(&self, event_id: i64) -> Result<Option<Event>> {
+        self.event_repository.find_by_id(event_id).await
+    }
+
+    /// Enable or disable the group reminder for a single event
+    pub async fn set_event_reminder_enabled(&self, event_id: i64, enabled: bool) -> Result<()> {
+        self.event_repository.set_group_reminder_enabled(event_id, enabled).await?;
+        info!(event_id = event_id, enabled = enabled, "Event group reminder toggled");
+        Ok(())
+    }
+
+    /// Enable or disable event reminders for a group. Returns `false` if the group is unknown.
+    pub async fn set_group_reminders_enabled(&self, group_telegram_id: i64, enabled: bool) -> Result<bool> {
+        let Some(group) = self.group_repository.find_by_telegram_id(group_telegram_id).await? else {
+            return Ok(false);
+        };
+
+        self.group_repository.set_setting(group.id, "event_reminders", serde_json::Value::Bool(enabled)).await?;
+
+        info!(group_id = group.id, enabled = enabled, "Group event reminders toggled");
+        Ok(true)
+    }
+
+    /// Build template parameters for a group reminder, including the spots line in the group's language
+    fn reminder_parameters(&self, event: &Event, participant_count: i64, language: &str) -> Result<HashMap<String, String>> {
+        let mut parameters = Self::event_parameters(event, participant_count);
+        let day = self.notification_service.format_message(event_day_key(event.event_date, Utc::now()), language, &HashMap::new())?;
+        parameters.insert("event_day".to_string(), day);
+
+        let spots = match (event.max_participants, spots_left(event.max_participants, participant_count)) {
+            (Some(_), Some(0)) => {
+                self.notification_service.format_message("event_spots_full", language, &HashMap::new())?
+            }
+            (Some(max_participants), Some(left)) => {
+                let mut spot_parameters = HashMap::new();
+                spot_parameters.insert("spots_left".to_string(), left.to_string());
+                spot_parameters.insert("max_participants".to_string(), max_participants.to_string());
+                self.notification_service.format_message("event_spots_left", language, &spot_parameters)?
+            }
+            _ => String::new(),
+        };
+        parameters.insert("spots".to_string(), spots);
+
+        Ok(parameters)
+    }
+
+    /// Build the event part of the template parameters for a group reminder
+    fn event_parameters(event: &Event, participant_count: i64) -> HashMap<String, String> {
+        let mut parameters = HashMap::new();
+        parameters.insert("event_title".to_string(), html::escape(&event.title));
+        parameters.insert("event_time".to_string(), event.event_date.format("%H:%M UTC").to_string());
+        parameters.insert("event_location".to_string(), html::escape(event.location.as_deref().unwrap_or("TBD")));
+        parameters.insert("participant_count".to_string(), participant_count.to_string());
+        parameters
+    }
+}
+
+/// Template naming the day of an event starting within a day: today or tomorrow.
+/// Event times are shown in UTC, so the day is the UTC one too.
+fn event_day_key(event_date: DateTime<Utc>, now: DateTime<Utc>) -> &'static str {
+    if event_date.date_naive() > now.date_naive() {
+        "event_day_tomorrow"
+    } else {
+        "event_day_today"
+    }
+}
+
+/// Get the number of free spots of an event with a participant limit
+pub fn spots_left(max_participants: Option<i32>, participant_count: i64) -> Option<i64> {
+    max_participants.map(|max| (max as i64 - participant_count).max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_reminder_parameters() {
+        let event = Event {
+            id: 1,
+            title: "Swing <Social>".to_string(),
+            description: None,
+            event_date: Utc.with_ymd_and_hms(2024, 5, 17, 20, 0, 0).unwrap(),
+            location: None,
+            max_participants: None,
+            google_calendar_id: None,
+            created_by: None,
+            group_id: Some(1),
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let parameters = ReminderService::event_parameters(&event, 42);
+        assert_eq!(parameters.get("event_title").unwrap(), "Swing &lt;Social&gt;");
+        assert_eq!(parameters.get("event_time").unwrap(), "20:00 UTC");
+        assert_eq!(parameters.get("event_location").unwrap(), "TBD");
+        assert_eq!(parameters.get("participant_count").unwrap(), "42");
+    }
+
+    #[test]
+    fn test_event_day_key() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 17, 18, 0, 0).unwrap();
+        assert_eq!(event_day_key(Utc.with_ymd_and_hms(2024, 5, 17, 20, 0, 0).unwrap(), now), "event_day_today");
+        assert_eq!(event_day_key(Utc.with_ymd_and_hms(2024, 5, 18, 9, 0, 0).unwrap(), now), "event_day_tomorrow");
+    }
+
+    #[test]
+    fn test_spots_left() {
+        assert_eq!(spots_left(None, 8), None);
+        assert_eq!(spots_left(Some(20), 8), Some(12));
+        assert_eq!(spots_left(Some(20), 25), Some(0));
+    }
+}
@@ -0,0 +1,205 @@
+//! Scheduled message service implementation
+//!
+//! Bot admins can schedule a broadcast or a group announcement for a later time
+//! from the admin panel. Scheduled messages wait in the database, so they survive
+//! restarts; a background task picks them up when they are due. Broadcasts are
+//! handed to the broadcast queue, announcements are copied into the group (its
+//! events topic in forum groups). Times are entered and shown in UTC.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use teloxide::{Bot, prelude::*, types::{ChatId, MessageId, ThreadId}};
+use tracing::{info, warn, error};
+use crate::database::repositories::{GroupRepository, ScheduledMessageRepository};
+use crate::models::broadcast::CreateBroadcastRequest;
+use crate::models::group::GroupTopic;
+use crate::models::scheduled_message::{ScheduledMessage, CreateScheduledMessageRequest, ScheduledTarget, ScheduledMessageStatus};
+use crate::services::broadcast::BroadcastService;
+use crate::services::notification::NotificationService;
+use crate::utils::errors::{Result, SwingBuddyError};
+
+/// How often the schedule is checked for due messages
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Format admins enter the send time in
+pub const SEND_AT_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Scheduled message service for delayed broadcasts and announcements
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ScheduledMessageService {
+    bot: Bot,
+    scheduled_message_repository: ScheduledMessageRepository,
+    group_repository: GroupRepository,
+    broadcast_service: BroadcastService,
+    notification_service: NotificationService,
+}
+
+impl ScheduledMessageService {
+    /// Create a new ScheduledMessageService instance
+    pub fn new(
+        bot: Bot,
+        scheduled_message_repository: ScheduledMessageRepository,
+        group_repository: GroupRepository,
+        broadcast_service: BroadcastService,
+        notification_service: NotificationService,
+    ) -> Self {
+        Self {
+            bot,
+            scheduled_message_repository,
+            group_repository,
+            broadcast_service,
+            notification_service,
+        }
+    }
+
+    /// Schedule a message
+    pub async fn schedule(&self, request: CreateScheduledMessageRequest) -> Result<ScheduledMessage> {
+        let message = self.scheduled_message_repository.create(request).await?;
+
+        info!(scheduled_id = message.id, created_by = message.created_by, target = %message.target, send_at = %message.send_at, "Message scheduled");
+        Ok(message)
+    }
+
+    /// Get the messages waiting to be sent, the next one first
+    pub async fn get_pending(&self) -> Result<Vec<ScheduledMessage>> {
+        self.scheduled_message_repository.get_pending().await
+    }
+
+    /// Cancel a message that was not sent yet; returns false if it is no longer pending
+    pub async fn cancel(&self, id: i64) -> Result<bool> {
+        let cancelled = self.scheduled_message_repository.cancel(id).await?;
+        if cancelled {
+            info!(scheduled_id = id, "Scheduled message cancelled");
+        }
+        Ok(cancelled)
+    }
+
+    /// Start the background task that sends due messages
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(SCHEDULE_INTERVAL);
+
+            loop {
+                check_interval.tick().await;
+
+                match self.send_due().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("Sent {} scheduled messages", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Scheduled message task failed: {}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Started scheduled message task with interval {:?}", SCHEDULE_INTERVAL);
+        handle
+    }
+
+    /// Send the messages that are due. Returns the number sent.
+    pub async fn send_due(&self) -> Result<usize> {
+        let mut sent = 0;
+
+        for message in self.scheduled_message_repository.get_due(Utc::now()).await? {
+            match self.send(&message).await {
+                Ok(()) => {
+                    self.scheduled_message_repository.set_status(message.id, ScheduledMessageStatus::Sent, None).await?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    warn!(scheduled_id = message.id, target = %message.target, error = %e, "Failed to send scheduled message");
+                    self.scheduled_message_repository.set_status(message.id, ScheduledMessageStatus::Failed, Some(&e.to_string())).await?;
+                    self.notify_failure(&message).await;
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+
+    /// Queue a due broadcast or post a due announcement
+    async fn send(&self, message: &ScheduledMessage) -> Result<()> {
+        let target = message.target.parse::<ScheduledTarget>()
+            .map_err(SwingBuddyError::InvalidInput)?;
+
+        match target {
+            ScheduledTarget::Broadcast(segment) => {
+                self.broadcast_service.create_broadcast(CreateBroadcastRequest {
+                    created_by: message.created_by,
+                    language_code: message.language_code.clone(),
+                    segment,
+                    source_chat_id: message.source_chat_id,
+                    source_message_id: message.source_message_id,
+                    text: message.text.clone(),
+                }).await?;
+            }
+            ScheduledTarget::Group(chat_id) => {
+                let thread_id = self.group_repository.find_by_telegram_id(chat_id).await?
+                    .and_then(|group| group.topic_id(GroupTopic::Events));
+
+                let mut request = self.bot.copy_message(
+                    ChatId(chat_id),
+                    ChatId(message.source_chat_id),
+                    MessageId(message.source_message_id),
+                );
+                if let Some(thread_id) = thread_id {
+                    request = request.message_thread_id(ThreadId(MessageId(thread_id)));
+                }
+                request.await?;
+            }
+        }
+
+        info!(scheduled_id = message.id, target = %message.target, "Scheduled message sent");
+        Ok(())
+    }
+
+    /// Tell the admin who scheduled a message that it could not be sent
+    async fn notify_failure(&self, message: &ScheduledMessage) {
+        let mut parameters = HashMap::new();
+        parameters.insert("id".to_string(), message.id.to_string());
+        parameters.insert("target".to_string(), message.target.clone());
+
+        let text = match self.notification_service.format_message("scheduled_message_failed", &message.language_code, &parameters) {
+            Ok(text) => text,
+            Err(e) => {
+                error!(error = %e, "Failed to format scheduled message failure notice");
+                return;
+            }
+        };
+
+        if let Err(e) = self.bot.send_message(ChatId(message.created_by), text).await {
+            warn!(scheduled_id = message.id, error = %e, "Failed to notify admin about scheduled message");
+        }
+    }
+}
+
+/// Parse a send time entered by an admin (`YYYY-MM-DD HH:MM`, UTC); times that
+/// are not in the future are rejected
+pub fn parse_send_at(input: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let send_at = NaiveDateTime::parse_from_str(input.trim(), SEND_AT_FORMAT).ok()?.and_utc();
+    (send_at > now).then_some(send_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_send_at() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            parse_send_at(" 2024-03-16 19:30 ", now),
+            Some(Utc.with_ymd_and_hms(2024, 3, 16, 19, 30, 0).unwrap())
+        );
+        assert_eq!(parse_send_at("2024-03-15 12:00", now), None);
+        assert_eq!(parse_send_at("2024-03-14 19:30", now), None);
+        assert_eq!(parse_send_at("tomorrow", now), None);
+    }
+}
@@ -0,0 +1,265 @@
+//! Google Sheets export service implementation
+//!
+//! Mirrors the participant list of an event into a Google Sheet, as the
+//! account connected through [`GoogleOAuthService`]. Registration changes only
+//! flag the sheet; a background task rewrites flagged sheets right away and
+//! retries failed exports every minute, so bursts of registrations end up in
+//! a single export.
+
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use serde::Deserialize;
+use tokio::sync::Notify;
+use tracing::{debug, error, info, warn};
+use crate::database::repositories::{EventRepository, EventSheetRepository};
+use crate::models::event::{Event, EventSheet, ParticipantExportRow};
+use crate::services::google_oauth::GoogleOAuthService;
+use crate::utils::errors::{SwingBuddyError, GoogleError, Result};
+
+const SHEETS_API_URL: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+
+/// Failed exports are retried this often
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Sheets exported per run
+const EXPORT_BATCH_SIZE: i64 = 20;
+
+/// Header row of the participant sheet
+const HEADER: [&str; 7] = ["Telegram ID", "Username", "First name", "Last name", "City", "Status", "Registered at"];
+
+/// Sheets API create response
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreatedSpreadsheet {
+    spreadsheet_id: String,
+}
+
+/// Google Sheets export of event participants
+#[derive(Debug, Clone)]
+pub struct SheetExportService {
+    http_client: reqwest::Client,
+    oauth: GoogleOAuthService,
+    sheet_repository: EventSheetRepository,
+    event_repository: EventRepository,
+    /// Wakes the export task when a sheet is flagged
+    wake: Arc<Notify>,
+}
+
+impl SheetExportService {
+    /// Create a new SheetExportService instance
+    pub fn new(oauth: GoogleOAuthService, sheet_repository: EventSheetRepository, event_repository: EventRepository) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("SwingBuddy-Bot/1.0")
+            .build()
+            .map_err(SwingBuddyError::Http)?;
+
+        Ok(Self {
+            http_client,
+            oauth,
+            sheet_repository,
+            event_repository,
+            wake: Arc::new(Notify::new()),
+        })
+    }
+
+    /// Start the background export task (no-op without `[google.oauth]`)
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        if !self.oauth.is_configured() {
+            return None;
+        }
+
+        let handle = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = self.wake.notified() => {}
+                    _ = tokio::time::sleep(RETRY_INTERVAL) => {}
+                }
+
+                match self.export_pending().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            debug!("Exported {} event sheets", count);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Event sheet export task failed: {}", e);
+                    }
+                }
+            }
+        });
+
+        info!("Started event sheet export task");
+        Some(handle)
+    }
+
+    /// The sheet linked to an event, if any
+    pub async fn get_sheet(&self, event_id: i64) -> Result<Option<EventSheet>> {
+        self.sheet_repository.get(event_id).await
+    }
+
+    /// Create a new spreadsheet for an event and mirror its participants into it
+    pub async fn create_sheet(&self, event: &Event, admin_id: i64) -> Result<EventSheet> {
+        let token = self.access_token().await?;
+        let title = format!("{} ({})", event.title, event.event_date.format("%Y-%m-%d"));
+        let body = serde_json::json!({ "properties": { "title": title } });
+
+        let created: CreatedSpreadsheet = self.api_request(self.http_client.post(SHEETS_API_URL).json(&body), &token).await?
+            .json().await.map_err(|e| GoogleError::ApiError(e.to_string()))?;
+
+        info!(event_id = event.id, spreadsheet_id = %created.spreadsheet_id, "Created event sheet");
+        self.link_sheet(event.id, &created.spreadsheet_id, admin_id).await
+    }
+
+    /// Mirror an event's participants into an existing spreadsheet, given by URL or ID
+    pub async fn link_sheet(&self, event_id: i64, spreadsheet: &str, admin_id: i64) -> Result<EventSheet> {
+        let spreadsheet_id = parse_spreadsheet_id(spreadsheet)
+            .ok_or_else(|| SwingBuddyError::InvalidInput("Not a Google Sheets link or ID".to_string()))?;
+
+        let sheet = self.sheet_repository.set(event_id, &spreadsheet_id, admin_id).await?;
+        self.wake.notify_one();
+        Ok(sheet)
+    }
+
+    /// Stop mirroring an event; the spreadsheet itself is kept. Returns whether it had one.
+    pub async fn unlink_sheet(&self, event_id: i64) -> Result<bool> {
+        self.sheet_repository.delete(event_id).await
+    }
+
+    /// Export an event's participants again after they changed. Cheap when the event has no sheet.
+    pub async fn request_sync(&self, event_id: i64) -> Result<()> {
+        if self.sheet_repository.mark_needs_sync(event_id).await? {
+            self.wake.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Export the flagged sheets. Returns the number exported.
+    pub async fn export_pending(&self) -> Result<usize> {
+        let sheets = self.sheet_repository.get_needing_sync(EXPORT_BATCH_SIZE).await?;
+        let mut exported = 0;
+
+        for sheet in sheets {
+            let started_at = Utc::now();
+            match self.export(&sheet).await {
+                Ok(()) => {
+                    self.sheet_repository.mark_synced(sheet.event_id, started_at).await?;
+                    exported += 1;
+                }
+                Err(e) => {
+                    warn!(event_id = sheet.event_id, error = %e, "Failed to export event sheet");
+                    self.sheet_repository.mark_failed(sheet.event_id, &e.to_string()).await?;
+                }
+            }
+        }
+
+        Ok(exported)
+    }
+
+    /// Replace the sheet's contents with the current participant list
+    async fn export(&self, sheet: &EventSheet) -> Result<()> {
+        let token = self.access_token().await?;
+        let participants = self.event_repository.get_participant_export(sheet.event_id).await?;
+        let values = participant_rows(&participants);
+        let base = format!("{}/{}/values", SHEETS_API_URL, urlencoding::encode(&sheet.spreadsheet_id));
+
+        // Ranges without a sheet name refer to the first sheet
+        self.api_request(self.http_client.post(format!("{}/A:Z:clear", base)), &token).await?;
+        let body = serde_json::json!({ "values": values });
+        self.api_request(
+            self.http_client.put(format!("{}/A1", base)).query(&[("valueInputOption", "RAW")]).json(&body),
+            &token,
+        ).await?;
+
+        debug!(event_id = sheet.event_id, participants = participants.len(), "Exported event sheet");
+        Ok(())
+    }
+
+    async fn access_token(&self) -> Result<String> {
+        self.oauth.access_token().await?
+            .ok_or_else(|| GoogleError::AuthenticationFailed("No Google account connected".to_string()).into())
+    }
+
+    /// Send a Sheets API request, turning error statuses into errors
+    async fn api_request(&self, request: reqwest::RequestBuilder, token: &str) -> Result<reqwest::Response> {
+        let response = request.bearer_auth(token).send().await
+            .map_err(|e| GoogleError::ApiError(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        Err(match status {
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => GoogleError::AuthenticationFailed(body),
+            _ => GoogleError::ApiError(format!("HTTP {}: {}", status, body)),
+        }.into())
+    }
+}
+
+/// Link to open a spreadsheet
+pub fn spreadsheet_url(spreadsheet_id: &str) -> String {
+    format!("https://docs.google.com/spreadsheets/d/{}/edit", spreadsheet_id)
+}
+
+/// Spreadsheet ID from a Google Sheets link or a bare ID
+pub fn parse_spreadsheet_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    let id = match input.split_once("/spreadsheets/d/") {
+        Some((_, rest)) => rest.split(['/', '?', '#']).next().unwrap_or_default(),
+        None => input,
+    };
+
+    let valid = id.len() >= 20 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| id.to_string())
+}
+
+/// Header and one row per participant
+fn participant_rows(participants: &[ParticipantExportRow]) -> Vec<Vec<String>> {
+    let mut rows = vec![HEADER.iter().map(|column| column.to_string()).collect::<Vec<_>>()];
+    rows.extend(participants.iter().map(|participant| vec![
+        participant.telegram_id.to_string(),
+        participant.username.as_ref().map(|username| format!("@{}", username)).unwrap_or_default(),
+        participant.first_name.clone().unwrap_or_default(),
+        participant.last_name.clone().unwrap_or_default(),
+        participant.location.clone().unwrap_or_default(),
+        participant.status.clone().unwrap_or_default(),
+        participant.registered_at.map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string()).unwrap_or_default(),
+    ]));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "1BxiMVs0XRA5nFMdKvBdBZjgmUUqptlbs74OgvE2upms";
+
+    #[test]
+    fn test_parse_spreadsheet_id() {
+        assert_eq!(parse_spreadsheet_id(ID).as_deref(), Some(ID));
+        assert_eq!(parse_spreadsheet_id(&format!("https://docs.google.com/spreadsheets/d/{}/edit#gid=0", ID)).as_deref(), Some(ID));
+        assert_eq!(parse_spreadsheet_id(&format!(" https://docs.google.com/spreadsheets/d/{}?usp=sharing ", ID)).as_deref(), Some(ID));
+        assert_eq!(parse_spreadsheet_id("https://example.com/not-a-sheet"), None);
+        assert_eq!(parse_spreadsheet_id("short"), None);
+    }
+
+    #[test]
+    fn test_participant_rows() {
+        let rows = participant_rows(&[ParticipantExportRow {
+            telegram_id: 42,
+            username: Some("dancer".to_string()),
+            first_name: Some("Frankie".to_string()),
+            last_name: None,
+            location: Some("Harlem".to_string()),
+            status: Some("registered".to_string()),
+            registered_at: None,
+        }]);
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].len(), HEADER.len());
+        assert_eq!(rows[1], vec!["42", "@dancer", "Frankie", "", "Harlem", "registered", ""]);
+    }
+}
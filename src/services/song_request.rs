@@ -0,0 +1,148 @@
+//! Song request service implementation
+//!
+//! Attendees ask the DJ for tracks while an event is on. The DJ works through
+//! the queue, marking tracks as played or skipped, and the played tracks stay
+//! with the event as its playlist.
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::info;
+use crate::database::repositories::{SongRequestRepository, EventRepository, UserRepository};
+use crate::models::event::Event;
+use crate::models::song_request::{SongRequest, CreateSongRequestRequest, SongRequestStatus};
+use crate::utils::errors::Result;
+
+/// Longest track name accepted in a request
+pub const MAX_TRACK_LENGTH: usize = 200;
+
+/// Tracks one attendee may have waiting in the queue at a time
+pub const MAX_PENDING_PER_ATTENDEE: i64 = 3;
+
+/// How long before its start an event takes requests
+const PARTY_OPENS_BEFORE_HOURS: i64 = 1;
+
+/// How long after its start an event is assumed to be on
+const PARTY_LENGTH_HOURS: i64 = 6;
+
+/// What came of a song request
+#[derive(Debug, Clone)]
+pub enum SongRequestOutcome {
+    /// Added to the queue, at this position
+    Queued(SongRequest, usize),
+    /// Somebody already asked for the track and it is still waiting
+    AlreadyQueued(SongRequest),
+    /// The attendee has too many tracks waiting already
+    TooMany,
+}
+
+/// Song request service for the DJ queues of events
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct SongRequestService {
+    song_request_repository: SongRequestRepository,
+    event_repository: EventRepository,
+    user_repository: UserRepository,
+}
+
+impl SongRequestService {
+    /// Create a new SongRequestService instance
+    pub fn new(song_request_repository: SongRequestRepository, event_repository: EventRepository, user_repository: UserRepository) -> Self {
+        Self { song_request_repository, event_repository, user_repository }
+    }
+
+    /// Find the event that is on right now: the group's when a group is given,
+    /// otherwise one the user registered for
+    pub async fn find_current_event(&self, group_id: Option<i64>, telegram_id: i64) -> Result<Option<Event>> {
+        let now = Utc::now();
+        let (from, until) = party_window(now);
+
+        let events = match group_id {
+            Some(group_id) => self.event_repository.get_group_events_between(group_id, from, until).await?,
+            None => match self.user_repository.find_by_telegram_id(telegram_id).await? {
+                Some(user) => self.event_repository.get_user_registered_events(user.id).await?
+                    .into_iter()
+                    .filter(|event| event.event_date >= from && event.event_date < until)
+                    .collect(),
+                None => Vec::new(),
+            },
+        };
+
+        // The latest event that already started, or the one about to start
+        Ok(events.into_iter().min_by_key(|event| (event.event_date > now, (event.event_date - now).num_seconds().abs())))
+    }
+
+    /// Put a track in an event's queue
+    pub async fn request_song(&self, event: &Event, telegram_id: i64, track: &str) -> Result<SongRequestOutcome> {
+        if let Some(song) = self.song_request_repository.find_pending_track(event.id, track).await? {
+            return Ok(SongRequestOutcome::AlreadyQueued(song));
+        }
+        if self.song_request_repository.count_pending_by_user(event.id, telegram_id).await? >= MAX_PENDING_PER_ATTENDEE {
+            return Ok(SongRequestOutcome::TooMany);
+        }
+
+        let song = self.song_request_repository.create(CreateSongRequestRequest {
+            event_id: event.id,
+            requested_by: telegram_id,
+            track: track.to_string(),
+        }).await?;
+        let position = self.get_queue(event.id).await?.len();
+
+        info!(event_id = event.id, song_id = song.id, user_id = telegram_id, "Song requested");
+        Ok(SongRequestOutcome::Queued(song, position))
+    }
+
+    /// Find a song request by ID
+    pub async fn get_request(&self, id: i64) -> Result<Option<SongRequest>> {
+        self.song_request_repository.find_by_id(id).await
+    }
+
+    /// Tracks waiting for the DJ, oldest first
+    pub async fn get_queue(&self, event_id: i64) -> Result<Vec<SongRequest>> {
+        self.song_request_repository.get_by_status(event_id, SongRequestStatus::Pending).await
+    }
+
+    /// Requested tracks the DJ played, in the order they were played
+    pub async fn get_playlist(&self, event_id: i64) -> Result<Vec<SongRequest>> {
+        self.song_request_repository.get_by_status(event_id, SongRequestStatus::Played).await
+    }
+
+    /// Take a track off the queue as played or skipped; returns `None` if it
+    /// was not waiting anymore
+    pub async fn handle_request(&self, id: i64, status: SongRequestStatus, dj_id: i64) -> Result<Option<SongRequest>> {
+        let song = self.song_request_repository.handle(id, status, dj_id).await?;
+        if let Some(song) = &song {
+            info!(event_id = song.event_id, song_id = id, dj_id = dj_id, status = status.as_str(), "Song request handled");
+        }
+        Ok(song)
+    }
+}
+
+/// Clean up a requested track name; `None` if it is empty or too long
+pub fn normalize_track(track: &str) -> Option<String> {
+    let track = track.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!track.is_empty() && track.chars().count() <= MAX_TRACK_LENGTH).then_some(track)
+}
+
+/// Start times of the events that take requests at `now`, as `[from, until)`
+fn party_window(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    (now - Duration::hours(PARTY_LENGTH_HOURS), now + Duration::hours(PARTY_OPENS_BEFORE_HOURS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_track() {
+        assert_eq!(normalize_track("  Sing,  Sing,\nSing "), Some("Sing, Sing, Sing".to_string()));
+        assert_eq!(normalize_track("   "), None);
+        assert_eq!(normalize_track(&"a".repeat(MAX_TRACK_LENGTH + 1)), None);
+    }
+
+    #[test]
+    fn test_party_window() {
+        let now = Utc::now();
+        let (from, until) = party_window(now);
+        assert!(from < now - Duration::hours(5));
+        assert!(until > now && until <= now + Duration::hours(1));
+    }
+}
@@ -0,0 +1,172 @@
+//! Group statistics service implementation
+//!
+//! This service counts group activity that no other table records and combines it
+//! with existing data into the weekly overview shown by /groupstats. It also builds
+//! the bot-wide growth series shown in the admin panel statistics.
+//!
+//! Counts are added up in memory and written to the database periodically, so a
+//! busy group costs one upsert per counter and flush rather than one per message.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::{Datelike, NaiveDate, Utc};
+use teloxide::types::ChatId;
+use tracing::{debug, info, warn};
+use crate::database::repositories::StatsRepository;
+use crate::models::stats::{GroupCounter, GroupStats, GrowthInterval, GrowthPoint};
+use crate::utils::errors::Result;
+
+/// Number of days covered by /groupstats
+pub const STATS_PERIOD_DAYS: i64 = 7;
+
+/// Number of days or weeks covered by the growth statistics
+pub const GROWTH_PERIODS: i64 = 14;
+
+/// How often counts added up in memory are written to the database
+pub const STATS_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Counts not written yet, by group, counter and day
+type PendingCounts = HashMap<(i64, GroupCounter, NaiveDate), i64>;
+
+/// Bars of a text sparkline, from lowest to highest
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Stats service for group activity counters
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct StatsService {
+    stats_repository: StatsRepository,
+    pending: Arc<Mutex<PendingCounts>>,
+}
+
+impl StatsService {
+    /// Create a new StatsService instance
+    pub fn new(stats_repository: StatsRepository) -> Self {
+        Self { stats_repository, pending: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    /// Count group activity; the count reaches the database with the next flush
+    pub fn record(&self, chat_id: ChatId, counter: GroupCounter) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        *pending.entry((chat_id.0, counter, Utc::now().date_naive())).or_insert(0) += 1;
+    }
+
+    /// Write the counts added up since the last flush. Counts that fail to be
+    /// written are kept for the next one. Returns the number of counters written.
+    pub async fn flush(&self) -> Result<usize> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+
+        let mut written = 0;
+        let mut failed = PendingCounts::new();
+        let mut error = None;
+        for ((group_telegram_id, counter, day), amount) in pending {
+            match self.stats_repository.increment(group_telegram_id, counter, day, amount).await {
+                Ok(()) => written += 1,
+                Err(e) => {
+                    failed.insert((group_telegram_id, counter, day), amount);
+                    error = Some(e);
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            for (key, amount) in failed {
+                *pending.entry(key).or_insert(0) += amount;
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(written),
+        }
+    }
+
+    /// Start the background task that writes the counts to the database
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        let handle = tokio::spawn(async move {
+            let mut flush_interval = tokio::time::interval(STATS_FLUSH_INTERVAL);
+
+            loop {
+                flush_interval.tick().await;
+
+                match self.flush().await {
+                    Ok(count) => debug!(counters = count, "Flushed group activity counters"),
+                    Err(e) => warn!(error = %e, "Failed to flush group activity counters"),
+                }
+            }
+        });
+
+        info!("Started group activity counter flush with interval {:?}", STATS_FLUSH_INTERVAL);
+        handle
+    }
+
+    /// Get a group's activity over the last week
+    pub async fn weekly_group_stats(&self, group_id: i64) -> Result<GroupStats> {
+        // Include the counts still held in memory
+        if let Err(e) = self.flush().await {
+            warn!(error = %e, "Failed to flush group activity counters");
+        }
+
+        // Today and the six days before it, from midnight UTC
+        let since = (Utc::now().date_naive() - chrono::Duration::days(STATS_PERIOD_DAYS - 1))
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc();
+        self.stats_repository.get_group_stats(group_id, since).await
+    }
+
+    /// Get the bot-wide growth over the last `GROWTH_PERIODS` days or weeks, oldest first
+    pub async fn growth(&self, interval: GrowthInterval) -> Result<Vec<GrowthPoint>> {
+        let since = growth_since(interval, Utc::now().date_naive());
+        self.stats_repository.get_growth(interval, since).await
+    }
+}
+
+/// Get the first day of the growth statistics: the start of the oldest of the
+/// `GROWTH_PERIODS` days or weeks (weeks start on Monday) ending with the current one
+pub fn growth_since(interval: GrowthInterval, today: NaiveDate) -> NaiveDate {
+    match interval {
+        GrowthInterval::Day => today - chrono::Duration::days(GROWTH_PERIODS - 1),
+        GrowthInterval::Week => {
+            let monday = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+            monday - chrono::Duration::weeks(GROWTH_PERIODS - 1)
+        }
+    }
+}
+
+/// Render values as a text sparkline, scaled to the largest one
+pub fn sparkline(values: &[i64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values.iter()
+        .map(|&value| {
+            if max <= 0 || value <= 0 {
+                return SPARKLINE_BARS[0];
+            }
+            let index = (value * (SPARKLINE_BARS.len() as i64 - 1) + max - 1) / max;
+            SPARKLINE_BARS[index as usize]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_growth_since() {
+        // A Friday
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        assert_eq!(growth_since(GrowthInterval::Day, today), NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+        assert_eq!(growth_since(GrowthInterval::Week, today), NaiveDate::from_ymd_opt(2023, 12, 11).unwrap());
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 1, 4, 7, 0]), "▁▂▅█▁");
+        assert_eq!(sparkline(&[0, 0, 0]), "▁▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
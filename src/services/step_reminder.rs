@@ -0,0 +1,141 @@
+//! Step reminder service implementation
+//!
+//! Users who stop halfway through a scenario, e.g. onboarding at the name
+//! step, lose what they entered when their conversation context expires.
+//! Shortly before that happens they get one direct message with a Continue
+//! button that gives them the scenario's full time again.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use chrono::Utc;
+use teloxide::{Bot, prelude::*, types::{ChatId, InlineKeyboardMarkup}};
+use tracing::{info, warn, error, debug};
+use crate::config::settings::SharedSettings;
+use crate::handlers::callbacks::{CallbackData, ScenarioAction};
+use crate::services::notification::NotificationService;
+use crate::services::user::UserService;
+use crate::state::{ConversationContext, ScenarioManager, StateStorage};
+use crate::utils::errors::Result;
+
+/// Step reminder service for abandoned scenarios
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct StepReminderService {
+    bot: Bot,
+    state_storage: StateStorage,
+    scenario_manager: ScenarioManager,
+    user_service: UserService,
+    notification_service: NotificationService,
+    settings: SharedSettings,
+}
+
+impl StepReminderService {
+    /// Create a new StepReminderService instance
+    pub fn new(
+        bot: Bot,
+        state_storage: StateStorage,
+        scenario_manager: ScenarioManager,
+        user_service: UserService,
+        notification_service: NotificationService,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            bot,
+            state_storage,
+            scenario_manager,
+            user_service,
+            notification_service,
+            settings,
+        }
+    }
+
+    /// Remind users whose context is about to expire at a step waiting for their
+    /// input. Returns the number of reminders sent.
+    pub async fn send_due(&self) -> Result<usize> {
+        let Some(config) = self.settings.get().step_reminders.clone() else {
+            return Ok(0);
+        };
+        let window = chrono::Duration::seconds(config.remind_before_seconds as i64);
+
+        let mut sent = 0;
+        for user_id in self.state_storage.get_active_users().await? {
+            // Mark first so a failing send isn't retried every check; a user
+            // moving on at the same time wins over the mark
+            let marked = self.state_storage.update_context((user_id, None), |context| {
+                if !context.needs_reminder(window) || !self.waits_for_input(context) {
+                    return Ok(false);
+                }
+                context.reminded_at = Some(Utc::now());
+                Ok(true)
+            }).await?;
+            let Some(context) = marked else {
+                continue;
+            };
+            if !self.user_service.get_notification_preferences(user_id).await?.reminders_enabled {
+                debug!(user_id = user_id, "Step reminder skipped, the user unsubscribed");
+                continue;
+            }
+
+            match self.send_reminder(&context).await {
+                Ok(()) => sent += 1,
+                Err(e) => warn!(user_id = user_id, error = %e, "Failed to send step reminder"),
+            }
+        }
+
+        if sent > 0 {
+            info!(sent = sent, "Sent step reminders");
+        }
+        Ok(sent)
+    }
+
+    /// Whether the user is at a step that waits for them to type or choose something
+    fn waits_for_input(&self, context: &ConversationContext) -> bool {
+        self.scenario_manager.get_current_step(context).is_ok_and(|step| step.requires_input)
+    }
+
+    async fn send_reminder(&self, context: &ConversationContext) -> Result<()> {
+        let language = match context.get_string("language") {
+            Some(language) => language,
+            None => self.user_service.get_user_by_telegram_id(context.user_id).await?
+                .map(|user| user.language_code)
+                .unwrap_or_else(|| self.settings.get().i18n.default_language.clone()),
+        };
+        let minutes = context.expires_at
+            .map(|expires_at| ((expires_at - Utc::now()).num_seconds() + 59) / 60)
+            .unwrap_or(0)
+            .max(1);
+
+        let mut parameters = HashMap::new();
+        parameters.insert("minutes".to_string(), minutes.to_string());
+        let text = self.notification_service.format_message("step_reminder", &language, &parameters)?;
+        let button = self.notification_service.format_message("step_reminder_button", &language, &HashMap::new())?;
+
+        self.bot.send_message(ChatId(context.user_id), text)
+            .reply_markup(InlineKeyboardMarkup::new(vec![vec![CallbackData::Scenario(ScenarioAction::Continue).button(button)]]))
+            .await?;
+
+        debug!(user_id = context.user_id, scenario = ?context.scenario, step = ?context.step, "Step reminder sent");
+        Ok(())
+    }
+
+    /// Start the background task that sends step reminders
+    pub fn start(self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = self.settings.get().step_reminders.clone()?;
+        let interval = Duration::from_secs(config.check_interval_seconds);
+
+        let handle = tokio::spawn(async move {
+            let mut check_interval = tokio::time::interval(interval);
+
+            loop {
+                check_interval.tick().await;
+
+                if let Err(e) = self.send_due().await {
+                    error!("Step reminder task failed: {}", e);
+                }
+            }
+        });
+
+        info!("Started step reminder task with interval {:?}", interval);
+        Some(handle)
+    }
+}
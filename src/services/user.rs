@@ -2,37 +2,150 @@
 //! 
 //! This service handles user registration, profile management, language preferences,
 //! location management, and user onboarding flow logic.
+//!
+//! Handlers look the same user up several times per update, so users found by
+//! Telegram ID are kept in memory for a few seconds. Every write goes through
+//! this service and refreshes the cached copy.
 
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tracing::{info, warn, debug};
-use crate::config::settings::Settings;
+use crate::config::settings::SharedSettings;
 use crate::database::repositories::UserRepository;
-use crate::models::user::{User, CreateUserRequest, UpdateUserRequest};
+use crate::models::user::{User, CreateUserRequest, UpdateUserRequest, NotificationPreferences, UserListEntry, UserListSort, UserExportColumn, UserImportReport, ProfileField};
+use crate::models::event::Event;
+use crate::models::trash::{TrashEntry, TRASH_PAGE_SIZE};
+use crate::models::activity::ActivityAction;
+use crate::services::activity_log::ActivityLogService;
+use crate::services::outgoing_webhook::{OutgoingWebhookService, WebhookEvent};
 use crate::utils::errors::{SwingBuddyError, Result};
+use crate::utils::metrics::metrics;
+
+/// Users shown per page of the admin user list
+pub const USER_LIST_PAGE_SIZE: i64 = 10;
+
+/// Largest CSV file accepted by the user import
+pub const MAX_IMPORT_BYTES: u32 = 1024 * 1024;
+
+/// Longest name an admin can set; Telegram allows 64 characters
+const MAX_NAME_LENGTH: usize = 64;
+
+/// Longest city an admin can set
+const MAX_CITY_LENGTH: usize = 100;
+
+/// Last activity is written at most this often per user
+const ACTIVITY_WRITE_INTERVAL_SECONDS: i64 = 300;
+
+/// How long a user looked up by Telegram ID is served from memory
+const USER_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Most users kept in memory; expired ones are dropped when it fills up
+const USER_CACHE_CAPACITY: usize = 10_000;
+
+/// Users recently looked up by Telegram ID
+#[derive(Debug, Clone)]
+pub struct UserCache {
+    entries: Arc<RwLock<HashMap<i64, (User, Instant)>>>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl UserCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            ttl,
+            capacity,
+        }
+    }
+
+    /// The cached user, unless it is older than the TTL
+    pub fn get(&self, telegram_id: i64) -> Option<User> {
+        let entries = self.entries.read().unwrap();
+        entries.get(&telegram_id)
+            .filter(|(_, cached_at)| cached_at.elapsed() < self.ttl)
+            .map(|(user, _)| user.clone())
+    }
+
+    /// Cache a user fresh from the database
+    pub fn insert(&self, user: &User) {
+        let mut entries = self.entries.write().unwrap();
+        if entries.len() >= self.capacity && !entries.contains_key(&user.telegram_id) {
+            entries.retain(|_, (_, cached_at)| cached_at.elapsed() < self.ttl);
+            if entries.len() >= self.capacity {
+                entries.clear();
+            }
+        }
+        entries.insert(user.telegram_id, (user.clone(), Instant::now()));
+    }
+
+    /// Forget one user, e.g. after deleting them
+    pub fn remove(&self, telegram_id: i64) {
+        self.entries.write().unwrap().remove(&telegram_id);
+    }
+
+    /// Forget every user, e.g. after a bulk change
+    pub fn clear(&self) {
+        self.entries.write().unwrap().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for UserCache {
+    fn default() -> Self {
+        Self::new(USER_CACHE_TTL, USER_CACHE_CAPACITY)
+    }
+}
 
 /// User service for managing user operations
 #[derive(Clone)]
 #[derive(Debug)]
 pub struct UserService {
     user_repository: UserRepository,
-    settings: Settings,
+    settings: SharedSettings,
+    webhook_service: Option<OutgoingWebhookService>,
+    activity_log: Option<ActivityLogService>,
+    cache: UserCache,
 }
 
 impl UserService {
     /// Create a new UserService instance
-    pub fn new(user_repository: UserRepository, settings: Settings) -> Self {
+    pub fn new(user_repository: UserRepository, settings: SharedSettings) -> Self {
         Self {
             user_repository,
             settings,
+            webhook_service: None,
+            activity_log: None,
+            cache: UserCache::default(),
         }
     }
 
+    /// Announce new users through outgoing webhooks
+    pub fn with_webhooks(mut self, webhook_service: OutgoingWebhookService) -> Self {
+        self.webhook_service = Some(webhook_service);
+        self
+    }
+
+    /// Record bot bans in the activity log
+    pub fn with_activity_log(mut self, activity_log: ActivityLogService) -> Self {
+        self.activity_log = Some(activity_log);
+        self
+    }
+
     /// Register a new user or get existing user
     pub async fn register_or_get_user(&self, telegram_id: i64, username: Option<String>, first_name: Option<String>, last_name: Option<String>) -> Result<User> {
         debug!(telegram_id = telegram_id, "Attempting to register or get user");
 
         // Check if user already exists
-        if let Some(existing_user) = self.user_repository.find_by_telegram_id(telegram_id).await? {
+        if let Some(existing_user) = self.find_user(telegram_id).await? {
             info!(user_id = existing_user.id, telegram_id = telegram_id, "User already exists, returning existing user");
             return Ok(existing_user);
         }
@@ -43,12 +156,24 @@ impl UserService {
             username,
             first_name,
             last_name,
-            language_code: Some(self.settings.i18n.default_language.clone()),
+            language_code: Some(self.settings.get().i18n.default_language.clone()),
             location: None,
         };
 
         let user = self.user_repository.create(create_request).await?;
+        self.cache.insert(&user);
         info!(user_id = user.id, telegram_id = telegram_id, "New user registered successfully");
+
+        if let Some(webhook_service) = &self.webhook_service {
+            webhook_service.emit(WebhookEvent::UserRegistered, &serde_json::json!({
+                "user": {
+                    "telegram_id": user.telegram_id,
+                    "username": user.username,
+                    "first_name": user.first_name,
+                    "last_name": user.last_name,
+                }
+            }));
+        }
         
         Ok(user)
     }
@@ -56,7 +181,34 @@ impl UserService {
     /// Get user by Telegram ID
     pub async fn get_user_by_telegram_id(&self, telegram_id: i64) -> Result<Option<User>> {
         debug!(telegram_id = telegram_id, "Getting user by Telegram ID");
-        self.user_repository.find_by_telegram_id(telegram_id).await
+        self.find_user(telegram_id).await
+    }
+
+    /// Look a user up by Telegram ID, from the cache if it was seen in the last few seconds
+    async fn find_user(&self, telegram_id: i64) -> Result<Option<User>> {
+        if let Some(user) = self.cache.get(telegram_id) {
+            metrics().user_cache_lookup("hit");
+            return Ok(Some(user));
+        }
+
+        metrics().user_cache_lookup("miss");
+        let user = self.user_repository.find_by_telegram_id(telegram_id).await?;
+        if let Some(user) = &user {
+            self.cache.insert(user);
+        }
+        Ok(user)
+    }
+
+    /// Get user by Telegram username, with or without the leading `@`
+    pub async fn get_user_by_username(&self, username: &str) -> Result<Option<User>> {
+        debug!(username = %username, "Getting user by username");
+        self.user_repository.find_by_username(username.trim_start_matches('@')).await
+    }
+
+    /// Check if the user organizes (created) the given event
+    pub async fn is_event_organizer(&self, telegram_id: i64, event: &Event) -> Result<bool> {
+        let user = self.find_user(telegram_id).await?;
+        Ok(user.is_some_and(|u| Some(u.id) == event.created_by))
     }
 
     /// Get user by ID
@@ -70,10 +222,11 @@ impl UserService {
         debug!(telegram_id = telegram_id, "Updating user profile");
         
         // First get the user by telegram_id to get the internal user_id
-        let existing_user = self.user_repository.find_by_telegram_id(telegram_id).await?
+        let existing_user = self.find_user(telegram_id).await?
             .ok_or_else(|| SwingBuddyError::UserNotFound { user_id: telegram_id })?;
         
         let user = self.user_repository.update(existing_user.id, update_request).await?;
+        self.cache.insert(&user);
         info!(telegram_id = telegram_id, user_id = existing_user.id, "User profile updated successfully");
         
         Ok(user)
@@ -84,13 +237,13 @@ impl UserService {
         debug!(telegram_id = telegram_id, language_code = %language_code, "Setting user language preference");
 
         // Validate language code
-        if !self.settings.i18n.supported_languages.contains(&language_code) {
+        if !self.settings.get().i18n.supported_languages.contains(&language_code) {
             warn!(telegram_id = telegram_id, language_code = %language_code, "Unsupported language code");
             return Err(SwingBuddyError::InvalidInput(format!("Unsupported language: {}", language_code)));
         }
 
         // First get the user by telegram_id to get the internal user_id
-        let existing_user = self.user_repository.find_by_telegram_id(telegram_id).await?
+        let existing_user = self.find_user(telegram_id).await?
             .ok_or_else(|| SwingBuddyError::UserNotFound { user_id: telegram_id })?;
 
         let update_request = UpdateUserRequest {
@@ -99,6 +252,7 @@ impl UserService {
         };
 
         let user = self.user_repository.update(existing_user.id, update_request).await?;
+        self.cache.insert(&user);
         info!(telegram_id = telegram_id, user_id = existing_user.id, language_code = %language_code, "User language preference updated");
         
         Ok(user)
@@ -112,7 +266,7 @@ impl UserService {
         let normalized_location = self.normalize_location(&location);
 
         // First get the user by telegram_id to get the internal user_id
-        let existing_user = self.user_repository.find_by_telegram_id(telegram_id).await?
+        let existing_user = self.find_user(telegram_id).await?
             .ok_or_else(|| SwingBuddyError::UserNotFound { user_id: telegram_id })?;
 
         let update_request = UpdateUserRequest {
@@ -121,6 +275,7 @@ impl UserService {
         };
 
         let user = self.user_repository.update(existing_user.id, update_request).await?;
+        self.cache.insert(&user);
         info!(telegram_id = telegram_id, user_id = existing_user.id, location = %normalized_location, "User location updated");
         
         Ok(user)
@@ -153,7 +308,7 @@ impl UserService {
     pub async fn needs_onboarding(&self, telegram_id: i64) -> Result<bool> {
         debug!(telegram_id = telegram_id, "Checking if user needs onboarding");
 
-        let user = self.user_repository.find_by_telegram_id(telegram_id).await?
+        let user = self.find_user(telegram_id).await?
             .ok_or(SwingBuddyError::UserNotFound { user_id: telegram_id })?;
 
         // User needs onboarding if they don't have a location set
@@ -168,13 +323,13 @@ impl UserService {
         info!(telegram_id = telegram_id, "Completing user onboarding");
 
         // First get the user by telegram_id to get the internal user_id
-        let existing_user = self.user_repository.find_by_telegram_id(telegram_id).await?
+        let existing_user = self.find_user(telegram_id).await?
             .ok_or_else(|| SwingBuddyError::UserNotFound { user_id: telegram_id })?;
 
         let mut update_request = UpdateUserRequest::default();
 
         if let Some(lang) = language_code {
-            if self.settings.i18n.supported_languages.contains(&lang) {
+            if self.settings.get().i18n.supported_languages.contains(&lang) {
                 update_request.language_code = Some(lang);
             }
         }
@@ -184,30 +339,72 @@ impl UserService {
         }
 
         let user = self.user_repository.update(existing_user.id, update_request).await?;
+        self.cache.insert(&user);
         info!(telegram_id = telegram_id, user_id = existing_user.id, "User onboarding completed successfully");
         
         Ok(user)
     }
 
+    /// Get which optional notifications a user gets; all of them for unknown users
+    pub async fn get_notification_preferences(&self, telegram_id: i64) -> Result<NotificationPreferences> {
+        Ok(self.user_repository.get_notification_preferences(telegram_id).await?
+            .unwrap_or(NotificationPreferences::all(true)))
+    }
+
+    /// Set which optional notifications a user gets. Returns whether the user was found.
+    pub async fn set_notification_preferences(&self, telegram_id: i64, preferences: NotificationPreferences) -> Result<bool> {
+        let updated = self.user_repository.set_notification_preferences(telegram_id, preferences).await?;
+        if updated {
+            info!(telegram_id = telegram_id, preferences = ?preferences, "Notification preferences changed");
+        }
+        Ok(updated)
+    }
+
     /// Ban or unban user
     pub async fn set_user_ban_status(&self, telegram_id: i64, is_banned: bool, admin_id: i64) -> Result<User> {
         info!(telegram_id = telegram_id, is_banned = is_banned, admin_id = admin_id, "Setting user ban status");
 
         // First get the user by telegram_id to get the internal user_id
-        let existing_user = self.user_repository.find_by_telegram_id(telegram_id).await?
+        let existing_user = self.find_user(telegram_id).await?
             .ok_or_else(|| SwingBuddyError::UserNotFound { user_id: telegram_id })?;
 
         let user = self.user_repository.set_ban_status(existing_user.id, is_banned).await?;
+        self.cache.insert(&user);
         
         if is_banned {
             warn!(telegram_id = telegram_id, user_id = existing_user.id, admin_id = admin_id, "User banned");
         } else {
             info!(telegram_id = telegram_id, user_id = existing_user.id, admin_id = admin_id, "User unbanned");
         }
+
+        if let Some(activity_log) = &self.activity_log {
+            let action = if is_banned { ActivityAction::UserBanned } else { ActivityAction::UserUnbanned };
+            activity_log.record(action, Some(admin_id), telegram_id, serde_json::json!({})).await;
+        }
         
         Ok(user)
     }
 
+    /// Correct one profile field of a user on behalf of an admin
+    pub async fn update_profile_field(&self, telegram_id: i64, field: ProfileField, value: &str, admin_id: i64) -> Result<User> {
+        let existing_user = self.find_user(telegram_id).await?
+            .ok_or(SwingBuddyError::UserNotFound { user_id: telegram_id })?;
+
+        let value = validate_profile_value(field, value, &self.settings.get().i18n.supported_languages)?;
+        let mut request = UpdateUserRequest::default();
+        match field {
+            ProfileField::Name => request.first_name = Some(value),
+            ProfileField::City => request.location = Some(self.normalize_location(&value)),
+            ProfileField::Language => request.language_code = Some(value),
+        }
+
+        let user = self.user_repository.update(existing_user.id, request).await?;
+        self.cache.insert(&user);
+        info!(telegram_id = telegram_id, field = field.as_str(), admin_id = admin_id, "User profile corrected by admin");
+
+        Ok(user)
+    }
+
     /// Get banned users
     pub async fn get_banned_users(&self) -> Result<Vec<User>> {
         debug!("Getting banned users");
@@ -251,6 +448,101 @@ impl UserService {
         self.user_repository.list(limit, offset).await
     }
 
+    /// Get a page of the admin user list and the number of pages; pages start at 0
+    pub async fn list_users_page(&self, sort: UserListSort, page: i64) -> Result<(Vec<UserListEntry>, i64)> {
+        debug!(sort = sort.as_str(), page = page, "Listing users for admin");
+
+        let total = self.user_repository.count().await?;
+        let pages = page_count(total, USER_LIST_PAGE_SIZE);
+        let page = page.clamp(0, pages - 1);
+        let users = self.user_repository.list_entries(sort, USER_LIST_PAGE_SIZE, page * USER_LIST_PAGE_SIZE).await?;
+
+        Ok((users, pages))
+    }
+
+    /// Move a user to the trash on behalf of an admin. They drop out of lists and
+    /// broadcasts until restored or until they use the bot again.
+    /// Returns whether there was a user to delete.
+    pub async fn delete_user(&self, telegram_id: i64, admin_id: i64) -> Result<bool> {
+        let Some(user) = self.find_user(telegram_id).await? else {
+            return Ok(false);
+        };
+
+        let deleted = self.user_repository.delete(user.id).await?;
+        self.cache.remove(telegram_id);
+        if deleted {
+            info!(telegram_id = telegram_id, user_id = user.id, admin_id = admin_id, "User moved to trash");
+        }
+        Ok(deleted)
+    }
+
+    /// Take a user out of the trash by database ID
+    pub async fn restore_user(&self, user_id: i64, admin_id: i64) -> Result<Option<User>> {
+        let user = self.user_repository.restore(user_id).await?;
+        if let Some(user) = &user {
+            self.cache.insert(user);
+            info!(telegram_id = user.telegram_id, user_id = user.id, admin_id = admin_id, "User restored from trash");
+        }
+        Ok(user)
+    }
+
+    /// Get a page of deleted users and the number of pages; pages start at 0
+    pub async fn list_deleted_page(&self, page: i64) -> Result<(Vec<TrashEntry>, i64)> {
+        let pages = page_count(self.user_repository.count_deleted().await?, TRASH_PAGE_SIZE);
+        let page = page.clamp(0, pages - 1);
+        let users = self.user_repository.list_deleted(TRASH_PAGE_SIZE, page * TRASH_PAGE_SIZE).await?;
+        Ok((users, pages))
+    }
+
+    /// Remember that a user talked to the bot; failures are only logged
+    pub async fn record_activity(&self, telegram_id: i64) {
+        let now = chrono::Utc::now();
+        let stale_before = now - chrono::Duration::seconds(ACTIVITY_WRITE_INTERVAL_SECONDS);
+        if let Err(e) = self.user_repository.touch_last_active(telegram_id, now, stale_before).await {
+            warn!(telegram_id = telegram_id, error = %e, "Failed to record user activity");
+        }
+    }
+
+    /// Export all users as CSV with the columns allowed by the `[export]` config section
+    pub async fn export_users_csv(&self) -> Result<Vec<u8>> {
+        let users = self.user_repository.list_all().await?;
+        let data = users_to_csv(&users, &self.export_columns())?;
+
+        info!(users = users.len(), "User CSV export created");
+        Ok(data)
+    }
+
+    /// Create or update users from a CSV file, e.g. one exported by another bot.
+    /// Importing the same file again changes nothing.
+    pub async fn import_users_csv(&self, data: &[u8]) -> Result<UserImportReport> {
+        let (users, skipped) = parse_user_import(data, &self.settings.get().i18n.supported_languages)?;
+        let mut report = UserImportReport { skipped, ..Default::default() };
+
+        for user in users {
+            if self.user_repository.upsert(user).await? {
+                report.created += 1;
+            } else {
+                report.updated += 1;
+            }
+        }
+
+        // Imported users may still be cached with their old profile
+        self.cache.clear();
+
+        info!(created = report.created, updated = report.updated, skipped = report.skipped.len(), "Users imported");
+        Ok(report)
+    }
+
+    /// Columns of the user export; unknown names are rejected by the config validation
+    fn export_columns(&self) -> Vec<UserExportColumn> {
+        match &self.settings.get().export {
+            Some(config) => config.user_columns.iter()
+                .filter_map(|column| column.parse().ok())
+                .collect(),
+            None => UserExportColumn::ALL.to_vec(),
+        }
+    }
+
     /// Normalize location input
     fn normalize_location(&self, location: &str) -> String {
         let location_lower = location.trim().to_lowercase();
@@ -290,9 +582,122 @@ impl Default for UpdateUserRequest {
     }
 }
 
+/// Check a profile value entered by an admin and trim it; languages must be supported
+pub fn validate_profile_value(field: ProfileField, value: &str, supported_languages: &[String]) -> Result<String> {
+    let value = value.trim();
+
+    if value.is_empty() {
+        return Err(SwingBuddyError::InvalidInput("The value cannot be empty".to_string()));
+    }
+
+    match field {
+        ProfileField::Name if value.chars().count() > MAX_NAME_LENGTH => {
+            Err(SwingBuddyError::InvalidInput(format!("Name cannot exceed {} characters", MAX_NAME_LENGTH)))
+        }
+        ProfileField::City if value.chars().count() > MAX_CITY_LENGTH => {
+            Err(SwingBuddyError::InvalidInput(format!("City cannot exceed {} characters", MAX_CITY_LENGTH)))
+        }
+        ProfileField::Language => {
+            let language = value.to_lowercase();
+            if supported_languages.contains(&language) {
+                Ok(language)
+            } else {
+                Err(SwingBuddyError::InvalidInput(format!("Language must be one of: {}", supported_languages.join(", "))))
+            }
+        }
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// Number of pages needed for `total` items; an empty list still has one page
+pub fn page_count(total: i64, page_size: i64) -> i64 {
+    ((total + page_size - 1) / page_size).max(1)
+}
+
+/// Read users from an import CSV file. The header names the columns: `telegram_id`
+/// is required; `username`, `first_name`, `last_name`, `name` (used when there is no
+/// `first_name`), `city` and `language` are optional, so files from the user export
+/// can be imported as they are. Returns the users and the line numbers of rows
+/// without a valid Telegram ID.
+pub fn parse_user_import(data: &[u8], supported_languages: &[String]) -> Result<(Vec<CreateUserRequest>, Vec<usize>)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let headers = reader.headers().map_err(std::io::Error::from)?.clone();
+    let column = |names: &[&str]| headers.iter().position(|header| names.iter().any(|name| header.eq_ignore_ascii_case(name)));
+    let Some(telegram_id_column) = column(&["telegram_id"]) else {
+        return Err(SwingBuddyError::InvalidInput("The file has no telegram_id column".to_string()));
+    };
+    let username_column = column(&["username"]);
+    let first_name_column = column(&["first_name"]).or_else(|| column(&["name"]));
+    let last_name_column = column(&["last_name"]);
+    let city_column = column(&["city", "location"]);
+    let language_column = column(&["language", "language_code"]);
+
+    let mut users = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        // The header is line 1
+        let line = index + 2;
+        let Ok(record) = record else {
+            skipped.push(line);
+            continue;
+        };
+        let field = |column: Option<usize>| column
+            .and_then(|column| record.get(column))
+            .filter(|value| !value.is_empty())
+            .map(str::to_string);
+
+        let Some(telegram_id) = field(Some(telegram_id_column)).and_then(|id| id.parse::<i64>().ok()).filter(|id| *id > 0) else {
+            skipped.push(line);
+            continue;
+        };
+
+        users.push(CreateUserRequest {
+            telegram_id,
+            username: field(username_column).map(|username| username.trim_start_matches('@').to_string()),
+            first_name: field(first_name_column),
+            last_name: field(last_name_column),
+            language_code: field(language_column).filter(|language| supported_languages.contains(language)),
+            location: field(city_column),
+        });
+    }
+
+    Ok((users, skipped))
+}
+
+/// Write users as CSV with a header row, keeping only the given columns
+pub fn users_to_csv(users: &[User], columns: &[UserExportColumn]) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(columns.iter().map(UserExportColumn::as_str))
+        .map_err(std::io::Error::from)?;
+
+    for user in users {
+        let record = columns.iter().map(|column| match column {
+            UserExportColumn::TelegramId => user.telegram_id.to_string(),
+            UserExportColumn::Name => [user.first_name.as_deref(), user.last_name.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" "),
+            UserExportColumn::City => user.location.clone().unwrap_or_default(),
+            UserExportColumn::Language => user.language_code.clone(),
+            UserExportColumn::RegisteredAt => user.created_at.to_rfc3339(),
+            UserExportColumn::Banned => user.is_banned.to_string(),
+        });
+        writer.write_record(record).map_err(std::io::Error::from)?;
+    }
+
+    writer.into_inner().map_err(|e| SwingBuddyError::Io(e.into_error()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::settings::Settings;
+    use chrono::{TimeZone, Utc};
 
     #[tokio::test]
     async fn test_normalize_location() {
@@ -301,7 +706,10 @@ mod tests {
         // We only need to test the normalize_location business logic
         let service = UserService {
             user_repository: UserRepository::new_for_testing(),
-            settings,
+            settings: SharedSettings::new(settings),
+            webhook_service: None,
+            activity_log: None,
+            cache: UserCache::default(),
         };
 
         assert_eq!(service.normalize_location("moscow"), "Moscow");
@@ -319,7 +727,10 @@ mod tests {
         // Create a service instance without database connection for unit testing
         let service = UserService {
             user_repository: UserRepository::new_for_testing(),
-            settings,
+            settings: SharedSettings::new(settings),
+            webhook_service: None,
+            activity_log: None,
+            cache: UserCache::default(),
         };
 
         let suggestions = service.get_city_suggestions("mos");
@@ -328,4 +739,120 @@ mod tests {
         let suggestions = service.get_city_suggestions("петер");
         assert!(suggestions.contains(&"Saint Petersburg".to_string()));
     }
-}
\ No newline at end of file
+
+    fn cached_user(telegram_id: i64) -> User {
+        User {
+            id: telegram_id,
+            telegram_id,
+            username: None,
+            first_name: Some("Norma".to_string()),
+            last_name: None,
+            language_code: "en".to_string(),
+            location: None,
+            is_banned: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_user_cache() {
+        let cache = UserCache::new(Duration::from_secs(60), 2);
+        assert!(cache.get(1).is_none());
+
+        cache.insert(&cached_user(1));
+        assert_eq!(cache.get(1).map(|user| user.telegram_id), Some(1));
+
+        // A write replaces the cached copy
+        let mut banned = cached_user(1);
+        banned.is_banned = true;
+        cache.insert(&banned);
+        assert!(cache.get(1).is_some_and(|user| user.is_banned));
+        assert_eq!(cache.len(), 1);
+
+        // A full cache with nothing expired starts over
+        cache.insert(&cached_user(2));
+        cache.insert(&cached_user(3));
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(3).is_some());
+
+        cache.remove(3);
+        assert!(cache.get(3).is_none());
+
+        cache.insert(&cached_user(3));
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_user_cache_expiry() {
+        let cache = UserCache::new(Duration::ZERO, 10);
+        cache.insert(&cached_user(1));
+        assert!(cache.get(1).is_none());
+    }
+
+    #[test]
+    fn test_users_to_csv() {
+        let registered = Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+        let user = User {
+            id: 1,
+            telegram_id: 42,
+            username: Some("frankie".to_string()),
+            first_name: Some("Frankie".to_string()),
+            last_name: Some("Manning, Jr.".to_string()),
+            language_code: "en".to_string(),
+            location: Some("Berlin".to_string()),
+            is_banned: false,
+            created_at: registered,
+            updated_at: registered,
+        };
+
+        let csv = users_to_csv(&[user], &[UserExportColumn::TelegramId, UserExportColumn::Name, UserExportColumn::RegisteredAt]).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "telegram_id,name,registered_at\n42,\"Frankie Manning, Jr.\",2024-03-15T12:00:00+00:00\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_user_import() {
+        let data = "Telegram_ID,name,city,language,username\n42,Frankie,Berlin,en,@frankie\nnot-a-number,Norma\n43,,,de\n";
+        let languages = vec!["en".to_string(), "ru".to_string()];
+
+        let (users, skipped) = parse_user_import(data.as_bytes(), &languages).unwrap();
+
+        assert_eq!(skipped, vec![3]);
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].telegram_id, 42);
+        assert_eq!(users[0].first_name.as_deref(), Some("Frankie"));
+        assert_eq!(users[0].username.as_deref(), Some("frankie"));
+        assert_eq!(users[0].location.as_deref(), Some("Berlin"));
+        assert_eq!(users[0].language_code.as_deref(), Some("en"));
+        assert_eq!(users[1].telegram_id, 43);
+        assert_eq!(users[1].first_name, None);
+        assert_eq!(users[1].language_code, None);
+
+        assert!(parse_user_import(b"name\nFrankie\n", &languages).is_err());
+    }
+
+    #[test]
+    fn test_page_count() {
+        assert_eq!(page_count(0, 10), 1);
+        assert_eq!(page_count(10, 10), 1);
+        assert_eq!(page_count(11, 10), 2);
+        assert_eq!(page_count(95, 10), 10);
+    }
+
+    #[test]
+    fn test_validate_profile_value() {
+        let languages = vec!["en".to_string(), "ru".to_string()];
+
+        assert_eq!(validate_profile_value(ProfileField::Name, "  Anna ", &languages).unwrap(), "Anna");
+        assert!(validate_profile_value(ProfileField::Name, "   ", &languages).is_err());
+        assert!(validate_profile_value(ProfileField::Name, &"a".repeat(65), &languages).is_err());
+        assert_eq!(validate_profile_value(ProfileField::City, "Moscow", &languages).unwrap(), "Moscow");
+        assert_eq!(validate_profile_value(ProfileField::Language, "RU", &languages).unwrap(), "ru");
+        assert!(validate_profile_value(ProfileField::Language, "fr", &languages).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+//! Venue service implementation
+//!
+//! Keeps the directory of dance venues per city and finds the upcoming events
+//! at each venue, by their location mentioning the venue's name.
+
+use tracing::info;
+use crate::database::repositories::{VenueRepository, EventRepository};
+use crate::models::event::Event;
+use crate::models::venue::{Venue, CreateVenueRequest};
+use crate::utils::errors::Result;
+
+/// Upcoming events listed under each venue
+pub const EVENTS_PER_VENUE: i64 = 3;
+
+/// Venue directory service
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct VenueService {
+    venue_repository: VenueRepository,
+    event_repository: EventRepository,
+}
+
+impl VenueService {
+    /// Create a new VenueService instance
+    pub fn new(venue_repository: VenueRepository, event_repository: EventRepository) -> Self {
+        Self { venue_repository, event_repository }
+    }
+
+    /// Get the venues of a city, or of all cities, each with its next events
+    pub async fn list_venues(&self, city: Option<&str>) -> Result<Vec<(Venue, Vec<Event>)>> {
+        let venues = self.venue_repository.list(city).await?;
+
+        let mut listed = Vec::with_capacity(venues.len());
+        for venue in venues {
+            let events = self.event_repository.get_upcoming_events_at(&venue.name, EVENTS_PER_VENUE).await?;
+            listed.push((venue, events));
+        }
+        Ok(listed)
+    }
+
+    /// Add a venue to the directory, or update the one with the same name in the city
+    pub async fn save_venue(&self, request: CreateVenueRequest) -> Result<Venue> {
+        let venue = self.venue_repository.upsert(request).await?;
+        info!(venue_id = venue.id, city = %venue.city, "Venue saved");
+        Ok(venue)
+    }
+
+    /// Take a venue out of the directory
+    pub async fn remove_venue(&self, venue_id: i64) -> Result<Option<Venue>> {
+        let venue = self.venue_repository.delete(venue_id).await?;
+        if venue.is_some() {
+            info!(venue_id = venue_id, "Venue removed");
+        }
+        Ok(venue)
+    }
+}
@@ -0,0 +1,167 @@
+//! Mini App service implementation
+//!
+//! The Telegram Mini App opened from /events lists the upcoming events with
+//! their details and spots left, and shows which ones the user is registered
+//! for. The page proves who is looking with the init data Telegram hands it,
+//! signed with the bot token. Registrations don't go through the HTTP server:
+//! the page sends them back to the chat as `web_app_data`, which the bot
+//! handles like a button press.
+
+use std::collections::{BTreeMap, HashSet};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use crate::config::settings::SharedSettings;
+use crate::database::repositories::{EventRepository, UserRepository};
+use crate::services::public_api::{ApiEvent, PublicApiService};
+use crate::utils::errors::Result;
+
+/// Init data older than this is refused, so a leaked one can't be replayed forever
+pub const INIT_DATA_MAX_AGE_SECONDS: i64 = 24 * 60 * 60;
+
+/// Events listed in the Mini App
+pub const WEBAPP_EVENT_LIMIT: i64 = 100;
+
+/// An event as the Mini App shows it to one user
+#[derive(Debug, Clone, Serialize)]
+pub struct WebAppEvent {
+    #[serde(flatten)]
+    pub event: ApiEvent,
+    pub registered: bool,
+}
+
+/// What the Mini App sends back to the chat with `Telegram.WebApp.sendData`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum WebAppAction {
+    Register { event_id: i64 },
+    Unregister { event_id: i64 },
+}
+
+impl WebAppAction {
+    /// Parse the data of a `web_app_data` message
+    pub fn parse(data: &str) -> Option<Self> {
+        serde_json::from_str(data).ok()
+    }
+}
+
+/// The part of the init data's `user` field we need
+#[derive(Deserialize)]
+struct InitDataUser {
+    id: i64,
+}
+
+/// Mini App service
+#[derive(Debug, Clone)]
+pub struct WebAppService {
+    public_api_service: PublicApiService,
+    event_repository: EventRepository,
+    user_repository: UserRepository,
+    settings: SharedSettings,
+}
+
+impl WebAppService {
+    /// Create a new WebAppService instance
+    pub fn new(
+        public_api_service: PublicApiService,
+        event_repository: EventRepository,
+        user_repository: UserRepository,
+        settings: SharedSettings,
+    ) -> Self {
+        Self {
+            public_api_service,
+            event_repository,
+            user_repository,
+            settings,
+        }
+    }
+
+    /// Public URL of the Mini App, if one is configured
+    pub fn url(&self) -> Option<String> {
+        self.settings.get().webapp.as_ref().map(|webapp| webapp.url.clone())
+    }
+
+    /// Telegram ID of the user the init data was issued to, if it is signed
+    /// with our bot token and recent enough
+    pub fn authenticate(&self, init_data: &str) -> Option<i64> {
+        let token = self.settings.get().bot.token.clone();
+        verify_init_data(&token, init_data, Utc::now())
+    }
+
+    /// Upcoming events, marked with whether the user is registered
+    pub async fn events(&self, telegram_id: i64) -> Result<Vec<WebAppEvent>> {
+        let events = self.public_api_service.upcoming_events(Some(WEBAPP_EVENT_LIMIT)).await?;
+        let registered: HashSet<i64> = match self.user_repository.find_by_telegram_id(telegram_id).await? {
+            Some(user) => self.event_repository.get_user_registered_events(user.id).await?
+                .into_iter()
+                .map(|event| event.id)
+                .collect(),
+            None => HashSet::new(),
+        };
+
+        Ok(events
+            .into_iter()
+            .map(|event| WebAppEvent { registered: registered.contains(&event.id), event })
+            .collect())
+    }
+}
+
+/// Check Mini App init data as Telegram describes: the `hash` field is the
+/// HMAC-SHA256 of the other fields, sorted and joined by newlines, keyed with
+/// the HMAC-SHA256 of the bot token under "WebAppData". Gives the ID of the user.
+pub fn verify_init_data(bot_token: &str, init_data: &str, now: DateTime<Utc>) -> Option<i64> {
+    let mut fields: BTreeMap<String, String> = url::form_urlencoded::parse(init_data.as_bytes())
+        .into_owned()
+        .collect();
+    let hash = hex::decode(fields.remove("hash")?).ok()?;
+
+    let data_check_string = fields.iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut secret = Hmac::<Sha256>::new_from_slice(b"WebAppData").expect("HMAC accepts keys of any length");
+    secret.update(bot_token.as_bytes());
+    let mut mac = Hmac::<Sha256>::new_from_slice(&secret.finalize().into_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(data_check_string.as_bytes());
+    mac.verify_slice(&hash).ok()?;
+
+    let auth_date: i64 = fields.get("auth_date")?.parse().ok()?;
+    if now.timestamp() - auth_date > INIT_DATA_MAX_AGE_SECONDS {
+        return None;
+    }
+
+    let user: InitDataUser = serde_json::from_str(fields.get("user")?).ok()?;
+    Some(user.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const TOKEN: &str = "123456:TEST-TOKEN";
+    // Signed with TOKEN, see verify_init_data
+    const INIT_DATA: &str = "auth_date=1700000000&query_id=AAH&user=%7B%22id%22%3A42%2C%22first_name%22%3A%22Ann%22%7D&hash=1fd9029f12533d000aa7dc5b57e3a899b022ee27501130cd7a18116ac89b8b5b";
+
+    #[test]
+    fn test_verify_init_data() {
+        let now = Utc.timestamp_opt(1_700_000_100, 0).unwrap();
+        assert_eq!(verify_init_data(TOKEN, INIT_DATA, now), Some(42));
+        assert_eq!(verify_init_data("654321:OTHER-TOKEN", INIT_DATA, now), None);
+        assert_eq!(verify_init_data(TOKEN, &INIT_DATA.replace("%3A42", "%3A43"), now), None);
+        assert_eq!(verify_init_data(TOKEN, "auth_date=1700000000&user=%7B%22id%22%3A42%7D", now), None);
+
+        let later = Utc.timestamp_opt(1_700_000_000 + INIT_DATA_MAX_AGE_SECONDS + 1, 0).unwrap();
+        assert_eq!(verify_init_data(TOKEN, INIT_DATA, later), None);
+    }
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!(WebAppAction::parse(r#"{"action":"register","event_id":42}"#), Some(WebAppAction::Register { event_id: 42 }));
+        assert_eq!(WebAppAction::parse(r#"{"action":"unregister","event_id":7}"#), Some(WebAppAction::Unregister { event_id: 7 }));
+        assert_eq!(WebAppAction::parse(r#"{"action":"delete","event_id":7}"#), None);
+        assert_eq!(WebAppAction::parse("register:42"), None);
+    }
+}
@@ -0,0 +1,76 @@
+//! Conversation state backends
+//!
+//! [`StateStorage`](super::StateStorage) handles expiry, scopes, statistics and
+//! backups the same way whatever keeps the contexts; a backend only stores each
+//! context under its scope until its TTL runs out. Redis is the default,
+//! PostgreSQL serves deployments without Redis.
+
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::utils::errors::Result;
+use super::context::{ConversationContext, ContextScope};
+
+/// Where a walk of the stored scopes goes on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScopeCursor {
+    /// The beginning, or the end of a finished walk
+    #[default]
+    Start,
+    /// A Redis `SCAN` cursor
+    Scan(u64),
+    /// The scopes after this one, in key order
+    After(ContextScope),
+}
+
+/// Where conversation contexts are kept
+#[async_trait]
+pub trait StateBackend: Send + Sync {
+    /// Backend name, as in `[state] backend`
+    fn name(&self) -> &'static str;
+
+    /// Store a context under its scope for `ttl`. Unless `expected_version` is 0
+    /// the stored context is only replaced if it has that version; returns
+    /// `false` without storing anything if another version, or none, is stored.
+    async fn put(&self, context: &ConversationContext, expected_version: u64, ttl: Duration) -> Result<bool>;
+
+    /// Get the context stored under a scope, unless its TTL ran out
+    async fn get(&self, scope: ContextScope) -> Result<Option<ConversationContext>>;
+
+    /// Remove the context stored under a scope. Returns whether there was one.
+    async fn delete(&self, scope: ContextScope) -> Result<bool>;
+
+    /// Whether a context is stored under a scope
+    async fn exists(&self, scope: ContextScope) -> Result<bool>;
+
+    /// Keep a context `additional` longer. Returns whether it was stored.
+    async fn extend(&self, scope: ContextScope, additional: Duration) -> Result<bool>;
+
+    /// A page of the scopes with a stored context, about `count` of them, so
+    /// large stores are walked without blocking the backend. Start at
+    /// [`ScopeCursor::Start`]; the walk is over when it is returned again. A
+    /// scope may turn up on more than one page.
+    async fn scopes_page(&self, cursor: ScopeCursor, count: usize) -> Result<(Vec<ContextScope>, ScopeCursor)>;
+
+    /// Delete contexts whose TTL ran out, for backends that don't drop them
+    /// on their own. Returns how many were deleted.
+    async fn purge_expired(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// Whether contexts are periodically purged, rather than expiring on their own
+    fn needs_cleanup(&self) -> bool {
+        false
+    }
+
+    /// Whether contexts are kept with weaker guarantees than usual, e.g. in
+    /// memory during an outage
+    fn is_degraded(&self) -> bool {
+        false
+    }
+
+    /// Check that the backend can be reached
+    async fn ping(&self) -> Result<()>;
+
+    /// Version of the server keeping the contexts
+    async fn server_version(&self) -> Result<String>;
+}
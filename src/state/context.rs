@@ -77,10 +77,10 @@ impl AppContext {
     /// Create from ServiceFactory and DatabaseService
     pub async fn from_factory(factory: ServiceFactory, database: Arc<DatabaseService>, settings: Settings) -> Result<Self> {
         // Create scenario manager
-        let scenario_manager = Arc::new(ScenarioManager::new());
+        let scenario_manager = Arc::new(ScenarioManager::load(settings.scenarios.as_ref())?);
         
         // Create state storage from settings (async)
-        let state_storage = Arc::new(StateStorage::new(settings.redis.clone()).await?);
+        let state_storage = Arc::new(StateStorage::from_settings(&settings, database.pool.clone()).await?);
         
         // Create I18n from settings and load translations
         let mut i18n_loader = crate::i18n::I18n::new(&settings.i18n);
@@ -107,21 +107,59 @@ impl AppContext {
     }
 }
 
+/// Steps kept for going back with /back; older ones are forgotten
+const MAX_STEP_HISTORY: usize = 20;
+
+/// Paused scenarios kept per conversation; the oldest is dropped beyond this
+const MAX_PAUSED_SCENARIOS: usize = 5;
+
+/// What a context is stored under: the user and the group chat it is scoped
+/// to, or `None` for the user's private conversation with the bot
+pub type ContextScope = (i64, Option<i64>);
+
 /// User conversation context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationContext {
     /// User ID this context belongs to
     pub user_id: i64,
+    /// Group chat this context is scoped to; `None` for the private chat
+    #[serde(default)]
+    pub chat_id: Option<i64>,
     /// Current scenario the user is in
     pub scenario: Option<String>,
     /// Current step within the scenario
     pub step: Option<String>,
+    /// Earlier steps of the current scenario, most recent last
+    #[serde(default)]
+    pub history: Vec<String>,
     /// Scenario-specific data
     pub data: HashMap<String, serde_json::Value>,
     /// When this context expires (for cleanup)
     pub expires_at: Option<DateTime<Utc>>,
+    /// When the user was reminded of the unfinished step; cleared when they move on
+    #[serde(default)]
+    pub reminded_at: Option<DateTime<Utc>>,
     /// When this context was last updated
     pub updated_at: DateTime<Utc>,
+    /// Number of times this context was saved; 0 until its first save. A save
+    /// is rejected when another one got in since the context was loaded.
+    #[serde(default)]
+    pub version: u64,
+    /// Scenarios put aside for an interrupting command, most recent last
+    #[serde(default)]
+    pub paused: Vec<PausedScenario>,
+}
+
+/// A scenario put aside while the user does something else, to be resumed
+/// at the step they left it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PausedScenario {
+    pub scenario: String,
+    pub step: String,
+    #[serde(default)]
+    pub history: Vec<String>,
+    pub data: HashMap<String, serde_json::Value>,
+    pub paused_at: DateTime<Utc>,
 }
 
 impl ConversationContext {
@@ -129,19 +167,37 @@ impl ConversationContext {
     pub fn new(user_id: i64) -> Self {
         Self {
             user_id,
+            chat_id: None,
             scenario: None,
             step: None,
+            history: Vec::new(),
             data: HashMap::new(),
             expires_at: None,
+            reminded_at: None,
             updated_at: Utc::now(),
+            version: 0,
+            paused: Vec::new(),
         }
     }
 
+    /// Create a context for a user's conversation in a group chat, kept apart
+    /// from their private conversation and from their contexts in other groups
+    pub fn for_chat(user_id: i64, chat_id: i64) -> Self {
+        Self { chat_id: Some(chat_id), ..Self::new(user_id) }
+    }
+
+    /// What this context is stored under
+    pub fn scope(&self) -> ContextScope {
+        (self.user_id, self.chat_id)
+    }
+
     /// Start a new scenario
     pub fn start_scenario(&mut self, scenario: &str, initial_step: &str) -> Result<()> {
         self.scenario = Some(scenario.to_string());
         self.step = Some(initial_step.to_string());
+        self.history.clear();
         self.data.clear();
+        self.reminded_at = None;
         self.updated_at = Utc::now();
         self.expires_at = Some(Utc::now() + Duration::hours(24)); // Default 24h expiry
         Ok(())
@@ -156,17 +212,75 @@ impl ConversationContext {
             });
         }
         
-        self.step = Some(step.to_string());
+        if let Some(previous) = self.step.replace(step.to_string()).filter(|previous| previous != step) {
+            if self.history.len() == MAX_STEP_HISTORY {
+                self.history.remove(0);
+            }
+            self.history.push(previous);
+        }
+        self.reminded_at = None;
         self.updated_at = Utc::now();
         Ok(())
     }
 
-    /// Complete the current scenario and clear context
+    /// Return to the previous step of the scenario, if there is one
+    pub fn step_back(&mut self) -> Option<&str> {
+        let previous = self.history.pop()?;
+        self.step = Some(previous);
+        self.updated_at = Utc::now();
+        self.step.as_deref()
+    }
+
+    /// Put the current scenario aside, with its step and data, so another one
+    /// can run. Returns whether there was a scenario to pause.
+    pub fn pause_scenario(&mut self) -> bool {
+        let (Some(scenario), Some(step)) = (self.scenario.clone(), self.step.clone()) else {
+            return false;
+        };
+
+        if self.paused.len() == MAX_PAUSED_SCENARIOS {
+            self.paused.remove(0);
+        }
+        self.paused.push(PausedScenario {
+            scenario,
+            step,
+            history: std::mem::take(&mut self.history),
+            data: std::mem::take(&mut self.data),
+            paused_at: Utc::now(),
+        });
+        self.complete_scenario();
+        true
+    }
+
+    /// Continue the most recently paused scenario where it was left; a scenario
+    /// in progress is paused in its place. Returns the resumed scenario.
+    pub fn resume_scenario(&mut self) -> Option<&str> {
+        let resumed = self.paused.pop()?;
+        self.pause_scenario();
+
+        self.scenario = Some(resumed.scenario);
+        self.step = Some(resumed.step);
+        self.history = resumed.history;
+        self.data = resumed.data;
+        self.updated_at = Utc::now();
+        self.scenario.as_deref()
+    }
+
+    /// Drop the most recently paused scenario
+    pub fn discard_paused(&mut self) -> Option<PausedScenario> {
+        let discarded = self.paused.pop();
+        self.updated_at = Utc::now();
+        discarded
+    }
+
+    /// Complete the current scenario and clear context; paused scenarios stay
     pub fn complete_scenario(&mut self) {
         self.scenario = None;
         self.step = None;
+        self.history.clear();
         self.data.clear();
         self.expires_at = None;
+        self.reminded_at = None;
         self.updated_at = Utc::now();
     }
 
@@ -214,6 +328,13 @@ impl ConversationContext {
         self.data.remove(key)
     }
 
+    /// Whether the context expires within `window` and the user was not reminded yet
+    pub fn needs_reminder(&self, window: Duration) -> bool {
+        self.scenario.is_some()
+            && self.reminded_at.is_none()
+            && self.expires_at.is_some_and(|expires_at| expires_at > Utc::now() && expires_at - Utc::now() <= window)
+    }
+
     /// Check if context has expired
     pub fn is_expired(&self) -> bool {
         if let Some(expires_at) = self.expires_at {
@@ -400,6 +521,44 @@ mod tests {
         assert!(context.step.is_none());
         assert!(context.data.is_empty());
         assert!(context.expires_at.is_none());
+        assert_eq!(context.scope(), (123, None));
+    }
+
+    #[test]
+    fn test_chat_context() {
+        let context = ConversationContext::for_chat(123, -100);
+        assert_eq!(context.scope(), (123, Some(-100)));
+
+        // Contexts saved before scoping belong to the private chat
+        let json = r#"{"user_id":123,"scenario":null,"step":null,"data":{},"expires_at":null,"updated_at":"2024-01-01T00:00:00Z"}"#;
+        let context: ConversationContext = serde_json::from_str(json).unwrap();
+        assert_eq!(context.chat_id, None);
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let mut context = ConversationContext::new(123);
+        assert!(!context.pause_scenario());
+
+        context.start_scenario("event_creation", "title_input").unwrap();
+        context.set_data("title", "Friday Social").unwrap();
+        context.next_step("description_input").unwrap();
+        assert!(context.pause_scenario());
+        assert!(context.scenario.is_none());
+        assert!(context.data.is_empty());
+
+        // A scenario in progress swaps places with the resumed one
+        context.start_scenario("broadcast", "message_input").unwrap();
+        assert_eq!(context.resume_scenario(), Some("event_creation"));
+        assert!(context.is_at_step("description_input"));
+        assert_eq!(context.history, vec!["title_input".to_string()]);
+        assert_eq!(context.get_string("title").as_deref(), Some("Friday Social"));
+        assert_eq!(context.paused.len(), 1);
+        assert_eq!(context.paused[0].scenario, "broadcast");
+
+        context.complete_scenario();
+        assert_eq!(context.discard_paused().map(|paused| paused.scenario).as_deref(), Some("broadcast"));
+        assert!(context.resume_scenario().is_none());
     }
 
     #[test]
@@ -447,6 +606,33 @@ mod tests {
         assert!(!context.is_at("onboarding", "name_input"));
     }
 
+    #[test]
+    fn test_step_history() {
+        let mut context = ConversationContext::new(123);
+        context.start_scenario("onboarding", "language_selection").unwrap();
+        assert!(context.step_back().is_none());
+
+        context.next_step("name_input").unwrap();
+        context.next_step("name_input").unwrap();
+        context.next_step("location_input").unwrap();
+        assert_eq!(context.history, vec!["language_selection".to_string(), "name_input".to_string()]);
+
+        assert_eq!(context.step_back(), Some("name_input"));
+        assert_eq!(context.step_back(), Some("language_selection"));
+        assert!(context.step_back().is_none());
+        assert!(context.is_at_step("language_selection"));
+
+        for _ in 0..MAX_STEP_HISTORY + 5 {
+            context.next_step("a").unwrap();
+            context.next_step("b").unwrap();
+        }
+        assert_eq!(context.history.len(), MAX_STEP_HISTORY);
+
+        // Contexts saved before the history existed still load
+        let json = r#"{"user_id":1,"scenario":null,"step":null,"data":{},"expires_at":null,"updated_at":"2024-01-01T00:00:00Z"}"#;
+        assert!(serde_json::from_str::<ConversationContext>(json).unwrap().history.is_empty());
+    }
+
     #[test]
     fn test_expiry() {
         let mut context = ConversationContext::new(123);
@@ -464,6 +650,26 @@ mod tests {
         assert!(!context.is_expired());
     }
 
+    #[test]
+    fn test_needs_reminder() {
+        let mut context = ConversationContext::new(123);
+        context.start_scenario("onboarding", "name_input").unwrap();
+        context.set_expiry(Utc::now() + Duration::minutes(5));
+        assert!(context.needs_reminder(Duration::minutes(10)));
+        assert!(!context.needs_reminder(Duration::minutes(1)));
+
+        // One reminder per step
+        context.reminded_at = Some(Utc::now());
+        assert!(!context.needs_reminder(Duration::minutes(10)));
+        context.next_step("location_input").unwrap();
+        assert!(context.needs_reminder(Duration::minutes(10)));
+
+        context.set_expiry(Utc::now() - Duration::minutes(1));
+        assert!(!context.needs_reminder(Duration::minutes(10)));
+        context.complete_scenario();
+        assert!(!context.needs_reminder(Duration::minutes(10)));
+    }
+
     #[test]
     fn test_validation() {
         let context = ConversationContext::new(123);
@@ -0,0 +1,209 @@
+//! In-process fallback for conversation state
+//!
+//! Used by `StateStorage` while Redis is unreachable, so conversations keep
+//! working through short outages. Contexts are kept for a shorter time than in
+//! Redis, are lost on restart and are not shared between bot instances; the
+//! least recently used ones are dropped once the store is full.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use super::context::{ConversationContext, ContextScope};
+
+/// Contexts kept in memory at most
+pub const MEMORY_STORE_CAPACITY: usize = 10_000;
+
+/// Longest time a context is kept in memory, whatever its own expiry
+pub const MEMORY_STORE_MAX_TTL: Duration = Duration::from_secs(15 * 60);
+
+struct Entry {
+    context: ConversationContext,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<ContextScope, Entry>,
+    /// Incremented on every access; orders the entries by recent use
+    clock: u64,
+}
+
+/// Bounded least-recently-used store of conversation contexts
+pub struct MemoryStateStore {
+    capacity: usize,
+    max_ttl: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl MemoryStateStore {
+    /// Create a store holding up to `capacity` contexts for at most `max_ttl` each
+    pub fn new(capacity: usize, max_ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            max_ttl,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Store a context for `ttl`, capped at the store's maximum
+    pub fn insert(&self, context: ConversationContext, ttl: Duration) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        inner.entries.retain(|_, entry| entry.expires_at > now);
+
+        let scope = context.scope();
+        if inner.entries.len() >= self.capacity && !inner.entries.contains_key(&scope) {
+            let oldest = inner.entries.iter().min_by_key(|(_, entry)| entry.last_used).map(|(&scope, _)| scope);
+            if let Some(scope) = oldest {
+                inner.entries.remove(&scope);
+            }
+        }
+
+        inner.clock += 1;
+        let last_used = inner.clock;
+        inner.entries.insert(scope, Entry {
+            context,
+            expires_at: now + ttl.min(self.max_ttl),
+            last_used,
+        });
+    }
+
+    /// Get a context that has not expired
+    pub fn get(&self, scope: ContextScope) -> Option<ConversationContext> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        match inner.entries.get_mut(&scope) {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                entry.last_used = clock;
+                Some(entry.context.clone())
+            }
+            Some(_) => {
+                inner.entries.remove(&scope);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Remove a context. Returns it if it was stored and not expired.
+    pub fn remove(&self, scope: ContextScope) -> Option<ConversationContext> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.entries.remove(&scope)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.context)
+    }
+
+    /// Keep a context longer, up to the store's maximum from now. Returns whether it was stored.
+    pub fn extend(&self, scope: ContextScope, additional: Duration) -> bool {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let max_ttl = self.max_ttl;
+
+        match inner.entries.get_mut(&scope) {
+            Some(entry) if entry.expires_at > now => {
+                entry.expires_at = (entry.expires_at + additional).min(now + max_ttl);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Scopes with a stored context that has not expired
+    pub fn scopes(&self) -> Vec<ContextScope> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        inner.entries.iter()
+            .filter(|(_, entry)| entry.expires_at > now)
+            .map(|(&scope, _)| scope)
+            .collect()
+    }
+
+    /// Number of stored contexts, including expired ones not dropped yet
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).entries.len()
+    }
+
+    /// Whether no contexts are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for MemoryStateStore {
+    fn default() -> Self {
+        Self::new(MEMORY_STORE_CAPACITY, MEMORY_STORE_MAX_TTL)
+    }
+}
+
+impl std::fmt::Debug for MemoryStateStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryStateStore")
+            .field("capacity", &self.capacity)
+            .field("max_ttl", &self.max_ttl)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get_remove() {
+        let store = MemoryStateStore::default();
+        store.insert(ConversationContext::new(1), Duration::from_secs(60));
+
+        assert_eq!(store.get((1, None)).map(|context| context.user_id), Some(1));
+        assert!(store.get((2, None)).is_none());
+        assert_eq!(store.scopes(), vec![(1, None)]);
+
+        assert!(store.remove((1, None)).is_some());
+        assert!(store.get((1, None)).is_none());
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_chat_contexts_are_separate() {
+        let store = MemoryStateStore::default();
+        store.insert(ConversationContext::new(1), Duration::from_secs(60));
+        store.insert(ConversationContext::for_chat(1, -100), Duration::from_secs(60));
+
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get((1, Some(-100))).and_then(|context| context.chat_id), Some(-100));
+        assert!(store.get((1, Some(-200))).is_none());
+
+        store.remove((1, Some(-100)));
+        assert!(store.get((1, None)).is_some());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let store = MemoryStateStore::new(2, Duration::from_secs(60));
+        store.insert(ConversationContext::new(1), Duration::from_secs(60));
+        store.insert(ConversationContext::new(2), Duration::from_secs(60));
+
+        // Using 1 makes 2 the least recently used
+        store.get((1, None));
+        store.insert(ConversationContext::new(3), Duration::from_secs(60));
+
+        assert!(store.get((1, None)).is_some());
+        assert!(store.get((2, None)).is_none());
+        assert!(store.get((3, None)).is_some());
+    }
+
+    #[test]
+    fn test_ttl_is_capped() {
+        let store = MemoryStateStore::new(10, Duration::ZERO);
+        store.insert(ConversationContext::new(1), Duration::from_secs(3600));
+        assert!(store.get((1, None)).is_none());
+
+        let store = MemoryStateStore::new(10, Duration::from_secs(60));
+        store.insert(ConversationContext::new(1), Duration::from_secs(60));
+        assert!(store.extend((1, None), Duration::from_secs(3600)));
+        assert!(!store.extend((2, None), Duration::from_secs(60)));
+    }
+}
@@ -2,11 +2,21 @@
 //! 
 //! This module handles conversation state and user context
 
+pub mod backend;
 pub mod context;
+pub mod memory;
+pub mod postgres;
 pub mod scenarios;
+pub mod simulator;
 pub mod storage;
+pub mod wizard;
 
 // Re-export commonly used state components
-pub use context::{AppContext, ConversationContext};
-pub use scenarios::{Scenario, ScenarioManager, ScenarioStep, StepValidation, InputType};
-pub use storage::{StateStorage, StateStorageManager, StorageStats, ConnectionInfo};
\ No newline at end of file
+pub use context::{AppContext, ConversationContext, ContextScope, PausedScenario};
+pub use backend::{ScopeCursor, StateBackend};
+pub use memory::MemoryStateStore;
+pub use postgres::PostgresStateBackend;
+pub use scenarios::{Scenario, ScenarioManager, ScenarioStep, StepCondition, StepValidation, InputType, MediaItem, MediaKind};
+pub use simulator::ScenarioSimulator;
+pub use storage::{StateStorage, StateStorageManager, StorageStats, ConnectionInfo};
+pub use wizard::{Wizard, WizardAction, WizardOutcome, WizardScreen};
\ No newline at end of file
@@ -0,0 +1,191 @@
+//! PostgreSQL backend for conversation state
+//!
+//! For deployments without Redis (`[state] backend = "postgres"`). Contexts
+//! are rows of the `conversation_contexts` table; rows whose TTL ran out are
+//! ignored by reads and deleted every `[state] cleanup_interval_seconds`.
+
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::PgPool;
+use sqlx::types::Json;
+use crate::utils::errors::Result;
+use super::backend::{ScopeCursor, StateBackend};
+use super::context::{ConversationContext, ContextScope};
+
+/// `chat_id` of private chat contexts; Telegram never uses 0 as a chat ID
+const PRIVATE_CHAT: i64 = 0;
+
+/// Conversation contexts kept in PostgreSQL
+#[derive(Debug, Clone)]
+pub struct PostgresStateBackend {
+    pool: PgPool,
+}
+
+impl PostgresStateBackend {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl StateBackend for PostgresStateBackend {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn put(&self, context: &ConversationContext, expected_version: u64, ttl: Duration) -> Result<bool> {
+        let expires_at = Utc::now() + chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
+
+        let result = if expected_version == 0 {
+            sqlx::query(
+                r#"
+                INSERT INTO conversation_contexts (user_id, chat_id, context, expires_at, updated_at)
+                VALUES ($1, $2, $3, $4, NOW())
+                ON CONFLICT (user_id, chat_id) DO UPDATE
+                SET context = EXCLUDED.context, expires_at = EXCLUDED.expires_at, updated_at = NOW()
+                "#
+            )
+            .bind(context.user_id)
+            .bind(context.chat_id.unwrap_or(PRIVATE_CHAT))
+            .bind(Json(context))
+            .bind(expires_at)
+            .execute(&self.pool)
+            .await?
+        } else {
+            // Only replace the version the caller loaded
+            sqlx::query(
+                r#"
+                UPDATE conversation_contexts
+                SET context = $3, expires_at = $4, updated_at = NOW()
+                WHERE user_id = $1 AND chat_id = $2 AND expires_at > NOW()
+                  AND COALESCE((context->>'version')::BIGINT, 0) = $5
+                "#
+            )
+            .bind(context.user_id)
+            .bind(context.chat_id.unwrap_or(PRIVATE_CHAT))
+            .bind(Json(context))
+            .bind(expires_at)
+            .bind(expected_version as i64)
+            .execute(&self.pool)
+            .await?
+        };
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get(&self, (user_id, chat_id): ContextScope) -> Result<Option<ConversationContext>> {
+        let context = sqlx::query_scalar::<_, Json<ConversationContext>>(
+            r#"
+            SELECT context FROM conversation_contexts
+            WHERE user_id = $1 AND chat_id = $2 AND expires_at > NOW()
+            "#
+        )
+        .bind(user_id)
+        .bind(chat_id.unwrap_or(PRIVATE_CHAT))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(context.map(|Json(context)| context))
+    }
+
+    async fn delete(&self, (user_id, chat_id): ContextScope) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM conversation_contexts WHERE user_id = $1 AND chat_id = $2")
+            .bind(user_id)
+            .bind(chat_id.unwrap_or(PRIVATE_CHAT))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn exists(&self, (user_id, chat_id): ContextScope) -> Result<bool> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM conversation_contexts
+                WHERE user_id = $1 AND chat_id = $2 AND expires_at > NOW()
+            )
+            "#
+        )
+        .bind(user_id)
+        .bind(chat_id.unwrap_or(PRIVATE_CHAT))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    async fn extend(&self, (user_id, chat_id): ContextScope, additional: Duration) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE conversation_contexts
+            SET expires_at = expires_at + make_interval(secs => $3)
+            WHERE user_id = $1 AND chat_id = $2 AND expires_at > NOW()
+            "#
+        )
+        .bind(user_id)
+        .bind(chat_id.unwrap_or(PRIVATE_CHAT))
+        .bind(additional.as_secs_f64())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn scopes_page(&self, cursor: ScopeCursor, count: usize) -> Result<(Vec<ContextScope>, ScopeCursor)> {
+        // Walk the primary key from the last scope of the previous page, so a
+        // page costs the same however far the walk got
+        let (after_user_id, after_chat_id) = match cursor {
+            ScopeCursor::After((user_id, chat_id)) => (user_id, chat_id.unwrap_or(PRIVATE_CHAT)),
+            ScopeCursor::Start | ScopeCursor::Scan(_) => (i64::MIN, i64::MIN),
+        };
+        let rows = sqlx::query_as::<_, (i64, i64)>(
+            r#"
+            SELECT user_id, chat_id FROM conversation_contexts
+            WHERE (user_id, chat_id) > ($1, $2) AND expires_at > NOW()
+            ORDER BY user_id, chat_id
+            LIMIT $3
+            "#
+        )
+        .bind(after_user_id)
+        .bind(after_chat_id)
+        .bind(count as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let scopes: Vec<ContextScope> = rows.into_iter()
+            .map(|(user_id, chat_id)| (user_id, (chat_id != PRIVATE_CHAT).then_some(chat_id)))
+            .collect();
+        let next = match scopes.last() {
+            Some(&last) if scopes.len() >= count => ScopeCursor::After(last),
+            _ => ScopeCursor::Start,
+        };
+        Ok((scopes, next))
+    }
+
+    async fn purge_expired(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM conversation_contexts WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    fn needs_cleanup(&self) -> bool {
+        true
+    }
+
+    async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn server_version(&self) -> Result<String> {
+        let version = sqlx::query_scalar::<_, String>("SHOW server_version")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(version)
+    }
+}
@@ -2,9 +2,15 @@
 //! 
 //! This module defines the various conversation scenarios that users can go through,
 //! including onboarding, group setup, event management, and admin operations.
+//!
+//! Deployments can override parts of the built-in scenarios, or add new ones,
+//! with TOML or JSON files in the `[scenarios] directory`, loaded at startup.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 use serde::{Deserialize, Serialize};
+use tracing::info;
+use crate::config::settings::ScenariosConfig;
 use crate::utils::errors::{SwingBuddyError, Result};
 use super::context::ConversationContext;
 
@@ -27,6 +33,28 @@ pub struct Scenario {
     pub interruptible: bool,
 }
 
+impl Scenario {
+    /// Check if a step can be reached from the initial step
+    pub fn is_reachable(&self, step: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([self.initial_step.as_str()]);
+
+        while let Some(current) = queue.pop_front() {
+            if current == step {
+                return true;
+            }
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some(current_step) = self.steps.get(current) {
+                queue.extend(current_step.next_steps.iter().map(String::as_str));
+            }
+        }
+
+        false
+    }
+}
+
 /// Represents a step within a scenario
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScenarioStep {
@@ -44,6 +72,50 @@ pub struct ScenarioStep {
     pub validation: Option<StepValidation>,
     /// Whether this step can be skipped
     pub skippable: bool,
+    /// Translation key or text asking for the input, instead of the handler's own
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Whether /back may return from this step to the one before it
+    #[serde(default)]
+    pub allow_back: bool,
+    /// Pass over this step, on to its first next step, when this holds for the
+    /// conversation's data, e.g. to not ask for a city that is already known
+    #[serde(default)]
+    pub skip_if: Option<StepCondition>,
+}
+
+/// Condition on a value of the conversation's data. With no checks given it
+/// holds when the value is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StepCondition {
+    /// Data key the condition looks at
+    pub key: String,
+    /// Holds when the value is set (`true`) or missing (`false`)
+    #[serde(default)]
+    pub exists: Option<bool>,
+    /// Holds when the value equals this one
+    #[serde(default)]
+    pub equals: Option<serde_json::Value>,
+    /// Holds when the value is missing or differs from this one
+    #[serde(default)]
+    pub not_equals: Option<serde_json::Value>,
+}
+
+impl StepCondition {
+    /// Check the condition against a conversation's data
+    pub fn holds(&self, context: &ConversationContext) -> bool {
+        let value = context.data.get(&self.key).filter(|value| !value.is_null());
+
+        match self.exists {
+            Some(exists) if value.is_some() != exists => return false,
+            None if self.equals.is_none() && self.not_equals.is_none() && value.is_none() => return false,
+            _ => {}
+        }
+
+        self.equals.as_ref().is_none_or(|expected| value == Some(expected))
+            && self.not_equals.as_ref().is_none_or(|unexpected| value != Some(unexpected))
+    }
 }
 
 /// Validation rules for a scenario step
@@ -51,13 +123,13 @@ pub struct ScenarioStep {
 pub struct StepValidation {
     /// Input type expected
     pub input_type: InputType,
-    /// Minimum length (for text inputs)
+    /// Minimum length (for text inputs), or number of files (for media inputs)
     pub min_length: Option<usize>,
     /// Maximum length (for text inputs)
     pub max_length: Option<usize>,
     /// Pattern to match (regex)
     pub pattern: Option<String>,
-    /// Custom validation message
+    /// Translation key or text of the message shown when the input is rejected
     pub error_message: Option<String>,
 }
 
@@ -72,6 +144,33 @@ pub enum InputType {
     Phone,
     Location,
     Choice(Vec<String>),
+    /// Files of the given kinds, up to `max_items`; steps taking more than one
+    /// collect them until the user presses Done
+    Media {
+        kinds: Vec<MediaKind>,
+        #[serde(default = "default_max_items")]
+        max_items: usize,
+    },
+}
+
+fn default_max_items() -> usize {
+    1
+}
+
+/// Kind of file a media step accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Photo,
+    Document,
+}
+
+/// A file sent to a media step; steps keep a list of them in the conversation's data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaItem {
+    pub kind: MediaKind,
+    /// Telegram file ID, to send or download the file again
+    pub file_id: String,
 }
 
 /// Scenario manager for handling all conversation scenarios
@@ -95,10 +194,64 @@ impl ScenarioManager {
     fn register_default_scenarios(&mut self) {
         self.register_scenario(create_onboarding_scenario());
         self.register_scenario(create_group_setup_scenario());
+        self.register_scenario(create_group_rules_scenario());
+        self.register_scenario(create_spam_filter_scenario());
+        self.register_scenario(create_broadcast_scenario());
+        self.register_scenario(create_announcement_scenario());
+        self.register_scenario(create_user_import_scenario());
+        self.register_scenario(create_calendar_creation_scenario());
+        self.register_scenario(create_calendar_edit_scenario());
+        self.register_scenario(create_template_edit_scenario());
+        self.register_scenario(create_user_profile_edit_scenario());
         self.register_scenario(create_event_creation_scenario());
         self.register_scenario(create_admin_panel_scenario());
     }
 
+    /// Create a scenario manager with the default scenarios and the overrides
+    /// from the configured directory, if any
+    pub fn load(config: Option<&ScenariosConfig>) -> Result<Self> {
+        let mut manager = Self::new();
+        if let Some(config) = config {
+            manager.load_directory(Path::new(&config.directory))?;
+        }
+        Ok(manager)
+    }
+
+    /// Apply every `.toml` and `.json` scenario file in a directory, in file name
+    /// order. Returns the number of files applied.
+    pub fn load_directory(&mut self, directory: &Path) -> Result<usize> {
+        let mut paths = std::fs::read_dir(directory)
+            .map_err(|e| SwingBuddyError::Config(format!("Cannot read scenario directory {}: {}", directory.display(), e)))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| matches!(path.extension().and_then(|ext| ext.to_str()), Some("toml" | "json")))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in &paths {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| SwingBuddyError::Config(format!("Cannot read scenario file {}: {}", path.display(), e)))?;
+            let definition = ScenarioDefinition::parse(&content, path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+                .map_err(|e| SwingBuddyError::Config(format!("Invalid scenario file {}: {}", path.display(), e)))?;
+            let id = definition.id.clone();
+            self.apply_definition(definition)
+                .map_err(|e| SwingBuddyError::Config(format!("Invalid scenario file {}: {}", path.display(), e)))?;
+            info!(scenario = %id, path = %path.display(), "Loaded scenario definition");
+        }
+
+        Ok(paths.len())
+    }
+
+    /// Merge a scenario definition into the registered scenarios; fields and steps
+    /// it leaves out keep their current values. The result is checked before it
+    /// replaces the current scenario.
+    pub fn apply_definition(&mut self, definition: ScenarioDefinition) -> Result<()> {
+        let base = self.scenarios.get(&definition.id).cloned();
+        let scenario = definition.apply(base)?;
+        check_scenario(&scenario)?;
+        self.register_scenario(scenario);
+        Ok(())
+    }
+
     /// Register a new scenario
     pub fn register_scenario(&mut self, scenario: Scenario) {
         self.scenarios.insert(scenario.id.clone(), scenario);
@@ -114,6 +267,21 @@ impl ScenarioManager {
         self.scenarios.values().collect()
     }
 
+    /// Get the prompt configured for a step, if it has one
+    pub fn step_prompt(&self, scenario_id: &str, step_id: &str) -> Option<&str> {
+        self.get_scenario(scenario_id)?
+            .steps.get(step_id)?
+            .prompt.as_deref()
+    }
+
+    /// Get the step the current step continues with by default: its first next
+    /// step, or where that leads when its skip condition holds
+    pub fn following_step(&self, context: &ConversationContext) -> Option<&str> {
+        let scenario = self.get_scenario(context.scenario.as_deref()?)?;
+        let next = self.get_current_step(context).ok()?.next_steps.first()?;
+        Some(landing_step(scenario, next, context))
+    }
+
     /// Start a scenario for a user
     pub fn start_scenario(&self, context: &mut ConversationContext, scenario_id: &str) -> Result<()> {
         let scenario = self.get_scenario(scenario_id)
@@ -130,6 +298,15 @@ impl ScenarioManager {
         Ok(())
     }
 
+    /// Give the user the scenario's full time again, e.g. after a reminder
+    pub fn renew_expiry(&self, context: &mut ConversationContext) {
+        let seconds = context.scenario.as_deref()
+            .and_then(|scenario_id| self.get_scenario(scenario_id))
+            .and_then(|scenario| scenario.max_duration)
+            .unwrap_or(24 * 3600);
+        context.set_expiry(chrono::Utc::now() + chrono::Duration::seconds(seconds as i64));
+    }
+
     /// Move to the next step in a scenario
     pub fn next_step(&self, context: &mut ConversationContext, next_step: &str) -> Result<()> {
         let scenario_id = context.scenario.as_ref()
@@ -150,8 +327,10 @@ impl ScenarioManager {
         let current_step = scenario.steps.get(current_step_id)
             .ok_or_else(|| SwingBuddyError::InvalidInput(format!("Unknown step: {}", current_step_id)))?;
 
-        // Validate that the next step is allowed
-        if !current_step.next_steps.contains(&next_step.to_string()) {
+        // Validate that the next step is allowed, directly or by skipping steps
+        let allowed = current_step.next_steps.iter()
+            .any(|step| step == next_step || landing_step(scenario, step, context) == next_step);
+        if !allowed {
             return Err(SwingBuddyError::InvalidStateTransition {
                 from: current_step_id.clone(),
                 to: next_step.to_string(),
@@ -167,6 +346,51 @@ impl ScenarioManager {
         Ok(())
     }
 
+    /// Move to a step that is not necessarily adjacent to the current one, as long as
+    /// the scenario can reach it from its initial step. Menus use this when a button
+    /// of an older message is pressed.
+    pub fn go_to_step(&self, context: &mut ConversationContext, step: &str) -> Result<()> {
+        if context.step.as_deref() == Some(step) || self.next_step(context, step).is_ok() {
+            return Ok(());
+        }
+
+        let scenario = context.scenario.as_ref()
+            .and_then(|scenario_id| self.get_scenario(scenario_id))
+            .ok_or_else(|| SwingBuddyError::InvalidStateTransition {
+                from: "no_scenario".to_string(),
+                to: step.to_string(),
+            })?;
+
+        if !scenario.is_reachable(step) {
+            return Err(SwingBuddyError::InvalidStateTransition {
+                from: context.step.clone().unwrap_or_default(),
+                to: step.to_string(),
+            });
+        }
+
+        context.next_step(step)
+    }
+
+    /// Whether /back can return from the current step: the step allows it and
+    /// the user came to it from another step of the scenario
+    pub fn can_go_back(&self, context: &ConversationContext) -> bool {
+        !context.history.is_empty() && self.get_current_step(context).is_ok_and(|step| step.allow_back)
+    }
+
+    /// Return to the previous step. Returns the step the user is at now.
+    pub fn back(&self, context: &mut ConversationContext) -> Result<String> {
+        if !self.can_go_back(context) {
+            return Err(SwingBuddyError::InvalidStateTransition {
+                from: context.step.clone().unwrap_or_else(|| "no_step".to_string()),
+                to: "back".to_string(),
+            });
+        }
+
+        context.step_back()
+            .map(str::to_string)
+            .ok_or_else(|| SwingBuddyError::InvalidInput("No previous step".to_string()))
+    }
+
     /// Validate user input for the current step
     pub fn validate_input(&self, context: &ConversationContext, input: &str) -> Result<()> {
         let scenario_id = context.scenario.as_ref()
@@ -188,8 +412,27 @@ impl ScenarioManager {
         Ok(())
     }
 
+    /// Check that the current step takes a file of this kind
+    pub fn validate_media(&self, context: &ConversationContext, kind: MediaKind) -> Result<()> {
+        let validation = self.get_current_step(context)?.validation.as_ref();
+        match validation.map(|validation| &validation.input_type) {
+            Some(InputType::Media { kinds, .. }) if kinds.contains(&kind) => Ok(()),
+            _ => Err(SwingBuddyError::InvalidInput(
+                validation.and_then(|validation| validation.error_message.clone())
+                    .unwrap_or_else(|| "messages.validation.unexpected_file".to_string())
+            )),
+        }
+    }
+
     /// Validate input against validation rules
     fn validate_input_against_rules(&self, input: &str, validation: &StepValidation) -> Result<()> {
+        // Media steps take files, not text
+        if matches!(validation.input_type, InputType::Media { .. }) {
+            return Err(SwingBuddyError::InvalidInput(
+                validation.error_message.clone().unwrap_or_else(|| "messages.validation.file_expected".to_string())
+            ));
+        }
+
         // Check length constraints
         if let Some(min_length) = validation.min_length {
             if input.len() < min_length {
@@ -280,6 +523,185 @@ impl ScenarioManager {
     }
 }
 
+/// A scenario as written in a scenario file. Everything but the ID may be left
+/// out when overriding a built-in scenario; new scenarios need an initial step.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioDefinition {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub initial_step: Option<String>,
+    #[serde(default)]
+    pub max_duration: Option<u64>,
+    #[serde(default)]
+    pub interruptible: Option<bool>,
+    /// Steps to add or change, by step ID
+    #[serde(default)]
+    pub steps: HashMap<String, StepDefinition>,
+}
+
+/// A step of a scenario file; fields left out keep their current values
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StepDefinition {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub next_steps: Option<Vec<String>>,
+    pub requires_input: Option<bool>,
+    pub validation: Option<StepValidation>,
+    pub skippable: Option<bool>,
+    pub prompt: Option<String>,
+    pub allow_back: Option<bool>,
+    pub skip_if: Option<StepCondition>,
+}
+
+impl ScenarioDefinition {
+    /// Parse a scenario file
+    pub fn parse(content: &str, json: bool) -> std::result::Result<Self, String> {
+        if json {
+            serde_json::from_str(content).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(content).map_err(|e| e.to_string())
+        }
+    }
+
+    /// Merge this definition into `base`, or build a new scenario without one
+    fn apply(self, base: Option<Scenario>) -> Result<Scenario> {
+        let mut scenario = match base {
+            Some(scenario) => scenario,
+            None => Scenario {
+                name: self.id.clone(),
+                description: String::new(),
+                initial_step: self.initial_step.clone().ok_or_else(|| {
+                    SwingBuddyError::Config(format!("New scenario '{}' needs an initial_step", self.id))
+                })?,
+                id: self.id.clone(),
+                steps: HashMap::new(),
+                max_duration: None,
+                interruptible: true,
+            },
+        };
+
+        if let Some(name) = self.name {
+            scenario.name = name;
+        }
+        if let Some(description) = self.description {
+            scenario.description = description;
+        }
+        if let Some(initial_step) = self.initial_step {
+            scenario.initial_step = initial_step;
+        }
+        if self.max_duration.is_some() {
+            scenario.max_duration = self.max_duration;
+        }
+        if let Some(interruptible) = self.interruptible {
+            scenario.interruptible = interruptible;
+        }
+
+        for (id, definition) in self.steps {
+            let step = scenario.steps.entry(id.clone()).or_insert_with(|| ScenarioStep {
+                name: id.clone(),
+                id,
+                description: String::new(),
+                next_steps: Vec::new(),
+                requires_input: false,
+                validation: None,
+                skippable: false,
+                prompt: None,
+                allow_back: false,
+                skip_if: None,
+            });
+            if let Some(name) = definition.name {
+                step.name = name;
+            }
+            if let Some(description) = definition.description {
+                step.description = description;
+            }
+            if let Some(next_steps) = definition.next_steps {
+                step.next_steps = next_steps;
+            }
+            if let Some(requires_input) = definition.requires_input {
+                step.requires_input = requires_input;
+            }
+            if definition.validation.is_some() {
+                step.validation = definition.validation;
+            }
+            if let Some(skippable) = definition.skippable {
+                step.skippable = skippable;
+            }
+            if definition.prompt.is_some() {
+                step.prompt = definition.prompt;
+            }
+            if let Some(allow_back) = definition.allow_back {
+                step.allow_back = allow_back;
+            }
+            if definition.skip_if.is_some() {
+                step.skip_if = definition.skip_if;
+            }
+        }
+
+        Ok(scenario)
+    }
+}
+
+/// Check that a scenario only refers to steps it has and that its patterns compile
+fn check_scenario(scenario: &Scenario) -> Result<()> {
+    if !scenario.steps.contains_key(&scenario.initial_step) {
+        return Err(SwingBuddyError::Config(format!(
+            "Scenario '{}' starts at unknown step '{}'", scenario.id, scenario.initial_step
+        )));
+    }
+
+    for step in scenario.steps.values() {
+        if let Some(unknown) = step.next_steps.iter().find(|next| !scenario.steps.contains_key(*next)) {
+            return Err(SwingBuddyError::Config(format!(
+                "Step '{}' of scenario '{}' leads to unknown step '{}'", step.id, scenario.id, unknown
+            )));
+        }
+        if step.skip_if.is_some() && step.next_steps.is_empty() {
+            return Err(SwingBuddyError::Config(format!(
+                "Step '{}' of scenario '{}' can be skipped but has no next step", step.id, scenario.id
+            )));
+        }
+        if let Some(InputType::Media { kinds, max_items }) = step.validation.as_ref().map(|validation| &validation.input_type) {
+            if kinds.is_empty() || *max_items == 0 {
+                return Err(SwingBuddyError::Config(format!(
+                    "Media step '{}' of scenario '{}' needs file kinds and a max_items of at least 1", step.id, scenario.id
+                )));
+            }
+        }
+        if let Some(pattern) = step.validation.as_ref().and_then(|validation| validation.pattern.as_ref()) {
+            regex::Regex::new(pattern).map_err(|e| SwingBuddyError::Config(format!(
+                "Step '{}' of scenario '{}' has an invalid pattern: {}", step.id, scenario.id, e
+            )))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the step a transition to `step_id` lands on, passing over steps whose
+/// skip condition holds. Gives up after visiting every step once, so skip
+/// conditions that loop can't hang the bot.
+fn landing_step<'a>(scenario: &'a Scenario, step_id: &'a str, context: &ConversationContext) -> &'a str {
+    let mut current = step_id;
+    for _ in 0..scenario.steps.len() {
+        let Some(step) = scenario.steps.get(current) else { break };
+        if !step.skip_if.as_ref().is_some_and(|condition| condition.holds(context)) {
+            break;
+        }
+        match step.next_steps.first() {
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+    current
+}
+
 /// Create the user onboarding scenario
 fn create_onboarding_scenario() -> Scenario {
     let mut steps = HashMap::new();
@@ -298,6 +720,9 @@ fn create_onboarding_scenario() -> Scenario {
             error_message: Some("Please select a valid language".to_string()),
         }),
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("name_input".to_string(), ScenarioStep {
@@ -311,9 +736,12 @@ fn create_onboarding_scenario() -> Scenario {
             min_length: Some(2),
             max_length: Some(50),
             pattern: Some(r"^[a-zA-Zа-яА-Я\s]+$".to_string()),
-            error_message: Some("Name should be 2-50 characters, letters and spaces only".to_string()),
+            error_message: Some("messages.validation.invalid_name".to_string()),
         }),
         skippable: false,
+        prompt: None,
+        allow_back: true,
+        skip_if: None,
     });
 
     steps.insert("location_input".to_string(), ScenarioStep {
@@ -330,6 +758,9 @@ fn create_onboarding_scenario() -> Scenario {
             error_message: Some("Please provide a valid location".to_string()),
         }),
         skippable: true,
+        prompt: None,
+        allow_back: true,
+        skip_if: None,
     });
 
     steps.insert("welcome".to_string(), ScenarioStep {
@@ -340,6 +771,9 @@ fn create_onboarding_scenario() -> Scenario {
         requires_input: false,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     Scenario {
@@ -365,6 +799,9 @@ fn create_group_setup_scenario() -> Scenario {
         requires_input: false,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("permission_request".to_string(), ScenarioStep {
@@ -375,16 +812,41 @@ fn create_group_setup_scenario() -> Scenario {
         requires_input: false,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("configuration".to_string(), ScenarioStep {
         id: "configuration".to_string(),
         name: "Group Configuration".to_string(),
-        description: "Configure group settings".to_string(),
-        next_steps: vec!["complete".to_string()],
-        requires_input: true,
+        description: "Group admin picks the language, welcome message and features".to_string(),
+        next_steps: vec!["welcome_message_input".to_string(), "complete".to_string()],
+        requires_input: false,
         validation: None,
         skippable: true,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    steps.insert("welcome_message_input".to_string(), ScenarioStep {
+        id: "welcome_message_input".to_string(),
+        name: "Welcome Message".to_string(),
+        description: "Group admin provides the welcome message for new members".to_string(),
+        next_steps: vec!["configuration".to_string()],
+        requires_input: true,
+        validation: Some(StepValidation {
+            input_type: InputType::Text,
+            min_length: Some(1),
+            max_length: Some(1000),
+            pattern: None,
+            error_message: Some("Welcome message must be at most 1000 characters".to_string()),
+        }),
+        skippable: true,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("complete".to_string(), ScenarioStep {
@@ -395,6 +857,9 @@ fn create_group_setup_scenario() -> Scenario {
         requires_input: false,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     Scenario {
@@ -408,6 +873,335 @@ fn create_group_setup_scenario() -> Scenario {
     }
 }
 
+/// Create the group rules scenario
+fn create_group_rules_scenario() -> Scenario {
+    let mut steps = HashMap::new();
+
+    steps.insert("rules_input".to_string(), ScenarioStep {
+        id: "rules_input".to_string(),
+        name: "Group Rules".to_string(),
+        description: "Group admin provides the rules of the group".to_string(),
+        next_steps: vec![],
+        requires_input: true,
+        validation: Some(StepValidation {
+            input_type: InputType::Text,
+            min_length: Some(1),
+            max_length: Some(3000),
+            pattern: None,
+            error_message: Some("Rules must be at most 3000 characters".to_string()),
+        }),
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    Scenario {
+        id: "group_rules".to_string(),
+        name: "Group Rules".to_string(),
+        description: "Set the rules of a group".to_string(),
+        initial_step: "rules_input".to_string(),
+        steps,
+        max_duration: Some(1800), // 30 minutes
+        interruptible: true,
+    }
+}
+
+/// Create the spam filter scenario
+fn create_spam_filter_scenario() -> Scenario {
+    let mut steps = HashMap::new();
+
+    steps.insert("pattern_input".to_string(), ScenarioStep {
+        id: "pattern_input".to_string(),
+        name: "Spam Filter Pattern".to_string(),
+        description: "Admin provides a keyword, regular expression or link domain to filter".to_string(),
+        next_steps: vec![],
+        requires_input: true,
+        validation: Some(StepValidation {
+            input_type: InputType::Text,
+            min_length: Some(1),
+            max_length: Some(200),
+            pattern: None,
+            error_message: Some("Pattern must be at most 200 characters".to_string()),
+        }),
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    Scenario {
+        id: "spam_filter".to_string(),
+        name: "Spam Filter".to_string(),
+        description: "Add a spam filter to a group from the admin panel".to_string(),
+        initial_step: "pattern_input".to_string(),
+        steps,
+        max_duration: Some(1800), // 30 minutes
+        interruptible: true,
+    }
+}
+
+/// Create the broadcast scenario
+fn create_broadcast_scenario() -> Scenario {
+    let mut steps = HashMap::new();
+
+    steps.insert("message_input".to_string(), ScenarioStep {
+        id: "message_input".to_string(),
+        name: "Broadcast Message".to_string(),
+        description: "Admin composes the message to broadcast; any message type is copied as is".to_string(),
+        next_steps: vec!["segment_select".to_string()],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    steps.insert("segment_select".to_string(), ScenarioStep {
+        id: "segment_select".to_string(),
+        name: "Broadcast Segment".to_string(),
+        description: "Admin picks the recipients, previews and confirms the broadcast".to_string(),
+        next_steps: vec!["schedule_input".to_string()],
+        requires_input: false,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    steps.insert("schedule_input".to_string(), ScenarioStep {
+        id: "schedule_input".to_string(),
+        name: "Broadcast Time".to_string(),
+        description: "Admin enters when to send the broadcast instead of sending it now".to_string(),
+        next_steps: vec![],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    Scenario {
+        id: "broadcast".to_string(),
+        name: "Broadcast".to_string(),
+        description: "Send a message to a segment of the users from the admin panel".to_string(),
+        initial_step: "message_input".to_string(),
+        steps,
+        max_duration: Some(3600), // 1 hour
+        interruptible: true,
+    }
+}
+
+/// Create the group announcement scenario
+fn create_announcement_scenario() -> Scenario {
+    let mut steps = HashMap::new();
+
+    steps.insert("message_input".to_string(), ScenarioStep {
+        id: "message_input".to_string(),
+        name: "Announcement Message".to_string(),
+        description: "Admin composes the announcement; any message type is copied as is".to_string(),
+        next_steps: vec!["schedule_input".to_string()],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    steps.insert("schedule_input".to_string(), ScenarioStep {
+        id: "schedule_input".to_string(),
+        name: "Announcement Time".to_string(),
+        description: "Admin enters when to post the announcement in the group".to_string(),
+        next_steps: vec![],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    Scenario {
+        id: "announcement".to_string(),
+        name: "Group Announcement".to_string(),
+        description: "Schedule an announcement in a group from the admin panel".to_string(),
+        initial_step: "message_input".to_string(),
+        steps,
+        max_duration: Some(3600), // 1 hour
+        interruptible: true,
+    }
+}
+
+/// Create the user import scenario
+fn create_user_import_scenario() -> Scenario {
+    let mut steps = HashMap::new();
+
+    steps.insert("document_input".to_string(), ScenarioStep {
+        id: "document_input".to_string(),
+        name: "User Import File".to_string(),
+        description: "Admin uploads a CSV file with the users to create or update".to_string(),
+        next_steps: vec![],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    Scenario {
+        id: "user_import".to_string(),
+        name: "User Import".to_string(),
+        description: "Import users from a CSV file in the admin panel".to_string(),
+        initial_step: "document_input".to_string(),
+        steps,
+        max_duration: Some(1800), // 30 minutes
+        interruptible: true,
+    }
+}
+
+/// Create the calendar creation scenario
+fn create_calendar_creation_scenario() -> Scenario {
+    let mut steps = HashMap::new();
+
+    steps.insert("name_input".to_string(), ScenarioStep {
+        id: "name_input".to_string(),
+        name: "Calendar Name".to_string(),
+        description: "Admin enters the name shown in /events".to_string(),
+        next_steps: vec!["description_input".to_string()],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    steps.insert("description_input".to_string(), ScenarioStep {
+        id: "description_input".to_string(),
+        name: "Calendar Description".to_string(),
+        description: "Admin describes the calendar or sends - to leave it empty".to_string(),
+        next_steps: vec!["google_id_input".to_string()],
+        requires_input: true,
+        validation: None,
+        skippable: true,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    steps.insert("google_id_input".to_string(), ScenarioStep {
+        id: "google_id_input".to_string(),
+        name: "Google Calendar ID".to_string(),
+        description: "Admin links a public Google Calendar or sends - to skip".to_string(),
+        next_steps: vec![],
+        requires_input: true,
+        validation: None,
+        skippable: true,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    Scenario {
+        id: "calendar_creation".to_string(),
+        name: "Calendar Creation".to_string(),
+        description: "Add an event calendar from the admin panel".to_string(),
+        initial_step: "name_input".to_string(),
+        steps,
+        max_duration: Some(1800), // 30 minutes
+        interruptible: true,
+    }
+}
+
+/// Create the calendar edit scenario
+fn create_calendar_edit_scenario() -> Scenario {
+    let mut steps = HashMap::new();
+
+    steps.insert("value_input".to_string(), ScenarioStep {
+        id: "value_input".to_string(),
+        name: "Calendar Field Value".to_string(),
+        description: "Admin enters the new value of the calendar field they picked".to_string(),
+        next_steps: vec![],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    Scenario {
+        id: "calendar_edit".to_string(),
+        name: "Calendar Edit".to_string(),
+        description: "Change a calendar field from the admin panel".to_string(),
+        initial_step: "value_input".to_string(),
+        steps,
+        max_duration: Some(1800), // 30 minutes
+        interruptible: true,
+    }
+}
+
+/// Create the notification template edit scenario
+fn create_template_edit_scenario() -> Scenario {
+    let mut steps = HashMap::new();
+
+    steps.insert("content_input".to_string(), ScenarioStep {
+        id: "content_input".to_string(),
+        name: "Template Text".to_string(),
+        description: "Admin enters the new text of the notification template in the language they picked".to_string(),
+        next_steps: vec![],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    Scenario {
+        id: "template_edit".to_string(),
+        name: "Notification Template Edit".to_string(),
+        description: "Change the text of a notification template from the admin panel".to_string(),
+        initial_step: "content_input".to_string(),
+        steps,
+        max_duration: Some(1800), // 30 minutes
+        interruptible: true,
+    }
+}
+
+/// Create the user profile edit scenario
+fn create_user_profile_edit_scenario() -> Scenario {
+    let mut steps = HashMap::new();
+
+    steps.insert("value_input".to_string(), ScenarioStep {
+        id: "value_input".to_string(),
+        name: "Profile Field Value".to_string(),
+        description: "Admin enters the corrected value of the user's profile field".to_string(),
+        next_steps: vec![],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    Scenario {
+        id: "user_profile_edit".to_string(),
+        name: "User Profile Edit".to_string(),
+        description: "Correct a user's name, city or language from the admin panel".to_string(),
+        initial_step: "value_input".to_string(),
+        steps,
+        max_duration: Some(1800), // 30 minutes
+        interruptible: true,
+    }
+}
+
 /// Create the event creation scenario
 fn create_event_creation_scenario() -> Scenario {
     let mut steps = HashMap::new();
@@ -426,6 +1220,9 @@ fn create_event_creation_scenario() -> Scenario {
             error_message: Some("Event title should be 3-100 characters".to_string()),
         }),
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("description_input".to_string(), ScenarioStep {
@@ -442,6 +1239,9 @@ fn create_event_creation_scenario() -> Scenario {
             error_message: Some("Event description should be 10-500 characters".to_string()),
         }),
         skippable: true,
+        prompt: None,
+        allow_back: true,
+        skip_if: None,
     });
 
     steps.insert("date_input".to_string(), ScenarioStep {
@@ -458,6 +1258,9 @@ fn create_event_creation_scenario() -> Scenario {
             error_message: Some("Please provide a valid date (YYYY-MM-DD)".to_string()),
         }),
         skippable: false,
+        prompt: None,
+        allow_back: true,
+        skip_if: None,
     });
 
     steps.insert("time_input".to_string(), ScenarioStep {
@@ -474,6 +1277,9 @@ fn create_event_creation_scenario() -> Scenario {
             error_message: Some("Please provide a valid time (HH:MM)".to_string()),
         }),
         skippable: false,
+        prompt: None,
+        allow_back: true,
+        skip_if: None,
     });
 
     steps.insert("location_input".to_string(), ScenarioStep {
@@ -490,6 +1296,9 @@ fn create_event_creation_scenario() -> Scenario {
             error_message: Some("Please provide a valid location".to_string()),
         }),
         skippable: false,
+        prompt: None,
+        allow_back: true,
+        skip_if: None,
     });
 
     steps.insert("confirmation".to_string(), ScenarioStep {
@@ -499,13 +1308,16 @@ fn create_event_creation_scenario() -> Scenario {
         next_steps: vec!["create".to_string(), "cancel".to_string()],
         requires_input: true,
         validation: Some(StepValidation {
-            input_type: InputType::Choice(vec!["confirm".to_string(), "cancel".to_string()]),
+            input_type: InputType::Choice(vec!["create".to_string(), "cancel".to_string()]),
             min_length: None,
             max_length: None,
             pattern: None,
             error_message: Some("Please confirm or cancel".to_string()),
         }),
         skippable: false,
+        prompt: None,
+        allow_back: true,
+        skip_if: None,
     });
 
     steps.insert("create".to_string(), ScenarioStep {
@@ -516,6 +1328,9 @@ fn create_event_creation_scenario() -> Scenario {
         requires_input: false,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("cancel".to_string(), ScenarioStep {
@@ -526,6 +1341,9 @@ fn create_event_creation_scenario() -> Scenario {
         requires_input: false,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     Scenario {
@@ -569,16 +1387,52 @@ fn create_admin_panel_scenario() -> Scenario {
             error_message: Some("Please select a valid option".to_string()),
         }),
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("user_management".to_string(), ScenarioStep {
         id: "user_management".to_string(),
         name: "User Management".to_string(),
         description: "Manage users".to_string(),
-        next_steps: vec!["main_menu".to_string()],
+        next_steps: vec![
+            "main_menu".to_string(),
+            "ban_user_input".to_string(),
+            "unban_user_input".to_string(),
+        ],
         requires_input: true,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    steps.insert("ban_user_input".to_string(), ScenarioStep {
+        id: "ban_user_input".to_string(),
+        name: "Ban User".to_string(),
+        description: "Admin sends the Telegram ID or @username of the user to ban".to_string(),
+        next_steps: vec!["user_management".to_string()],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
+    });
+
+    steps.insert("unban_user_input".to_string(), ScenarioStep {
+        id: "unban_user_input".to_string(),
+        name: "Unban User".to_string(),
+        description: "Admin sends the Telegram ID or @username of the user to unban".to_string(),
+        next_steps: vec!["user_management".to_string()],
+        requires_input: true,
+        validation: None,
+        skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("group_management".to_string(), ScenarioStep {
@@ -589,6 +1443,9 @@ fn create_admin_panel_scenario() -> Scenario {
         requires_input: true,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("event_management".to_string(), ScenarioStep {
@@ -599,6 +1456,9 @@ fn create_admin_panel_scenario() -> Scenario {
         requires_input: true,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("system_settings".to_string(), ScenarioStep {
@@ -609,6 +1469,9 @@ fn create_admin_panel_scenario() -> Scenario {
         requires_input: true,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     steps.insert("statistics".to_string(), ScenarioStep {
@@ -619,6 +1482,9 @@ fn create_admin_panel_scenario() -> Scenario {
         requires_input: false,
         validation: None,
         skippable: false,
+        prompt: None,
+        allow_back: false,
+        skip_if: None,
     });
 
     Scenario {
@@ -648,6 +1514,8 @@ mod tests {
         
         assert!(manager.get_scenario("onboarding").is_some());
         assert!(manager.get_scenario("group_setup").is_some());
+        assert!(manager.get_scenario("group_rules").is_some());
+        assert!(manager.get_scenario("spam_filter").is_some());
         assert!(manager.get_scenario("event_creation").is_some());
         assert!(manager.get_scenario("admin_panel").is_some());
         assert!(manager.get_scenario("nonexistent").is_none());
@@ -697,4 +1565,146 @@ mod tests {
         // Try to go to a non-existent step
         assert!(manager.next_step(&mut context, "nonexistent").is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_go_to_step() {
+        let manager = ScenarioManager::new();
+        let mut context = ConversationContext::new(123);
+
+        manager.start_scenario(&mut context, "admin_panel").unwrap();
+        manager.next_step(&mut context, "user_management").unwrap();
+        manager.next_step(&mut context, "ban_user_input").unwrap();
+
+        // A button of an older menu jumps across the panel
+        manager.go_to_step(&mut context, "group_management").unwrap();
+        assert_eq!(context.step, Some("group_management".to_string()));
+        manager.go_to_step(&mut context, "unban_user_input").unwrap();
+        assert_eq!(context.step, Some("unban_user_input".to_string()));
+
+        assert!(manager.go_to_step(&mut context, "nonexistent").is_err());
+    }
+
+    #[test]
+    fn test_back() {
+        let manager = ScenarioManager::new();
+        let mut context = ConversationContext::new(123);
+
+        manager.start_scenario(&mut context, "onboarding").unwrap();
+        assert!(!manager.can_go_back(&context));
+        assert!(manager.back(&mut context).is_err());
+
+        manager.next_step(&mut context, "name_input").unwrap();
+        manager.next_step(&mut context, "location_input").unwrap();
+        assert_eq!(manager.back(&mut context).unwrap(), "name_input");
+        assert_eq!(manager.back(&mut context).unwrap(), "language_selection");
+        // The first step doesn't allow going back
+        assert!(manager.back(&mut context).is_err());
+        assert_eq!(context.step, Some("language_selection".to_string()));
+    }
+
+    #[test]
+    fn test_scenario_definition_overrides() {
+        let mut manager = ScenarioManager::new();
+        let definition = ScenarioDefinition::parse(r#"
+            id = "onboarding"
+
+            [steps.name_input]
+            next_steps = ["welcome"]
+            prompt = "How should other dancers call you?"
+
+            [steps.name_input.validation]
+            input_type = "Text"
+            min_length = 3
+        "#, false).unwrap();
+        manager.apply_definition(definition).unwrap();
+
+        let scenario = manager.get_scenario("onboarding").unwrap();
+        assert_eq!(scenario.initial_step, "language_selection");
+        assert_eq!(scenario.steps["name_input"].next_steps, vec!["welcome".to_string()]);
+        assert!(scenario.steps.contains_key("location_input"));
+        assert_eq!(manager.step_prompt("onboarding", "name_input"), Some("How should other dancers call you?"));
+
+        let mut context = ConversationContext::new(123);
+        manager.start_scenario(&mut context, "onboarding").unwrap();
+        manager.next_step(&mut context, "name_input").unwrap();
+        assert_eq!(manager.following_step(&context), Some("welcome"));
+        assert!(manager.validate_input(&context, "Al").is_err());
+        assert!(manager.validate_input(&context, "Alice").is_ok());
+    }
+
+    #[test]
+    fn test_scenario_definition_new_scenario() {
+        let mut manager = ScenarioManager::new();
+        let definition = ScenarioDefinition::parse(r#"{
+            "id": "feedback",
+            "initial_step": "ask",
+            "steps": {
+                "ask": { "next_steps": ["thanks"], "requires_input": true },
+                "thanks": {}
+            }
+        }"#, true).unwrap();
+        manager.apply_definition(definition).unwrap();
+        assert!(manager.get_scenario("feedback").unwrap().is_reachable("thanks"));
+
+        // Transitions to missing steps and new scenarios without a start are rejected
+        let broken = ScenarioDefinition::parse(r#"
+            id = "onboarding"
+            [steps.welcome]
+            next_steps = ["nowhere"]
+        "#, false).unwrap();
+        assert!(manager.apply_definition(broken).is_err());
+        assert!(manager.get_scenario("onboarding").unwrap().steps["welcome"].next_steps.is_empty());
+        let no_start = ScenarioDefinition::parse(r#"id = "empty""#, false).unwrap();
+        assert!(manager.apply_definition(no_start).is_err());
+        assert!(ScenarioDefinition::parse(r#"id = "onboarding"
+            unknown = 1"#, false).is_err());
+    }
+
+    #[test]
+    fn test_step_condition() {
+        let condition = |toml: &str| toml::from_str::<StepCondition>(toml).unwrap();
+        let mut context = ConversationContext::new(123);
+        context.set_data("price", 0).unwrap();
+
+        assert!(condition(r#"key = "price""#).holds(&context));
+        assert!(!condition(r#"key = "city""#).holds(&context));
+        assert!(condition(r#"key = "city"
+            exists = false"#).holds(&context));
+        assert!(condition(r#"key = "price"
+            equals = 0"#).holds(&context));
+        assert!(!condition(r#"key = "price"
+            not_equals = 0"#).holds(&context));
+        assert!(condition(r#"key = "city"
+            not_equals = "Moscow""#).holds(&context));
+    }
+
+    #[test]
+    fn test_skipped_steps() {
+        let mut manager = ScenarioManager::new();
+        let definition = ScenarioDefinition::parse(r#"
+            id = "onboarding"
+
+            [steps.location_input]
+            skip_if = { key = "location" }
+        "#, false).unwrap();
+        manager.apply_definition(definition).unwrap();
+
+        let mut context = ConversationContext::new(123);
+        manager.start_scenario(&mut context, "onboarding").unwrap();
+        manager.next_step(&mut context, "name_input").unwrap();
+        assert_eq!(manager.following_step(&context), Some("location_input"));
+
+        context.set_data("location", "Moscow").unwrap();
+        assert_eq!(manager.following_step(&context), Some("welcome"));
+        manager.next_step(&mut context, "welcome").unwrap();
+        assert_eq!(context.step, Some("welcome".to_string()));
+
+        // A step with nowhere to go can't be skipped
+        let dead_end = ScenarioDefinition::parse(r#"
+            id = "onboarding"
+            [steps.welcome]
+            skip_if = { key = "name" }
+        "#, false).unwrap();
+        assert!(manager.apply_definition(dead_end).is_err());
+    }
+}
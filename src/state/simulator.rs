@@ -0,0 +1,382 @@
+//! Scenario simulator
+//!
+//! This module walks conversation scenarios without a bot and renders the
+//! localized texts and keyboards of every step, so translators and designers
+//! can review flows in the terminal. Texts are shown with their placeholders
+//! unfilled; missing translations and hard-coded labels are flagged.
+
+use std::fmt;
+use std::io::{BufRead, Write};
+use crate::i18n::I18n;
+use crate::utils::errors::{SwingBuddyError, Result};
+use super::scenarios::{Scenario, ScenarioManager};
+
+/// Label of a keyboard button
+#[derive(Debug, Clone, Copy)]
+pub enum ButtonLabel {
+    /// Translation key
+    Key(&'static str),
+    /// Text hard-coded in the handler
+    Text(&'static str),
+}
+
+/// Keyboard button shown for a step
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenButton {
+    pub label: ButtonLabel,
+    /// Callback data or URL the button carries
+    pub action: &'static str,
+}
+
+/// What the bot sends when a user reaches a step
+#[derive(Debug, Clone, Default)]
+pub struct StepScreen {
+    /// Translation keys of the messages, in the order they are sent
+    pub texts: Vec<&'static str>,
+    pub keyboard: Vec<Vec<ScreenButton>>,
+}
+
+const fn key(label: &'static str, action: &'static str) -> ScreenButton {
+    ScreenButton { label: ButtonLabel::Key(label), action }
+}
+
+const fn text(label: &'static str, action: &'static str) -> ScreenButton {
+    ScreenButton { label: ButtonLabel::Text(label), action }
+}
+
+/// Get the screen the handlers show for a scenario step, if the step sends anything
+pub fn step_screen(scenario: &str, step: &str) -> Option<StepScreen> {
+    let (texts, keyboard) = match (scenario, step) {
+        ("onboarding", "language_selection") => (
+            vec!["commands.start.new_user_greeting", "commands.start.choose_language"],
+            vec![vec![key("buttons.language.english", "lang:en"), key("buttons.language.russian", "lang:ru")]],
+        ),
+        ("onboarding", "name_input") => (
+            vec!["commands.start.language_selected", "commands.start.ask_name"],
+            vec![],
+        ),
+        ("onboarding", "location_input") => (
+            vec!["commands.start.ask_location"],
+            vec![
+                vec![text("📍 Moscow", "location:Moscow"), text("📍 Saint Petersburg", "location:Saint Petersburg")],
+                vec![text("⏭️ Skip", "location:skip")],
+            ],
+        ),
+        ("onboarding", "welcome") => (vec!["commands.start.setup_complete"], vec![]),
+        ("group_setup", "permission_request") => (
+            vec!["group.setup.permission_request"],
+            vec![
+                vec![key("buttons.group.documentation", "https://github.com/your-repo/swing-buddy/wiki/Bot-Setup")],
+                vec![key("buttons.group.check_again", "group_setup:check_permissions"), key("buttons.group.language", "group_setup:language")],
+            ],
+        ),
+        ("group_setup", "configuration") => (
+            vec!["group.setup.configuration"],
+            vec![
+                vec![key("buttons.group.language", "group_setup:language"), key("buttons.group.welcome_message", "group_setup:welcome")],
+                vec![key("commands.admin.group_settings.features.cas_protection", "group_setup:feature:cas_protection")],
+                vec![key("commands.admin.group_settings.features.welcome_messages", "group_setup:feature:welcome_messages")],
+                vec![key("commands.admin.group_settings.features.event_reminders", "group_setup:feature:event_reminders")],
+                vec![key("commands.admin.group_settings.features.captcha", "group_setup:feature:captcha")],
+                vec![key("buttons.group.done", "group_setup:done")],
+            ],
+        ),
+        ("group_setup", "complete") => (vec!["group.setup.complete"], vec![]),
+        ("group_setup", "welcome_message_input") => (vec!["messages.group.welcome_message_prompt"], vec![]),
+        ("group_rules", "rules_input") => (vec!["commands.rules.prompt"], vec![]),
+        ("spam_filter", "pattern_input") => (vec!["commands.admin.spam_filters.prompt_keyword"], vec![]),
+        ("admin_panel", "main_menu") => (
+            vec!["commands.admin.panel_title"],
+            vec![
+                vec![key("commands.admin.user_management", "admin:users"), key("commands.admin.group_management", "admin:groups")],
+                vec![key("commands.admin.event_management", "admin:events"), key("commands.admin.statistics", "admin:stats")],
+                vec![key("commands.admin.system_settings", "admin:settings")],
+            ],
+        ),
+        ("admin_panel", "user_management") => (
+            vec!["commands.admin.user_management"],
+            vec![
+                vec![key("buttons.admin.ban", "admin:ban_user"), key("buttons.admin.unban", "admin:unban_user")],
+                vec![key("buttons.navigation.back", "admin:back")],
+            ],
+        ),
+        ("admin_panel", "ban_user_input") => (
+            vec!["commands.admin.ban_user_prompt"],
+            vec![vec![key("buttons.navigation.back", "admin:users")]],
+        ),
+        ("admin_panel", "unban_user_input") => (
+            vec!["commands.admin.unban_user_prompt"],
+            vec![vec![key("buttons.navigation.back", "admin:users")]],
+        ),
+        ("admin_panel", "group_management") => (
+            vec!["commands.admin.group_management"],
+            vec![
+                vec![text("📋 List Groups", "admin:list_groups"), text("⚙️ Group Settings", "admin:group_settings")],
+                vec![key("buttons.navigation.back", "admin:back")],
+            ],
+        ),
+        ("admin_panel", "event_management") => (
+            vec!["commands.admin.event_management"],
+            vec![
+                vec![key("buttons.events.create", "admin:create_event"), key("buttons.events.list", "admin:list_events")],
+                vec![text("📅 Manage Calendars", "admin:manage_calendars")],
+                vec![key("buttons.navigation.back", "admin:back")],
+            ],
+        ),
+        ("admin_panel", "statistics") => (
+            vec!["commands.admin.statistics", "commands.admin.top_inviters"],
+            vec![
+                vec![text("🔄 Refresh", "admin:stats"), key("buttons.admin.backup", "admin:backup")],
+                vec![key("buttons.navigation.back", "admin:back")],
+            ],
+        ),
+        ("admin_panel", "system_settings") => (
+            vec!["commands.admin.system_settings"],
+            vec![
+                vec![text("🔧 Features", "admin:features"), text("🗄️ Cache", "admin:cache")],
+                vec![key("buttons.navigation.back", "admin:back")],
+            ],
+        ),
+        _ => return None,
+    };
+
+    Some(StepScreen { texts, keyboard })
+}
+
+/// A scenario step rendered in one language
+#[derive(Debug, Clone)]
+pub struct RenderedStep {
+    pub scenario: String,
+    pub step: String,
+    pub name: String,
+    pub description: String,
+    pub requires_input: bool,
+    pub texts: Vec<String>,
+    pub keyboard: Vec<Vec<String>>,
+    /// Translation keys missing in the selected language
+    pub missing: Vec<String>,
+    /// Button labels hard-coded in the handlers
+    pub untranslated: Vec<String>,
+    pub next_steps: Vec<String>,
+}
+
+impl fmt::Display for RenderedStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "== [{}] {} - {}", self.scenario, self.step, self.name)?;
+        writeln!(f, "   {}", self.description)?;
+
+        if self.texts.is_empty() && self.keyboard.is_empty() {
+            writeln!(f, "   (no message)")?;
+        }
+        for text in &self.texts {
+            writeln!(f)?;
+            for line in text.lines() {
+                writeln!(f, "   | {}", line)?;
+            }
+        }
+        if !self.keyboard.is_empty() {
+            writeln!(f)?;
+            for row in &self.keyboard {
+                let row = row.iter().map(|label| format!("[ {} ]", label)).collect::<Vec<_>>();
+                writeln!(f, "   {}", row.join(" "))?;
+            }
+        }
+        if self.requires_input {
+            writeln!(f, "   > waits for user input")?;
+        }
+
+        for key in &self.missing {
+            writeln!(f, "   ! missing translation: {}", key)?;
+        }
+        for label in &self.untranslated {
+            writeln!(f, "   ! hard-coded label: {}", label)?;
+        }
+
+        if self.next_steps.is_empty() {
+            writeln!(f, "   -> end of scenario")
+        } else {
+            writeln!(f, "   -> {}", self.next_steps.join(", "))
+        }
+    }
+}
+
+/// Renders scenarios in a single language
+pub struct ScenarioSimulator<'a> {
+    scenario_manager: &'a ScenarioManager,
+    i18n: &'a I18n,
+    language: String,
+}
+
+impl<'a> ScenarioSimulator<'a> {
+    /// Create a simulator for the given language
+    pub fn new(scenario_manager: &'a ScenarioManager, i18n: &'a I18n, language: &str) -> Self {
+        Self {
+            scenario_manager,
+            i18n,
+            language: language.to_string(),
+        }
+    }
+
+    /// Render a single step of a scenario
+    pub fn render_step(&self, scenario_id: &str, step_id: &str) -> Result<RenderedStep> {
+        let scenario = self.scenario(scenario_id)?;
+        let step = scenario.steps.get(step_id)
+            .ok_or_else(|| SwingBuddyError::InvalidInput(format!("Unknown step: {}", step_id)))?;
+        let screen = step_screen(scenario_id, step_id).unwrap_or_default();
+
+        let mut missing = Vec::new();
+        let mut untranslated = Vec::new();
+        let mut translate = |key: &str| {
+            if !self.i18n.has_translation(key, &self.language) {
+                missing.push(key.to_string());
+            }
+            self.i18n.t(key, &self.language, None)
+        };
+
+        let texts = screen.texts.iter().map(|key| translate(key)).collect();
+        let keyboard = screen.keyboard.iter()
+            .map(|row| row.iter().map(|button| {
+                let label = match button.label {
+                    ButtonLabel::Key(key) => translate(key),
+                    ButtonLabel::Text(text) => {
+                        untranslated.push(text.to_string());
+                        text.to_string()
+                    }
+                };
+                format!("{} -> {}", label, button.action)
+            }).collect())
+            .collect();
+
+        let mut next_steps = step.next_steps.clone();
+        next_steps.retain(|next| scenario.steps.contains_key(next));
+
+        Ok(RenderedStep {
+            scenario: scenario_id.to_string(),
+            step: step_id.to_string(),
+            name: step.name.clone(),
+            description: step.description.clone(),
+            requires_input: step.requires_input,
+            texts,
+            keyboard,
+            missing,
+            untranslated,
+            next_steps,
+        })
+    }
+
+    /// Render every step reachable from the initial step, in breadth-first order
+    pub fn walk(&self, scenario_id: &str) -> Result<Vec<RenderedStep>> {
+        let scenario = self.scenario(scenario_id)?;
+
+        let mut queue = std::collections::VecDeque::from([scenario.initial_step.clone()]);
+        let mut seen = std::collections::HashSet::new();
+        let mut steps = Vec::new();
+
+        while let Some(step_id) = queue.pop_front() {
+            if !seen.insert(step_id.clone()) {
+                continue;
+            }
+            let step = self.render_step(scenario_id, &step_id)?;
+            queue.extend(step.next_steps.iter().cloned());
+            steps.push(step);
+        }
+
+        Ok(steps)
+    }
+
+    /// Walk a scenario step by step, letting the user pick the next step.
+    /// Enter follows the first transition, a number picks one, `q` quits.
+    pub fn run_interactive<R: BufRead, W: Write>(&self, scenario_id: &str, mut input: R, mut output: W) -> Result<()> {
+        let scenario = self.scenario(scenario_id)?;
+        let mut step_id = scenario.initial_step.clone();
+
+        loop {
+            let step = self.render_step(scenario_id, &step_id)?;
+            writeln!(output, "{}", step)?;
+
+            if step.next_steps.is_empty() {
+                return Ok(());
+            }
+            for (index, next) in step.next_steps.iter().enumerate() {
+                writeln!(output, "   {}) {}", index + 1, next)?;
+            }
+
+            step_id = loop {
+                write!(output, "next step [1, q to quit]: ")?;
+                output.flush()?;
+
+                let mut line = String::new();
+                if input.read_line(&mut line)? == 0 {
+                    return Ok(());
+                }
+
+                match line.trim() {
+                    "q" => return Ok(()),
+                    "" => break step.next_steps[0].clone(),
+                    choice => match choice.parse::<usize>().ok().and_then(|n| step.next_steps.get(n.wrapping_sub(1))) {
+                        Some(next) => break next.clone(),
+                        None => writeln!(output, "   unknown choice: {}", choice)?,
+                    },
+                }
+            };
+            writeln!(output)?;
+        }
+    }
+
+    fn scenario(&self, scenario_id: &str) -> Result<&Scenario> {
+        self.scenario_manager.get_scenario(scenario_id)
+            .ok_or_else(|| SwingBuddyError::InvalidInput(format!("Unknown scenario: {}", scenario_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screens_have_texts() {
+        let manager = ScenarioManager::new();
+        for scenario in manager.get_all_scenarios() {
+            for step in scenario.steps.keys() {
+                if let Some(screen) = step_screen(&scenario.id, step) {
+                    assert!(!screen.texts.is_empty(), "{}/{} has no text", scenario.id, step);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_walk_covers_reachable_steps() {
+        let manager = ScenarioManager::new();
+        let i18n = I18n::new(&crate::config::settings::I18nConfig {
+            default_language: "en".to_string(),
+            supported_languages: vec!["en".to_string()],
+        });
+        let simulator = ScenarioSimulator::new(&manager, &i18n, "en");
+
+        let steps = simulator.walk("onboarding").unwrap();
+        let ids: Vec<&str> = steps.iter().map(|s| s.step.as_str()).collect();
+        assert_eq!(ids.first(), Some(&"language_selection"));
+        assert!(ids.contains(&"welcome"));
+
+        // Nothing is loaded, so every key is reported missing
+        assert!(steps[0].missing.contains(&"commands.start.choose_language".to_string()));
+        assert!(simulator.walk("unknown").is_err());
+    }
+
+    #[test]
+    fn test_interactive_quits() {
+        let manager = ScenarioManager::new();
+        let i18n = I18n::new(&crate::config::settings::I18nConfig {
+            default_language: "en".to_string(),
+            supported_languages: vec!["en".to_string()],
+        });
+        let simulator = ScenarioSimulator::new(&manager, &i18n, "en");
+
+        let mut output = Vec::new();
+        simulator.run_interactive("onboarding", "\nq\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("[onboarding] language_selection"));
+        assert!(output.contains("[onboarding] name_input"));
+    }
+}
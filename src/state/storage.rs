@@ -1,139 +1,258 @@
 //! State storage implementation
 //! 
-//! This module handles persistence of conversation state using Redis,
-//! including serialization, deserialization, expiration, and cleanup.
+//! This module handles persistence of conversation state, including
+//! serialization, deserialization, expiration, and cleanup. Contexts are kept
+//! in Redis, or in PostgreSQL with `[state] backend = "postgres"`.
+//!
+//! While Redis is unreachable, contexts are kept in an in-process
+//! [`MemoryStateStore`] instead, so conversations keep working through short
+//! outages. Such contexts live at most 15 minutes, are lost on restart and
+//! are moved to Redis when it is back. Redis is retried every few seconds.
 
-use std::time::Duration;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
 use redis::AsyncCommands;
+use redis::aio::ConnectionManager;
 use serde_json;
+use sqlx::PgPool;
 use tracing::{debug, warn, error, info};
-use crate::utils::errors::Result;
-use crate::config::RedisConfig;
-use super::context::ConversationContext;
+use crate::utils::errors::{Result, SwingBuddyError};
+use crate::config::{RedisConfig, Settings, StateBackendKind};
+use crate::utils::redis_connection::RedisConnector;
+use super::backend::{ScopeCursor, StateBackend};
+use super::context::{ConversationContext, ContextScope};
+use super::memory::MemoryStateStore;
+use super::postgres::PostgresStateBackend;
 
-/// Redis-based state storage manager
+/// How long a Redis call may take before falling back to memory
+const REDIS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long Redis is left alone after a failure
+const REDIS_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Shortest time a context is stored for, even when it is about to expire
+const MIN_CONTEXT_TTL: Duration = Duration::from_secs(60);
+
+/// Times `update_context` starts over when other saves keep getting in between
+const MAX_UPDATE_ATTEMPTS: usize = 3;
+
+/// Scopes asked for at a time when walking the stored contexts
+const SCOPES_PAGE_SIZE: usize = 500;
+
+/// Most scopes listed at once; statistics and backups of larger stores are partial
+const MAX_LISTED_SCOPES: usize = 10_000;
+
+/// Most contexts one cleanup run looks at; the next run goes on from there
+const MAX_CLEANUP_CHECKS: usize = 10_000;
+
+/// Store a context unless another version of it was stored since it was
+/// loaded: KEYS[1] is the context key, ARGV the version the caller loaded
+/// (0 to store unconditionally), the serialized context and its TTL
+static SAVE_CONTEXT_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| redis::Script::new(r"
+    local expected = tonumber(ARGV[1])
+    if expected > 0 then
+        local stored = redis.call('GET', KEYS[1])
+        if not stored or (cjson.decode(stored)['version'] or 0) ~= expected then
+            return 0
+        end
+    end
+    redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+    return 1
+"));
+
+/// Whether Redis is failing, so it is not tried on every call
+#[derive(Debug, Default)]
+struct RedisHealth {
+    failing_since: Option<Instant>,
+    retry_at: Option<Instant>,
+}
+
+/// Conversation state storage, on top of a Redis or PostgreSQL backend
 #[derive(Clone)]
 pub struct StateStorage {
-    /// Redis connection manager
-    connection_manager: redis::aio::ConnectionManager,
-    /// Redis configuration
-    config: RedisConfig,
+    backend: Arc<dyn StateBackend>,
+    /// Lifetime of contexts without an expiry of their own
+    default_ttl: Duration,
+    /// How often expired contexts are purged, for backends that need it
+    cleanup_interval: Duration,
+    /// Where the last cleanup run stopped walking the stored contexts
+    cleanup_cursor: Arc<Mutex<ScopeCursor>>,
+    /// Expired contexts removed since startup
+    cleaned_up: Arc<AtomicU64>,
 }
 
 impl StateStorage {
-    /// Create a new state storage instance
+    /// Create a new state storage instance. If Redis can't be reached the
+    /// storage starts in memory and switches to Redis once it is up.
     pub async fn new(config: RedisConfig) -> Result<Self> {
-        let client = redis::Client::open(config.url.as_str())?;
-        let connection_manager = redis::aio::ConnectionManager::new(client).await?;
-        
-        Ok(Self {
-            connection_manager,
-            config,
-        })
+        let default_ttl = Duration::from_secs(config.ttl_seconds);
+        let backend = RedisStateBackend::connect(config).await?;
+        Ok(Self::with_backend(Arc::new(backend), default_ttl))
     }
 
-    /// Save conversation context to Redis
-    pub async fn save_context(&self, context: &ConversationContext) -> Result<()> {
-        let key = self.get_context_key(context.user_id);
-        debug!(user_id = context.user_id, key = %key, scenario = ?context.scenario,
-               step = ?context.step, "Saving context to Redis");
-        
-        let serialized = match serde_json::to_string(context) {
-            Ok(data) => {
-                debug!(user_id = context.user_id, data_length = data.len(), "Context serialized successfully");
-                data
-            },
-            Err(e) => {
-                error!(user_id = context.user_id, error = %e, "Failed to serialize context");
-                return Err(e.into());
+    /// Create a storage keeping contexts in the `conversation_contexts` table
+    pub fn postgres(pool: PgPool, default_ttl: Duration, cleanup_interval: Duration) -> Self {
+        Self {
+            cleanup_interval,
+            ..Self::with_backend(Arc::new(PostgresStateBackend::new(pool)), default_ttl)
+        }
+    }
+
+    /// Create a storage on top of any backend
+    pub fn with_backend(backend: Arc<dyn StateBackend>, default_ttl: Duration) -> Self {
+        Self {
+            backend,
+            default_ttl,
+            cleanup_interval: Duration::from_secs(300),
+            cleanup_cursor: Arc::new(Mutex::new(ScopeCursor::Start)),
+            cleaned_up: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Create the storage the `[state]` settings ask for; Redis by default
+    pub async fn from_settings(settings: &Settings, pool: PgPool) -> Result<Self> {
+        match settings.state.as_ref() {
+            Some(state) if state.backend == StateBackendKind::Postgres => Ok(Self::postgres(
+                pool,
+                Duration::from_secs(settings.redis.ttl_seconds),
+                Duration::from_secs(state.cleanup_interval_seconds),
+            )),
+            _ => Self::new(settings.redis.clone()).await,
+        }
+    }
+
+    /// Name of the backend keeping the contexts
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Whether contexts are being kept in memory because Redis is unreachable
+    pub fn is_degraded(&self) -> bool {
+        self.backend.is_degraded()
+    }
+
+    /// Save a conversation context under its scope and bump its version.
+    /// Fails with [`SwingBuddyError::StaleContext`] if the context was saved or
+    /// deleted by someone else since it was loaded, e.g. by a second button
+    /// press handled at the same time; new contexts replace whatever is stored.
+    pub async fn save_context(&self, context: &mut ConversationContext) -> Result<()> {
+        debug!(user_id = context.user_id, chat_id = ?context.chat_id, scenario = ?context.scenario,
+               step = ?context.step, version = context.version, backend = self.backend.name(), "Saving context");
+
+        let ttl = context_ttl(context, self.default_ttl);
+        let expected_version = context.version;
+        context.version += 1;
+
+        if !self.backend.put(context, expected_version, ttl).await? {
+            context.version = expected_version;
+            warn!(user_id = context.user_id, chat_id = ?context.chat_id, version = expected_version,
+                  "Context changed since it was loaded, not saving");
+            return Err(SwingBuddyError::StaleContext { user_id: context.user_id });
+        }
+
+        debug!(user_id = context.user_id, ttl_seconds = ttl.as_secs(), "Context saved");
+        Ok(())
+    }
+
+    /// Load the context under a scope, let `change` update it and save it,
+    /// starting over with the newer context when another save got in between.
+    /// `change` returns `false` to leave the context as it is. Returns the
+    /// saved context, or `None` if there was none or it was left unchanged.
+    pub async fn update_context<F>(&self, scope: ContextScope, mut change: F) -> Result<Option<ConversationContext>>
+    where
+        F: FnMut(&mut ConversationContext) -> Result<bool>,
+    {
+        let mut attempt = 1;
+        loop {
+            let Some(mut context) = self.load_scoped_context(scope).await? else {
+                return Ok(None);
+            };
+            if !change(&mut context)? {
+                return Ok(None);
             }
-        };
-        
-        let mut conn = self.connection_manager.clone();
-        
-        // Set the context with TTL
-        let ttl_seconds = if let Some(expires_at) = context.expires_at {
-            let now = chrono::Utc::now();
-            let duration = expires_at - now;
-            std::cmp::max(duration.num_seconds(), 60) as u64 // Minimum 60 seconds
-        } else {
-            self.config.ttl_seconds
-        };
 
-        match conn.set_ex::<_, _, ()>(&key, serialized, ttl_seconds).await {
-            Ok(_) => {
-                debug!(user_id = context.user_id, ttl_seconds = ttl_seconds, "Context saved to Redis successfully");
-                Ok(())
-            },
-            Err(e) => {
-                error!(user_id = context.user_id, error = %e, "Failed to save context to Redis");
-                Err(e.into())
+            match self.save_context(&mut context).await {
+                Ok(()) => return Ok(Some(context)),
+                Err(SwingBuddyError::StaleContext { .. }) if attempt < MAX_UPDATE_ATTEMPTS => {
+                    debug!(user_id = scope.0, attempt = attempt, "Context changed while updating it, retrying");
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
             }
         }
     }
 
-    /// Load conversation context from Redis
+    /// Load a user's private conversation context
     pub async fn load_context(&self, user_id: i64) -> Result<Option<ConversationContext>> {
-        let key = self.get_context_key(user_id);
-        debug!(user_id = user_id, key = %key, "Loading context from Redis");
-        
-        let mut conn = self.connection_manager.clone();
-        
-        let serialized: Option<String> = match conn.get::<&str, Option<String>>(&key).await {
-            Ok(data) => {
-                debug!(user_id = user_id, has_data = data.is_some(), "Redis GET result");
-                data
-            },
-            Err(e) => {
-                error!(user_id = user_id, error = %e, "Failed to get context from Redis");
-                return Err(e.into());
-            }
+        self.load_scoped_context((user_id, None)).await
+    }
+
+    /// Load a user's conversation context in a group chat
+    pub async fn load_chat_context(&self, user_id: i64, chat_id: i64) -> Result<Option<ConversationContext>> {
+        self.load_scoped_context((user_id, Some(chat_id))).await
+    }
+
+    /// Load the context stored under a scope
+    pub async fn load_scoped_context(&self, scope: ContextScope) -> Result<Option<ConversationContext>> {
+        let (user_id, chat_id) = scope;
+        debug!(user_id = user_id, chat_id = ?chat_id, backend = self.backend.name(), "Loading context");
+
+        let Some(context) = self.backend.get(scope).await? else {
+            debug!(user_id = user_id, "No context found");
+            return Ok(None);
         };
-        
-        match serialized {
-            Some(data) => {
-                debug!(user_id = user_id, data_length = data.len(), "Deserializing context data");
-                let context: ConversationContext = match serde_json::from_str::<ConversationContext>(&data) {
-                    Ok(ctx) => {
-                        debug!(user_id = user_id, scenario = ?ctx.scenario, step = ?ctx.step,
-                               "Context deserialized successfully");
-                        ctx
-                    },
-                    Err(e) => {
-                        error!(user_id = user_id, error = %e, "Failed to deserialize context");
-                        return Err(e.into());
-                    }
-                };
-                
-                // Check if context has expired
-                if context.is_expired() {
-                    warn!(user_id = user_id, expires_at = ?context.expires_at, "Context has expired, removing");
-                    self.delete_context(user_id).await?;
-                    return Ok(None);
-                }
-                
-                debug!(user_id = user_id, scenario = ?context.scenario, step = ?context.step,
-                       "Context loaded successfully");
-                Ok(Some(context))
-            }
-            None => {
-                debug!(user_id = user_id, "No context found in Redis");
-                Ok(None)
+
+        // Check if context has expired
+        if context.is_expired() {
+            warn!(user_id = user_id, expires_at = ?context.expires_at, "Context has expired, removing");
+            self.delete_scoped_context(scope).await?;
+            self.cleaned_up.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+
+        debug!(user_id = user_id, scenario = ?context.scenario, step = ?context.step,
+               "Context loaded successfully");
+        Ok(Some(context))
+    }
+
+    /// Load all of a user's contexts: the private one first, then those of group chats
+    pub async fn load_user_contexts(&self, user_id: i64) -> Result<Vec<ConversationContext>> {
+        let mut scopes: Vec<ContextScope> = self.get_active_scopes().await?
+            .into_iter()
+            .filter(|(scope_user_id, _)| *scope_user_id == user_id)
+            .collect();
+        scopes.sort();
+
+        let mut contexts = Vec::with_capacity(scopes.len());
+        for scope in scopes {
+            if let Some(context) = self.load_scoped_context(scope).await? {
+                contexts.push(context);
             }
         }
+        Ok(contexts)
     }
 
-    /// Delete conversation context from Redis
+    /// Delete a user's private conversation context
     pub async fn delete_context(&self, user_id: i64) -> Result<()> {
-        let key = self.get_context_key(user_id);
-        let mut conn = self.connection_manager.clone();
-        
-        let deleted: u32 = conn.del(&key).await?;
-        
-        if deleted > 0 {
-            debug!("Deleted context for user {}", user_id);
+        self.delete_scoped_context((user_id, None)).await
+    }
+
+    /// Delete a user's conversation context in a group chat
+    pub async fn delete_chat_context(&self, user_id: i64, chat_id: i64) -> Result<()> {
+        self.delete_scoped_context((user_id, Some(chat_id))).await
+    }
+
+    /// Delete the context stored under a scope
+    pub async fn delete_scoped_context(&self, scope: ContextScope) -> Result<()> {
+        let (user_id, chat_id) = scope;
+        if self.backend.delete(scope).await? {
+            debug!(user_id = user_id, chat_id = ?chat_id, "Deleted context");
         } else {
-            debug!("No context to delete for user {}", user_id);
+            debug!(user_id = user_id, chat_id = ?chat_id, "No context to delete");
         }
         
         Ok(())
@@ -141,69 +260,80 @@ impl StateStorage {
 
     /// Check if context exists for a user
     pub async fn context_exists(&self, user_id: i64) -> Result<bool> {
-        let key = self.get_context_key(user_id);
-        let mut conn = self.connection_manager.clone();
-        
-        let exists: bool = conn.exists(&key).await?;
-        Ok(exists)
+        self.backend.exists((user_id, None)).await
     }
 
     /// Extend the TTL of a context
     pub async fn extend_context_ttl(&self, user_id: i64, additional_seconds: u64) -> Result<bool> {
-        let key = self.get_context_key(user_id);
-        let mut conn = self.connection_manager.clone();
-        
-        // Get current TTL
-        let current_ttl: i64 = conn.ttl(&key).await?;
-        
-        if current_ttl > 0 {
-            let new_ttl = current_ttl as u64 + additional_seconds;
-            let result: bool = conn.expire(&key, new_ttl as i64).await?;
-            
-            if result {
-                debug!("Extended TTL for user {} to {}s", user_id, new_ttl);
-            }
-            
-            Ok(result)
-        } else {
-            // Key doesn't exist or has no expiry
-            Ok(false)
+        let extended = self.backend.extend((user_id, None), Duration::from_secs(additional_seconds)).await?;
+        if extended {
+            debug!("Extended TTL for user {} by {}s", user_id, additional_seconds);
         }
+        Ok(extended)
     }
 
-    /// Get all active user contexts (for cleanup/monitoring)
+    /// Get the users with an active private context (for reminders/monitoring)
     pub async fn get_active_users(&self) -> Result<Vec<i64>> {
-        let pattern = format!("{}context:*", self.config.prefix);
-        let mut conn = self.connection_manager.clone();
-        
-        let keys: Vec<String> = conn.keys(&pattern).await?;
-        
-        let mut user_ids = Vec::new();
-        for key in keys {
-            if let Some(user_id_str) = key.strip_prefix(&format!("{}context:", self.config.prefix)) {
-                if let Ok(user_id) = user_id_str.parse::<i64>() {
-                    user_ids.push(user_id);
+        let user_ids: Vec<i64> = self.get_active_scopes().await?
+            .into_iter()
+            .filter_map(|(user_id, chat_id)| chat_id.is_none().then_some(user_id))
+            .collect();
+
+        debug!("Found {} active user contexts", user_ids.len());
+        Ok(user_ids)
+    }
+
+    /// Get the scopes of active contexts, group chat ones included (for monitoring).
+    /// Lists at most 10,000 of them, walking the store a page at a time.
+    pub async fn get_active_scopes(&self) -> Result<Vec<ContextScope>> {
+        let mut scopes = Vec::new();
+        let mut seen = HashSet::new();
+        let mut cursor = ScopeCursor::Start;
+        loop {
+            let (page, next) = self.backend.scopes_page(cursor, SCOPES_PAGE_SIZE).await?;
+            for scope in page {
+                if seen.insert(scope) {
+                    scopes.push(scope);
                 }
             }
+            if next == ScopeCursor::Start {
+                break;
+            }
+            if scopes.len() >= MAX_LISTED_SCOPES {
+                warn!(limit = MAX_LISTED_SCOPES, backend = self.backend.name(), "Too many conversation contexts to list them all");
+                scopes.truncate(MAX_LISTED_SCOPES);
+                break;
+            }
+            cursor = next;
         }
-        
-        debug!("Found {} active user contexts", user_ids.len());
-        Ok(user_ids)
+        Ok(scopes)
     }
 
-    /// Clean up expired contexts
+    /// Clean up expired contexts. Each run looks at a limited number of them,
+    /// going on where the previous run stopped.
     pub async fn cleanup_expired_contexts(&self) -> Result<u32> {
-        let active_users = self.get_active_users().await?;
-        let mut cleaned_count = 0;
-        
-        for user_id in active_users {
-            if let Ok(Some(context)) = self.load_context(user_id).await {
-                if context.is_expired() {
-                    self.delete_context(user_id).await?;
-                    cleaned_count += 1;
+        let mut cleaned_count = self.backend.purge_expired().await? as u32;
+        let mut cursor = *self.cleanup_cursor.lock().unwrap_or_else(|e| e.into_inner());
+        let mut checked = 0;
+
+        loop {
+            let (page, next) = self.backend.scopes_page(cursor, SCOPES_PAGE_SIZE).await?;
+            checked += page.len();
+            for scope in page {
+                if let Ok(Some(context)) = self.backend.get(scope).await {
+                    if context.is_expired() {
+                        self.delete_scoped_context(scope).await?;
+                        cleaned_count += 1;
+                    }
                 }
             }
+            cursor = next;
+            if cursor == ScopeCursor::Start || checked >= MAX_CLEANUP_CHECKS {
+                break;
+            }
         }
+        *self.cleanup_cursor.lock().unwrap_or_else(|e| e.into_inner()) = cursor;
+        self.cleaned_up.fetch_add(cleaned_count as u64, Ordering::Relaxed);
         
         if cleaned_count > 0 {
             info!("Cleaned up {} expired contexts", cleaned_count);
@@ -212,21 +342,48 @@ impl StateStorage {
         Ok(cleaned_count)
     }
 
+    /// Start purging expired contexts every `[state] cleanup_interval_seconds`,
+    /// for backends where they don't expire on their own
+    pub fn start_cleanup(self) -> Option<tokio::task::JoinHandle<()>> {
+        if !self.backend.needs_cleanup() {
+            return None;
+        }
+
+        info!(backend = self.backend.name(), interval = ?self.cleanup_interval, "Starting conversation state cleanup");
+
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.cleanup_interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.cleanup_expired_contexts().await {
+                    error!(error = %e, "Failed to clean up expired contexts");
+                }
+            }
+        }))
+    }
+
     /// Get storage statistics
     pub async fn get_stats(&self) -> Result<StorageStats> {
-        let active_users = self.get_active_users().await?;
-        let total_contexts = active_users.len();
+        let active_scopes = self.get_active_scopes().await?;
+        let total_contexts = active_scopes.len();
         
         let mut expired_contexts = 0;
+        let mut paused_contexts = 0;
         let mut scenarios_count = std::collections::HashMap::new();
         
-        for user_id in &active_users {
-            if let Ok(Some(context)) = self.load_context(*user_id).await {
+        // Read the backend directly, so expired contexts are counted rather than removed
+        for scope in &active_scopes {
+            if let Ok(Some(context)) = self.backend.get(*scope).await {
                 if context.is_expired() {
                     expired_contexts += 1;
-                } else if let Some(scenario) = &context.scenario {
+                    continue;
+                }
+                if let Some(scenario) = &context.scenario {
                     *scenarios_count.entry(scenario.clone()).or_insert(0) += 1;
                 }
+                if !context.paused.is_empty() {
+                    paused_contexts += 1;
+                }
             }
         }
         
@@ -234,17 +391,19 @@ impl StateStorage {
             total_contexts,
             expired_contexts,
             active_contexts: total_contexts - expired_contexts,
+            paused_contexts,
+            cleaned_up: self.cleaned_up.load(Ordering::Relaxed),
             scenarios_count,
         })
     }
 
     /// Backup all contexts to a JSON string
     pub async fn backup_contexts(&self) -> Result<String> {
-        let active_users = self.get_active_users().await?;
+        let active_scopes = self.get_active_scopes().await?;
         let mut contexts = Vec::new();
         
-        for user_id in active_users {
-            if let Ok(Some(context)) = self.load_context(user_id).await {
+        for scope in active_scopes {
+            if let Ok(Some(context)) = self.load_scoped_context(scope).await {
                 if !context.is_expired() {
                     contexts.push(context);
                 }
@@ -264,7 +423,7 @@ impl StateStorage {
         
         for context in contexts {
             if !context.is_expired() {
-                self.save_context(&context).await?;
+                self.backend.put(&context, 0, context_ttl(&context, self.default_ttl)).await?;
                 restored_count += 1;
             }
         }
@@ -273,38 +432,17 @@ impl StateStorage {
         Ok(restored_count)
     }
 
-    /// Get the Redis key for a user's context
-    fn get_context_key(&self, user_id: i64) -> String {
-        format!("{}context:{}", self.config.prefix, user_id)
-    }
-
-    /// Test Redis connection
+    /// Test the backend connection
     pub async fn test_connection(&self) -> Result<()> {
-        let mut conn = self.connection_manager.clone();
-        let _: String = redis::cmd("PING").query_async(&mut conn).await?;
-        Ok(())
+        self.backend.ping().await
     }
 
-    /// Get Redis connection info
+    /// Get backend connection info
     pub async fn get_connection_info(&self) -> Result<ConnectionInfo> {
-        let mut conn = self.connection_manager.clone();
-        
-        // Get Redis info
-        let info: String = redis::cmd("INFO").arg("server").query_async(&mut conn).await?;
-        let mut redis_version = "unknown".to_string();
-        
-        for line in info.lines() {
-            if line.starts_with("redis_version:") {
-                redis_version = line.split(':').nth(1).unwrap_or("unknown").to_string();
-                break;
-            }
-        }
-        
         Ok(ConnectionInfo {
-            redis_version,
-            url: self.config.url.clone(),
-            prefix: self.config.prefix.clone(),
-            default_ttl: self.config.ttl_seconds,
+            backend: self.backend.name().to_string(),
+            version: self.backend.server_version().await?,
+            default_ttl: self.default_ttl.as_secs(),
         })
     }
 }
@@ -312,26 +450,41 @@ impl StateStorage {
 impl std::fmt::Debug for StateStorage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("StateStorage")
-            .field("config", &self.config)
+            .field("backend", &self.backend.name())
+            .field("default_ttl", &self.default_ttl)
             .finish_non_exhaustive()
     }
 }
 
+/// How long a context is stored for: until it expires, or the default
+fn context_ttl(context: &ConversationContext, default_ttl: Duration) -> Duration {
+    match context.expires_at {
+        Some(expires_at) => (expires_at - chrono::Utc::now()).to_std()
+            .unwrap_or_default()
+            .max(MIN_CONTEXT_TTL),
+        None => default_ttl,
+    }
+}
+
 /// Storage statistics
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct StorageStats {
     pub total_contexts: usize,
     pub active_contexts: usize,
     pub expired_contexts: usize,
+    /// Contexts with scenarios put aside for an interrupting command
+    pub paused_contexts: usize,
+    /// Expired contexts removed since startup
+    pub cleaned_up: u64,
+    /// Contexts in progress by scenario
     pub scenarios_count: std::collections::HashMap<String, u32>,
 }
 
 /// Connection information
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ConnectionInfo {
-    pub redis_version: String,
-    pub url: String,
-    pub prefix: String,
+    pub backend: String,
+    pub version: String,
     pub default_ttl: u64,
 }
 
@@ -408,6 +561,289 @@ impl Drop for StateStorageManager {
     }
 }
 
+/// Redis backend, falling back to memory while Redis is unreachable
+struct RedisStateBackend {
+    /// Redis connections, following Sentinel failovers
+    redis: RedisConnector,
+    /// Redis configuration
+    config: RedisConfig,
+    /// Contexts saved while Redis was unreachable
+    fallback: MemoryStateStore,
+    health: Mutex<RedisHealth>,
+}
+
+impl RedisStateBackend {
+    /// Create the backend, trying Redis once right away
+    async fn connect(config: RedisConfig) -> Result<Self> {
+        let backend = Self {
+            redis: RedisConnector::new(&config)?,
+            config,
+            fallback: MemoryStateStore::default(),
+            health: Mutex::new(RedisHealth::default()),
+        };
+
+        backend.connection().await;
+        Ok(backend)
+    }
+
+    /// Get the Redis key of the context stored under a scope
+    fn get_context_key(&self, scope: ContextScope) -> String {
+        match scope {
+            (user_id, None) => format!("{}context:{}", self.config.prefix, user_id),
+            (user_id, Some(chat_id)) => format!("{}context:{}:{}", self.config.prefix, user_id, chat_id),
+        }
+    }
+
+    /// Get a context kept in memory during an outage, dropping it if it expired
+    fn load_fallback(&self, scope: ContextScope) -> Option<ConversationContext> {
+        let context = self.fallback.get(scope)?;
+        if context.is_expired() {
+            self.fallback.remove(scope);
+            return None;
+        }
+        debug!(user_id = scope.0, chat_id = ?scope.1, "Context loaded from memory while Redis is unavailable");
+        Some(context)
+    }
+
+    /// Get a Redis connection, or `None` while Redis is failing and not due for a retry
+    async fn connection(&self) -> Option<ConnectionManager> {
+        let due = self.health.lock().unwrap_or_else(|e| e.into_inner())
+            .retry_at
+            .is_none_or(|retry_at| Instant::now() >= retry_at);
+        if !due {
+            return None;
+        }
+
+        match tokio::time::timeout(REDIS_TIMEOUT, self.redis.connection_manager()).await {
+            Ok(Ok(conn)) => Some(conn),
+            Ok(Err(e)) => {
+                self.redis_failed(&e);
+                None
+            }
+            Err(_) => {
+                self.redis_failed(&"connection timed out");
+                None
+            }
+        }
+    }
+
+    /// Run a Redis command within the time limit. Returns `None` if it failed.
+    async fn run<T>(&self, command: impl Future<Output = redis::RedisResult<T>>) -> Option<T> {
+        match tokio::time::timeout(REDIS_TIMEOUT, command).await {
+            Ok(Ok(value)) => {
+                self.redis_recovered();
+                Some(value)
+            }
+            Ok(Err(e)) => {
+                self.redis_failed(&e);
+                None
+            }
+            Err(_) => {
+                self.redis_failed(&"command timed out");
+                None
+            }
+        }
+    }
+
+    fn redis_failed(&self, error: &dyn std::fmt::Display) {
+        let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        if health.failing_since.is_none() {
+            warn!(error = %error, "Redis unavailable, keeping conversation state in memory; contexts may be lost on restart");
+            health.failing_since = Some(Instant::now());
+        }
+        health.retry_at = Some(Instant::now() + REDIS_RETRY_INTERVAL);
+    }
+
+    fn redis_recovered(&self) {
+        let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(since) = health.failing_since.take() {
+            warn!(outage = ?since.elapsed(), in_memory = self.fallback.len(), "Redis available again, storing conversation state in Redis");
+        }
+        health.retry_at = None;
+    }
+}
+
+#[async_trait]
+impl StateBackend for RedisStateBackend {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn put(&self, context: &ConversationContext, expected_version: u64, ttl: Duration) -> Result<bool> {
+        let key = self.get_context_key(context.scope());
+        let serialized = match serde_json::to_string(context) {
+            Ok(data) => {
+                debug!(user_id = context.user_id, key = %key, data_length = data.len(), "Context serialized successfully");
+                data
+            },
+            Err(e) => {
+                error!(user_id = context.user_id, error = %e, "Failed to serialize context");
+                return Err(e.into());
+            }
+        };
+
+        if let Some(mut conn) = self.connection().await {
+            let mut invocation = SAVE_CONTEXT_SCRIPT.prepare_invoke();
+            invocation.key(&key).arg(expected_version).arg(serialized).arg(ttl.as_secs());
+            if let Some(stored) = self.run(invocation.invoke_async::<_, bool>(&mut conn)).await {
+                if stored {
+                    self.fallback.remove(context.scope());
+                }
+                return Ok(stored);
+            }
+        }
+
+        // Contexts kept in memory can only be checked against what was saved during the outage
+        let stale = expected_version > 0 && self.fallback.get(context.scope())
+            .is_some_and(|stored| stored.version != expected_version);
+        if stale {
+            return Ok(false);
+        }
+
+        self.fallback.insert(context.clone(), ttl);
+        debug!(user_id = context.user_id, "Context saved in memory while Redis is unavailable");
+        Ok(true)
+    }
+
+    async fn get(&self, scope: ContextScope) -> Result<Option<ConversationContext>> {
+        let Some(mut conn) = self.connection().await else {
+            return Ok(self.load_fallback(scope));
+        };
+
+        // A context saved in memory during an outage is newer than the one in Redis
+        if let Some(context) = self.fallback.remove(scope) {
+            if !context.is_expired() {
+                debug!(user_id = context.user_id, "Moving context saved during a Redis outage to Redis");
+                self.put(&context, 0, context_ttl(&context, Duration::from_secs(self.config.ttl_seconds))).await?;
+                return Ok(Some(context));
+            }
+        }
+
+        let key = self.get_context_key(scope);
+        let Some(serialized) = self.run(conn.get::<&str, Option<String>>(&key)).await else {
+            return Ok(self.load_fallback(scope));
+        };
+
+        match serialized {
+            Some(data) => match serde_json::from_str::<ConversationContext>(&data) {
+                Ok(context) => Ok(Some(context)),
+                Err(e) => {
+                    error!(user_id = scope.0, error = %e, "Failed to deserialize context");
+                    Err(e.into())
+                }
+            },
+            None => Ok(None),
+        }
+    }
+
+    async fn delete(&self, scope: ContextScope) -> Result<bool> {
+        let in_memory = self.fallback.remove(scope).is_some();
+
+        let deleted = match self.connection().await {
+            Some(mut conn) => self.run(conn.del::<_, u32>(self.get_context_key(scope))).await.unwrap_or(0),
+            None => 0,
+        };
+
+        Ok(deleted > 0 || in_memory)
+    }
+
+    async fn exists(&self, scope: ContextScope) -> Result<bool> {
+        if self.fallback.get(scope).is_some() {
+            return Ok(true);
+        }
+
+        let exists = match self.connection().await {
+            Some(mut conn) => self.run(conn.exists::<_, bool>(self.get_context_key(scope))).await.unwrap_or(false),
+            None => false,
+        };
+        Ok(exists)
+    }
+
+    async fn extend(&self, scope: ContextScope, additional: Duration) -> Result<bool> {
+        if self.fallback.extend(scope, additional) {
+            return Ok(true);
+        }
+
+        let key = self.get_context_key(scope);
+        let Some(mut conn) = self.connection().await else {
+            return Ok(false);
+        };
+        
+        // Get current TTL
+        let current_ttl: i64 = self.run(conn.ttl(&key)).await.unwrap_or(0);
+        
+        if current_ttl > 0 {
+            let new_ttl = current_ttl + additional.as_secs() as i64;
+            Ok(self.run(conn.expire(&key, new_ttl)).await.unwrap_or(false))
+        } else {
+            // Key doesn't exist or has no expiry
+            Ok(false)
+        }
+    }
+
+    async fn scopes_page(&self, cursor: ScopeCursor, count: usize) -> Result<(Vec<ContextScope>, ScopeCursor)> {
+        let prefix = format!("{}context:", self.config.prefix);
+        let pattern = format!("{}*", prefix);
+        let cursor = match cursor {
+            ScopeCursor::Scan(cursor) => cursor,
+            ScopeCursor::Start | ScopeCursor::After(_) => 0,
+        };
+        // Contexts kept in memory during an outage come with the first page
+        let mut scopes = if cursor == 0 { self.fallback.scopes() } else { Vec::new() };
+
+        // SCAN walks the keyspace a bit at a time instead of blocking Redis like KEYS
+        let page: Option<(u64, Vec<String>)> = match self.connection().await {
+            Some(mut conn) => self.run(
+                redis::cmd("SCAN").arg(cursor).arg("MATCH").arg(&pattern).arg("COUNT").arg(count)
+                    .query_async(&mut conn)
+            ).await,
+            None => None,
+        };
+        let Some((next, keys)) = page else {
+            return Ok((scopes, ScopeCursor::Start));
+        };
+
+        for key in keys {
+            if let Some(scope) = key.strip_prefix(&prefix).and_then(parse_context_scope) {
+                if !scopes.contains(&scope) {
+                    scopes.push(scope);
+                }
+            }
+        }
+
+        let next = if next == 0 { ScopeCursor::Start } else { ScopeCursor::Scan(next) };
+        Ok((scopes, next))
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.health.lock().unwrap_or_else(|e| e.into_inner()).failing_since.is_some()
+    }
+
+    async fn ping(&self) -> Result<()> {
+        let mut conn = self.redis.connection_manager().await?;
+        let _: String = redis::cmd("PING").query_async(&mut conn).await?;
+        Ok(())
+    }
+
+    async fn server_version(&self) -> Result<String> {
+        let mut conn = self.redis.connection_manager().await?;
+        let info: String = redis::cmd("INFO").arg("server").query_async(&mut conn).await?;
+
+        Ok(info.lines()
+            .find_map(|line| line.strip_prefix("redis_version:"))
+            .unwrap_or("unknown")
+            .to_string())
+    }
+}
+
+/// Parse what follows the context key prefix: `<user_id>` or `<user_id>:<chat_id>`
+fn parse_context_scope(rest: &str) -> Option<ContextScope> {
+    match rest.split_once(':') {
+        Some((user_id, chat_id)) => Some((user_id.parse().ok()?, Some(chat_id.parse().ok()?))),
+        None => Some((rest.parse().ok()?, None)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -418,6 +854,7 @@ mod tests {
             url: "redis://localhost:6379".to_string(),
             prefix: "test_swingbuddy:".to_string(),
             ttl_seconds: 3600,
+            sentinel: None,
         }
     }
 
@@ -431,7 +868,7 @@ mod tests {
         context.set_data("key", "value").unwrap();
         
         // Save context
-        storage.save_context(&context).await.unwrap();
+        storage.save_context(&mut context).await.unwrap();
         
         // Load context
         let loaded = storage.load_context(123).await.unwrap();
@@ -459,22 +896,41 @@ mod tests {
         context.set_expiry(chrono::Utc::now() - chrono::Duration::hours(1));
         
         // Save context
-        storage.save_context(&context).await.unwrap();
+        storage.save_context(&mut context).await.unwrap();
         
         // Try to load - should return None due to expiry
         let loaded = storage.load_context(456).await.unwrap();
         assert!(loaded.is_none());
     }
 
+    #[tokio::test]
+    async fn test_stats_count_scenarios_and_paused() {
+        let config = create_test_config();
+        let storage = StateStorage::new(config).await.unwrap();
+
+        let mut active = ConversationContext::new(457);
+        active.start_scenario("stats_test", "step1").unwrap();
+        storage.save_context(&mut active).await.unwrap();
+
+        let mut paused = ConversationContext::new(458);
+        paused.start_scenario("stats_test", "step1").unwrap();
+        assert!(paused.pause_scenario());
+        storage.save_context(&mut paused).await.unwrap();
+
+        let stats = storage.get_stats().await.unwrap();
+        assert_eq!(stats.scenarios_count.get("stats_test"), Some(&1));
+        assert_eq!(stats.paused_contexts, 1);
+    }
+
     #[tokio::test]
     async fn test_context_deletion() {
         let config = create_test_config();
         let storage = StateStorage::new(config).await.unwrap();
         
-        let context = ConversationContext::new(789);
+        let mut context = ConversationContext::new(789);
         
         // Save context
-        storage.save_context(&context).await.unwrap();
+        storage.save_context(&mut context).await.unwrap();
         
         // Verify it exists
         assert!(storage.context_exists(789).await.unwrap());
@@ -485,4 +941,158 @@ mod tests {
         // Verify it's gone
         assert!(!storage.context_exists(789).await.unwrap());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_chat_context_is_separate() {
+        let config = create_test_config();
+        let storage = StateStorage::new(config).await.unwrap();
+
+        let mut private = ConversationContext::new(321);
+        private.start_scenario("event_creation", "title_input").unwrap();
+        storage.save_context(&mut private).await.unwrap();
+
+        let mut group = ConversationContext::for_chat(321, -100321);
+        group.start_scenario("group_setup", "welcome_message_input").unwrap();
+        storage.save_context(&mut group).await.unwrap();
+
+        let loaded = storage.load_context(321).await.unwrap().unwrap();
+        assert!(loaded.is_in_scenario("event_creation"));
+        let loaded = storage.load_chat_context(321, -100321).await.unwrap().unwrap();
+        assert!(loaded.is_in_scenario("group_setup"));
+        assert!(storage.load_chat_context(321, -100322).await.unwrap().is_none());
+
+        let scopes: Vec<ContextScope> = storage.load_user_contexts(321).await.unwrap()
+            .iter()
+            .map(ConversationContext::scope)
+            .collect();
+        assert_eq!(scopes, vec![(321, None), (321, Some(-100321))]);
+
+        storage.delete_chat_context(321, -100321).await.unwrap();
+        assert!(storage.load_chat_context(321, -100321).await.unwrap().is_none());
+        assert!(storage.load_context(321).await.unwrap().is_some());
+
+        // Cleanup
+        storage.delete_context(321).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stale_context_is_not_saved() {
+        let config = create_test_config();
+        let storage = StateStorage::new(config).await.unwrap();
+
+        let mut context = ConversationContext::new(654);
+        context.start_scenario("test", "step1").unwrap();
+        storage.save_context(&mut context).await.unwrap();
+        assert_eq!(context.version, 1);
+
+        // Two handlers load the context at the same time; the second save loses
+        let mut first = storage.load_context(654).await.unwrap().unwrap();
+        let mut second = storage.load_context(654).await.unwrap().unwrap();
+        first.next_step("step2").unwrap();
+        storage.save_context(&mut first).await.unwrap();
+        second.next_step("step3").unwrap();
+        assert!(matches!(
+            storage.save_context(&mut second).await,
+            Err(SwingBuddyError::StaleContext { user_id: 654 })
+        ));
+        assert_eq!(second.version, 1);
+
+        // Updates start over with the newer context
+        let updated = storage.update_context((654, None), |context| {
+            context.set_data("seen", true)?;
+            Ok(true)
+        }).await.unwrap().unwrap();
+        assert_eq!(updated.step, Some("step2".to_string()));
+        assert_eq!(updated.version, 3);
+
+        // Cleanup
+        storage.delete_context(654).await.unwrap();
+    }
+
+    #[test]
+    fn test_context_ttl() {
+        let default_ttl = Duration::from_secs(3600);
+        let mut context = ConversationContext::new(1);
+        assert_eq!(context_ttl(&context, default_ttl), default_ttl);
+
+        context.set_expiry(chrono::Utc::now() + chrono::Duration::hours(2));
+        assert!(context_ttl(&context, default_ttl) > Duration::from_secs(7000));
+
+        // Contexts about to expire are still kept for a minute
+        context.set_expiry(chrono::Utc::now() - chrono::Duration::hours(1));
+        assert_eq!(context_ttl(&context, default_ttl), MIN_CONTEXT_TTL);
+    }
+
+    /// A backend serving fixed pages of scopes, like a Redis SCAN that
+    /// returns some scopes twice
+    struct PagedBackend {
+        pages: Vec<Vec<ContextScope>>,
+    }
+
+    #[async_trait]
+    impl StateBackend for PagedBackend {
+        fn name(&self) -> &'static str {
+            "paged"
+        }
+
+        async fn put(&self, _context: &ConversationContext, _expected_version: u64, _ttl: Duration) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn get(&self, _scope: ContextScope) -> Result<Option<ConversationContext>> {
+            Ok(None)
+        }
+
+        async fn delete(&self, _scope: ContextScope) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn exists(&self, _scope: ContextScope) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn extend(&self, _scope: ContextScope, _additional: Duration) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn scopes_page(&self, cursor: ScopeCursor, _count: usize) -> Result<(Vec<ContextScope>, ScopeCursor)> {
+            let index = match cursor {
+                ScopeCursor::Scan(index) => index as usize,
+                _ => 0,
+            };
+            let next = if index + 1 < self.pages.len() { ScopeCursor::Scan(index as u64 + 1) } else { ScopeCursor::Start };
+            Ok((self.pages[index].clone(), next))
+        }
+
+        async fn ping(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn server_version(&self) -> Result<String> {
+            Ok("test".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_active_scopes_deduplicated_across_pages() {
+        let backend = PagedBackend {
+            pages: vec![
+                vec![(1, None), (2, Some(-100)), (1, None)],
+                vec![(3, None), (2, Some(-100))],
+                vec![(2, None), (1, None)],
+            ],
+        };
+        let storage = StateStorage::with_backend(Arc::new(backend), Duration::from_secs(60));
+
+        let scopes = storage.get_active_scopes().await.unwrap();
+        assert_eq!(scopes, vec![(1, None), (2, Some(-100)), (3, None), (2, None)]);
+    }
+
+    #[test]
+    fn test_parse_context_scope() {
+        assert_eq!(parse_context_scope("42"), Some((42, None)));
+        assert_eq!(parse_context_scope("42:-1001234"), Some((42, Some(-1001234))));
+        assert_eq!(parse_context_scope("42:x"), None);
+        assert_eq!(parse_context_scope("lock:42"), None);
+    }
+}
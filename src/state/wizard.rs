@@ -0,0 +1,401 @@
+//! Inline-keyboard wizards
+//!
+//! A wizard runs a scenario straight from its definition: every step that needs
+//! input is shown as a message with buttons for its choices, Skip, Back and
+//! Cancel, typed answers are checked against the step's validation, and the
+//! buttons carry [`CallbackData::Wizard`] with the action [`Wizard::act`]
+//! handles. A wizard finishes at the first step that needs no
+//! input; what happens then is up to the scenario's handler.
+//!
+//! Step texts are the step's prompt or `wizard.<scenario>.<step>`, with the
+//! answers given so far as placeholders. Choice buttons are labelled
+//! `buttons.wizard.<choice>`.
+//!
+//! Media steps take photos or documents instead of text. Steps taking more
+//! than one file keep them until the user presses Done or the step is full.
+
+use crate::handlers::callbacks::CallbackData;
+use crate::i18n::{I18n, TranslationParams};
+use crate::utils::errors::{SwingBuddyError, Result};
+use super::context::ConversationContext;
+use super::scenarios::{InputType, MediaItem, ScenarioManager};
+
+/// What a wizard button does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WizardAction {
+    /// Answer the step with one of its choices
+    Choose(String),
+    Skip,
+    Back,
+    Cancel,
+    /// Finish collecting files for a media step
+    Done,
+}
+
+impl WizardAction {
+    /// Parse the action as written in callback data, e.g. `choose:create`
+    pub fn parse(data: &str) -> Option<Self> {
+        match data.split_once(':').unwrap_or((data, "")) {
+            ("choose", value) if !value.is_empty() => Some(Self::Choose(value.to_string())),
+            ("skip", _) => Some(Self::Skip),
+            ("back", _) => Some(Self::Back),
+            ("cancel", _) => Some(Self::Cancel),
+            ("done", _) => Some(Self::Done),
+            _ => None,
+        }
+    }
+
+    /// The action as written in callback data
+    pub fn as_data(&self) -> String {
+        match self {
+            Self::Choose(value) => format!("choose:{}", value),
+            Self::Skip => "skip".to_string(),
+            Self::Back => "back".to_string(),
+            Self::Cancel => "cancel".to_string(),
+            Self::Done => "done".to_string(),
+        }
+    }
+
+    /// Callback data of a button doing this in a scenario
+    pub fn callback_data(&self, scenario: &str) -> String {
+        CallbackData::Wizard { scenario: scenario.to_string(), action: self.clone() }.encode()
+    }
+}
+
+/// Where an answer or a button took the wizard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WizardOutcome {
+    /// The wizard is at a step that asks for input
+    Next,
+    /// A file was kept; the media step takes more until Done. Carries the
+    /// number of files collected.
+    Collected(usize),
+    /// The wizard reached a step that needs no input, e.g. "create"
+    Finished(String),
+    /// The user cancelled the wizard
+    Cancelled,
+    /// The answer was rejected; carries the reason, a translation key or text
+    Invalid(String),
+}
+
+/// A step of a wizard, ready to be sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WizardScreen {
+    pub text: String,
+    /// Rows of (label, callback data) buttons
+    pub keyboard: Vec<Vec<(String, String)>>,
+}
+
+/// Runs scenarios as inline-keyboard wizards
+#[derive(Debug, Clone, Copy)]
+pub struct Wizard<'a> {
+    manager: &'a ScenarioManager,
+}
+
+impl<'a> Wizard<'a> {
+    pub fn new(manager: &'a ScenarioManager) -> Self {
+        Self { manager }
+    }
+
+    /// Data key the answer to a step is kept under: the step ID without `_input`
+    pub fn data_key(step_id: &str) -> &str {
+        step_id.strip_suffix("_input").unwrap_or(step_id)
+    }
+
+    /// Start a scenario as a wizard, passing over the steps it starts with
+    /// that need no input
+    pub fn start(&self, context: &mut ConversationContext, scenario_id: &str) -> Result<WizardOutcome> {
+        self.manager.start_scenario(context, scenario_id)?;
+        Ok(self.outcome_at(context))
+    }
+
+    /// Answer the current step with a typed text or a chosen value
+    pub fn answer(&self, context: &mut ConversationContext, input: &str) -> Result<WizardOutcome> {
+        let input = input.trim();
+        match self.manager.validate_input(context, input) {
+            Ok(()) => {}
+            Err(SwingBuddyError::InvalidInput(reason)) => return Ok(WizardOutcome::Invalid(reason)),
+            Err(e) => return Err(e),
+        }
+
+        let step = self.manager.get_current_step(context)?;
+        context.set_data(Self::data_key(&step.id), input)?;
+
+        // A choice named after one of the next steps leads there
+        let next = if step.next_steps.iter().any(|next| next == input) {
+            Some(input.to_string())
+        } else {
+            self.manager.following_step(context).map(str::to_string)
+        };
+        self.advance(context, next)
+    }
+
+    /// Files collected for a media step
+    pub fn media_items(context: &ConversationContext, step_id: &str) -> Vec<MediaItem> {
+        context.get_data(Self::data_key(step_id)).ok().flatten().unwrap_or_default()
+    }
+
+    /// Answer the current media step with a file. Steps taking one file move
+    /// on right away; others collect files until they are full.
+    pub fn add_media(&self, context: &mut ConversationContext, item: MediaItem) -> Result<WizardOutcome> {
+        match self.manager.validate_media(context, item.kind) {
+            Ok(()) => {}
+            Err(SwingBuddyError::InvalidInput(reason)) => return Ok(WizardOutcome::Invalid(reason)),
+            Err(e) => return Err(e),
+        }
+
+        let step = self.manager.get_current_step(context)?;
+        let max_items = match step.validation.as_ref().map(|validation| &validation.input_type) {
+            Some(InputType::Media { max_items, .. }) => *max_items,
+            _ => 1,
+        };
+        let mut items = Self::media_items(context, &step.id);
+        items.push(item);
+        let collected = items.len();
+        context.set_data(Self::data_key(&step.id), items)?;
+
+        if collected < max_items {
+            return Ok(WizardOutcome::Collected(collected));
+        }
+        let next = self.manager.following_step(context).map(str::to_string);
+        self.advance(context, next)
+    }
+
+    /// Handle a wizard button
+    pub fn act(&self, context: &mut ConversationContext, action: &WizardAction) -> Result<WizardOutcome> {
+        match action {
+            WizardAction::Choose(value) => self.answer(context, value),
+            WizardAction::Skip => {
+                let step = self.manager.get_current_step(context)?;
+                if !step.skippable {
+                    return Ok(WizardOutcome::Invalid("messages.wizard.cannot_skip".to_string()));
+                }
+                context.remove_data(Self::data_key(&step.id));
+                let next = self.manager.following_step(context).map(str::to_string);
+                self.advance(context, next)
+            }
+            WizardAction::Back => {
+                if self.manager.back(context).is_err() {
+                    return Ok(WizardOutcome::Invalid("commands.back.unavailable".to_string()));
+                }
+                Ok(WizardOutcome::Next)
+            }
+            WizardAction::Cancel => {
+                context.cancel_scenario();
+                Ok(WizardOutcome::Cancelled)
+            }
+            WizardAction::Done => {
+                let step = self.manager.get_current_step(context)?;
+                let min_items = step.validation.as_ref().and_then(|validation| validation.min_length).unwrap_or(1);
+                if Self::media_items(context, &step.id).len() < min_items {
+                    return Ok(WizardOutcome::Invalid("messages.wizard.too_few_files".to_string()));
+                }
+                let next = self.manager.following_step(context).map(str::to_string);
+                self.advance(context, next)
+            }
+        }
+    }
+
+    /// Render the current step in a language
+    pub fn render(&self, context: &ConversationContext, i18n: &I18n, language_code: &str) -> Result<WizardScreen> {
+        let scenario = context.scenario.as_deref()
+            .ok_or_else(|| SwingBuddyError::InvalidInput("No active scenario".to_string()))?;
+        let step = self.manager.get_current_step(context)?;
+
+        // The answers so far fill the text's placeholders, e.g. a summary to
+        // confirm; collected files by their number
+        let params: TranslationParams = context.data.iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_json::Value::String(text) => text.clone(),
+                    serde_json::Value::Array(items) => items.len().to_string(),
+                    _ => value.to_string(),
+                };
+                (key.clone(), value)
+            })
+            .collect();
+        let key = step.prompt.clone().unwrap_or_else(|| format!("wizard.{}.{}", scenario, step.id));
+        let text = i18n.t(&key, language_code, Some(&params));
+
+        let mut keyboard = Vec::new();
+        if let Some(InputType::Choice(choices)) = step.validation.as_ref().map(|validation| &validation.input_type) {
+            keyboard.extend(choices.iter().map(|choice| vec![(
+                i18n.t(&format!("buttons.wizard.{}", choice), language_code, None),
+                WizardAction::Choose(choice.clone()).callback_data(scenario),
+            )]));
+        }
+
+        let mut navigation = Vec::new();
+        if self.manager.can_go_back(context) {
+            navigation.push((i18n.t("buttons.wizard.back", language_code, None), WizardAction::Back.callback_data(scenario)));
+        }
+        if step.skippable {
+            navigation.push((i18n.t("buttons.wizard.skip", language_code, None), WizardAction::Skip.callback_data(scenario)));
+        }
+        if let Some(InputType::Media { max_items, .. }) = step.validation.as_ref().map(|validation| &validation.input_type) {
+            if *max_items > 1 {
+                navigation.push((i18n.t("buttons.wizard.done", language_code, None), WizardAction::Done.callback_data(scenario)));
+            }
+        }
+        navigation.push((i18n.t("buttons.wizard.cancel", language_code, None), WizardAction::Cancel.callback_data(scenario)));
+        keyboard.push(navigation);
+
+        Ok(WizardScreen { text, keyboard })
+    }
+
+    /// Move on to a step, or finish when the current step leads nowhere
+    fn advance(&self, context: &mut ConversationContext, next: Option<String>) -> Result<WizardOutcome> {
+        match next {
+            Some(next) => {
+                self.manager.next_step(context, &next)?;
+                Ok(self.outcome_at(context))
+            }
+            None => {
+                let step = context.step.clone().unwrap_or_default();
+                Ok(WizardOutcome::Finished(step))
+            }
+        }
+    }
+
+    fn outcome_at(&self, context: &ConversationContext) -> WizardOutcome {
+        match self.manager.get_current_step(context) {
+            Ok(step) if !step.requires_input => WizardOutcome::Finished(step.id.clone()),
+            _ => WizardOutcome::Next,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wizard_at_title() -> (ScenarioManager, ConversationContext) {
+        let manager = ScenarioManager::new();
+        let mut context = ConversationContext::new(1);
+        assert_eq!(Wizard::new(&manager).start(&mut context, "event_creation").unwrap(), WizardOutcome::Next);
+        (manager, context)
+    }
+
+    #[test]
+    fn test_action_parsing() {
+        assert_eq!(WizardAction::parse("choose:create"), Some(WizardAction::Choose("create".to_string())));
+        assert_eq!(WizardAction::parse("skip"), Some(WizardAction::Skip));
+        assert_eq!(WizardAction::parse("choose"), None);
+        assert_eq!(WizardAction::parse("jump"), None);
+        assert_eq!(WizardAction::Back.callback_data("event_creation"), "1w:event_creation:back");
+    }
+
+    #[test]
+    fn test_event_creation_walkthrough() {
+        let (manager, mut context) = wizard_at_title();
+        let wizard = Wizard::new(&manager);
+
+        assert!(matches!(wizard.answer(&mut context, "ab").unwrap(), WizardOutcome::Invalid(_)));
+        assert!(context.is_at_step("title_input"));
+
+        assert_eq!(wizard.answer(&mut context, "Friday Lindy Social").unwrap(), WizardOutcome::Next);
+        assert_eq!(wizard.act(&mut context, &WizardAction::Skip).unwrap(), WizardOutcome::Next);
+        assert!(context.is_at_step("date_input"));
+
+        // Only skippable steps can be skipped
+        assert!(matches!(wizard.act(&mut context, &WizardAction::Skip).unwrap(), WizardOutcome::Invalid(_)));
+
+        wizard.answer(&mut context, "2026-11-06").unwrap();
+        wizard.answer(&mut context, "20:00").unwrap();
+        wizard.answer(&mut context, "Ballroom, Main St 1").unwrap();
+        assert!(context.is_at_step("confirmation"));
+        assert_eq!(context.get_string("title").as_deref(), Some("Friday Lindy Social"));
+        assert_eq!(context.get_string("date").as_deref(), Some("2026-11-06"));
+        assert_eq!(context.get_string("description"), None);
+
+        let outcome = wizard.act(&mut context, &WizardAction::Choose("create".to_string())).unwrap();
+        assert_eq!(outcome, WizardOutcome::Finished("create".to_string()));
+    }
+
+    #[test]
+    fn test_choice_leads_to_its_step() {
+        let (manager, mut context) = wizard_at_title();
+        context.next_step("confirmation").unwrap();
+
+        let outcome = Wizard::new(&manager).answer(&mut context, "cancel").unwrap();
+        assert_eq!(outcome, WizardOutcome::Finished("cancel".to_string()));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let (manager, mut context) = wizard_at_title();
+
+        let outcome = Wizard::new(&manager).act(&mut context, &WizardAction::Cancel).unwrap();
+        assert_eq!(outcome, WizardOutcome::Cancelled);
+        assert!(context.scenario.is_none());
+    }
+
+    #[test]
+    fn test_render_keyboard() {
+        let (manager, mut context) = wizard_at_title();
+        let i18n = I18n::new(&crate::config::settings::I18nConfig {
+            default_language: "en".to_string(),
+            supported_languages: vec!["en".to_string()],
+        });
+        let wizard = Wizard::new(&manager);
+
+        // Nothing is loaded, so texts are their keys
+        let screen = wizard.render(&context, &i18n, "en").unwrap();
+        assert_eq!(screen.text, "wizard.event_creation.title_input");
+        assert_eq!(screen.keyboard, vec![vec![("buttons.wizard.cancel".to_string(), "1w:event_creation:cancel".to_string())]]);
+
+        context.next_step("confirmation").unwrap();
+        let screen = wizard.render(&context, &i18n, "en").unwrap();
+        let data: Vec<&str> = screen.keyboard.iter().flatten().map(|(_, data)| data.as_str()).collect();
+        assert_eq!(data, vec![
+            "1w:event_creation:choose:create",
+            "1w:event_creation:choose:cancel",
+            "1w:event_creation:back",
+            "1w:event_creation:cancel",
+        ]);
+    }
+
+    #[test]
+    fn test_media_collection() {
+        use crate::state::scenarios::{MediaKind, ScenarioDefinition};
+
+        let mut manager = ScenarioManager::new();
+        let definition = ScenarioDefinition::parse(r#"
+            id = "receipts"
+            initial_step = "receipts_input"
+
+            [steps.receipts_input]
+            next_steps = ["submit"]
+            requires_input = true
+
+            [steps.receipts_input.validation]
+            input_type = { Media = { kinds = ["photo", "document"], max_items = 3 } }
+            min_length = 2
+
+            [steps.submit]
+        "#, false).unwrap();
+        manager.apply_definition(definition).unwrap();
+        let wizard = Wizard::new(&manager);
+
+        let mut context = ConversationContext::new(1);
+        wizard.start(&mut context, "receipts").unwrap();
+        let photo = |file_id: &str| MediaItem { kind: MediaKind::Photo, file_id: file_id.to_string() };
+
+        // Text is not a file, and one file is not enough
+        assert!(matches!(wizard.answer(&mut context, "here").unwrap(), WizardOutcome::Invalid(_)));
+        assert_eq!(wizard.add_media(&mut context, photo("a")).unwrap(), WizardOutcome::Collected(1));
+        assert!(matches!(wizard.act(&mut context, &WizardAction::Done).unwrap(), WizardOutcome::Invalid(_)));
+
+        assert_eq!(wizard.add_media(&mut context, photo("b")).unwrap(), WizardOutcome::Collected(2));
+        assert_eq!(wizard.act(&mut context, &WizardAction::Done).unwrap(), WizardOutcome::Finished("submit".to_string()));
+        assert_eq!(Wizard::media_items(&context, "receipts_input"), vec![photo("a"), photo("b")]);
+
+        // A full step moves on by itself
+        let mut context = ConversationContext::new(2);
+        wizard.start(&mut context, "receipts").unwrap();
+        for file_id in ["a", "b"] {
+            wizard.add_media(&mut context, photo(file_id)).unwrap();
+        }
+        assert_eq!(wizard.add_media(&mut context, photo("c")).unwrap(), WizardOutcome::Finished("submit".to_string()));
+    }
+}
@@ -17,6 +17,10 @@ pub enum SwingBuddyError {
     #[error("Telegram API error: {0}")]
     Telegram(#[from] teloxide::RequestError),
     
+    /// The bot can't reach the chat any more (blocked, kicked, chat deleted)
+    #[error("Recipient unavailable: {0}")]
+    RecipientUnavailable(teloxide::RequestError),
+    
     #[error("CAS API error: {0}")]
     Cas(#[from] CasError),
     
@@ -41,6 +45,10 @@ pub enum SwingBuddyError {
     #[error("Invalid state transition: {from} -> {to}")]
     InvalidStateTransition { from: String, to: String },
     
+    /// The conversation context was saved or deleted by someone else since it was loaded
+    #[error("Conversation context of user {user_id} changed since it was loaded")]
+    StaleContext { user_id: i64 },
+    
     #[error("Redis error: {0}")]
     Redis(#[from] redis::RedisError),
     
@@ -70,7 +78,7 @@ pub enum SwingBuddyError {
 }
 
 /// CAS API specific errors
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum CasError {
     #[error("CAS API request failed: {0}")]
     RequestFailed(String),
@@ -102,6 +110,9 @@ pub enum GoogleError {
     
     #[error("Invalid event data: {0}")]
     InvalidEventData(String),
+    
+    #[error("Calendar sync token expired, a full sync is needed")]
+    SyncTokenExpired,
 }
 
 /// Result type alias for SwingBuddy operations
@@ -120,6 +131,7 @@ impl SwingBuddyError {
             SwingBuddyError::Database(_) => false,
             SwingBuddyError::Migration(_) => false,
             SwingBuddyError::Telegram(_) => true,
+            SwingBuddyError::RecipientUnavailable(_) => false,
             SwingBuddyError::Cas(_) => true,
             SwingBuddyError::Google(_) => true,
             SwingBuddyError::Config(_) => false,
@@ -128,6 +140,7 @@ impl SwingBuddyError {
             SwingBuddyError::GroupNotFound { .. } => false,
             SwingBuddyError::EventNotFound { .. } => false,
             SwingBuddyError::InvalidStateTransition { .. } => false,
+            SwingBuddyError::StaleContext { .. } => true,
             SwingBuddyError::Redis(_) => true,
             SwingBuddyError::Http(_) => true,
             SwingBuddyError::Serialization(_) => false,
@@ -149,6 +162,8 @@ impl SwingBuddyError {
             SwingBuddyError::PermissionDenied(_) => ErrorSeverity::Warning,
             SwingBuddyError::Authentication(_) => ErrorSeverity::Warning,
             SwingBuddyError::RateLimitExceeded => ErrorSeverity::Warning,
+            SwingBuddyError::RecipientUnavailable(_) => ErrorSeverity::Warning,
+            SwingBuddyError::StaleContext { .. } => ErrorSeverity::Warning,
             SwingBuddyError::InvalidInput(_) => ErrorSeverity::Info,
             _ => ErrorSeverity::Error,
         }
@@ -0,0 +1,285 @@
+//! Metrics registry
+//!
+//! Counters and histograms for the bot, rendered in the Prometheus text
+//! format at `/metrics`. The registry is process-wide so repositories and
+//! services can record without having it passed around.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Histogram buckets in seconds, from fast queries to slow Telegram calls
+const BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// The process-wide metrics registry
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+/// Queries at least this slow count as slow unless the config says otherwise
+const DEFAULT_SLOW_QUERY_MS: u64 = 500;
+
+/// Await a database query and record how long it took
+pub async fn time_query<F: Future>(query: &'static str, future: F) -> F::Output {
+    let started = Instant::now();
+    let output = future.await;
+    let elapsed = started.elapsed();
+    if metrics().observe_query(query, elapsed) {
+        tracing::warn!(query = query, elapsed_ms = elapsed.as_millis() as u64, "Slow database query");
+    }
+    output
+}
+
+/// All metrics the bot exports
+#[derive(Debug)]
+pub struct Metrics {
+    updates: Counter,
+    command_duration: Histogram,
+    telegram_api_errors: Counter,
+    cas_checks: Counter,
+    db_query_duration: Histogram,
+    db_slow_queries: Counter,
+    slow_query_ms: AtomicU64,
+    webhook_deliveries: Counter,
+    user_cache_lookups: Counter,
+}
+
+impl Metrics {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            updates: Counter::new("swingbuddy_updates_total", "Updates handled, by handler", "handler"),
+            command_duration: Histogram::new("swingbuddy_command_duration_seconds", "Time spent handling a command", "command"),
+            telegram_api_errors: Counter::new("swingbuddy_telegram_api_errors_total", "Failed Telegram API requests, by error kind", "kind"),
+            cas_checks: Counter::new("swingbuddy_cas_checks_total", "CAS checks, by result", "result"),
+            db_query_duration: Histogram::new("swingbuddy_db_query_duration_seconds", "Time spent on database queries", "query"),
+            db_slow_queries: Counter::new("swingbuddy_db_slow_queries_total", "Database queries slower than the slow query threshold", "query"),
+            slow_query_ms: AtomicU64::new(DEFAULT_SLOW_QUERY_MS),
+            webhook_deliveries: Counter::new("swingbuddy_webhook_deliveries_total", "Outgoing webhook deliveries, by result", "result"),
+            user_cache_lookups: Counter::new("swingbuddy_user_cache_lookups_total", "User lookups by Telegram ID, by whether the cache had them", "result"),
+        }
+    }
+
+    /// Count an update handled by `handler`
+    pub fn update_handled(&self, handler: &str) {
+        self.updates.increment(handler);
+    }
+
+    /// Record how long a command took
+    pub fn observe_command(&self, command: &str, duration: Duration) {
+        self.command_duration.observe(command, duration);
+    }
+
+    /// Count a failed Telegram API request
+    pub fn telegram_api_error(&self, kind: &str) {
+        self.telegram_api_errors.increment(kind);
+    }
+
+    /// Count a CAS check and its result
+    pub fn cas_check(&self, result: &str) {
+        self.cas_checks.increment(result);
+    }
+
+    /// Record how long a database query took; returns whether it was slow
+    pub fn observe_query(&self, query: &str, duration: Duration) -> bool {
+        self.db_query_duration.observe(query, duration);
+        let slow = duration >= self.slow_query_threshold();
+        if slow {
+            self.db_slow_queries.increment(query);
+        }
+        slow
+    }
+
+    /// Set from `database.slow_query_ms` at startup
+    pub fn set_slow_query_threshold(&self, threshold: Duration) {
+        self.slow_query_ms.store(threshold.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    pub fn slow_query_threshold(&self) -> Duration {
+        Duration::from_millis(self.slow_query_ms.load(Ordering::Relaxed))
+    }
+
+    /// Slow queries since startup
+    pub fn slow_query_count(&self) -> u64 {
+        self.db_slow_queries.total()
+    }
+
+    /// Count an outgoing webhook delivery and its result
+    pub fn webhook_delivery(&self, result: &str) {
+        self.webhook_deliveries.increment(result);
+    }
+
+    /// Count a user lookup answered from the cache (`hit`) or the database (`miss`)
+    pub fn user_cache_lookup(&self, result: &str) {
+        self.user_cache_lookups.increment(result);
+    }
+
+    /// Render all metrics in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        self.updates.render(&mut output);
+        self.command_duration.render(&mut output);
+        self.telegram_api_errors.render(&mut output);
+        self.cas_checks.render(&mut output);
+        self.db_query_duration.render(&mut output);
+        self.db_slow_queries.render(&mut output);
+        self.webhook_deliveries.render(&mut output);
+        self.user_cache_lookups.render(&mut output);
+        output
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+struct Counter {
+    name: &'static str,
+    help: &'static str,
+    label: &'static str,
+    values: Mutex<BTreeMap<String, u64>>,
+}
+
+impl Counter {
+    fn new(name: &'static str, help: &'static str, label: &'static str) -> Self {
+        Self { name, help, label, values: Mutex::new(BTreeMap::new()) }
+    }
+
+    fn increment(&self, label_value: &str) {
+        let mut values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        *values.entry(label_value.to_string()).or_insert(0) += 1;
+    }
+
+    fn total(&self) -> u64 {
+        self.values.lock().unwrap_or_else(|e| e.into_inner()).values().sum()
+    }
+
+    fn render(&self, output: &mut String) {
+        let _ = writeln!(output, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(output, "# TYPE {} counter", self.name);
+        let values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        for (label_value, count) in values.iter() {
+            let _ = writeln!(output, "{}{{{}=\"{}\"}} {}", self.name, self.label, escape_label(label_value), count);
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct HistogramValues {
+    /// Observations per bucket, not cumulative
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Debug)]
+struct Histogram {
+    name: &'static str,
+    help: &'static str,
+    label: &'static str,
+    values: Mutex<BTreeMap<String, HistogramValues>>,
+}
+
+impl Histogram {
+    fn new(name: &'static str, help: &'static str, label: &'static str) -> Self {
+        Self { name, help, label, values: Mutex::new(BTreeMap::new()) }
+    }
+
+    fn observe(&self, label_value: &str, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let mut values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = values.entry(label_value.to_string()).or_default();
+        if let Some(bucket) = BUCKETS.iter().position(|&bound| seconds <= bound) {
+            entry.buckets[bucket] += 1;
+        }
+        entry.sum += seconds;
+        entry.count += 1;
+    }
+
+    fn render(&self, output: &mut String) {
+        let _ = writeln!(output, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(output, "# TYPE {} histogram", self.name);
+        let values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        for (label_value, histogram) in values.iter() {
+            // Bucket, sum and count lines all share the escaped label
+            let labels = format!("{}=\"{}\"", self.label, escape_label(label_value));
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(histogram.buckets.iter()) {
+                cumulative += count;
+                let _ = writeln!(output, "{}_bucket{{{},le=\"{}\"}} {}", self.name, labels, bound, cumulative);
+            }
+            let _ = writeln!(output, "{}_bucket{{{},le=\"+Inf\"}} {}", self.name, labels, histogram.count);
+            let _ = writeln!(output, "{}_sum{{{}}} {}", self.name, labels, histogram.sum);
+            let _ = writeln!(output, "{}_count{{{}}} {}", self.name, labels, histogram.count);
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::new();
+        metrics.update_handled("commands");
+        metrics.update_handled("commands");
+        metrics.cas_check("clean");
+        metrics.observe_command("start", Duration::from_millis(30));
+        metrics.observe_command("start", Duration::from_secs(20));
+
+        let output = metrics.render();
+        assert!(output.contains("# TYPE swingbuddy_updates_total counter\n"));
+        assert!(output.contains("swingbuddy_updates_total{handler=\"commands\"} 2\n"));
+        assert!(output.contains("swingbuddy_cas_checks_total{result=\"clean\"} 1\n"));
+        assert!(output.contains("swingbuddy_command_duration_seconds_bucket{command=\"start\",le=\"0.025\"} 0\n"));
+        assert!(output.contains("swingbuddy_command_duration_seconds_bucket{command=\"start\",le=\"0.05\"} 1\n"));
+        assert!(output.contains("swingbuddy_command_duration_seconds_bucket{command=\"start\",le=\"10\"} 1\n"));
+        assert!(output.contains("swingbuddy_command_duration_seconds_bucket{command=\"start\",le=\"+Inf\"} 2\n"));
+        assert!(output.contains("swingbuddy_command_duration_seconds_count{command=\"start\"} 2\n"));
+        assert!(output.contains("# TYPE swingbuddy_db_query_duration_seconds histogram\n"));
+    }
+
+    #[test]
+    fn test_slow_queries() {
+        let metrics = Metrics::new();
+        metrics.set_slow_query_threshold(Duration::from_millis(100));
+
+        assert!(!metrics.observe_query("users.find", Duration::from_millis(20)));
+        assert!(metrics.observe_query("users.find", Duration::from_millis(100)));
+        assert!(metrics.observe_query("events.list", Duration::from_secs(2)));
+
+        assert_eq!(metrics.slow_query_count(), 2);
+        assert!(metrics.render().contains("swingbuddy_db_slow_queries_total{query=\"users.find\"} 1\n"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("a\"b\\c"), "a\\\"b\\\\c");
+    }
+
+    #[test]
+    fn test_render_escapes_histogram_labels() {
+        let metrics = Metrics::new();
+        metrics.observe_command("say \"hi\"\nnow", Duration::from_millis(30));
+
+        let output = metrics.render();
+        assert!(output.contains("swingbuddy_command_duration_seconds_bucket{command=\"say \\\"hi\\\"\\nnow\",le=\"0.05\"} 1\n"));
+        assert!(output.contains("swingbuddy_command_duration_seconds_bucket{command=\"say \\\"hi\\\"\\nnow\",le=\"+Inf\"} 1\n"));
+        assert!(output.contains("swingbuddy_command_duration_seconds_count{command=\"say \\\"hi\\\"\\nnow\"} 1\n"));
+        // Every line is a comment or a complete sample
+        assert!(output.lines().all(|line| line.starts_with('#') || line.starts_with("swingbuddy_")));
+    }
+}
@@ -6,5 +6,9 @@
 pub mod errors;
 pub mod logging;
 pub mod helpers;
+pub mod metrics;
+pub mod redis_connection;
+pub mod retry;
+pub mod webhook;
 
 pub use errors::{SwingBuddyError, Result};
\ No newline at end of file
@@ -0,0 +1,201 @@
+//! Redis connections that follow Sentinel failovers
+//!
+//! With a plain `redis.url` this is a thin wrapper around one client. With
+//! `[redis.sentinel]` the master is looked up through Sentinel and looked up
+//! again every few seconds; when Sentinel promotes a replica, new connections
+//! go to the new master and the shared connection manager is rebuilt.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use redis::aio::ConnectionManager;
+use redis::sentinel::{Sentinel, SentinelNodeConnectionInfo};
+use redis::{Client, RedisConnectionInfo};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use crate::config::{RedisConfig, RedisSentinelConfig};
+use crate::utils::errors::Result;
+
+/// Retries when opening the shared connection
+const CONNECT_RETRIES: usize = 1;
+
+/// Where the Redis server is found
+enum Source {
+    Url(Client),
+    Sentinel {
+        sentinel: Mutex<Sentinel>,
+        master_name: String,
+        node_info: SentinelNodeConnectionInfo,
+        check_interval: Duration,
+    },
+}
+
+/// The Sentinel master in use and the connection manager for it
+#[derive(Default)]
+struct Current {
+    client: Option<Client>,
+    manager: Option<ConnectionManager>,
+    checked_at: Option<Instant>,
+}
+
+struct Inner {
+    source: Source,
+    current: Mutex<Current>,
+}
+
+/// Hands out Redis clients and connections for the current master
+#[derive(Clone)]
+pub struct RedisConnector {
+    inner: Arc<Inner>,
+}
+
+impl std::fmt::Debug for RedisConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match &self.inner.source {
+            Source::Url(_) => "url",
+            Source::Sentinel { .. } => "sentinel",
+        };
+        f.debug_struct("RedisConnector").field("source", &source).finish()
+    }
+}
+
+impl RedisConnector {
+    /// Create a connector from the Redis configuration. Nothing is connected yet.
+    pub fn new(config: &RedisConfig) -> Result<Self> {
+        let source = match &config.sentinel {
+            Some(sentinel) => Source::Sentinel {
+                sentinel: Mutex::new(Sentinel::build(sentinel.urls.clone())?),
+                master_name: sentinel.master_name.clone(),
+                node_info: node_info(sentinel),
+                check_interval: Duration::from_secs(sentinel.check_interval_seconds),
+            },
+            None => Source::Url(Client::open(config.url.as_str())?),
+        };
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                source,
+                current: Mutex::new(Current::default()),
+            }),
+        })
+    }
+
+    /// Get a client for the current master
+    pub async fn client(&self) -> Result<Client> {
+        let mut current = self.inner.current.lock().await;
+        self.refresh(&mut current).await
+    }
+
+    /// Get the shared connection manager for the current master
+    pub async fn connection_manager(&self) -> Result<ConnectionManager> {
+        let mut current = self.inner.current.lock().await;
+        let client = self.refresh(&mut current).await?;
+
+        if let Some(manager) = &current.manager {
+            return Ok(manager.clone());
+        }
+
+        // Retry the first connection only briefly; callers have their own fallbacks
+        let manager = ConnectionManager::new_with_backoff(client, 2, 100, CONNECT_RETRIES).await?;
+        current.manager = Some(manager.clone());
+        Ok(manager)
+    }
+
+    /// Look up the master if it is not known yet or the check interval passed,
+    /// and return a client for it. While Sentinel can't be reached the last
+    /// known master is kept.
+    async fn refresh(&self, current: &mut Current) -> Result<Client> {
+        let (sentinel, master_name, node_info, check_interval) = match &self.inner.source {
+            Source::Url(client) => return Ok(client.clone()),
+            Source::Sentinel { sentinel, master_name, node_info, check_interval } => {
+                (sentinel, master_name, node_info, *check_interval)
+            }
+        };
+
+        if let Some(client) = &current.client {
+            if current.checked_at.is_some_and(|checked| checked.elapsed() < check_interval) {
+                return Ok(client.clone());
+            }
+        }
+
+        let result = sentinel.lock().await.async_master_for(master_name, Some(node_info)).await;
+        current.checked_at = Some(Instant::now());
+
+        let client = match (result, &current.client) {
+            (Ok(client), _) => client,
+            (Err(e), Some(known)) => {
+                warn!(error = %e, "Redis Sentinel unreachable, keeping the current master");
+                return Ok(known.clone());
+            }
+            (Err(e), None) => return Err(e.into()),
+        };
+
+        let address = client.get_connection_info().addr.clone();
+        match &current.client {
+            Some(previous) if previous.get_connection_info().addr == address => return Ok(client),
+            Some(previous) => {
+                warn!(previous = %previous.get_connection_info().addr, master = %address, "Redis master changed, reconnecting");
+                current.manager = None;
+            }
+            None => info!(master = %address, master_name = %master_name, "Found Redis master through Sentinel"),
+        }
+
+        current.client = Some(client.clone());
+        Ok(client)
+    }
+}
+
+/// Connection settings Sentinel uses for the master it finds
+fn node_info(config: &RedisSentinelConfig) -> SentinelNodeConnectionInfo {
+    SentinelNodeConnectionInfo {
+        tls_mode: None,
+        redis_connection_info: Some(RedisConnectionInfo {
+            db: config.db,
+            username: None,
+            password: config.password.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(sentinel: Option<RedisSentinelConfig>) -> RedisConfig {
+        RedisConfig {
+            url: "redis://localhost:6379".to_string(),
+            prefix: "test:".to_string(),
+            ttl_seconds: 60,
+            sentinel,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_url_source() {
+        let connector = RedisConnector::new(&config(None)).unwrap();
+        let client = connector.client().await.unwrap();
+        assert_eq!(client.get_connection_info().addr.to_string(), "localhost:6379");
+    }
+
+    #[test]
+    fn test_sentinel_source() {
+        let connector = RedisConnector::new(&config(Some(RedisSentinelConfig {
+            urls: vec!["redis://localhost:26379".to_string()],
+            master_name: "mymaster".to_string(),
+            password: Some("secret".to_string()),
+            db: 2,
+            check_interval_seconds: 5,
+        }))).unwrap();
+        assert_eq!(format!("{:?}", connector), "RedisConnector { source: \"sentinel\" }");
+
+        let info = node_info(&RedisSentinelConfig {
+            urls: vec![],
+            master_name: "mymaster".to_string(),
+            password: Some("secret".to_string()),
+            db: 2,
+            check_interval_seconds: 5,
+        });
+        let redis = info.redis_connection_info.unwrap();
+        assert_eq!(redis.db, 2);
+        assert_eq!(redis.password.as_deref(), Some("secret"));
+    }
+}
@@ -0,0 +1,163 @@
+//! Telegram API retries
+//!
+//! Outgoing requests are retried with exponential backoff when the failure
+//! is transient. When Telegram answers `429 Too Many Requests` the request
+//! is retried after the time it asks for. Failures that a retry can't fix,
+//! such as a user who blocked the bot or a chat that no longer exists, are
+//! returned right away as [`SwingBuddyError::RecipientUnavailable`].
+
+use std::time::Duration;
+use teloxide::requests::{Output, Request};
+use teloxide::{ApiError, RequestError};
+use tracing::{debug, warn};
+use crate::utils::errors::{Result, SwingBuddyError};
+
+/// Longest wait for a `RetryAfter` we are willing to honour before giving up
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+
+/// How a failed request should be handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// Telegram asked to wait this long before trying again
+    RetryAfter(Duration),
+    /// Network hiccup or server error; worth retrying after a backoff
+    Transient,
+    /// Retrying won't help
+    Permanent,
+}
+
+/// Classify a Telegram API error
+pub fn classify(error: &RequestError) -> Failure {
+    match error {
+        RequestError::RetryAfter(seconds) => Failure::RetryAfter(seconds.duration()),
+        RequestError::Network(_) | RequestError::Io(_) => Failure::Transient,
+        RequestError::Api(ApiError::Unknown(text)) if is_server_error(text) => Failure::Transient,
+        _ => Failure::Permanent,
+    }
+}
+
+/// Whether the error means the chat can't be reached by the bot at all
+pub fn is_recipient_unavailable(error: &RequestError) -> bool {
+    matches!(
+        error,
+        RequestError::Api(
+            ApiError::BotBlocked
+                | ApiError::ChatNotFound
+                | ApiError::UserNotFound
+                | ApiError::UserDeactivated
+                | ApiError::GroupDeactivated
+                | ApiError::BotKicked
+                | ApiError::BotKickedFromSupergroup
+                | ApiError::BotKickedFromChannel
+                | ApiError::CantInitiateConversation
+                | ApiError::CantTalkWithBots
+        )
+    )
+}
+
+/// Wrap a Telegram API error, telling unreachable recipients apart
+pub fn telegram_error(error: RequestError) -> SwingBuddyError {
+    if is_recipient_unavailable(&error) {
+        SwingBuddyError::RecipientUnavailable(error)
+    } else {
+        SwingBuddyError::Telegram(error)
+    }
+}
+
+fn is_server_error(text: &str) -> bool {
+    text.contains("Internal Server Error") || text.contains("Bad Gateway") || text.contains("Gateway Timeout")
+}
+
+/// Retry policy for outgoing Telegram requests
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first one
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled for each further one
+    pub base_delay: Duration,
+    /// Upper bound for the backoff
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff before retry number `retry` (starting at 1)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Wait before retry number `retry` after `error`, or `None` to give up
+    pub fn delay_for(&self, error: &RequestError, retry: u32) -> Option<Duration> {
+        if retry >= self.max_attempts {
+            return None;
+        }
+
+        match classify(error) {
+            Failure::RetryAfter(delay) if delay <= MAX_RETRY_AFTER => Some(delay),
+            Failure::RetryAfter(_) | Failure::Permanent => None,
+            Failure::Transient => Some(self.backoff(retry)),
+        }
+    }
+
+    /// Send a request, retrying transient failures
+    pub async fn send<R>(&self, request: &R) -> Result<Output<R>>
+    where
+        R: Request<Err = RequestError>,
+    {
+        let mut retry = 1;
+        loop {
+            match request.send_ref().await {
+                Ok(output) => return Ok(output),
+                Err(e) => match self.delay_for(&e, retry) {
+                    Some(delay) => {
+                        warn!(error = %e, retry = retry, delay_ms = delay.as_millis() as u64, "Telegram request failed, retrying");
+                        tokio::time::sleep(delay).await;
+                        retry += 1;
+                    }
+                    None => {
+                        debug!(error = %e, attempts = retry, "Telegram request failed, giving up");
+                        return Err(telegram_error(e));
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teloxide::types::Seconds;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(&RequestError::RetryAfter(Seconds::from_seconds(3))), Failure::RetryAfter(Duration::from_secs(3)));
+        assert_eq!(classify(&RequestError::Api(ApiError::BotBlocked)), Failure::Permanent);
+        assert_eq!(classify(&RequestError::Api(ApiError::Unknown("Internal Server Error".to_string()))), Failure::Transient);
+        assert!(is_recipient_unavailable(&RequestError::Api(ApiError::ChatNotFound)));
+        assert!(!is_recipient_unavailable(&RequestError::Api(ApiError::MessageNotModified)));
+    }
+
+    #[test]
+    fn test_delay_for() {
+        let policy = RetryPolicy::default();
+        let server_error = RequestError::Api(ApiError::Unknown("Bad Gateway".to_string()));
+        assert_eq!(policy.delay_for(&server_error, 1), Some(Duration::from_millis(500)));
+        assert_eq!(policy.delay_for(&server_error, 3), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay_for(&server_error, 4), None);
+        assert_eq!(policy.delay_for(&RequestError::RetryAfter(Seconds::from_seconds(7)), 2), Some(Duration::from_secs(7)));
+        assert_eq!(policy.delay_for(&RequestError::RetryAfter(Seconds::from_seconds(600)), 1), None);
+        assert_eq!(policy.delay_for(&RequestError::Api(ApiError::BotBlocked), 1), None);
+        assert_eq!(policy.backoff(20), Duration::from_secs(30));
+    }
+}
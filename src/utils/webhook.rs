@@ -0,0 +1,137 @@
+//! Webhook mode setup
+//!
+//! Turns the `bot.webhook_url` and `[webhook]` settings into the options
+//! teloxide uses to serve updates, and registers the webhook with Telegram.
+
+use std::net::SocketAddr;
+use teloxide::{Bot, prelude::*};
+use teloxide::types::{AllowedUpdate, InputFile};
+use teloxide::update_listeners::webhooks::Options;
+use crate::config::Settings;
+use crate::utils::errors::{Result, SwingBuddyError};
+
+/// Longest secret token Telegram accepts
+const MAX_SECRET_TOKEN_LENGTH: usize = 256;
+
+/// Updates the bot handles. Telegram leaves out changes of group members
+/// unless they are asked for.
+pub const ALLOWED_UPDATES: [AllowedUpdate; 5] = [
+    AllowedUpdate::Message,
+    AllowedUpdate::CallbackQuery,
+    AllowedUpdate::MyChatMember,
+    AllowedUpdate::ChatMember,
+    AllowedUpdate::ChatJoinRequest,
+];
+
+/// Check a secret token against Telegram's rules: 1-256 characters out of
+/// `A-Z`, `a-z`, `0-9`, `_` and `-`
+pub fn is_valid_secret_token(token: &str) -> bool {
+    !token.is_empty()
+        && token.len() <= MAX_SECRET_TOKEN_LENGTH
+        && token.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// Build the webhook options, or `None` when the bot should poll for updates
+pub fn webhook_options(settings: &Settings) -> Result<Option<Options>> {
+    let Some(url) = settings.bot.webhook_url() else {
+        return Ok(None);
+    };
+
+    let url = url::Url::parse(url)
+        .map_err(|e| SwingBuddyError::Config(format!("Invalid webhook URL: {}", e)))?;
+    let webhook = settings.webhook.clone().unwrap_or_default();
+    let address: SocketAddr = webhook.listen_address.parse()
+        .map_err(|e| SwingBuddyError::Config(format!("Invalid webhook listen address: {}", e)))?;
+
+    let mut options = Options::new(address, url);
+
+    if let Some(path) = webhook.path.filter(|path| !path.is_empty()) {
+        let path = if path.starts_with('/') { path } else { format!("/{}", path) };
+        options = options.path(path);
+    }
+
+    if let Some(token) = webhook.secret_token.filter(|token| !token.is_empty()) {
+        if !is_valid_secret_token(&token) {
+            return Err(SwingBuddyError::Config(
+                "Webhook secret token must be 1-256 characters of A-Z, a-z, 0-9, _ and -".to_string()
+            ));
+        }
+        options = options.secret_token(token);
+    }
+
+    if let Some(path) = webhook.certificate_path {
+        options = options.certificate(InputFile::file(path));
+    }
+
+    if let Some(max_connections) = webhook.max_connections {
+        options = options.max_connections(max_connections);
+    }
+
+    if webhook.drop_pending_updates {
+        options = options.drop_pending_updates();
+    }
+
+    Ok(Some(options))
+}
+
+/// Register the webhook with Telegram, asking for the updates the bot handles.
+/// Takes the certificate out of `options`, and fills in the secret token if
+/// none was configured.
+pub async fn register_webhook(bot: &Bot, options: &mut Options) -> Result<()> {
+    let secret_token = options.get_or_gen_secret_token().to_string();
+    let mut request = bot.set_webhook(options.url.clone())
+        .allowed_updates(ALLOWED_UPDATES)
+        .drop_pending_updates(options.drop_pending_updates)
+        .secret_token(secret_token);
+    if let Some(certificate) = options.certificate.take() {
+        request = request.certificate(certificate);
+    }
+    if let Some(max_connections) = options.max_connections {
+        request = request.max_connections(max_connections);
+    }
+    request.await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::WebhookConfig;
+
+    #[test]
+    fn test_is_valid_secret_token() {
+        assert!(is_valid_secret_token("abc_DEF-123"));
+        assert!(!is_valid_secret_token(""));
+        assert!(!is_valid_secret_token("with space"));
+        assert!(!is_valid_secret_token(&"a".repeat(257)));
+    }
+
+    #[test]
+    fn test_webhook_options() {
+        let mut settings = Settings::default();
+        assert!(webhook_options(&settings).unwrap().is_none());
+
+        settings.bot.webhook_url = Some(String::new());
+        assert!(webhook_options(&settings).unwrap().is_none());
+
+        settings.bot.webhook_url = Some("https://bot.example.com/telegram".to_string());
+        let options = webhook_options(&settings).unwrap().unwrap();
+        assert_eq!(options.address, "0.0.0.0:8443".parse().unwrap());
+        assert_eq!(options.path, "/telegram");
+        assert!(options.secret_token.is_none());
+
+        settings.webhook = Some(WebhookConfig {
+            listen_address: "127.0.0.1:8080".to_string(),
+            path: Some("hook".to_string()),
+            secret_token: Some("s3cret".to_string()),
+            ..WebhookConfig::default()
+        });
+        let options = webhook_options(&settings).unwrap().unwrap();
+        assert_eq!(options.address, "127.0.0.1:8080".parse().unwrap());
+        assert_eq!(options.path, "/hook");
+        assert_eq!(options.secret_token.as_deref(), Some("s3cret"));
+
+        settings.webhook.as_mut().unwrap().secret_token = Some("not valid!".to_string());
+        assert!(webhook_options(&settings).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Ephemeral test infrastructure
+//!
+//! This module starts PostgreSQL and Redis in Docker via testcontainers so tests
+//! do not need pre-provisioned servers. Containers are shared by all tests of a
+//! test binary and stopped once the last test using them is done.
+//!
+//! Set `TEST_DATABASE_URL` / `TEST_REDIS_URL` to use existing servers instead
+//! (e.g. CI service containers).
+
+use std::sync::{Arc, Weak};
+use testcontainers::{runners::AsyncRunner, ContainerAsync, ImageExt};
+use testcontainers_modules::postgres::Postgres as PostgresImage;
+use testcontainers_modules::redis::{Redis as RedisImage, REDIS_PORT};
+use tokio::sync::Mutex;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+const POSTGRES_TAG: &str = "16-alpine";
+const REDIS_TAG: &str = "7-alpine";
+
+static POSTGRES: Mutex<Weak<PostgresServer>> = Mutex::const_new(Weak::new());
+static REDIS: Mutex<Weak<RedisServer>> = Mutex::const_new(Weak::new());
+
+/// PostgreSQL server used by tests
+pub struct PostgresServer {
+    /// URL of a database on the server that can be used to create test databases
+    admin_url: url::Url,
+    container: Option<ContainerAsync<PostgresImage>>,
+}
+
+impl PostgresServer {
+    /// URL of the maintenance database, used to create and drop test databases
+    pub fn admin_url(&self) -> String {
+        self.admin_url.to_string()
+    }
+
+    /// URL of a database with the given name on this server
+    pub fn database_url(&self, database_name: &str) -> String {
+        let mut url = self.admin_url.clone();
+        url.set_path(&format!("/{}", database_name));
+        url.to_string()
+    }
+
+    /// Whether the server was provided through `TEST_DATABASE_URL`
+    pub fn is_external(&self) -> bool {
+        self.container.is_none()
+    }
+}
+
+/// Redis server used by tests
+pub struct RedisServer {
+    pub url: String,
+    _container: Option<ContainerAsync<RedisImage>>,
+}
+
+/// Get the shared PostgreSQL server, starting a container if needed
+pub async fn postgres_server() -> Result<Arc<PostgresServer>, BoxError> {
+    let mut shared = POSTGRES.lock().await;
+    if let Some(server) = shared.upgrade() {
+        return Ok(server);
+    }
+
+    let server = if let Ok(url) = std::env::var("TEST_DATABASE_URL") {
+        PostgresServer {
+            admin_url: url.parse()?,
+            container: None,
+        }
+    } else {
+        let container = PostgresImage::default()
+            .with_tag(POSTGRES_TAG)
+            .start()
+            .await?;
+        let host = container.get_host().await?;
+        let port = container.get_host_port_ipv4(5432).await?;
+
+        PostgresServer {
+            admin_url: format!("postgresql://postgres:postgres@{}:{}/postgres", host, port).parse()?,
+            container: Some(container),
+        }
+    };
+
+    let server = Arc::new(server);
+    *shared = Arc::downgrade(&server);
+    Ok(server)
+}
+
+/// Get the shared Redis server, starting a container if needed
+pub async fn redis_server() -> Result<Arc<RedisServer>, BoxError> {
+    let mut shared = REDIS.lock().await;
+    if let Some(server) = shared.upgrade() {
+        return Ok(server);
+    }
+
+    let server = if let Ok(url) = std::env::var("TEST_REDIS_URL") {
+        RedisServer {
+            url,
+            _container: None,
+        }
+    } else {
+        let container = RedisImage::default()
+            .with_tag(REDIS_TAG)
+            .start()
+            .await?;
+        let host = container.get_host().await?;
+        let port = container.get_host_port_ipv4(REDIS_PORT).await?;
+
+        RedisServer {
+            url: format!("redis://{}:{}", host, port),
+            _container: Some(container),
+        }
+    };
+
+    let server = Arc::new(server);
+    *shared = Arc::downgrade(&server);
+    Ok(server)
+}
+
+/// Generate a unique, identifier-safe name for per-test resources
+pub fn unique_name(prefix: &str) -> String {
+    format!("{}_{}", prefix, uuid::Uuid::new_v4().simple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_url_replaces_path() {
+        let server = PostgresServer {
+            admin_url: "postgresql://u:p@localhost:5433/postgres?host=/tmp".parse().unwrap(),
+            container: None,
+        };
+
+        assert_eq!(server.database_url("test_abc"), "postgresql://u:p@localhost:5433/test_abc?host=/tmp");
+        assert!(server.is_external());
+    }
+
+    #[test]
+    fn test_unique_name() {
+        let a = unique_name("test_swingbuddy");
+        let b = unique_name("test_swingbuddy");
+        assert!(a.starts_with("test_swingbuddy_"));
+        assert_ne!(a, b);
+        assert!(a.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    }
+}
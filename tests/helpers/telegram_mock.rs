@@ -269,6 +269,27 @@ impl TelegramMockServer {
             .await;
     }
 
+    /// Setup mock for getChatMember answering with the given status ("member", "left", ...)
+    pub async fn mock_get_chat_member(&self, user_id: i64, status: &str) {
+        let response = ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "result": {
+                "status": status,
+                "user": {
+                    "id": user_id,
+                    "is_bot": false,
+                    "first_name": "Test"
+                }
+            }
+        }));
+
+        Mock::given(method("POST"))
+            .and(path(format!("/bot{}/GetChatMember", test_bot_token())))
+            .respond_with(response)
+            .mount(&self.server)
+            .await;
+    }
+
     /// Setup all common mocks with default success responses
     pub async fn setup_default_mocks(&self) {
         self.setup_default_mocks_with_token(&test_bot_token()).await;
@@ -360,6 +381,26 @@ pub fn test_bot_token() -> String {
     "12345:test_token".to_string()
 }
 
+/// The bot's own user, as the getMe mock describes it
+pub fn test_bot_me() -> teloxide::types::Me {
+    teloxide::types::Me {
+        user: teloxide::types::User {
+            id: teloxide::types::UserId(12345),
+            is_bot: true,
+            first_name: "TestBot".to_string(),
+            last_name: None,
+            username: Some("test_bot".to_string()),
+            language_code: None,
+            is_premium: false,
+            added_to_attachment_menu: false,
+        },
+        can_join_groups: true,
+        can_read_all_group_messages: false,
+        supports_inline_queries: false,
+        can_connect_to_business: false,
+    }
+}
+
 /// Helper function to create test chat ID
 pub fn test_chat_id() -> i64 {
     -1001234567890
@@ -8,7 +8,7 @@ use redis::aio::ConnectionManager;
 use std::sync::Arc;
 use tempfile::TempDir;
 
-use super::{database_helper::TestDatabase, telegram_mock::TelegramMockServer};
+use super::{containers, containers::RedisServer, database_helper::TestDatabase, telegram_mock::{test_bot_me, TelegramMockServer}};
 
 /// Unified test context that manages all test components
 pub struct TestContext {
@@ -18,6 +18,7 @@ pub struct TestContext {
     pub settings: Settings,
     pub temp_dir: TempDir,
     pub bot_token: String,
+    _redis_server: Option<Arc<RedisServer>>,
 }
 
 impl TestContext {
@@ -51,15 +52,24 @@ impl TestContext {
             telegram_mock.setup_default_mocks_with_token(&bot_token).await;
         }
 
-        // Initialize Redis connection (optional)
+        // Initialize Redis server and connection (optional)
+        let redis_server = if config.use_redis {
+            Some(containers::redis_server().await?)
+        } else {
+            None
+        };
+        let redis_url = redis_server.as_ref()
+            .map(|server| server.url.clone())
+            .or_else(|| std::env::var("TEST_REDIS_URL").ok())
+            .unwrap_or_else(|| "redis://localhost:6379".to_string());
         let redis_connection = if config.use_redis {
-            Some(Self::setup_redis_connection().await?)
+            Some(Self::setup_redis_connection(&redis_url).await?)
         } else {
             None
         };
 
         // Create test settings
-        let settings = Self::create_test_settings(&database, &telegram_mock, &bot_token, &temp_dir)?;
+        let settings = Self::create_test_settings(&database, &telegram_mock, &redis_url, &bot_token, &temp_dir)?;
 
         Ok(Self {
             database,
@@ -68,14 +78,12 @@ impl TestContext {
             settings,
             temp_dir,
             bot_token,
+            _redis_server: redis_server,
         })
     }
 
     /// Setup Redis connection for testing
-    async fn setup_redis_connection() -> Result<ConnectionManager, redis::RedisError> {
-        let redis_url = std::env::var("TEST_REDIS_URL")
-            .unwrap_or_else(|_| "redis://localhost:6379".to_string());
-        
+    async fn setup_redis_connection(redis_url: &str) -> Result<ConnectionManager, redis::RedisError> {
         let client = redis::Client::open(redis_url)?;
         let connection = ConnectionManager::new(client).await?;
         
@@ -86,6 +94,7 @@ impl TestContext {
     fn create_test_settings(
         database: &TestDatabase,
         telegram_mock: &TelegramMockServer,
+        redis_url: &str,
         bot_token: &str,
         temp_dir: &TempDir,
     ) -> Result<Settings, Box<dyn std::error::Error + Send + Sync>> {
@@ -101,10 +110,9 @@ impl TestContext {
         settings.database.max_connections = 5;
         settings.database.min_connections = 1;
 
-        // Configure Redis settings
-        settings.redis.url = std::env::var("TEST_REDIS_URL")
-            .unwrap_or_else(|_| "redis://localhost:6379".to_string());
-        settings.redis.prefix = "test_swingbuddy:".to_string();
+        // Configure Redis settings; the prefix is unique so parallel tests can share a server
+        settings.redis.url = redis_url.to_string();
+        settings.redis.prefix = format!("{}:", containers::unique_name("test_swingbuddy"));
         settings.redis.ttl_seconds = 300; // Shorter TTL for tests
 
         // Configure CAS settings (use mock)
@@ -137,10 +145,24 @@ impl TestContext {
         // Reset mock server
         self.telegram_mock.reset().await;
         
-        // Clear Redis if available
+        // Clear this context's Redis keys if available
         if let Some(ref conn) = self.redis_connection {
             let mut conn = conn.clone();
-            redis::cmd("FLUSHDB").query_async::<_, ()>(&mut conn).await?;
+            let pattern = format!("{}*", self.settings.redis.prefix);
+            let keys: Vec<String> = {
+                let mut iter = redis::cmd("SCAN").cursor_arg(0).arg("MATCH").arg(&pattern)
+                    .clone()
+                    .iter_async::<String>(&mut conn)
+                    .await?;
+                let mut keys = Vec::new();
+                while let Some(key) = iter.next_item().await {
+                    keys.push(key);
+                }
+                keys
+            };
+            if !keys.is_empty() {
+                redis::cmd("DEL").arg(keys).query_async::<_, ()>(&mut conn).await?;
+            }
         }
 
         Ok(())
@@ -168,8 +190,8 @@ impl TestContext {
     pub async fn create_app_state(&self) -> Result<Arc<SwingBuddy::state::context::AppContext>, Box<dyn std::error::Error + Send + Sync>> {
         let database_service = Arc::new(SwingBuddy::database::service::DatabaseService::new(self.db_pool().clone()));
         
-        // Create shared Redis client
-        let redis_client = redis::Client::open(self.settings.redis.url.clone())?;
+        // Create shared Redis connector
+        let redis = SwingBuddy::utils::redis_connection::RedisConnector::new(&self.settings.redis)?;
 
         // Create user repository
         let user_repository = SwingBuddy::database::repositories::UserRepository::new(self.db_pool().clone());
@@ -178,34 +200,260 @@ impl TestContext {
         let bot = self.create_bot().await?;
 
         // Initialize all services
+        let shared_settings = SwingBuddy::config::settings::SharedSettings::new(self.settings.clone());
+        let outgoing_webhook_service = SwingBuddy::services::outgoing_webhook::OutgoingWebhookService::new(
+            shared_settings.clone(),
+        )?;
+
         let user_service = SwingBuddy::services::user::UserService::new(
             user_repository,
-            self.settings.clone(),
+            shared_settings.clone(),
         );
 
         let auth_service = SwingBuddy::services::auth::AuthService::new(
             bot.clone(),
-            self.settings.clone(),
+            test_bot_me().id,
+            database_service.roles.clone(),
+            shared_settings.clone(),
         );
 
         let notification_service = SwingBuddy::services::notification::NotificationService::new(
             bot.clone(),
-            self.settings.clone(),
+            shared_settings.clone(),
+        ).with_user_repository(database_service.users.clone());
+
+        let feature_flags = SwingBuddy::services::feature_flags::FeatureFlags::new(self.settings.features.clone());
+        let feature_flag_service = SwingBuddy::services::feature_flags::FeatureFlagService::new(
+            feature_flags.clone(),
+            database_service.admin.clone(),
         );
 
         let cas_service = SwingBuddy::services::cas::CasService::new(
-            redis_client.clone(),
-            self.settings.clone(),
+            redis.clone(),
+            database_service.admin.clone(),
+            feature_flags.clone(),
+            shared_settings.clone(),
         )?;
 
         let redis_service = SwingBuddy::services::redis::RedisService::new(
             self.settings.clone(),
         )?;
 
+        let google_oauth_service = SwingBuddy::services::google_oauth::GoogleOAuthService::new(
+            database_service.google_account.clone(),
+            shared_settings.clone(),
+        )?;
+
         let google_service = SwingBuddy::services::google::GoogleCalendarService::new(
-            self.settings.clone(),
+            feature_flags.clone(),
+            shared_settings.clone(),
+        )?.with_oauth(google_oauth_service.clone());
+
+        let sheet_export_service = SwingBuddy::services::sheet_export::SheetExportService::new(
+            google_oauth_service.clone(),
+            database_service.event_sheets.clone(),
+            database_service.events.clone(),
         )?;
 
+        let event_service = SwingBuddy::services::event::EventService::new(
+            database_service.events.clone(),
+            database_service.users.clone(),
+            sheet_export_service.clone(),
+        ).with_webhooks(outgoing_webhook_service.clone());
+
+        let google_watch_service = SwingBuddy::services::google_watch::GoogleCalendarWatchService::new(
+            google_service.clone(),
+            google_oauth_service.clone(),
+            database_service.google_calendar_sync.clone(),
+            event_service.clone(),
+            shared_settings.clone(),
+        );
+
+        let note_service = SwingBuddy::services::note::NoteService::new(
+            database_service.notes.clone(),
+            database_service.users.clone(),
+            database_service.events.clone(),
+        );
+
+        let referral_service = SwingBuddy::services::referral::ReferralService::new(
+            database_service.referrals.clone(),
+            database_service.users.clone(),
+        );
+
+        let quiet_hours_service = SwingBuddy::services::quiet_hours::QuietHoursService::new(
+            bot.clone(),
+            notification_service.clone(),
+            database_service.quiet_hours.clone(),
+            database_service.groups.clone(),
+        );
+
+        let reminder_service = SwingBuddy::services::reminder::ReminderService::new(
+            bot.clone(),
+            database_service.events.clone(),
+            database_service.groups.clone(),
+            notification_service.clone(),
+            quiet_hours_service.clone(),
+            shared_settings.clone(),
+        );
+
+        let birthday_service = SwingBuddy::services::birthday::BirthdayService::new(
+            database_service.birthdays.clone(),
+            database_service.users.clone(),
+            database_service.groups.clone(),
+            notification_service.clone(),
+            quiet_hours_service.clone(),
+            shared_settings.clone(),
+        );
+
+        let dance_style_service = SwingBuddy::services::dance_style::DanceStyleService::new(
+            database_service.dance_styles.clone(),
+            database_service.users.clone(),
+        );
+
+        let captcha_service = SwingBuddy::services::captcha::CaptchaService::new(
+            bot.clone(),
+            database_service.captchas.clone(),
+            database_service.groups.clone(),
+            feature_flags.clone(),
+            shared_settings.clone(),
+        );
+
+        let group_service = SwingBuddy::services::group::GroupService::new(
+            database_service.groups.clone(),
+            database_service.roles.clone(),
+        );
+
+        let moderation_service = SwingBuddy::services::moderation::ModerationService::new(
+            bot.clone(),
+            test_bot_me().id,
+            database_service.moderation.clone(),
+            database_service.groups.clone(),
+            shared_settings.clone(),
+        );
+
+        let cleanup_service = SwingBuddy::services::cleanup::CleanupService::new(
+            bot.clone(),
+            database_service.cleanup.clone(),
+            database_service.groups.clone(),
+            shared_settings.clone(),
+        );
+
+        let archive_service = SwingBuddy::services::archive::ArchiveService::new(
+            database_service.events.clone(),
+            shared_settings.clone(),
+        );
+
+        let digest_service = SwingBuddy::services::digest::DigestService::new(
+            bot.clone(),
+            database_service.events.clone(),
+            database_service.groups.clone(),
+            notification_service.clone(),
+            shared_settings.clone(),
+        );
+
+        let stats_service = SwingBuddy::services::stats::StatsService::new(
+            database_service.stats.clone(),
+        );
+
+        let cas_recheck_service = SwingBuddy::services::cas_recheck::CasRecheckService::new(
+            bot.clone(),
+            cas_service.clone(),
+            database_service.member_activity.clone(),
+            redis_service.clone(),
+            database_service.groups.clone(),
+            database_service.moderation.clone(),
+            stats_service.clone(),
+            notification_service.clone(),
+            shared_settings.clone(),
+        );
+
+        let cas_queue_service = SwingBuddy::services::cas_queue::CasQueueService::new(
+            bot.clone(),
+            cas_service.clone(),
+            database_service.groups.clone(),
+            moderation_service.clone(),
+            stats_service.clone(),
+            shared_settings.clone(),
+        );
+
+        let invite_link_service = SwingBuddy::services::invite_link::InviteLinkService::new(
+            bot.clone(),
+            database_service.invite_links.clone(),
+        );
+
+        let song_request_service = SwingBuddy::services::song_request::SongRequestService::new(
+            database_service.song_requests.clone(),
+            database_service.events.clone(),
+            database_service.users.clone(),
+        );
+
+        let venue_service = SwingBuddy::services::venue::VenueService::new(
+            database_service.venues.clone(),
+            database_service.events.clone(),
+        );
+
+        let broadcast_service = SwingBuddy::services::broadcast::BroadcastService::new(
+            bot.clone(),
+            database_service.broadcasts.clone(),
+            database_service.events.clone(),
+            database_service.users.clone(),
+            notification_service.clone(),
+        );
+
+        let admin_audit_service = SwingBuddy::services::admin_audit::AdminAuditService::new(
+            database_service.admin.clone(),
+        );
+
+        let activity_log_service = SwingBuddy::services::activity_log::ActivityLogService::new(
+            database_service.activity.clone(),
+        );
+
+        let backup_service = SwingBuddy::services::backup::BackupService::new(
+            database_service.admin.clone(),
+        );
+
+        let scheduled_message_service = SwingBuddy::services::scheduled_message::ScheduledMessageService::new(
+            bot.clone(),
+            database_service.scheduled_messages.clone(),
+            database_service.groups.clone(),
+            broadcast_service.clone(),
+            notification_service.clone(),
+        );
+
+        let calendar_service = SwingBuddy::services::calendar::CalendarService::new(
+            database_service.calendars.clone(),
+        );
+
+        let config_service = SwingBuddy::services::config::ConfigService::new(
+            shared_settings.clone(),
+            feature_flag_service.clone(),
+        );
+        let notification_queue_service = SwingBuddy::services::notification_queue::NotificationQueueService::new(
+            bot.clone(),
+            database_service.notification_queue.clone(),
+            database_service.users.clone(),
+            notification_service.clone(),
+        );
+        let notification_template_service = SwingBuddy::services::notification_template::NotificationTemplateService::new(
+            database_service.notification_templates.clone(),
+            notification_service.clone(),
+            shared_settings.clone(),
+        );
+
+        let public_api_service = SwingBuddy::services::public_api::PublicApiService::new(
+            database_service.events.clone(),
+            database_service.calendars.clone(),
+            database_service.users.clone(),
+            database_service.groups.clone(),
+            shared_settings.clone(),
+        );
+        let webapp_service = SwingBuddy::services::webapp::WebAppService::new(
+            public_api_service.clone(),
+            database_service.events.clone(),
+            database_service.users.clone(),
+            shared_settings.clone(),
+        );
+
         // Create service factory
         let service_factory = SwingBuddy::services::ServiceFactory {
             user_service,
@@ -214,6 +462,42 @@ impl TestContext {
             cas_service,
             redis_service,
             google_service,
+            google_oauth_service,
+            google_watch_service,
+            sheet_export_service,
+            event_service,
+            note_service,
+            referral_service,
+            reminder_service,
+            birthday_service,
+            dance_style_service,
+            captcha_service,
+            group_service,
+            moderation_service,
+            cleanup_service,
+            archive_service,
+            digest_service,
+            quiet_hours_service,
+            stats_service,
+            cas_recheck_service,
+            cas_queue_service,
+            invite_link_service,
+            song_request_service,
+            venue_service,
+            broadcast_service,
+            admin_audit_service,
+            activity_log_service,
+            feature_flag_service,
+            backup_service,
+            scheduled_message_service,
+            calendar_service,
+            config_service,
+            notification_queue_service,
+            notification_template_service,
+            public_api_service,
+            webapp_service,
+            outgoing_webhook_service,
+            database_pool: database_service.pool.clone(),
         };
 
         // Create app context using factory (now async)
@@ -7,7 +7,7 @@ use teloxide::types::{
     Message, User, Chat, ChatKind, MessageKind, MessageCommon, CallbackQuery, 
     InlineKeyboardButton, InlineKeyboardMarkup, UserId, ChatId, MessageId,
     ChatPrivate, ChatPublic, PublicChatKind, PublicChatSupergroup,
-    MediaKind, MediaText
+    MediaKind, MediaText, ChatMember, ChatMemberKind, ChatMemberUpdated
 };
 use chrono::Utc;
 
@@ -160,6 +160,27 @@ pub fn create_simple_test_callback_query(user_id: i64, chat_id: i64, data: &str)
     )
 }
 
+/// Helper function to create a test update of a group member's status, changed by the member
+pub fn create_test_chat_member_update(
+    chat_id: i64,
+    user_id: i64,
+    old: ChatMemberKind,
+    new: ChatMemberKind,
+) -> ChatMemberUpdated {
+    let user = create_test_user(user_id, Some("testuser"), "TestUser", None, Some("en"));
+
+    ChatMemberUpdated {
+        chat: create_test_group_chat(chat_id, "Test Group"),
+        from: user.clone(),
+        date: Utc::now(),
+        old_chat_member: ChatMember { user: user.clone(), kind: old },
+        new_chat_member: ChatMember { user, kind: new },
+        invite_link: None,
+        via_join_request: false,
+        via_chat_folder_invite_link: false,
+    }
+}
+
 /// Helper function to create an inline keyboard with language options
 pub fn create_language_keyboard() -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(vec![
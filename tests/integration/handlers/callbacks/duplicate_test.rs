@@ -0,0 +1,77 @@
+//! Integration tests for double-tapped inline buttons
+//!
+//! This module checks that a callback repeated within the deduplication
+//! window is answered, so the button stops spinning, but not handled again.
+
+use std::time::Duration;
+use serial_test::serial;
+use SwingBuddy::handlers::callbacks::handle_callback_query;
+
+use crate::helpers::{TestContext, TestConfig, create_simple_test_callback_query, test_bot_me};
+
+const USER_ID: i64 = 123456789;
+
+/// Tap a button; an unsupported language is answered with a message every time it is handled
+async fn tap(ctx: &TestContext, data: &str) {
+    let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+
+    let result = handle_callback_query(
+        bot,
+        create_simple_test_callback_query(USER_ID, USER_ID, data),
+        test_bot_me(),
+        (*app_state.services).clone(),
+        (*app_state.scenario_manager).clone(),
+        (*app_state.state_storage).clone(),
+        (*app_state.i18n).clone(),
+    ).await;
+    assert!(result.is_ok(), "Callback should succeed: {:?}", result);
+}
+
+/// Test that a second identical callback within the TTL is answered but not handled
+#[tokio::test]
+#[serial]
+async fn test_double_tap_handled_once() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: true,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+
+    tap(&ctx, "lang:xx").await;
+    tap(&ctx, "lang:xx").await;
+
+    ctx.verify_telegram_calls("AnswerCallbackQuery", 2).await;
+    ctx.verify_telegram_calls("SendMessage", 1).await;
+
+    // Another button is not a duplicate
+    tap(&ctx, "lang:yy").await;
+    ctx.verify_telegram_calls("AnswerCallbackQuery", 3).await;
+    ctx.verify_telegram_calls("SendMessage", 2).await;
+}
+
+/// Test that the same button is handled again once the TTL ran out
+#[tokio::test]
+#[serial]
+async fn test_tap_handled_again_after_ttl() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: true,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+
+    tap(&ctx, "lang:xx").await;
+    tokio::time::sleep(Duration::from_secs(4)).await;
+    tap(&ctx, "lang:xx").await;
+
+    ctx.verify_telegram_calls("AnswerCallbackQuery", 2).await;
+    ctx.verify_telegram_calls("SendMessage", 2).await;
+}
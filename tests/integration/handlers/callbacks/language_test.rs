@@ -9,7 +9,7 @@ use SwingBuddy::handlers::commands::start;
 use SwingBuddy::models::user::{User as DbUser};
 use SwingBuddy::state::ConversationContext;
 
-use crate::helpers::{TestContext, TestConfig, create_simple_test_callback_query, create_simple_test_message};
+use crate::helpers::{TestContext, TestConfig, create_simple_test_callback_query, create_simple_test_message, test_bot_me};
 
 /// Test language selection callback during onboarding
 #[tokio::test]
@@ -50,6 +50,7 @@ async fn test_language_selection_callback_onboarding() {
     let result = handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -139,6 +140,7 @@ async fn test_russian_language_selection_callback() {
     let result = handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -227,6 +229,7 @@ async fn test_invalid_language_callback() {
         let result = handle_callback_query(
             bot.clone(),
             lang_callback,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -281,6 +284,7 @@ async fn test_language_callback_no_context() {
     let _result = handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -326,7 +330,7 @@ async fn test_language_callback_wrong_step() {
     context.scenario = Some("onboarding".to_string());
     context.step = Some("name_input".to_string());
     
-    app_state.state_storage.save_context(&context).await
+    app_state.state_storage.save_context(&mut context).await
         .expect("Failed to save context");
     
     // Try language selection in wrong step
@@ -335,6 +339,7 @@ async fn test_language_callback_wrong_step() {
     let result = handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -399,6 +404,7 @@ async fn test_concurrent_language_callbacks() {
         handle_callback_query(
             bot.clone(),
             lang_callback1,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -407,6 +413,7 @@ async fn test_concurrent_language_callbacks() {
         handle_callback_query(
             bot.clone(),
             lang_callback2,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -415,6 +422,7 @@ async fn test_concurrent_language_callbacks() {
         handle_callback_query(
             bot.clone(),
             lang_callback3,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -548,6 +556,7 @@ async fn test_malformed_language_callback_data() {
         let result = handle_callback_query(
             bot.clone(),
             callback,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
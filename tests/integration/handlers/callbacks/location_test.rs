@@ -14,7 +14,7 @@ use sqlx::FromRow;
 
 use SwingBuddy::state::ConversationContext;
 
-use crate::helpers::{TestContext, TestConfig, create_simple_test_callback_query, create_simple_test_message, DbUser};
+use crate::helpers::{TestContext, TestConfig, create_simple_test_callback_query, create_simple_test_message, test_bot_me, DbUser};
 
 /// Test location selection callback during onboarding
 #[tokio::test]
@@ -52,6 +52,7 @@ async fn test_location_selection_callback_onboarding() {
     handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -75,6 +76,7 @@ async fn test_location_selection_callback_onboarding() {
     let result = handle_callback_query(
         bot.clone(),
         location_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -154,6 +156,7 @@ async fn test_saint_petersburg_location_selection() {
     handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -176,6 +179,7 @@ async fn test_saint_petersburg_location_selection() {
     let result = handle_callback_query(
         bot.clone(),
         location_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -249,6 +253,7 @@ async fn test_location_skip_callback() {
     handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -271,6 +276,7 @@ async fn test_location_skip_callback() {
     let result = handle_callback_query(
         bot.clone(),
         skip_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -351,6 +357,7 @@ async fn test_invalid_location_callback() {
     handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -386,6 +393,7 @@ async fn test_invalid_location_callback() {
         let result = handle_callback_query(
             bot.clone(),
             location_callback,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -436,6 +444,7 @@ async fn test_location_callback_no_context() {
     let result = handle_callback_query(
         bot.clone(),
         location_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -480,7 +489,7 @@ async fn test_location_callback_wrong_step() {
     context.scenario = Some("onboarding".to_string());
     context.step = Some("language_selection".to_string());
     
-    app_state.state_storage.save_context(&context).await
+    app_state.state_storage.save_context(&mut context).await
         .expect("Failed to save context");
     
     // Try location selection in wrong step
@@ -489,6 +498,7 @@ async fn test_location_callback_wrong_step() {
     let result = handle_callback_query(
         bot.clone(),
         location_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -551,6 +561,7 @@ async fn test_concurrent_location_callbacks() {
         handle_callback_query(
             bot.clone(),
             lang_callback,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -585,6 +596,7 @@ async fn test_concurrent_location_callbacks() {
         handle_callback_query(
             bot.clone(),
             location_callback1,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -593,6 +605,7 @@ async fn test_concurrent_location_callbacks() {
         handle_callback_query(
             bot.clone(),
             location_callback2,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -601,6 +614,7 @@ async fn test_concurrent_location_callbacks() {
         handle_callback_query(
             bot.clone(),
             location_callback3,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -723,6 +737,7 @@ async fn test_malformed_location_callback_data() {
     handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -754,6 +769,7 @@ async fn test_malformed_location_callback_data() {
         let result = handle_callback_query(
             bot.clone(),
             callback,
+            test_bot_me(),
             (*app_state.services).clone(),
             (*app_state.scenario_manager).clone(),
             (*app_state.state_storage).clone(),
@@ -2,6 +2,7 @@
 //!
 //! This module contains integration tests for all bot callback handlers.
 
+pub mod duplicate_test;
 pub mod language_test;
 pub mod location_test;
 
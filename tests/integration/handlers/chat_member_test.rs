@@ -0,0 +1,147 @@
+//! Integration tests for group member status updates
+//!
+//! This module feeds synthetic `chat_member` updates to the handler and
+//! checks the member records, and checks that the webhook asks Telegram
+//! for those updates at all.
+
+use serial_test::serial;
+use teloxide::types::{Banned, ChatMemberKind, UntilDate};
+use wiremock::{Mock, ResponseTemplate, matchers::{method, path}};
+use SwingBuddy::handlers::messages::handle_chat_member_update;
+use SwingBuddy::models::{Role, SetGroupRoleRequest};
+use SwingBuddy::utils::webhook::{register_webhook, webhook_options};
+
+use crate::helpers::{TestContext, TestConfig, create_test_chat_member_update, test_bot_token};
+
+const GROUP_ID: i64 = -1001234567890;
+const USER_ID: i64 = 123456789;
+
+async fn test_context() -> TestContext {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    ctx.load_fixtures().await.expect("Failed to load fixtures");
+    ctx
+}
+
+/// Role of the member record, if there is one
+async fn member_role(ctx: &TestContext, user_id: i64) -> Option<String> {
+    sqlx::query_scalar(
+        r#"
+        SELECT gm.role FROM group_members gm
+        JOIN groups g ON g.id = gm.group_id
+        JOIN users u ON u.id = gm.user_id
+        WHERE g.telegram_id = $1 AND u.telegram_id = $2
+        "#
+    )
+    .bind(GROUP_ID)
+    .bind(user_id)
+    .fetch_optional(ctx.db_pool())
+    .await
+    .expect("Failed to read member")
+}
+
+/// Value of a field of a `multipart/form-data` body
+fn form_field(body: &str, name: &str) -> Option<String> {
+    let header = format!("Content-Disposition: form-data; name=\"{}\"", name);
+    let (_, rest) = body.split_once(&header)?;
+    let (_, value) = rest.split_once("\r\n\r\n")?;
+    let (value, _) = value.split_once("\r\n--")?;
+    Some(value.to_string())
+}
+
+/// Test that a member joining the group gets a member record
+#[tokio::test]
+#[serial]
+async fn test_member_join_recorded() {
+    let ctx = test_context().await;
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    assert_eq!(member_role(&ctx, USER_ID).await, None);
+
+    let update = create_test_chat_member_update(GROUP_ID, USER_ID, ChatMemberKind::Left, ChatMemberKind::Member);
+    handle_chat_member_update(update, (*app_state.services).clone()).await
+        .expect("Join should be handled");
+
+    assert_eq!(member_role(&ctx, USER_ID).await.as_deref(), Some("member"));
+}
+
+/// Test that a member leaving the group loses the member record and their delegated role
+#[tokio::test]
+#[serial]
+async fn test_member_leave_forgotten() {
+    let ctx = test_context().await;
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    let services = (*app_state.services).clone();
+
+    let join = create_test_chat_member_update(GROUP_ID, USER_ID, ChatMemberKind::Left, ChatMemberKind::Member);
+    handle_chat_member_update(join, services.clone()).await.expect("Join should be handled");
+    app_state.database.roles.set_role(SetGroupRoleRequest {
+        chat_telegram_id: GROUP_ID,
+        user_telegram_id: USER_ID,
+        role: Role::Moderator,
+        granted_by: 555666777,
+    }).await.expect("Failed to grant role");
+
+    let leave = create_test_chat_member_update(GROUP_ID, USER_ID, ChatMemberKind::Member, ChatMemberKind::Left);
+    handle_chat_member_update(leave, services).await.expect("Leave should be handled");
+
+    assert_eq!(member_role(&ctx, USER_ID).await, None);
+    assert!(app_state.database.roles.find_role(GROUP_ID, USER_ID).await.unwrap().is_none());
+}
+
+/// Test that a banned member is forgotten like one who left, and other members are kept
+#[tokio::test]
+#[serial]
+async fn test_member_ban_forgotten() {
+    let ctx = test_context().await;
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    let services = (*app_state.services).clone();
+
+    let join = create_test_chat_member_update(GROUP_ID, USER_ID, ChatMemberKind::Left, ChatMemberKind::Member);
+    handle_chat_member_update(join, services.clone()).await.expect("Join should be handled");
+
+    let banned = ChatMemberKind::Banned(Banned { until_date: UntilDate::Forever });
+    let ban = create_test_chat_member_update(GROUP_ID, USER_ID, ChatMemberKind::Member, banned);
+    handle_chat_member_update(ban, services).await.expect("Ban should be handled");
+
+    assert_eq!(member_role(&ctx, USER_ID).await, None);
+    assert_eq!(member_role(&ctx, 555666777).await.as_deref(), Some("admin"), "Other members are left alone");
+}
+
+/// Test that the webhook asks Telegram for every update kind the bot handles,
+/// including member changes Telegram leaves out by default
+#[tokio::test]
+#[serial]
+async fn test_webhook_requests_allowed_updates() {
+    let mut ctx = test_context().await;
+    Mock::given(method("POST"))
+        .and(path(format!("/bot{}/SetWebhook", test_bot_token())))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "ok": true, "result": true })))
+        .mount(&ctx.telegram_mock.server)
+        .await;
+
+    ctx.settings.bot.webhook_url = Some("https://bot.example.com/telegram".to_string());
+    let mut options = webhook_options(&ctx.settings).expect("Invalid webhook settings").expect("Webhook should be configured");
+    let bot = ctx.create_bot().await.expect("Failed to create bot");
+    register_webhook(&bot, &mut options).await.expect("Failed to register webhook");
+
+    let requests = ctx.telegram_mock.server.received_requests().await.unwrap();
+    let request = requests.iter()
+        .find(|request| request.url.path().ends_with("/SetWebhook"))
+        .expect("Webhook should be registered");
+    let body = String::from_utf8_lossy(&request.body);
+    assert_eq!(form_field(&body, "url").as_deref(), Some("https://bot.example.com/telegram"));
+    let allowed_updates: serde_json::Value = serde_json::from_str(&form_field(&body, "allowed_updates").expect("Allowed updates should be sent"))
+        .expect("Allowed updates should be a JSON list");
+    assert_eq!(
+        allowed_updates,
+        serde_json::json!(["message", "callback_query", "my_chat_member", "chat_member", "chat_join_request"])
+    );
+    assert!(form_field(&body, "secret_token").is_some_and(|token| !token.is_empty()));
+}
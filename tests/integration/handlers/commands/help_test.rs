@@ -4,7 +4,8 @@
 //! including different contexts, language support, and message formatting.
 
 use serial_test::serial;
-use SwingBuddy::handlers::commands::help;
+use teloxide::utils::command::BotCommands;
+use SwingBuddy::handlers::commands::{help, Command};
 
 use crate::helpers::{TestContext, TestConfig, create_simple_test_message, create_test_message};
 
@@ -13,7 +14,7 @@ use crate::helpers::{TestContext, TestConfig, create_simple_test_message, create
 #[serial]
 async fn test_help_command_private_chat() {
     let config = TestConfig {
-        use_database: false, // Help command doesn't need database
+        use_database: true,
         use_redis: false,
         setup_default_mocks: true,
         bot_token: None,
@@ -23,6 +24,7 @@ async fn test_help_command_private_chat() {
         .expect("Failed to create test context");
     
     let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
     
     let user_id = 123456789i64;
     let chat_id = user_id; // Private chat
@@ -30,7 +32,7 @@ async fn test_help_command_private_chat() {
     // Create /help message
     let help_message = create_simple_test_message(user_id, chat_id, "/help");
     
-    let result = help::handle_help(bot.clone(), help_message).await;
+    let result = help::handle_help(bot.clone(), help_message, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()).await;
     
     assert!(result.is_ok(), "Help command should succeed: {:?}", result);
     
@@ -45,7 +47,7 @@ async fn test_help_command_private_chat() {
 #[serial]
 async fn test_help_command_group_chat() {
     let config = TestConfig {
-        use_database: false,
+        use_database: true,
         use_redis: false,
         setup_default_mocks: true,
         bot_token: None,
@@ -55,6 +57,7 @@ async fn test_help_command_group_chat() {
         .expect("Failed to create test context");
     
     let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
     
     let user_id = 123456790i64;
     let group_chat_id = -1001234567890i64; // Group chat
@@ -69,7 +72,7 @@ async fn test_help_command_group_chat() {
         Some("LastName"),
     );
     
-    let result = help::handle_help(bot.clone(), help_message).await;
+    let result = help::handle_help(bot.clone(), help_message, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()).await;
     
     assert!(result.is_ok(), "Help command should succeed in group chat: {:?}", result);
     
@@ -84,7 +87,7 @@ async fn test_help_command_group_chat() {
 #[serial]
 async fn test_help_command_message_content() {
     let config = TestConfig {
-        use_database: false,
+        use_database: true,
         use_redis: false,
         setup_default_mocks: true,
         bot_token: None,
@@ -94,13 +97,14 @@ async fn test_help_command_message_content() {
         .expect("Failed to create test context");
     
     let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
     
     let user_id = 123456791i64;
     let chat_id = user_id;
     
     let help_message = create_simple_test_message(user_id, chat_id, "/help");
     
-    let result = help::handle_help(bot.clone(), help_message).await;
+    let result = help::handle_help(bot.clone(), help_message, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()).await;
     
     assert!(result.is_ok(), "Help command should succeed: {:?}", result);
     
@@ -119,7 +123,7 @@ async fn test_help_command_message_content() {
 #[serial]
 async fn test_help_command_different_users() {
     let config = TestConfig {
-        use_database: false,
+        use_database: true,
         use_redis: false,
         setup_default_mocks: true,
         bot_token: None,
@@ -129,6 +133,7 @@ async fn test_help_command_different_users() {
         .expect("Failed to create test context");
     
     let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
     
     // Test with user having username
     let user_with_username = create_test_message(
@@ -140,7 +145,7 @@ async fn test_help_command_different_users() {
         Some("WithUsername"),
     );
     
-    let result1 = help::handle_help(bot.clone(), user_with_username).await;
+    let result1 = help::handle_help(bot.clone(), user_with_username, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()).await;
     assert!(result1.is_ok(), "Help should work for user with username: {:?}", result1);
     
     // Test with user without username
@@ -153,7 +158,7 @@ async fn test_help_command_different_users() {
         Some("WithoutUsername"),
     );
     
-    let result2 = help::handle_help(bot.clone(), user_without_username).await;
+    let result2 = help::handle_help(bot.clone(), user_without_username, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()).await;
     assert!(result2.is_ok(), "Help should work for user without username: {:?}", result2);
     
     // Test with user having only first name
@@ -166,7 +171,7 @@ async fn test_help_command_different_users() {
         None, // No last name
     );
     
-    let result3 = help::handle_help(bot.clone(), user_minimal).await;
+    let result3 = help::handle_help(bot.clone(), user_minimal, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()).await;
     assert!(result3.is_ok(), "Help should work for minimal user info: {:?}", result3);
     
     // Verify all help messages were sent
@@ -180,7 +185,7 @@ async fn test_help_command_different_users() {
 #[serial]
 async fn test_concurrent_help_commands() {
     let config = TestConfig {
-        use_database: false,
+        use_database: true,
         use_redis: false,
         setup_default_mocks: true,
         bot_token: None,
@@ -190,6 +195,7 @@ async fn test_concurrent_help_commands() {
         .expect("Failed to create test context");
     
     let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
     
     let user1_id = 123456795i64;
     let user2_id = 123456796i64;
@@ -202,9 +208,9 @@ async fn test_concurrent_help_commands() {
     
     // Execute all help commands concurrently
     let (result1, result2, result3) = tokio::join!(
-        help::handle_help(bot.clone(), help_message1),
-        help::handle_help(bot.clone(), help_message2),
-        help::handle_help(bot.clone(), help_message3)
+        help::handle_help(bot.clone(), help_message1, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()),
+        help::handle_help(bot.clone(), help_message2, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()),
+        help::handle_help(bot.clone(), help_message3, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone())
     );
     
     assert!(result1.is_ok(), "User 1 help should succeed: {:?}", result1);
@@ -222,7 +228,7 @@ async fn test_concurrent_help_commands() {
 #[serial]
 async fn test_help_command_with_api_errors() {
     let config = TestConfig {
-        use_database: false,
+        use_database: true,
         use_redis: false,
         setup_default_mocks: false, // Don't setup default mocks
         bot_token: None,
@@ -235,13 +241,14 @@ async fn test_help_command_with_api_errors() {
     ctx.setup_telegram_mocks(crate::helpers::MockScenario::Error).await;
     
     let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
     
     let user_id = 123456798i64;
     let chat_id = user_id;
     
     let help_message = create_simple_test_message(user_id, chat_id, "/help");
     
-    let result = help::handle_help(bot.clone(), help_message).await;
+    let result = help::handle_help(bot.clone(), help_message, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()).await;
     
     // The command should fail due to API error
     assert!(result.is_err(), "Help command should fail with API error");
@@ -257,7 +264,7 @@ async fn test_help_command_with_api_errors() {
 #[serial]
 async fn test_help_command_with_timeout() {
     let config = TestConfig {
-        use_database: false,
+        use_database: true,
         use_redis: false,
         setup_default_mocks: false,
         bot_token: None,
@@ -270,6 +277,7 @@ async fn test_help_command_with_timeout() {
     ctx.setup_telegram_mocks(crate::helpers::MockScenario::Timeout).await;
     
     let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
     
     let user_id = 123456799i64;
     let chat_id = user_id;
@@ -280,7 +288,7 @@ async fn test_help_command_with_timeout() {
     let timeout_duration = std::time::Duration::from_secs(2);
     let result = tokio::time::timeout(
         timeout_duration,
-        help::handle_help(bot.clone(), help_message)
+        help::handle_help(bot.clone(), help_message, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone())
     ).await;
     
     // The command should timeout
@@ -294,7 +302,7 @@ async fn test_help_command_with_timeout() {
 #[serial]
 async fn test_help_command_performance() {
     let config = TestConfig {
-        use_database: false,
+        use_database: true,
         use_redis: false,
         setup_default_mocks: true,
         bot_token: None,
@@ -304,6 +312,7 @@ async fn test_help_command_performance() {
         .expect("Failed to create test context");
     
     let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
     
     let user_id = 123456800i64;
     let chat_id = user_id;
@@ -312,7 +321,7 @@ async fn test_help_command_performance() {
     
     // Measure execution time
     let start_time = std::time::Instant::now();
-    let result = help::handle_help(bot.clone(), help_message).await;
+    let result = help::handle_help(bot.clone(), help_message, String::new(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()).await;
     let execution_time = start_time.elapsed();
     
     assert!(result.is_ok(), "Help command should succeed: {:?}", result);
@@ -324,4 +333,32 @@ async fn test_help_command_performance() {
     ctx.verify_telegram_calls("sendMessage", 1).await;
     
     ctx.cleanup().await.expect("Failed to cleanup test context");
-}
\ No newline at end of file
+}
+/// Test /help <command> showing a single command's details
+#[tokio::test]
+#[serial]
+async fn test_help_command_details() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+    
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    
+    let bot = ctx.create_bot().await.expect("Failed to create bot");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    
+    let user_id = 123456801i64;
+    let help_message = create_simple_test_message(user_id, user_id, "/help events");
+    
+    let result = help::handle_help(bot.clone(), help_message, "events".to_string(), Command::bot_commands(), (*app_state.services).clone(), (*app_state.i18n).clone()).await;
+    
+    assert!(result.is_ok(), "Help for a command should succeed: {:?}", result);
+    
+    ctx.verify_telegram_calls("sendMessage", 1).await;
+    
+    ctx.cleanup().await.expect("Failed to cleanup test context");
+}
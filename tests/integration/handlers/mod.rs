@@ -5,6 +5,7 @@
 
 pub mod commands;
 pub mod callbacks;
+pub mod chat_member_test;
 
 #[cfg(test)]
 mod tests {
@@ -4,7 +4,10 @@
 //! organized by functionality and test scenarios.
 
 pub mod handlers;
+pub mod repositories;
 pub mod scenarios;
+pub mod services;
+pub mod state;
 
 use std::sync::Once;
 use tracing_subscriber;
@@ -76,7 +79,7 @@ pub async fn run_complete_user_journey(
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     use SwingBuddy::handlers::commands::start;
     use SwingBuddy::handlers::callbacks::handle_callback_query;
-    use crate::helpers::{create_simple_test_message, create_simple_test_callback_query};
+    use crate::helpers::{create_simple_test_message, create_simple_test_callback_query, test_bot_me};
     
     let bot = ctx.create_bot().await?;
     let app_state = ctx.create_app_state().await?;
@@ -97,6 +100,7 @@ pub async fn run_complete_user_journey(
     handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -125,6 +129,7 @@ pub async fn run_complete_user_journey(
     handle_callback_query(
         bot.clone(),
         location_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -0,0 +1,55 @@
+//! Integration tests for the birthday repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::BirthdayRepository;
+use SwingBuddy::models::SetBirthdayRequest;
+
+use super::create_user;
+use crate::helpers::TestDatabase;
+
+/// Test that setting a birthday again replaces the date and keeps the greeting state
+#[tokio::test]
+#[serial]
+async fn test_upsert_replaces_date() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = BirthdayRepository::new(db.pool.clone());
+    let user = create_user(&db.pool, 100001, None, "en").await;
+
+    repository.upsert(SetBirthdayRequest { user_id: user.id, birth_day: 1, birth_month: 2 }).await
+        .expect("Failed to set birthday");
+    repository.mark_greeted(user.id, 2025).await.expect("Failed to mark greeted");
+
+    let birthday = repository.upsert(SetBirthdayRequest { user_id: user.id, birth_day: 14, birth_month: 3 }).await
+        .expect("Failed to update birthday");
+    assert_eq!((birthday.birth_day, birthday.birth_month), (14, 3));
+    assert_eq!(birthday.last_greeted_year, Some(2025));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_birthdays")
+        .fetch_one(&db.pool)
+        .await
+        .expect("Failed to count birthdays");
+    assert_eq!(count, 1, "The second upsert should update the row");
+}
+
+/// Test that birthdays already greeted this year are not returned again
+#[tokio::test]
+#[serial]
+async fn test_pending_greetings_skip_greeted() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = BirthdayRepository::new(db.pool.clone());
+    let greeted = create_user(&db.pool, 100001, None, "en").await;
+    let pending = create_user(&db.pool, 100002, None, "en").await;
+
+    for user_id in [greeted.id, pending.id] {
+        repository.upsert(SetBirthdayRequest { user_id, birth_day: 5, birth_month: 6 }).await
+            .expect("Failed to set birthday");
+    }
+    repository.mark_greeted(greeted.id, 2026).await.expect("Failed to mark greeted");
+
+    let due = repository.get_pending_greetings(6, &[5], 2026).await.expect("Failed to get greetings");
+    assert_eq!(due.iter().map(|b| b.user_id).collect::<Vec<_>>(), vec![pending.id]);
+
+    // A new year makes both due again
+    let due = repository.get_pending_greetings(6, &[5], 2027).await.expect("Failed to get greetings");
+    assert_eq!(due.len(), 2);
+}
@@ -0,0 +1,43 @@
+//! Integration tests for the broadcast repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::BroadcastRepository;
+use SwingBuddy::models::{BroadcastSegment, CreateBroadcastRequest};
+
+use super::{create_user, delete_user};
+use crate::helpers::TestDatabase;
+
+/// Test that deleted users are left out of segments, city statistics and queued deliveries
+#[tokio::test]
+#[serial]
+async fn test_deleted_users_not_reached() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = BroadcastRepository::new(db.pool.clone());
+    let kept = create_user(&db.pool, 100001, Some("Moscow"), "ru").await;
+    let deleted = create_user(&db.pool, 100002, Some("moscow"), "ru").await;
+    create_user(&db.pool, 100003, Some("Berlin"), "en").await;
+    delete_user(&db.pool, deleted.id).await;
+
+    let moscow = BroadcastSegment::City("MOSCOW".to_string());
+    assert_eq!(repository.count_recipients(&BroadcastSegment::All).await.expect("Failed to count"), 2);
+    assert_eq!(repository.count_recipients(&moscow).await.expect("Failed to count"), 1);
+    assert_eq!(repository.count_recipients(&BroadcastSegment::Language("ru".to_string())).await.expect("Failed to count"), 1);
+    assert_eq!(repository.sample_recipient(&moscow).await.expect("Failed to sample"), Some(kept.telegram_id));
+
+    let cities = repository.get_top_cities(10).await.expect("Failed to get cities");
+    assert_eq!(cities.iter().map(|(_, count)| count).sum::<i64>(), 2);
+
+    let broadcast = repository.create(CreateBroadcastRequest {
+        created_by: kept.telegram_id,
+        language_code: "ru".to_string(),
+        segment: moscow,
+        source_chat_id: kept.telegram_id,
+        source_message_id: 1,
+        text: Some("Hello".to_string()),
+    }).await.expect("Failed to create broadcast");
+    assert_eq!(broadcast.total, 1);
+    assert_eq!(
+        repository.get_pending_recipients(broadcast.id, 10).await.expect("Failed to get recipients"),
+        vec![kept.telegram_id]
+    );
+}
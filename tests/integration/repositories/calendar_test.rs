@@ -0,0 +1,39 @@
+//! Integration tests for the calendar repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::CalendarRepository;
+use SwingBuddy::models::{CreateCalendarRequest, UpdateCalendarRequest};
+
+use super::create_user;
+use crate::helpers::TestDatabase;
+
+/// Test creating, updating and deleting a calendar
+#[tokio::test]
+#[serial]
+async fn test_calendar_lifecycle() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = CalendarRepository::new(db.pool.clone());
+    let admin = create_user(&db.pool, 100001, None, "en").await;
+    let seeded = repository.list().await.expect("Failed to list").len();
+
+    let calendar = repository.create(CreateCalendarRequest {
+        name: "Socials".to_string(),
+        description: None,
+        google_calendar_id: None,
+        created_by: admin.telegram_id,
+    }).await.expect("Failed to create calendar");
+
+    let updated = repository.update(calendar.id, UpdateCalendarRequest {
+        name: "Parties".to_string(),
+        description: Some("Weekend parties".to_string()),
+        google_calendar_id: Some("parties@group.calendar.google.com".to_string()),
+    }).await.expect("Failed to update calendar");
+    assert_eq!(updated.name, "Parties");
+    assert_eq!(updated.google_calendar_id.as_deref(), Some("parties@group.calendar.google.com"));
+    assert!(updated.updated_at >= calendar.updated_at);
+
+    assert_eq!(repository.list().await.expect("Failed to list").len(), seeded + 1);
+    assert!(repository.delete(calendar.id).await.expect("Failed to delete"));
+    assert!(!repository.delete(calendar.id).await.expect("Failed to delete"));
+    assert!(repository.find_by_id(calendar.id).await.expect("Failed to find").is_none());
+}
@@ -0,0 +1,40 @@
+//! Integration tests for the captcha repository
+
+use chrono::{Duration, Utc};
+use serial_test::serial;
+use SwingBuddy::database::repositories::CaptchaRepository;
+use SwingBuddy::models::CreatePendingCaptchaRequest;
+
+use crate::helpers::TestDatabase;
+
+const CHAT_ID: i64 = -1001234567890;
+
+/// Test that a new captcha for the same member replaces the old one and forgets its message
+#[tokio::test]
+#[serial]
+async fn test_upsert_replaces_pending_captcha() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = CaptchaRepository::new(db.pool.clone());
+
+    let first = repository.upsert(CreatePendingCaptchaRequest {
+        chat_telegram_id: CHAT_ID,
+        user_telegram_id: 100001,
+        answer: "7".to_string(),
+        expires_at: Utc::now() - Duration::minutes(1),
+    }).await.expect("Failed to create captcha");
+    repository.set_message_id(first.id, 42).await.expect("Failed to set message");
+
+    let second = repository.upsert(CreatePendingCaptchaRequest {
+        chat_telegram_id: CHAT_ID,
+        user_telegram_id: 100001,
+        answer: "12".to_string(),
+        expires_at: Utc::now() + Duration::minutes(5),
+    }).await.expect("Failed to replace captcha");
+    assert_eq!(second.id, first.id);
+    assert_eq!(second.answer, "12");
+    assert_eq!(second.message_id, None);
+
+    let stored = repository.find(CHAT_ID, 100001).await.expect("Failed to find").expect("Captcha should exist");
+    assert_eq!(stored.answer, "12");
+    assert!(repository.get_expired(Utc::now()).await.expect("Failed to get expired").is_empty());
+}
@@ -0,0 +1,31 @@
+//! Integration tests for the cleanup repository
+
+use chrono::{Duration, Utc};
+use serial_test::serial;
+use SwingBuddy::database::repositories::CleanupRepository;
+use SwingBuddy::models::CreateScheduledDeletionRequest;
+
+use crate::helpers::TestDatabase;
+
+const CHAT_ID: i64 = -1001234567890;
+
+/// Test that scheduling the same message twice keeps the first deletion time
+#[tokio::test]
+#[serial]
+async fn test_schedule_ignores_duplicates() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = CleanupRepository::new(db.pool.clone());
+    let now = Utc::now();
+
+    repository.schedule(CreateScheduledDeletionRequest { chat_telegram_id: CHAT_ID, message_id: 10, delete_at: now - Duration::minutes(1) }).await
+        .expect("Failed to schedule");
+    repository.schedule(CreateScheduledDeletionRequest { chat_telegram_id: CHAT_ID, message_id: 10, delete_at: now + Duration::hours(1) }).await
+        .expect("Scheduling twice should not fail");
+    repository.schedule(CreateScheduledDeletionRequest { chat_telegram_id: CHAT_ID, message_id: 11, delete_at: now + Duration::hours(1) }).await
+        .expect("Failed to schedule");
+
+    let due = repository.get_due(now).await.expect("Failed to get due");
+    assert_eq!(due.iter().map(|d| d.message_id).collect::<Vec<_>>(), vec![10]);
+
+    assert_eq!(repository.delete_for_chat(CHAT_ID).await.expect("Failed to delete"), 2);
+}
@@ -0,0 +1,65 @@
+//! Integration tests for the dance style repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::DanceStyleRepository;
+use SwingBuddy::models::DanceStyle;
+
+use super::{create_event, create_user, delete_user};
+use crate::helpers::TestDatabase;
+
+/// Test that setting styles replaces the old ones
+#[tokio::test]
+#[serial]
+async fn test_set_user_styles_replaces() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = DanceStyleRepository::new(db.pool.clone());
+    let user = create_user(&db.pool, 100001, None, "en").await;
+
+    repository.set_user_styles(user.id, &[DanceStyle::LindyHop, DanceStyle::Balboa]).await
+        .expect("Failed to set styles");
+    repository.set_user_styles(user.id, &[DanceStyle::Blues]).await
+        .expect("Failed to replace styles");
+
+    assert_eq!(repository.get_user_styles(user.id).await.expect("Failed to get styles"), vec![DanceStyle::Blues]);
+}
+
+/// Test that adding and removing a single style leaves the others alone
+#[tokio::test]
+#[serial]
+async fn test_add_and_remove_user_style() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = DanceStyleRepository::new(db.pool.clone());
+    let user = create_user(&db.pool, 100001, None, "en").await;
+    repository.set_user_styles(user.id, &[DanceStyle::LindyHop]).await.expect("Failed to set styles");
+
+    assert!(repository.add_user_style(user.id, DanceStyle::Balboa).await.expect("Failed to add style"));
+    assert!(!repository.add_user_style(user.id, DanceStyle::Balboa).await.expect("Failed to add style twice"));
+    assert!(repository.remove_user_style(user.id, DanceStyle::LindyHop).await.expect("Failed to remove style"));
+    assert!(!repository.remove_user_style(user.id, DanceStyle::LindyHop).await.expect("Failed to remove style twice"));
+
+    assert_eq!(repository.get_user_styles(user.id).await.expect("Failed to get styles"), vec![DanceStyle::Balboa]);
+}
+
+/// Test that deleted users are not matched by style
+#[tokio::test]
+#[serial]
+async fn test_style_matches_skip_deleted_users() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = DanceStyleRepository::new(db.pool.clone());
+    let kept = create_user(&db.pool, 100001, None, "en").await;
+    let deleted = create_user(&db.pool, 100002, None, "en").await;
+    let event = create_event(&db.pool, Some(kept.id), None).await;
+
+    for user in [&kept, &deleted] {
+        repository.set_user_styles(user.id, &[DanceStyle::LindyHop]).await.expect("Failed to set styles");
+    }
+    repository.set_event_styles(event.id, &[DanceStyle::LindyHop, DanceStyle::SoloJazz]).await
+        .expect("Failed to set event styles");
+    delete_user(&db.pool, deleted.id).await;
+
+    let by_style = repository.find_users_by_styles(&[DanceStyle::LindyHop]).await.expect("Failed to find users");
+    assert_eq!(by_style.iter().map(|u| u.id).collect::<Vec<_>>(), vec![kept.id]);
+
+    let for_event = repository.find_users_for_event(event.id).await.expect("Failed to find users");
+    assert_eq!(for_event.iter().map(|u| u.id).collect::<Vec<_>>(), vec![kept.id]);
+}
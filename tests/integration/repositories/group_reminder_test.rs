@@ -0,0 +1,28 @@
+//! Integration tests for group event reminder state
+
+use chrono::{Duration, Utc};
+use serial_test::serial;
+use SwingBuddy::database::repositories::EventRepository;
+
+use super::{create_event, create_group};
+use crate::helpers::TestDatabase;
+
+/// Test that a reminder that keeps failing stops being pending after the last attempt
+#[tokio::test]
+#[serial]
+async fn test_failed_reminders_are_given_up() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = EventRepository::new(db.pool.clone());
+    let group = create_group(&db.pool, -100200300).await;
+    let event = create_event(&db.pool, None, Some(group.id)).await;
+    let until = Utc::now() + Duration::days(30);
+
+    for expected in 1..=2 {
+        assert_eq!(repository.record_group_reminder_failure(event.id).await.expect("Failed to record failure"), expected);
+        let pending = repository.get_pending_group_reminders(until, 3).await.expect("Failed to get pending reminders");
+        assert_eq!(pending.iter().map(|e| e.id).collect::<Vec<_>>(), vec![event.id]);
+    }
+
+    assert_eq!(repository.record_group_reminder_failure(event.id).await.expect("Failed to record failure"), 3);
+    assert!(repository.get_pending_group_reminders(until, 3).await.expect("Failed to get pending reminders").is_empty());
+}
@@ -0,0 +1,53 @@
+//! Integration tests for the invite link repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::InviteLinkRepository;
+use SwingBuddy::models::{CreateInviteLinkRequest, JoinRequestStatus};
+
+use super::create_group;
+use crate::helpers::TestDatabase;
+
+/// Test that a repeated join request updates its status instead of counting twice
+#[tokio::test]
+#[serial]
+async fn test_record_request_upserts() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = InviteLinkRepository::new(db.pool.clone());
+    let group = create_group(&db.pool, -1001234567890).await;
+
+    let link = repository.create(CreateInviteLinkRequest {
+        group_id: group.id,
+        name: "Flyer".to_string(),
+        invite_link: "https://t.me/+flyer".to_string(),
+        created_by: 100001,
+    }).await.expect("Failed to create link");
+
+    repository.record_request(link.id, 200001, JoinRequestStatus::Declined).await.expect("Failed to record");
+    repository.record_request(link.id, 200001, JoinRequestStatus::Approved).await.expect("Failed to record again");
+    repository.record_request(link.id, 200002, JoinRequestStatus::Declined).await.expect("Failed to record");
+
+    let stats = repository.get_stats_for_group(group.id).await.expect("Failed to get stats");
+    assert_eq!(stats.len(), 1);
+    assert_eq!((stats[0].requests, stats[0].approved, stats[0].declined), (2, 1, 1));
+}
+
+/// Test that revoked links are no longer found or listed
+#[tokio::test]
+#[serial]
+async fn test_revoked_links_hidden() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = InviteLinkRepository::new(db.pool.clone());
+    let group = create_group(&db.pool, -1001234567890).await;
+
+    let link = repository.create(CreateInviteLinkRequest {
+        group_id: group.id,
+        name: "Flyer".to_string(),
+        invite_link: "https://t.me/+flyer".to_string(),
+        created_by: 100001,
+    }).await.expect("Failed to create link");
+
+    assert!(repository.revoke(link.id).await.expect("Failed to revoke"));
+    assert!(!repository.revoke(link.id).await.expect("Failed to revoke"), "A link is only revoked once");
+    assert!(repository.find_active_by_link("https://t.me/+flyer").await.expect("Failed to find").is_none());
+    assert!(repository.get_stats_for_group(group.id).await.expect("Failed to get stats").is_empty());
+}
@@ -0,0 +1,60 @@
+//! Integration tests for the member activity repository
+
+use chrono::{Duration, Utc};
+use serial_test::serial;
+use SwingBuddy::database::repositories::MemberActivityRepository;
+
+use crate::helpers::TestDatabase;
+
+const CHAT_ID: i64 = -1001234567890;
+
+async fn last_seen(db: &TestDatabase) -> chrono::DateTime<Utc> {
+    sqlx::query_scalar("SELECT last_seen_at FROM member_activity WHERE chat_telegram_id = $1 AND user_telegram_id = 100001")
+        .bind(CHAT_ID)
+        .fetch_one(&db.pool)
+        .await
+        .expect("Failed to read activity")
+}
+
+/// Test that a touch only moves the stored time once it is stale
+#[tokio::test]
+#[serial]
+async fn test_touch_skips_fresh_rows() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = MemberActivityRepository::new(db.pool.clone());
+    let start = Utc::now() - Duration::hours(2);
+
+    repository.touch(CHAT_ID, 100001, start, start - Duration::hours(1)).await.expect("Failed to touch");
+    let first = last_seen(&db).await;
+
+    // Stored time is newer than `stale_before`, so the row is left alone
+    repository.touch(CHAT_ID, 100001, start + Duration::minutes(5), start - Duration::minutes(1)).await.expect("Failed to touch");
+    assert_eq!(last_seen(&db).await, first);
+
+    // Stored time is older than `stale_before`, so the row is updated
+    let later = start + Duration::hours(1);
+    repository.touch(CHAT_ID, 100001, later, start + Duration::minutes(1)).await.expect("Failed to touch");
+    assert!(last_seen(&db).await > first);
+}
+
+/// Test that members are due for a recheck until checked, never checked first
+#[tokio::test]
+#[serial]
+async fn test_due_for_recheck() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = MemberActivityRepository::new(db.pool.clone());
+    let now = Utc::now();
+
+    repository.touch(CHAT_ID, 100001, now, now).await.expect("Failed to touch");
+    repository.touch(CHAT_ID, 100002, now, now).await.expect("Failed to touch");
+    repository.mark_checked(100001, now - Duration::days(30)).await.expect("Failed to mark checked");
+
+    let due = repository.get_due_for_recheck(now - Duration::days(1), now - Duration::days(7), 10).await
+        .expect("Failed to get due members");
+    assert_eq!(due, vec![100002, 100001]);
+
+    repository.mark_checked(100002, now).await.expect("Failed to mark checked");
+    let due = repository.get_due_for_recheck(now - Duration::days(1), now - Duration::days(7), 10).await
+        .expect("Failed to get due members");
+    assert_eq!(due, vec![100001]);
+}
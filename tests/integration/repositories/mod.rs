@@ -0,0 +1,79 @@
+//! Integration tests for the repositories
+//!
+//! This module runs the repository queries against a freshly migrated
+//! database, so upserts, conflict handling and soft-delete filters are
+//! checked by Postgres itself.
+
+pub mod birthday_test;
+pub mod broadcast_test;
+pub mod calendar_test;
+pub mod captcha_test;
+pub mod cleanup_test;
+pub mod dance_style_test;
+pub mod group_reminder_test;
+pub mod invite_link_test;
+pub mod member_activity_test;
+pub mod note_test;
+pub mod quiet_hours_test;
+pub mod referral_test;
+pub mod role_test;
+pub mod scheduled_message_test;
+pub mod soft_delete_test;
+pub mod song_request_test;
+pub mod stats_test;
+pub mod venue_test;
+
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use SwingBuddy::database::repositories::{EventRepository, GroupRepository, UserRepository};
+use SwingBuddy::models::{CreateEventRequest, CreateGroupRequest, CreateUserRequest, Event, Group, User};
+
+/// Create a user with the given location and language
+pub async fn create_user(pool: &PgPool, telegram_id: i64, location: Option<&str>, language_code: &str) -> User {
+    UserRepository::new(pool.clone())
+        .create(CreateUserRequest {
+            telegram_id,
+            username: Some(format!("user{}", telegram_id)),
+            first_name: Some("Test".to_string()),
+            last_name: None,
+            language_code: Some(language_code.to_string()),
+            location: location.map(str::to_string),
+        })
+        .await
+        .expect("Failed to create user")
+}
+
+/// Soft-delete a user
+pub async fn delete_user(pool: &PgPool, id: i64) {
+    assert!(UserRepository::new(pool.clone()).delete(id).await.expect("Failed to delete user"));
+}
+
+/// Create a group
+pub async fn create_group(pool: &PgPool, telegram_id: i64) -> Group {
+    GroupRepository::new(pool.clone())
+        .create(CreateGroupRequest {
+            telegram_id,
+            title: "Test Group".to_string(),
+            description: None,
+            language_code: Some("en".to_string()),
+            settings: None,
+        })
+        .await
+        .expect("Failed to create group")
+}
+
+/// Create an event a week from now
+pub async fn create_event(pool: &PgPool, created_by: Option<i64>, group_id: Option<i64>) -> Event {
+    EventRepository::new(pool.clone())
+        .create(CreateEventRequest {
+            title: "Test Event".to_string(),
+            description: None,
+            event_date: Utc::now() + Duration::days(7),
+            location: Some("Test Venue".to_string()),
+            max_participants: None,
+            created_by,
+            group_id,
+        })
+        .await
+        .expect("Failed to create event")
+}
@@ -0,0 +1,37 @@
+//! Integration tests for the note repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::NoteRepository;
+use SwingBuddy::models::{CreateUserNoteRequest, UpdateUserNoteRequest};
+
+use super::create_user;
+use crate::helpers::TestDatabase;
+
+/// Test that editing a note keeps the previous text as a revision
+#[tokio::test]
+#[serial]
+async fn test_update_keeps_revision() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = NoteRepository::new(db.pool.clone());
+    let user = create_user(&db.pool, 100001, None, "en").await;
+    let author = create_user(&db.pool, 100002, None, "en").await;
+
+    let note = repository.create(CreateUserNoteRequest {
+        user_id: user.id,
+        author_id: author.id,
+        content: "Leads well".to_string(),
+    }).await.expect("Failed to create note");
+
+    let updated = repository.update(note.id, UpdateUserNoteRequest {
+        content: "Leads and follows well".to_string(),
+        edited_by: author.id,
+    }).await.expect("Failed to update note");
+    assert_eq!(updated.content, "Leads and follows well");
+
+    let revisions = repository.get_revisions(note.id).await.expect("Failed to get revisions");
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(revisions[0].content, "Leads well");
+
+    repository.delete(note.id).await.expect("Failed to delete note");
+    assert!(repository.get_revisions(note.id).await.expect("Failed to get revisions").is_empty());
+}
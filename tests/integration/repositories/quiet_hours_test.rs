@@ -0,0 +1,34 @@
+//! Integration tests for the quiet hours repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::QuietHoursRepository;
+use SwingBuddy::models::CreateQueuedPostRequest;
+
+use super::create_group;
+use crate::helpers::TestDatabase;
+
+/// Test that queued posts come back per group, oldest first, and are cleared per group
+#[tokio::test]
+#[serial]
+async fn test_queue_per_group() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = QuietHoursRepository::new(db.pool.clone());
+    let quiet = create_group(&db.pool, -1001234567890).await.id;
+    let other = create_group(&db.pool, -1009876543210).await.id;
+
+    for (group_id, text) in [(quiet, "first"), (other, "other"), (quiet, "second")] {
+        repository.enqueue(CreateQueuedPostRequest { group_id, thread_id: None, text: text.to_string() }).await
+            .expect("Failed to enqueue");
+    }
+
+    let mut group_ids = repository.get_queued_group_ids().await.expect("Failed to get groups");
+    group_ids.sort();
+    assert_eq!(group_ids, vec![quiet, other]);
+
+    let posts = repository.get_for_group(quiet).await.expect("Failed to get posts");
+    assert_eq!(posts.iter().map(|p| p.text.as_str()).collect::<Vec<_>>(), vec!["first", "second"]);
+    assert_eq!(repository.count_for_group(quiet).await.expect("Failed to count"), 2);
+
+    assert_eq!(repository.delete_for_group(quiet).await.expect("Failed to delete"), 2);
+    assert_eq!(repository.get_queued_group_ids().await.expect("Failed to get groups"), vec![other]);
+}
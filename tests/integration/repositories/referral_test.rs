@@ -0,0 +1,35 @@
+//! Integration tests for the referral repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::ReferralRepository;
+use SwingBuddy::models::CreateReferralRequest;
+
+use super::create_user;
+use crate::helpers::TestDatabase;
+
+/// Test that a user is only credited to the first referrer
+#[tokio::test]
+#[serial]
+async fn test_first_referrer_wins() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = ReferralRepository::new(db.pool.clone());
+    let first = create_user(&db.pool, 100001, None, "en").await;
+    let second = create_user(&db.pool, 100002, None, "en").await;
+    let referred = create_user(&db.pool, 100003, None, "en").await;
+
+    let referral = repository.create(CreateReferralRequest { referrer_id: first.id, referred_id: referred.id }).await
+        .expect("Failed to create referral");
+    assert!(referral.is_some());
+
+    let duplicate = repository.create(CreateReferralRequest { referrer_id: second.id, referred_id: referred.id }).await
+        .expect("A second referral should not fail");
+    assert!(duplicate.is_none());
+
+    let stored = repository.find_by_referred(referred.id).await.expect("Failed to find").expect("Referral should exist");
+    assert_eq!(stored.referrer_id, first.id);
+    assert_eq!(repository.count_by_referrer(second.id).await.expect("Failed to count"), 0);
+
+    let top = repository.get_top_referrers(10).await.expect("Failed to get top referrers");
+    assert_eq!(top.len(), 1);
+    assert_eq!((top[0].user_id, top[0].referral_count), (first.id, 1));
+}
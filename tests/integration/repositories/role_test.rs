@@ -0,0 +1,31 @@
+//! Integration tests for the role repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::RoleRepository;
+use SwingBuddy::models::{Role, SetGroupRoleRequest};
+
+use crate::helpers::TestDatabase;
+
+const CHAT_ID: i64 = -1001234567890;
+
+/// Test that granting a member a new role replaces the old one
+#[tokio::test]
+#[serial]
+async fn test_set_role_replaces() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = RoleRepository::new(db.pool.clone());
+
+    repository.set_role(SetGroupRoleRequest { chat_telegram_id: CHAT_ID, user_telegram_id: 100001, role: Role::Moderator, granted_by: 1 }).await
+        .expect("Failed to set role");
+    let role = repository.set_role(SetGroupRoleRequest { chat_telegram_id: CHAT_ID, user_telegram_id: 100001, role: Role::Admin, granted_by: 2 }).await
+        .expect("Failed to replace role");
+    assert_eq!(role.role_kind(), Some(Role::Admin));
+    assert_eq!(role.granted_by, 2);
+
+    let roles = repository.list_roles(CHAT_ID).await.expect("Failed to list roles");
+    assert_eq!(roles.len(), 1);
+
+    assert!(repository.remove_role(CHAT_ID, 100001).await.expect("Failed to remove"));
+    assert!(!repository.remove_role(CHAT_ID, 100001).await.expect("Failed to remove"));
+    assert!(repository.find_role(CHAT_ID, 100001).await.expect("Failed to find").is_none());
+}
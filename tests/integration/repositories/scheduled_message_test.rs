@@ -0,0 +1,43 @@
+//! Integration tests for the scheduled message repository
+
+use chrono::{Duration, Utc};
+use serial_test::serial;
+use SwingBuddy::database::repositories::ScheduledMessageRepository;
+use SwingBuddy::models::{CreateScheduledMessageRequest, ScheduledMessageStatus, ScheduledTarget};
+
+use crate::helpers::TestDatabase;
+
+fn request(send_at: chrono::DateTime<Utc>) -> CreateScheduledMessageRequest {
+    CreateScheduledMessageRequest {
+        created_by: 100001,
+        language_code: "en".to_string(),
+        target: ScheduledTarget::Group(-1001234567890),
+        source_chat_id: 100001,
+        source_message_id: 1,
+        text: None,
+        send_at,
+    }
+}
+
+/// Test that only pending messages are due, and only pending ones can be cancelled
+#[tokio::test]
+#[serial]
+async fn test_due_and_cancel() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = ScheduledMessageRepository::new(db.pool.clone());
+    let now = Utc::now();
+
+    let sent = repository.create(request(now - Duration::minutes(2))).await.expect("Failed to create");
+    let due = repository.create(request(now - Duration::minutes(1))).await.expect("Failed to create");
+    let later = repository.create(request(now + Duration::hours(1))).await.expect("Failed to create");
+    repository.set_status(sent.id, ScheduledMessageStatus::Sent, None).await.expect("Failed to set status");
+
+    let due_ids: Vec<i64> = repository.get_due(now).await.expect("Failed to get due").iter().map(|m| m.id).collect();
+    assert_eq!(due_ids, vec![due.id]);
+
+    assert!(repository.cancel(later.id).await.expect("Failed to cancel"));
+    assert!(!repository.cancel(sent.id).await.expect("Failed to cancel"), "Sent messages can't be cancelled");
+
+    let pending_ids: Vec<i64> = repository.get_pending().await.expect("Failed to get pending").iter().map(|m| m.id).collect();
+    assert_eq!(pending_ids, vec![due.id]);
+}
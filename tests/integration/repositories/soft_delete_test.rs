@@ -0,0 +1,150 @@
+//! Integration tests for soft-deleted users and events
+//!
+//! This module checks that a deleted row is left out of every lookup while
+//! it stays in the trash, and that restoring or re-creating it brings the
+//! same row back.
+
+use chrono::{Duration, Utc};
+use serial_test::serial;
+use SwingBuddy::database::repositories::{EventRepository, UserRepository};
+use SwingBuddy::models::{CreateUserRequest, Event, RegisterParticipantRequest, UserListSort};
+
+use super::{create_event, create_group, create_user};
+use crate::helpers::TestDatabase;
+
+/// Test that a deleted user is hidden from every user lookup but kept in the trash
+#[tokio::test]
+#[serial]
+async fn test_deleted_user_hidden_from_lookups() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let users = UserRepository::new(db.pool.clone());
+    let events = EventRepository::new(db.pool.clone());
+    let kept = create_user(&db.pool, 100001, Some("Moscow"), "en").await;
+    let deleted = create_user(&db.pool, 100002, Some("Moscow"), "en").await;
+    users.set_ban_status(deleted.id, true).await.expect("Failed to ban user");
+    let event = create_event(&db.pool, Some(kept.id), None).await;
+    for user in [&kept, &deleted] {
+        events.register_participant(RegisterParticipantRequest { event_id: event.id, user_id: user.id, status: None }).await
+            .expect("Failed to register participant");
+    }
+
+    assert!(users.delete(deleted.id).await.expect("Failed to delete user"));
+    assert!(!users.delete(deleted.id).await.expect("Failed to delete user"), "A user is only deleted once");
+
+    assert!(users.find_by_id(deleted.id).await.unwrap().is_none());
+    assert!(users.find_by_telegram_id(deleted.telegram_id).await.unwrap().is_none());
+    assert!(users.find_by_username("user100002").await.unwrap().is_none());
+    assert!(users.get_notification_preferences(deleted.telegram_id).await.unwrap().is_none());
+    assert!(users.get_banned_users().await.unwrap().is_empty());
+
+    let only_kept = vec![kept.id];
+    assert_eq!(users.list(10, 0).await.unwrap().iter().map(|u| u.id).collect::<Vec<_>>(), only_kept);
+    assert_eq!(users.list_all().await.unwrap().iter().map(|u| u.id).collect::<Vec<_>>(), only_kept);
+    assert_eq!(users.find_by_username_pattern("user1000").await.unwrap().iter().map(|u| u.id).collect::<Vec<_>>(), only_kept);
+    assert_eq!(users.get_event_participants(event.id).await.unwrap().iter().map(|u| u.id).collect::<Vec<_>>(), only_kept);
+    for sort in [UserListSort::Registered, UserListSort::LastActive] {
+        assert_eq!(users.list_entries(sort, 10, 0).await.unwrap().len(), 1);
+    }
+    assert_eq!(users.count().await.unwrap(), 1);
+
+    let trash = users.list_deleted(10, 0).await.unwrap();
+    assert_eq!(trash.iter().map(|entry| entry.id).collect::<Vec<_>>(), vec![deleted.id]);
+    assert_eq!(users.count_deleted().await.unwrap(), 1);
+}
+
+/// Test that restoring or re-creating a deleted user brings back the same row
+#[tokio::test]
+#[serial]
+async fn test_deleted_user_restored() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let users = UserRepository::new(db.pool.clone());
+    let user = create_user(&db.pool, 100001, None, "en").await;
+
+    users.delete(user.id).await.expect("Failed to delete user");
+    let restored = users.restore(user.id).await.expect("Failed to restore user").expect("User should be restored");
+    assert_eq!(restored.id, user.id);
+    assert!(users.restore(user.id).await.expect("Failed to restore user").is_none(), "Only deleted users are restored");
+    assert!(users.find_by_telegram_id(user.telegram_id).await.unwrap().is_some());
+
+    // Starting over with the bot re-creates the user in the same row, with the new details
+    users.delete(user.id).await.expect("Failed to delete user");
+    let recreated = users.create(CreateUserRequest {
+        telegram_id: user.telegram_id,
+        username: Some("renamed".to_string()),
+        first_name: Some("Renamed".to_string()),
+        last_name: None,
+        language_code: Some("en".to_string()),
+        location: None,
+    }).await.expect("Failed to re-create user");
+    assert_eq!(recreated.id, user.id);
+    assert_eq!(recreated.username.as_deref(), Some("renamed"));
+
+    let found = users.find_by_telegram_id(user.telegram_id).await.unwrap().expect("User should be found again");
+    assert_eq!(found.id, user.id);
+    assert_eq!(users.count_deleted().await.unwrap(), 0);
+
+    // Creating a user that is not deleted still fails
+    assert!(users.create(CreateUserRequest {
+        telegram_id: user.telegram_id,
+        username: None,
+        first_name: None,
+        last_name: None,
+        language_code: None,
+        location: None,
+    }).await.is_err());
+}
+
+/// Test that a deleted event is hidden from every event lookup, and back once restored
+#[tokio::test]
+#[serial]
+async fn test_deleted_event_hidden_until_restored() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let events = EventRepository::new(db.pool.clone());
+    let user = create_user(&db.pool, 100001, None, "en").await;
+    let group = create_group(&db.pool, -1001234567890).await;
+    let event = create_event(&db.pool, Some(user.id), Some(group.id)).await;
+    events.register_participant(RegisterParticipantRequest { event_id: event.id, user_id: user.id, status: None }).await
+        .expect("Failed to register participant");
+    sqlx::query("UPDATE events SET google_calendar_id = 'google-event' WHERE id = $1")
+        .bind(event.id)
+        .execute(&db.pool)
+        .await
+        .expect("Failed to link event");
+
+    let visible_ids = |list: Vec<Event>| list.iter().map(|e| e.id).collect::<Vec<_>>();
+    let now = Utc::now();
+    let lookups = || async {
+        vec![
+            events.find_by_id(event.id).await.unwrap().into_iter().collect::<Vec<_>>(),
+            events.find_by_google_calendar_id("google-event").await.unwrap().into_iter().collect(),
+            events.list(10, 0).await.unwrap(),
+            events.get_upcoming_events(None).await.unwrap(),
+            events.get_upcoming_events_at("test venue", 10).await.unwrap(),
+            events.get_next_event(None, Some(group.id)).await.unwrap().into_iter().collect(),
+            events.get_next_events(Some("Test"), None, 10).await.unwrap(),
+            events.get_group_events(group.id).await.unwrap(),
+            events.get_group_events_between(group.id, now, now + Duration::days(30)).await.unwrap(),
+            events.get_user_events(user.id).await.unwrap(),
+            events.get_user_registered_events(user.id).await.unwrap(),
+            events.get_pending_group_reminders(now + Duration::days(30), 3).await.unwrap(),
+        ]
+    };
+
+    for (index, found) in lookups().await.into_iter().enumerate() {
+        assert_eq!(visible_ids(found), vec![event.id], "Lookup {} should find the event", index);
+    }
+
+    assert!(events.delete(event.id).await.expect("Failed to delete event"));
+    for (index, found) in lookups().await.into_iter().enumerate() {
+        assert!(found.is_empty(), "Lookup {} should hide the deleted event", index);
+    }
+    assert_eq!((events.count().await.unwrap(), events.count_upcoming().await.unwrap()), (0, 0));
+    assert_eq!(events.list_deleted(10, 0).await.unwrap().iter().map(|entry| entry.id).collect::<Vec<_>>(), vec![event.id]);
+
+    let restored = events.restore(event.id).await.expect("Failed to restore event").expect("Event should be restored");
+    assert_eq!(restored.id, event.id);
+    for (index, found) in lookups().await.into_iter().enumerate() {
+        assert_eq!(visible_ids(found), vec![event.id], "Lookup {} should find the restored event", index);
+    }
+    assert_eq!(events.count_deleted().await.unwrap(), 0);
+}
@@ -0,0 +1,34 @@
+//! Integration tests for the song request repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::SongRequestRepository;
+use SwingBuddy::models::{CreateSongRequestRequest, SongRequestStatus};
+
+use super::create_event;
+use crate::helpers::TestDatabase;
+
+/// Test the queue of an event: duplicates are found ignoring case, and a request is handled once
+#[tokio::test]
+#[serial]
+async fn test_song_queue() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = SongRequestRepository::new(db.pool.clone());
+    let event = create_event(&db.pool, None, None).await;
+
+    let request = repository.create(CreateSongRequestRequest {
+        event_id: event.id,
+        requested_by: 100001,
+        track: "Shiny Stockings".to_string(),
+    }).await.expect("Failed to create request");
+
+    let duplicate = repository.find_pending_track(event.id, "shiny stockings").await.expect("Failed to find");
+    assert_eq!(duplicate.map(|r| r.id), Some(request.id));
+    assert_eq!(repository.count_pending_by_user(event.id, 100001).await.expect("Failed to count"), 1);
+
+    let played = repository.handle(request.id, SongRequestStatus::Played, 100002).await.expect("Failed to handle");
+    assert_eq!(played.and_then(|r| r.handled_by), Some(100002));
+    assert!(repository.handle(request.id, SongRequestStatus::Skipped, 100002).await.expect("Failed to handle").is_none());
+
+    assert!(repository.find_pending_track(event.id, "Shiny Stockings").await.expect("Failed to find").is_none());
+    assert_eq!(repository.get_by_status(event.id, SongRequestStatus::Played).await.expect("Failed to list").len(), 1);
+}
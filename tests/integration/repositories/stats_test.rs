@@ -0,0 +1,36 @@
+//! Integration tests for the stats repository
+
+use chrono::{Duration, Utc};
+use serial_test::serial;
+use SwingBuddy::database::repositories::StatsRepository;
+use SwingBuddy::models::GroupCounter;
+
+use super::create_group;
+use crate::helpers::TestDatabase;
+
+const CHAT_ID: i64 = -1001234567890;
+
+/// Test that increments of the same counter and day add up in one row
+#[tokio::test]
+#[serial]
+async fn test_increment_adds_up() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = StatsRepository::new(db.pool.clone());
+    let group = create_group(&db.pool, CHAT_ID).await;
+    let today = Utc::now().date_naive();
+
+    repository.increment(CHAT_ID, GroupCounter::Messages, today, 3).await.expect("Failed to increment");
+    repository.increment(CHAT_ID, GroupCounter::Messages, today, 4).await.expect("Failed to increment");
+    repository.increment(CHAT_ID, GroupCounter::NewMembers, today, 1).await.expect("Failed to increment");
+    // Unknown groups are skipped instead of failing
+    repository.increment(-1009999999999, GroupCounter::Messages, today, 1).await.expect("Failed to increment");
+
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM group_activity_counters")
+        .fetch_one(&db.pool)
+        .await
+        .expect("Failed to count counters");
+    assert_eq!(rows, 2);
+
+    let stats = repository.get_group_stats(group.id, Utc::now() - Duration::days(7)).await.expect("Failed to get stats");
+    assert_eq!((stats.messages, stats.new_members, stats.cas_bans), (7, 1, 0));
+}
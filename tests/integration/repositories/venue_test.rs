@@ -0,0 +1,38 @@
+//! Integration tests for the venue repository
+
+use serial_test::serial;
+use SwingBuddy::database::repositories::VenueRepository;
+use SwingBuddy::models::CreateVenueRequest;
+
+use crate::helpers::TestDatabase;
+
+fn request(city: &str, name: &str, address: &str) -> CreateVenueRequest {
+    CreateVenueRequest {
+        city: city.to_string(),
+        name: name.to_string(),
+        address: address.to_string(),
+        floor_type: None,
+        created_by: None,
+    }
+}
+
+/// Test that adding a venue again updates its address, and listing filters by city ignoring case
+#[tokio::test]
+#[serial]
+async fn test_upsert_and_list() {
+    let db = TestDatabase::new().await.expect("Failed to create test database");
+    let repository = VenueRepository::new(db.pool.clone());
+
+    let first = repository.upsert(request("Moscow", "Studio", "Old street 1")).await.expect("Failed to add venue");
+    let updated = repository.upsert(request("Moscow", "Studio", "New street 2")).await.expect("Failed to update venue");
+    assert_eq!(updated.id, first.id);
+    assert_eq!(updated.address, "New street 2");
+    repository.upsert(request("Berlin", "Studio", "Hauptstraße 3")).await.expect("Failed to add venue");
+
+    assert_eq!(repository.list(None).await.expect("Failed to list").len(), 2);
+    let moscow = repository.list(Some("moscow")).await.expect("Failed to list");
+    assert_eq!(moscow.iter().map(|v| v.id).collect::<Vec<_>>(), vec![first.id]);
+
+    assert_eq!(repository.delete(first.id).await.expect("Failed to delete").map(|v| v.id), Some(first.id));
+    assert!(repository.delete(first.id).await.expect("Failed to delete").is_none());
+}
@@ -49,7 +49,7 @@ async fn run_complete_user_journey(
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     use SwingBuddy::handlers::commands::start;
     use SwingBuddy::handlers::callbacks::handle_callback_query;
-    use crate::helpers::{create_simple_test_message, create_simple_test_callback_query};
+    use crate::helpers::{create_simple_test_message, create_simple_test_callback_query, test_bot_me};
     
     let bot = ctx.create_bot().await?;
     let app_state = ctx.create_app_state().await?;
@@ -70,6 +70,7 @@ async fn run_complete_user_journey(
     handle_callback_query(
         bot.clone(),
         lang_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
@@ -98,6 +99,7 @@ async fn run_complete_user_journey(
     handle_callback_query(
         bot.clone(),
         location_callback,
+        test_bot_me(),
         (*app_state.services).clone(),
         (*app_state.scenario_manager).clone(),
         (*app_state.state_storage).clone(),
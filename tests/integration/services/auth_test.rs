@@ -0,0 +1,71 @@
+//! Integration tests for the authentication service
+//!
+//! This module checks how roles delegated with /role combine with the
+//! member's status in Telegram.
+
+use serial_test::serial;
+use teloxide::types::ChatId;
+use SwingBuddy::models::role::{Role, SetGroupRoleRequest};
+use SwingBuddy::services::auth::Permission;
+
+use crate::helpers::{TestContext, TestConfig};
+
+const GROUP_ID: i64 = -1001234567890;
+const USER_ID: i64 = 123456789;
+
+async fn setup_moderator(ctx: &TestContext) -> std::sync::Arc<SwingBuddy::state::context::AppContext> {
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    app_state.database.roles.set_role(SetGroupRoleRequest {
+        chat_telegram_id: GROUP_ID,
+        user_telegram_id: USER_ID,
+        role: Role::Moderator,
+        granted_by: 555666777,
+    }).await.expect("Failed to set role");
+    app_state
+}
+
+/// Test that a delegated role counts while the user is in the group
+#[tokio::test]
+#[serial]
+async fn test_delegated_role_applies_to_member() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    ctx.telegram_mock.mock_get_chat_member(USER_ID, "member").await;
+    let app_state = setup_moderator(&ctx).await;
+
+    let context = app_state.services.auth_service.get_auth_context(USER_ID, Some(ChatId(GROUP_ID))).await
+        .expect("Failed to get auth context");
+    assert!(context.is_group_member);
+    assert_eq!(context.group_role, Some(Role::Moderator));
+    assert!(context.permissions.contains(&Permission::GroupModerator));
+}
+
+/// Test that a delegated role is ignored once the user left the group
+#[tokio::test]
+#[serial]
+async fn test_delegated_role_ignored_for_former_member() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    ctx.telegram_mock.mock_get_chat_member(USER_ID, "left").await;
+    let app_state = setup_moderator(&ctx).await;
+
+    let context = app_state.services.auth_service.get_auth_context(USER_ID, Some(ChatId(GROUP_ID))).await
+        .expect("Failed to get auth context");
+    assert!(!context.is_group_member);
+    assert_eq!(context.group_role, None);
+    assert!(!context.permissions.contains(&Permission::GroupModerator));
+}
@@ -0,0 +1,66 @@
+//! Integration tests for the CAS re-check service
+//!
+//! This module checks that member activity is written at most once per
+//! member and group within the write interval.
+
+use serial_test::serial;
+use teloxide::types::{ChatId, UserId};
+use SwingBuddy::config::settings::CasRecheckConfig;
+
+use crate::helpers::{TestContext, TestConfig};
+
+const GROUP_ID: i64 = -1001234567890;
+const USER_ID: u64 = 123456789;
+
+async fn activity_rows(ctx: &TestContext) -> i64 {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM member_activity WHERE chat_telegram_id = $1 AND user_telegram_id = $2")
+        .bind(GROUP_ID)
+        .bind(USER_ID as i64)
+        .fetch_one(ctx.db_pool())
+        .await
+        .expect("Failed to count member activity")
+}
+
+/// Test that a second message within the interval does not reach the database
+#[tokio::test]
+#[serial]
+async fn test_record_activity_debounced_in_redis() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: true,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let mut ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    ctx.settings.features.cas_protection = true;
+    ctx.settings.cas_recheck = Some(CasRecheckConfig {
+        interval_hours: 24,
+        active_within_days: 30,
+        batch_size: 10,
+        request_delay_ms: 0,
+        check_interval_seconds: 60,
+    });
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    let cas_recheck_service = &app_state.services.cas_recheck_service;
+    assert!(cas_recheck_service.is_enabled());
+
+    cas_recheck_service.record_activity(ChatId(GROUP_ID), UserId(USER_ID)).await;
+    assert_eq!(activity_rows(&ctx).await, 1);
+
+    // Without the guard the next message would write the row again
+    sqlx::query("DELETE FROM member_activity").execute(ctx.db_pool()).await
+        .expect("Failed to clear member activity");
+    cas_recheck_service.record_activity(ChatId(GROUP_ID), UserId(USER_ID)).await;
+    assert_eq!(activity_rows(&ctx).await, 0, "Second message within the interval should be skipped");
+
+    // Other members and groups have their own guard
+    cas_recheck_service.record_activity(ChatId(GROUP_ID - 1), UserId(USER_ID)).await;
+    cas_recheck_service.record_activity(ChatId(GROUP_ID), UserId(USER_ID + 1)).await;
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM member_activity")
+        .fetch_one(ctx.db_pool())
+        .await
+        .expect("Failed to count member activity");
+    assert_eq!(total, 2);
+}
@@ -0,0 +1,95 @@
+//! Integration tests for event lifecycle webhooks
+//!
+//! This module checks that changes made to events inside the bot are sent
+//! to the outgoing webhook endpoints, not only those synced from Google Calendar.
+
+use std::time::Duration;
+use chrono::Utc;
+use serial_test::serial;
+use wiremock::{matchers::{method, path}, Mock, MockServer, ResponseTemplate};
+use SwingBuddy::config::settings::{OutgoingWebhooksConfig, WebhookEndpointConfig};
+use SwingBuddy::models::event::{CreateEventRequest, UpdateEventRequest};
+
+use crate::helpers::{TestContext, TestConfig};
+
+const ADMIN: i64 = 555666777;
+
+/// Event types delivered to `receiver`, in order, once `expected` have arrived
+async fn delivered_events(receiver: &MockServer, expected: usize) -> Vec<String> {
+    for _ in 0..50 {
+        let requests = receiver.received_requests().await.unwrap();
+        if requests.len() >= expected {
+            return requests.iter()
+                .filter_map(|request| serde_json::from_slice::<serde_json::Value>(&request.body).ok())
+                .filter_map(|body| body["type"].as_str().map(str::to_string))
+                .collect();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Expected {} webhook deliveries", expected);
+}
+
+/// Test that updating, cancelling and deleting an event notify webhook subscribers
+#[tokio::test]
+#[serial]
+async fn test_event_changes_are_sent_to_webhooks() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+    let mut ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+
+    let receiver = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hooks"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&receiver)
+        .await;
+    ctx.settings.outgoing_webhooks = Some(OutgoingWebhooksConfig {
+        endpoints: vec![WebhookEndpointConfig {
+            name: "website".to_string(),
+            url: format!("{}/hooks", receiver.uri()),
+            secret: "secret".to_string(),
+            events: vec!["event.updated".to_string(), "event.cancelled".to_string()],
+        }],
+        timeout_seconds: 5,
+        max_attempts: 1,
+    });
+
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    let event_service = &app_state.services.event_service;
+
+    let request = CreateEventRequest {
+        title: "Friday Social".to_string(),
+        description: None,
+        event_date: Utc::now() + chrono::Duration::days(3),
+        location: Some("Studio".to_string()),
+        max_participants: None,
+        created_by: None,
+        group_id: None,
+    };
+    let cancelled = event_service.create_event(request.clone()).await.expect("Failed to create event");
+    let deleted = event_service.create_event(request).await.expect("Failed to create event");
+
+    let updated = event_service.update_event(cancelled.id, UpdateEventRequest {
+        title: Some("Friday Social Party".to_string()),
+        ..Default::default()
+    }).await.expect("Failed to update event");
+    assert_eq!(updated.title, "Friday Social Party");
+    assert_eq!(delivered_events(&receiver, 1).await, vec!["event.updated"]);
+
+    let cancelled = event_service.cancel_event(cancelled.id).await.expect("Failed to cancel event");
+    assert!(!cancelled.is_active);
+    assert_eq!(delivered_events(&receiver, 2).await, vec!["event.updated", "event.cancelled"]);
+
+    assert!(event_service.delete_event(deleted.id, ADMIN).await.expect("Failed to delete event"));
+    assert_eq!(delivered_events(&receiver, 3).await, vec!["event.updated", "event.cancelled", "event.cancelled"]);
+
+    // Deleting it again changes nothing, so nothing is sent
+    assert!(!event_service.delete_event(deleted.id, ADMIN).await.expect("Failed to delete event again"));
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(receiver.received_requests().await.unwrap().len(), 3);
+}
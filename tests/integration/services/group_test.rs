@@ -0,0 +1,75 @@
+//! Integration tests for the group service
+//!
+//! This module checks that group member records follow joins and leaves.
+
+use serial_test::serial;
+use SwingBuddy::models::role::{Role, SetGroupRoleRequest};
+
+use crate::helpers::{TestContext, TestConfig};
+
+const GROUP_ID: i64 = -1001234567890;
+const USER_ID: i64 = 123456789;
+
+/// Test that a member leaving the group loses the role delegated to them
+#[tokio::test]
+#[serial]
+async fn test_leaving_member_loses_delegated_role() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    ctx.load_fixtures().await.expect("Failed to load fixtures");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    let services = &app_state.services;
+
+    services.group_service.sync_member(GROUP_ID, USER_ID, Some("member")).await
+        .expect("Failed to record member");
+    app_state.database.roles.set_role(SetGroupRoleRequest {
+        chat_telegram_id: GROUP_ID,
+        user_telegram_id: USER_ID,
+        role: Role::Moderator,
+        granted_by: 555666777,
+    }).await.expect("Failed to set role");
+
+    services.group_service.sync_member(GROUP_ID, USER_ID, None).await
+        .expect("Failed to remove member");
+
+    let role = app_state.database.roles.find_role(GROUP_ID, USER_ID).await
+        .expect("Failed to look up role");
+    assert!(role.is_none(), "Role should be removed with the member");
+}
+
+/// Test that the role goes even for a member who never started the bot
+#[tokio::test]
+#[serial]
+async fn test_leaving_unregistered_member_loses_delegated_role() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+
+    app_state.database.roles.set_role(SetGroupRoleRequest {
+        chat_telegram_id: GROUP_ID,
+        user_telegram_id: USER_ID,
+        role: Role::Organizer,
+        granted_by: 555666777,
+    }).await.expect("Failed to set role");
+
+    app_state.services.group_service.sync_member(GROUP_ID, USER_ID, None).await
+        .expect("Failed to remove member");
+
+    let role = app_state.database.roles.find_role(GROUP_ID, USER_ID).await
+        .expect("Failed to look up role");
+    assert!(role.is_none(), "Role should be removed with the member");
+}
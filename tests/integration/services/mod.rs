@@ -0,0 +1,11 @@
+//! Integration tests for services
+//!
+//! This module contains tests of the services against a real database,
+//! below the handlers and their Telegram replies.
+
+pub mod auth_test;
+pub mod cas_recheck_test;
+pub mod event_webhook_test;
+pub mod group_test;
+pub mod stats_test;
+pub mod subscription_test;
@@ -0,0 +1,83 @@
+//! Integration tests for the stats service
+//!
+//! This module checks that group activity counted in memory reaches the
+//! database when flushed.
+
+use serial_test::serial;
+use teloxide::types::ChatId;
+use SwingBuddy::models::stats::GroupCounter;
+
+use crate::helpers::{TestContext, TestConfig};
+
+const GROUP_ID: i64 = -1001234567890;
+
+async fn stored_messages(ctx: &TestContext) -> i64 {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COALESCE(SUM(value), 0)::BIGINT FROM group_activity_counters WHERE counter = 'messages'"
+    )
+    .fetch_one(ctx.db_pool())
+    .await
+    .expect("Failed to read counters")
+}
+
+/// Test that counts are held in memory until flushed, then written in one go
+#[tokio::test]
+#[serial]
+async fn test_counts_written_on_flush() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    ctx.load_fixtures().await.expect("Failed to load fixtures");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    let stats_service = &app_state.services.stats_service;
+
+    for _ in 0..3 {
+        stats_service.record(ChatId(GROUP_ID), GroupCounter::Messages);
+    }
+    stats_service.record(ChatId(GROUP_ID), GroupCounter::NewMembers);
+    assert_eq!(stored_messages(&ctx).await, 0, "Counts should wait for the flush");
+
+    let written = stats_service.flush().await.expect("Failed to flush");
+    assert_eq!(written, 2, "One upsert per counter");
+    assert_eq!(stored_messages(&ctx).await, 3);
+
+    // Nothing left to write, and later counts add to the stored ones
+    assert_eq!(stats_service.flush().await.expect("Failed to flush"), 0);
+    stats_service.record(ChatId(GROUP_ID), GroupCounter::Messages);
+    stats_service.flush().await.expect("Failed to flush");
+    assert_eq!(stored_messages(&ctx).await, 4);
+}
+
+/// Test that /groupstats includes counts not flushed yet
+#[tokio::test]
+#[serial]
+async fn test_weekly_stats_include_pending_counts() {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    ctx.load_fixtures().await.expect("Failed to load fixtures");
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    let services = &app_state.services;
+
+    let group = services.group_service.get_group(GROUP_ID).await
+        .expect("Failed to get group")
+        .expect("Group fixture missing");
+    services.stats_service.record(ChatId(GROUP_ID), GroupCounter::Messages);
+    services.stats_service.record(ChatId(GROUP_ID), GroupCounter::Messages);
+
+    let stats = services.stats_service.weekly_group_stats(group.id).await
+        .expect("Failed to get stats");
+    assert_eq!(stats.messages, 2);
+}
@@ -0,0 +1,121 @@
+//! Integration tests for notification preferences
+//!
+//! This module checks that users who turned broadcasts or reminders off with
+//! /unsubscribe are left out by the services sending them.
+
+use chrono::Utc;
+use serial_test::serial;
+use SwingBuddy::config::settings::{SharedSettings, StateBackendKind, StateConfig, StepRemindersConfig};
+use SwingBuddy::models::broadcast::{BroadcastSegment, CreateBroadcastRequest};
+use SwingBuddy::models::user::NotificationPreferences;
+use SwingBuddy::services::StepReminderService;
+use SwingBuddy::state::ConversationContext;
+
+use crate::helpers::{TestContext, TestConfig};
+
+const SUBSCRIBED: i64 = 123456789;
+const UNSUBSCRIBED: i64 = 987654321;
+const ADMIN: i64 = 555666777;
+
+async fn test_context() -> TestContext {
+    let config = TestConfig {
+        use_database: true,
+        use_redis: false,
+        setup_default_mocks: true,
+        bot_token: None,
+    };
+
+    let mut ctx = TestContext::new_with_config(config).await
+        .expect("Failed to create test context");
+    ctx.load_fixtures().await.expect("Failed to load fixtures");
+    // Keep conversation state in the database, as there is no Redis
+    ctx.settings.state = Some(StateConfig {
+        backend: StateBackendKind::Postgres,
+        cleanup_interval_seconds: 300,
+    });
+    ctx
+}
+
+/// Chats that were sent a message, in order
+async fn message_recipients(ctx: &TestContext) -> Vec<i64> {
+    ctx.telegram_mock.server.received_requests().await.unwrap()
+        .iter()
+        .filter(|request| request.url.path().ends_with("/SendMessage"))
+        .filter_map(|request| serde_json::from_slice::<serde_json::Value>(&request.body).ok())
+        .filter_map(|body| body["chat_id"].as_i64())
+        .collect()
+}
+
+/// Test that broadcasts skip users who turned them off
+#[tokio::test]
+#[serial]
+async fn test_broadcast_skips_unsubscribed_users() {
+    let ctx = test_context().await;
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    let services = &app_state.services;
+
+    let everyone = services.broadcast_service.count_recipients(&BroadcastSegment::All).await.unwrap();
+    services.user_service.set_notification_preferences(UNSUBSCRIBED, NotificationPreferences {
+        broadcasts_enabled: false,
+        reminders_enabled: true,
+    }).await.expect("Failed to unsubscribe");
+    assert_eq!(services.broadcast_service.count_recipients(&BroadcastSegment::All).await.unwrap(), everyone - 1);
+
+    let broadcast = services.broadcast_service.create_broadcast(CreateBroadcastRequest {
+        created_by: ADMIN,
+        language_code: "en".to_string(),
+        segment: BroadcastSegment::All,
+        source_chat_id: ADMIN,
+        source_message_id: 1,
+        text: Some("Social dance on Friday!".to_string()),
+    }).await.expect("Failed to create broadcast");
+    assert_eq!(broadcast.total as i64, everyone - 1);
+
+    let delivered = services.broadcast_service.process_queue().await.expect("Failed to deliver broadcast");
+    assert_eq!(delivered as i64, everyone - 1);
+
+    let recipients = message_recipients(&ctx).await;
+    assert!(recipients.contains(&SUBSCRIBED));
+    assert!(!recipients.contains(&UNSUBSCRIBED), "Unsubscribed user got a broadcast: {:?}", recipients);
+}
+
+/// Test that step reminders skip users who turned reminders off
+#[tokio::test]
+#[serial]
+async fn test_step_reminders_honour_preferences() {
+    let mut ctx = test_context().await;
+    ctx.settings.step_reminders = Some(StepRemindersConfig {
+        remind_before_seconds: 600,
+        check_interval_seconds: 60,
+    });
+    let app_state = ctx.create_app_state().await.expect("Failed to create app state");
+    let services = &app_state.services;
+
+    // Both users stopped at the name step, a few minutes before their context expires
+    for user_id in [SUBSCRIBED, UNSUBSCRIBED] {
+        let mut context = ConversationContext::new(user_id);
+        context.scenario = Some("onboarding".to_string());
+        context.step = Some("name_input".to_string());
+        context.expires_at = Some(Utc::now() + chrono::Duration::minutes(5));
+        app_state.state_storage.save_context(&mut context).await.expect("Failed to save context");
+    }
+    services.user_service.set_notification_preferences(UNSUBSCRIBED, NotificationPreferences {
+        broadcasts_enabled: true,
+        reminders_enabled: false,
+    }).await.expect("Failed to unsubscribe");
+
+    let step_reminder_service = StepReminderService::new(
+        ctx.create_bot().await.expect("Failed to create bot"),
+        (*app_state.state_storage).clone(),
+        (*app_state.scenario_manager).clone(),
+        services.user_service.clone(),
+        services.notification_service.clone(),
+        SharedSettings::new(ctx.settings.clone()),
+    );
+
+    assert_eq!(step_reminder_service.send_due().await.expect("Failed to send reminders"), 1);
+    assert_eq!(message_recipients(&ctx).await, vec![SUBSCRIBED]);
+
+    // Both contexts are marked, so neither user is looked at again
+    assert_eq!(step_reminder_service.send_due().await.expect("Failed to send reminders"), 0);
+}
@@ -0,0 +1,167 @@
+//! Integration tests running the same scenarios against every state backend
+//!
+//! This module checks that Redis and PostgreSQL behave alike for saving,
+//! loading and deleting contexts, letting them expire and rejecting stale
+//! saves, so switching `[state] backend` changes nothing for the handlers.
+
+use std::sync::Arc;
+use std::time::Duration;
+use serial_test::serial;
+use SwingBuddy::config::settings::RedisConfig;
+use SwingBuddy::state::{ConversationContext, StateStorage};
+use SwingBuddy::utils::errors::SwingBuddyError;
+
+use crate::helpers::containers::{redis_server, unique_name, RedisServer};
+use crate::helpers::TestDatabase;
+
+const USER_ID: i64 = 123456789;
+const CHAT_ID: i64 = -1001234567890;
+const TTL: Duration = Duration::from_secs(3600);
+const SHORT_TTL: Duration = Duration::from_secs(1);
+
+/// Storage on the shared Redis server, under a prefix of its own
+async fn redis_storage(default_ttl: Duration) -> (StateStorage, Arc<RedisServer>) {
+    let server = redis_server().await.expect("Failed to start Redis");
+    let storage = StateStorage::new(RedisConfig {
+        url: server.url.clone(),
+        prefix: format!("{}:", unique_name("test_state")),
+        ttl_seconds: default_ttl.as_secs(),
+        sentinel: None,
+    }).await.expect("Failed to create Redis storage");
+    assert!(!storage.is_degraded(), "Redis should be reachable, not replaced by memory");
+    (storage, server)
+}
+
+/// Storage in the `conversation_contexts` table of a fresh database
+async fn postgres_storage(default_ttl: Duration) -> (StateStorage, TestDatabase) {
+    let database = TestDatabase::new().await.expect("Failed to create test database");
+    let storage = StateStorage::postgres(database.pool.clone(), default_ttl, Duration::from_secs(300));
+    (storage, database)
+}
+
+fn onboarding_context() -> ConversationContext {
+    let mut context = ConversationContext::new(USER_ID);
+    context.scenario = Some("onboarding".to_string());
+    context.step = Some("name_input".to_string());
+    context.data.insert("language".to_string(), serde_json::json!("en"));
+    context
+}
+
+async fn check_save_load_delete(storage: &StateStorage) {
+    assert!(storage.load_context(USER_ID).await.unwrap().is_none());
+
+    let mut private = onboarding_context();
+    storage.save_context(&mut private).await.unwrap();
+    let mut group = ConversationContext::new(USER_ID);
+    group.chat_id = Some(CHAT_ID);
+    group.scenario = Some("intro".to_string());
+    storage.save_context(&mut group).await.unwrap();
+
+    let loaded = storage.load_context(USER_ID).await.unwrap().expect("Private context should be stored");
+    assert_eq!(loaded.scenario.as_deref(), Some("onboarding"));
+    assert_eq!(loaded.step.as_deref(), Some("name_input"));
+    assert_eq!(loaded.data.get("language"), Some(&serde_json::json!("en")));
+    assert!(storage.context_exists(USER_ID).await.unwrap());
+
+    let loaded = storage.load_chat_context(USER_ID, CHAT_ID).await.unwrap().expect("Group context should be stored");
+    assert_eq!(loaded.scenario.as_deref(), Some("intro"));
+
+    // Deleting one scope leaves the other alone
+    storage.delete_context(USER_ID).await.unwrap();
+    assert!(storage.load_context(USER_ID).await.unwrap().is_none());
+    assert!(!storage.context_exists(USER_ID).await.unwrap());
+    assert!(storage.load_chat_context(USER_ID, CHAT_ID).await.unwrap().is_some());
+
+    storage.delete_chat_context(USER_ID, CHAT_ID).await.unwrap();
+    assert!(storage.load_chat_context(USER_ID, CHAT_ID).await.unwrap().is_none());
+}
+
+async fn check_ttl_expiry(storage: &StateStorage) {
+    let mut context = onboarding_context();
+    storage.save_context(&mut context).await.unwrap();
+    assert!(storage.load_context(USER_ID).await.unwrap().is_some());
+
+    tokio::time::sleep(SHORT_TTL + Duration::from_millis(1500)).await;
+    assert!(storage.load_context(USER_ID).await.unwrap().is_none(), "Context should expire after its TTL");
+    assert!(!storage.context_exists(USER_ID).await.unwrap());
+}
+
+async fn check_versioning(storage: &StateStorage) {
+    let mut context = onboarding_context();
+    storage.save_context(&mut context).await.unwrap();
+    assert_eq!(context.version, 1);
+
+    // Two handlers load the same context; the second save loses
+    let mut first = storage.load_context(USER_ID).await.unwrap().unwrap();
+    let mut second = storage.load_context(USER_ID).await.unwrap().unwrap();
+    first.step = Some("location_input".to_string());
+    storage.save_context(&mut first).await.unwrap();
+    assert_eq!(first.version, 2);
+
+    second.step = Some("name_input".to_string());
+    let result = storage.save_context(&mut second).await;
+    assert!(matches!(result, Err(SwingBuddyError::StaleContext { user_id: USER_ID })), "Stale save should fail: {:?}", result);
+    assert_eq!(second.version, 1, "A rejected save keeps the loaded version");
+
+    let stored = storage.load_context(USER_ID).await.unwrap().unwrap();
+    assert_eq!(stored.version, 2);
+    assert_eq!(stored.step.as_deref(), Some("location_input"));
+
+    // A context deleted since it was loaded is not brought back
+    storage.delete_context(USER_ID).await.unwrap();
+    assert!(storage.save_context(&mut first).await.is_err());
+    assert!(storage.load_context(USER_ID).await.unwrap().is_none());
+
+    // A new context replaces whatever is stored
+    let mut fresh = onboarding_context();
+    storage.save_context(&mut fresh).await.unwrap();
+    assert_eq!(storage.load_context(USER_ID).await.unwrap().unwrap().version, 1);
+}
+
+/// Test saving, loading and deleting contexts in Redis
+#[tokio::test]
+#[serial]
+async fn test_redis_save_load_delete() {
+    let (storage, _server) = redis_storage(TTL).await;
+    check_save_load_delete(&storage).await;
+}
+
+/// Test saving, loading and deleting contexts in PostgreSQL
+#[tokio::test]
+#[serial]
+async fn test_postgres_save_load_delete() {
+    let (storage, _database) = postgres_storage(TTL).await;
+    check_save_load_delete(&storage).await;
+}
+
+/// Test that contexts in Redis expire after their TTL
+#[tokio::test]
+#[serial]
+async fn test_redis_ttl_expiry() {
+    let (storage, _server) = redis_storage(SHORT_TTL).await;
+    check_ttl_expiry(&storage).await;
+}
+
+/// Test that contexts in PostgreSQL expire after their TTL
+#[tokio::test]
+#[serial]
+async fn test_postgres_ttl_expiry() {
+    let (storage, _database) = postgres_storage(SHORT_TTL).await;
+    check_ttl_expiry(&storage).await;
+}
+
+/// Test that Redis rejects saves of a context changed since it was loaded
+#[tokio::test]
+#[serial]
+async fn test_redis_versioning() {
+    let (storage, _server) = redis_storage(TTL).await;
+    check_versioning(&storage).await;
+}
+
+/// Test that PostgreSQL rejects saves of a context changed since it was loaded
+#[tokio::test]
+#[serial]
+async fn test_postgres_versioning() {
+    let (storage, _database) = postgres_storage(TTL).await;
+    check_versioning(&storage).await;
+}
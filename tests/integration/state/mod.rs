@@ -0,0 +1,6 @@
+//! Integration tests for conversation state storage
+//!
+//! This module contains tests of the state backends against real servers.
+
+pub mod backends_test;
+pub mod scopes_test;
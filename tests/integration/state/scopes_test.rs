@@ -0,0 +1,95 @@
+//! Integration tests for walking the stored scopes
+//!
+//! This module checks that the PostgreSQL backend pages through scopes by
+//! key, so rows deleted during a walk don't make it skip others.
+
+use std::sync::Arc;
+use std::time::Duration;
+use serial_test::serial;
+use SwingBuddy::state::{ConversationContext, PostgresStateBackend, ScopeCursor, StateBackend, StateStorage};
+
+use crate::helpers::TestDatabase;
+
+const TTL: Duration = Duration::from_secs(3600);
+
+async fn backend_with_contexts(database: &TestDatabase, user_ids: &[i64]) -> PostgresStateBackend {
+    let backend = PostgresStateBackend::new(database.pool.clone());
+    for &user_id in user_ids {
+        assert!(backend.put(&ConversationContext::new(user_id), 0, TTL).await.unwrap());
+    }
+    backend
+}
+
+/// Test that pages follow each other in key order until the walk is over
+#[tokio::test]
+#[serial]
+async fn test_postgres_scopes_page_walks_in_key_order() {
+    let database = TestDatabase::new().await.expect("Failed to create test database");
+    let backend = backend_with_contexts(&database, &[5, 1, 4, 2, 3]).await;
+    let mut group_context = ConversationContext::new(2);
+    group_context.chat_id = Some(-100);
+    backend.put(&group_context, 0, TTL).await.unwrap();
+
+    let (first, cursor) = backend.scopes_page(ScopeCursor::Start, 2).await.unwrap();
+    assert_eq!(first, vec![(1, None), (2, Some(-100))]);
+    assert_eq!(cursor, ScopeCursor::After((2, Some(-100))));
+
+    let (second, cursor) = backend.scopes_page(cursor, 2).await.unwrap();
+    assert_eq!(second, vec![(2, None), (3, None)]);
+
+    let (third, cursor) = backend.scopes_page(cursor, 2).await.unwrap();
+    assert_eq!(third, vec![(4, None), (5, None)]);
+
+    let (last, cursor) = backend.scopes_page(cursor, 2).await.unwrap();
+    assert!(last.is_empty());
+    assert_eq!(cursor, ScopeCursor::Start);
+}
+
+/// Test that deleting scopes already walked doesn't skip the next ones,
+/// as an offset would
+#[tokio::test]
+#[serial]
+async fn test_postgres_scopes_page_survives_deletes() {
+    let database = TestDatabase::new().await.expect("Failed to create test database");
+    let backend = backend_with_contexts(&database, &[1, 2, 3, 4, 5]).await;
+
+    let (first, cursor) = backend.scopes_page(ScopeCursor::Start, 2).await.unwrap();
+    assert_eq!(first, vec![(1, None), (2, None)]);
+    for scope in first {
+        backend.delete(scope).await.unwrap();
+    }
+
+    let (second, _) = backend.scopes_page(cursor, 2).await.unwrap();
+    assert_eq!(second, vec![(3, None), (4, None)]);
+}
+
+/// Test that expired contexts are left out of the walk
+#[tokio::test]
+#[serial]
+async fn test_postgres_scopes_page_skips_expired() {
+    let database = TestDatabase::new().await.expect("Failed to create test database");
+    let backend = backend_with_contexts(&database, &[1, 2, 3]).await;
+    sqlx::query("UPDATE conversation_contexts SET expires_at = NOW() - INTERVAL '1 minute' WHERE user_id = 2")
+        .execute(&database.pool)
+        .await
+        .unwrap();
+
+    let (scopes, cursor) = backend.scopes_page(ScopeCursor::Start, 10).await.unwrap();
+    assert_eq!(scopes, vec![(1, None), (3, None)]);
+    assert_eq!(cursor, ScopeCursor::Start);
+}
+
+/// Test that the storage lists every scope once across many pages
+#[tokio::test]
+#[serial]
+async fn test_active_scopes_across_pages() {
+    let database = TestDatabase::new().await.expect("Failed to create test database");
+    let user_ids: Vec<i64> = (1..=1200).collect();
+    backend_with_contexts(&database, &user_ids).await;
+    let storage = StateStorage::with_backend(Arc::new(PostgresStateBackend::new(database.pool.clone())), TTL);
+
+    let scopes = storage.get_active_scopes().await.unwrap();
+    assert_eq!(scopes.len(), 1200);
+    assert_eq!(scopes.first(), Some(&(1, None)));
+    assert_eq!(scopes.last(), Some(&(1200, None)));
+}